@@ -68,6 +68,42 @@ fn micro_benches(c: &mut Criterion) {
             criterion::BatchSize::PerIteration,
         )
     });
+
+    // Sequential vs. chunked lexing of a large source.
+    {
+        let source = black_box(get_src("Optimism"));
+        let sess = solar::parse::interface::Session::builder().with_stderr_emitter().build();
+        sess.enter(|| {
+            g.throughput(Throughput::Bytes(source.src.len() as u64));
+            g.bench_function("lex/Optimism/sequential", |b| {
+                b.iter(|| solar::parse::Lexer::new(&sess, source.src).into_tokens())
+            });
+            g.bench_function("lex/Optimism/chunked", |b| {
+                b.iter(|| {
+                    solar::parse::Lexer::new(&sess, source.src)
+                        .with_token_stream(|tokens| tokens.count())
+                })
+            });
+            g.bench_function("parse/Optimism/sequential", |b| {
+                b.iter(|| {
+                    let arena = solar::parse::ast::Arena::new();
+                    let tokens = solar::parse::Lexer::new(&sess, source.src).into_tokens();
+                    let mut parser = solar::parse::Parser::new(&sess, &arena, tokens);
+                    black_box(parser.parse_file().map_err(|e| e.emit()).is_ok())
+                })
+            });
+            g.bench_function("parse/Optimism/streamed", |b| {
+                b.iter(|| {
+                    let arena = solar::parse::ast::Arena::new();
+                    solar::parse::Lexer::new(&sess, source.src).with_token_stream(|tokens| {
+                        let mut parser =
+                            solar::parse::Parser::from_token_stream(&sess, &arena, tokens);
+                        black_box(parser.parse_file().map_err(|e| e.emit()).is_ok())
+                    })
+                })
+            });
+        });
+    }
 }
 
 fn compiler_benches(c: &mut Criterion) {
@@ -77,10 +113,9 @@ fn compiler_benches(c: &mut Criterion) {
     eprintln!();
 
     let mut g = make_group(c, "compiler");
-    let benches: [CompilerBench; 4] = [
+    let benches: [CompilerBench; 3] = [
         ("lex", can_lex, bytes, run_lex),
         ("parse", can_parse, bytes, run_parse),
-        ("lower", can_lower, bytes, run_lower),
         ("codegen", can_codegen, bytes, run_codegen),
     ];
 
@@ -98,16 +133,7 @@ fn compiler_benches(c: &mut Criterion) {
             for (name, should_run, throughput, run) in benches {
                 if should_run(compiler, source) {
                     g.throughput(throughput(source));
-                    g.bench_function(mk_id(name), |b| {
-                        b.iter_batched(
-                            || compiler.setup(source),
-                            |mut setup| {
-                                run(compiler, source, &mut *setup);
-                                setup
-                            },
-                            criterion::BatchSize::SmallInput,
-                        )
-                    });
+                    bench_compiler(&mut g, mk_id(name), compiler, source, run);
                 }
             }
         }
@@ -117,6 +143,50 @@ fn compiler_benches(c: &mut Criterion) {
     g.finish();
 }
 
+/// Semantic analysis stages, in separate groups so that they can be run and compared on their own.
+fn sema_benches(c: &mut Criterion) {
+    let stages: [CompilerBench; 2] =
+        [("lower", can_lower, bytes, run_lower), ("analysis", can_analyze, bytes, run_analysis)];
+
+    for (name, should_run, throughput, run) in stages {
+        let mut g = make_group(c, name);
+        for source in get_srcs() {
+            for &compiler in COMPILERS {
+                if !should_run(compiler, source) {
+                    continue;
+                }
+                let id = if COMPILERS.len() == 1 {
+                    source.name.to_string()
+                } else {
+                    format!("{}/{}", source.name, compiler.name())
+                };
+                g.throughput(throughput(source));
+                bench_compiler(&mut g, id, compiler, source, run);
+            }
+        }
+        g.finish();
+    }
+}
+
+fn bench_compiler(
+    g: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    id: String,
+    compiler: &dyn Compiler,
+    source: &Source,
+    run: fn(&dyn Compiler, &Source, &mut dyn Any),
+) {
+    g.bench_function(id, |b| {
+        b.iter_batched(
+            || compiler.setup(source),
+            |mut setup| {
+                run(compiler, source, &mut *setup);
+                setup
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
 fn bytes(source: &Source) -> Throughput {
     Throughput::Bytes(source.src.len() as u64)
 }
@@ -135,6 +205,10 @@ fn can_lower(compiler: &dyn Compiler, source: &Source) -> bool {
     compiler.capabilities().can_lower() && source.capabilities.can_lower()
 }
 
+fn can_analyze(compiler: &dyn Compiler, source: &Source) -> bool {
+    compiler.capabilities().can_analyze() && source.capabilities.can_analyze()
+}
+
 fn can_codegen(compiler: &dyn Compiler, source: &Source) -> bool {
     compiler.capabilities().can_codegen() && source.capabilities.can_codegen()
 }
@@ -151,6 +225,10 @@ fn run_lower(compiler: &dyn Compiler, source: &Source, setup: &mut dyn Any) {
     compiler.lower(source, setup);
 }
 
+fn run_analysis(compiler: &dyn Compiler, source: &Source, setup: &mut dyn Any) {
+    compiler.analysis(source, setup);
+}
+
 fn run_codegen(compiler: &dyn Compiler, source: &Source, setup: &mut dyn Any) {
     compiler.codegen(source, setup);
 }
@@ -167,5 +245,5 @@ fn make_group<'a>(
     g
 }
 
-criterion_group!(benches, micro_benches, compiler_benches);
+criterion_group!(benches, micro_benches, compiler_benches, sema_benches);
 criterion_main!(benches);
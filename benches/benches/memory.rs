@@ -0,0 +1,50 @@
+//! Peak memory usage of each compiler stage.
+//!
+//! Each stage is run once per source in a fresh compiler, after resetting the peak resident set
+//! size of the process, so every row reports the peak memory required to reach that stage.
+
+use solar_bench::{Compiler, Solar, Source, get_srcs, peak_rss, reset_peak_rss};
+use std::any::Any;
+
+type Stage = (&'static str, fn(&Source) -> bool, fn(&dyn Compiler, &Source, &mut dyn Any));
+
+const STAGES: &[Stage] = &[
+    ("lex", |s| s.capabilities.can_lex(), |c, s, setup| c.lex(s, setup)),
+    ("parse", |_| true, |c, s, setup| c.parse(s, setup)),
+    ("lower", |s| s.capabilities.can_lower(), |c, s, setup| c.lower(s, setup)),
+    ("analysis", |s| s.capabilities.can_analyze(), |c, s, setup| c.analysis(s, setup)),
+    ("codegen", |s| s.capabilities.can_codegen(), |c, s, setup| c.codegen(s, setup)),
+];
+
+fn main() {
+    if !reset_peak_rss() || peak_rss().is_none() {
+        eprintln!("peak RSS measurement is not supported on this platform");
+        return;
+    }
+
+    println!("| Source | Stage | Peak RSS | Increase |");
+    println!("| --- | --- | --- | --- |");
+    for source in get_srcs() {
+        for &(stage, should_run, run) in STAGES {
+            if !should_run(source) {
+                continue;
+            }
+            let mut setup = Solar.setup(source);
+            reset_peak_rss();
+            let before = peak_rss().unwrap();
+            run(&Solar, source, &mut *setup);
+            let after = peak_rss().unwrap();
+            drop(setup);
+            println!(
+                "| {} | {stage} | {} | {} |",
+                source.name,
+                fmt_mib(after),
+                fmt_mib(after.saturating_sub(before))
+            );
+        }
+    }
+}
+
+fn fmt_mib(bytes: u64) -> String {
+    format!("{:.2} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
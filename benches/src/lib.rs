@@ -46,7 +46,7 @@ pub fn get_srcs() -> &'static [Source] {
             ),
             // Pre-0.8 source semantics: rejected by 0.8 type rules (unary `-` on
             // unsigned, one-step sign+width conversions).
-            include_source("../testdata/UniswapV3.sol", Capabilities::no_codegen()),
+            include_source("../testdata/UniswapV3.sol", Capabilities::no_analysis()),
             include_source("../testdata/Solarray.sol", Capabilities::all()),
             include_source("../testdata/console.sol", Capabilities::all()),
             include_source("../testdata/Vm.sol", Capabilities::all()),
@@ -106,10 +106,16 @@ fn parse_source(compiler: &mut CompilerRef<'_>, source: &Source) -> Result {
     compiler.dcx().has_errors()
 }
 
-fn codegen_source(compiler: &mut CompilerRef<'_>, source: &Source) -> Result {
+fn analyze_source(compiler: &mut CompilerRef<'_>, source: &Source) -> Result<ControlFlow<()>> {
     parse_source(compiler, source)?;
-    let ControlFlow::Continue(()) = compiler.lower_asts()? else { return Ok(()) };
-    let ControlFlow::Continue(()) = compiler.analysis()? else { return Ok(()) };
+    let ControlFlow::Continue(()) = compiler.lower_asts()? else {
+        return Ok(ControlFlow::Break(()));
+    };
+    compiler.analysis()
+}
+
+fn codegen_source(compiler: &mut CompilerRef<'_>, source: &Source) -> Result {
+    let ControlFlow::Continue(()) = analyze_source(compiler, source)? else { return Ok(()) };
 
     let gcx = compiler.gcx();
     for contract_id in gcx.hir.contract_ids() {
@@ -126,6 +132,23 @@ fn codegen_source(compiler: &mut CompilerRef<'_>, source: &Source) -> Result {
     Ok(())
 }
 
+/// Resets the peak resident set size of the current process to its current value.
+///
+/// Returns `false` if this is not supported on the current platform.
+pub fn reset_peak_rss() -> bool {
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Returns the peak resident set size of the current process, in bytes.
+///
+/// Returns `None` if this is not supported on the current platform.
+pub fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kib = kib.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+    Some(kib * 1024)
+}
+
 /// `include!` at runtime, since the submodule may not be initialized.
 fn include_source(path: &str, capabilities: Capabilities) -> Source {
     let source = match std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)) {
@@ -155,24 +178,29 @@ pub struct Source {
 pub struct Capabilities {
     lex: bool,
     lower: bool,
+    analysis: bool,
     codegen: bool,
 }
 
 impl Capabilities {
     pub fn all() -> Self {
-        Self { lex: true, lower: true, codegen: true }
+        Self { lex: true, lower: true, analysis: true, codegen: true }
     }
 
     pub fn parse_only() -> Self {
-        Self { lex: false, lower: false, codegen: false }
+        Self { lex: false, lower: false, analysis: false, codegen: false }
     }
 
     pub fn lex_and_parse() -> Self {
-        Self { lex: true, lower: false, codegen: false }
+        Self { lex: true, lower: false, analysis: false, codegen: false }
+    }
+
+    pub fn parse_and_analysis() -> Self {
+        Self { lex: false, lower: false, analysis: true, codegen: false }
     }
 
-    pub fn no_codegen() -> Self {
-        Self { lex: true, lower: true, codegen: false }
+    pub fn no_analysis() -> Self {
+        Self { lex: true, lower: true, analysis: false, codegen: false }
     }
 
     pub fn can_lex(&self) -> bool {
@@ -183,6 +211,10 @@ impl Capabilities {
         self.lower
     }
 
+    pub fn can_analyze(&self) -> bool {
+        self.analysis
+    }
+
     pub fn can_codegen(&self) -> bool {
         self.codegen
     }
@@ -197,6 +229,7 @@ pub trait Compiler {
     fn lex(&self, _source: &Source, _setup: &mut dyn Any) {}
     fn parse(&self, source: &Source, setup: &mut dyn Any);
     fn lower(&self, _source: &Source, _setup: &mut dyn Any) {}
+    fn analysis(&self, _source: &Source, _setup: &mut dyn Any) {}
     fn codegen(&self, _source: &Source, _setup: &mut dyn Any) {}
 }
 
@@ -207,32 +240,45 @@ impl Compiler for Solc {
     }
 
     fn capabilities(&self) -> Capabilities {
-        Capabilities::parse_only()
+        Capabilities::parse_and_analysis()
     }
 
     fn parse(&self, source: &Source, _: &mut dyn Any) {
-        let solc = std::env::var_os("SOLC");
-        let solc = solc.as_deref().unwrap_or_else(|| "solc".as_ref());
-        let mut cmd = std::process::Command::new(solc);
-        cmd.arg("-");
-        cmd.arg("--stop-after=parsing");
-        // cmd.arg("--ast-compact-json");
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-        cmd.stdin(Stdio::piped());
-        let mut child = cmd.spawn().expect("failed to spawn child");
-        child
-            .stdin
-            .as_mut()
-            .unwrap()
-            .write_all(source.src.as_bytes())
-            .expect("failed to write to stdin");
-        let output = child.wait_with_output().expect("failed to wait for child");
-        if !output.status.success() {
-            panic!("solc failed.\ncmd: {cmd:?}\nout: {output:#?}");
+        let _stdout = run_solc(&["-", "--stop-after=parsing"], source.src);
+    }
+
+    /// Runs the full `solc` front-end through Standard JSON.
+    ///
+    /// Only the AST is requested, so that `solc` stops after analysis without generating code.
+    fn analysis(&self, source: &Source, _: &mut dyn Any) {
+        let input = serde_json::json!({
+            "language": "Solidity",
+            "sources": { source.path: { "content": source.src } },
+            "settings": { "outputSelection": { "*": { "": ["ast"] } } },
+        });
+        let stdout = run_solc(&["--standard-json"], &input.to_string());
+        if stdout.contains(r#""severity":"error""#) {
+            panic!("solc failed.\nout: {stdout}");
         }
-        let _stdout = String::from_utf8(output.stdout).expect("failed to read stdout");
+        black_box(stdout);
+    }
+}
+
+fn run_solc(args: &[&str], stdin: &str) -> String {
+    let solc = std::env::var_os("SOLC");
+    let solc = solc.as_deref().unwrap_or_else(|| "solc".as_ref());
+    let mut cmd = std::process::Command::new(solc);
+    cmd.args(args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.stdin(Stdio::piped());
+    let mut child = cmd.spawn().expect("failed to spawn child");
+    child.stdin.as_mut().unwrap().write_all(stdin.as_bytes()).expect("failed to write to stdin");
+    let output = child.wait_with_output().expect("failed to wait for child");
+    if !output.status.success() {
+        panic!("solc failed.\ncmd: {cmd:?}\nout: {output:#?}");
     }
+    String::from_utf8(output.stdout).expect("failed to read stdout")
 }
 
 pub struct Solar;
@@ -287,6 +333,13 @@ impl Compiler for Solar {
         })
     }
 
+    fn analysis(&self, source: &Source, compiler_any: &mut dyn Any) {
+        let compiler = compiler_any.downcast_mut::<SemaCompiler>().unwrap();
+        compiler.enter_mut(|compiler| {
+            let _ = analyze_source(compiler, source).unwrap();
+        })
+    }
+
     fn codegen(&self, source: &Source, compiler_any: &mut dyn Any) {
         let compiler = compiler_any.downcast_mut::<SemaCompiler>().unwrap();
         compiler.enter_mut(|compiler| codegen_source(compiler, source).unwrap())
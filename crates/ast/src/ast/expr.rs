@@ -227,6 +227,32 @@ impl BinOpKind {
         use BinOpKind::*;
         matches!(self, Shl | Shr | Sar)
     }
+
+    /// Returns the precedence of the operator. Operators with a higher precedence bind tighter.
+    ///
+    /// See the [Solidity documentation](https://docs.soliditylang.org/en/latest/cheatsheet.html#order-of-precedence-of-operators).
+    pub const fn precedence(self) -> u8 {
+        use BinOpKind::*;
+        match self {
+            Or => 4,
+            And => 5,
+            Eq | Ne => 6,
+            Lt | Le | Gt | Ge => 7,
+            BitOr => 8,
+            BitXor => 9,
+            BitAnd => 10,
+            Shl | Shr | Sar => 11,
+            Add | Sub => 12,
+            Mul | Div | Rem => 13,
+            Pow => 14,
+        }
+    }
+
+    /// Returns `true` if the operator is right-associative, meaning that `a ** b ** c` is parsed
+    /// as `a ** (b ** c)`.
+    pub const fn is_right_assoc(self) -> bool {
+        matches!(self, Self::Pow)
+    }
 }
 
 /// A unary operation: `!x`, `-x`, `x++`.
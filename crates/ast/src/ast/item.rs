@@ -438,6 +438,7 @@ pub struct ItemContract<'ast> {
     pub name: Ident,
     pub layout: Option<StorageLayoutSpecifier<'ast>>,
     pub bases: BoxSlice<'ast, Modifier<'ast>>,
+    /// The contract items, in declaration order.
     pub body: BoxSlice<'ast, Item<'ast>>,
 }
 
@@ -22,6 +22,9 @@ pub use natspec::*;
 mod path;
 pub use path::*;
 
+mod print;
+pub use print::AstPrinter;
+
 mod semver;
 pub use semver::*;
 
@@ -164,6 +167,24 @@ newtype_index! {
     pub struct ItemId;
 }
 
+// Some nodes are used a lot. Make sure they don't unintentionally get bigger.
+#[cfg(all(target_pointer_width = "64", not(feature = "nightly")))]
+mod size_asserts {
+    use super::*;
+    use solar_data_structures::static_assert_size;
+
+    static_assert_size!(Expr<'_>, 48);
+    static_assert_size!(ExprKind<'_>, 40);
+    static_assert_size!(Item<'_>, 160);
+    static_assert_size!(ItemKind<'_>, 144);
+    static_assert_size!(Stmt<'_>, 64);
+    static_assert_size!(StmtKind<'_>, 48);
+    static_assert_size!(Type<'_>, 24);
+    static_assert_size!(TypeKind<'_>, 16);
+    static_assert_size!(yul::Expr<'_>, 40);
+    static_assert_size!(yul::Stmt<'_>, 72);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,6 +217,8 @@ mod tests {
             assert_data_eq!(actual.to_string(), expected);
         }
 
+        // The sizes of the most common nodes are asserted at compile time in `size_asserts`.
+
         assert_size::<Span>(str!["8"]);
         assert_size::<DocComments<'_>>(str!["8"]);
 
@@ -212,29 +235,17 @@ mod tests {
         assert_size::<ItemUdvt<'_>>(str!["40"]);
         assert_size::<ItemError<'_>>(str!["32"]);
         assert_size::<ItemEvent<'_>>(str!["32"]);
-        assert_size::<ItemKind<'_>>(str!["144"]);
-        assert_size::<Item<'_>>(str!["160"]);
 
         assert_size::<FunctionHeader<'_>>(str!["112"]);
         assert_size::<ParameterList<'_>>(str!["16"]);
 
         assert_size::<ElementaryType>(str!["4"]);
-        assert_size::<TypeKind<'_>>(str!["16"]);
-        assert_size::<Type<'_>>(str!["24"]);
-
-        assert_size::<ExprKind<'_>>(str!["40"]);
-        assert_size::<Expr<'_>>(str!["48"]);
-
-        assert_size::<StmtKind<'_>>(str!["48"]);
-        assert_size::<Stmt<'_>>(str!["64"]);
         assert_size::<Block<'_>>(str!["16"]);
 
         assert_size::<yul::ExprCall<'_>>(str!["24"]);
         assert_size::<yul::ExprKind<'_>>(str!["32"]);
-        assert_size::<yul::Expr<'_>>(str!["40"]);
 
         assert_size::<yul::StmtKind<'_>>(str!["56"]);
-        assert_size::<yul::Stmt<'_>>(str!["72"]);
         assert_size::<yul::Block<'_>>(str!["16"]);
         assert_size::<yul::StmtFor<'_>>(str!["88"]);
     }
@@ -0,0 +1,882 @@
+use super::*;
+use solar_interface::SpannedOption;
+use std::fmt::Write;
+
+/// Pretty-prints the AST as Solidity source.
+///
+/// Comments are not preserved and the output is formatted with a fixed style, so the printed
+/// source is not the same as the parsed one, but it parses to the same AST.
+#[derive(Default)]
+pub struct AstPrinter {
+    out: String,
+    indent: usize,
+}
+
+impl AstPrinter {
+    /// Creates a new AST printer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prints a source unit.
+    pub fn print_source_unit(&mut self, source: &SourceUnit<'_>) {
+        self.print_items(&source.items.raw);
+    }
+
+    /// Returns the accumulated output.
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn print_items(&mut self, items: &[Item<'_>]) {
+        for (i, item) in items.iter().enumerate() {
+            if i != 0 && !(is_directive(&items[i - 1]) && is_directive(item)) {
+                self.out.push('\n');
+            }
+            self.print_item(item);
+        }
+    }
+
+    fn print_item(&mut self, item: &Item<'_>) {
+        self.write_indent();
+        match &item.kind {
+            ItemKind::Pragma(pragma) => self.print_pragma(pragma),
+            ItemKind::Import(import) => self.print_import(import),
+            ItemKind::Using(using) => self.print_using(using),
+            ItemKind::Contract(contract) => self.print_contract(contract),
+            ItemKind::Function(func) => self.print_function(func),
+            ItemKind::Variable(var) => {
+                self.print_var(var);
+                self.out.push(';');
+            }
+            ItemKind::Struct(strukt) => {
+                writeln!(self.out, "struct {} {{", strukt.name).unwrap();
+                self.indent += 1;
+                for field in strukt.fields.iter() {
+                    self.write_indent();
+                    self.print_var(field);
+                    self.out.push_str(";\n");
+                }
+                self.indent -= 1;
+                self.write_indent();
+                self.out.push('}');
+            }
+            ItemKind::Enum(enumm) => {
+                write!(self.out, "enum {} {{", enumm.name).unwrap();
+                for (i, variant) in enumm.variants.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    write!(self.out, "{variant}").unwrap();
+                }
+                self.out.push('}');
+            }
+            ItemKind::Udvt(udvt) => {
+                write!(self.out, "type {} is ", udvt.name).unwrap();
+                self.print_ty(&udvt.ty);
+                self.out.push(';');
+            }
+            ItemKind::Error(error) => {
+                write!(self.out, "error {}", error.name).unwrap();
+                self.print_params(&error.parameters);
+                self.out.push(';');
+            }
+            ItemKind::Event(event) => {
+                write!(self.out, "event {}", event.name).unwrap();
+                self.print_params(&event.parameters);
+                if event.anonymous {
+                    self.out.push_str(" anonymous");
+                }
+                self.out.push(';');
+            }
+        }
+        self.out.push('\n');
+    }
+
+    fn print_pragma(&mut self, pragma: &PragmaDirective<'_>) {
+        self.out.push_str("pragma ");
+        match &pragma.tokens {
+            PragmaTokens::Version(name, req) => write!(self.out, "{name} {req}").unwrap(),
+            PragmaTokens::Custom(name, value) => {
+                self.print_ident_or_str(name);
+                if let Some(value) = value {
+                    self.out.push(' ');
+                    self.print_ident_or_str(value);
+                }
+            }
+            PragmaTokens::Verbatim(tokens) => {
+                for (i, token) in tokens.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(token.as_str());
+                }
+            }
+        }
+        self.out.push(';');
+    }
+
+    fn print_ident_or_str(&mut self, x: &IdentOrStrLit) {
+        match x {
+            IdentOrStrLit::Ident(ident) => write!(self.out, "{ident}").unwrap(),
+            IdentOrStrLit::StrLit(lit) => write!(self.out, "\"{}\"", lit.value).unwrap(),
+        }
+    }
+
+    fn print_import(&mut self, import: &ImportDirective<'_>) {
+        let path = import.path.value;
+        match &import.items {
+            ImportItems::Plain(None) => write!(self.out, "import \"{path}\"").unwrap(),
+            ImportItems::Plain(Some(alias)) => {
+                write!(self.out, "import \"{path}\" as {alias}").unwrap()
+            }
+            ImportItems::Aliases(aliases) => {
+                self.out.push_str("import {");
+                for (i, (name, alias)) in aliases.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    write!(self.out, "{name}").unwrap();
+                    if let Some(alias) = alias {
+                        write!(self.out, " as {alias}").unwrap();
+                    }
+                }
+                write!(self.out, "}} from \"{path}\"").unwrap();
+            }
+            ImportItems::Glob(alias) => {
+                write!(self.out, "import * as {alias} from \"{path}\"").unwrap()
+            }
+        }
+        self.out.push(';');
+    }
+
+    fn print_using(&mut self, using: &UsingDirective<'_>) {
+        self.out.push_str("using ");
+        match &using.list {
+            UsingList::Single(path) => write!(self.out, "{}", path.as_slice()).unwrap(),
+            UsingList::Multiple(paths) => {
+                self.out.push('{');
+                for (i, (path, op)) in paths.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    write!(self.out, "{}", path.as_slice()).unwrap();
+                    if let Some(op) = op {
+                        write!(self.out, " as {}", op.to_str()).unwrap();
+                    }
+                }
+                self.out.push('}');
+            }
+        }
+        self.out.push_str(" for ");
+        if let Some(ty) = &using.ty {
+            self.print_ty(ty);
+        } else {
+            self.out.push('*');
+        }
+        if using.global {
+            self.out.push_str(" global");
+        }
+        self.out.push(';');
+    }
+
+    fn print_contract(&mut self, contract: &ItemContract<'_>) {
+        write!(self.out, "{} {}", contract.kind, contract.name).unwrap();
+        if let Some(layout) = &contract.layout {
+            self.out.push_str(" layout at ");
+            self.print_expr(&layout.slot);
+        }
+        if !contract.bases.is_empty() {
+            self.out.push_str(" is ");
+            for (i, base) in contract.bases.iter().enumerate() {
+                if i != 0 {
+                    self.out.push_str(", ");
+                }
+                self.print_modifier(base);
+            }
+        }
+        if contract.body.is_empty() {
+            self.out.push_str(" {}");
+            return;
+        }
+        self.out.push_str(" {\n");
+        self.indent += 1;
+        self.print_items(&contract.body);
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn print_function(&mut self, func: &ItemFunction<'_>) {
+        let header = &func.header;
+        self.out.push_str(func.kind.to_str());
+        if let Some(name) = header.name {
+            write!(self.out, " {name}").unwrap();
+        }
+        self.print_params(&header.parameters);
+        if let Some(visibility) = &header.visibility {
+            write!(self.out, " {}", visibility.data).unwrap();
+        }
+        if let Some(state_mutability) = &header.state_mutability {
+            write!(self.out, " {}", state_mutability.data).unwrap();
+        }
+        for modifier in header.modifiers.iter() {
+            self.out.push(' ');
+            self.print_modifier(modifier);
+        }
+        if header.virtual_.is_some() {
+            self.out.push_str(" virtual");
+        }
+        if let Some(override_) = &header.override_ {
+            self.print_override(override_);
+        }
+        if let Some(returns) = &header.returns
+            && !returns.is_empty()
+        {
+            self.out.push_str(" returns ");
+            self.print_params(returns);
+        }
+        if let Some(body) = &func.body {
+            self.out.push(' ');
+            self.print_block(body);
+        } else {
+            self.out.push(';');
+        }
+    }
+
+    fn print_modifier(&mut self, modifier: &Modifier<'_>) {
+        write!(self.out, "{}", modifier.name.as_slice()).unwrap();
+        if !modifier.arguments.is_dummy() || !modifier.arguments.is_empty() {
+            self.print_call_args(&modifier.arguments);
+        }
+    }
+
+    fn print_override(&mut self, override_: &Override<'_>) {
+        self.out.push_str(" override");
+        if !override_.paths.is_empty() {
+            self.out.push('(');
+            for (i, path) in override_.paths.iter().enumerate() {
+                if i != 0 {
+                    self.out.push_str(", ");
+                }
+                write!(self.out, "{}", path.as_slice()).unwrap();
+            }
+            self.out.push(')');
+        }
+    }
+
+    fn print_params(&mut self, params: &ParameterList<'_>) {
+        self.out.push('(');
+        for (i, var) in params.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str(", ");
+            }
+            self.print_var(var);
+        }
+        self.out.push(')');
+    }
+
+    fn print_var(&mut self, var: &VariableDefinition<'_>) {
+        self.print_ty(&var.ty);
+        if let Some(data_location) = var.data_location {
+            write!(self.out, " {data_location}").unwrap();
+        }
+        if let Some(visibility) = var.visibility {
+            write!(self.out, " {visibility}").unwrap();
+        }
+        if let Some(mutability) = var.mutability {
+            write!(self.out, " {mutability}").unwrap();
+        }
+        if let Some(override_) = &var.override_ {
+            self.print_override(override_);
+        }
+        if var.indexed {
+            self.out.push_str(" indexed");
+        }
+        if let Some(name) = var.name {
+            write!(self.out, " {name}").unwrap();
+        }
+        if let Some(initializer) = &var.initializer {
+            self.out.push_str(" = ");
+            self.print_expr(initializer);
+        }
+    }
+
+    fn print_block(&mut self, block: &Block<'_>) {
+        if block.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push_str("{\n");
+        self.indent += 1;
+        for stmt in block.iter() {
+            self.write_indent();
+            self.print_stmt(stmt);
+            self.out.push('\n');
+        }
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    /// Prints a statement without indentation or a trailing newline.
+    fn print_stmt(&mut self, stmt: &Stmt<'_>) {
+        match &stmt.kind {
+            StmtKind::Assembly(assembly) => {
+                self.out.push_str("assembly ");
+                if let Some(dialect) = &assembly.dialect {
+                    write!(self.out, "\"{}\" ", dialect.value).unwrap();
+                }
+                if !assembly.flags.is_empty() {
+                    self.out.push('(');
+                    for (i, flag) in assembly.flags.iter().enumerate() {
+                        if i != 0 {
+                            self.out.push_str(", ");
+                        }
+                        write!(self.out, "\"{}\"", flag.value).unwrap();
+                    }
+                    self.out.push_str(") ");
+                }
+                self.print_yul_block(&assembly.block);
+            }
+            StmtKind::Block(block) => self.print_block(block),
+            StmtKind::UncheckedBlock(block) => {
+                self.out.push_str("unchecked ");
+                self.print_block(block);
+            }
+            StmtKind::Break => self.out.push_str("break;"),
+            StmtKind::Continue => self.out.push_str("continue;"),
+            StmtKind::DoWhile(body, cond) => {
+                self.out.push_str("do ");
+                self.print_stmt_as_block(body);
+                self.out.push_str(" while (");
+                self.print_expr(cond);
+                self.out.push_str(");");
+            }
+            StmtKind::Emit(path, args) => {
+                write!(self.out, "emit {}", path.as_slice()).unwrap();
+                self.print_call_args(args);
+                self.out.push(';');
+            }
+            StmtKind::Revert(path, args) => {
+                write!(self.out, "revert {}", path.as_slice()).unwrap();
+                self.print_call_args(args);
+                self.out.push(';');
+            }
+            StmtKind::For { init, cond, next, body } => {
+                self.out.push_str("for (");
+                if let Some(init) = init {
+                    self.print_simple_stmt(init);
+                }
+                self.out.push(';');
+                if let Some(cond) = cond {
+                    self.out.push(' ');
+                    self.print_expr(cond);
+                }
+                self.out.push(';');
+                if let Some(next) = next {
+                    self.out.push(' ');
+                    self.print_expr(next);
+                }
+                self.out.push_str(") ");
+                self.print_stmt_as_block(body);
+            }
+            StmtKind::If(cond, then, else_) => {
+                self.out.push_str("if (");
+                self.print_expr(cond);
+                self.out.push_str(") ");
+                self.print_stmt_as_block(then);
+                if let Some(else_) = else_ {
+                    self.out.push_str(" else ");
+                    if let StmtKind::If(..) = else_.kind {
+                        self.print_stmt(else_);
+                    } else {
+                        self.print_stmt_as_block(else_);
+                    }
+                }
+            }
+            StmtKind::Return(expr) => {
+                self.out.push_str("return");
+                if let Some(expr) = expr {
+                    self.out.push(' ');
+                    self.print_expr(expr);
+                }
+                self.out.push(';');
+            }
+            StmtKind::Try(try_) => self.print_try(try_),
+            StmtKind::While(cond, body) => {
+                self.out.push_str("while (");
+                self.print_expr(cond);
+                self.out.push_str(") ");
+                self.print_stmt_as_block(body);
+            }
+            StmtKind::Placeholder => self.out.push_str("_;"),
+            StmtKind::DeclSingle(_) | StmtKind::DeclMulti(..) | StmtKind::Expr(_) => {
+                self.print_simple_stmt(stmt);
+                self.out.push(';');
+            }
+        }
+    }
+
+    /// Prints a declaration or an expression statement without the trailing semicolon, as in the
+    /// initializer of a `for` loop.
+    fn print_simple_stmt(&mut self, stmt: &Stmt<'_>) {
+        match &stmt.kind {
+            StmtKind::DeclSingle(var) => self.print_var(var),
+            StmtKind::DeclMulti(vars, expr) => {
+                self.out.push('(');
+                for (i, var) in vars.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    if let SpannedOption::Some(var) = var {
+                        self.print_var(var);
+                    }
+                }
+                self.out.push_str(") = ");
+                self.print_expr(expr);
+            }
+            StmtKind::Expr(expr) => self.print_expr(expr),
+            _ => self.print_stmt(stmt),
+        }
+    }
+
+    fn print_stmt_as_block(&mut self, stmt: &Stmt<'_>) {
+        if let StmtKind::Block(block) = &stmt.kind {
+            self.print_block(block);
+            return;
+        }
+        self.out.push_str("{\n");
+        self.indent += 1;
+        self.write_indent();
+        self.print_stmt(stmt);
+        self.out.push('\n');
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn print_try(&mut self, try_: &StmtTry<'_>) {
+        self.out.push_str("try ");
+        self.print_expr(&try_.expr);
+        for (i, clause) in try_.clauses.iter().enumerate() {
+            self.out.push(' ');
+            if i == 0 {
+                if !clause.args.is_empty() {
+                    self.out.push_str("returns ");
+                    self.print_params(&clause.args);
+                    self.out.push(' ');
+                }
+            } else {
+                self.out.push_str("catch ");
+                if let Some(name) = clause.name {
+                    write!(self.out, "{name}").unwrap();
+                }
+                if clause.name.is_some() || !clause.args.is_empty() {
+                    self.print_params(&clause.args);
+                    self.out.push(' ');
+                }
+            }
+            self.print_block(&clause.block);
+        }
+    }
+
+    fn print_expr(&mut self, expr: &Expr<'_>) {
+        self.print_expr_prec(expr, PREC_MIN);
+    }
+
+    /// Prints an expression in a context that binds with precedence `min`, parenthesizing it if
+    /// needed.
+    fn print_expr_prec(&mut self, expr: &Expr<'_>, min: u8) {
+        // Parentheses are grouping-only tuples, so they are printed based on precedence instead.
+        if let ExprKind::Tuple(exprs) = &expr.kind
+            && let [SpannedOption::Some(inner)] = exprs.as_slice()
+        {
+            return self.print_expr_prec(inner, min);
+        }
+
+        let parens = expr_precedence(expr) < min;
+        if parens {
+            self.out.push('(');
+        }
+        self.print_expr_kind(expr);
+        if parens {
+            self.out.push(')');
+        }
+    }
+
+    fn print_expr_kind(&mut self, expr: &Expr<'_>) {
+        match &expr.kind {
+            ExprKind::Array(exprs) => {
+                self.out.push('[');
+                self.print_expr_list(exprs.iter().map(|expr| &**expr));
+                self.out.push(']');
+            }
+            ExprKind::Assign(lhs, op, rhs) => {
+                self.print_expr_prec(lhs, PREC_TERNARY + 1);
+                self.out.push(' ');
+                if let Some(op) = op {
+                    self.out.push_str(op.kind.to_str());
+                }
+                self.out.push_str("= ");
+                self.print_expr_prec(rhs, PREC_ASSIGN);
+            }
+            ExprKind::Binary(lhs, op, rhs) => {
+                let prec = op.kind.precedence();
+                let (lhs_prec, rhs_prec) =
+                    if op.kind.is_right_assoc() { (prec + 1, prec) } else { (prec, prec + 1) };
+                self.print_expr_prec(lhs, lhs_prec);
+                write!(self.out, " {} ", op.kind.to_str()).unwrap();
+                self.print_expr_prec(rhs, rhs_prec);
+            }
+            ExprKind::Call(callee, args) => {
+                if let ExprKind::New(_) = callee.kind {
+                    self.print_expr(callee);
+                } else {
+                    self.print_expr_prec(callee, PREC_POSTFIX);
+                }
+                self.print_call_args(args);
+            }
+            ExprKind::CallOptions(callee, options) => {
+                if let ExprKind::New(_) = callee.kind {
+                    self.print_expr(callee);
+                } else {
+                    self.print_expr_prec(callee, PREC_POSTFIX);
+                }
+                self.out.push('{');
+                self.print_named_args(options);
+                self.out.push('}');
+            }
+            ExprKind::Delete(expr) => {
+                self.out.push_str("delete ");
+                self.print_expr_prec(expr, PREC_PREFIX);
+            }
+            ExprKind::Ident(ident) => write!(self.out, "{ident}").unwrap(),
+            ExprKind::Index(expr, index) => {
+                self.print_expr_prec(expr, PREC_POSTFIX);
+                self.out.push('[');
+                match index {
+                    IndexKind::Index(index) => {
+                        if let Some(index) = index {
+                            self.print_expr(index);
+                        }
+                    }
+                    IndexKind::Range(start, end) => {
+                        if let Some(start) = start {
+                            self.print_expr(start);
+                        }
+                        self.out.push(':');
+                        if let Some(end) = end {
+                            self.print_expr(end);
+                        }
+                    }
+                }
+                self.out.push(']');
+            }
+            ExprKind::Lit(lit, sub_denomination) => {
+                write!(self.out, "{lit}").unwrap();
+                if let Some(sub_denomination) = sub_denomination {
+                    write!(self.out, " {sub_denomination}").unwrap();
+                }
+            }
+            ExprKind::Member(expr, ident) => {
+                self.print_expr_prec(expr, PREC_POSTFIX);
+                write!(self.out, ".{ident}").unwrap();
+            }
+            ExprKind::New(ty) => {
+                self.out.push_str("new ");
+                self.print_ty(ty);
+            }
+            ExprKind::Payable(args) => {
+                self.out.push_str("payable");
+                self.print_call_args(args);
+            }
+            ExprKind::Ternary(cond, then, else_) => {
+                self.print_expr_prec(cond, PREC_TERNARY + 1);
+                self.out.push_str(" ? ");
+                self.print_expr_prec(then, PREC_ASSIGN);
+                self.out.push_str(" : ");
+                self.print_expr_prec(else_, PREC_ASSIGN);
+            }
+            ExprKind::Tuple(exprs) => {
+                self.out.push('(');
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    if let SpannedOption::Some(expr) = expr {
+                        self.print_expr(expr);
+                    }
+                }
+                self.out.push(')');
+            }
+            ExprKind::TypeCall(ty) => {
+                self.out.push_str("type(");
+                self.print_ty(ty);
+                self.out.push(')');
+            }
+            ExprKind::Type(ty) => self.print_ty(ty),
+            ExprKind::Unary(op, expr) => {
+                if op.kind.is_prefix() {
+                    self.out.push_str(op.kind.to_str());
+                    // `-(-x)` must not be printed as `--x`.
+                    let min = match expr.peel_parens().kind {
+                        ExprKind::Unary(inner, _)
+                            if inner.kind.is_prefix()
+                                && op.kind.to_str().ends_with('-')
+                                && inner.kind.to_str().starts_with('-') =>
+                        {
+                            PREC_PRIMARY
+                        }
+                        _ => PREC_PREFIX,
+                    };
+                    self.print_expr_prec(expr, min);
+                } else {
+                    self.print_expr_prec(expr, PREC_POSTFIX);
+                    self.out.push_str(op.kind.to_str());
+                }
+            }
+            ExprKind::Err(_) => self.out.push_str("<error>"),
+        }
+    }
+
+    fn print_expr_list<'a, 'ast: 'a>(&mut self, exprs: impl Iterator<Item = &'a Expr<'ast>>) {
+        for (i, expr) in exprs.enumerate() {
+            if i != 0 {
+                self.out.push_str(", ");
+            }
+            self.print_expr(expr);
+        }
+    }
+
+    fn print_named_args(&mut self, args: &[NamedArg<'_>]) {
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str(", ");
+            }
+            write!(self.out, "{}: ", arg.name).unwrap();
+            self.print_expr(&arg.value);
+        }
+    }
+
+    fn print_call_args(&mut self, args: &CallArgs<'_>) {
+        match &args.kind {
+            CallArgsKind::Unnamed(exprs) => {
+                self.out.push('(');
+                self.print_expr_list(exprs.iter().map(|expr| &**expr));
+                self.out.push(')');
+            }
+            CallArgsKind::Named(args) => {
+                self.out.push_str("({");
+                self.print_named_args(args);
+                self.out.push_str("})");
+            }
+        }
+    }
+
+    fn print_ty(&mut self, ty: &Type<'_>) {
+        match &ty.kind {
+            TypeKind::Elementary(ty) => write!(self.out, "{ty}").unwrap(),
+            TypeKind::Array(arr) => {
+                self.print_ty(&arr.element);
+                self.out.push('[');
+                if let Some(size) = &arr.size {
+                    self.print_expr(size);
+                }
+                self.out.push(']');
+            }
+            TypeKind::Function(func) => {
+                self.out.push_str("function");
+                self.print_params(&func.parameters);
+                if let Some(visibility) = &func.visibility {
+                    write!(self.out, " {}", visibility.data).unwrap();
+                }
+                if let Some(state_mutability) = &func.state_mutability {
+                    write!(self.out, " {}", state_mutability.data).unwrap();
+                }
+                if let Some(returns) = &func.returns
+                    && !returns.is_empty()
+                {
+                    self.out.push_str(" returns ");
+                    self.print_params(returns);
+                }
+            }
+            TypeKind::Mapping(map) => {
+                self.out.push_str("mapping(");
+                self.print_ty(&map.key);
+                if let Some(name) = map.key_name {
+                    write!(self.out, " {name}").unwrap();
+                }
+                self.out.push_str(" => ");
+                self.print_ty(&map.value);
+                if let Some(name) = map.value_name {
+                    write!(self.out, " {name}").unwrap();
+                }
+                self.out.push(')');
+            }
+            TypeKind::Custom(path) => write!(self.out, "{}", path.as_slice()).unwrap(),
+        }
+    }
+
+    fn print_yul_block(&mut self, block: &yul::Block<'_>) {
+        if block.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push_str("{\n");
+        self.indent += 1;
+        for stmt in block.iter() {
+            self.write_indent();
+            self.print_yul_stmt(stmt);
+            self.out.push('\n');
+        }
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn print_yul_stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => self.print_yul_block(block),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                write!(self.out, "{} := ", path.as_slice()).unwrap();
+                self.print_yul_expr(expr);
+            }
+            yul::StmtKind::AssignMulti(paths, expr) => {
+                for (i, path) in paths.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    write!(self.out, "{}", path.as_slice()).unwrap();
+                }
+                self.out.push_str(" := ");
+                self.print_yul_expr(expr);
+            }
+            yul::StmtKind::Expr(expr) => self.print_yul_expr(expr),
+            yul::StmtKind::If(cond, block) => {
+                self.out.push_str("if ");
+                self.print_yul_expr(cond);
+                self.out.push(' ');
+                self.print_yul_block(block);
+            }
+            yul::StmtKind::For(for_) => {
+                self.out.push_str("for ");
+                self.print_yul_block(&for_.init);
+                self.out.push(' ');
+                self.print_yul_expr(&for_.cond);
+                self.out.push(' ');
+                self.print_yul_block(&for_.step);
+                self.out.push(' ');
+                self.print_yul_block(&for_.body);
+            }
+            yul::StmtKind::Switch(switch) => {
+                self.out.push_str("switch ");
+                self.print_yul_expr(&switch.selector);
+                for case in switch.cases.iter() {
+                    self.out.push('\n');
+                    self.write_indent();
+                    if let Some(constant) = &case.constant {
+                        write!(self.out, "case {constant} ").unwrap();
+                    } else {
+                        self.out.push_str("default ");
+                    }
+                    self.print_yul_block(&case.body);
+                }
+            }
+            yul::StmtKind::Leave => self.out.push_str("leave"),
+            yul::StmtKind::Break => self.out.push_str("break"),
+            yul::StmtKind::Continue => self.out.push_str("continue"),
+            yul::StmtKind::FunctionDef(func) => {
+                write!(self.out, "function {}(", func.name).unwrap();
+                self.print_idents(&func.parameters);
+                self.out.push(')');
+                if !func.returns.is_empty() {
+                    self.out.push_str(" -> ");
+                    self.print_idents(&func.returns);
+                }
+                self.out.push(' ');
+                self.print_yul_block(&func.body);
+            }
+            yul::StmtKind::VarDecl(idents, expr) => {
+                self.out.push_str("let ");
+                self.print_idents(idents);
+                if let Some(expr) = expr {
+                    self.out.push_str(" := ");
+                    self.print_yul_expr(expr);
+                }
+            }
+        }
+    }
+
+    fn print_yul_expr(&mut self, expr: &yul::Expr<'_>) {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => write!(self.out, "{}", path.as_slice()).unwrap(),
+            yul::ExprKind::Call(call) => {
+                write!(self.out, "{}(", call.name).unwrap();
+                for (i, arg) in call.arguments.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.print_yul_expr(arg);
+                }
+                self.out.push(')');
+            }
+            yul::ExprKind::Lit(lit) => write!(self.out, "{lit}").unwrap(),
+        }
+    }
+
+    fn print_idents(&mut self, idents: &[Ident]) {
+        for (i, ident) in idents.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str(", ");
+            }
+            write!(self.out, "{ident}").unwrap();
+        }
+    }
+
+    fn write_indent(&mut self) {
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+    }
+}
+
+/// The precedence of an expression that is not an operand, e.g. a statement or a call argument.
+const PREC_MIN: u8 = 0;
+/// The precedence of assignments. Binary operators are in between this and [`PREC_PREFIX`], see
+/// [`BinOpKind::precedence`].
+const PREC_ASSIGN: u8 = 2;
+const PREC_TERNARY: u8 = 3;
+const PREC_PREFIX: u8 = 15;
+const PREC_POSTFIX: u8 = 16;
+const PREC_PRIMARY: u8 = 17;
+
+/// Returns the precedence of an expression when printed. Higher precedences bind tighter.
+fn expr_precedence(expr: &Expr<'_>) -> u8 {
+    match expr.kind {
+        ExprKind::Assign(..) => PREC_ASSIGN,
+        ExprKind::Ternary(..) => PREC_TERNARY,
+        ExprKind::Binary(_, op, _) => op.kind.precedence(),
+        ExprKind::Unary(op, _) if op.kind.is_prefix() => PREC_PREFIX,
+        ExprKind::Delete(_) | ExprKind::New(_) => PREC_PREFIX,
+        ExprKind::Unary(..)
+        | ExprKind::Call(..)
+        | ExprKind::CallOptions(..)
+        | ExprKind::Index(..)
+        | ExprKind::Member(..) => PREC_POSTFIX,
+        ExprKind::Array(_)
+        | ExprKind::Ident(_)
+        | ExprKind::Lit(..)
+        | ExprKind::Payable(_)
+        | ExprKind::Tuple(_)
+        | ExprKind::TypeCall(_)
+        | ExprKind::Type(_)
+        | ExprKind::Err(_) => PREC_PRIMARY,
+    }
+}
+
+/// Returns `true` for pragmas, imports, and `using` directives, which are not separated by blank
+/// lines.
+fn is_directive(item: &Item<'_>) -> bool {
+    matches!(item.kind, ItemKind::Pragma(_) | ItemKind::Import(_) | ItemKind::Using(_))
+}
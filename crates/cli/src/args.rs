@@ -1,4 +1,4 @@
-use crate::commands::{evm_opt::EvmOptArgs, mir_opt::MirOptArgs};
+use crate::commands::{ast_query::AstQueryArgs, evm_opt::EvmOptArgs, mir_opt::MirOptArgs};
 use clap::{Parser, Subcommand};
 use solar_config::CompileOpts;
 #[cfg(feature = "lsp")]
@@ -29,4 +29,6 @@ pub(crate) enum Subcommands {
     MirOpt(MirOptArgs),
     /// Run one or more EVM IR passes on an EVM IR file.
     EvmOpt(EvmOptArgs),
+    /// Run a structural query over the AST of Solidity files.
+    AstQuery(AstQueryArgs),
 }
@@ -0,0 +1,483 @@
+//! The `solar ast-query` subcommand — run structural queries over the AST and
+//! print the matching nodes.
+//!
+//! Queries are written in a small CSS-like selector language, for example
+//! `contract[name=Foo] function[visibility=external]`. Every node matching the
+//! query is printed with its location and the first line of its source, or as
+//! JSON with `--json`.
+
+use clap::ValueHint;
+use solar_config::CompileOpts;
+use solar_data_structures::Never;
+use solar_interface::{Ident, Session, Span};
+use solar_sema::ast::{self, visit::Visit};
+use std::{ops::ControlFlow, path::Path, process::ExitCode, str::FromStr};
+
+fn after_help() -> &'static str {
+    "\
+Query syntax:
+  A query is a list of selectors separated by combinators:
+    a b    matches `b` nodes anywhere inside of an `a` node
+    a > b  matches `b` nodes whose closest enclosing node is an `a` node
+
+  A selector is a node kind followed by any number of attribute filters:
+    kind            any node of the given kind, or of any kind with `*`
+    kind[attr]      nodes which have the attribute `attr`
+    kind[attr=val]  nodes whose attribute `attr` is equal to `val`
+
+Node kinds:
+  contract, function, variable, struct, enum, udvt, error, event, call
+
+Attributes:
+  name        the name of the node; for calls, the name of the called function or member
+  kind        contract: contract, abstract, interface, library
+              function: function, constructor, fallback, receive, modifier
+  visibility  function, variable: private, internal, public, external
+  mutability  function: pure, view, payable, nonpayable
+              variable: constant, immutable
+  location    variable: storage, transient, memory, calldata
+
+Examples:
+  solar ast-query 'contract[name=Foo] function[visibility=external]' Foo.sol
+  solar ast-query --json 'function > call[name=delegatecall]' Foo.sol Bar.sol"
+}
+
+#[derive(clap::Args)]
+#[command(after_help = after_help(), arg_required_else_help = true)]
+pub(crate) struct AstQueryArgs {
+    /// The query to run. See below for the syntax.
+    #[arg(value_parser = Query::from_str)]
+    query: Query,
+    /// Print the matches as JSON.
+    #[arg(long)]
+    json: bool,
+    /// Paths to the Solidity files to query.
+    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    input: Vec<String>,
+}
+
+/// A parsed query: a list of selectors, each with the combinator relating it to the previous one.
+#[derive(Clone, Debug)]
+struct Query {
+    parts: Vec<(Combinator, Selector)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+}
+
+#[derive(Clone, Debug)]
+struct Selector {
+    /// `None` for `*`.
+    kind: Option<NodeKind>,
+    attrs: Vec<(Attr, Option<String>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum NodeKind {
+    Contract,
+    Function,
+    Variable,
+    Struct,
+    Enum,
+    Udvt,
+    Error,
+    Event,
+    Call,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Attr {
+    Name,
+    Kind,
+    Visibility,
+    Mutability,
+    Location,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut combinator = Combinator::Descendant;
+        let mut rest = s.trim_start();
+        while !rest.is_empty() {
+            let (selector, after) = Selector::parse(rest)?;
+            parts.push((combinator, selector));
+            if !after.is_empty() && !after.starts_with([' ', '\t', '\n', '>']) {
+                return Err(format!("expected whitespace or `>` after selector, found `{after}`"));
+            }
+            rest = after.trim_start();
+            combinator = match rest.strip_prefix('>') {
+                Some(after) => {
+                    rest = after.trim_start();
+                    if rest.is_empty() {
+                        return Err("expected a selector after `>`".into());
+                    }
+                    Combinator::Child
+                }
+                None => Combinator::Descendant,
+            };
+        }
+        if parts.is_empty() {
+            return Err("empty query".into());
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl Query {
+    /// Returns `true` if the last node in `nodes` matches the query, with the previous nodes being
+    /// its ancestors, from outermost to innermost.
+    fn matches(&self, nodes: &[Node<'_>]) -> bool {
+        Self::matches_parts(&self.parts, nodes)
+    }
+
+    fn matches_parts(parts: &[(Combinator, Selector)], nodes: &[Node<'_>]) -> bool {
+        let Some(((combinator, selector), parts)) = parts.split_last() else { return true };
+        let Some((node, ancestors)) = nodes.split_last() else { return false };
+        if !selector.matches(node) {
+            return false;
+        }
+        if parts.is_empty() {
+            return true;
+        }
+        match combinator {
+            Combinator::Child => Self::matches_parts(parts, ancestors),
+            Combinator::Descendant => {
+                (1..=ancestors.len()).rev().any(|i| Self::matches_parts(parts, &ancestors[..i]))
+            }
+        }
+    }
+}
+
+impl Selector {
+    /// Parses a selector from the start of `s`, returning it and the rest of the string.
+    fn parse(s: &str) -> Result<(Self, &str), String> {
+        let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '*')).unwrap_or(s.len());
+        let (kind, mut rest) = s.split_at(end);
+        let kind = match kind {
+            "*" => None,
+            "" => return Err(format!("expected a node kind, found `{rest}`")),
+            kind => Some(kind.parse()?),
+        };
+        let mut attrs = Vec::new();
+        while let Some(after) = rest.strip_prefix('[') {
+            let Some(end) = after.find(']') else {
+                return Err(format!("unclosed `[` in `{s}`"));
+            };
+            let (attr, value) = match after[..end].split_once('=') {
+                Some((attr, value)) => (attr, Some(value.trim().to_string())),
+                None => (&after[..end], None),
+            };
+            attrs.push((attr.trim().parse()?, value));
+            rest = &after[end + 1..];
+        }
+        Ok((Self { kind, attrs }, rest))
+    }
+
+    fn matches(&self, node: &Node<'_>) -> bool {
+        self.kind.is_none_or(|kind| kind == node.kind())
+            && self.attrs.iter().all(|(attr, expected)| match (node.attr(*attr), expected) {
+                (Some(value), Some(expected)) => value == *expected,
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+    }
+}
+
+impl FromStr for NodeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "contract" => Self::Contract,
+            "function" => Self::Function,
+            "variable" => Self::Variable,
+            "struct" => Self::Struct,
+            "enum" => Self::Enum,
+            "udvt" => Self::Udvt,
+            "error" => Self::Error,
+            "event" => Self::Event,
+            "call" => Self::Call,
+            _ => return Err(format!("unknown node kind `{s}`")),
+        })
+    }
+}
+
+impl FromStr for Attr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "name" => Self::Name,
+            "kind" => Self::Kind,
+            "visibility" => Self::Visibility,
+            "mutability" => Self::Mutability,
+            "location" => Self::Location,
+            _ => return Err(format!("unknown attribute `{s}`")),
+        })
+    }
+}
+
+/// A queryable AST node.
+#[derive(Clone, Copy)]
+enum Node<'ast> {
+    Contract(&'ast ast::ItemContract<'ast>, Span),
+    Function(&'ast ast::ItemFunction<'ast>, Span),
+    Variable(&'ast ast::VariableDefinition<'ast>),
+    Struct(&'ast ast::ItemStruct<'ast>, Span),
+    Enum(&'ast ast::ItemEnum<'ast>, Span),
+    Udvt(&'ast ast::ItemUdvt<'ast>, Span),
+    Error(&'ast ast::ItemError<'ast>, Span),
+    Event(&'ast ast::ItemEvent<'ast>, Span),
+    Call(&'ast ast::Expr<'ast>, &'ast ast::Expr<'ast>),
+}
+
+impl Node<'_> {
+    fn kind(&self) -> NodeKind {
+        match self {
+            Self::Contract(..) => NodeKind::Contract,
+            Self::Function(..) => NodeKind::Function,
+            Self::Variable(..) => NodeKind::Variable,
+            Self::Struct(..) => NodeKind::Struct,
+            Self::Enum(..) => NodeKind::Enum,
+            Self::Udvt(..) => NodeKind::Udvt,
+            Self::Error(..) => NodeKind::Error,
+            Self::Event(..) => NodeKind::Event,
+            Self::Call(..) => NodeKind::Call,
+        }
+    }
+
+    fn span(&self) -> Span {
+        match *self {
+            Self::Contract(_, span)
+            | Self::Function(_, span)
+            | Self::Struct(_, span)
+            | Self::Enum(_, span)
+            | Self::Udvt(_, span)
+            | Self::Error(_, span)
+            | Self::Event(_, span) => span,
+            Self::Variable(var) => var.span,
+            Self::Call(call, _) => call.span,
+        }
+    }
+
+    fn name(&self) -> Option<Ident> {
+        match *self {
+            Self::Contract(contract, _) => Some(contract.name),
+            Self::Function(function, _) => function.header.name,
+            Self::Variable(var) => var.name,
+            Self::Struct(strukt, _) => Some(strukt.name),
+            Self::Enum(enum_, _) => Some(enum_.name),
+            Self::Udvt(udvt, _) => Some(udvt.name),
+            Self::Error(error, _) => Some(error.name),
+            Self::Event(event, _) => Some(event.name),
+            Self::Call(_, mut callee) => loop {
+                match &callee.kind {
+                    ast::ExprKind::Ident(ident) | ast::ExprKind::Member(_, ident) => {
+                        break Some(*ident);
+                    }
+                    ast::ExprKind::CallOptions(expr, _) => callee = &**expr,
+                    _ => break None,
+                }
+            },
+        }
+    }
+
+    fn attr(&self, attr: Attr) -> Option<String> {
+        let value = match (attr, *self) {
+            (Attr::Name, _) => return self.name().map(|name| name.to_string()),
+            (Attr::Kind, Self::Contract(contract, _)) => match contract.kind {
+                ast::ContractKind::AbstractContract => "abstract",
+                kind => kind.to_str(),
+            },
+            (Attr::Kind, Self::Function(function, _)) => function.kind.to_str(),
+            (Attr::Visibility, Self::Function(function, _)) => {
+                function.header.visibility()?.to_str()
+            }
+            (Attr::Visibility, Self::Variable(var)) => var.visibility?.to_str(),
+            (Attr::Mutability, Self::Function(function, _)) => {
+                function.header.state_mutability().to_str()
+            }
+            (Attr::Mutability, Self::Variable(var)) => var.mutability?.to_str(),
+            (Attr::Location, Self::Variable(var)) => var.data_location?.to_str(),
+            _ => return None,
+        };
+        Some(value.to_string())
+    }
+}
+
+/// Collects the nodes matching a query.
+struct Matcher<'a, 'ast> {
+    query: &'a Query,
+    /// The enclosing nodes of the current node, from outermost to innermost.
+    stack: Vec<Node<'ast>>,
+    matches: Vec<Node<'ast>>,
+}
+
+impl<'ast> Matcher<'_, 'ast> {
+    fn with_node(
+        &mut self,
+        node: Node<'ast>,
+        f: impl FnOnce(&mut Self) -> ControlFlow<Never>,
+    ) -> ControlFlow<Never> {
+        self.stack.push(node);
+        if self.query.matches(&self.stack) {
+            self.matches.push(node);
+        }
+        let r = f(self);
+        self.stack.pop();
+        r
+    }
+}
+
+impl<'ast> Visit<'ast> for Matcher<'_, 'ast> {
+    type BreakValue = Never;
+
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        let span = item.span;
+        let node = match &item.kind {
+            ast::ItemKind::Contract(contract) => Node::Contract(contract, span),
+            ast::ItemKind::Function(function) => Node::Function(function, span),
+            ast::ItemKind::Struct(strukt) => Node::Struct(strukt, span),
+            ast::ItemKind::Enum(enum_) => Node::Enum(enum_, span),
+            ast::ItemKind::Udvt(udvt) => Node::Udvt(udvt, span),
+            ast::ItemKind::Error(error) => Node::Error(error, span),
+            ast::ItemKind::Event(event) => Node::Event(event, span),
+            // Variables are handled in `visit_variable_definition`.
+            ast::ItemKind::Variable(_)
+            | ast::ItemKind::Pragma(_)
+            | ast::ItemKind::Import(_)
+            | ast::ItemKind::Using(_) => return self.walk_item(item),
+        };
+        self.with_node(node, |this| this.walk_item(item))
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        self.with_node(Node::Variable(var), |this| this.walk_variable_definition(var))
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        if let ast::ExprKind::Call(callee, _) = &expr.kind {
+            return self.with_node(Node::Call(expr, &**callee), |this| this.walk_expr(expr));
+        }
+        self.walk_expr(expr)
+    }
+}
+
+/// A printed match.
+#[derive(serde::Serialize)]
+struct Match {
+    file: String,
+    kind: NodeKind,
+    name: Option<String>,
+    start: Position,
+    end: Position,
+    snippet: String,
+}
+
+/// A 1-based line and column.
+#[derive(serde::Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Match {
+    fn new(sess: &Session, node: &Node<'_>) -> Self {
+        let sm = sess.source_map();
+        let span = node.span();
+        let lo = sm.lookup_char_pos(span.lo());
+        let hi = sm.lookup_char_pos(span.hi());
+        Self {
+            file: sm.filename_for_diagnostics(&lo.file.name).to_string(),
+            kind: node.kind(),
+            name: node.name().map(|name| name.to_string()),
+            start: Position { line: lo.line, column: lo.col.0 + 1 },
+            end: Position { line: hi.line, column: hi.col.0 + 1 },
+            snippet: sm.span_to_snippet(span).unwrap_or_default(),
+        }
+    }
+}
+
+/// Entry point for the `ast-query` subcommand.
+pub(super) fn run(args: AstQueryArgs, mut opts: CompileOpts) -> ExitCode {
+    opts.input.extend(args.input.iter().cloned());
+    // Only query the given files.
+    opts.unstable.no_resolve_imports = true;
+    let result = super::compile::run_compiler_with(opts, |compiler| {
+        {
+            let mut pcx = compiler.parse();
+            pcx.load_files(args.input.iter().map(Path::new))?;
+            pcx.parse();
+        }
+
+        let gcx = compiler.gcx();
+        gcx.sess.dcx.has_errors()?;
+        let mut matches = Vec::new();
+        for ast in gcx.sources.asts() {
+            let mut matcher =
+                Matcher { query: &args.query, stack: Vec::new(), matches: Vec::new() };
+            let _ = matcher.visit_source_unit(ast);
+            matches.extend(matcher.matches.iter().map(|node| Match::new(gcx.sess, node)));
+        }
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&matches).unwrap());
+        } else {
+            for Match { file, start, snippet, .. } in &matches {
+                let line = snippet.lines().next().unwrap_or_default();
+                println!("{file}:{}:{}: {line}", start.line, start.column);
+            }
+        }
+        Ok(())
+    });
+
+    if result.is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query() {
+        let query: Query = "contract[name=Foo] function[visibility=external]".parse().unwrap();
+        assert_eq!(query.parts.len(), 2);
+        assert_eq!(query.parts[0].1.kind, Some(NodeKind::Contract));
+        assert_eq!(query.parts[0].1.attrs, [(Attr::Name, Some("Foo".to_string()))]);
+        assert_eq!(query.parts[1].0, Combinator::Descendant);
+        assert_eq!(query.parts[1].1.attrs, [(Attr::Visibility, Some("external".to_string()))]);
+
+        let query: Query = " * > call[name][kind = x] ".parse().unwrap();
+        assert_eq!(query.parts.len(), 2);
+        assert_eq!(query.parts[0].1.kind, None);
+        assert_eq!(query.parts[1].0, Combinator::Child);
+        assert_eq!(
+            query.parts[1].1.attrs,
+            [(Attr::Name, None), (Attr::Kind, Some("x".to_string()))]
+        );
+    }
+
+    #[test]
+    fn parse_query_errors() {
+        let err = |s: &str| s.parse::<Query>().unwrap_err();
+        assert_eq!(err(""), "empty query");
+        assert_eq!(err("foo"), "unknown node kind `foo`");
+        assert_eq!(err("call[foo]"), "unknown attribute `foo`");
+        assert_eq!(err("call[name"), "unclosed `[` in `call[name`");
+        assert_eq!(err("contract >"), "expected a selector after `>`");
+        assert_eq!(err("> call"), "expected a node kind, found `> call`");
+        assert_eq!(err("call(x)"), "expected whitespace or `>` after selector, found `(x)`");
+    }
+}
@@ -1,12 +1,26 @@
-use solar_config::CompileOpts;
-use solar_interface::{Result, Session};
+use crate::{exit_code, overlay::OverlayFileLoader, progress_bar::ProgressBar};
+use indexmap::IndexMap;
+use solar_config::{CompileOpts, CompilerStage};
+use solar_interface::{Result, Session, source_map::FileName};
 use solar_sema::{CompilerRef, ParsingContext};
-use std::{ops::ControlFlow, process::ExitCode};
+use std::{ops::ControlFlow, path::PathBuf, process::ExitCode};
 
 pub(super) fn run(opts: CompileOpts) -> ExitCode {
-    match run_compiler_args(opts) {
+    let uniform_exit_code = opts.unstable.uniform_exit_code;
+    let mut error_stage = None;
+    let result = if opts.standard_json {
+        run_compiler_args(opts)
+    } else {
+        run_compiler_with(opts, |compiler| {
+            let result = run_default(compiler);
+            error_stage = compiler.gcx().error_stage();
+            result
+        })
+    };
+    match result {
         Ok(()) => ExitCode::SUCCESS,
-        Err(_) => ExitCode::FAILURE,
+        Err(_) if uniform_exit_code => ExitCode::FAILURE,
+        Err(_) => exit_code::from_error_stage(error_stage),
     }
 }
 
@@ -33,7 +47,7 @@ fn run_default(compiler: &mut CompilerRef<'_>) -> Result {
             for arg in pcx.sess.opts.input.clone() {
                 if arg == "-" {
                     if !seen_stdin {
-                        pcx.load_stdin()?;
+                        load_stdin(pcx)?;
                     }
                     seen_stdin = true;
                     continue;
@@ -53,6 +67,37 @@ fn run_default(compiler: &mut CompilerRef<'_>) -> Result {
     .map(|_| ())
 }
 
+/// Sources passed through `stdin` as `{"sources": {"a.sol": "...", ...}}`.
+#[derive(serde::Deserialize)]
+struct StdinSources {
+    sources: IndexMap<String, String>,
+}
+
+/// Loads `stdin` into the context.
+///
+/// Solidity sources cannot start with `{`, so such input is parsed as [`StdinSources`] instead of
+/// a single `<stdin>` source.
+fn load_stdin(pcx: &mut ParsingContext<'_>) -> Result {
+    let source_map = pcx.sess.source_map();
+    let src = source_map
+        .file_loader()
+        .load_stdin()
+        .map_err(|e| pcx.dcx().err(format!("couldn't read stdin: {e}")).emit())?;
+    if !src.trim_start().starts_with('{') {
+        let file = source_map
+            .new_source_file(FileName::Stdin, src)
+            .map_err(|e| pcx.dcx().err(format!("failed to load source: {e}")).emit())?;
+        pcx.add_file(file);
+        return Ok(());
+    }
+
+    let StdinSources { sources } = serde_json::from_str(&src)
+        .map_err(|e| pcx.dcx().err(format!("invalid JSON sources in stdin: {e}")).emit())?;
+    let files =
+        sources.into_iter().map(|(name, src)| (PathBuf::from(name), src)).collect::<Vec<_>>();
+    pcx.par_load_files_with_contents(files)
+}
+
 pub(crate) fn run_pipeline(
     compiler: &mut CompilerRef<'_>,
     load_sources: impl FnOnce(&mut ParsingContext<'_>) -> Result,
@@ -64,7 +109,9 @@ pub(crate) fn run_pipeline(
     }
 
     let mut pcx = compiler.parse();
+    let progress_bar = ProgressBar::install(sess, pcx.progress());
     load_sources(&mut pcx)?;
+    let inputs = pcx.files().cloned().collect::<Vec<_>>();
     pcx.parse();
 
     if compiler.gcx().sources.is_empty() {
@@ -83,10 +130,19 @@ pub(crate) fn run_pipeline(
     let ControlFlow::Continue(()) = compiler.analysis()? else {
         return Ok(ControlFlow::Break(()));
     };
+    drop(progress_bar);
+
+    crate::plugin::run_plugins(compiler.gcx())?;
+
+    if compiler.start_codegen().is_break() {
+        return Ok(ControlFlow::Break(()));
+    }
 
     // Code generation (MIR, EVM IR, and bytecode) is experimental and not part of the
     // stable, solc-compatible pipeline yet, so it is gated behind `-Zcodegen`.
-    let needs_codegen = sess.opts.emit.iter().any(|e| e.is_codegen())
+    let stop_after_codegen = sess.stop_after(CompilerStage::Codegen);
+    let needs_codegen = stop_after_codegen
+        || sess.opts.emit.iter().any(|e| e.is_codegen())
         || sess.opts.unstable.dump.as_ref().is_some_and(|dump| dump.needs_codegen());
     if needs_codegen && !sess.opts.unstable.codegen {
         return Err(sess
@@ -96,7 +152,12 @@ pub(crate) fn run_pipeline(
             .emit());
     }
 
-    crate::emit::emit_requested(compiler)?;
+    if stop_after_codegen {
+        crate::emit::generate_bytecode(compiler.gcx())?;
+        return Ok(ControlFlow::Break(()));
+    }
+
+    crate::emit::emit_requested(compiler, &inputs)?;
 
     Ok(ControlFlow::Continue(()))
 }
@@ -120,6 +181,9 @@ pub(crate) fn run_session_with(
 
 fn new_session(opts: CompileOpts) -> Session {
     let mut sess = Session::new(opts);
+    if !sess.opts.overlays.is_empty() {
+        sess.source_map().set_file_loader(OverlayFileLoader::new(&sess.opts.overlays));
+    }
     sess.infer_language();
     sess
 }
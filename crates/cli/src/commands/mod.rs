@@ -4,11 +4,13 @@ use crate::args::{Args, Subcommands};
 use solar_data_structures::fmt::line_diff;
 use std::{fmt::Display, process::ExitCode};
 
+pub(crate) mod ast_query;
 pub mod compile;
 pub(crate) mod evm_opt;
 #[cfg(feature = "lsp")]
 mod lsp;
 pub(crate) mod mir_opt;
+pub(crate) mod version;
 
 fn print_pass_diff(
     name: impl Display,
@@ -23,11 +25,16 @@ fn print_pass_diff(
 
 pub(crate) fn run(args: Args) -> ExitCode {
     let Args { commands, compile } = args;
+    if compile.print_config {
+        print!("{}", solar_config::FileOpts::from_opts(&compile).to_toml());
+        return ExitCode::SUCCESS;
+    }
     match commands {
         #[cfg(feature = "lsp")]
         Some(Subcommands::Lsp(args)) => lsp::run(args),
         Some(Subcommands::MirOpt(args)) => mir_opt::run(args, compile),
         Some(Subcommands::EvmOpt(args)) => evm_opt::run(args, compile),
+        Some(Subcommands::AstQuery(args)) => ast_query::run(args, compile),
         None => compile::run(compile),
     }
 }
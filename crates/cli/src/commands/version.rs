@@ -0,0 +1,63 @@
+//! `solar --version --verbose` and `solar --version --json`.
+
+use crate::utils;
+use solar_config::version::BuildInfo;
+use std::{ffi::OsString, process::ExitCode};
+
+/// How to print the version information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Verbose,
+    Json,
+}
+
+/// Prints the build information if the arguments are `--version` with `--verbose` or `--json`.
+///
+/// Clap prints the version as soon as it sees `--version`, so the arguments are checked before
+/// they are parsed.
+pub(crate) fn try_run(args: &[OsString]) -> Option<ExitCode> {
+    let format = parse_format(args.iter().skip(1).map(|arg| arg.to_str()))?;
+    let info = BuildInfo::new().with_allocator(utils::ALLOCATOR_NAME);
+    match format {
+        Format::Verbose => println!("{info}"),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&info).unwrap()),
+    }
+    Some(ExitCode::SUCCESS)
+}
+
+fn parse_format<'a>(args: impl Iterator<Item = Option<&'a str>>) -> Option<Format> {
+    let mut version = false;
+    let mut format = None;
+    for arg in args {
+        match arg? {
+            "-V" | "--version" => version = true,
+            "-v" | "--verbose" => format = Some(format.unwrap_or(Format::Verbose)),
+            "--json" => format = Some(Format::Json),
+            _ => return None,
+        }
+    }
+    if version { format } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn format(args: &[&str]) -> Option<Format> {
+        parse_format(args.iter().map(|&arg| Some(arg)))
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(format(&["--version", "--verbose"]), Some(Format::Verbose));
+        assert_eq!(format(&["-v", "-V"]), Some(Format::Verbose));
+        assert_eq!(format(&["--version", "--json"]), Some(Format::Json));
+        assert_eq!(format(&["--json", "--verbose", "--version"]), Some(Format::Json));
+
+        assert_eq!(format(&["--version"]), None);
+        assert_eq!(format(&["--verbose", "a.sol"]), None);
+        assert_eq!(format(&["--json"]), None);
+        assert_eq!(format(&["--version", "--verbose", "a.sol"]), None);
+    }
+}
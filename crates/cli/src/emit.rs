@@ -1,14 +1,19 @@
 use alloy_json_abi::AbiItem;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Address, Bytes, keccak256};
 use solar_codegen::{Backend, EvmCodegen, backend::evm::ir, lower};
-use solar_config::{CompilerOutput, Dump, DumpKind};
+use solar_config::{CompilerOutput, Dump, DumpKind, OptimizationMode};
 use solar_data_structures::{bit_set::DenseBitSet, map::FxHashMap};
-use solar_interface::Result;
-use solar_sema::{CompilerRef, Gcx, hir::ContractId};
+use solar_interface::{Result, events::PassTimer, source_map::SourceFile};
+use solar_sema::{
+    CompilerRef, Gcx,
+    hir::{ContractId, SourceId},
+    output::StorageLayoutOutput,
+};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
+    fs::{self, File},
     io::{self, Write},
-    path::Path,
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
@@ -32,35 +37,223 @@ struct CombinedJsonContract<'a> {
     bin_runtime: Option<Bytes>,
     #[serde(skip_serializing_if = "Option::is_none")]
     hashes: Option<Hashes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_layout: Option<StorageLayoutOutput>,
+}
+
+/// A contract artifact in the format of Foundry's `ConfigurableContractArtifact`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContractArtifact<'a> {
+    abi: Vec<AbiItem<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytecode: Option<ArtifactBytecode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deployed_bytecode: Option<ArtifactBytecode>,
+    method_identifiers: Hashes,
+    raw_metadata: String,
+    metadata: serde_json::Value,
+    /// The ID of the contract's source.
+    id: u32,
+    // The solc JSON AST is not supported yet. Foundry accepts artifacts without it.
+    // #[serde(skip_serializing_if = "Option::is_none")]
+    // ast: Option<serde_json::Value>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactBytecode {
+    object: String,
+    /// Always empty: library calls are either linked with `--libraries` or inlined, so the
+    /// bytecode never contains placeholders.
+    link_references: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+}
+
+impl ArtifactBytecode {
+    fn new(bytes: &Bytes) -> Self {
+        Self {
+            object: alloy_primitives::hex::encode_prefixed(bytes),
+            link_references: BTreeMap::new(),
+        }
+    }
+
+    /// The bytecode of a contract that cannot be deployed.
+    fn empty() -> Self {
+        Self::new(&Bytes::new())
+    }
 }
 
-pub(crate) fn emit_requested(compiler: &mut CompilerRef<'_>) -> Result {
+pub(crate) fn emit_requested(compiler: &mut CompilerRef<'_>, inputs: &[Arc<SourceFile>]) -> Result {
+    let _timer = PassTimer::new("emit");
     let gcx = compiler.gcx();
-    dump_mir(gcx)?;
-    emit_combined_json(gcx)?;
-    dump_evm_ir(gcx)
+    emit_deps(gcx, inputs)?;
+    let contracts = gcx.selected_contracts().collect::<Vec<_>>();
+    dump_mir(gcx, &contracts)?;
+    note_non_deployable_contracts(gcx, &contracts);
+    emit_combined_json(gcx, &contracts)?;
+    emit_abi_files(gcx, &contracts)?;
+    emit_artifacts(gcx, &contracts)?;
+    dump_evm_ir(gcx, &contracts)
+}
+
+/// Generates the bytecode of all selected deployable contracts without emitting it.
+pub(crate) fn generate_bytecode(gcx: Gcx<'_>) -> Result {
+    let _timer = PassTimer::new("codegen");
+    let contracts = gcx.selected_contracts().collect::<Vec<_>>();
+    generate_contract_bytecodes(gcx, &contracts, false).map(drop)
+}
+
+/// Notes each selected interface or abstract contract when bytecode output is requested, since
+/// its bytecode is left empty.
+fn note_non_deployable_contracts(gcx: Gcx<'_>, contracts: &[ContractId]) {
+    let sess = gcx.sess;
+    let emits_bytecode = sess.do_emit(CompilerOutput::Bin)
+        || sess.do_emit(CompilerOutput::BinRuntime)
+        || (sess.opts.out.is_some() && sess.opts.unstable.codegen);
+    if !emits_bytecode {
+        return;
+    }
+
+    for &id in contracts {
+        if is_deployable(gcx, id) {
+            continue;
+        }
+        let contract = gcx.hir.contract(id);
+        gcx.dcx()
+            .note(format!(
+                "no bytecode is generated for {} `{}`",
+                contract.kind.to_str(),
+                contract.name
+            ))
+            .span(contract.name.span)
+            .note("it cannot be deployed, so its bytecode output is empty")
+            .emit();
+    }
 }
 
-fn emit_combined_json(gcx: Gcx<'_>) -> Result {
+fn emit_deps(gcx: Gcx<'_>, inputs: &[Arc<SourceFile>]) -> Result {
+    let sess = gcx.sess;
+    let (emit_makefile, emit_json) =
+        (sess.do_emit(CompilerOutput::Deps), sess.do_emit(CompilerOutput::DepsJson));
+    if !emit_makefile && !emit_json {
+        return Ok(());
+    }
+
+    let deps = source_dependencies(gcx, inputs);
+    let write = |file_name: &str, f: &dyn Fn(&mut dyn Write) -> io::Result<()>| {
+        let out_path = sess.opts.out_dir.as_deref().map(|dir| dir.join(file_name));
+        out_writer(out_path.as_deref())
+            .and_then(|mut writer| {
+                f(&mut writer)?;
+                writer.flush()
+            })
+            .map_err(|e| sess.dcx.err(format!("failed to write to output: {e}")).emit())
+    };
+    if emit_makefile {
+        write("deps.d", &|writer| write_makefile_deps(writer, &deps))?;
+    }
+    if emit_json {
+        write("deps.json", &|writer| {
+            let deps = deps.iter().cloned().collect::<BTreeMap<_, _>>();
+            to_json(&mut *writer, &deps, sess.opts.pretty_json)?;
+            writer.write_all(b"\n")
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns the name of each input source along with the names of all the sources that it
+/// transitively imports, in breadth-first import order.
+fn source_dependencies(gcx: Gcx<'_>, inputs: &[Arc<SourceFile>]) -> Vec<(String, Vec<String>)> {
+    inputs
+        .iter()
+        .filter_map(|file| {
+            let (input, _) =
+                gcx.hir.sources_enumerated().find(|(_, source)| Arc::ptr_eq(&source.file, file))?;
+            let deps = imported_sources(gcx, input).into_iter().map(|id| source_name(gcx, id));
+            Some((source_name(gcx, input), deps.collect()))
+        })
+        .collect()
+}
+
+/// Returns all the sources that the given source transitively imports, in breadth-first import
+/// order.
+fn imported_sources(gcx: Gcx<'_>, source: SourceId) -> Vec<SourceId> {
+    let mut seen = DenseBitSet::new_empty(gcx.hir.source_ids().len());
+    seen.insert(source);
+    let mut queue = VecDeque::from([source]);
+    let mut imports = Vec::new();
+    while let Some(id) = queue.pop_front() {
+        for &(_, import) in gcx.hir.source(id).imports {
+            if seen.insert(import) {
+                imports.push(import);
+                queue.push_back(import);
+            }
+        }
+    }
+    imports
+}
+
+fn source_name(gcx: Gcx<'_>, id: SourceId) -> String {
+    gcx.hir.source(id).file.name.display().to_string().replace('\\', "/")
+}
+
+/// Writes one `input: dependencies...` Makefile rule per input, like `gcc -MD`.
+fn write_makefile_deps(writer: &mut dyn Write, deps: &[(String, Vec<String>)]) -> io::Result<()> {
+    for (input, deps) in deps {
+        write!(writer, "{}:", escape_makefile_path(input))?;
+        for dep in deps {
+            write!(writer, " {}", escape_makefile_path(dep))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+fn escape_makefile_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            ' ' | '#' | ':' => escaped.push('\\'),
+            '$' => escaped.push('$'),
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn emit_combined_json(gcx: Gcx<'_>, contracts: &[ContractId]) -> Result {
     let sess = gcx.sess;
     let (mut emit_abi, mut emit_hashes, mut emit_bin, mut emit_bin_runtime) =
         (false, false, false, false);
+    let (mut emit_metadata, mut emit_storage_layout) = (false, false);
     for output in &sess.opts.emit {
         match output {
             CompilerOutput::Abi => emit_abi = true,
             CompilerOutput::Hashes => emit_hashes = true,
             CompilerOutput::Bin => emit_bin = true,
             CompilerOutput::BinRuntime => emit_bin_runtime = true,
+            CompilerOutput::Metadata => emit_metadata = true,
+            CompilerOutput::StorageLayout => emit_storage_layout = true,
             _ => {}
         }
     }
 
-    if !emit_abi && !emit_hashes && !emit_bin && !emit_bin_runtime {
+    if !emit_abi
+        && !emit_hashes
+        && !emit_bin
+        && !emit_bin_runtime
+        && !emit_metadata
+        && !emit_storage_layout
+    {
         return Ok(());
     }
 
     let bytecodes = if emit_bin || emit_bin_runtime {
-        Some(generate_contract_bytecodes(gcx, false)?)
+        Some(generate_contract_bytecodes(gcx, contracts, false)?)
     } else {
         None
     };
@@ -70,7 +263,7 @@ fn emit_combined_json(gcx: Gcx<'_>) -> Result {
         version: solar_config::version::SEMVER_VERSION,
     };
 
-    for id in gcx.hir.contract_ids() {
+    for &id in contracts {
         let name = contract_output_name(gcx, id);
         let contract_output = output.contracts.entry(name).or_default();
 
@@ -80,8 +273,24 @@ fn emit_combined_json(gcx: Gcx<'_>) -> Result {
         if emit_hashes {
             contract_output.hashes = Some(contract_hashes(gcx, id));
         }
+        if emit_metadata {
+            // Like solc, the metadata is a JSON string rather than an object.
+            let metadata = contract_metadata(gcx, id, &gcx.contract_abi(id));
+            contract_output.metadata = Some(metadata.to_string());
+        }
+        if emit_storage_layout {
+            contract_output.storage_layout = Some(gcx.storage_layout(id));
+        }
 
-        if let Some(bytecode) = bytecodes.as_ref().and_then(|bytecodes| bytecodes.get(&id)) {
+        if bytecodes.is_some() && !is_deployable(gcx, id) {
+            // Like solc, non-deployable contracts have empty bytecode rather than none.
+            if emit_bin {
+                contract_output.bin = Some(Bytes::new());
+            }
+            if emit_bin_runtime {
+                contract_output.bin_runtime = Some(Bytes::new());
+            }
+        } else if let Some(bytecode) = bytecodes.as_ref().and_then(|bytecodes| bytecodes.get(&id)) {
             if emit_bin {
                 contract_output.bin = Some(bytecode.deployment.clone());
             }
@@ -115,13 +324,208 @@ fn write_output_json<T: serde::Serialize>(
     Ok(())
 }
 
+/// Writes the ABI of each contract to its own `<file name>/<contract name>.abi` file in the
+/// `--out-dir` directory.
+///
+/// Unlike solc's flat `<contract name>.abi` files, these use the same layout as the `--out`
+/// artifacts, so that contracts with the same name in different files don't overwrite each other.
+fn emit_abi_files(gcx: Gcx<'_>, contracts: &[ContractId]) -> Result {
+    let sess = gcx.sess;
+    let Some(out_dir) = sess.opts.out_dir.as_deref() else { return Ok(()) };
+    if !sess.do_emit(CompilerOutput::Abi) {
+        return Ok(());
+    }
+
+    let names = contracts
+        .iter()
+        .map(|&id| {
+            let contract = gcx.hir.contract(id);
+            (source_name(gcx, contract.source), contract.name.to_string())
+        })
+        .collect::<Vec<_>>();
+    for (&id, path) in contracts.iter().zip(artifact_paths(&names, "abi")) {
+        let path = out_dir.join(path);
+        let abi = gcx.contract_abi(id);
+        let write = || -> io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut writer = out_writer(Some(&path))?;
+            to_json(&mut writer, &abi, sess.opts.pretty_json)?;
+            writer.write_all(b"\n")?;
+            writer.flush()
+        };
+        write().map_err(|e| {
+            sess.dcx.err(format!("failed to write ABI to `{}`: {e}", path.display())).emit()
+        })?;
+    }
+
+    Ok(())
+}
+
+fn emit_artifacts(gcx: Gcx<'_>, contracts: &[ContractId]) -> Result {
+    let sess = gcx.sess;
+    let Some(out) = sess.opts.out.as_deref() else { return Ok(()) };
+
+    // Bytecode is only included when code generation is enabled.
+    let bytecodes = if sess.opts.unstable.codegen {
+        Some(generate_contract_bytecodes(gcx, contracts, false)?)
+    } else {
+        None
+    };
+
+    let names = contracts
+        .iter()
+        .map(|&id| {
+            let contract = gcx.hir.contract(id);
+            (source_name(gcx, contract.source), contract.name.to_string())
+        })
+        .collect::<Vec<_>>();
+    for (&id, path) in contracts.iter().zip(artifact_paths(&names, "json")) {
+        let (bytecode, deployed_bytecode) = match bytecodes.as_ref() {
+            // Non-deployable contracts are marked with an empty `0x` object, as in Foundry.
+            Some(_) if !is_deployable(gcx, id) => {
+                (Some(ArtifactBytecode::empty()), Some(ArtifactBytecode::empty()))
+            }
+            Some(bytecodes) => bytecodes.get(&id).map_or((None, None), |bytecode| {
+                (
+                    Some(ArtifactBytecode::new(&bytecode.deployment)),
+                    Some(ArtifactBytecode::new(&bytecode.runtime)),
+                )
+            }),
+            None => (None, None),
+        };
+        let abi = gcx.contract_abi(id);
+        let metadata = contract_metadata(gcx, id, &abi);
+        let artifact = ContractArtifact {
+            bytecode,
+            deployed_bytecode,
+            method_identifiers: contract_hashes(gcx, id),
+            raw_metadata: metadata.to_string(),
+            metadata,
+            id: gcx.hir.contract(id).source.index() as u32,
+            abi,
+        };
+
+        let path = out.join(path);
+        write_artifact(&path, &artifact, sess.opts.overwrite, sess.opts.pretty_json).map_err(
+            |e| {
+                if e.kind() == io::ErrorKind::AlreadyExists {
+                    sess.dcx
+                        .err(format!("artifact `{}` already exists", path.display()))
+                        .help("pass `--overwrite` to replace existing artifacts")
+                        .emit()
+                } else {
+                    sess.dcx
+                        .err(format!("failed to write artifact `{}`: {e}", path.display()))
+                        .emit()
+                }
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the solc metadata of the given contract.
+fn contract_metadata(gcx: Gcx<'_>, id: ContractId, abi: &[AbiItem<'_>]) -> serde_json::Value {
+    let opts = &gcx.sess.opts;
+    let contract = gcx.hir.contract(id);
+    let libraries = opts
+        .libraries
+        .iter()
+        .map(|library| (library.name.clone(), Address::from(library.address).to_string()))
+        .collect::<BTreeMap<_, _>>();
+    let remappings = opts.import_remappings.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let mut sources = imported_sources(gcx, contract.source);
+    sources.push(contract.source);
+    let sources = sources
+        .into_iter()
+        .map(|id| {
+            let hash = keccak256(gcx.hir.source(id).file.src.as_bytes());
+            (source_name(gcx, id), serde_json::json!({ "keccak256": hash.to_string(), "urls": [] }))
+        })
+        .collect::<BTreeMap<_, _>>();
+    serde_json::json!({
+        "compiler": { "version": solar_config::version::SEMVER_VERSION },
+        "language": "Solidity",
+        "output": {
+            "abi": abi,
+            "devdoc": gcx.dev_documentation(id),
+            "userdoc": gcx.user_documentation(id),
+        },
+        "settings": {
+            "compilationTarget": { source_name(gcx, contract.source): contract.name.as_str() },
+            "evmVersion": opts.evm_version.to_string(),
+            "libraries": libraries,
+            "optimizer": { "enabled": opts.optimization != OptimizationMode::None },
+            "remappings": remappings,
+        },
+        "sources": sources,
+        "version": 1,
+    })
+}
+
+/// Returns the artifact path of each `(source path, contract name)` pair, relative to the output
+/// directory.
+///
+/// Artifacts are laid out as `<file name>/<contract name>.<extension>`, like Foundry's `out/`
+/// directory. Contracts whose artifact path collides with another's are nested under their full
+/// source path instead.
+fn artifact_paths(contracts: &[(String, String)], extension: &str) -> Vec<PathBuf> {
+    let short_path = |(source, name): &(String, String)| {
+        let file_name = source.rsplit('/').next().unwrap_or(source);
+        Path::new(file_name).join(format!("{name}.{extension}"))
+    };
+
+    let mut counts = FxHashMap::<PathBuf, usize>::default();
+    for contract in contracts {
+        *counts.entry(short_path(contract)).or_default() += 1;
+    }
+
+    contracts
+        .iter()
+        .map(|contract| {
+            let path = short_path(contract);
+            if counts[&path] == 1 {
+                return path;
+            }
+            let (source, name) = contract;
+            let mut path = Path::new(source)
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(component) => Some(component),
+                    _ => None,
+                })
+                .collect::<PathBuf>();
+            path.push(format!("{name}.{extension}"));
+            path
+        })
+        .collect()
+}
+
+fn write_artifact(
+    path: &Path,
+    artifact: &ContractArtifact<'_>,
+    overwrite: bool,
+    pretty: bool,
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = if overwrite { File::create(path)? } else { File::create_new(path)? };
+    let mut writer = io::BufWriter::new(file);
+    to_json(&mut writer, artifact, pretty)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
 fn contract_output_name(gcx: Gcx<'_>, id: ContractId) -> String {
     let contract = gcx.hir.contract(id);
-    let source = gcx.hir.source(contract.source);
-    format!("{}:{}", source.file.name.display().to_string().replace('\\', "/"), contract.name)
+    format!("{}:{}", source_name(gcx, contract.source), contract.name)
 }
 
-fn dump_mir(gcx: Gcx<'_>) -> Result {
+fn dump_mir(gcx: Gcx<'_>, contracts: &[ContractId]) -> Result {
     let sess = gcx.sess;
     let Some(dump) = &sess.opts.unstable.dump else { return Ok(()) };
     if !dump.kinds.contains(&DumpKind::Mir) && !dump.kinds.contains(&DumpKind::MirCfg) {
@@ -130,7 +534,7 @@ fn dump_mir(gcx: Gcx<'_>) -> Result {
 
     let mut writer = out_writer(None)
         .map_err(|e| sess.dcx.err(format!("failed to write to output: {e}")).emit())?;
-    for id in matching_dump_contracts(gcx, dump)? {
+    for id in matching_dump_contracts(gcx, dump, contracts)? {
         let module = lower::lower_contract(gcx, id);
         gcx.dcx().has_errors()?;
         if dump.kinds.contains(&DumpKind::Mir) {
@@ -145,7 +549,7 @@ fn dump_mir(gcx: Gcx<'_>) -> Result {
     Ok(())
 }
 
-fn is_dumpable_contract(gcx: Gcx<'_>, id: ContractId) -> bool {
+fn is_deployable(gcx: Gcx<'_>, id: ContractId) -> bool {
     let contract = gcx.hir.contract(id);
     !contract.kind.is_interface() && !contract.kind.is_abstract_contract()
 }
@@ -161,17 +565,21 @@ fn contract_dump_path_matches(gcx: Gcx<'_>, id: ContractId, path: &str) -> bool
     path == gcx.contract_fully_qualified_name(id).to_string().replace('\\', "/")
 }
 
-fn matching_dump_contracts(gcx: Gcx<'_>, dump: &Dump) -> Result<Vec<ContractId>> {
+fn matching_dump_contracts(
+    gcx: Gcx<'_>,
+    dump: &Dump,
+    contracts: &[ContractId],
+) -> Result<Vec<ContractId>> {
     let Some(paths) = dump.paths.as_deref() else {
-        return Ok(gcx.hir.contract_ids().filter(|&id| is_dumpable_contract(gcx, id)).collect());
+        return Ok(contracts.iter().copied().filter(|&id| is_deployable(gcx, id)).collect());
     };
 
     let mut seen = DenseBitSet::new_empty(gcx.hir.contract_ids().len());
     let mut contracts = Vec::new();
     for path in paths {
         let mut matched = false;
-        for id in gcx.hir.contract_ids() {
-            if !is_dumpable_contract(gcx, id) || !contract_dump_path_matches(gcx, id, path) {
+        for &id in contracts {
+            if !is_deployable(gcx, id) || !contract_dump_path_matches(gcx, id, path) {
                 continue;
             }
             matched = true;
@@ -182,7 +590,7 @@ fn matching_dump_contracts(gcx: Gcx<'_>, dump: &Dump) -> Result<Vec<ContractId>>
         if !matched {
             let kinds = dump.kinds.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
             let msg = format!("`-Zdump={kinds}={path}` did not match any contract");
-            let note = format!("available contracts: {}", available_dump_contracts(gcx));
+            let note = format!("available contracts: {}", available_dump_contracts(gcx, contracts));
             return Err(gcx.sess.dcx.err(msg).note(note).emit());
         }
     }
@@ -208,37 +616,42 @@ fn write_mir_dump_contract(
     Ok(())
 }
 
-fn available_dump_contracts(gcx: Gcx<'_>) -> String {
-    gcx.hir
-        .contract_ids()
-        .filter(|&id| is_dumpable_contract(gcx, id))
+fn available_dump_contracts(gcx: Gcx<'_>, contracts: &[ContractId]) -> String {
+    contracts
+        .iter()
+        .copied()
+        .filter(|&id| is_deployable(gcx, id))
         .map(|id| gcx.contract_fully_qualified_name(id).to_string().replace('\\', "/"))
         .collect::<Vec<_>>()
         .join(", ")
 }
 
-fn dump_evm_ir(gcx: Gcx<'_>) -> Result {
+fn dump_evm_ir(gcx: Gcx<'_>, contracts: &[ContractId]) -> Result {
     let sess = gcx.sess;
     let Some(dump) = &sess.opts.unstable.dump else { return Ok(()) };
     if !dump.kinds.contains(&DumpKind::EvmIr) && !dump.kinds.contains(&DumpKind::EvmIrRuntime) {
         return Ok(());
     }
 
-    let contracts = matching_dump_contracts(gcx, dump)?;
-    let bytecodes = generate_contract_bytecodes(gcx, true)?;
+    let matched = matching_dump_contracts(gcx, dump, contracts)?;
+    let bytecodes = generate_contract_bytecodes(gcx, contracts, true)?;
     let mut writer = out_writer(None)
         .map_err(|e| sess.dcx.err(format!("failed to write to output: {e}")).emit())?;
     if sess.opts.out_dir.is_none()
-        && sess
-            .opts
-            .emit
-            .iter()
-            .any(|output| matches!(output, CompilerOutput::Abi | CompilerOutput::Hashes))
+        && sess.opts.emit.iter().any(|output| {
+            matches!(
+                output,
+                CompilerOutput::Abi
+                    | CompilerOutput::Hashes
+                    | CompilerOutput::Metadata
+                    | CompilerOutput::StorageLayout
+            )
+        })
     {
         writeln!(writer)
             .map_err(|e| sess.dcx.err(format!("failed to write to output: {e}")).emit())?;
     }
-    for id in contracts {
+    for id in matched {
         let Some(bytecode) = bytecodes.get(&id) else { continue };
         let name = gcx.contract_fully_qualified_name(id);
         if dump.kinds.contains(&DumpKind::EvmIr) {
@@ -265,16 +678,17 @@ struct GeneratedBytecodes {
     runtime_evm_ir: Option<String>,
 }
 
+/// Generates the bytecode of the given deployable contracts, and of the contracts they create.
 fn generate_contract_bytecodes(
     gcx: Gcx<'_>,
+    contracts: &[ContractId],
     capture_evm_ir: bool,
 ) -> Result<FxHashMap<ContractId, GeneratedBytecodes>> {
     let mut all_bytecodes = FxHashMap::default();
     let mut artifacts = FxHashMap::default();
     let mut visiting = DenseBitSet::new_empty(gcx.hir.contract_ids().len());
-    for id in gcx.hir.contract_ids() {
-        let contract = gcx.hir.contract(id);
-        if !contract.kind.is_interface() && !contract.kind.is_abstract_contract() {
+    for &id in contracts {
+        if is_deployable(gcx, id) {
             ensure_contract_bytecode(
                 gcx,
                 id,
@@ -387,7 +801,7 @@ fn contract_hashes(gcx: Gcx<'_>, id: ContractId) -> Hashes {
 
 fn out_writer(path: Option<&Path>) -> io::Result<impl io::Write> {
     let out: Box<dyn io::Write> = if let Some(path) = path {
-        Box::new(std::fs::File::create(path)?)
+        Box::new(File::create(path)?)
     } else {
         Box::new(std::io::stdout())
     };
@@ -405,3 +819,49 @@ fn to_json<W: io::Write, T: serde::Serialize>(
         serde_json::to_writer(writer, value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makefile_deps() {
+        let deps: [(&str, &[&str]); 3] = [
+            ("src/A.sol", &["src/B.sol", "lib/my lib/C.sol"]),
+            ("src/B.sol", &[]),
+            ("src/$#.sol", &["C:/D.sol"]),
+        ];
+        let deps = deps.map(|(input, deps)| {
+            (input.to_string(), deps.iter().map(|dep| dep.to_string()).collect::<Vec<_>>())
+        });
+        let mut out = Vec::new();
+        write_makefile_deps(&mut out, &deps).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "src/A.sol: src/B.sol lib/my\\ lib/C.sol\nsrc/B.sol:\nsrc/$$\\#.sol: C\\:/D.sol\n"
+        );
+    }
+
+    #[test]
+    fn artifact_paths_nest_colliding_contracts() {
+        let contracts = [
+            ("src/Counter.sol", "Counter"),
+            ("src/a/Token.sol", "Token"),
+            ("src/b/Token.sol", "Token"),
+            ("src/b/Token.sol", "Helper"),
+            ("/abs/../lib/Token.sol", "Lib"),
+        ]
+        .map(|(source, name)| (source.to_string(), name.to_string()));
+        assert_eq!(
+            artifact_paths(&contracts, "json"),
+            [
+                "Counter.sol/Counter.json",
+                "src/a/Token.sol/Token.json",
+                "src/b/Token.sol/Token.json",
+                "Token.sol/Helper.json",
+                "Token.sol/Lib.json",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+}
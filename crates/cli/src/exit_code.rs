@@ -0,0 +1,54 @@
+//! Exit codes of the `solar` binary.
+//!
+//! | Code  | Meaning                                                        |
+//! |-------|----------------------------------------------------------------|
+//! | `0`   | Success.                                                       |
+//! | `1`   | An error that is not specific to a compiler stage.             |
+//! | `2`   | Invalid command-line arguments or configuration.               |
+//! | `3`   | A parse error.                                                 |
+//! | `4`   | A semantic error: name resolution, type checking, or analysis. |
+//! | `5`   | A code generation error.                                       |
+//! | `101` | An internal compiler error.                                    |
+//!
+//! When errors are emitted in several stages, the exit code is the one of the earliest stage.
+
+use solar_config::CompilerStage;
+use std::process::ExitCode;
+
+/// Successful compilation.
+pub const SUCCESS: u8 = 0;
+
+/// An error that is not specific to a compiler stage, such as an unreadable input file.
+pub const FAILURE: u8 = 1;
+
+/// Invalid command-line arguments or configuration.
+///
+/// This is the exit code used by `clap`.
+pub const USAGE_ERROR: u8 = 2;
+
+/// An error emitted while parsing.
+pub const PARSE_ERROR: u8 = 3;
+
+/// An error emitted during lowering, type checking, or analysis.
+pub const SEMANTIC_ERROR: u8 = 4;
+
+/// An error emitted during code generation.
+pub const CODEGEN_ERROR: u8 = 5;
+
+/// An internal compiler error, also known as an ICE.
+///
+/// This is the exit code of a Rust panic.
+pub const INTERNAL_ERROR: u8 = 101;
+
+/// Returns the exit code of a compilation whose first error was emitted in the given stage.
+pub fn from_error_stage(stage: Option<CompilerStage>) -> ExitCode {
+    let code = match stage {
+        Some(CompilerStage::Parsing) => PARSE_ERROR,
+        Some(CompilerStage::Lowering | CompilerStage::Typeck | CompilerStage::Analysis) => {
+            SEMANTIC_ERROR
+        }
+        Some(CompilerStage::Codegen) => CODEGEN_ERROR,
+        _ => FAILURE,
+    };
+    ExitCode::from(code)
+}
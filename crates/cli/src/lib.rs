@@ -5,13 +5,17 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use clap::Parser as _;
+use clap::{CommandFactory, FromArgMatches, parser::ValueSource};
 use solar_interface::Result;
 use std::process::ExitCode;
 
 pub use solar_config::{self as config, CompileOpts, LspArgs, UnstableOpts, version};
 
 mod emit;
+pub mod exit_code;
+mod overlay;
+pub mod plugin;
+mod progress_bar;
 pub mod standard_json;
 
 pub mod commands;
@@ -45,12 +49,21 @@ use tracing as _;
 pub fn main() -> ExitCode {
     signal_handler::install();
     solar_interface::panic_hook::install();
-    let _guard = utils::init_logger(utils::LogDestination::Stderr);
 
-    let args = match parse_args(std::env::args_os()) {
+    let args = std::env::args_os().collect::<Vec<_>>();
+    if let Some(code) = commands::version::try_run(&args) {
+        return code;
+    }
+    let args = match parse_args(args) {
         Ok(args) => args,
         Err(e) => e.exit(),
     };
+    let _guard = utils::init_logger_with_events(
+        utils::LogDestination::Stderr,
+        args.compile.events.as_deref(),
+        args.compile.reproducible,
+        args.compile.unstable.trace_lowering.as_deref(),
+    );
     commands::run(args)
 }
 
@@ -59,7 +72,14 @@ where
     I: IntoIterator<Item = T>,
     T: Into<std::ffi::OsString> + Clone,
 {
-    let mut args = Args::try_parse_from(itr)?;
+    let mut command = Args::command();
+    let matches = command.try_get_matches_from_mut(itr)?;
+    let mut args = Args::from_arg_matches(&matches).map_err(|e| e.format(&mut command))?;
+    // Options from the configuration file and the environment only replace default values.
+    let is_set = |id: &str| {
+        matches.value_source(id).is_some_and(|source| source != ValueSource::DefaultValue)
+    };
+    args.compile.load_config(is_set).map_err(|e| command.error(clap::error::ErrorKind::Io, e))?;
     args.compile.finish()?;
     Ok(args)
 }
@@ -0,0 +1,57 @@
+//! Overlays of unsaved editor buffers, set with `--overlay`.
+
+use solar_data_structures::map::FxHashMap;
+use solar_interface::source_map::{FileLoader, RealFileLoader};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// A file loader that reads the overlaid sources from other files.
+pub(crate) struct OverlayFileLoader {
+    /// The files to read instead, by the absolute path of the source that they replace.
+    overlays: FxHashMap<PathBuf, PathBuf>,
+}
+
+impl OverlayFileLoader {
+    pub(crate) fn new(overlays: &[(PathBuf, PathBuf)]) -> Self {
+        let overlays =
+            overlays.iter().map(|(path, file)| (absolute_path(path), file.clone())).collect();
+        Self { overlays }
+    }
+
+    fn overlay(&self, path: &Path) -> Option<&Path> {
+        self.overlays.get(&absolute_path(path)).map(PathBuf::as_path)
+    }
+}
+
+impl FileLoader for OverlayFileLoader {
+    fn canonicalize_path(&self, path: &Path) -> io::Result<PathBuf> {
+        // An overlaid source may be a new file which does not exist on disk yet.
+        let absolute = absolute_path(path);
+        if self.overlays.contains_key(&absolute) {
+            return Ok(absolute);
+        }
+        RealFileLoader.canonicalize_path(path)
+    }
+
+    fn load_stdin(&self) -> io::Result<String> {
+        RealFileLoader.load_stdin()
+    }
+
+    fn load_file(&self, path: &Path) -> io::Result<String> {
+        RealFileLoader.load_file(self.overlay(path).unwrap_or(path))
+    }
+
+    fn load_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        RealFileLoader.load_binary_file(self.overlay(path).unwrap_or(path))
+    }
+}
+
+/// Returns the canonical form of `path` if it exists, or its absolute form otherwise.
+fn absolute_path(path: &Path) -> PathBuf {
+    RealFileLoader
+        .canonicalize_path(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
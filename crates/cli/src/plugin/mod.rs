@@ -0,0 +1,556 @@
+//! Compiler plugins loaded from dynamic libraries.
+//!
+//! A plugin is a shared library that exports a [`PLUGIN_ENTRY_POINT`] function of type
+//! [`PluginEntryPoint`]. The entry point is called once when the plugin is loaded and returns a
+//! [`PluginDescriptor`] declaring the plugin's ABI version, name, capabilities, and callbacks.
+//!
+//! After analysis, the plugin's `run` callback receives a [`PluginCompiler`], a façade over the
+//! compiler through which it can inspect sources and contracts, emit diagnostics, and write output
+//! files. Each of these is only permitted if the plugin declared the matching
+//! [`PluginCapabilities`] flag.
+//!
+//! Plugins with a `.wasm` extension are instead loaded as sandboxed WebAssembly modules when the
+//! `wasm-plugins` feature is enabled. See the `wasm` module for their interface.
+
+use solar_interface::Result;
+use solar_sema::Gcx;
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    path::{Component, Path, PathBuf},
+};
+
+#[cfg(feature = "wasm-plugins")]
+mod wasm;
+
+/// The plugin ABI version implemented by this compiler.
+///
+/// This is bumped on every breaking change to the types in this module.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The name of the symbol that plugins must export.
+pub const PLUGIN_ENTRY_POINT: &str = "solar_plugin_entry";
+
+/// The type of the [`PLUGIN_ENTRY_POINT`] function.
+///
+/// The returned descriptor must be valid for as long as the plugin is loaded.
+pub type PluginEntryPoint = unsafe extern "C" fn() -> *const PluginDescriptor;
+
+/// The type of [`PluginDescriptor::run`]. Returns zero on success.
+pub type PluginRun = unsafe extern "C" fn(compiler: *const PluginCompiler) -> c_int;
+
+bitflags::bitflags! {
+    /// Permissions declared by a plugin.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct PluginCapabilities: u32 {
+        /// Read source file names and contents, and contract names.
+        const READ_SOURCES = 1 << 0;
+        /// Emit diagnostics.
+        const DIAGNOSTICS = 1 << 1;
+        /// Write files to the output directory (`--out-dir`).
+        const WRITE_OUTPUT = 1 << 2;
+    }
+}
+
+/// Describes a plugin. Returned by the [`PLUGIN_ENTRY_POINT`] function.
+#[repr(C)]
+pub struct PluginDescriptor {
+    /// Must be [`PLUGIN_ABI_VERSION`].
+    pub abi_version: u32,
+    /// The null-terminated UTF-8 name of the plugin.
+    pub name: *const c_char,
+    /// The [`PluginCapabilities`] bits requested by the plugin.
+    pub capabilities: u32,
+    /// Called after analysis.
+    pub run: Option<PluginRun>,
+}
+
+/// Compiler façade passed to [`PluginDescriptor::run`].
+///
+/// All returned strings are null-terminated UTF-8, and are only valid for the duration of the
+/// `run` callback. Functions that are not permitted by the plugin's capabilities return null or
+/// a non-zero value, and report an error.
+#[repr(C)]
+pub struct PluginCompiler {
+    /// Returns the number of sources.
+    pub source_count: unsafe extern "C" fn(compiler: *const PluginCompiler) -> usize,
+    /// Returns the name of the source at the given index, or null if out of bounds.
+    pub source_name:
+        unsafe extern "C" fn(compiler: *const PluginCompiler, index: usize) -> *const c_char,
+    /// Returns the contents of the source at the given index, or null if out of bounds.
+    pub source_contents:
+        unsafe extern "C" fn(compiler: *const PluginCompiler, index: usize) -> *const c_char,
+    /// Returns the number of contracts.
+    pub contract_count: unsafe extern "C" fn(compiler: *const PluginCompiler) -> usize,
+    /// Returns the fully qualified name of the contract at the given index, or null if out of
+    /// bounds.
+    pub contract_name:
+        unsafe extern "C" fn(compiler: *const PluginCompiler, index: usize) -> *const c_char,
+    /// Emits an error (`level = 0`) or a warning (`level = 1`). Returns zero on success.
+    pub emit_diagnostic: unsafe extern "C" fn(
+        compiler: *const PluginCompiler,
+        level: u32,
+        message: *const c_char,
+    ) -> c_int,
+    /// Writes `len` bytes of `data` to the file `name`, relative to the output directory. Returns
+    /// zero on success.
+    pub write_output: unsafe extern "C" fn(
+        compiler: *const PluginCompiler,
+        name: *const c_char,
+        data: *const u8,
+        len: usize,
+    ) -> c_int,
+    ctx: *const c_void,
+}
+
+/// A loaded plugin.
+struct Plugin {
+    path: PathBuf,
+    name: String,
+    capabilities: PluginCapabilities,
+    run: Option<PluginRun>,
+    // Must be dropped last, since `run` points into the library.
+    _library: dl::Library,
+}
+
+/// State behind [`PluginCompiler::ctx`].
+///
+/// Everything the plugin can observe is computed up front, and everything it produces is collected
+/// and processed after `run` returns, so that no compiler state is borrowed across the FFI
+/// boundary.
+struct PluginContext {
+    capabilities: PluginCapabilities,
+    sources: Vec<(CString, CString)>,
+    contracts: Vec<CString>,
+    diagnostics: RefCell<Vec<(bool, String)>>,
+    outputs: RefCell<Vec<(PathBuf, Vec<u8>)>>,
+    denied: RefCell<PluginCapabilities>,
+}
+
+/// Loads and runs the plugins specified with `--plugin`.
+pub(crate) fn run_plugins(gcx: Gcx<'_>) -> Result {
+    let sess = gcx.sess;
+    for path in &sess.opts.plugins {
+        if path.extension() == Some("wasm".as_ref()) {
+            wasm::run(gcx, path)?;
+            continue;
+        }
+
+        let plugin = load(path).map_err(|msg| {
+            sess.dcx.err(format!("failed to load plugin `{}`", path.display())).note(msg).emit()
+        })?;
+        run(gcx, &plugin)?;
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<Plugin, String> {
+    let library = dl::Library::open(path)?;
+    let entry = CString::new(PLUGIN_ENTRY_POINT).unwrap();
+    let Some(entry) = library.symbol(&entry) else {
+        return Err(format!("the library does not export `{PLUGIN_ENTRY_POINT}`"));
+    };
+    // SAFETY: plugins must export the entry point with the `PluginEntryPoint` signature.
+    let entry = unsafe { std::mem::transmute::<*mut c_void, PluginEntryPoint>(entry) };
+    // SAFETY: see above.
+    let descriptor = unsafe { entry() };
+    // SAFETY: the descriptor is either null or valid for as long as the library is loaded.
+    let Some(descriptor) = (unsafe { descriptor.as_ref() }) else {
+        return Err(format!("`{PLUGIN_ENTRY_POINT}` returned null"));
+    };
+    if descriptor.abi_version != PLUGIN_ABI_VERSION {
+        return Err(format!(
+            "the plugin uses ABI version {}, but this compiler supports version {PLUGIN_ABI_VERSION}",
+            descriptor.abi_version
+        ));
+    }
+    let Some(capabilities) = PluginCapabilities::from_bits(descriptor.capabilities) else {
+        return Err(format!(
+            "the plugin declares unknown capabilities {:#x}",
+            descriptor.capabilities
+        ));
+    };
+    let name = if descriptor.name.is_null() {
+        path.display().to_string()
+    } else {
+        // SAFETY: non-null names must be null-terminated.
+        unsafe { CStr::from_ptr(descriptor.name) }.to_string_lossy().into_owned()
+    };
+    Ok(Plugin {
+        path: path.to_path_buf(),
+        name,
+        capabilities,
+        run: descriptor.run,
+        _library: library,
+    })
+}
+
+fn run(gcx: Gcx<'_>, plugin: &Plugin) -> Result {
+    let sess = gcx.sess;
+    let Some(run) = plugin.run else { return Ok(()) };
+
+    let mut cx = PluginContext {
+        capabilities: plugin.capabilities,
+        sources: Vec::new(),
+        contracts: Vec::new(),
+        diagnostics: RefCell::default(),
+        outputs: RefCell::default(),
+        denied: RefCell::new(PluginCapabilities::empty()),
+    };
+    if plugin.capabilities.contains(PluginCapabilities::READ_SOURCES) {
+        cx.sources = gcx
+            .sources
+            .iter()
+            .map(|source| {
+                (
+                    c_string(source.file.name.display().to_string()),
+                    c_string(source.file.src.to_string()),
+                )
+            })
+            .collect();
+        cx.contracts = gcx
+            .hir
+            .contract_ids()
+            .map(|id| c_string(gcx.contract_fully_qualified_name(id).to_string()))
+            .collect();
+    }
+
+    let compiler = PluginCompiler {
+        source_count,
+        source_name,
+        source_contents,
+        contract_count,
+        contract_name,
+        emit_diagnostic,
+        write_output,
+        ctx: (&raw const cx).cast(),
+    };
+    // SAFETY: `run` was provided by the plugin, and `compiler` outlives the call.
+    let status = unsafe { run(&compiler) };
+
+    let name = &plugin.name;
+    let mut result = Ok(());
+    for (is_error, message) in cx.diagnostics.take() {
+        let note = format!("emitted by plugin `{name}`");
+        if is_error {
+            result = Err(sess.dcx.err(message).note(note).emit());
+        } else {
+            sess.dcx.warn(message).note(note).emit();
+        }
+    }
+    for capability in cx.denied.take().iter_names().map(|(capability, _)| capability) {
+        result = Err(sess
+            .dcx
+            .err(format!("plugin `{name}` used the `{capability}` capability without declaring it"))
+            .emit());
+    }
+    let outputs = cx.outputs.take();
+    if !outputs.is_empty() {
+        let Some(out_dir) = &sess.opts.out_dir else {
+            return Err(sess
+                .dcx
+                .err(format!(
+                    "plugin `{name}` writes output files, but no output directory was given"
+                ))
+                .help("pass `--out-dir` to write plugin outputs")
+                .emit());
+        };
+        for (path, data) in outputs {
+            let path = out_dir.join(path);
+            let written = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, data));
+            if let Err(e) = written {
+                result =
+                    Err(sess.dcx.err(format!("failed to write `{}`: {e}", path.display())).emit());
+            }
+        }
+    }
+    if status != 0 && result.is_ok() {
+        result = Err(sess
+            .dcx
+            .err(format!("plugin `{name}` failed with status {status}"))
+            .note(format!("loaded from `{}`", plugin.path.display()))
+            .emit());
+    }
+    result
+}
+
+fn c_string(s: String) -> CString {
+    // Interior nul bytes cannot be represented; truncate at the first one.
+    CString::new(s).unwrap_or_else(|e| {
+        let nul = e.nul_position();
+        let mut bytes = e.into_vec();
+        bytes.truncate(nul);
+        CString::new(bytes).unwrap()
+    })
+}
+
+/// Returns the context of `compiler` if it has the given capability, recording a denial otherwise.
+///
+/// # Safety
+///
+/// `compiler` must be the pointer passed to [`PluginDescriptor::run`].
+unsafe fn context<'a>(
+    compiler: *const PluginCompiler,
+    capability: PluginCapabilities,
+) -> Option<&'a PluginContext> {
+    // SAFETY: guaranteed by the caller.
+    let cx = unsafe { &*(*compiler).ctx.cast::<PluginContext>() };
+    if !cx.capabilities.contains(capability) {
+        *cx.denied.borrow_mut() |= capability;
+        return None;
+    }
+    Some(cx)
+}
+
+unsafe extern "C" fn source_count(compiler: *const PluginCompiler) -> usize {
+    // SAFETY: called by the plugin with the pointer it was given.
+    unsafe { context(compiler, PluginCapabilities::READ_SOURCES) }.map_or(0, |cx| cx.sources.len())
+}
+
+unsafe extern "C" fn source_name(compiler: *const PluginCompiler, index: usize) -> *const c_char {
+    // SAFETY: called by the plugin with the pointer it was given.
+    unsafe { context(compiler, PluginCapabilities::READ_SOURCES) }
+        .and_then(|cx| cx.sources.get(index))
+        .map_or(std::ptr::null(), |(name, _)| name.as_ptr())
+}
+
+unsafe extern "C" fn source_contents(
+    compiler: *const PluginCompiler,
+    index: usize,
+) -> *const c_char {
+    // SAFETY: called by the plugin with the pointer it was given.
+    unsafe { context(compiler, PluginCapabilities::READ_SOURCES) }
+        .and_then(|cx| cx.sources.get(index))
+        .map_or(std::ptr::null(), |(_, contents)| contents.as_ptr())
+}
+
+unsafe extern "C" fn contract_count(compiler: *const PluginCompiler) -> usize {
+    // SAFETY: called by the plugin with the pointer it was given.
+    unsafe { context(compiler, PluginCapabilities::READ_SOURCES) }
+        .map_or(0, |cx| cx.contracts.len())
+}
+
+unsafe extern "C" fn contract_name(compiler: *const PluginCompiler, index: usize) -> *const c_char {
+    // SAFETY: called by the plugin with the pointer it was given.
+    unsafe { context(compiler, PluginCapabilities::READ_SOURCES) }
+        .and_then(|cx| cx.contracts.get(index))
+        .map_or(std::ptr::null(), |name| name.as_ptr())
+}
+
+unsafe extern "C" fn emit_diagnostic(
+    compiler: *const PluginCompiler,
+    level: u32,
+    message: *const c_char,
+) -> c_int {
+    // SAFETY: called by the plugin with the pointer it was given.
+    let Some(cx) = (unsafe { context(compiler, PluginCapabilities::DIAGNOSTICS) }) else {
+        return -1;
+    };
+    if message.is_null() || level > 1 {
+        return -1;
+    }
+    // SAFETY: non-null messages must be null-terminated.
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned();
+    cx.diagnostics.borrow_mut().push((level == 0, message));
+    0
+}
+
+unsafe extern "C" fn write_output(
+    compiler: *const PluginCompiler,
+    name: *const c_char,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    // SAFETY: called by the plugin with the pointer it was given.
+    let Some(cx) = (unsafe { context(compiler, PluginCapabilities::WRITE_OUTPUT) }) else {
+        return -1;
+    };
+    if name.is_null() || (data.is_null() && len != 0) {
+        return -1;
+    }
+    // SAFETY: non-null names must be null-terminated.
+    let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else { return -1 };
+    // Outputs must stay inside the output directory.
+    let path = Path::new(name);
+    if !path.components().all(|component| matches!(component, Component::Normal(_))) {
+        return -1;
+    }
+    let data = if len == 0 {
+        Vec::new()
+    } else {
+        // SAFETY: non-null `data` must point to `len` readable bytes.
+        unsafe { std::slice::from_raw_parts(data, len) }.to_vec()
+    };
+    cx.outputs.borrow_mut().push((path.to_path_buf(), data));
+    0
+}
+
+#[cfg(unix)]
+mod dl {
+    use std::{
+        ffi::{CStr, CString, c_void},
+        os::unix::ffi::OsStrExt,
+        path::Path,
+    };
+
+    /// A dynamic library opened with `dlopen`.
+    pub(super) struct Library(*mut c_void);
+
+    impl Library {
+        pub(super) fn open(path: &Path) -> Result<Self, String> {
+            let path = CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+            // SAFETY: `path` is null-terminated.
+            let handle = unsafe { libc::dlopen(path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+            if handle.is_null() {
+                return Err(last_error());
+            }
+            Ok(Self(handle))
+        }
+
+        pub(super) fn symbol(&self, name: &CStr) -> Option<*mut c_void> {
+            // SAFETY: `self.0` is a valid handle and `name` is null-terminated.
+            let symbol = unsafe { libc::dlsym(self.0, name.as_ptr()) };
+            (!symbol.is_null()).then_some(symbol)
+        }
+    }
+
+    impl Drop for Library {
+        fn drop(&mut self) {
+            // SAFETY: `self.0` is a valid handle that is not used after this.
+            unsafe { libc::dlclose(self.0) };
+        }
+    }
+
+    fn last_error() -> String {
+        // SAFETY: `dlerror` returns null or a null-terminated string.
+        let error = unsafe { libc::dlerror() };
+        if error.is_null() {
+            return "unknown error".into();
+        }
+        // SAFETY: see above.
+        unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(not(unix))]
+mod dl {
+    use std::{
+        ffi::{CStr, c_void},
+        path::Path,
+    };
+
+    /// Dynamic libraries are not supported on this platform.
+    pub(super) enum Library {}
+
+    impl Library {
+        pub(super) fn open(_path: &Path) -> Result<Self, String> {
+            Err("plugins are only supported on Unix platforms".into())
+        }
+
+        pub(super) fn symbol(&self, _name: &CStr) -> Option<*mut c_void> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+mod wasm {
+    use solar_interface::Result;
+    use solar_sema::Gcx;
+    use std::path::Path;
+
+    /// WASM plugins are not supported without the `wasm-plugins` feature.
+    pub(super) fn run(gcx: Gcx<'_>, path: &Path) -> Result {
+        Err(gcx
+            .sess
+            .dcx
+            .err(format!("cannot load WASM plugin `{}`", path.display()))
+            .note("this build of solar does not support WASM plugins")
+            .help("rebuild solar with the `wasm-plugins` feature")
+            .emit())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// A minimal plugin whose descriptor declares `ABI_VERSION`.
+    const PLUGIN: &str = r#"
+        #[repr(C)]
+        pub struct PluginDescriptor {
+            abi_version: u32,
+            name: *const std::ffi::c_char,
+            capabilities: u32,
+            run: Option<unsafe extern "C" fn(*const std::ffi::c_void) -> std::ffi::c_int>,
+        }
+
+        unsafe impl Sync for PluginDescriptor {}
+
+        static DESCRIPTOR: PluginDescriptor = PluginDescriptor {
+            abi_version: ABI_VERSION,
+            name: c"test".as_ptr(),
+            capabilities: 0,
+            run: None,
+        };
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn solar_plugin_entry() -> *const PluginDescriptor {
+            &DESCRIPTOR
+        }
+    "#;
+
+    /// Compiles `src` to a dynamic library with `rustc` and returns its path.
+    fn build(name: &str, src: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("solar-plugin-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let src_path = dir.join("plugin.rs");
+        std::fs::write(&src_path, src).unwrap();
+        let lib_path = dir.join(format!("plugin.{}", std::env::consts::DLL_EXTENSION));
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let status = Command::new(rustc)
+            .args(["--crate-type=cdylib", "--edition=2024", "-o"])
+            .arg(&lib_path)
+            .arg(&src_path)
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to compile the test plugin");
+        lib_path
+    }
+
+    fn plugin(name: &str, abi_version: u32) -> PathBuf {
+        build(name, &PLUGIN.replace("ABI_VERSION", &abi_version.to_string()))
+    }
+
+    #[test]
+    fn loads_plugin() {
+        let path = plugin("loads", PLUGIN_ABI_VERSION);
+        let plugin = load(&path).unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(plugin.name, "test");
+        assert_eq!(plugin.capabilities, PluginCapabilities::empty());
+        assert!(plugin.run.is_none());
+    }
+
+    #[test]
+    fn rejects_abi_version_mismatch() {
+        let path = plugin("abi-mismatch", PLUGIN_ABI_VERSION + 1);
+        assert_eq!(
+            load(&path).err().unwrap(),
+            format!(
+                "the plugin uses ABI version {}, but this compiler supports version {}",
+                PLUGIN_ABI_VERSION + 1,
+                PLUGIN_ABI_VERSION,
+            )
+        );
+    }
+
+    #[test]
+    fn requires_entry_point() {
+        let path = build("no-entry", "pub fn unrelated() {}");
+        assert_eq!(load(&path).err().unwrap(), "the library does not export `solar_plugin_entry`");
+    }
+}
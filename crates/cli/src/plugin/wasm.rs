@@ -0,0 +1,284 @@
+//! Sandboxed compiler plugins loaded from WebAssembly modules.
+//!
+//! A WASM plugin is a module that exports:
+//! - `memory`: its linear memory;
+//! - `solar_alloc(len: i32) -> i32`: allocates `len` bytes and returns a pointer to them;
+//! - `solar_plugin_run(ptr: i32, len: i32) -> i32`: runs the plugin on the JSON input at `ptr`,
+//!   returning zero on success.
+//!
+//! The input is a JSON object with the sources and a summary of the contracts and functions in
+//! the HIR. All spans are `[lo, hi]` byte offsets into the contents of the item's source:
+//!
+//! ```json
+//! {
+//!   "abiVersion": 1,
+//!   "sources": [{ "name": "A.sol", "contents": "..." }],
+//!   "contracts": [{
+//!     "name": "A", "kind": "contract", "source": 0, "span": [0, 42],
+//!     "functions": [{
+//!       "name": "f", "kind": "function", "visibility": "public",
+//!       "stateMutability": "view", "span": [14, 40]
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! The only function the module may import is
+//! `solar.emit_diagnostic(level: i32, source: i32, lo: i32, hi: i32, ptr: i32, len: i32)`, which
+//! emits an error (`level = 0`) or a warning (`level = 1`) with the UTF-8 message at `ptr`. A
+//! negative `source` emits the diagnostic without a span.
+//!
+//! Plugins have no access to the file system, network, or clock, and their execution is bounded in
+//! both memory and fuel.
+
+use solar_data_structures::index::Idx;
+use solar_interface::{BytePos, Result, Span};
+use solar_sema::{Gcx, hir};
+use std::path::Path;
+use wasmtime::{
+    Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+/// The WASM plugin input version. Bumped on every breaking change to the input or imports.
+const WASM_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The maximum size of a plugin's linear memory, in bytes.
+const MEMORY_LIMIT: usize = 256 << 20;
+
+/// The amount of fuel a plugin may consume, roughly the number of executed instructions.
+const FUEL_LIMIT: u64 = 10_000_000_000;
+
+struct HostState {
+    limits: StoreLimits,
+    diagnostics: Vec<PluginDiagnostic>,
+}
+
+struct PluginDiagnostic {
+    is_error: bool,
+    /// `(source index, lo, hi)`.
+    span: Option<(usize, u32, u32)>,
+    message: String,
+}
+
+/// Runs the WASM plugin at `path`.
+pub(super) fn run(gcx: Gcx<'_>, path: &Path) -> Result {
+    let sess = gcx.sess;
+    let (diagnostics, status) = run_module(path, input(gcx).as_bytes()).map_err(|e| {
+        sess.dcx
+            .err(format!("failed to run WASM plugin `{}`", path.display()))
+            .note(e.to_string())
+            .emit()
+    })?;
+
+    let note = format!("emitted by plugin `{}`", path.display());
+    let mut result = Ok(());
+    for PluginDiagnostic { is_error, span, message } in diagnostics {
+        let span = span.and_then(|(source, lo, hi)| plugin_span(gcx, source, lo, hi));
+        if is_error {
+            let mut diag = sess.dcx.err(message).note(note.clone());
+            if let Some(span) = span {
+                diag = diag.span(span);
+            }
+            result = Err(diag.emit());
+        } else {
+            let mut diag = sess.dcx.warn(message).note(note.clone());
+            if let Some(span) = span {
+                diag = diag.span(span);
+            }
+            diag.emit();
+        }
+    }
+    if status != 0 && result.is_ok() {
+        result = Err(sess
+            .dcx
+            .err(format!("WASM plugin `{}` failed with status {status}", path.display()))
+            .emit());
+    }
+    result
+}
+
+fn run_module(path: &Path, input: &[u8]) -> wasmtime::Result<(Vec<PluginDiagnostic>, i32)> {
+    let engine = engine()?;
+    let module = Module::from_file(&engine, path)?;
+    run_instance(&engine, &module, input)
+}
+
+fn engine() -> wasmtime::Result<Engine> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+fn run_instance(
+    engine: &Engine,
+    module: &Module,
+    input: &[u8],
+) -> wasmtime::Result<(Vec<PluginDiagnostic>, i32)> {
+    let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+    let mut store = Store::new(engine, HostState { limits, diagnostics: Vec::new() });
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(FUEL_LIMIT)?;
+
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(
+        "solar",
+        "emit_diagnostic",
+        |mut caller: Caller<'_, HostState>,
+         level: i32,
+         source: i32,
+         lo: i32,
+         hi: i32,
+         ptr: i32,
+         len: i32|
+         -> wasmtime::Result<()> {
+            let message = read_string(&mut caller, ptr, len)?;
+            let span = usize::try_from(source).ok().map(|source| (source, lo as u32, hi as u32));
+            caller.data_mut().diagnostics.push(PluginDiagnostic {
+                is_error: level == 0,
+                span,
+                message,
+            });
+            Ok(())
+        },
+    )?;
+
+    let instance = linker.instantiate(&mut store, module)?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| wasmtime::Error::msg("the module does not export `memory`"))?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "solar_alloc")?;
+    let run = instance.get_typed_func::<(i32, i32), i32>(&mut store, "solar_plugin_run")?;
+
+    let len = i32::try_from(input.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, ptr as u32 as usize, input)?;
+    let status = run.call(&mut store, (ptr, len))?;
+    Ok((std::mem::take(&mut store.data_mut().diagnostics), status))
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+        return Err(wasmtime::Error::msg("the module does not export `memory`"));
+    };
+    let mut bytes = vec![0; usize::try_from(len)?];
+    memory.read(&*caller, ptr as u32 as usize, &mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Serializes the plugin input. See the module documentation.
+fn input(gcx: Gcx<'_>) -> String {
+    let sources = gcx
+        .hir
+        .sources()
+        .map(|source| {
+            serde_json::json!({
+                "name": source.file.name.display().to_string(),
+                "contents": source.file.src.as_str(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let contracts = gcx
+        .hir
+        .contracts()
+        .map(|contract| {
+            let functions = contract
+                .all_functions()
+                .map(|id| {
+                    let function = gcx.hir.function(id);
+                    serde_json::json!({
+                        "name": function.name.map(|name| name.to_string()),
+                        "kind": function.kind.to_str(),
+                        "visibility": function.visibility.to_str(),
+                        "stateMutability": function.state_mutability.to_str(),
+                        "span": relative_span(gcx, function.source, function.span),
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({
+                "name": contract.name.to_string(),
+                "kind": contract.kind.to_str(),
+                "source": contract.source.index(),
+                "span": relative_span(gcx, contract.source, contract.span),
+                "functions": functions,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "abiVersion": WASM_PLUGIN_ABI_VERSION,
+        "sources": sources,
+        "contracts": contracts,
+    })
+    .to_string()
+}
+
+fn relative_span(gcx: Gcx<'_>, source: hir::SourceId, span: Span) -> [u32; 2] {
+    let start = gcx.hir.source(source).file.start_pos.to_u32();
+    [span.lo().to_u32().saturating_sub(start), span.hi().to_u32().saturating_sub(start)]
+}
+
+/// Converts a span reported by a plugin back into a [`Span`], if it is in bounds.
+fn plugin_span(gcx: Gcx<'_>, source: usize, lo: u32, hi: u32) -> Option<Span> {
+    let source = gcx.hir.sources().nth(source)?;
+    if lo > hi || hi as usize > source.file.src.len() {
+        return None;
+    }
+    let start = source.file.start_pos.to_u32();
+    Some(Span::new(BytePos::from_u32(start + lo), BytePos::from_u32(start + hi)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Emits a spanned error and an unspanned warning, and returns the input length as status.
+    const PLUGIN: &str = r#"
+        (module
+          (import "solar" "emit_diagnostic" (func $emit (param i32 i32 i32 i32 i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "bad")
+          (func (export "solar_alloc") (param i32) (result i32)
+            i32.const 1024)
+          (func (export "solar_plugin_run") (param $ptr i32) (param $len i32) (result i32)
+            (call $emit (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 4)
+                        (i32.const 0) (i32.const 3))
+            (call $emit (i32.const 1) (i32.const -1) (i32.const 0) (i32.const 0)
+                        (i32.const 0) (i32.const 2))
+            local.get $len))
+    "#;
+
+    fn run_wat(wat: &str, input: &[u8]) -> wasmtime::Result<(Vec<PluginDiagnostic>, i32)> {
+        let engine = engine()?;
+        let module = Module::new(&engine, wat::parse_str(wat)?)?;
+        run_instance(&engine, &module, input)
+    }
+
+    #[test]
+    fn runs_plugin() {
+        let (diagnostics, status) = run_wat(PLUGIN, b"{}").unwrap();
+        assert_eq!(status, 2);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].is_error);
+        assert_eq!(diagnostics[0].span, Some((0, 1, 4)));
+        assert_eq!(diagnostics[0].message, "bad");
+        assert!(!diagnostics[1].is_error);
+        assert_eq!(diagnostics[1].span, None);
+        assert_eq!(diagnostics[1].message, "ba");
+    }
+
+    #[test]
+    fn rejects_disallowed_imports() {
+        let wat = r#"(module (import "wasi_snapshot_preview1" "fd_write" (func)))"#;
+        assert!(run_wat(wat, b"{}").is_err());
+    }
+
+    #[test]
+    fn requires_memory_export() {
+        let wat = r#"
+            (module
+              (func (export "solar_alloc") (param i32) (result i32) i32.const 0)
+              (func (export "solar_plugin_run") (param i32 i32) (result i32) i32.const 0))
+        "#;
+        let err = run_wat(wat, b"{}").unwrap_err();
+        assert_eq!(err.to_string(), "the module does not export `memory`");
+    }
+}
@@ -0,0 +1,45 @@
+//! Terminal progress bar, enabled with `--progress`.
+
+use solar_data_structures::sync::Mutex;
+use solar_interface::Session;
+use solar_sema::Progress;
+use std::{
+    io::{self, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+/// The minimum time between two redraws.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Clears the progress bar when dropped.
+#[must_use = "the progress bar is cleared when the guard is dropped"]
+pub(crate) struct ProgressBar(());
+
+impl ProgressBar {
+    /// Draws the counts of `progress` on stderr as they change.
+    ///
+    /// Returns `None` if `--progress` was not passed or stderr is not a terminal.
+    pub(crate) fn install(sess: &Session, progress: &Progress) -> Option<Self> {
+        if !sess.opts.progress || !io::stderr().is_terminal() {
+            return None;
+        }
+        let last_draw = Mutex::new(None::<Instant>);
+        progress.set_listener(move |counts| {
+            let now = Instant::now();
+            let mut last_draw = last_draw.lock();
+            if last_draw.is_some_and(|last| now - last < REDRAW_INTERVAL) {
+                return;
+            }
+            *last_draw = Some(now);
+            // Leave the cursor at the start of the line so that diagnostics overwrite the bar.
+            let _ = write!(io::stderr().lock(), "\r\x1b[2K{counts}\r");
+        });
+        Some(Self(()))
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        let _ = write!(io::stderr().lock(), "\r\x1b[2K");
+    }
+}
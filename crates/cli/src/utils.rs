@@ -1,6 +1,7 @@
 //! Utility functions used by the Solar CLI.
 
 use solar_interface::diagnostics::DiagCtxt;
+use std::path::Path;
 
 #[cfg(feature = "tracing")]
 use solar_sema::ast::Either;
@@ -17,12 +18,16 @@ use tikv_jemallocator as _;
 cfg_if::cfg_if! {
     if #[cfg(debug_assertions)] {
         type AllocatorInner = std::alloc::System;
+        const ALLOCATOR_INNER_NAME: &str = "system";
     } else if #[cfg(feature = "mimalloc")] {
         type AllocatorInner = mimalloc::MiMalloc;
+        const ALLOCATOR_INNER_NAME: &str = "mimalloc";
     } else if #[cfg(all(feature = "jemalloc", unix))] {
         type AllocatorInner = tikv_jemallocator::Jemalloc;
+        const ALLOCATOR_INNER_NAME: &str = "jemalloc";
     } else {
         type AllocatorInner = std::alloc::System;
+        const ALLOCATOR_INNER_NAME: &str = "system";
     }
 }
 
@@ -43,6 +48,9 @@ cfg_if::cfg_if! {
 /// The global allocator used by the compiler.
 pub type Allocator = WrappedAllocator;
 
+/// The name of the global allocator used by the compiler.
+pub const ALLOCATOR_NAME: &str = ALLOCATOR_INNER_NAME;
+
 /// Create a new instance of the global allocator.
 pub const fn new_allocator() -> Allocator {
     new_wrapped_allocator()
@@ -73,9 +81,33 @@ impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogDestination {
 /// Initialize the tracing logger.
 #[must_use]
 pub fn init_logger(dst: LogDestination) -> impl Sized {
+    init_logger_with_events(dst, None, false, None)
+}
+
+/// Initialize the tracing logger, additionally writing structured
+/// [`events`](solar_interface::events) to the given file and logging the MIR lowering decisions
+/// of the `trace_lowering` contract.
+///
+/// If `reproducible` is set, the timing fields of the events are written as zero.
+#[must_use]
+pub fn init_logger_with_events(
+    dst: LogDestination,
+    events: Option<&Path>,
+    reproducible: bool,
+    trace_lowering: Option<&str>,
+) -> impl Sized {
     #[cfg(not(feature = "tracing"))]
     {
-        let _ = dst;
+        let _ = (dst, reproducible);
+        if events.is_some() {
+            let msg = "`--events` is set, but \"tracing\" support was not enabled at compile time";
+            DiagCtxt::new_early().warn(msg).emit();
+        }
+        if trace_lowering.is_some() {
+            let msg = "`-Ztrace-lowering` is set, but \"tracing\" support was not enabled at \
+                       compile time";
+            DiagCtxt::new_early().warn(msg).emit();
+        }
         if std::env::var_os("RUST_LOG").is_some() {
             let msg = "`RUST_LOG` is set, but \"tracing\" support was not enabled at compile time";
             DiagCtxt::new_early().warn(msg).emit();
@@ -88,14 +120,19 @@ pub fn init_logger(dst: LogDestination) -> impl Sized {
     }
 
     #[cfg(feature = "tracing")]
-    match try_init_logger(dst) {
+    match try_init_logger(dst, events, reproducible, trace_lowering) {
         Ok(guard) => guard,
         Err(e) => DiagCtxt::new_early().fatal(e).emit(),
     }
 }
 
 #[cfg(feature = "tracing")]
-fn try_init_logger(dst: LogDestination) -> Result<impl Sized, String> {
+fn try_init_logger(
+    dst: LogDestination,
+    events: Option<&Path>,
+    reproducible: bool,
+    trace_lowering: Option<&str>,
+) -> Result<impl Sized, String> {
     use tracing_subscriber::prelude::*;
 
     let (profile_layer, guard) = match std::env::var("SOLAR_PROFILE").as_deref() {
@@ -125,15 +162,110 @@ fn try_init_logger(dst: LogDestination) -> Result<impl Sized, String> {
         }
         Err(_) => Default::default(),
     };
+    let mut env_filter = tracing_subscriber::EnvFilter::from_default_env();
+    if let Some(contract) = trace_lowering {
+        env_filter = env_filter.add_directive(trace_lowering_directive(contract)?);
+    }
+    let events_layer = match events {
+        Some(path) => {
+            let directive = format!("{}=info", solar_interface::events::TARGET);
+            env_filter = env_filter.add_directive(directive.parse().map_err(|e| e.to_string())?);
+            let file = std::fs::File::create(path)
+                .map_err(|e| format!("failed to create events file `{}`: {e}", path.display()))?;
+            Some(EventsLayer { file: std::sync::Mutex::new(file), reproducible })
+        }
+        None => None,
+    };
+    // Events are written to their own file instead of being logged when `--events` is passed.
+    let log_events = events.is_none();
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(dst).with_filter(
+        tracing_subscriber::filter::filter_fn(move |metadata| {
+            log_events || metadata.target() != solar_interface::events::TARGET
+        }),
+    );
     tracing_subscriber::Registry::default()
-        .with(tracing_subscriber::EnvFilter::from_default_env())
+        .with(env_filter)
         .with(profile_layer)
-        .with(tracing_subscriber::fmt::layer().with_writer(dst))
+        .with(fmt_layer)
+        .with(events_layer)
         .try_init()
         .map(|()| guard)
         .map_err(|e| e.to_string())
 }
 
+/// Returns the filter directive enabling the MIR lowering events of `contract`.
+///
+/// Only the lowering span of the requested contract enables its events.
+#[cfg(feature = "tracing")]
+fn trace_lowering_directive(
+    contract: &str,
+) -> Result<tracing_subscriber::filter::Directive, String> {
+    format!("solar_codegen::lower[mir_lower_contract{{contract={contract}}}]=debug")
+        .parse()
+        .map_err(|e| format!("invalid `-Ztrace-lowering` contract: {e}"))
+}
+
+/// Writes structured [`events`](solar_interface::events) to a file as JSON Lines.
+#[cfg(feature = "tracing")]
+struct EventsLayer {
+    file: std::sync::Mutex<std::fs::File>,
+    /// Whether to write timing fields as zero, so that the file does not depend on the machine.
+    reproducible: bool,
+}
+
+#[cfg(feature = "tracing")]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for EventsLayer {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        use std::io::Write;
+
+        if event.metadata().target() != solar_interface::events::TARGET {
+            return;
+        }
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+        if self.reproducible
+            && let Some(elapsed) = fields.0.get_mut("elapsed_us")
+        {
+            *elapsed = 0.into();
+        }
+        let Ok(mut line) = serde_json::to_vec(&fields.0) else { return };
+        line.push(b'\n');
+        // Write whole lines at once so that they're not interleaved between threads.
+        let _ = self.file.lock().unwrap().write_all(&line);
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct JsonFields(serde_json::Map<String, serde_json::Value>);
+
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for JsonFields {
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().into(), format!("{value:?}").into());
+    }
+}
+
 #[cfg(feature = "tracing")]
 #[cfg(feature = "tracy")]
 fn tracy_layer() -> tracing_tracy::TracyLayer<impl tracing_tracy::Config> {
@@ -186,6 +318,87 @@ fn samply_layer() -> std::io::Result<tracing_subscriber::layer::Identity> {
     Ok(tracing_subscriber::layer::Identity::new())
 }
 
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use solar_interface::{ColorChoice, Session};
+    use solar_sema::Compiler;
+    use std::{
+        ops::ControlFlow,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+    use tracing_subscriber::prelude::*;
+
+    /// Records the fields of every event.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<serde_json::Map<String, serde_json::Value>>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Recorder {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = JsonFields::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[test]
+    fn trace_lowering_selects_contract() {
+        const SRC: &str = r#"
+contract A {
+    uint256 a;
+}
+
+contract B {
+    uint256 b;
+    uint256 c;
+    uint256 d;
+}
+"#;
+        let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).threads(1).build();
+        let mut compiler = Compiler::new(sess);
+        compiler.enter_mut(|c| {
+            let mut pcx = c.parse();
+            let file =
+                c.sess().source_map().new_source_file(PathBuf::from("test.sol"), SRC).unwrap();
+            pcx.add_file(file);
+            pcx.parse();
+
+            assert_eq!(c.lower_asts(), Ok(ControlFlow::Continue(())));
+            assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
+        });
+
+        // Returns the number of storage slots logged while lowering every contract.
+        let storage_slots = |contract: &str| {
+            let recorder = Recorder::default();
+            let filter = tracing_subscriber::EnvFilter::default()
+                .add_directive(trace_lowering_directive(contract).unwrap());
+            let subscriber =
+                tracing_subscriber::Registry::default().with(filter).with(recorder.clone());
+            compiler.enter(|c| {
+                let gcx = c.gcx();
+                tracing::subscriber::with_default(subscriber, || {
+                    for id in gcx.hir.contract_ids() {
+                        let _ = solar_codegen::lower::lower_contract(gcx, id);
+                    }
+                });
+            });
+            let events = recorder.0.lock().unwrap();
+            events
+                .iter()
+                .filter(|fields| fields.get("message").is_some_and(|m| m == "storage slot"))
+                .count()
+        };
+        assert_eq!(storage_slots("A"), 1);
+        assert_eq!(storage_slots("B"), 3);
+        assert_eq!(storage_slots("C"), 0);
+    }
+}
+
 /*
 pub(crate) fn env_to_bool(value: Option<&std::ffi::OsStr>) -> bool {
     value.is_some_and(|value| value == "1" || value == "true")
@@ -2,9 +2,12 @@
 //!
 //! The transformation passes need the same basic CFG facts over and over:
 //! reachable blocks, reverse postorder, immediate dominators, dominator-tree
-//! children, and path reachability. Keeping those in one place avoids subtle
-//! differences between passes when unreachable predecessors or critical-edge
-//! rewrites are involved.
+//! children, post-dominators, dominance frontiers, and path reachability.
+//! Keeping those in one place avoids subtle differences between passes when
+//! unreachable predecessors or critical-edge rewrites are involved.
+//!
+//! SSA construction for promoted memory goes through [`CfgInfo::phi_blocks`],
+//! the iterated dominance frontier of a variable's definitions.
 
 use std::cell::OnceCell;
 
@@ -20,9 +23,12 @@ use solar_data_structures::{
 #[derive(Clone, Debug)]
 pub(crate) struct CfgInfo {
     successors: IndexVec<BlockId, SmallVec<[BlockId; 2]>>,
+    predecessors: OnceCell<IndexVec<BlockId, Vec<BlockId>>>,
     reachable: OnceCell<DenseBitSet<BlockId>>,
     rpo: OnceCell<Vec<BlockId>>,
     dominators: OnceCell<DominatorTree>,
+    post_dominators: OnceCell<PostDominatorTree>,
+    frontiers: OnceCell<IndexVec<BlockId, Vec<BlockId>>>,
     reachability: OnceCell<FxHashMap<BlockId, DenseBitSet<BlockId>>>,
 }
 
@@ -39,9 +45,12 @@ impl CfgInfo {
             .collect();
         Self {
             successors,
+            predecessors: OnceCell::new(),
             reachable: OnceCell::new(),
             rpo: OnceCell::new(),
             dominators: OnceCell::new(),
+            post_dominators: OnceCell::new(),
+            frontiers: OnceCell::new(),
             reachability: OnceCell::new(),
         }
     }
@@ -52,6 +61,16 @@ impl CfgInfo {
         &self.successors[block]
     }
 
+    /// Returns predecessor blocks for `block`, including unreachable ones.
+    #[must_use]
+    pub(crate) fn predecessors(&self, block: BlockId) -> &[BlockId] {
+        &self.predecessor_lists()[block]
+    }
+
+    fn predecessor_lists(&self) -> &IndexVec<BlockId, Vec<BlockId>> {
+        self.predecessors.get_or_init(|| predecessors_of(&self.successors))
+    }
+
     /// Returns the blocks reachable from the entry.
     #[must_use]
     pub(crate) fn reachable(&self) -> &DenseBitSet<BlockId> {
@@ -78,22 +97,7 @@ impl CfgInfo {
     #[must_use]
     pub(crate) fn rpo(&self) -> &[BlockId] {
         self.rpo.get_or_init(|| {
-            let mut reachable = DenseBitSet::new_empty(self.successors.len());
-            let mut rpo = Vec::with_capacity(self.successors.len());
-            let mut stack = vec![(BlockId::ENTRY, 0usize)];
-            reachable.insert(BlockId::ENTRY);
-            while let Some((block, next)) = stack.last_mut() {
-                if let Some(&succ) = self.successors[*block].get(*next) {
-                    *next += 1;
-                    if reachable.insert(succ) {
-                        stack.push((succ, 0));
-                    }
-                } else {
-                    rpo.push(*block);
-                    stack.pop();
-                }
-            }
-            rpo.reverse();
+            let (rpo, reachable) = reverse_postorder(&self.successors, BlockId::ENTRY);
             let _ = self.reachable.set(reachable);
             rpo
         })
@@ -102,7 +106,84 @@ impl CfgInfo {
     /// Returns immediate-dominator information.
     #[must_use]
     pub(crate) fn dominators(&self) -> &DominatorTree {
-        self.dominators.get_or_init(|| DominatorTree::compute(&self.successors, self.rpo()))
+        self.dominators.get_or_init(|| {
+            DominatorTree::compute(self.predecessor_lists(), BlockId::ENTRY, self.rpo())
+        })
+    }
+
+    /// Returns immediate-post-dominator information.
+    #[must_use]
+    pub(crate) fn post_dominators(&self) -> &PostDominatorTree {
+        self.post_dominators.get_or_init(|| PostDominatorTree::compute(&self.successors))
+    }
+
+    /// Returns the dominance frontier of every block.
+    ///
+    /// The frontier of `block` holds the blocks where its dominance ends: the
+    /// reachable join points with a predecessor dominated by `block` that
+    /// `block` does not strictly dominate. Frontiers are sorted by block index.
+    #[must_use]
+    pub(crate) fn dominance_frontiers(&self) -> &IndexVec<BlockId, Vec<BlockId>> {
+        self.frontiers.get_or_init(|| {
+            let dominators = self.dominators();
+            let mut frontiers = index_vec![Vec::new(); self.successors.len()];
+            for block in self.successors.indices() {
+                if !self.is_reachable(block) {
+                    continue;
+                }
+                let preds: SmallVec<[BlockId; 4]> = self
+                    .predecessors(block)
+                    .iter()
+                    .copied()
+                    .filter(|&pred| self.is_reachable(pred))
+                    .collect();
+                if preds.len() < 2 {
+                    continue;
+                }
+
+                let Some(idom) = dominators.idom(block) else { continue };
+                for mut runner in preds {
+                    while runner != idom {
+                        if !frontiers[runner].contains(&block) {
+                            frontiers[runner].push(block);
+                        }
+                        let Some(next) = dominators.idom(runner) else { break };
+                        if next == runner {
+                            break;
+                        }
+                        runner = next;
+                    }
+                }
+            }
+            for frontier in &mut frontiers {
+                frontier.sort_by_key(|block| block.index());
+            }
+            frontiers
+        })
+    }
+
+    /// Returns the blocks that need a phi for a variable defined in `def_blocks`.
+    ///
+    /// This is the iterated dominance frontier of the definitions. `live_in`
+    /// prunes the placement: a frontier block where the variable is not live
+    /// gets no phi and does not propagate further.
+    #[must_use]
+    pub(crate) fn phi_blocks(
+        &self,
+        def_blocks: impl IntoIterator<Item = BlockId>,
+        mut live_in: impl FnMut(BlockId) -> bool,
+    ) -> DenseBitSet<BlockId> {
+        let frontiers = self.dominance_frontiers();
+        let mut phi_blocks = DenseBitSet::new_empty(self.successors.len());
+        let mut worklist: Vec<BlockId> = def_blocks.into_iter().collect();
+        while let Some(block) = worklist.pop() {
+            for &frontier_block in &frontiers[block] {
+                if live_in(frontier_block) && phi_blocks.insert(frontier_block) {
+                    worklist.push(frontier_block);
+                }
+            }
+        }
+        phi_blocks
     }
 
     /// Returns block-to-block reachability through at least one CFG edge.
@@ -137,27 +218,27 @@ pub(crate) struct DominatorTree {
 }
 
 impl DominatorTree {
-    fn compute(successors: &IndexVec<BlockId, SmallVec<[BlockId; 2]>>, rpo: &[BlockId]) -> Self {
-        let block_count = successors.len();
-        let mut predecessors = index_vec![Vec::new(); block_count];
-        for (block, block_successors) in successors.iter_enumerated() {
-            for &successor in block_successors {
-                predecessors[successor].push(block);
-            }
-        }
+    /// Computes the tree rooted at `root` from each block's predecessors and
+    /// the reverse postorder of the blocks reachable from `root`.
+    fn compute(
+        predecessors: &IndexVec<BlockId, Vec<BlockId>>,
+        root: BlockId,
+        rpo: &[BlockId],
+    ) -> Self {
+        let block_count = predecessors.len();
         let mut rpo_numbers = index_vec![usize::MAX; block_count];
         for (number, &block) in rpo.iter().enumerate() {
             rpo_numbers[block] = number;
         }
 
         let mut idoms = index_vec![None; block_count];
-        idoms[BlockId::ENTRY] = Some(BlockId::ENTRY);
+        idoms[root] = Some(root);
         let mut changed = true;
         while changed {
             changed = false;
             for &block in rpo {
                 let block_predecessors = &predecessors[block];
-                if block_predecessors.is_empty() {
+                if block == root || block_predecessors.is_empty() {
                     continue;
                 }
                 let mut new_idom: Option<BlockId> = None;
@@ -251,3 +332,156 @@ impl DominatorTree {
         out
     }
 }
+
+/// Immediate-post-dominator tree for one MIR function.
+///
+/// Every block without successors exits to one virtual exit, which roots the
+/// tree. Blocks that cannot reach an exit, such as the body of an infinite
+/// loop, are post-dominated by nothing but themselves.
+#[derive(Clone, Debug)]
+pub(crate) struct PostDominatorTree {
+    tree: DominatorTree,
+    exit: BlockId,
+}
+
+impl PostDominatorTree {
+    fn compute(successors: &IndexVec<BlockId, SmallVec<[BlockId; 2]>>) -> Self {
+        let exit = BlockId::from_usize(successors.len());
+        // Walk the reversed CFG from the virtual exit: the forward successors
+        // become the predecessors, and every exit block leads to the virtual exit.
+        let mut reversed: IndexVec<BlockId, SmallVec<[BlockId; 2]>> =
+            index_vec![SmallVec::new(); successors.len() + 1];
+        let mut predecessors: IndexVec<BlockId, Vec<BlockId>> =
+            index_vec![Vec::new(); successors.len() + 1];
+        for (block, block_successors) in successors.iter_enumerated() {
+            if block_successors.is_empty() {
+                reversed[exit].push(block);
+                predecessors[block].push(exit);
+            }
+            for &successor in block_successors {
+                reversed[successor].push(block);
+                predecessors[block].push(successor);
+            }
+        }
+        let (rpo, _) = reverse_postorder(&reversed, exit);
+        Self { tree: DominatorTree::compute(&predecessors, exit, &rpo), exit }
+    }
+
+    /// Returns the immediate post-dominator of `block`, or `None` if `block`
+    /// exits directly or cannot reach an exit.
+    #[must_use]
+    pub(crate) fn ipdom(&self, block: BlockId) -> Option<BlockId> {
+        self.tree.idom(block).filter(|&ipdom| ipdom != self.exit && ipdom != block)
+    }
+}
+
+fn predecessors_of(
+    successors: &IndexVec<BlockId, SmallVec<[BlockId; 2]>>,
+) -> IndexVec<BlockId, Vec<BlockId>> {
+    let mut predecessors = index_vec![Vec::new(); successors.len()];
+    for (block, block_successors) in successors.iter_enumerated() {
+        for &successor in block_successors {
+            predecessors[successor].push(block);
+        }
+    }
+    predecessors
+}
+
+/// Returns the blocks reachable from `root` in reverse postorder, and the set
+/// of those blocks.
+fn reverse_postorder(
+    successors: &IndexVec<BlockId, SmallVec<[BlockId; 2]>>,
+    root: BlockId,
+) -> (Vec<BlockId>, DenseBitSet<BlockId>) {
+    let mut reachable = DenseBitSet::new_empty(successors.len());
+    let mut rpo = Vec::with_capacity(successors.len());
+    let mut stack = vec![(root, 0usize)];
+    reachable.insert(root);
+    while let Some((block, next)) = stack.last_mut() {
+        if let Some(&succ) = successors[*block].get(*next) {
+            *next += 1;
+            if reachable.insert(succ) {
+                stack.push((succ, 0));
+            }
+        } else {
+            rpo.push(*block);
+            stack.pop();
+        }
+    }
+    rpo.reverse();
+    (rpo, reachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::{Immediate, Terminator, Value};
+    use solar_interface::Ident;
+
+    /// Builds `entry -> {then, else} -> join -> exit` and returns the blocks.
+    fn diamond() -> (Function, [BlockId; 4]) {
+        let mut func = Function::new(Ident::DUMMY);
+        let entry = BlockId::ENTRY;
+        let then_block = func.alloc_block();
+        let else_block = func.alloc_block();
+        let join = func.alloc_block();
+
+        let cond = func.alloc_value(Value::Immediate(Immediate::bool(true)));
+        func.blocks[entry].terminator =
+            Some(Terminator::Branch { condition: cond, then_block, else_block });
+        func.blocks[then_block].predecessors.push(entry);
+        func.blocks[else_block].predecessors.push(entry);
+
+        func.blocks[then_block].terminator = Some(Terminator::Jump(join));
+        func.blocks[else_block].terminator = Some(Terminator::Jump(join));
+        func.blocks[join].predecessors.extend([then_block, else_block]);
+        func.blocks[join].terminator = Some(Terminator::Stop);
+
+        (func, [entry, then_block, else_block, join])
+    }
+
+    #[test]
+    fn diamond_frontiers_and_phis() {
+        let (func, [entry, then_block, else_block, join]) = diamond();
+        let cfg = CfgInfo::new(&func);
+
+        let frontiers = cfg.dominance_frontiers();
+        assert!(frontiers[entry].is_empty());
+        assert_eq!(frontiers[then_block], [join]);
+        assert_eq!(frontiers[else_block], [join]);
+        assert!(frontiers[join].is_empty());
+
+        let phis = cfg.phi_blocks([then_block], |_| true);
+        assert!(phis.contains(join));
+        assert_eq!(phis.count(), 1);
+        assert!(cfg.phi_blocks([then_block], |block| block != join).is_empty());
+        assert!(cfg.phi_blocks([entry], |_| true).is_empty());
+    }
+
+    #[test]
+    fn diamond_post_dominators() {
+        let (func, [entry, then_block, else_block, join]) = diamond();
+        let cfg = CfgInfo::new(&func);
+
+        let post_dominators = cfg.post_dominators();
+        assert_eq!(post_dominators.ipdom(entry), Some(join));
+        assert_eq!(post_dominators.ipdom(then_block), Some(join));
+        assert_eq!(post_dominators.ipdom(else_block), Some(join));
+        assert_eq!(post_dominators.ipdom(join), None);
+    }
+
+    #[test]
+    fn infinite_loop_has_no_post_dominator() {
+        let mut func = Function::new(Ident::DUMMY);
+        let body = func.alloc_block();
+        func.blocks[BlockId::ENTRY].terminator = Some(Terminator::Jump(body));
+        func.blocks[body].predecessors.extend([BlockId::ENTRY, body]);
+        func.blocks[body].terminator = Some(Terminator::Jump(body));
+        let cfg = CfgInfo::new(&func);
+
+        let post_dominators = cfg.post_dominators();
+        assert_eq!(post_dominators.ipdom(BlockId::ENTRY), None);
+        assert_eq!(post_dominators.ipdom(body), None);
+        assert_eq!(cfg.dominance_frontiers()[body], [body]);
+    }
+}
@@ -7,7 +7,10 @@
 //! - EVM IR optimization, relocation, and byte encoding
 
 use super::{
-    assembler::{Assembler, DeferredAlloc, DeferredConst, ImmutableRef, Label, PreparedAssembly},
+    assembler::{
+        AssembledCode, Assembler, DeferredAlloc, DeferredConst, ImmutableRef, Label,
+        PreparedAssembly,
+    },
     ir,
     layout::{RelayoutAddress, preserves_push_width},
     op,
@@ -25,6 +28,7 @@ use crate::{
     pass::run_default_pipeline,
 };
 use alloy_primitives::U256;
+use rayon::prelude::*;
 use solar_config::OptimizationMode;
 use solar_data_structures::{
     bit_set::{DenseBitSet, GrowableBitSet},
@@ -52,6 +56,8 @@ struct PreparedDeploymentPrefix {
     assembly: PreparedAssembly,
     constructor_arg_offset: Option<DeferredConst>,
     runtime_offset: DeferredConst,
+    /// Data segments appended after the runtime code.
+    data_segments: Vec<(Box<[u8]>, DeferredConst)>,
 }
 
 /// Describes the stack effect of an EVM instruction.
@@ -346,6 +352,43 @@ impl GlobalStackPlan {
     }
 }
 
+/// The analyses a function body is emitted from.
+///
+/// They only read their own function, so the bodies of a pass can be planned in parallel before
+/// being emitted, in function order, into the shared assembler.
+struct FunctionPlan {
+    liveness: Liveness,
+    /// Copies to insert at block exits (from phi elimination).
+    block_copies: FxHashMap<BlockId, Vec<ParallelCopy>>,
+    stack_phi_plan: StackPhiPlan,
+    global_stack_plan: GlobalStackPlan,
+}
+
+impl FunctionPlan {
+    fn analyze(func: &Function, dispatch_entry: bool) -> Self {
+        let liveness = dispatch_entry
+            .then(|| Liveness::compute_block_local_for_codegen(func))
+            .flatten()
+            .unwrap_or_else(|| Liveness::compute(func));
+
+        // Eliminate phis.
+        let phi_result = PhiEliminator::analyze(func);
+        let has_phis = !phi_result.phis_to_remove.is_empty();
+        let block_copies = phi_result
+            .block_copies
+            .into_iter()
+            .map(|(block_id, copies)| (block_id, copies.copies))
+            .collect();
+        // Stack-phi planning starts with loop analysis, but cannot produce a
+        // plan without a phi. Avoid that analysis for the overwhelmingly
+        // common phi-free function.
+        let stack_phi_plan =
+            if has_phis { StackPhiPlan::analyze(func) } else { StackPhiPlan::default() };
+        let global_stack_plan = GlobalStackPlan::analyze(func, &liveness, &stack_phi_plan);
+        Self { liveness, block_copies, stack_phi_plan, global_stack_plan }
+    }
+}
+
 impl StackPhiPlan {
     fn analyze(func: &Function) -> Self {
         StackPhiPlanner::new(func).plan()
@@ -575,6 +618,8 @@ pub struct EvmCodegen<'gcx> {
     runtime_free_memory_const: Option<DeferredConst>,
     /// Every external body emitted this pass, for sizing the heap floor.
     runtime_entry_funcs: Vec<FunctionId>,
+    /// Plans of the bodies left to emit this pass, taken by `generate_function_body`.
+    function_plans: FxHashMap<FunctionId, FunctionPlan>,
     /// The internal-convention function currently being emitted.
     current_internal_function: Option<FunctionId>,
     /// Copies to insert at block exits (from phi elimination).
@@ -588,6 +633,9 @@ pub struct EvmCodegen<'gcx> {
     global_stack_aliases: FxHashMap<ValueId, ValueId>,
     /// Immutable `PUSH32` placeholders in the last assembled runtime code.
     runtime_immutable_refs: Vec<ImmutableRef>,
+    /// Data segments referenced by `dataoffset` in the code being emitted, in first-use order.
+    /// Each offset is resolved once the code length is known.
+    data_segments: Vec<(Box<[u8]>, DeferredConst)>,
     /// Whether we're currently generating constructor code.
     /// When true, LoadArg uses CODECOPY from the end of code instead of CALLDATALOAD.
     in_constructor: bool,
@@ -638,12 +686,14 @@ impl<'gcx> EvmCodegen<'gcx> {
             pending_static_allocs: FxHashMap::default(),
             runtime_free_memory_const: None,
             runtime_entry_funcs: Vec::new(),
+            function_plans: FxHashMap::default(),
             current_internal_function: None,
             block_copies: FxHashMap::default(),
             stack_phi_sources: FxHashMap::default(),
             global_stack_active: false,
             global_stack_aliases: FxHashMap::default(),
             runtime_immutable_refs: Vec::new(),
+            data_segments: Vec::new(),
             in_constructor: false,
             constructor_exit: None,
             constructor_param_count: 0,
@@ -825,10 +875,12 @@ impl<'gcx> EvmCodegen<'gcx> {
         // are appended after the generated deployment prefix, so their offset
         // and the runtime-code offset depend on its final push widths. Only
         // repeat final assembly while both offsets stabilize.
+        // Data segments referenced by the deployment prefix follow the runtime code.
         let prepared_deploy_code =
             self.prepare_deployment_prefix(module, runtime_len, copy_base, &immutable_refs);
+        let data_len = Self::data_segments_len(&prepared_deploy_code.data_segments);
         let mut deploy_code_len = 0usize;
-        let mut constructor_arg_offset = runtime_len;
+        let mut constructor_arg_offset = runtime_len + data_len;
         let mut deploy_code = self.assemble_deployment_prefix(
             &prepared_deploy_code,
             constructor_arg_offset,
@@ -836,7 +888,7 @@ impl<'gcx> EvmCodegen<'gcx> {
         );
         for _ in 0..8 {
             let next_deploy_code_len = deploy_code.bytecode.len();
-            let next_arg_offset = next_deploy_code_len + runtime_len;
+            let next_arg_offset = next_deploy_code_len + runtime_len + data_len;
             if next_deploy_code_len == deploy_code_len && next_arg_offset == constructor_arg_offset
             {
                 break;
@@ -860,12 +912,15 @@ impl<'gcx> EvmCodegen<'gcx> {
         // [immutable patches]   ; patch staged words into the PUSH32 placeholders
         // PUSH<n> copy_base     ; memory offset
         // RETURN                ; return the runtime code
+        // [runtime_code]        ; including the runtime's own data segments
+        // [data_segments]       ; data referenced by the deployment prefix
         if let Some(evm_ir) = &mut deploy_code.evm_ir {
             evm_ir.set_name("deployment");
         }
 
         let mut deploy_bytecode = deploy_code.bytecode;
         deploy_bytecode.extend_from_slice(&runtime_code.bytecode);
+        Self::append_data_segments(&mut deploy_bytecode, &prepared_deploy_code.data_segments);
 
         let mut deployment_evm_ir = Vec::new();
         if let Some(evm_ir) = deploy_code.evm_ir {
@@ -927,6 +982,7 @@ impl<'gcx> EvmCodegen<'gcx> {
         immutable_refs: &[ImmutableRef],
     ) -> PreparedDeploymentPrefix {
         self.asm.clear();
+        self.data_segments.clear();
         let runtime_offset = self.asm.new_deferred_const();
 
         // Find constructor function if it exists
@@ -1052,6 +1108,7 @@ impl<'gcx> EvmCodegen<'gcx> {
             assembly: self.asm.prepare(self.capture_evm_ir),
             constructor_arg_offset,
             runtime_offset,
+            data_segments: std::mem::take(&mut self.data_segments),
         }
     }
 
@@ -1061,11 +1118,14 @@ impl<'gcx> EvmCodegen<'gcx> {
         constructor_arg_offset: usize,
         runtime_offset: usize,
     ) -> GeneratedCode {
-        let mut deferred_values = Vec::with_capacity(2);
+        let mut deferred_values = Vec::with_capacity(2 + prepared.data_segments.len());
         if let Some(id) = prepared.constructor_arg_offset {
             deferred_values.push((id, U256::from(constructor_arg_offset)));
         }
         deferred_values.push((prepared.runtime_offset, U256::from(runtime_offset)));
+        // The deployment data segments end where the constructor arguments begin.
+        let data_base = constructor_arg_offset - Self::data_segments_len(&prepared.data_segments);
+        deferred_values.extend(Self::data_segment_offsets(&prepared.data_segments, data_base));
         let result = self.asm.assemble_prepared(&prepared.assembly, &deferred_values);
         GeneratedCode { bytecode: result.bytecode, evm_ir: result.evm_ir }
     }
@@ -1085,6 +1145,7 @@ impl<'gcx> EvmCodegen<'gcx> {
     /// Generates runtime bytecode for a module.
     fn generate_runtime_code(&mut self, module: &Module) -> GeneratedCode {
         self.asm.clear();
+        self.data_segments.clear();
         self.block_labels.clear();
         self.function_labels.clear();
         self.cold_functions = if matches!(self.gcx.sess.opts.optimization, OptimizationMode::None) {
@@ -1102,6 +1163,7 @@ impl<'gcx> EvmCodegen<'gcx> {
         self.pending_static_allocs.clear();
         self.runtime_free_memory_const = None;
         self.runtime_entry_funcs.clear();
+        self.function_plans.clear();
         self.current_internal_function = None;
         self.block_copies.clear();
         self.stack_phi_sources.clear();
@@ -1118,11 +1180,79 @@ impl<'gcx> EvmCodegen<'gcx> {
             }
         }
 
-        let result = self.asm.assemble_with_evm_ir(self.capture_evm_ir);
+        let result = self.assemble_with_data_segments();
         self.runtime_immutable_refs = result.immutable_refs;
         GeneratedCode { bytecode: result.bytecode, evm_ir: result.evm_ir }
     }
 
+    /// Assembles the emitted code followed by its data segments.
+    ///
+    /// The segment offsets are pushed with their minimal width, so the code length depends on
+    /// them. Offsets only grow with the code length, so repeating assembly from the shortest
+    /// layout reaches the least fixed point.
+    fn assemble_with_data_segments(&mut self) -> AssembledCode {
+        let data_segments = std::mem::take(&mut self.data_segments);
+        if data_segments.is_empty() {
+            return self.asm.assemble_with_evm_ir(self.capture_evm_ir);
+        }
+
+        let prepared = self.asm.prepare(self.capture_evm_ir);
+        let mut code_len = 0usize;
+        let mut result = self
+            .asm
+            .assemble_prepared(&prepared, &Self::data_segment_offsets(&data_segments, code_len));
+        for _ in 0..8 {
+            if result.bytecode.len() == code_len {
+                break;
+            }
+            code_len = result.bytecode.len();
+            result = self.asm.assemble_prepared(
+                &prepared,
+                &Self::data_segment_offsets(&data_segments, code_len),
+            );
+        }
+        self.asm.clear();
+        Self::append_data_segments(&mut result.bytecode, &data_segments);
+        result
+    }
+
+    /// Returns the deferred constant holding the code offset of a data segment, reusing the
+    /// segment if identical data was already referenced.
+    fn data_segment_offset(&mut self, data: &[u8]) -> DeferredConst {
+        if let Some((_, id)) = self.data_segments.iter().find(|(segment, _)| **segment == *data) {
+            return *id;
+        }
+        let id = self.asm.new_deferred_const();
+        self.data_segments.push((data.into(), id));
+        id
+    }
+
+    /// Resolves the offsets of data segments laid out back to back from `base`.
+    fn data_segment_offsets(
+        data_segments: &[(Box<[u8]>, DeferredConst)],
+        base: usize,
+    ) -> Vec<(DeferredConst, U256)> {
+        let mut offset = base;
+        data_segments
+            .iter()
+            .map(|(data, id)| {
+                let value = (*id, U256::from(offset));
+                offset += data.len();
+                value
+            })
+            .collect()
+    }
+
+    fn data_segments_len(data_segments: &[(Box<[u8]>, DeferredConst)]) -> usize {
+        data_segments.iter().map(|(data, _)| data.len()).sum()
+    }
+
+    fn append_data_segments(code: &mut Vec<u8>, data_segments: &[(Box<[u8]>, DeferredConst)]) {
+        for (data, _) in data_segments {
+            code.extend_from_slice(data);
+        }
+    }
+
     /// Generates the runtime from a `dispatch`-phase module: the MIR `entry`
     /// function is the runtime prologue, its `tail_call`s jump to the ABI
     /// wrappers, and no backend dispatcher is synthesized.
@@ -1176,6 +1306,17 @@ impl<'gcx> EvmCodegen<'gcx> {
                 self.function_labels.insert(func_id, label);
             }
         }
+        self.plan_functions(
+            module,
+            module
+                .functions
+                .indices()
+                .filter(|func_id| {
+                    *func_id == entry_id || self.function_labels.contains_key(func_id)
+                })
+                .map(|func_id| (func_id, func_id == entry_id))
+                .collect(),
+        );
 
         // The MIR entry is the runtime prologue: one shared free-memory
         // store covers every wrapper reached through it.
@@ -1219,11 +1360,29 @@ impl<'gcx> EvmCodegen<'gcx> {
             self.current_internal_function = None;
             self.record_function_spill_size(func_id);
         }
+        debug_assert!(self.function_plans.is_empty(), "planned bodies were not emitted");
+        self.function_plans.clear();
 
         self.resolve_pending_frame_size_consts(module);
         self.resolve_static_frames(module);
     }
 
+    /// Plans the bodies of `funcs`, given with whether each is the dispatch entry.
+    ///
+    /// The plans are computed in parallel unless the session is sequential. Emission still
+    /// happens in function order, so the bytecode does not depend on the thread count.
+    fn plan_functions(&mut self, module: &Module, funcs: Vec<(FunctionId, bool)>) {
+        let functions = &module.functions;
+        let plan = |&(func_id, dispatch_entry): &(FunctionId, bool)| {
+            (func_id, FunctionPlan::analyze(&functions[func_id], dispatch_entry))
+        };
+        self.function_plans = if self.gcx.sess.is_sequential() {
+            funcs.iter().map(plan).collect()
+        } else {
+            funcs.par_iter().map(plan).collect()
+        };
+    }
+
     /// Generates the function dispatcher.
     ///
     /// The dispatcher logic is:
@@ -1536,27 +1695,17 @@ impl<'gcx> EvmCodegen<'gcx> {
     }
 
     /// Generates the body of a function.
+    ///
+    /// Uses the plan computed by `plan_functions` if there is one, and plans the body otherwise.
     fn generate_function_body(&mut self, func_id: FunctionId, func: &Function) {
-        let liveness = self
-            .emitting_dispatch_entry
-            .then(|| Liveness::compute_block_local_for_codegen(func))
-            .flatten()
-            .unwrap_or_else(|| Liveness::compute(func));
+        let FunctionPlan { liveness, block_copies, stack_phi_plan, global_stack_plan } =
+            match self.function_plans.remove(&func_id) {
+                Some(plan) => plan,
+                None => FunctionPlan::analyze(func, self.emitting_dispatch_entry),
+            };
         let liveness = &liveness;
-
-        // Eliminate phis.
-        let phi_result = PhiEliminator::analyze(func);
-        let has_phis = !phi_result.phis_to_remove.is_empty();
-        for (block_id, copies) in phi_result.block_copies {
-            self.block_copies.insert(block_id, copies.copies);
-        }
-        // Stack-phi planning starts with loop analysis, but cannot produce a
-        // plan without a phi. Avoid that analysis for the overwhelmingly
-        // common phi-free function.
-        let stack_phi_plan =
-            if has_phis { StackPhiPlan::analyze(func) } else { StackPhiPlan::default() };
+        self.block_copies.extend(block_copies);
         self.stack_phi_sources = stack_phi_plan.edge_sources.clone();
-        let global_stack_plan = GlobalStackPlan::analyze(func, liveness, &stack_phi_plan);
         self.global_stack_active = !global_stack_plan.is_empty();
         self.global_stack_aliases = global_stack_plan.aliases.clone();
 
@@ -3085,6 +3234,11 @@ impl<'gcx> EvmCodegen<'gcx> {
                 self.asm.emit_op(op::CODESIZE);
                 self.scheduler.instruction_executed(0, result_value);
             }
+            InstKind::DataOffset(data) => {
+                let id = self.data_segment_offset(data);
+                self.asm.emit_push_deferred(id);
+                self.scheduler.instruction_executed(0, result_value);
+            }
             InstKind::LoadImmutable(offset) => {
                 if self.in_constructor {
                     // The running constructor's own placeholders are never
@@ -4640,6 +4794,11 @@ impl<'gcx> EvmCodegen<'gcx> {
                             }
                             self.scheduler.stack.push(val);
                         }
+                        crate::mir::InstKind::DataOffset(data) => {
+                            let id = self.data_segment_offset(data);
+                            self.asm.emit_push_deferred(id);
+                            self.scheduler.stack.push(val);
+                        }
                         crate::mir::InstKind::CallValue => {
                             self.asm.emit_op(op::CALLVALUE);
                             self.scheduler.stack.push(val);
@@ -5490,15 +5649,191 @@ impl crate::backend::Backend for EvmCodegen<'_> {
 mod tests {
     use super::*;
     use crate::mir::FunctionBuilder;
+    use alloy_primitives::hex;
     use solar_config::CompileOpts;
-    use solar_interface::{Ident, Session, sym};
+    use solar_interface::{ColorChoice, Ident, Session, sym};
     use solar_sema::{Compiler, hir::Visibility};
+    use std::{ops::ControlFlow, path::PathBuf};
+
+    /// Contract with enough functions for the function passes to be split across threads.
+    const PARALLEL_SRC: &str = r#"
+contract C {
+    uint256[] values;
+    mapping(address => uint256) balances;
+
+    function sum(uint256 a, uint256 b) external pure returns (uint256) {
+        return (a + b) * (a + b) + (a + b);
+    }
+
+    function pick(uint256 a, bool flag) external pure returns (uint256 r) {
+        if (flag) {
+            r = a * 2;
+        } else {
+            r = a * 3;
+        }
+        if (flag) {
+            r += 1;
+        }
+    }
+
+    function loop(uint256 n) external pure returns (uint256 acc) {
+        for (uint256 i = 0; i < n; i++) {
+            if (i % 3 == 0) continue;
+            acc += i * i;
+        }
+    }
+
+    function push(uint256 x) external {
+        values.push(x);
+        values.push(x + 1);
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        require(balances[msg.sender] >= amount, "balance");
+        balances[msg.sender] -= amount;
+        balances[to] += amount;
+        return true;
+    }
+
+    function hash(bytes calldata data) external pure returns (bytes32, bytes32) {
+        return (keccak256(data), keccak256(abi.encodePacked(data, data.length)));
+    }
+}
+"#;
 
     fn with_codegen<T: Send>(opts: CompileOpts, f: impl FnOnce(EvmCodegen<'_>) -> T + Send) -> T {
         let compiler = Compiler::new(Session::builder().opts(opts).build());
         compiler.enter(|c| f(EvmCodegen::new(c.gcx())))
     }
 
+    /// Parses and analyzes `src` with `threads` threads, then calls `f` with the global context.
+    fn with_analyzed_source<T: Send>(
+        src: &str,
+        threads: usize,
+        f: impl FnOnce(Gcx<'_>) -> T + Send,
+    ) -> T {
+        let sess =
+            Session::builder().with_buffer_emitter(ColorChoice::Never).threads(threads).build();
+        let mut compiler = Compiler::new(sess);
+        compiler.enter_mut(|c| {
+            let mut pcx = c.parse();
+            let file =
+                c.sess().source_map().new_source_file(PathBuf::from("test.sol"), src).unwrap();
+            pcx.add_file(file);
+            pcx.parse();
+
+            assert_eq!(c.lower_asts(), Ok(ControlFlow::Continue(())));
+            assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
+        });
+        assert!(compiler.sess().dcx.has_errors().is_ok());
+
+        compiler.enter(|c| f(c.gcx()))
+    }
+
+    /// Compiles `src` with `threads` threads, returning the optimized MIR and the deployment and
+    /// runtime bytecode of each contract.
+    fn compile_with_threads(src: &str, threads: usize) -> Vec<(String, Vec<u8>, Vec<u8>)> {
+        with_analyzed_source(src, threads, |gcx| {
+            gcx.hir
+                .contract_ids()
+                .map(|id| {
+                    let mut module = crate::lower::lower_contract(gcx, id);
+                    let (deployment, runtime) =
+                        EvmCodegen::new(gcx).generate_deployment_bytecode(&mut module);
+                    (module.to_string(), deployment, runtime)
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn output_is_independent_of_thread_count() {
+        let sequential = compile_with_threads(PARALLEL_SRC, 1);
+        assert!(sequential.iter().all(|(_, _, runtime)| !runtime.is_empty()));
+        assert_eq!(compile_with_threads(PARALLEL_SRC, 4), sequential);
+    }
+
+    #[test]
+    fn output_is_reproducible() {
+        // Common subexpressions across stores and branches on known conditions, for CSE and jump
+        // threading.
+        const SRC: &str = r#"
+contract C {
+    uint256 x;
+    uint256 y;
+
+    function cse(uint256 a, uint256 b) external returns (uint256) {
+        x = a * b + a;
+        y = a * b + b;
+        uint256 c = (a * b) ^ (a + b);
+        x = c + a * b;
+        return x + y + (a + b);
+    }
+
+    function thread(uint256 a, bool p) external pure returns (uint256 r) {
+        bool q = a > 10;
+        if (q) r = 1;
+        if (p) r += 2;
+        if (q) r += 3;
+        if (p && q) r *= 4;
+    }
+}
+"#;
+        let expected = compile_with_threads(SRC, 1);
+        for threads in [1, 2, 8, 1, 2, 8] {
+            assert_eq!(compile_with_threads(SRC, threads), expected, "threads: {threads}");
+        }
+        assert_eq!(compile_with_threads(PARALLEL_SRC, 8), compile_with_threads(PARALLEL_SRC, 2));
+    }
+
+    #[test]
+    fn creation_code_is_copied_from_data_segment() {
+        const SRC: &str = r#"
+contract Child {
+    uint256 public value = 1;
+}
+
+contract Factory {
+    function make() external returns (address) {
+        return address(new Child());
+    }
+
+    function makeTwo() external returns (address, address) {
+        return (address(new Child()), address(new Child()));
+    }
+}
+"#;
+        with_analyzed_source(SRC, 1, |gcx| {
+            let ids = gcx.hir.contract_ids().collect::<Vec<_>>();
+            let [child, factory] = ids[..] else { unreachable!() };
+            let mut child_module = crate::lower::lower_contract(gcx, child);
+            let (child_code, _) =
+                EvmCodegen::new(gcx).generate_deployment_bytecode(&mut child_module);
+            let bytecodes = FxHashMap::from_iter([(child, child_code.clone())]);
+            let mut module = crate::lower::lower_contract_with_bytecodes(gcx, factory, &bytecodes);
+            let (deployment, runtime) =
+                EvmCodegen::new(gcx).generate_deployment_bytecode(&mut module);
+
+            let mir = module.to_string();
+            let segment = format!("dataoffset hex\"{}\"", hex::encode(&child_code));
+            assert!(mir.contains(&segment), "{mir}");
+
+            // Every `new Child()` shares one copy, appended after the runtime code.
+            assert!(runtime.ends_with(&child_code));
+            assert!(
+                !runtime[..runtime.len() - child_code.len()]
+                    .windows(child_code.len())
+                    .any(|window| window == child_code)
+            );
+            let offset = U256::from(runtime.len() - child_code.len()).to_be_bytes_trimmed_vec();
+            let push = [&[op::push(offset.len() as u8)][..], &offset].concat();
+            assert!(runtime.windows(push.len()).any(|window| window == push));
+
+            // The deployment code returns the runtime code, data segment included.
+            assert!(deployment.ends_with(&runtime));
+        });
+    }
+
     #[test]
     fn empty_external_return_falls_off_end() {
         with_codegen(CompileOpts::default(), |mut codegen| {
@@ -446,16 +446,52 @@ impl<'gcx> Lowerer<'gcx> {
             lower_abi_encode::AbiScratch { base: scratch_base, depth: 0 },
         );
 
-        let thirty_one = builder.imm_u64(31);
-        let rounded = builder.add(size, thirty_one);
-        let mask = builder.not(thirty_one);
-        let aligned = builder.and(rounded, mask);
-        let new_free_ptr = builder.add(data, aligned);
-        builder.set_fmp(new_free_ptr);
+        self.commit_fmp_allocation(builder, data, size);
 
         (data, size)
     }
 
+    /// Builds contract creation initcode in a fresh allocation from the free
+    /// memory pointer: the creation `bytecode`, copied with `codecopy` from a
+    /// data segment appended to the running code, followed by the ABI-encoded
+    /// constructor arguments. Returns `(offset, size)`, or `None` when an
+    /// argument's type cannot be determined.
+    pub(super) fn abi_encode_initcode(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        bytecode: &[u8],
+        arg_exprs: &[&solar_sema::hir::Expr<'_>],
+    ) -> Option<(ValueId, ValueId)> {
+        let LoweredAbiItems { items, calldata_slices } =
+            self.lower_abi_encode_items(builder, arg_exprs)?;
+        let scratch_words = self.abi_scratch_words(&items);
+        let scratch_base =
+            (scratch_words > 0).then(|| self.allocate_memory(builder, scratch_words * 32));
+
+        let data = builder.fmp();
+        let code_offset = builder.data_offset(bytecode.into());
+        let code_len = builder.imm_u64(bytecode.len() as u64);
+        builder.codecopy(data, code_offset, code_len);
+
+        let size = if items.is_empty() {
+            code_len
+        } else {
+            let args_base = self.offset_ptr(builder, data, bytecode.len() as u64);
+            let args_size = self.abi_encode_tuple(
+                builder,
+                &items,
+                args_base,
+                &calldata_slices,
+                lower_abi_encode::AbiScratch { base: scratch_base, depth: 0 },
+            );
+            builder.add(code_len, args_size)
+        };
+
+        self.commit_fmp_allocation(builder, data, size);
+
+        Some((data, size))
+    }
+
     /// ABI-encodes call arguments (optionally prefixed by a left-aligned
     /// 4-byte selector word) into a fresh allocation from the free memory
     /// pointer. Returns `(offset, size)` of the encoded payload, or `None`
@@ -795,6 +831,10 @@ impl<'gcx> Lowerer<'gcx> {
                 .map(|(i, e)| (self.lower_return_value_for_ty(builder, e, tys[i]), tys[i]))
                 .collect();
         }
+        if tys.len() > 1 && self.is_low_level_call_expr(expr) {
+            let values = self.lower_low_level_call_values(builder, expr);
+            return values.into_iter().zip(tys).collect();
+        }
         if let Some(arity) = self.get_ternary_tuple_arity(expr) {
             let first = self.lower_expr(builder, expr);
             let mut items = Vec::with_capacity(arity);
@@ -427,10 +427,21 @@ impl<'gcx> Lowerer<'gcx> {
         (consumed >= 2 && len != 0 && has_value).then_some((consumed, len))
     }
 
+    /// Returns the arguments of an `abi.encodePacked(...)`, `string.concat(...)`
+    /// or `bytes.concat(...)` call expression. All three produce the packed
+    /// encoding of their arguments.
     pub(super) fn abi_encode_packed_call_args<'a>(
         &self,
         expr: &'a hir::Expr<'a>,
     ) -> Option<&'a CallArgs<'a>> {
+        if let ExprKind::Call(callee, args, _) = &expr.kind
+            && matches!(
+                self.gcx.builtin_callee(callee.id),
+                Some(Builtin::StringConcat | Builtin::BytesConcat)
+            )
+        {
+            return Some(args);
+        }
         self.abi_member_call_args(expr, sym::encodePacked)
     }
 
@@ -2,7 +2,7 @@
 
 use super::{Lowerer, checked_arith::PanicCode};
 use crate::mir::{FunctionBuilder, MemoryObjectKind, SliceLocation, ValueId};
-use alloy_primitives::{U256, keccak256};
+use alloy_primitives::U256;
 use solar_ast::LitKind;
 use solar_interface::{Symbol, kw, sym};
 use solar_sema::{
@@ -959,7 +959,7 @@ impl<'gcx> Lowerer<'gcx> {
                         && let ExprKind::Lit(lit) = &sig_expr.kind
                         && let LitKind::Str(_, sig, _) = &lit.kind
                     {
-                        let hash = keccak256(sig.as_byte_str());
+                        let hash = self.gcx.keccak256(*sig);
                         let selector =
                             U256::from(u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]))
                                 << 224;
@@ -1049,7 +1049,7 @@ impl<'gcx> Lowerer<'gcx> {
         if let ExprKind::Lit(lit) = &inner.kind
             && let LitKind::Str(_, bytes, _) = &lit.kind
         {
-            let hash = keccak256(bytes.as_byte_str());
+            let hash = self.gcx.keccak256(*bytes);
             return Some(builder.imm_u256(U256::from_be_bytes(hash.0)));
         }
 
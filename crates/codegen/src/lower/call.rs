@@ -3,9 +3,9 @@
 use super::{Lowerer, checked_arith::PanicCode};
 use crate::{
     memory::EvmMemoryLayout,
-    mir::{FunctionBuilder, ValueId},
+    mir::{FunctionBuilder, FunctionId, ValueId},
 };
-use alloy_primitives::{U256, keccak256};
+use alloy_primitives::U256;
 use solar_ast::{LitKind, Span};
 use solar_data_structures::bit_set::GrowableBitSet;
 use solar_interface::{Ident, Symbol, kw, sym};
@@ -58,6 +58,10 @@ impl<'gcx> Lowerer<'gcx> {
             return builder.imm_u64(0);
         }
 
+        if let Some(fn_ty) = self.indirect_internal_callee(callee) {
+            return self.lower_indirect_internal_call(builder, callee, fn_ty, args);
+        }
+
         if let ExprKind::Member(base, member) = &callee.kind {
             return self
                 .lower_member_call_with_opts(builder, callee, base, *member, args, call_opts);
@@ -80,11 +84,17 @@ impl<'gcx> Lowerer<'gcx> {
                 hir::ItemId::Function(func_id) => {
                     return self.lower_internal_call(builder, func_id, args);
                 }
-                hir::ItemId::Contract(_) | hir::ItemId::Enum(_) => {
+                hir::ItemId::Contract(_) => {
                     if let Some(first_arg) = args.exprs().next() {
                         return self.lower_expr(builder, first_arg);
                     }
                 }
+                hir::ItemId::Enum(enum_id) => {
+                    if let Some(first_arg) = args.exprs().next() {
+                        let value = self.lower_expr(builder, first_arg);
+                        return self.lower_enum_conversion(builder, enum_id, first_arg, value);
+                    }
+                }
                 hir::ItemId::Struct(struct_id) => {
                     return self.lower_struct_constructor(builder, struct_id, args);
                 }
@@ -206,9 +216,7 @@ impl<'gcx> Lowerer<'gcx> {
     }
 
     fn custom_error_selector(&self, error_id: hir::ErrorId) -> [u8; 4] {
-        let signature = self.gcx.item_signature(hir::ItemId::Error(error_id));
-        let hash = keccak256(signature.as_bytes());
-        [hash[0], hash[1], hash[2], hash[3]]
+        self.gcx.function_selector(error_id).0
     }
 
     /// Lowers a `new T[](len)` memory array expression.
@@ -317,8 +325,6 @@ impl<'gcx> Lowerer<'gcx> {
             }
         };
 
-        let bytecode_len = bytecode.len();
-
         // Extract call options (salt, value)
         let mut salt_opt: Option<ValueId> = None;
         let mut value_opt: Option<ValueId> = None;
@@ -339,41 +345,18 @@ impl<'gcx> Lowerer<'gcx> {
             }
         }
 
-        // Allocate memory for bytecode + constructor args from free memory pointer
-        let mem_offset = builder.fmp();
-
-        // Copy bytecode to memory using MSTORE
-        // For each 32-byte chunk of bytecode, emit an MSTORE at (mem_offset + offset)
-        for (i, chunk) in bytecode.chunks(32).enumerate() {
-            let mut padded = [0u8; 32];
-            padded[..chunk.len()].copy_from_slice(chunk);
-            let value = U256::from_be_bytes(padded);
-            let val_id = builder.imm_u256(value);
-            let chunk_offset = builder.imm_u64((i as u64) * 32);
-            let dest = builder.add(mem_offset, chunk_offset);
-            builder.mstore(dest, val_id);
-        }
-
-        // Append constructor arguments after bytecode
-        let mut args_offset = bytecode_len as u64;
-        for arg in args.exprs() {
-            let arg_val = self.lower_expr(builder, arg);
-            let arg_offset_imm = builder.imm_u64(args_offset);
-            let arg_dest = builder.add(mem_offset, arg_offset_imm);
-            builder.mstore(arg_dest, arg_val);
-            args_offset += 32; // Each arg is 32 bytes ABI encoded
-        }
-
-        // Total size = bytecode + args
-        let total_size = builder.imm_u64(args_offset);
-
-        // Update free memory pointer: new_free = mem_offset + ((total_size + 31) & ~31)
-        let thirty_one = builder.imm_u64(31);
-        let aligned_size = builder.add(total_size, thirty_one);
-        let mask = builder.imm_u256(U256::from(!31u64));
-        let aligned_size = builder.and(aligned_size, mask);
-        let new_free = builder.add(mem_offset, aligned_size);
-        builder.set_fmp(new_free);
+        // Initcode is the creation bytecode followed by the ABI-encoded
+        // constructor arguments.
+        let arg_exprs: Vec<_> = args.exprs().collect();
+        let Some((mem_offset, total_size)) =
+            self.abi_encode_initcode(builder, &bytecode, &arg_exprs)
+        else {
+            return self.err_value(
+                builder,
+                ty.span,
+                "codegen could not determine the constructor argument types for `new`",
+            );
+        };
 
         // Value to send with CREATE/CREATE2 (0 for non-payable, or from value option)
         let value = value_opt.unwrap_or_else(|| builder.imm_u64(0));
@@ -405,7 +388,7 @@ impl<'gcx> Lowerer<'gcx> {
                     if let ExprKind::Lit(lit) = &first.kind
                         && let LitKind::Str(_, bytes, _) = &lit.kind
                     {
-                        let hash = keccak256(bytes.as_byte_str());
+                        let hash = self.gcx.keccak256(*bytes);
                         return builder.imm_u256(U256::from_be_bytes(hash.0));
                     }
 
@@ -517,6 +500,12 @@ impl<'gcx> Lowerer<'gcx> {
                 // Returns bytes memory (length + data)
                 self.lower_abi_encode_packed(builder, args)
             }
+            // `string.concat`/`bytes.concat` take only `bytes`/`string` and
+            // `bytesN` arguments, whose packed encoding is their raw contents
+            // back to back.
+            Builtin::StringConcat | Builtin::BytesConcat => {
+                self.lower_abi_encode_packed(builder, args)
+            }
             Builtin::AbiEncodeWithSelector => {
                 // A selector-prefixed payload adapted to a `bytes memory`
                 // value: `[length][selector + ABI tuple encoding]`.
@@ -544,7 +533,7 @@ impl<'gcx> Lowerer<'gcx> {
                     && let hir::ExprKind::Lit(lit) = &sig_expr.kind
                     && let solar_ast::LitKind::Str(_, sig, _) = &lit.kind
                 {
-                    let hash = alloy_primitives::keccak256(sig.as_byte_str());
+                    let hash = self.gcx.keccak256(*sig);
                     let selector =
                         U256::from(u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])) << 224;
                     let selector = builder.imm_u256(selector);
@@ -1006,14 +995,14 @@ impl<'gcx> Lowerer<'gcx> {
             return self.lower_struct_constructor(builder, struct_id, args);
         }
 
-        // Handle enum conversion written as `Container.Enum(x)`. An enum value is
-        // represented by its integer, so — like the `Ident` enum-callee path in
-        // `lower_call` — the conversion is the identity on the argument.
+        // Handle enum conversion written as `Container.Enum(x)`, like the `Ident`
+        // enum-callee path in `lower_call`.
         if let Some(resolved) = resolved
-            && let hir::Res::Item(hir::ItemId::Enum(_)) = resolved.res
+            && let hir::Res::Item(hir::ItemId::Enum(enum_id)) = resolved.res
             && let Some(arg) = args.exprs().next()
         {
-            return self.lower_expr(builder, arg);
+            let value = self.lower_expr(builder, arg);
+            return self.lower_enum_conversion(builder, enum_id, arg, value);
         }
 
         // Handle library function calls: Library.func(args).
@@ -1023,10 +1012,17 @@ impl<'gcx> Lowerer<'gcx> {
             return self.lower_library_call(builder, func_id, args, None);
         }
 
+        // Handle free function calls through an import namespace: Module.func(args).
+        if self.is_module_expr(base)
+            && let Some(func_id) = self.resolved_function_callee(callee)
+        {
+            return self.lower_internal_call(builder, func_id, args);
+        }
+
         // Handle address payable transfer/send builtins
         if matches!(builtin, Some(Builtin::AddressPayableTransfer | Builtin::AddressPayableSend)) {
             // payable(addr).transfer(amount) or payable(addr).send(amount)
-            // CALL(2300, addr, amount, 0, 0, 0, 0)
+            // CALL(stipend, addr, amount, 0, 0, 0, 0)
             let addr = self.lower_expr(builder, base);
             let mut exprs = args.exprs();
             let amount = if let Some(first) = exprs.next() {
@@ -1035,8 +1031,13 @@ impl<'gcx> Lowerer<'gcx> {
                 builder.imm_u64(0)
             };
 
-            // transfer/send uses 2300 gas stipend
-            let gas_stipend = builder.imm_u64(2300);
+            // transfer/send forward a fixed gas stipend (see the
+            // `transfer-send` lint). CALL already adds the stipend when value
+            // is nonzero, so pass it explicitly only for a zero amount, like
+            // solc.
+            let stipend = builder.imm_u64(Builtin::TRANSFER_GAS_STIPEND);
+            let zero_amount = builder.iszero(amount);
+            let gas_stipend = builder.mul(zero_amount, stipend);
             // Create fresh zero values for each CALL argument to avoid stack issues
             let zero_args_offset = builder.imm_u64(0);
             let zero_args_size = builder.imm_u64(0);
@@ -1055,15 +1056,16 @@ impl<'gcx> Lowerer<'gcx> {
             );
 
             if builtin == Some(Builtin::AddressPayableTransfer) {
-                // transfer reverts on failure
+                // transfer bubbles the recipient's revert data on failure.
                 let is_failure = builder.iszero(success);
                 let revert_block = builder.create_block();
                 let continue_block = builder.create_block();
                 builder.branch(is_failure, revert_block, continue_block);
                 builder.switch_to_block(revert_block);
-                let revert_offset = builder.imm_u64(0);
-                let revert_size = builder.imm_u64(0);
-                builder.revert(revert_offset, revert_size);
+                let zero = builder.imm_u64(0);
+                let size = builder.returndatasize();
+                builder.returndatacopy(zero, zero, size);
+                builder.revert(zero, size);
                 builder.switch_to_block(continue_block);
                 return builder.imm_u64(0);
             }
@@ -1103,9 +1105,8 @@ impl<'gcx> Lowerer<'gcx> {
                 builder.imm_u64(0)
             };
 
-            // This lowering models only the success flag. Solidity's second
-            // `bytes` result is rejected by `lower_multi_var_decl` until the
-            // compiler materializes returndata bytes.
+            // The returndata is never written to a fixed output buffer; the
+            // consumers of the `bytes` result copy it out after the call.
             let ret_offset = builder.imm_u64(0);
             let ret_size = builder.imm_u64(0);
 
@@ -1140,8 +1141,8 @@ impl<'gcx> Lowerer<'gcx> {
             };
 
             // Low-level calls return `(bool, bytes)`, but this expression path
-            // exposes only the first value. `lower_multi_var_decl` copies the
-            // returndata bytes out of the return buffer when they are bound.
+            // exposes only the first value. Consumers of the whole tuple go
+            // through `lower_low_level_call_values`.
             return success;
         }
 
@@ -1320,6 +1321,10 @@ impl<'gcx> Lowerer<'gcx> {
         self.gcx.hir.contract(contract_id).kind.is_library()
     }
 
+    fn is_module_expr(&self, expr: &hir::Expr<'_>) -> bool {
+        self.get_expr_type(expr).is_some_and(|ty| matches!(ty.kind, TyKind::Module(_)))
+    }
+
     fn array_builtin_method_name(builtin: Builtin) -> Option<Symbol> {
         match builtin {
             Builtin::ArrayPush0 | Builtin::ArrayPush => Some(sym::push),
@@ -1442,8 +1447,7 @@ impl<'gcx> Lowerer<'gcx> {
         // Fallback: compute selector from member name
         // This is a simplified version - proper implementation would use full signature
         let sig = format!("{}()", member.name);
-        let hash = alloy_primitives::keccak256(sig.as_bytes());
-        u32::from_be_bytes(hash[..4].try_into().unwrap())
+        u32::from_be_bytes(self.gcx.signature_selector(&sig).0)
     }
 
     /// Gets the number of return values for a member function call.
@@ -1580,6 +1584,10 @@ impl<'gcx> Lowerer<'gcx> {
         // as a memory value and would miscompile subsequent field/element reads.
         let has_storage_ref_param = params.iter().any(|&p| self.param_is_storage_ref(p));
 
+        // Modifiers wrap the body in inline exit blocks, which only the full
+        // statement lowering of an internal frame handles.
+        let has_modifiers = Self::has_modifier_invocations(func);
+
         // `-O size`: lowering-time inlining duplicates the body at every call
         // site with no call-count awareness. Emit a shared call instead — with
         // static frames and stack return addresses a call site costs a few
@@ -1588,7 +1596,11 @@ impl<'gcx> Lowerer<'gcx> {
         let size_mode = self.gcx.sess.opts.optimization.is_size();
 
         if func.returns.is_empty() {
-            if size_mode || has_storage_ref_param || self.function_is_recursive(func_id) {
+            if size_mode
+                || has_storage_ref_param
+                || has_modifiers
+                || self.function_is_recursive(func_id)
+            {
                 return self.lower_internal_call_fallback(builder, func_id, arg_vals);
             }
             return self.lower_inline_void_call(builder, func_id, arg_vals);
@@ -1605,7 +1617,7 @@ impl<'gcx> Lowerer<'gcx> {
                 builder,
                 func_id,
                 arg_vals,
-                has_storage_ref_param,
+                has_storage_ref_param || has_modifiers,
             );
         }
 
@@ -1622,6 +1634,7 @@ impl<'gcx> Lowerer<'gcx> {
         // (`ensure_internal_mir_function`) for `internal_call` to target.
         let needs_call = size_mode
             || has_storage_ref_param
+            || has_modifiers
             || !Self::is_simple_return_function(func)
             || self.function_is_recursive(func_id);
         if needs_call {
@@ -1859,17 +1872,12 @@ impl<'gcx> Lowerer<'gcx> {
         arg_vals: Vec<ValueId>,
     ) -> ValueId {
         let func = self.gcx.hir.function(func_id);
+        tracing::debug!(function = ?func.name, "internal call");
         let result_ty = func
             .returns
             .first()
             .map(|&ret_id| self.lower_type_from_var(self.gcx.hir.variable(ret_id)));
-        let is_internal =
-            matches!(func.visibility, hir::Visibility::Internal | hir::Visibility::Private);
-        let mir_id = if is_internal {
-            self.ensure_function_lowered(func_id)
-        } else {
-            self.ensure_internal_mir_function(func_id)
-        };
+        let mir_id = self.internal_call_target(func_id);
         let Some(result_ty) = result_ty else {
             // Void call: the instruction produces no value, so hand back a
             // placeholder for the expression position, which is never read.
@@ -1879,6 +1887,18 @@ impl<'gcx> Lowerer<'gcx> {
         builder.internal_call(mir_id, arg_vals, result_ty, func.returns.len())
     }
 
+    /// Returns the internal-frame MIR function for `func_id`: the function
+    /// itself when it is internal or private, or an internal copy of a public
+    /// function.
+    pub(super) fn internal_call_target(&mut self, func_id: hir::FunctionId) -> FunctionId {
+        let func = self.gcx.hir.function(func_id);
+        if matches!(func.visibility, hir::Visibility::Internal | hir::Visibility::Private) {
+            self.ensure_function_lowered(func_id)
+        } else {
+            self.ensure_internal_mir_function(func_id)
+        }
+    }
+
     /// Lowers a base constructor call using already-resolved constructor arguments.
     pub(super) fn lower_base_constructor_call(
         &mut self,
@@ -2283,13 +2303,18 @@ impl<'gcx> Lowerer<'gcx> {
             // miscompile subsequent field/element reads.
             let has_storage_ref_param =
                 func.parameters.iter().any(|&p| self.param_is_storage_ref(p));
+            let has_modifiers = Self::has_modifier_invocations(func);
 
             // `-O size`: share multi-use helpers through (cheap static-frame)
             // calls instead of duplicating their body at every call site.
             let size_mode = self.gcx.sess.opts.optimization.is_size();
 
             if func.returns.is_empty() {
-                if size_mode || has_storage_ref_param || self.function_is_recursive(func_id) {
+                if size_mode
+                    || has_storage_ref_param
+                    || has_modifiers
+                    || self.function_is_recursive(func_id)
+                {
                     return self.lower_internal_call_fallback(builder, func_id, arg_vals);
                 }
                 return self.lower_inline_void_call(builder, func_id, arg_vals);
@@ -2303,12 +2328,13 @@ impl<'gcx> Lowerer<'gcx> {
                     builder,
                     func_id,
                     arg_vals,
-                    has_storage_ref_param,
+                    has_storage_ref_param || has_modifiers,
                 );
             }
 
             if size_mode
                 || has_storage_ref_param
+                || has_modifiers
                 || !Self::is_simple_return_function(func)
                 || self.function_is_recursive(func_id)
             {
@@ -2436,7 +2462,9 @@ impl<'gcx> Lowerer<'gcx> {
             | StmtKind::Return(Some(e))
             | StmtKind::Revert(e)
             | StmtKind::Emit(e) => self.expr_collect_callees(e, callees),
-            StmtKind::Block(b) | StmtKind::UncheckedBlock(b) | StmtKind::AssemblyBlock(b) => {
+            StmtKind::Block(b)
+            | StmtKind::UncheckedBlock(b)
+            | StmtKind::AssemblyBlock(hir::StmtAssembly { block: b, .. }) => {
                 for stmt in b.stmts {
                     self.stmt_collect_callees(stmt, callees);
                 }
@@ -28,9 +28,11 @@ pub(super) enum PanicCode {
     Assert,
     ArithmeticOverflowUnderflow,
     DivisionByZero,
+    EnumConversion,
     PopEmptyArray,
     ArrayOutOfBounds,
     MemoryAllocationOverflow,
+    InvalidInternalFunction,
 }
 
 impl PanicCode {
@@ -39,9 +41,11 @@ impl PanicCode {
             Self::Assert => 0x01,
             Self::ArithmeticOverflowUnderflow => 0x11,
             Self::DivisionByZero => 0x12,
+            Self::EnumConversion => 0x21,
             Self::PopEmptyArray => 0x31,
             Self::ArrayOutOfBounds => 0x32,
             Self::MemoryAllocationOverflow => 0x41,
+            Self::InvalidInternalFunction => 0x51,
         }
     }
 }
@@ -904,6 +908,24 @@ impl<'gcx> Lowerer<'gcx> {
         self.emit_panic_if_zero(builder, in_range, PanicCode::ArrayOutOfBounds);
     }
 
+    /// Emits the range check of an integer to enum conversion:
+    /// `if (!(value < variants)) Panic(0x21)`.
+    ///
+    /// A constant in-range value emits no check.
+    pub(super) fn emit_enum_conversion_check(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        value: ValueId,
+        variants: usize,
+    ) {
+        if Self::const_u256_of(builder, value).is_some_and(|value| value < U256::from(variants)) {
+            return;
+        }
+        let variants = builder.imm_u64(variants as u64);
+        let in_range = builder.lt(value, variants);
+        self.emit_panic_if_zero(builder, in_range, PanicCode::EnumConversion);
+    }
+
     /// Returns the constant value of a MIR immediate, if `value` is one.
     fn const_u256_of(builder: &FunctionBuilder<'_>, value: ValueId) -> Option<U256> {
         match builder.func().value(value) {
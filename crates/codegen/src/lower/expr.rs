@@ -10,6 +10,7 @@ use solar_ast::{LitKind, StrKind};
 use solar_interface::{Ident, Span, Symbol, kw, sym};
 use solar_sema::{
     builtins::Builtin,
+    eval::{ConstValue, is_number_const_expr},
     hir::{self, CallArgs, ElementaryType, ExprKind},
     ty::{Ty, TyKind},
 };
@@ -33,6 +34,19 @@ impl<'gcx> Lowerer<'gcx> {
         builder: &mut FunctionBuilder<'_>,
         expr: &hir::Expr<'_>,
     ) -> ValueId {
+        // Literal operands cannot always be lowered on their own, so expressions like `0.1 * 10`
+        // or `2**256 - 1` are folded exactly instead. Type checking guarantees that they are not
+        // fractional and fit in 256 bits.
+        if is_number_const_expr(expr)
+            && let Ok(value) = self.gcx.try_eval_const_value(expr)
+        {
+            match value {
+                ConstValue::Integer(value) => return builder.imm_u256(value.as_evm_word()),
+                ConstValue::Bool(value) => return builder.imm_bool(*value),
+                ConstValue::Rational(_) | ConstValue::String(_) => {}
+            }
+        }
+
         match &expr.kind {
             ExprKind::Lit(lit) => {
                 // A numeric literal typed `bytesN` uses the left-aligned word
@@ -276,6 +290,18 @@ impl<'gcx> Lowerer<'gcx> {
                     }
                 }
 
+                // An internal function named through its library or a base
+                // contract (`L.f`, `Base.f`, `super.f`) used as a value.
+                if let Some(hir::Res::Item(hir::ItemId::Function(func_id))) =
+                    self.resolved_member(expr)
+                    && let Some(ty) = self.get_expr_type(expr)
+                    && let TyKind::Fn(f) = ty.kind
+                    && f.is_internal()
+                    && !f.attached
+                {
+                    return self.lower_internal_function_ref(builder, func_id);
+                }
+
                 // A `bytes`/`string` struct field living in storage, reached
                 // through a storage reference (`state.part` with
                 // `S storage state`): its value is the packed storage form, so
@@ -588,6 +614,9 @@ impl<'gcx> Lowerer<'gcx> {
     fn lower_ident(&mut self, builder: &mut FunctionBuilder<'_>, res: &hir::Res) -> ValueId {
         match res {
             hir::Res::Item(item_id) => {
+                if let hir::ItemId::Function(func_id) = item_id {
+                    return self.lower_internal_function_ref(builder, *func_id);
+                }
                 if let hir::ItemId::Variable(var_id) = item_id {
                     let var = self.gcx.hir.variable(*var_id);
 
@@ -1046,6 +1075,11 @@ impl<'gcx> Lowerer<'gcx> {
                 }
                 _ => builder.imm_u64(0),
             },
+            // min = first variant, max = last variant
+            hir::TypeKind::Custom(hir::ItemId::Enum(enum_id)) => {
+                let variants = self.gcx.hir.enumm(*enum_id).variants.len() as u64;
+                builder.imm_u64(if is_max { variants.saturating_sub(1) } else { 0 })
+            }
             _ => builder.imm_u64(0),
         }
     }
@@ -1529,11 +1563,31 @@ impl<'gcx> Lowerer<'gcx> {
             hir::TypeKind::Elementary(elem) => {
                 self.lower_elementary_type_conversion(builder, elem, source, value)
             }
-            hir::TypeKind::Custom(hir::ItemId::Enum(_)) => self.mask_to_bits(builder, value, 8),
+            hir::TypeKind::Custom(hir::ItemId::Enum(enum_id)) => {
+                self.lower_enum_conversion(builder, *enum_id, source, value)
+            }
             _ => value,
         }
     }
 
+    /// Lowers the conversion of `source` to the enum `enum_id`.
+    ///
+    /// An enum value is represented by its integer, so converting an integer only checks that it
+    /// names a variant, panicking with `0x21` otherwise, like solc.
+    pub(super) fn lower_enum_conversion(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        enum_id: hir::EnumId,
+        source: &hir::Expr<'_>,
+        value: ValueId,
+    ) -> ValueId {
+        if self.get_expr_type(source).is_some_and(|ty| ty.is_integer()) {
+            let variants = self.gcx.hir.enumm(enum_id).variants.len();
+            self.emit_enum_conversion_check(builder, value, variants);
+        }
+        value
+    }
+
     fn lower_elementary_type_conversion(
         &mut self,
         builder: &mut FunctionBuilder<'_>,
@@ -1917,6 +1971,23 @@ impl<'gcx> Lowerer<'gcx> {
         struct_ptr
     }
 
+    /// Reserves `size` bytes that were written in place at `base`, the
+    /// unbumped free memory pointer. The reservation is rounded up to a whole
+    /// word so the free memory pointer stays aligned.
+    pub(super) fn commit_fmp_allocation(
+        &self,
+        builder: &mut FunctionBuilder<'_>,
+        base: ValueId,
+        size: ValueId,
+    ) {
+        let thirty_one = builder.imm_u64(31);
+        let rounded = builder.add(size, thirty_one);
+        let mask = builder.not(thirty_one);
+        let aligned = builder.and(rounded, mask);
+        let new_free_ptr = builder.add(base, aligned);
+        builder.set_fmp(new_free_ptr);
+    }
+
     /// Allocates memory for a given size and returns the pointer.
     pub(super) fn allocate_memory(
         &mut self,
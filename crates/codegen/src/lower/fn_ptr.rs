@@ -0,0 +1,190 @@
+//! Internal function pointer lowering.
+//!
+//! An internal function value is a small integer ID: the position of the
+//! function among the module's address-taken functions, counting from 1, so
+//! that the zero value of an unassigned pointer names no function. A call
+//! through a pointer goes to a dispatch helper shared by all calls with the
+//! same parameter and return types. The helper switches on the ID, calls the
+//! matching function, and panics with code `0x51` for any other ID.
+//!
+//! Dispatch helpers are created empty on first use and filled in after the
+//! contract is lowered, once every address-taken function is known. Creation
+//! and runtime code share one module, so an ID stored by the constructor
+//! dispatches to the same function at runtime.
+
+use super::{Lowerer, checked_arith::PanicCode};
+use crate::mir::{Function, FunctionBuilder, FunctionId, MirType, ValueId};
+use solar_ast::DataLocation;
+use solar_interface::{Ident, Span, sym};
+use solar_sema::{
+    hir::{self, CallArgs},
+    ty::{Ty, TyFn, TyKind},
+};
+
+/// The indirect call helper for one internal function signature.
+#[derive(Clone, Copy)]
+pub(super) struct InternalDispatcher<'gcx> {
+    parameters: &'gcx [Ty<'gcx>],
+    returns: &'gcx [Ty<'gcx>],
+    function: FunctionId,
+}
+
+impl<'gcx> Lowerer<'gcx> {
+    /// Lowers a reference to `func_id` used as an internal function value.
+    pub(super) fn lower_internal_function_ref(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        func_id: hir::FunctionId,
+    ) -> ValueId {
+        let (index, _) = self.internal_fn_ptr_targets.insert_full(func_id);
+        builder.imm_u64(index as u64 + 1)
+    }
+
+    /// Returns the function type of `callee` when calling it dispatches
+    /// through an internal function pointer rather than naming a function.
+    pub(super) fn indirect_internal_callee(
+        &self,
+        callee: &hir::Expr<'_>,
+    ) -> Option<&'gcx TyFn<'gcx>> {
+        let TyKind::Fn(f) = self.get_expr_type(callee)?.kind else { return None };
+        (f.is_internal() && f.function_id.is_none() && !f.attached).then_some(f)
+    }
+
+    /// Lowers a call through an internal function pointer.
+    pub(super) fn lower_indirect_internal_call(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        callee: &hir::Expr<'_>,
+        fn_ty: &'gcx TyFn<'gcx>,
+        args: &CallArgs<'_>,
+    ) -> ValueId {
+        let pointer = self.lower_expr(builder, callee);
+        let mut arg_vals = vec![pointer];
+        for (arg, &param_ty) in args.exprs().zip(fn_ty.parameters) {
+            // Storage references travel by slot, as in direct internal calls.
+            if param_ty.is_ref_at(DataLocation::Storage)
+                && let Some(slot) = self.lower_lvalue_slot(builder, arg)
+            {
+                arg_vals.push(slot);
+            } else {
+                let value = self.lower_expr(builder, arg);
+                arg_vals.push(self.coerce_memory_slice_value(builder, value));
+            }
+        }
+
+        let dispatcher = self.ensure_internal_dispatcher(fn_ty.parameters, fn_ty.returns);
+        let Some(&ret_ty) = fn_ty.returns.first() else {
+            builder.internal_call_void(dispatcher, arg_vals, 0);
+            return builder.imm_u64(0);
+        };
+        let result_ty = self.lower_type_from_ty(ret_ty);
+        builder.internal_call(dispatcher, arg_vals, result_ty, fn_ty.returns.len())
+    }
+
+    /// Returns the dispatch helper for the given signature, creating an empty
+    /// one on first use.
+    fn ensure_internal_dispatcher(
+        &mut self,
+        parameters: &'gcx [Ty<'gcx>],
+        returns: &'gcx [Ty<'gcx>],
+    ) -> FunctionId {
+        if let Some(dispatcher) = self
+            .internal_fn_dispatchers
+            .iter()
+            .find(|d| d.parameters == parameters && d.returns == returns)
+        {
+            return dispatcher.function;
+        }
+        let name = Ident::new(sym::__internal_dispatch, Span::DUMMY);
+        let function = self.module.add_function(Function::new(name));
+        self.internal_fn_dispatchers.push(InternalDispatcher { parameters, returns, function });
+        function
+    }
+
+    /// Fills in every dispatch helper of the module.
+    pub(super) fn finish_internal_dispatchers(&mut self) {
+        // Lowering a target can take the address of further functions or call
+        // through another signature, so rebuild until both sets are stable.
+        loop {
+            let targets = self.internal_fn_ptr_targets.len();
+            let dispatchers = self.internal_fn_dispatchers.len();
+            for index in 0..dispatchers {
+                self.build_internal_dispatcher(self.internal_fn_dispatchers[index]);
+            }
+            if targets == self.internal_fn_ptr_targets.len()
+                && dispatchers == self.internal_fn_dispatchers.len()
+            {
+                break;
+            }
+        }
+    }
+
+    fn build_internal_dispatcher(&mut self, dispatcher: InternalDispatcher<'gcx>) {
+        let InternalDispatcher { parameters, returns, function } = dispatcher;
+        let matching: Vec<(u64, hir::FunctionId)> = self
+            .internal_fn_ptr_targets
+            .iter()
+            .enumerate()
+            .filter(|&(_, &func_id)| {
+                let TyKind::Fn(f) = self.gcx.type_of_item(func_id.into()).kind else {
+                    return false;
+                };
+                f.parameters == parameters && f.returns == returns
+            })
+            .map(|(index, &func_id)| (index as u64 + 1, func_id))
+            .collect();
+        // Resolve the targets before building: lowering them re-enters `self`.
+        let cases: Vec<(u64, FunctionId)> = matching
+            .into_iter()
+            .map(|(id, func_id)| (id, self.internal_call_target(func_id)))
+            .collect();
+
+        let mut func = Function::new(Ident::new(sym::__internal_dispatch, Span::DUMMY));
+        func.attributes.no_inline = true;
+        {
+            let mut builder = FunctionBuilder::new(&mut func);
+            let pointer = builder.add_param(MirType::Function);
+            let args: Vec<ValueId> = parameters
+                .iter()
+                .map(|&ty| builder.add_param(self.lower_type_from_ty(ty)))
+                .collect();
+            let result_tys: Vec<MirType> =
+                returns.iter().map(|&ty| self.lower_type_from_ty(ty)).collect();
+            for &ty in &result_tys {
+                builder.add_return(ty);
+            }
+
+            let default_block = builder.create_block();
+            let mut case_blocks = Vec::with_capacity(cases.len());
+            let mut targets = Vec::with_capacity(cases.len());
+            for &(id, target) in &cases {
+                let block = builder.create_block();
+                case_blocks.push((builder.imm_u64(id), block));
+                targets.push((block, target));
+            }
+            builder.switch(pointer, default_block, case_blocks);
+
+            for (block, target) in targets {
+                builder.switch_to_block(block);
+                let Some(&result_ty) = result_tys.first() else {
+                    builder.internal_call_void(target, args.clone(), 0);
+                    builder.ret([]);
+                    continue;
+                };
+                let first = builder.internal_call(target, args.clone(), result_ty, returns.len());
+                let mut values = vec![first];
+                if returns.len() > 1 {
+                    let base = self.multi_return_buffer_base(&mut builder);
+                    for i in 1..returns.len() {
+                        values.push(self.load_multi_return_value(&mut builder, base, i));
+                    }
+                }
+                builder.ret(values);
+            }
+
+            builder.switch_to_block(default_block);
+            self.emit_panic_revert(&mut builder, PanicCode::InvalidInternalFunction);
+        }
+        *self.module.function_mut(function) = func;
+    }
+}
@@ -8,7 +8,9 @@ mod bytes;
 mod call;
 mod checked_arith;
 mod expr;
+mod fn_ptr;
 mod index;
+mod modifiers;
 mod stmt;
 mod storage;
 mod type_query;
@@ -24,9 +26,9 @@ use alloy_primitives::U256;
 use solar_data_structures::{
     Never,
     bit_set::GrowableBitSet,
-    map::{FxHashMap, FxHashSet},
+    map::{FxHashMap, FxHashSet, FxIndexSet},
 };
-use solar_interface::{Ident, Span, diagnostics::DiagMsg, kw, sym};
+use solar_interface::{Ident, Span, Symbol, diagnostics::DiagMsg, kw, sym};
 use solar_sema::{
     hir::{self, ContractId, ElementaryType, FunctionId as HirFunctionId, VariableId, Visit},
     ty::{Gcx, Ty, TyKind},
@@ -115,10 +117,17 @@ pub(crate) struct Lowerer<'gcx> {
     /// returns cannot ride the one-word-per-value multi-return buffer
     /// (calldata slices). Destructuring consumes them directly.
     pending_inline_returns: Option<Vec<ValueId>>,
+    /// Position in the modifier chain of the function being lowered; a `_`
+    /// placeholder lowers the next step.
+    modifier_chain: Option<modifiers::ModifierChain>,
+    /// Functions used as internal function values. A function's pointer value
+    /// is its position in this set plus one.
+    internal_fn_ptr_targets: FxIndexSet<HirFunctionId>,
+    /// Indirect call helpers for internal function pointers, one per signature.
+    internal_fn_dispatchers: Vec<fn_ptr::InternalDispatcher<'gcx>>,
     /// Next available memory offset for locals.
     next_local_memory_offset: u64,
-    /// Bytecodes of other contracts (for `new` expressions).
-    /// Maps contract ID to (deployment_bytecode, data_segment_index).
+    /// Creation bytecodes of other contracts, for `new` and `type(C).creationCode`.
     contract_bytecodes: FxHashMap<ContractId, Vec<u8>>,
     /// Stack of loop contexts for nested loops.
     loop_stack: Vec<LoopContext>,
@@ -206,6 +215,9 @@ impl<'gcx> Lowerer<'gcx> {
             local_memory_slots: FxHashMap::default(),
             slice_slot_locals: FxHashSet::default(),
             inline_returns: None,
+            modifier_chain: None,
+            internal_fn_ptr_targets: FxIndexSet::default(),
+            internal_fn_dispatchers: Vec::new(),
             pending_inline_returns: None,
             next_local_memory_offset: EvmMemoryLayout::HEAP_START,
             contract_bytecodes: FxHashMap::default(),
@@ -252,15 +264,19 @@ impl<'gcx> Lowerer<'gcx> {
     /// Attempts to enter inlining for a function. Returns false if a cycle is detected
     /// or the max inline depth is exceeded.
     fn try_enter_inline(&mut self, func_id: HirFunctionId) -> bool {
+        let name = self.gcx.hir.function(func_id).name;
         // Check for cycle
         if self.inline_stack.contains(&func_id) {
+            tracing::debug!(function = ?name, reason = "cycle", "not inlining");
             return false;
         }
         // Check depth limit
         if self.inline_stack.len() >= Self::MAX_INLINE_DEPTH {
+            tracing::debug!(function = ?name, reason = "depth", "not inlining");
             return false;
         }
         self.inline_stack.push(func_id);
+        tracing::debug!(function = ?name, depth = self.inline_stack.len(), "inlining");
         true
     }
 
@@ -275,6 +291,7 @@ impl<'gcx> Lowerer<'gcx> {
         let offset = self.next_local_memory_offset;
         self.next_local_memory_offset += EvmMemoryLayout::WORD_SIZE;
         self.local_memory_slots.insert(var_id, offset);
+        tracing::debug!(var = ?self.gcx.hir.variable(var_id).name, offset, "local memory slot");
         offset
     }
 
@@ -285,6 +302,11 @@ impl<'gcx> Lowerer<'gcx> {
         self.next_local_memory_offset += 2 * EvmMemoryLayout::WORD_SIZE;
         self.local_memory_slots.insert(var_id, offset);
         self.slice_slot_locals.insert(var_id);
+        tracing::debug!(
+            var = ?self.gcx.hir.variable(var_id).name,
+            offset,
+            "local slice memory slot"
+        );
         offset
     }
 
@@ -395,8 +417,8 @@ impl<'gcx> Lowerer<'gcx> {
         // Track the current contract for using directive resolution.
         self.current_contract_id = Some(contract_id);
 
-        // Mark interfaces - they don't generate deployable bytecode.
-        if contract.kind == hir::ContractKind::Interface {
+        // Interfaces and abstract contracts are never deployed, so they get no bytecode.
+        if contract.kind.is_interface() || contract.kind.is_abstract_contract() {
             self.module.is_interface = true;
         }
 
@@ -416,6 +438,7 @@ impl<'gcx> Lowerer<'gcx> {
         for func_id in functions {
             self.ensure_function_lowered(func_id);
         }
+        self.finish_internal_dispatchers();
 
         self.current_contract_id = None;
     }
@@ -579,6 +602,7 @@ impl<'gcx> Lowerer<'gcx> {
                         .checked_add(IMMUTABLE_WORD_SIZE as u32)
                         .expect("immutable offset overflow");
                     self.immutable_slots.insert(var_id, offset);
+                    tracing::debug!(var = ?var.name, offset, "immutable slot");
 
                     self.module.add_immutable();
                 } else if var.is_state_variable() && !var.is_constant() {
@@ -591,6 +615,13 @@ impl<'gcx> Lowerer<'gcx> {
                         self.struct_storage_base_slots.insert(var_id, base_slot);
                     }
 
+                    tracing::debug!(
+                        var = ?var.name,
+                        slot = location.slot,
+                        offset = location.offset,
+                        size = location.size,
+                        "storage slot"
+                    );
                     self.storage_slots.insert(var_id, base_slot);
                     self.storage_locations.insert(var_id, location);
                 }
@@ -638,6 +669,7 @@ impl<'gcx> Lowerer<'gcx> {
         let saved_assigned_vars = std::mem::take(&mut self.assigned_vars);
         let saved_inline_returns = self.inline_returns.take();
         let saved_pending_inline_returns = self.pending_inline_returns.take();
+        let saved_modifier_chain = self.modifier_chain.take();
         let saved_current_contract_id = self.current_contract_id;
         let saved_lowering_constructor = self.lowering_constructor;
         let saved_lowering_internal_function = self.lowering_internal_function;
@@ -657,6 +689,7 @@ impl<'gcx> Lowerer<'gcx> {
         self.assigned_vars = saved_assigned_vars;
         self.inline_returns = saved_inline_returns;
         self.pending_inline_returns = saved_pending_inline_returns;
+        self.modifier_chain = saved_modifier_chain;
         self.current_contract_id = saved_current_contract_id;
         self.lowering_constructor = saved_lowering_constructor;
         self.lowering_internal_function = saved_lowering_internal_function;
@@ -798,6 +831,7 @@ impl<'gcx> Lowerer<'gcx> {
         let saved_assigned_vars = std::mem::take(&mut self.assigned_vars);
         let saved_inline_returns = self.inline_returns.take();
         let saved_pending_inline_returns = self.pending_inline_returns.take();
+        let saved_modifier_chain = self.modifier_chain.take();
         let saved_current_contract_id = self.current_contract_id;
         let saved_lowering_constructor = self.lowering_constructor;
         let saved_lowering_internal_function = self.lowering_internal_function;
@@ -815,6 +849,7 @@ impl<'gcx> Lowerer<'gcx> {
         self.assigned_vars = saved_assigned_vars;
         self.inline_returns = saved_inline_returns;
         self.pending_inline_returns = saved_pending_inline_returns;
+        self.modifier_chain = saved_modifier_chain;
         self.current_contract_id = saved_current_contract_id;
         self.lowering_constructor = saved_lowering_constructor;
         self.lowering_internal_function = saved_lowering_internal_function;
@@ -830,6 +865,8 @@ impl<'gcx> Lowerer<'gcx> {
         let hir_func = self.gcx.hir.function(func_id);
 
         let func_name = hir_func.name.unwrap_or_else(|| Ident::new(sym::_anonymous, Span::DUMMY));
+        let _span =
+            tracing::debug_span!("lower_function", name = %func_name, force_internal).entered();
 
         // Reserve and register the MIR id before lowering the body so recursive
         // self-calls can resolve to this function.
@@ -841,6 +878,10 @@ impl<'gcx> Lowerer<'gcx> {
         }
 
         let mut mir_func = Function::new(func_name);
+        if self.gcx.sess.opts.profile_data.is_some() {
+            mir_func.profile_signature =
+                Some(Symbol::intern(&self.gcx.function_profile_signature(func_id)));
+        }
 
         mir_func.attributes = FunctionAttributes {
             visibility: hir_func.visibility,
@@ -1277,7 +1318,9 @@ impl<'gcx> Lowerer<'gcx> {
                 self.lower_constructor_prelude(&mut builder, contract_id);
             }
 
-            if let Some(body) = &hir_func.body {
+            if Self::has_modifier_invocations(hir_func) {
+                self.lower_modified_body(&mut builder, func_id);
+            } else if let Some(body) = &hir_func.body {
                 self.lower_block(&mut builder, body);
             }
 
@@ -1311,6 +1354,13 @@ impl<'gcx> Lowerer<'gcx> {
             mir_func.external_static_return_size =
                 self.current_return_tys.iter().map(|&ty| self.abi_head_size(ty)).sum();
         }
+        tracing::debug!(
+            selector = ?mir_func.selector,
+            internal_frame = uses_internal_frame,
+            frame_size = mir_func.internal_frame_size,
+            static_return_size = mir_func.external_static_return_size,
+            "lowered function"
+        );
 
         *self.module.function_mut(mir_id) = mir_func;
         mir_id
@@ -1646,7 +1696,7 @@ impl<'gcx> Lowerer<'gcx> {
                     self.collect_assigned_vars_block(&clause.block);
                 }
             }
-            StmtKind::AssemblyBlock(block) => self.collect_assigned_vars_block(block),
+            StmtKind::AssemblyBlock(assembly) => self.collect_assigned_vars_block(&assembly.block),
             StmtKind::DeclSingle(_)
             | StmtKind::DeclMulti(_, _)
             | StmtKind::Return(None)
@@ -1923,7 +1973,12 @@ impl<'gcx> Visit<'gcx> for BytecodeDependencyCollector<'_, 'gcx> {
 }
 
 /// Lowers a contract from HIR to MIR with pre-compiled bytecodes available for `new` expressions.
-#[tracing::instrument(name = "mir_lower_contract", level = "debug", skip_all, fields(?contract_id))]
+#[tracing::instrument(
+    name = "mir_lower_contract",
+    level = "debug",
+    skip_all,
+    fields(?contract_id, contract = %gcx.hir.contract(contract_id).name)
+)]
 pub fn lower_contract_with_bytecodes(
     gcx: Gcx<'_>,
     contract_id: ContractId,
@@ -0,0 +1,166 @@
+//! Function modifier lowering.
+//!
+//! Modifiers are inlined around the function body. Lowering a modified
+//! function enters its outermost modifier; each `_` placeholder in a modifier
+//! body lowers the next modifier of the chain, and the last one's placeholder
+//! lowers the function body itself. A placeholder may appear any number of
+//! times, so the code after it is lowered once per occurrence.
+//!
+//! Control leaves each level through an inline exit block, using the same
+//! machinery as inlined internal calls: a `return` in the function body stores
+//! the return variables and resumes the innermost modifier after its `_`, and
+//! a `return;` in a modifier body resumes its enclosing modifier. The real
+//! function return is emitted once the outermost modifier completes, from the
+//! return variables' slots.
+//!
+//! The same modifier may be applied more than once, as in `f() m(1) m(2)`.
+//! Each level binds the modifier's variables anew, and the bindings of the
+//! enclosing level are restored once a placeholder completes, so code after
+//! `_` reads its own arguments and locals.
+
+use super::{InlineReturnCtx, Lowerer};
+use crate::mir::{FunctionBuilder, ValueId};
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+use solar_sema::hir::{self, FunctionId as HirFunctionId, VariableId};
+
+/// Position in the modifier chain of the function being lowered.
+#[derive(Clone, Copy)]
+pub(super) struct ModifierChain {
+    /// The modified function.
+    function: HirFunctionId,
+    /// Index of the modifier that the next `_` placeholder enters. Equal to
+    /// the number of modifiers when the placeholder runs the function body.
+    next: usize,
+}
+
+/// Bindings of local variables, saved around each placeholder.
+struct LocalBindings {
+    locals: FxHashMap<VariableId, ValueId>,
+    local_memory_slots: FxHashMap<VariableId, u64>,
+    slice_slot_locals: FxHashSet<VariableId>,
+}
+
+impl<'gcx> Lowerer<'gcx> {
+    /// Whether `func` invokes at least one modifier. Constructor base calls,
+    /// which share the same list, do not count.
+    pub(super) fn has_modifier_invocations(func: &hir::Function<'_>) -> bool {
+        func.modifiers.iter().any(|modifier| matches!(modifier.id, hir::ItemId::Function(_)))
+    }
+
+    /// Lowers the body of `func_id` wrapped in its modifiers.
+    pub(super) fn lower_modified_body(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        func_id: HirFunctionId,
+    ) {
+        for (_, modifier_id) in self.applied_modifiers(func_id) {
+            if let Some(body) = &self.gcx.hir.function(modifier_id).body {
+                self.collect_assigned_vars_block(body);
+            }
+        }
+
+        let saved_chain = self.modifier_chain.replace(ModifierChain { function: func_id, next: 0 });
+        self.lower_placeholder(builder);
+        self.modifier_chain = saved_chain;
+    }
+
+    /// Lowers a `_` placeholder: enters the next modifier of the chain, or
+    /// runs the function body after the last one.
+    pub(super) fn lower_placeholder(&mut self, builder: &mut FunctionBuilder<'_>) {
+        let Some(chain) = self.modifier_chain else { return };
+        let modifiers = self.applied_modifiers(chain.function);
+        let saved_inline_returns = self.inline_returns.take();
+        let saved_bindings = self.save_local_bindings();
+        let exit_block = builder.create_block();
+
+        if let Some(&(invocation, modifier_id)) = modifiers.get(chain.next) {
+            // Arguments are evaluated on entry to the modifier, in the scope
+            // of the modified function.
+            let modifier = self.gcx.hir.function(modifier_id);
+            for (&param_id, arg) in modifier.parameters.iter().zip(invocation.args.exprs()) {
+                let value = self.lower_expr(builder, arg);
+                let value = self.coerce_memory_slice_value(builder, value);
+                self.bind_local_value(builder, param_id, value);
+            }
+
+            self.inline_returns = Some(InlineReturnCtx { exit_block, return_vars: Vec::new() });
+            self.modifier_chain = Some(ModifierChain { next: chain.next + 1, ..chain });
+            if let Some(body) = &modifier.body {
+                self.lower_block(builder, body);
+            }
+        } else {
+            let func = self.gcx.hir.function(chain.function);
+            self.inline_returns =
+                Some(InlineReturnCtx { exit_block, return_vars: func.returns.to_vec() });
+            self.modifier_chain = None;
+            if let Some(body) = &func.body {
+                self.lower_block(builder, body);
+            }
+        }
+
+        if !builder.func().block(builder.current_block()).is_terminated() {
+            builder.jump(exit_block);
+        }
+        builder.switch_to_block(exit_block);
+        self.modifier_chain = Some(chain);
+        self.inline_returns = saved_inline_returns;
+        self.restore_local_bindings(saved_bindings);
+    }
+
+    fn save_local_bindings(&self) -> LocalBindings {
+        LocalBindings {
+            locals: self.locals.clone(),
+            local_memory_slots: self.local_memory_slots.clone(),
+            slice_slot_locals: self.slice_slot_locals.clone(),
+        }
+    }
+
+    fn restore_local_bindings(&mut self, bindings: LocalBindings) {
+        let LocalBindings { locals, local_memory_slots, slice_slot_locals } = bindings;
+        self.locals = locals;
+        self.local_memory_slots = local_memory_slots;
+        self.slice_slot_locals = slice_slot_locals;
+    }
+
+    /// Returns the modifier invocations of `func_id`, outermost first, each
+    /// paired with the modifier that runs for it.
+    fn applied_modifiers(
+        &self,
+        func_id: HirFunctionId,
+    ) -> Vec<(&'gcx hir::Modifier<'gcx>, HirFunctionId)> {
+        self.gcx
+            .hir
+            .function(func_id)
+            .modifiers
+            .iter()
+            .filter_map(|invocation| {
+                let hir::ItemId::Function(modifier_id) = invocation.id else { return None };
+                Some((invocation, self.resolve_modifier_override(modifier_id)))
+            })
+            .collect()
+    }
+
+    /// Returns the most derived override of `modifier_id` in the contract
+    /// being lowered. Modifiers cannot be overloaded, so the first modifier
+    /// with the same name in the linearization is the one that runs.
+    fn resolve_modifier_override(&self, modifier_id: HirFunctionId) -> HirFunctionId {
+        let modifier = self.gcx.hir.function(modifier_id);
+        let (Some(contract_id), Some(defining), Some(name)) =
+            (self.current_contract_id, modifier.contract, modifier.name)
+        else {
+            return modifier_id;
+        };
+        let bases = self.gcx.hir.contract(contract_id).linearized_bases;
+        if !bases.contains(&defining) {
+            return modifier_id;
+        }
+        bases
+            .iter()
+            .flat_map(|&base| self.gcx.hir.contract(base).functions())
+            .find(|&id| {
+                let func = self.gcx.hir.function(id);
+                func.kind.is_modifier() && func.name.is_some_and(|n| n.name == name.name)
+            })
+            .unwrap_or(modifier_id)
+    }
+}
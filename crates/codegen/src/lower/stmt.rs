@@ -78,7 +78,9 @@ impl<'gcx> Lowerer<'gcx> {
         builder: &mut FunctionBuilder<'_>,
         args: &hir::CallArgs<'_>,
     ) -> bool {
-        if self.current_return_tys.len() != 1 {
+        // Inside a modifier or an inlined body, `return` resumes the
+        // enclosing code instead of leaving the function.
+        if self.current_return_tys.len() != 1 || self.inline_returns.is_some() {
             return false;
         }
         let ty = self.current_return_tys[0];
@@ -151,12 +153,12 @@ impl<'gcx> Lowerer<'gcx> {
                 }
             }
 
-            StmtKind::Placeholder => {}
+            StmtKind::Placeholder => self.lower_placeholder(builder),
 
             StmtKind::UncheckedBlock(block) => self.lower_unchecked_block(builder, block),
 
-            StmtKind::AssemblyBlock(block) => {
-                self.lower_block(builder, block);
+            StmtKind::AssemblyBlock(assembly) => {
+                self.lower_block(builder, &assembly.block);
             }
 
             StmtKind::Err(_) => {}
@@ -515,7 +517,7 @@ impl<'gcx> Lowerer<'gcx> {
     /// Binds a lowered value to a freshly declared local, mirroring
     /// single-declaration lowering: a reassigned local gets a memory slot (two
     /// words for a calldata slice), everything else stays an SSA value.
-    fn bind_local_value(
+    pub(super) fn bind_local_value(
         &mut self,
         builder: &mut FunctionBuilder<'_>,
         var_id: hir::VariableId,
@@ -687,6 +689,19 @@ impl<'gcx> Lowerer<'gcx> {
             && !self.is_contract_type_expr(base)
     }
 
+    /// Lowers a low-level call to its full `(bool success, bytes memory data)`
+    /// result. The returndata is copied into a fresh allocation right after
+    /// the call, before anything else can clobber the return buffer.
+    pub(super) fn lower_low_level_call_values(
+        &mut self,
+        builder: &mut FunctionBuilder<'_>,
+        expr: &hir::Expr<'_>,
+    ) -> [ValueId; 2] {
+        let success = self.lower_expr(builder, expr);
+        let data = self.materialize_returndata_bytes(builder);
+        [success, data]
+    }
+
     /// Lowers an if statement.
     fn lower_if(
         &mut self,
@@ -904,6 +919,9 @@ impl<'gcx> Lowerer<'gcx> {
                     })
                     .collect();
                 builder.ret(ret_vals);
+            } else if self.is_low_level_call_expr(expr) {
+                let ret_vals = self.lower_low_level_call_values(builder, expr);
+                builder.ret(ret_vals);
             } else if let Some(arity) = self.get_ternary_tuple_arity(expr) {
                 let first = self.lower_expr(builder, expr);
                 let mut ret_vals = Vec::with_capacity(arity);
@@ -953,6 +971,8 @@ impl<'gcx> Lowerer<'gcx> {
                 for elem in elements.iter().flatten() {
                     values.push(self.lower_expr(builder, elem));
                 }
+            } else if n > 1 && self.is_low_level_call_expr(expr) {
+                values.extend(self.lower_low_level_call_values(builder, expr));
             } else {
                 self.pending_inline_returns = None;
                 let first = self.lower_expr(builder, expr);
@@ -1023,9 +1043,8 @@ impl<'gcx> Lowerer<'gcx> {
 
         let event = self.gcx.hir.event(event_id);
 
-        // Compute event signature hash (topic0 for non-anonymous events)
-        let sig = self.compute_event_signature(event);
-        let sig_hash = alloy_primitives::keccak256(sig.as_bytes());
+        // Event signature hash (topic0 for non-anonymous events).
+        let sig_hash = self.gcx.event_selector(event_id);
         let topic0 = builder.imm_u256(alloy_primitives::U256::from_be_bytes(sig_hash.0));
 
         // Collect indexed parameters (additional topics) and non-indexed (data).
@@ -1079,39 +1098,6 @@ impl<'gcx> Lowerer<'gcx> {
         }
     }
 
-    /// Computes the event signature string: "EventName(type1,type2,...)"
-    fn compute_event_signature(&self, event: &hir::Event<'_>) -> String {
-        let params: Vec<String> = event
-            .parameters
-            .iter()
-            .map(|param_id| {
-                let param = self.gcx.hir.variable(*param_id);
-                self.type_to_abi_string(&param.ty)
-            })
-            .collect();
-        format!("{}({})", event.name.name, params.join(","))
-    }
-
-    /// Converts a HIR type to its ABI string representation
-    fn type_to_abi_string(&self, ty: &hir::Type<'_>) -> String {
-        match &ty.kind {
-            hir::TypeKind::Elementary(elem) => elem.to_abi_str().to_string(),
-            hir::TypeKind::Custom(item_id) => {
-                // For contracts, use "address"
-                if let hir::ItemId::Contract(_) = item_id {
-                    "address".to_string()
-                } else {
-                    "uint256".to_string() // Fallback
-                }
-            }
-            hir::TypeKind::Array(arr) => {
-                let inner = self.type_to_abi_string(&arr.element);
-                format!("{inner}[]")
-            }
-            _ => "uint256".to_string(), // Fallback for other types
-        }
-    }
-
     /// Lowers a try/catch statement.
     ///
     /// try expr returns (...) { success_block } catch (...) { catch_block }
@@ -539,6 +539,11 @@ impl<'a> FunctionBuilder<'a> {
         self.emit_inst(InstKind::LoadImmutable(offset), Some(MirType::uint256()))
     }
 
+    /// Emits a dataoffset instruction for a data segment appended after the code.
+    pub(crate) fn data_offset(&mut self, data: Box<[u8]>) -> ValueId {
+        self.emit_inst(InstKind::DataOffset(data), Some(MirType::uint256()))
+    }
+
     /// Emits an extcodecopy instruction.
     pub(crate) fn extcodecopy(
         &mut self,
@@ -6,6 +6,7 @@ use super::{
     BasicBlock, BlockId, EffectKind, Function, FunctionId, InstId, InstKind, Instruction,
     MemoryRegion, StorageAlias, Terminator, Value, ValueId,
 };
+use alloy_primitives::hex;
 use arrayvec::ArrayVec;
 use solar_data_structures::fmt::{self, FmtIteratorExt};
 use solar_sema::hir;
@@ -307,6 +308,9 @@ fn display_inst_kind<'a>(
 
     fmt::from_fn(move |f| match kind {
         InstKind::LoadImmutable(offset) => write!(f, "loadimmutable {offset}"),
+        InstKind::DataOffset(data) => {
+            write!(f, "dataoffset hex\"{}\"", hex::encode(data))
+        }
         InstKind::Alloc { size, kind, semantics } => {
             let kind = match kind {
                 crate::mir::AllocationKind::Raw => "raw".to_string(),
@@ -10,7 +10,7 @@ use solar_data_structures::{
     index::IndexVec,
     map::FxHashMap,
 };
-use solar_interface::Ident;
+use solar_interface::{Ident, Symbol};
 use solar_sema::hir::{StateMutability, Visibility};
 
 /// A function in the MIR.
@@ -18,6 +18,10 @@ use solar_sema::hir::{StateMutability, Visibility};
 pub(crate) struct Function {
     /// Function name.
     pub(crate) name: Ident,
+    /// Source signature used to look the function up in the execution profile, such as
+    /// `transfer(address,uint256)`. Only set for functions lowered from Solidity with
+    /// `--profile-data`.
+    pub(crate) profile_signature: Option<Symbol>,
     /// Function selector (4 bytes, for external functions).
     pub(crate) selector: Option<[u8; 4]>,
     /// Function attributes.
@@ -51,6 +55,7 @@ impl Function {
 
         Self {
             name,
+            profile_signature: None,
             selector: None,
             attributes: FunctionAttributes::default(),
             params: Vec::new(),
@@ -290,7 +295,7 @@ impl Function {
 }
 
 /// Function attributes.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) struct FunctionAttributes {
     /// Visibility modifier.
     pub(crate) visibility: Visibility,
@@ -493,7 +493,7 @@ impl Instruction {
 /// TODO(codegen): Consider separating opcode and operands once the MIR shape stabilizes, e.g.
 /// `Instruction { opcode: Opcode, operands: SmallVec<[ValueId; 4]>, ... }`. That would make generic
 /// operand visitors and rewrites less variant-heavy.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum InstKind {
     // Arithmetic operations
     /// Addition: `a + b`
@@ -682,6 +682,11 @@ pub(crate) enum InstKind {
     /// constructor patches with the staged value before returning the runtime
     /// code. In constructor code it reads the staged scratch word instead.
     LoadImmutable(u32),
+    /// Get the code offset of a data segment: `dataoffset hex"<data>"`
+    ///
+    /// The data is appended after the code being assembled, and the offset is a constant that is
+    /// only known once the code has been laid out. Identical segments share one copy.
+    DataOffset(Box<[u8]>),
 
     // Return data operations
     /// Get return data size: `returndatasize()`
@@ -989,6 +994,7 @@ impl InstKind {
             | Self::InternalFrameAddr(_)
             | Self::CodeSize
             | Self::LoadImmutable(_)
+            | Self::DataOffset(_)
             | Self::ReturnDataSize
             | Self::Caller
             | Self::CallValue
@@ -1176,6 +1182,7 @@ impl InstKind {
             | Self::InternalFrameAddr(_)
             | Self::CodeSize
             | Self::LoadImmutable(_)
+            | Self::DataOffset(_)
             | Self::ReturnDataSize
             | Self::Caller
             | Self::CallValue
@@ -1255,6 +1262,7 @@ impl InstKind {
             Self::CodeSize => "codesize",
             Self::CodeCopy(_, _, _) => "codecopy",
             Self::LoadImmutable(_) => "loadimmutable",
+            Self::DataOffset(_) => "dataoffset",
             Self::ExtCodeSize(_) => "extcodesize",
             Self::ExtCodeCopy(_, _, _, _) => "extcodecopy",
             Self::ExtCodeHash(_) => "extcodehash",
@@ -1440,6 +1448,7 @@ impl InstKind {
             | Self::MemoryObjectFieldAddr { .. }
             | Self::MemoryObjectElementAddr { .. }
             | Self::InternalFrameAddr(_)
+            | Self::DataOffset(_)
             | Self::Phi(_)
             | Self::Select(_, _, _)
             | Self::SignExtend(_, _) => EffectKind::Pure,
@@ -100,7 +100,7 @@ pub struct Module {
     pub(crate) aggregate_layouts: Vec<StorageLayoutRef>,
     /// Size of the constructor scratch area used to stage immutables.
     immutable_data_len: usize,
-    /// Whether this is an interface (no bytecode generation).
+    /// Whether this is an interface or abstract contract, which gets no bytecode.
     pub(crate) is_interface: bool,
     /// The lowering phase this module is in.
     pub(crate) phase: MirPhase,
@@ -27,6 +27,8 @@
 //! - Address and fixed-bytes immediate literals are not currently parsed — they're allocated as
 //!   `Immediate::uint256(0)`. If you need them, extend `parse_value`.
 //! - Phi nodes are represented only as phi *instructions* (`InstKind::Phi`).
+//! - Function attributes (`[selector=0x..., constructor, ...]`) and the module's immutable word
+//!   count (`@immutables N`) are accepted as input but not printed.
 
 use super::{
     AbiLayout, AbiLayoutRef, AbiType, AllocationAlignment, AllocationFailure,
@@ -36,7 +38,7 @@ use super::{
     StorageLayout, StorageLayoutRef, Terminator, Value, ValueId,
 };
 use crate::mir::{MirType, SliceLocation};
-use alloy_primitives::U256;
+use alloy_primitives::{U256, hex};
 use smallvec::SmallVec;
 use solar_ast::{
     Arena,
@@ -161,6 +163,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
 
     fn parse_module(&mut self) -> PResult<'sess, Module> {
         let mut phase = super::MirPhase::default();
+        let mut immutables = 0;
         self.parser.expect(TokenKind::At)?;
         self.parser.expect_keyword(sym::module)?;
         let module_name = self.parser.parse_ident()?;
@@ -175,6 +178,10 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                             .error_at(phase_span, format!("unknown MIR phase `{phase_name}`"))
                     })?;
                 }
+                sym::immutables => {
+                    let count = self.parser.parse_uint()?;
+                    immutables = self.u256_to_u32(count)?;
+                }
                 _ => return Err(self.parser.error(format!("unknown module attribute `@{attr}`"))),
             }
         }
@@ -182,6 +189,9 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let module_ident = Ident::with_dummy_span(module_name);
         let mut module = Module::new(module_ident);
         module.phase = phase;
+        for _ in 0..immutables {
+            module.add_immutable();
+        }
         let mut function_refs = Vec::new();
 
         while !self.parser.is_eof() {
@@ -403,6 +413,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                     let selector = self.u256_to_u32(selector)?;
                     builder.func_mut().selector = Some(selector.to_be_bytes());
                 }
+                kw::Constructor => builder.func_mut().attributes.is_constructor = true,
                 kw::Receive => builder.func_mut().attributes.is_receive = true,
                 kw::Fallback => builder.func_mut().attributes.is_fallback = true,
                 kw::Payable => {
@@ -1380,6 +1391,16 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 let offset = self.u256_to_u32(offset)?;
                 (InstKind::LoadImmutable(offset), Some(MirType::uint256()))
             }
+            kw::Dataoffset => {
+                let TokenKind::Literal(TokenLitKind::HexStr, symbol) = self.parser.token().kind
+                else {
+                    return Err(self.parser.error("expected hex string data segment"));
+                };
+                let data = hex::decode(symbol.as_str())
+                    .map_err(|e| self.parser.error(format!("invalid data segment: {e}")))?;
+                self.parser.bump();
+                (InstKind::DataOffset(data.into()), Some(MirType::uint256()))
+            }
             kw::Extcodesize => inst!(ExtCodeSize(a) => MirType::uint256()),
             kw::Extcodecopy => inst!(ExtCodeCopy(a, b, c, d)),
             kw::Extcodehash => inst!(ExtCodeHash(a) => MirType::uint256()),
@@ -6,7 +6,7 @@ use solar_interface::diagnostics::ErrorGuaranteed;
 use std::fmt;
 
 /// An SSA value in the MIR.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum Value {
     /// Result of an instruction.
     Inst(InstId),
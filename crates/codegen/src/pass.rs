@@ -25,14 +25,15 @@ use crate::{
     analysis::{AliasAnalysis, CfgInfo, MemoryCallSummaries},
     mir::{Function, FunctionId, InstId, MirPhase, Module},
     transform::{
-        adce, cfg_simplify, check_elim, copy_elision, cse, dce, frame_promotion, gvn,
-        indvar_simplify, inline, inst_simplify, jump_threading, load_pre, loop_canonicalize,
-        loop_opt, lower_abi, lower_abi_encode, lower_aggregates, lower_alloc, lower_dispatch,
-        lower_evm_shaped, lower_mapping_slots, lower_memory_objects, lower_slices, memory_dse,
-        outline_reverts, pre, pure_eval, sccp, sroa, static_alloc, storage_dse, storage_load_cse,
-        storage_promotion,
+        adce, cfg_simplify, check_elim, copy_elision, cse, dce, dedup_functions, frame_coloring,
+        frame_promotion, gvn, indvar_simplify, inline, inst_simplify, jump_threading, load_pre,
+        loop_canonicalize, loop_opt, lower_abi, lower_abi_encode, lower_aggregates, lower_alloc,
+        lower_dispatch, lower_evm_shaped, lower_mapping_slots, lower_memory_objects, lower_slices,
+        memory_dse, outline_reverts, pre, pure_eval, sccp, sroa, static_alloc, storage_dse,
+        storage_load_cse, storage_promotion,
     },
 };
+use rayon::prelude::*;
 use solar_data_structures::map::FxHashMap;
 use std::{
     any::{Any, TypeId},
@@ -44,6 +45,7 @@ use std::{
 pub static ALL_PASSES: &[&dyn MirPass] = &[
     &inline::Inline,
     &outline_reverts::OutlineReverts,
+    &dedup_functions::DedupFunctions,
     &cfg_simplify::FunctionDce,
     &sccp::Sccp,
     &pure_eval::PureEval,
@@ -62,6 +64,7 @@ pub static ALL_PASSES: &[&dyn MirPass] = &[
     &jump_threading::JumpThreading,
     &cfg_simplify::CfgSimplify,
     &frame_promotion::FrameSlotPromotion,
+    &frame_coloring::FrameSlotColoring,
     &memory_dse::MemoryDse,
     &static_alloc::StaticAlloc,
     &sroa::Sroa,
@@ -167,6 +170,10 @@ pub static DEFAULT_PIPELINE: &[&dyn MirPass] = &[
     &memory_dse::MemoryDse,
     &adce::Adce,
     &dce::Dce,
+    // Bodies are now canonical, so templated copies that optimized to the same
+    // MIR can share one definition.
+    &dedup_functions::DedupFunctions,
+    &cfg_simplify::FunctionDce,
     // MIR outlining remains profitable even though EVM IR can merge
     // equivalent terminal blocks: lowering and stack scheduling can
     // hide their shared semantic shape from the backend passes.
@@ -184,6 +191,8 @@ pub static DEFAULT_PIPELINE: &[&dyn MirPass] = &[
     &jump_threading::JumpThreading,
     &cfg_simplify::CfgSimplify,
     &frame_promotion::FrameSlotPromotion,
+    // Slots promotion could not scalarize can still share frame words.
+    &frame_coloring::FrameSlotColoring,
     &memory_dse::MemoryDse,
     &adce::Adce,
     // Progressive lowering materializes ABI wrappers, the dispatcher, and
@@ -261,6 +270,15 @@ pub(crate) fn run_function_pass(
     module: &mut Module,
     analyses: &mut ModuleAnalyses,
     mut run: impl FnMut(&mut Function, &FunctionAnalyses) -> bool,
+) -> bool {
+    run_function_pass_with_ids(module, analyses, |_, func, analyses| run(func, analyses))
+}
+
+/// Like [`run_function_pass`], but also hands the transform each function's id.
+pub(crate) fn run_function_pass_with_ids(
+    module: &mut Module,
+    analyses: &mut ModuleAnalyses,
+    mut run: impl FnMut(FunctionId, &mut Function, &FunctionAnalyses) -> bool,
 ) -> bool {
     let mut changed = false;
     for func_id in module.functions.indices() {
@@ -273,6 +291,45 @@ pub(crate) fn run_function_pass(
     changed
 }
 
+/// Like [`run_function_pass`], but transforms the functions in parallel.
+///
+/// The transform only sees its own function, so it cannot use the cached analyses, which are not
+/// thread-safe. The cache is updated afterwards in function order, which keeps the result
+/// identical to a sequential run.
+pub(crate) fn run_function_pass_par(
+    gcx: solar_sema::Gcx<'_>,
+    module: &mut Module,
+    analyses: &mut ModuleAnalyses,
+    run: impl Fn(&mut Function) -> bool + Sync,
+) -> bool {
+    if gcx.sess.is_sequential() {
+        return run_function_pass(module, analyses, |func, _| run(func));
+    }
+
+    let preservations = module
+        .functions
+        .raw
+        .par_iter_mut()
+        .map(|func| {
+            if func.blocks.is_empty() {
+                return None;
+            }
+            let edges_before = cfg_edges(func);
+            let insts_before = func.instructions.len();
+            run(func).then(|| verified_preservation(func, &edges_before, insts_before))
+        })
+        .collect::<Vec<_>>();
+    let mut changed = false;
+    for (func_id, preservation) in module.functions.indices().zip(preservations) {
+        if let Some((keep_alias, keep_cfg)) = preservation {
+            analyses.retain(func_id, keep_alias, keep_cfg);
+            changed = true;
+        }
+    }
+    analyses.preserved_by_pass = true;
+    changed
+}
+
 /// Per-function analysis snapshots handed to a pass run.
 pub(crate) struct FunctionAnalyses {
     /// Shared alias analysis; provenance and address memos build lazily.
@@ -384,13 +441,13 @@ fn run_function_pass_cached(
     analyses: &mut ModuleAnalyses,
     module: &mut Module,
     func_id: FunctionId,
-    run: &mut impl FnMut(&mut Function, &FunctionAnalyses) -> bool,
+    run: &mut impl FnMut(FunctionId, &mut Function, &FunctionAnalyses) -> bool,
 ) -> bool {
     let bundle = analyses.bundle(func_id, &module.functions[func_id]);
     let func = &mut module.functions[func_id];
     let edges_before = cfg_edges(func);
     let insts_before = func.instructions.len();
-    let changed = run(func, &bundle);
+    let changed = run(func_id, func, &bundle);
     if changed {
         let (keep_alias, keep_cfg) = verified_preservation(func, &edges_before, insts_before);
         analyses.retain(func_id, keep_alias, keep_cfg);
@@ -50,7 +50,7 @@ use crate::{
 use alloy_primitives::U256;
 use solar_data_structures::{
     bit_set::{DenseBitSet, GrowableBitSet},
-    map::FxHashMap,
+    map::{FxHashMap, FxIndexMap},
 };
 use std::{cmp::Ordering, rc::Rc, sync::Arc};
 
@@ -161,7 +161,8 @@ type MemRangeKey = MemoryLocation;
 struct GlobalCseContext<'a> {
     dom_tree: &'a DominatorTree,
     inst_results: &'a FxHashMap<InstId, ValueId>,
-    block_clobbers: &'a FxHashMap<BlockId, Vec<Clobber>>,
+    /// Clobber summaries in block order, so that they are always applied in the same order.
+    block_clobbers: &'a FxIndexMap<BlockId, Vec<Clobber>>,
     reachability: &'a FxHashMap<BlockId, DenseBitSet<BlockId>>,
     replacements: &'a mut FxHashMap<ValueId, ValueId>,
     dead: &'a mut DenseBitSet<InstId>,
@@ -270,7 +271,7 @@ impl CommonSubexprEliminator {
         let block_clobbers = if has_path_sensitive_expr {
             self.block_clobber_summaries(func)
         } else {
-            FxHashMap::default()
+            FxIndexMap::default()
         };
         let empty_reachability = FxHashMap::default();
         let (dom_tree, reachability) = if block_clobbers.is_empty() {
@@ -505,9 +506,9 @@ impl CommonSubexprEliminator {
     }
 
     /// Returns the per-block invalidation summaries for blocks with clobbering effects.
-    fn block_clobber_summaries(&self, func: &Function) -> FxHashMap<BlockId, Vec<Clobber>> {
+    fn block_clobber_summaries(&self, func: &Function) -> FxIndexMap<BlockId, Vec<Clobber>> {
         let no_replacements = FxHashMap::default();
-        let mut summaries = FxHashMap::default();
+        let mut summaries = FxIndexMap::default();
         for (block_id, block) in func.blocks.iter_enumerated() {
             let mut clobbers = Vec::new();
             for &inst_id in &block.instructions {
@@ -7,7 +7,7 @@ use crate::{
     mir::{
         BlockId, Function, InstId, Module, Terminator, ValueId, utils::repair_reachability_phis,
     },
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_par},
 };
 use solar_data_structures::{bit_set::GrowableBitSet, map::FxHashMap};
 
@@ -21,11 +21,11 @@ impl MirPass for Dce {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| {
+        run_function_pass_par(gcx, module, analyses, |func| {
             let changed = DeadCodeEliminator::new().run_to_fixpoint(func) != 0;
             repair_reachability_phis(func);
             changed
@@ -0,0 +1,198 @@
+//! Merge structurally identical internal functions.
+//!
+//! Heavily templated code — library overloads, per-type helpers, modifiers
+//! applied to many functions — often lowers to internal functions whose MIR
+//! bodies are identical apart from their names and source locations. Each
+//! copy costs its full size in bytecode even though one would do.
+//!
+//! This pass fingerprints every internal function by its whole body:
+//! signature, attributes, frame size, values, instructions and blocks, with
+//! the name and per-instruction source provenance erased. Functions with the
+//! same fingerprint are interchangeable, so every `internal_call` and
+//! `tail_call` to a duplicate is redirected to the lowest-numbered copy, and
+//! function DCE drops the now-unreferenced duplicates.
+//!
+//! Callee references are part of the fingerprint, so redirecting calls can
+//! make two callers identical; the pass iterates until no new class forms.
+//! Recursive functions fingerprint their own id and are only merged when
+//! their callers are.
+
+use crate::{
+    mir::{Function, FunctionId, InstKind, Module, Terminator},
+    pass::MirPass,
+};
+use solar_data_structures::map::{FxHashMap, StdEntry};
+use std::hash::{Hash, Hasher};
+
+/// Identical function merging pass.
+pub(crate) struct DedupFunctions;
+
+impl MirPass for DedupFunctions {
+    fn name(&self) -> &'static str {
+        "dedup-functions"
+    }
+
+    fn run_pass(
+        &self,
+        _gcx: solar_sema::Gcx<'_>,
+        module: &mut Module,
+        _analyses: &mut crate::pass::ModuleAnalyses,
+    ) -> bool {
+        let mut cx = DedupFunctionsCx::default();
+        let changed = cx.run(module);
+        tracing::debug!(stats = ?cx.stats, "dedup-functions");
+        changed
+    }
+}
+
+/// Statistics from identical function merging.
+#[derive(Clone, Debug, Default)]
+struct DedupFunctionsStats {
+    /// Number of functions merged into an identical copy.
+    merged: usize,
+    /// Number of call and tail-call sites redirected.
+    calls_redirected: usize,
+}
+
+#[derive(Debug, Default)]
+struct DedupFunctionsCx {
+    stats: DedupFunctionsStats,
+    /// Duplicate function to the copy that replaces it.
+    replaced: FxHashMap<FunctionId, FunctionId>,
+}
+
+impl DedupFunctionsCx {
+    fn run(&mut self, module: &mut Module) -> bool {
+        loop {
+            let mut classes: FxHashMap<BodyKey<'_>, FunctionId> = FxHashMap::default();
+            let mut merges = Vec::new();
+            for (id, func) in module.functions.iter_enumerated() {
+                if self.replaced.contains_key(&id) {
+                    continue;
+                }
+                let Some(key) = body_key(func) else { continue };
+                match classes.entry(key) {
+                    StdEntry::Occupied(entry) => merges.push((id, *entry.get())),
+                    StdEntry::Vacant(entry) => {
+                        entry.insert(id);
+                    }
+                }
+            }
+            if merges.is_empty() {
+                break;
+            }
+
+            self.stats.merged += merges.len();
+            self.replaced.extend(merges);
+            self.redirect_calls(module);
+        }
+
+        self.stats.merged != 0
+    }
+
+    /// Points every call to a merged duplicate at its replacement.
+    fn redirect_calls(&mut self, module: &mut Module) {
+        for func in &mut module.functions {
+            for inst in &mut func.instructions {
+                if let InstKind::InternalCall { function, .. } = &mut inst.kind
+                    && let Some(replacement) = self.resolve(*function)
+                {
+                    *function = replacement;
+                    self.stats.calls_redirected += 1;
+                }
+            }
+            for block in &mut func.blocks {
+                if let Some(Terminator::TailCall { function, .. }) = &mut block.terminator
+                    && let Some(replacement) = self.resolve(*function)
+                {
+                    *function = replacement;
+                    self.stats.calls_redirected += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the surviving copy of `function` if it was merged away.
+    ///
+    /// A copy kept in one round can itself be merged in a later one, so this
+    /// follows the chain to its end.
+    fn resolve(&self, mut function: FunctionId) -> Option<FunctionId> {
+        let mut replacement = None;
+        while let Some(&next) = self.replaced.get(&function) {
+            function = next;
+            replacement = Some(next);
+        }
+        replacement
+    }
+}
+
+/// Returns the fingerprint of `func`, or `None` if it must keep its identity.
+///
+/// Entry points and externally visible functions are reached by selector or
+/// by the dispatcher rather than through call edges, and bodiless functions
+/// have nothing to compare.
+fn body_key(func: &Function) -> Option<BodyKey<'_>> {
+    if func.selector.is_some()
+        || func.is_public()
+        || func.attributes.is_constructor
+        || func.attributes.is_fallback
+        || func.attributes.is_receive
+        || func.blocks.iter().any(|block| block.terminator.is_none())
+    {
+        return None;
+    }
+
+    Some(BodyKey(func))
+}
+
+/// The structural fingerprint of a function body.
+///
+/// Compares and hashes everything that affects codegen, skipping the name,
+/// instruction metadata and block predecessors. Values, instructions and blocks
+/// are referenced by their index in the function's own arenas, so identical
+/// bodies are identical index for index.
+struct BodyKey<'a>(&'a Function);
+
+impl PartialEq for BodyKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.0, other.0);
+        a.profile_signature == b.profile_signature
+            && a.attributes == b.attributes
+            && a.params == b.params
+            && a.returns == b.returns
+            && a.internal_frame_size == b.internal_frame_size
+            && a.external_static_return_size == b.external_static_return_size
+            && a.values.raw == b.values.raw
+            && a.instructions.len() == b.instructions.len()
+            && std::iter::zip(&a.instructions, &b.instructions)
+                .all(|(a, b)| a.kind == b.kind && a.result_ty == b.result_ty)
+            && a.blocks.len() == b.blocks.len()
+            && std::iter::zip(&a.blocks, &b.blocks)
+                .all(|(a, b)| a.instructions == b.instructions && a.terminator == b.terminator)
+    }
+}
+
+impl Eq for BodyKey<'_> {}
+
+impl Hash for BodyKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let func = self.0;
+        func.profile_signature.hash(state);
+        func.attributes.hash(state);
+        func.params.hash(state);
+        func.returns.hash(state);
+        func.internal_frame_size.hash(state);
+        func.external_static_return_size.hash(state);
+        func.values.raw.hash(state);
+        state.write_usize(func.instructions.len());
+        for inst in &func.instructions {
+            inst.kind.hash(state);
+            inst.result_ty.hash(state);
+        }
+        state.write_usize(func.blocks.len());
+        for block in &func.blocks {
+            block.instructions.hash(state);
+            block.terminator.hash(state);
+        }
+    }
+}
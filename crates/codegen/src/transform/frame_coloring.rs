@@ -0,0 +1,372 @@
+//! Liveness-based reuse of compiler-local memory slots.
+//!
+//! Lowering hands every local its own word in the function's frame, bumping
+//! the frame for each declaration. Locals whose lifetimes never overlap can
+//! share a word, so functions with many short-lived locals reserve far more
+//! frame memory than they need, and every extra word is memory expansion paid
+//! on each call.
+//!
+//! This pass colors the interference graph of the slots frame-slot promotion
+//! could not turn into SSA values. A slot is live from a store to the last
+//! load reading it; two slots interfere when one is stored while the other is
+//! live. Slots are then greedily assigned the lowest word, among the words
+//! they already occupied, that no interfering slot holds, and frame words left
+//! unused at the top of the frame are released.
+//!
+//! Safety contract:
+//! - recolor only slots that frame-slot promotion considers private: every access is an exact
+//!   full-word `mload`/`mstore`, and nothing else may observe the word
+//! - slots live at function entry read memory the function did not write, so they keep their word
+//! - skip functions observing `gas` or `msize`
+
+use super::frame_promotion::{FrameSlotPromoter, PromotableSlot};
+use crate::{
+    analysis::{AliasAnalysis, CfgInfo},
+    memory::EvmMemoryLayout,
+    mir::{
+        BlockId, Function, Immediate, InstId, InstKind, Instruction, MirType, Module, Value,
+        ValueId,
+    },
+    pass::{MirPass, run_function_pass},
+};
+use alloy_primitives::U256;
+use solar_data_structures::{
+    bit_set::DenseBitSet,
+    index::{IndexVec, index_vec},
+    map::FxHashMap,
+};
+
+/// Function pass that shares frame words between non-interfering local slots.
+pub(crate) struct FrameSlotColoring;
+
+impl MirPass for FrameSlotColoring {
+    fn name(&self) -> &'static str {
+        "frame-slot-coloring"
+    }
+
+    fn run_pass(
+        &self,
+        _gcx: solar_sema::Gcx<'_>,
+        module: &mut Module,
+        analyses: &mut crate::pass::ModuleAnalyses,
+    ) -> bool {
+        let mut stats = FrameColoringStats::default();
+        let changed =
+            run_function_pass(module, analyses, |func, _| FrameSlotColorer::run(func, &mut stats));
+        tracing::debug!(?stats, "frame-slot-coloring");
+        changed
+    }
+}
+
+/// Statistics from frame slot coloring.
+#[derive(Clone, Copy, Debug, Default)]
+struct FrameColoringStats {
+    /// Number of slots moved into a word shared with another slot.
+    slots_recolored: usize,
+    /// Number of frame bytes released.
+    bytes_freed: u64,
+}
+
+/// One recolorable slot and the instructions accessing it.
+#[derive(Debug)]
+struct ColoredSlot {
+    slot: PromotableSlot,
+    /// Exact accesses as `(block, instruction)` pairs.
+    accesses: Vec<(BlockId, InstId)>,
+}
+
+impl ColoredSlot {
+    /// Returns the slot's word offset from the start of the frame.
+    const fn frame_offset(&self) -> u64 {
+        frame_offset(self.slot)
+    }
+}
+
+const fn frame_offset(slot: PromotableSlot) -> u64 {
+    match slot {
+        PromotableSlot::InternalFrame(offset) => offset,
+        PromotableSlot::ExternalLocal(addr) => addr - EvmMemoryLayout::HEAP_START,
+    }
+}
+
+const fn same_kind(a: PromotableSlot, b: PromotableSlot) -> bool {
+    matches!(
+        (a, b),
+        (PromotableSlot::InternalFrame(_), PromotableSlot::InternalFrame(_))
+            | (PromotableSlot::ExternalLocal(_), PromotableSlot::ExternalLocal(_))
+    )
+}
+
+/// Returns `slot` moved to the frame word at `offset`.
+const fn with_frame_offset(slot: PromotableSlot, offset: u64) -> PromotableSlot {
+    match slot {
+        PromotableSlot::InternalFrame(_) => PromotableSlot::InternalFrame(offset),
+        PromotableSlot::ExternalLocal(_) => {
+            PromotableSlot::ExternalLocal(EvmMemoryLayout::HEAP_START + offset)
+        }
+    }
+}
+
+struct FrameSlotColorer<'a> {
+    cfg: &'a CfgInfo,
+    slots: Vec<ColoredSlot>,
+    /// Slot index of each exact slot access.
+    access_slots: FxHashMap<InstId, usize>,
+}
+
+impl<'a> FrameSlotColorer<'a> {
+    fn run(func: &mut Function, stats: &mut FrameColoringStats) -> bool {
+        if FrameSlotPromoter::has_global_observation_barrier(func) {
+            return false;
+        }
+
+        let cfg = CfgInfo::new(func);
+        let aa = AliasAnalysis::new(func);
+        let colorer = FrameSlotColorer::new(func, &cfg, &aa);
+        if colorer.slots.len() < 2 {
+            return false;
+        }
+
+        let live_in = colorer.live_in(func);
+        let interference = colorer.interference(func, &live_in);
+        let colors = colorer.color(&interference, &live_in[BlockId::ENTRY]);
+        colorer.apply(func, &colors, stats)
+    }
+
+    fn new(func: &Function, cfg: &'a CfgInfo, aa: &AliasAnalysis) -> Self {
+        let mut accesses: FxHashMap<PromotableSlot, Vec<(BlockId, InstId)>> = FxHashMap::default();
+        for (block_id, block) in func.blocks.iter_enumerated() {
+            for &inst_id in &block.instructions {
+                let (InstKind::MLoad(addr) | InstKind::MStore(addr, _)) =
+                    func.instructions[inst_id].kind
+                else {
+                    continue;
+                };
+                if let Some(slot) = FrameSlotPromoter::promotable_slot(func, aa, addr) {
+                    accesses.entry(slot).or_default().push((block_id, inst_id));
+                }
+            }
+        }
+
+        let mut slots: Vec<ColoredSlot> = accesses
+            .into_iter()
+            .filter(|&(slot, _)| match slot {
+                PromotableSlot::InternalFrame(offset) => {
+                    FrameSlotPromoter::internal_frame_slot_safe(func, aa, offset)
+                }
+                PromotableSlot::ExternalLocal(addr) => {
+                    FrameSlotPromoter::external_local_slot_safe(func, aa, addr)
+                }
+            })
+            .map(|(slot, accesses)| ColoredSlot { slot, accesses })
+            .collect();
+        slots.sort_by_key(|slot| slot.slot);
+
+        let mut access_slots = FxHashMap::default();
+        for (index, slot) in slots.iter().enumerate() {
+            for &(_, inst_id) in &slot.accesses {
+                access_slots.insert(inst_id, index);
+            }
+        }
+        Self { cfg, slots, access_slots }
+    }
+
+    /// Computes the slots live at the entry of each block.
+    ///
+    /// A load is a use and a store a definition, so this is the usual backward
+    /// dataflow: `live_in(b) = gen(b) ∪ (live_out(b) − kill(b))`.
+    fn live_in(&self, func: &Function) -> IndexVec<BlockId, DenseBitSet<usize>> {
+        let slot_count = self.slots.len();
+        let empty = DenseBitSet::new_empty(slot_count);
+        let mut gen_sets = index_vec![empty.clone(); func.blocks.len()];
+        let mut kill_sets = index_vec![empty.clone(); func.blocks.len()];
+        for (block_id, block) in func.blocks.iter_enumerated() {
+            for &inst_id in &block.instructions {
+                let Some(&slot) = self.access_slots.get(&inst_id) else { continue };
+                match func.instructions[inst_id].kind {
+                    InstKind::MLoad(_) if !kill_sets[block_id].contains(slot) => {
+                        gen_sets[block_id].insert(slot);
+                    }
+                    InstKind::MStore(..) => {
+                        kill_sets[block_id].insert(slot);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut live_in = index_vec![empty; func.blocks.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in self.cfg.rpo().iter().rev() {
+                let mut live = self.live_out(block, &live_in);
+                live.subtract(&kill_sets[block]);
+                live.union(&gen_sets[block]);
+                if live != live_in[block] {
+                    live_in[block] = live;
+                    changed = true;
+                }
+            }
+        }
+        live_in
+    }
+
+    fn live_out(
+        &self,
+        block: BlockId,
+        live_in: &IndexVec<BlockId, DenseBitSet<usize>>,
+    ) -> DenseBitSet<usize> {
+        let mut live = DenseBitSet::new_empty(self.slots.len());
+        for &succ in self.cfg.successors(block) {
+            live.union(&live_in[succ]);
+        }
+        live
+    }
+
+    /// Builds the slot interference graph as one adjacency set per slot.
+    fn interference(
+        &self,
+        func: &Function,
+        live_in: &IndexVec<BlockId, DenseBitSet<usize>>,
+    ) -> Vec<DenseBitSet<usize>> {
+        let slot_count = self.slots.len();
+        let mut interference = vec![DenseBitSet::new_empty(slot_count); slot_count];
+        let mut add_edges = |slot: usize, live: &DenseBitSet<usize>| {
+            for other in live.iter().filter(|&other| other != slot) {
+                interference[slot].insert(other);
+                interference[other].insert(slot);
+            }
+        };
+
+        // Slots live on entry all hold whatever memory held before the call.
+        let entry = &live_in[BlockId::ENTRY];
+        for slot in entry.iter() {
+            add_edges(slot, entry);
+        }
+
+        for &block in self.cfg.rpo() {
+            let mut live = self.live_out(block, live_in);
+            for &inst_id in func.blocks[block].instructions.iter().rev() {
+                let Some(&slot) = self.access_slots.get(&inst_id) else { continue };
+                match func.instructions[inst_id].kind {
+                    InstKind::MStore(..) => {
+                        add_edges(slot, &live);
+                        live.remove(slot);
+                    }
+                    InstKind::MLoad(_) => {
+                        live.insert(slot);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        interference
+    }
+
+    /// Assigns each slot a frame word, reusing the lowest word of its kind
+    /// that no interfering slot already holds.
+    ///
+    /// Slots live on entry read memory this function did not write, so they
+    /// keep their own word and are colored first.
+    fn color(
+        &self,
+        interference: &[DenseBitSet<usize>],
+        entry_live: &DenseBitSet<usize>,
+    ) -> Vec<u64> {
+        let mut colors: Vec<Option<u64>> = vec![None; self.slots.len()];
+        for slot in entry_live.iter() {
+            colors[slot] = Some(self.slots[slot].frame_offset());
+        }
+        for slot in 0..self.slots.len() {
+            if colors[slot].is_some() {
+                continue;
+            }
+            // Slots are sorted, so candidates of the same kind come in word order.
+            let word = self
+                .slots
+                .iter()
+                .filter(|other| same_kind(other.slot, self.slots[slot].slot))
+                .map(ColoredSlot::frame_offset)
+                .find(|&word| {
+                    interference[slot].iter().all(|other| {
+                        colors[other] != Some(word)
+                            || !same_kind(self.slots[other].slot, self.slots[slot].slot)
+                    })
+                })
+                .expect("a slot interferes with fewer slots than its kind has words");
+            colors[slot] = Some(word);
+        }
+        colors.into_iter().map(|color| color.expect("every slot is colored")).collect()
+    }
+
+    /// Rewrites every access of a moved slot and releases unused frame words.
+    fn apply(&self, func: &mut Function, colors: &[u64], stats: &mut FrameColoringStats) -> bool {
+        let mut changed = false;
+        for (slot, &word) in self.slots.iter().zip(colors) {
+            if slot.frame_offset() == word {
+                continue;
+            }
+            let target = with_frame_offset(slot.slot, word);
+            // One address per block, defined before the first access it feeds.
+            let mut block_addrs = FxHashMap::default();
+            for &(block, inst_id) in &slot.accesses {
+                let addr = *block_addrs
+                    .entry(block)
+                    .or_insert_with(|| Self::slot_address(func, target, block, inst_id));
+                let (InstKind::MLoad(old) | InstKind::MStore(old, _)) =
+                    &mut func.instructions[inst_id].kind
+                else {
+                    unreachable!("slot accesses are loads and stores");
+                };
+                *old = addr;
+            }
+            stats.slots_recolored += 1;
+            changed = true;
+        }
+
+        // The words at the top of the frame that no slot holds any more can be
+        // released: no other access may observe a recolorable slot's word.
+        let mut held: Vec<u64> = colors.to_vec();
+        held.sort_unstable();
+        let mut words: Vec<u64> = self.slots.iter().map(ColoredSlot::frame_offset).collect();
+        words.sort_unstable();
+        let word_size = EvmMemoryLayout::WORD_SIZE;
+        while let Some(top) = func.internal_frame_size.checked_sub(word_size)
+            && words.binary_search(&top).is_ok()
+            && held.binary_search(&top).is_err()
+        {
+            func.internal_frame_size = top;
+            stats.bytes_freed += word_size;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Materializes the address of `slot` in `block`, before `inst_id`.
+    fn slot_address(
+        func: &mut Function,
+        slot: PromotableSlot,
+        block: BlockId,
+        inst_id: InstId,
+    ) -> ValueId {
+        match slot {
+            PromotableSlot::ExternalLocal(addr) => {
+                func.alloc_value(Value::Immediate(Immediate::uint256(U256::from(addr))))
+            }
+            PromotableSlot::InternalFrame(offset) => {
+                let addr_inst = func.alloc_inst(Instruction::new(
+                    InstKind::InternalFrameAddr(offset),
+                    Some(MirType::MemPtr),
+                ));
+                let instructions = &mut func.blocks[block].instructions;
+                let pos = instructions
+                    .iter()
+                    .position(|&inst| inst == inst_id)
+                    .expect("slot access is in its block");
+                instructions.insert(pos, addr_inst);
+                func.alloc_value(Value::Inst(addr_inst))
+            }
+        }
+    }
+}
@@ -24,7 +24,6 @@ use crate::{
 };
 use solar_data_structures::{
     bit_set::{DenseBitSet, GrowableBitSet},
-    index::{IndexVec, index_vec},
     map::FxHashMap,
 };
 
@@ -100,13 +99,13 @@ impl FramePromotionStats {
 
 /// Promotes non-escaping compiler-local slots to SSA values.
 #[derive(Debug, Default)]
-struct FrameSlotPromoter {
+pub(super) struct FrameSlotPromoter {
     stats: FramePromotionStats,
     summaries: Vec<PromotedSlotSummary>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum PromotableSlot {
+pub(super) enum PromotableSlot {
     InternalFrame(u64),
     ExternalLocal(u64),
 }
@@ -248,7 +247,7 @@ impl FrameSlotPromoter {
         self.stats
     }
 
-    fn has_global_observation_barrier(func: &Function) -> bool {
+    pub(super) fn has_global_observation_barrier(func: &Function) -> bool {
         func.blocks.iter().any(|block| {
             block.instructions.iter().any(|&inst_id| {
                 matches!(func.instructions[inst_id].kind, InstKind::Gas | InstKind::MSize)
@@ -308,7 +307,7 @@ impl FrameSlotPromoter {
         slots
     }
 
-    fn promotable_slot(
+    pub(super) fn promotable_slot(
         func: &Function,
         aa: &AliasAnalysis,
         value: ValueId,
@@ -346,7 +345,11 @@ impl FrameSlotPromoter {
         base.checked_add(func.value_u64(offset)?)
     }
 
-    fn external_local_slot_safe(func: &Function, aa: &AliasAnalysis, slot_addr: u64) -> bool {
+    pub(super) fn external_local_slot_safe(
+        func: &Function,
+        aa: &AliasAnalysis,
+        slot_addr: u64,
+    ) -> bool {
         if aa
             .memory_alias(
                 MemoryLocation::new(MemoryAddress::absolute(slot_addr), LocationSize::Const(32)),
@@ -381,7 +384,11 @@ impl FrameSlotPromoter {
         true
     }
 
-    fn internal_frame_slot_safe(func: &Function, aa: &AliasAnalysis, slot_offset: u64) -> bool {
+    pub(super) fn internal_frame_slot_safe(
+        func: &Function,
+        aa: &AliasAnalysis,
+        slot_offset: u64,
+    ) -> bool {
         for block in func.blocks.iter() {
             for &inst_id in &block.instructions {
                 if Self::inst_may_observe_internal_slot(
@@ -754,67 +761,6 @@ impl<'a> SlotSsaBuilder<'a> {
         live_in
     }
 
-    fn compute_phi_blocks(
-        &self,
-        func: &Function,
-        live_in: &DenseBitSet<BlockId>,
-    ) -> DenseBitSet<BlockId> {
-        let frontiers = self.compute_dominance_frontiers(func);
-        let mut phi_blocks = DenseBitSet::new_empty(func.blocks.len());
-        let mut worklist = sorted_blocks(&self.info.def_blocks);
-
-        while let Some(block) = worklist.pop() {
-            let Some(frontier) = frontiers.get(block) else { continue };
-            for &frontier_block in frontier {
-                if !live_in.contains(frontier_block) || !phi_blocks.insert(frontier_block) {
-                    continue;
-                }
-                worklist.push(frontier_block);
-            }
-        }
-
-        phi_blocks
-    }
-
-    fn compute_dominance_frontiers(&self, func: &Function) -> IndexVec<BlockId, Vec<BlockId>> {
-        let mut frontiers = index_vec![Vec::new(); func.blocks.len()];
-        for block in func.blocks.indices() {
-            if !self.cfg.is_reachable(block) {
-                continue;
-            }
-
-            let preds: Vec<_> = func.blocks[block]
-                .predecessors
-                .iter()
-                .copied()
-                .filter(|&pred| self.cfg.is_reachable(pred))
-                .collect();
-            if preds.len() < 2 {
-                continue;
-            }
-
-            let Some(idom) = self.cfg.dominators().idom(block) else { continue };
-            for mut runner in preds {
-                while runner != idom {
-                    if !frontiers[runner].contains(&block) {
-                        frontiers[runner].push(block);
-                    }
-
-                    let Some(next) = self.cfg.dominators().idom(runner) else { break };
-                    if next == runner {
-                        break;
-                    }
-                    runner = next;
-                }
-            }
-        }
-
-        for frontier in &mut frontiers {
-            frontier.sort_by_key(|block| block.index());
-        }
-        frontiers
-    }
-
     fn run(&mut self, func: &mut Function) -> bool {
         if self.rewrite_single_block(func) || self.failed {
             return !self.failed;
@@ -824,7 +770,8 @@ impl<'a> SlotSsaBuilder<'a> {
         }
 
         self.live_in = self.compute_live_in(func);
-        self.phi_blocks = self.compute_phi_blocks(func, &self.live_in);
+        self.phi_blocks =
+            self.cfg.phi_blocks(self.info.def_blocks.iter(), |block| self.live_in.contains(block));
         for block in sorted_blocks(&self.phi_blocks) {
             self.create_phi(func, block);
         }
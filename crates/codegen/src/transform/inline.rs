@@ -2,7 +2,14 @@
 //!
 //! This module inlines profitable MIR internal calls to remove their call
 //! protocol and expose further optimization opportunities.
+//!
+//! With `--profile-data`, function hit counts from execution traces bias the
+//! decisions: calls to hot functions are treated like calls inside loops, and
+//! shared functions that were never executed are kept out of line. The
+//! constant revert paths of hot callees do not count as code growth, since
+//! `outline-reverts` moves their clones back out of line.
 
+use super::outline_reverts::is_outlinable_revert;
 use crate::{
     analysis::LoopAnalyzer,
     memory::{EvmMemoryLayout, MemoryLayoutPolicy},
@@ -15,7 +22,7 @@ use crate::{
 use alloy_primitives::U256;
 use smallvec::SmallVec;
 use solar_data_structures::{bit_set::DenseBitSet, map::FxHashMap};
-use solar_sema::Gcx;
+use solar_sema::{Gcx, profile::ExecutionProfile};
 
 /// Module pass for metadata-backed MIR inlining.
 pub(crate) struct Inline;
@@ -36,7 +43,57 @@ impl MirPass for Inline {
         } else {
             MirInliner::default()
         };
-        inliner.run(module).inlined != 0
+        let stats = inliner.run(module, gcx.execution_profile());
+        tracing::debug!(?stats, "inlined MIR calls");
+        if gcx.sess.opts.unstable.inline_stats {
+            eprintln!("INLINE STATS {}\n{stats:#?}", module.name);
+        }
+        stats.inlined != 0
+    }
+}
+
+/// How often a function ran according to the execution profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Hotness {
+    /// The function is not in the profile.
+    Unknown,
+    /// The function was never executed.
+    Cold,
+    /// The function was executed at least `1 / HOT_FRACTION` as often as the hottest function in
+    /// the profile.
+    Hot,
+    /// The function was executed, but is not hot.
+    Warm,
+}
+
+impl Hotness {
+    /// A function is hot if it ran at least `1 / HOT_FRACTION` as often as the hottest function.
+    const HOT_FRACTION: u64 = 10;
+
+    /// Returns the hotness of every function in `module`.
+    ///
+    /// Functions lowered from Solidity are looked up by their source signature; functions parsed
+    /// from textual MIR by their name.
+    fn of_module(module: &Module, profile: Option<&ExecutionProfile>) -> Vec<Self> {
+        let Some(profile) = profile else {
+            return vec![Self::Unknown; module.functions.len()];
+        };
+        let contract = module.name.as_str();
+        module
+            .functions
+            .iter()
+            .map(|func| {
+                let signature = func.profile_signature.unwrap_or(func.name.name);
+                match profile.hits(contract, signature.as_str()) {
+                    None => Self::Unknown,
+                    Some(0) => Self::Cold,
+                    Some(hits) if hits.saturating_mul(Self::HOT_FRACTION) >= profile.max_hits() => {
+                        Self::Hot
+                    }
+                    Some(_) => Self::Warm,
+                }
+            })
+            .collect()
     }
 }
 
@@ -114,6 +171,13 @@ struct MirInlineStats {
     inlined: usize,
     /// Number of call sites skipped because the callee was not inlineable.
     skipped: usize,
+    /// Number of inlined call sites whose callee is hot in the execution profile.
+    profile_hot: usize,
+    /// Number of call sites kept out of line because the callee is cold in the execution profile.
+    profile_cold: usize,
+    /// Number of constant revert blocks cloned from hot callees. These are cold paths that
+    /// `outline-reverts` shares with the callee's own copy, so they do not count as code growth.
+    profile_outlined_reverts: usize,
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -124,6 +188,10 @@ struct MirInlineSummary {
     param_count: usize,
     estimated_code_size: usize,
     estimated_runtime_gas: u64,
+    /// Number of constant revert blocks that `outline-reverts` can share.
+    outlinable_revert_count: usize,
+    /// Estimated code size of the blocks counted in `outlinable_revert_count`.
+    outlinable_revert_code_size: usize,
     internal_frame_size: u64,
     has_internal_call: bool,
     has_phi: bool,
@@ -138,8 +206,8 @@ struct MirInlineSummary {
 }
 
 impl MirInliner {
-    /// Runs the inliner over the whole module.
-    fn run(&mut self, module: &mut Module) -> MirInlineStats {
+    /// Runs the inliner over the whole module, biased by the execution profile if given.
+    fn run(&mut self, module: &mut Module, profile: Option<&ExecutionProfile>) -> MirInlineStats {
         let mut stats = MirInlineStats::default();
 
         // A zero budget is an explicit off switch (used by `-O size`). Avoid
@@ -162,6 +230,7 @@ impl MirInliner {
 
         let mut call_counts = self.call_counts(module);
         let recursive_functions = self.recursive_functions(module);
+        let function_hotness = Hotness::of_module(module, profile);
 
         for caller_id in module.functions.indices().collect::<Vec<_>>() {
             let loop_depths = block_loop_depths(module.function(caller_id));
@@ -186,12 +255,16 @@ impl MirInliner {
                     s.instruction_count.saturating_sub(base_instructions)
                         > self.max_caller_inlined_instructions
                 });
+                let hotness = function_hotness[site.callee.index()];
                 if module_code_size >= self.max_module_code_size
                     || grew_too_much
                     || recursive_functions.contains(site.callee)
-                    || !self.is_inlineable(caller_id, site, summary, call_count)
+                    || !self.is_inlineable(caller_id, site, summary, call_count, hotness)
                 {
                     stats.skipped += 1;
+                    if hotness == Hotness::Cold {
+                        stats.profile_cold += 1;
+                    }
                     continue;
                 }
 
@@ -201,6 +274,12 @@ impl MirInliner {
                 let caller = module.function_mut(caller_id);
                 if inline_call(caller, site.block, site.inst_index, &callee) {
                     stats.inlined += 1;
+                    if hotness == Hotness::Hot {
+                        stats.profile_hot += 1;
+                        if call_count > 1 {
+                            stats.profile_outlined_reverts += summary.outlinable_revert_count;
+                        }
+                    }
                     let new_summary = summarize_function(module.function(caller_id));
                     module_code_size = module_code_size
                         .saturating_sub(old_size)
@@ -316,8 +395,11 @@ impl MirInliner {
         site: CallSite,
         summary: MirInlineSummary,
         call_count: usize,
+        hotness: Hotness,
     ) -> bool {
         let single_call = self.inline_single_call && call_count == 1;
+        // Profiled hot callees are treated like calls inside loops.
+        let hot = site.loop_depth > 0 || hotness == Hotness::Hot;
 
         // `no_inline` prevents cloning a shared helper into every caller; with
         // a single call site there is nothing to duplicate, and absorbing the
@@ -333,6 +415,13 @@ impl MirInliner {
             return false;
         }
 
+        // Cloning a shared callee that never ran only grows the code; keep it
+        // out of line. A single-call callee disappears after inlining, so it is
+        // still considered.
+        if hotness == Hotness::Cold && !single_call {
+            return false;
+        }
+
         if single_call {
             if summary.instruction_count > self.max_single_call_sanity_instructions {
                 return false;
@@ -349,7 +438,7 @@ impl MirInliner {
         // bytecode after inlining, so they are allowed through the normal
         // code-growth check below.
         if !single_call
-            && site.loop_depth == 0
+            && !hot
             && (summary.has_storage_write || summary.has_external_call || summary.has_log)
             && summary.estimated_code_size
                 > estimated_internal_call_code_size(site)
@@ -358,9 +447,13 @@ impl MirInliner {
             return false;
         }
 
-        let code_growth = estimated_inline_code_growth(summary, site, single_call);
-        let max_growth =
-            if site.loop_depth > 0 { self.max_hot_code_growth } else { self.max_cold_code_growth };
+        let mut code_growth = estimated_inline_code_growth(summary, site, single_call);
+        // The constant revert paths of a shared hot callee are cold: once cloned, `outline-reverts`
+        // folds each of them and the callee's own copy into one helper.
+        if hotness == Hotness::Hot && !single_call {
+            code_growth = code_growth.saturating_sub(summary.outlinable_revert_code_size);
+        }
+        let max_growth = if hot { self.max_hot_code_growth } else { self.max_cold_code_growth };
         if code_growth > max_growth {
             return false;
         }
@@ -394,7 +487,8 @@ fn summarize_function(func: &Function) -> MirInlineSummary {
         ..MirInlineSummary::default()
     };
 
-    for block in func.blocks.iter() {
+    for (block_id, block) in func.blocks.iter_enumerated() {
+        let block_start_size = summary.estimated_code_size;
         for &inst_id in &block.instructions {
             let kind = &func.instructions[inst_id].kind;
             summary.instruction_count += match kind {
@@ -458,6 +552,10 @@ fn summarize_function(func: &Function) -> MirInlineSummary {
             | None => summary.has_unsupported_terminator = true,
             Some(Terminator::Invalid) => {}
         }
+        if is_outlinable_revert(func, block_id) {
+            summary.outlinable_revert_count += 1;
+            summary.outlinable_revert_code_size += summary.estimated_code_size - block_start_size;
+        }
     }
 
     summary
@@ -556,6 +654,8 @@ fn estimate_inst_cost(kind: &InstKind) -> MirCost {
         InstKind::InternalFrameAddr(_) => (6, 3),
         // PUSH32 placeholder patched at deploy time.
         InstKind::LoadImmutable(_) => (3, 33),
+        // Push of the segment offset, resolved once the code is laid out.
+        InstKind::DataOffset(_) => (3, 3),
         InstKind::ExtCodeSize(..)
         | InstKind::ExtCodeHash(..)
         | InstKind::Balance(..)
@@ -930,6 +1030,7 @@ impl<'a> InlineCloner<'a> {
             }
             InstKind::CodeSize => InstKind::CodeSize,
             InstKind::LoadImmutable(offset) => InstKind::LoadImmutable(offset),
+            InstKind::DataOffset(data) => InstKind::DataOffset(data),
             InstKind::CodeCopy(a, b, c) => {
                 InstKind::CodeCopy(self.clone_value(a)?, self.clone_value(b)?, self.clone_value(c)?)
             }
@@ -16,7 +16,7 @@ use crate::{
         Function, Immediate, InstId, InstKind, MirType, Module, Terminator, Value, ValueId,
         utils as mir_utils,
     },
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_par},
     utils::evm_word,
 };
 use alloy_primitives::U256;
@@ -32,11 +32,11 @@ impl MirPass for InstSimplify {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| {
+        run_function_pass_par(gcx, module, analyses, |func| {
             InstSimplifier::new().run_to_fixpoint(func) != 0
         })
     }
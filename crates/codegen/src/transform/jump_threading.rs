@@ -19,9 +19,9 @@ use crate::{
         BlockId, Function, InstKind, Module, Terminator, Value, ValueId,
         utils::repair_reachability_phis,
     },
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_par},
 };
-use solar_data_structures::{bit_set::DenseBitSet, map::FxHashMap};
+use solar_data_structures::{bit_set::DenseBitSet, map::FxIndexMap};
 
 /// Function pass for jump threading.
 pub(crate) struct JumpThreading;
@@ -33,11 +33,11 @@ impl MirPass for JumpThreading {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| {
+        run_function_pass_par(gcx, module, analyses, |func| {
             JumpThreader::new().run_to_fixpoint(func).total_threaded() != 0
         })
     }
@@ -126,8 +126,8 @@ impl JumpThreader {
     }
 
     /// Finds blocks that only contain an unconditional jump (forwarder blocks).
-    fn find_forwarder_blocks(&self, func: &Function) -> FxHashMap<BlockId, BlockId> {
-        let mut forwarders = FxHashMap::default();
+    fn find_forwarder_blocks(&self, func: &Function) -> FxIndexMap<BlockId, BlockId> {
+        let mut forwarders = FxIndexMap::default();
 
         for (block_id, block) in func.blocks.iter_enumerated() {
             if block.predecessors.is_empty() {
@@ -155,10 +155,10 @@ impl JumpThreader {
     /// Resolves chains of forwarders to find the final target.
     fn resolve_final_targets(
         &self,
-        forwarders: &FxHashMap<BlockId, BlockId>,
+        forwarders: &FxIndexMap<BlockId, BlockId>,
         block_count: usize,
-    ) -> FxHashMap<BlockId, BlockId> {
-        let mut final_targets = FxHashMap::default();
+    ) -> FxIndexMap<BlockId, BlockId> {
+        let mut final_targets = FxIndexMap::default();
 
         for &block_id in forwarders.keys() {
             let final_target = self.follow_chain(block_id, forwarders, block_count);
@@ -174,7 +174,7 @@ impl JumpThreader {
     fn follow_chain(
         &self,
         start: BlockId,
-        forwarders: &FxHashMap<BlockId, BlockId>,
+        forwarders: &FxIndexMap<BlockId, BlockId>,
         block_count: usize,
     ) -> BlockId {
         let mut visited = DenseBitSet::new_empty(block_count);
@@ -191,7 +191,7 @@ impl JumpThreader {
     }
 
     /// Updates all terminators to use the final targets.
-    fn thread_jumps(&mut self, func: &mut Function, final_targets: &FxHashMap<BlockId, BlockId>) {
+    fn thread_jumps(&mut self, func: &mut Function, final_targets: &FxIndexMap<BlockId, BlockId>) {
         let block_ids: Vec<_> = func.blocks.indices().collect();
         for block_id in block_ids {
             let Some(mut term) = func.blocks[block_id].terminator.clone() else {
@@ -207,7 +207,7 @@ impl JumpThreader {
         &mut self,
         func: &Function,
         term: &mut Terminator,
-        final_targets: &FxHashMap<BlockId, BlockId>,
+        final_targets: &FxIndexMap<BlockId, BlockId>,
     ) {
         match term {
             Terminator::Jump(target) => {
@@ -268,7 +268,7 @@ impl JumpThreader {
     fn threaded_target(
         func: &Function,
         target: BlockId,
-        final_targets: &FxHashMap<BlockId, BlockId>,
+        final_targets: &FxIndexMap<BlockId, BlockId>,
     ) -> Option<BlockId> {
         let final_target = final_targets.get(&target).copied()?;
         (!func.block_has_phi(final_target)).then_some(final_target)
@@ -5,11 +5,15 @@
 //! block, before any operation can observe memory or gas. It also forwards
 //! same-block `mload` instructions from the latest exact-address `mstore` when
 //! no intervening operation can mutate memory.
+//!
+//! Across blocks, a trailing `mstore` is removed when the block's immediate
+//! post-dominator starts with an `mstore` to the same address and no block on
+//! the way there can observe memory.
 
 use crate::{
     analysis::{
         Access, AddressSpace, AliasAnalysis, CfgInfo, Location, LocationSize, MemoryAddress,
-        MemoryLocation,
+        MemoryBase, MemoryLocation,
     },
     memory::EvmMemoryLayout,
     mir::{
@@ -924,25 +928,24 @@ impl MemoryStoreEliminator {
             return;
         }
 
+        let cfg = self.cfg.as_ref().map_or_else(|| Rc::new(CfgInfo::new(func)), Rc::clone);
+        let post_dominators = cfg.post_dominators();
         let mut dead = DenseBitSet::new_empty(func.instructions.len());
 
-        for pred in func.blocks.indices() {
-            let Some(succ) = Self::single_jump_successor(func, pred) else {
+        for block in func.blocks.indices() {
+            let Some(post_dominator) = post_dominators.ipdom(block) else {
                 continue;
             };
-            if func.blocks[succ].predecessors.as_slice() != [pred] {
-                continue;
-            }
-
-            let Some((store, pred_key)) = self.last_cross_block_store_candidate(func, pred) else {
+            let Some((store, key)) = self.last_cross_block_store_candidate(func, block) else {
                 continue;
             };
-            let Some(succ_key) = self.first_cross_block_overwrite(func, succ) else {
+            if self.first_cross_block_overwrite(func, post_dominator) != Some(key) {
                 continue;
-            };
-            if pred_key == succ_key {
-                dead.insert(store);
             }
+            if self.memory_observed_before(func, &cfg, block, post_dominator, key) {
+                continue;
+            }
+            dead.insert(store);
         }
 
         if dead.is_empty() {
@@ -955,11 +958,48 @@ impl MemoryStoreEliminator {
         }
     }
 
-    fn single_jump_successor(func: &Function, block: BlockId) -> Option<BlockId> {
-        let Some(Terminator::Jump(target)) = func.blocks[block].terminator.as_ref() else {
-            return None;
-        };
-        Some(*target)
+    /// Returns true if memory may be observed on some path from the end of `block` to the
+    /// start of its immediate post-dominator `post_dominator`.
+    ///
+    /// A variable address is only followed over a direct edge: a block in between could sit in a
+    /// loop that recomputes it, so the overwrite would hit a different word.
+    fn memory_observed_before(
+        &self,
+        func: &Function,
+        cfg: &CfgInfo,
+        block: BlockId,
+        post_dominator: BlockId,
+        key: MemAddrKey,
+    ) -> bool {
+        let mut stack: Vec<BlockId> = cfg
+            .successors(block)
+            .iter()
+            .copied()
+            .filter(|&successor| successor != post_dominator)
+            .collect();
+        if !stack.is_empty()
+            && matches!(key.0.base, MemoryBase::Value(_) | MemoryBase::DynamicAllocation(_))
+        {
+            return true;
+        }
+
+        let mut visited = DenseBitSet::new_empty(func.blocks.len());
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if func.blocks[current]
+                .instructions
+                .iter()
+                .any(|&inst_id| self.cross_block_memory_barrier(func, inst_id))
+            {
+                return true;
+            }
+            stack.extend(
+                cfg.successors(current).iter().copied().filter(|&next| next != post_dominator),
+            );
+        }
+        false
     }
 
     fn last_cross_block_store_candidate(
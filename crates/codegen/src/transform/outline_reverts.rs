@@ -16,7 +16,7 @@
 //! MIR-dispatch and backend-dispatcher paths.
 
 use crate::{
-    mir::{Function, FunctionBuilder, InstKind, Module, Terminator, Value},
+    mir::{BlockId, Function, FunctionBuilder, InstKind, Module, Terminator, Value},
     pass::MirPass,
 };
 use alloy_primitives::U256;
@@ -123,6 +123,13 @@ impl OutlineRevertsCx {
 /// Below this estimated inline footprint the jump to a helper saves nothing.
 const MIN_OUTLINED_SIZE: usize = 12;
 
+/// Returns `true` if the block is a constant revert block that this pass outlines once its shape
+/// occurs at least twice in the module.
+pub(crate) fn is_outlinable_revert(func: &Function, block: BlockId) -> bool {
+    constant_revert_shape(func, block.index())
+        .is_some_and(|shape| estimated_inline_size(&shape) >= MIN_OUTLINED_SIZE)
+}
+
 /// Returns the block's shape when every instruction is a fully-constant
 /// `mstore` and the terminator is a fully-constant `revert`.
 fn constant_revert_shape(func: &Function, block_idx: usize) -> Option<RevertShape> {
@@ -36,7 +36,7 @@ use crate::{
         MemoryObjectKind, MemoryObjectLayout, MirType, Module, Terminator, Value, ValueId,
         utils::{repair_reachability_phis, split_edge},
     },
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_par},
 };
 use solar_data_structures::{
     bit_set::{DenseBitSet, GrowableBitSet},
@@ -54,11 +54,11 @@ impl MirPass for Pre {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| {
+        run_function_pass_par(gcx, module, analyses, |func| {
             PartialRedundancyEliminator::new().run(func).total() != 0
         })
     }
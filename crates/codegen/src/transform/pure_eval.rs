@@ -7,7 +7,7 @@
 
 use crate::{
     mir::{BlockId, Function, Immediate, InstKind, Module, Terminator, Value, ValueId},
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_par},
     utils::evm_word,
 };
 use alloy_primitives::U256;
@@ -23,11 +23,11 @@ impl MirPass for PureEval {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| {
+        run_function_pass_par(gcx, module, analyses, |func| {
             let changed = PureEvaluator::new().run(func).functions_folded != 0;
             let repaired = crate::mir::utils::repair_reachability_phis(func);
             changed || repaired
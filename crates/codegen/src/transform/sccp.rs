@@ -13,17 +13,25 @@
 //!
 //! After reaching a fixed point, the rewrite phase replaces constant values
 //! with immediates and rewrites branches with known-constant conditions.
+//!
+//! Runtime reads of immutables are constants too when the constructor always
+//! stages the same compile-time value for them (see [`KnownImmutables`]). With
+//! `--assume-ctor-args`, values staged directly from constructor parameters
+//! count as well.
 
 use crate::{
+    analysis::CallGraphInfo,
+    memory::EvmMemoryLayout,
     mir::{
-        BlockId, Function, Immediate, InstId, InstKind, MirType, Module, Terminator, Value,
-        ValueId,
+        BlockId, Function, FunctionId, Immediate, InstId, InstKind, MirType, Module, Terminator,
+        Value, ValueId,
         utils::{self as mir_utils, repair_reachability_phis},
     },
-    pass::{MirPass, run_function_pass},
+    pass::{MirPass, run_function_pass_with_ids},
     utils::evm_word,
 };
 use alloy_primitives::U256;
+use solar_config::CtorArgs;
 use solar_data_structures::{
     bit_set::DenseBitSet,
     index::{IndexVec, index_vec},
@@ -41,14 +49,188 @@ impl MirPass for Sccp {
 
     fn run_pass(
         &self,
-        _gcx: solar_sema::Gcx<'_>,
+        gcx: solar_sema::Gcx<'_>,
         module: &mut Module,
         analyses: &mut crate::pass::ModuleAnalyses,
     ) -> bool {
-        run_function_pass(module, analyses, |func, _| SccpCx::new().run(func) != 0)
+        let immutables = KnownImmutables::new(module, gcx.sess.opts.assume_ctor_args.as_ref());
+        run_function_pass_with_ids(module, analyses, |func_id, func, _| {
+            let mut cx = SccpCx::new();
+            if !immutables.constructor_context.contains(func_id) {
+                cx.immutables = Some(&immutables.values);
+            }
+            cx.run(func) != 0
+        })
+    }
+}
+
+/// Immutable words whose deployed value is fixed at compile time.
+///
+/// The constructor stages each immutable in a scratch word that is patched into
+/// the runtime code's placeholders when construction returns, so the deployed
+/// value is the last one stored there. Nothing else writes the staging area:
+/// the constructor heap starts above it. A word is known when every store to it
+/// writes the same constant and, unless that constant is zero (the value of a
+/// word that is never written), one of the stores runs on every path that
+/// completes construction.
+#[derive(Debug)]
+struct KnownImmutables {
+    /// Deployed value of each known word, by immutable byte offset.
+    values: FxHashMap<u32, U256>,
+    /// Functions that may run during construction. Their loads read the staged
+    /// word, which may not hold its final value yet, so they are never folded.
+    constructor_context: DenseBitSet<FunctionId>,
+}
+
+impl KnownImmutables {
+    fn new(module: &Module, assumed_args: Option<&CtorArgs>) -> Self {
+        let constructors: Vec<FunctionId> = module
+            .functions
+            .iter_enumerated()
+            .filter(|(_, func)| func.attributes.is_constructor)
+            .map(|(func_id, _)| func_id)
+            .collect();
+        let mut constructor_context =
+            CallGraphInfo::new(module).reachable_callees_from(constructors.iter().copied());
+        for &func_id in &constructors {
+            constructor_context.insert(func_id);
+        }
+        let values = match *constructors.as_slice() {
+            [ctor_id] => Self::staged_values(module, ctor_id, &constructor_context, assumed_args),
+            _ => FxHashMap::default(),
+        };
+        Self { values, constructor_context }
+    }
+
+    /// Collects the known words staged by constructor `ctor_id`.
+    fn staged_values(
+        module: &Module,
+        ctor_id: FunctionId,
+        constructor_context: &DenseBitSet<FunctionId>,
+        assumed_args: Option<&CtorArgs>,
+    ) -> FxHashMap<u32, U256> {
+        let data_len = module.immutable_data_len() as u64;
+        // The staged constant of each word, or `None` once two stores disagree
+        // or one stores an unknown value.
+        let mut staged: FxHashMap<u64, Option<U256>> = FxHashMap::default();
+        // Constructor blocks that store each word.
+        let mut store_blocks: FxHashMap<u64, DenseBitSet<BlockId>> = FxHashMap::default();
+        for func_id in constructor_context.iter() {
+            let func = &module.functions[func_id];
+            // Only the constructor's own parameters are the deployment arguments.
+            let assumed_args = assumed_args
+                .filter(|args| func_id == ctor_id && args.len_words() == func.params.len());
+            for (block_id, block) in func.blocks.iter_enumerated() {
+                for &inst_id in &block.instructions {
+                    let InstKind::MStore(addr, value) = func.instructions[inst_id].kind else {
+                        continue;
+                    };
+                    let Some(offset) = immediate_u256(func, addr)
+                        .and_then(|addr| {
+                            addr.checked_sub(U256::from(EvmMemoryLayout::IMMUTABLE_SCRATCH_BASE))
+                        })
+                        .and_then(|offset| u64::try_from(offset).ok())
+                        .filter(|&offset| offset < data_len)
+                    else {
+                        continue;
+                    };
+                    if offset % EvmMemoryLayout::WORD_SIZE != 0 {
+                        // A store straddling two words: give up on the module.
+                        return FxHashMap::default();
+                    }
+                    let value = staged_constant(func, value, assumed_args);
+                    staged
+                        .entry(offset)
+                        .and_modify(|staged| {
+                            if *staged != value {
+                                *staged = None;
+                            }
+                        })
+                        .or_insert(value);
+                    if func_id == ctor_id {
+                        store_blocks
+                            .entry(offset)
+                            .or_insert_with(|| DenseBitSet::new_empty(func.blocks.len()))
+                            .insert(block_id);
+                    }
+                }
+            }
+        }
+
+        let ctor = &module.functions[ctor_id];
+        let mut values = FxHashMap::default();
+        for (offset, value) in staged {
+            let Some(value) = value else { continue };
+            if !value.is_zero()
+                && !store_blocks.get(&offset).is_some_and(|blocks| on_every_exit_path(ctor, blocks))
+            {
+                continue;
+            }
+            values.insert(offset as u32, value);
+        }
+        values
+    }
+}
+
+/// Returns the value of an immediate operand.
+fn immediate_u256(func: &Function, value: ValueId) -> Option<U256> {
+    match func.value(value) {
+        Value::Immediate(imm) => imm.as_u256(),
+        _ => None,
+    }
+}
+
+/// Returns the compile-time value of a word staged by the constructor.
+///
+/// Parameters take their assumed argument word, provided it is a value the ABI
+/// decoder would accept for the parameter's type.
+fn staged_constant(
+    func: &Function,
+    value: ValueId,
+    assumed_args: Option<&CtorArgs>,
+) -> Option<U256> {
+    match func.value(value) {
+        Value::Immediate(imm) => imm.as_u256(),
+        &Value::Arg { index, ty } => {
+            let word = U256::from_be_bytes(*assumed_args?.word(index as usize)?);
+            abi_word_fits(ty, word).then_some(word)
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether `word` is a valid ABI encoding of a value of type `ty`.
+fn abi_word_fits(ty: MirType, word: U256) -> bool {
+    match ty {
+        MirType::Bool => word <= U256::from(1),
+        MirType::UInt(bits) => fits_unsigned(word, bits),
+        MirType::Int(bits) => fits_signed(word, bits),
+        MirType::Address => fits_unsigned(word, 160),
+        MirType::FixedBytes(bytes) => word.trailing_zeros() >= 256 - 8 * usize::from(bytes),
+        _ => false,
     }
 }
 
+/// Returns whether every path from the entry to a successful exit passes
+/// through one of `blocks`.
+fn on_every_exit_path(func: &Function, blocks: &DenseBitSet<BlockId>) -> bool {
+    let mut visited = DenseBitSet::new_empty(func.blocks.len());
+    let mut worklist = vec![BlockId::ENTRY];
+    while let Some(block_id) = worklist.pop() {
+        if blocks.contains(block_id) || !visited.insert(block_id) {
+            continue;
+        }
+        let Some(term) = &func.blocks[block_id].terminator else { return false };
+        let successors = term.successors();
+        if successors.is_empty() && !matches!(term, Terminator::Revert { .. } | Terminator::Invalid)
+        {
+            return false;
+        }
+        worklist.extend(successors);
+    }
+    true
+}
+
 /// Lattice element for a single SSA value.
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum LatticeValue {
@@ -89,16 +271,21 @@ struct SccpStats {
     switches_folded: usize,
     /// Number of unreachable blocks emptied and marked invalid.
     blocks_invalidated: usize,
+    /// Number of immutable loads replaced with their deployed value.
+    immutables_folded: usize,
 }
 
 /// Sparse Conditional Constant Propagation pass.
 #[derive(Debug, Default)]
-struct SccpCx {
+struct SccpCx<'a> {
     /// Statistics from the last run.
     stats: SccpStats,
+    /// Known deployed values of immutable words, when this function only runs
+    /// after construction.
+    immutables: Option<&'a FxHashMap<u32, U256>>,
 }
 
-impl SccpCx {
+impl SccpCx<'_> {
     /// Creates a new SCCP pass.
     fn new() -> Self {
         Self::default()
@@ -459,6 +646,13 @@ impl SccpCx {
                 }
             }
 
+            InstKind::LoadImmutable(offset) => {
+                match self.immutables.and_then(|immutables| immutables.get(offset)) {
+                    Some(&value) => LatticeValue::Constant(value),
+                    None => LatticeValue::Bottom,
+                }
+            }
+
             // Everything else (memory, storage, calls, environment, etc.) is
             // conservatively overdefined — we can't evaluate them at compile time.
             _ => LatticeValue::Bottom,
@@ -651,6 +845,9 @@ impl SccpCx {
                 const_values.insert(vid, imm_vid);
                 dead_insts.insert(inst_id);
                 self.stats.constants_folded += 1;
+                if matches!(func.instructions[inst_id].kind, InstKind::LoadImmutable(_)) {
+                    self.stats.immutables_folded += 1;
+                }
             }
         }
 
@@ -53,7 +53,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let profile = out_dir.rsplit(std::path::MAIN_SEPARATOR).nth(3).unwrap();
 
     let mut cargo_features = env::var("VERGEN_CARGO_FEATURES").unwrap();
-    let ignore = ["clap", "version", "serde"];
+    let ignore = ["clap", "version", "serde", "toml"];
     for feature in ignore {
         cargo_features = cargo_features
             .replace(&format!(",{feature}"), "")
@@ -72,6 +72,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("cargo:rustc-env=LONG_VERSION{i}={line}");
     }
 
+    let host = env::var("HOST").unwrap();
+    let target = vergen_env("VERGEN_CARGO_TARGET_TRIPLE");
+    for (key, value) in [
+        ("COMMIT_SHA", sha.as_str()),
+        ("BUILD_TIMESTAMP", &timestamp),
+        ("BUILD_FEATURES", &cargo_features),
+        ("BUILD_PROFILE", profile),
+        ("HOST", &host),
+        ("TARGET", &target),
+    ] {
+        println!("cargo:rustc-env=BUILD_INFO_{key}={value}");
+    }
+
     let solc_version = solc_version();
     let solc_compat_version = format!("{solc_version}+commit.{sha_short}.solar.{version}");
 
@@ -0,0 +1,397 @@
+//! Configuration files and environment variables.
+
+use crate::{
+    CompileOpts, CompilerOutput, ErrorFormat, EvmVersion, ImportRemapping, Language, LintOpts,
+    OptimizationMode,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The name of the Solar configuration file.
+const CONFIG_FILE: &str = "solar.toml";
+
+/// The name of the Foundry configuration file, of which only the `[solar]` table is read.
+const FOUNDRY_CONFIG_FILE: &str = "foundry.toml";
+
+/// The prefix of the environment variables that set options.
+const ENV_PREFIX: &str = "SOLAR_";
+
+/// Options that can be set in a configuration file or through environment variables.
+///
+/// Options are layered, from lowest to highest priority: the defaults, the configuration file,
+/// the environment, and the command line. Unset options do not override lower layers.
+///
+/// ```toml
+/// evm-version = "cancun"
+/// include-paths = ["lib"]
+/// remappings = ["@openzeppelin/=lib/openzeppelin-contracts/"]
+/// emit = ["abi", "bin"]
+///
+/// [lints]
+/// deny = ["reentrancy"]
+/// ```
+///
+/// Each option can also be set with an environment variable named after its key, such as
+/// `SOLAR_EVM_VERSION=cancun`. Lists are comma separated.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FileOpts {
+    /// See [`CompileOpts::base_path`].
+    pub base_path: Option<PathBuf>,
+    /// See [`CompileOpts::include_paths`].
+    pub include_paths: Option<Vec<PathBuf>>,
+    /// See [`CompileOpts::import_remappings`].
+    pub remappings: Option<Vec<ImportRemapping>>,
+    /// See [`CompileOpts::allow_paths`].
+    pub allow_paths: Option<Vec<PathBuf>>,
+    /// See [`CompileOpts::language`].
+    pub language: Option<Language>,
+    /// See [`CompileOpts::threads`].
+    pub threads: Option<usize>,
+    /// See [`CompileOpts::evm_version`].
+    pub evm_version: Option<EvmVersion>,
+    /// See [`CompileOpts::optimization`].
+    pub optimization: Option<OptimizationMode>,
+    /// See [`CompileOpts::out_dir`].
+    pub out_dir: Option<PathBuf>,
+    /// See [`CompileOpts::emit`].
+    pub emit: Option<Vec<CompilerOutput>>,
+    /// See [`CompileOpts::error_format`].
+    pub error_format: Option<ErrorFormat>,
+    /// See [`CompileOpts::no_warnings`].
+    pub no_warnings: Option<bool>,
+    /// See [`CompileOpts::allow`].
+    pub allow: Option<Vec<String>>,
+    /// See [`CompileOpts::lints`].
+    pub lints: Option<LintOpts>,
+}
+
+/// The parts of a Foundry configuration file that are read by Solar.
+///
+/// Only the `[solar]` table is read, so that Foundry's own tables, such as its `[lint]` table, do
+/// not have to be understood by Solar. Unknown tables, and unknown keys of the `[solar]` table, are
+/// ignored; the `[solar.lints]` table is parsed as strictly as the `[lints]` table of `solar.toml`.
+#[derive(Deserialize)]
+struct FoundryFile {
+    solar: Option<FoundrySolarTable>,
+}
+
+/// The `[solar]` table of a Foundry configuration file.
+#[derive(Deserialize)]
+struct FoundrySolarTable {
+    lints: Option<LintOpts>,
+}
+
+impl FileOpts {
+    /// Loads the configuration file at `path`.
+    ///
+    /// Only the `[solar.lints]` table is read from a `foundry.toml` file.
+    #[allow(clippy::disallowed_methods)] // Configuration files are not source files.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let src = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read `{}`: {e}", path.display()))?;
+        let foundry = path.file_name().is_some_and(|name| name == FOUNDRY_CONFIG_FILE);
+        Self::parse(&src, foundry).map_err(|e| format!("failed to parse `{}`: {e}", path.display()))
+    }
+
+    /// Loads the configuration files in `dir`.
+    ///
+    /// Options are read from `solar.toml`. If it has no `[lints]` table, the `[solar.lints]` table
+    /// of `foundry.toml` is used instead.
+    pub fn discover(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(CONFIG_FILE);
+        let mut opts = if path.is_file() { Self::load(&path)? } else { Self::default() };
+        let path = dir.join(FOUNDRY_CONFIG_FILE);
+        if opts.lints.is_none() && path.is_file() {
+            opts.lints = Self::load(&path)?.lints;
+        }
+        Ok(opts)
+    }
+
+    /// Parses a configuration file.
+    pub fn parse(src: &str, foundry: bool) -> Result<Self, toml_edit::de::Error> {
+        if foundry {
+            let FoundryFile { solar } = toml_edit::de::from_str(src)?;
+            Ok(Self { lints: solar.and_then(|solar| solar.lints), ..Default::default() })
+        } else {
+            toml_edit::de::from_str(src)
+        }
+    }
+
+    /// Reads the options from the `SOLAR_*` environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    fn from_vars(get: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let var = |key: &str| {
+            let key = format!("{ENV_PREFIX}{key}");
+            get(&key).filter(|value| !value.is_empty()).map(|value| (key, value))
+        };
+        Ok(Self {
+            base_path: parse_var(var("BASE_PATH"))?,
+            include_paths: parse_list_var(var("INCLUDE_PATHS"))?,
+            remappings: parse_list_var(var("REMAPPINGS"))?,
+            allow_paths: parse_list_var(var("ALLOW_PATHS"))?,
+            language: parse_var(var("LANGUAGE"))?,
+            threads: parse_var(var("THREADS"))?,
+            evm_version: parse_var(var("EVM_VERSION"))?,
+            optimization: parse_var(var("OPTIMIZATION"))?,
+            out_dir: parse_var(var("OUT_DIR"))?,
+            emit: parse_list_var(var("EMIT"))?,
+            error_format: parse_var(var("ERROR_FORMAT"))?,
+            no_warnings: parse_var(var("NO_WARNINGS"))?,
+            allow: parse_list_var(var("ALLOW"))?,
+            lints: None,
+        })
+    }
+
+    /// Returns the options of `self`, overridden by the options that are set in `other`.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            base_path: other.base_path.or(self.base_path),
+            include_paths: other.include_paths.or(self.include_paths),
+            remappings: other.remappings.or(self.remappings),
+            allow_paths: other.allow_paths.or(self.allow_paths),
+            language: other.language.or(self.language),
+            threads: other.threads.or(self.threads),
+            evm_version: other.evm_version.or(self.evm_version),
+            optimization: other.optimization.or(self.optimization),
+            out_dir: other.out_dir.or(self.out_dir),
+            emit: other.emit.or(self.emit),
+            error_format: other.error_format.or(self.error_format),
+            no_warnings: other.no_warnings.or(self.no_warnings),
+            allow: other.allow.or(self.allow),
+            lints: other.lints.or(self.lints),
+        }
+    }
+
+    /// Sets the options of `opts` that are set in `self`.
+    ///
+    /// `is_set` is called with the name of each option, as in [`CompileOpts`], and returns
+    /// whether it was explicitly set on the command line, in which case it is not overridden.
+    pub fn apply(self, opts: &mut CompileOpts, is_set: impl Fn(&str) -> bool) {
+        fn set<T>(dst: &mut T, value: Option<T>, is_set: bool) {
+            if let Some(value) = value
+                && !is_set
+            {
+                *dst = value;
+            }
+        }
+
+        set(&mut opts.base_path, self.base_path.map(Some), is_set("base_path"));
+        set(&mut opts.include_paths, self.include_paths, is_set("include_paths"));
+        // Remappings are given as inputs, and parsed in `CompileOpts::finish`.
+        let remappings_set = opts.input.iter().any(|input| input.contains('='));
+        set(&mut opts.import_remappings, self.remappings, remappings_set);
+        set(&mut opts.allow_paths, self.allow_paths, is_set("allow_paths"));
+        set(&mut opts.language, self.language, is_set("language"));
+        set(&mut opts.threads, self.threads.map(Into::into), is_set("threads"));
+        set(&mut opts.evm_version, self.evm_version, is_set("evm_version"));
+        set(&mut opts.optimization, self.optimization, is_set("optimization"));
+        set(&mut opts.out_dir, self.out_dir.map(Some), is_set("out_dir"));
+        set(&mut opts.emit, self.emit, is_set("emit"));
+        set(&mut opts.error_format, self.error_format, is_set("error_format"));
+        set(&mut opts.no_warnings, self.no_warnings, is_set("no_warnings"));
+        set(&mut opts.allow, self.allow, is_set("allow"));
+        set(&mut opts.lints, self.lints.map(Some), opts.lints.is_some());
+    }
+
+    /// Returns the effective options of `opts`.
+    pub fn from_opts(opts: &CompileOpts) -> Self {
+        Self {
+            base_path: opts.base_path.clone(),
+            include_paths: Some(opts.include_paths.clone()),
+            remappings: Some(opts.import_remappings.clone()),
+            allow_paths: Some(opts.allow_paths.clone()),
+            language: Some(opts.language),
+            threads: Some(opts.threads().get()),
+            evm_version: Some(opts.evm_version),
+            optimization: Some(opts.optimization),
+            out_dir: opts.out_dir.clone(),
+            emit: Some(opts.emit.clone()),
+            error_format: Some(opts.error_format),
+            no_warnings: Some(opts.no_warnings),
+            allow: Some(opts.allow.clone()),
+            lints: opts.lints.clone(),
+        }
+    }
+
+    /// Formats the options as a configuration file.
+    pub fn to_toml(&self) -> String {
+        toml_edit::ser::to_string_pretty(self).expect("failed to serialize options")
+    }
+}
+
+fn parse_var<T: FromStr>(var: Option<(String, String)>) -> Result<Option<T>, String>
+where
+    T::Err: fmt::Display,
+{
+    var.map(|(key, value)| {
+        value.parse().map_err(|e| format!("invalid value {value:?} for `{key}`: {e}"))
+    })
+    .transpose()
+}
+
+fn parse_list_var<T: FromStr>(var: Option<(String, String)>) -> Result<Option<Vec<T>>, String>
+where
+    T::Err: fmt::Display,
+{
+    var.map(|(key, value)| {
+        value
+            .split(',')
+            .map(|item| {
+                item.trim().parse().map_err(|e| format!("invalid value {item:?} for `{key}`: {e}"))
+            })
+            .collect()
+    })
+    .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let src = r#"
+evm-version = "cancun"
+include-paths = ["lib"]
+remappings = ["a/=b/"]
+
+[lints]
+max-line-length = 100
+"#;
+        let opts = FileOpts::parse(src, false).unwrap();
+        assert_eq!(opts.evm_version, Some(EvmVersion::Cancun));
+        assert_eq!(opts.include_paths, Some(vec![PathBuf::from("lib")]));
+        assert_eq!(opts.remappings.unwrap()[0].to_string(), "a/=b/");
+        assert_eq!(opts.lints.unwrap().max_line_length, 100);
+        assert_eq!(opts.threads, None);
+
+        assert!(FileOpts::parse("evm-verison = \"cancun\"\n", false).is_err());
+        assert!(FileOpts::parse("[lints]\nmax-line-lenght = 100\n", false).is_err());
+        assert!(FileOpts::parse("remappings = [\"a/\"]\n", false).is_err());
+    }
+
+    #[test]
+    fn foundry_lints_table() {
+        let src = r#"
+[profile.default]
+src = "src"
+
+[solar.lints]
+deny = ["reentrancy"]
+max-line-length = 100
+"#;
+        let lints = FileOpts::parse(src, true).unwrap().lints.unwrap();
+        assert_eq!(lints.deny, ["reentrancy"]);
+        assert_eq!(lints.max_line_length, 100);
+        assert_eq!(lints.allowed_numbers, LintOpts::default().allowed_numbers);
+
+        let opts = FileOpts::parse("[profile.default]\nsrc = \"src\"\n", true).unwrap();
+        assert_eq!(opts.lints, None);
+        let opts = FileOpts::parse("[solar]\nversion = \"0.1\"\n", true).unwrap();
+        assert_eq!(opts.lints, None);
+        assert!(FileOpts::parse("[solar.lints]\nmax-line-lenght = 100\n", true).is_err());
+    }
+
+    #[test]
+    fn discover_foundry_project() {
+        // Foundry's own lint options and other tables are not read.
+        let foundry = r#"
+[profile.default]
+src = "src"
+out = "out"
+libs = ["lib"]
+solc = "0.8.30"
+optimizer = true
+optimizer_runs = 200
+via_ir = false
+remappings = ["@openzeppelin/=lib/openzeppelin-contracts/"]
+fs_permissions = [{ access = "read", path = "./" }]
+
+[profile.ci.fuzz]
+runs = 10000
+
+[lint]
+severity = ["high", "med"]
+exclude_lints = ["mixed-case-function"]
+ignore = ["src/Vendored.sol"]
+lint_on_build = false
+
+[fmt]
+line_length = 100
+tab_width = 4
+
+[rpc_endpoints]
+mainnet = "${MAINNET_RPC_URL}"
+
+[etherscan]
+mainnet = { key = "${ETHERSCAN_API_KEY}" }
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, src: &str| std::fs::write(dir.path().join(name), src).unwrap();
+
+        write(FOUNDRY_CONFIG_FILE, foundry);
+        let opts = FileOpts::discover(dir.path()).unwrap();
+        assert_eq!(opts.lints, None);
+        assert!(opts.remappings.is_none());
+
+        write(
+            FOUNDRY_CONFIG_FILE,
+            &format!("{foundry}\n[solar.lints]\nallow = [\"magic-number\"]\n"),
+        );
+        let opts = FileOpts::discover(dir.path()).unwrap();
+        assert_eq!(opts.lints.unwrap().allow, ["magic-number"]);
+
+        // The `[lints]` table of `solar.toml` takes precedence.
+        write(CONFIG_FILE, "evm-version = \"paris\"\n\n[lints]\ndeny = [\"reentrancy\"]\n");
+        let opts = FileOpts::discover(dir.path()).unwrap();
+        assert_eq!(opts.evm_version, Some(EvmVersion::Paris));
+        let lints = opts.lints.unwrap();
+        assert_eq!(lints.deny, ["reentrancy"]);
+        assert!(lints.allow.is_empty());
+    }
+
+    #[test]
+    fn env() {
+        let vars = |key: &str| match key {
+            "SOLAR_EVM_VERSION" => Some("paris".to_string()),
+            "SOLAR_EMIT" => Some("abi, bin".to_string()),
+            "SOLAR_NO_WARNINGS" => Some("true".to_string()),
+            "SOLAR_THREADS" => Some(String::new()),
+            _ => None,
+        };
+        let opts = FileOpts::from_vars(vars).unwrap();
+        assert_eq!(opts.evm_version, Some(EvmVersion::Paris));
+        assert_eq!(opts.emit, Some(vec![CompilerOutput::Abi, CompilerOutput::Bin]));
+        assert_eq!(opts.no_warnings, Some(true));
+        assert_eq!(opts.threads, None);
+
+        let err = FileOpts::from_vars(|_| Some("x".to_string())).unwrap_err();
+        assert!(err.contains("`SOLAR_"), "{err}");
+    }
+
+    #[test]
+    fn layers() {
+        let file =
+            FileOpts::parse("evm-version = \"paris\"\nthreads = 3\nallow = [\"a\"]\n", false)
+                .unwrap();
+        let env = FileOpts { threads: Some(2), ..Default::default() };
+        let mut opts = CompileOpts { allow: vec!["b".into()], ..Default::default() };
+        file.merge(env).apply(&mut opts, |name| name == "allow");
+        assert_eq!(opts.evm_version, EvmVersion::Paris);
+        assert_eq!(opts.threads().get(), 2);
+        assert_eq!(opts.allow, ["b"]);
+
+        let printed = FileOpts::from_opts(&opts).to_toml();
+        let parsed = FileOpts::parse(&printed, false).unwrap();
+        assert_eq!(parsed.evm_version, Some(EvmVersion::Paris));
+        assert_eq!(parsed.allow, Some(vec!["b".to_string()]));
+    }
+}
@@ -13,6 +13,14 @@ mod macros;
 mod opts;
 pub use opts::{CompileOpts, UnstableOpts};
 
+mod lints;
+pub use lints::LintOpts;
+
+#[cfg(feature = "toml")]
+mod file;
+#[cfg(feature = "toml")]
+pub use file::FileOpts;
+
 mod lsp;
 pub use lsp::LspArgs;
 
@@ -45,10 +53,19 @@ str_enum! {
         ///
         /// Includes lowering all ASTs to a single HIR, inheritance resolution, name resolution, basic type checking.
         Lowering,
+        /// Type checking.
+        ///
+        /// Includes computing the types of all items and expressions, computing ABI, NatSpec validation.
+        Typeck,
         /// Analysis.
         ///
-        /// Includes type checking, computing ABI, static analysis.
+        /// Includes static analysis and lints.
         Analysis,
+        /// Code generation.
+        ///
+        /// Includes lowering to MIR, optimizations, and EVM bytecode generation. This stage is driven by
+        /// the caller, and stopping after it skips emitting any output.
+        Codegen,
     }
 }
 
@@ -188,6 +205,14 @@ str_enum! {
         BinRuntime,
         /// Function signature hashes.
         Hashes,
+        /// Contract metadata, as a JSON string.
+        Metadata,
+        /// Storage layout of state variables.
+        StorageLayout,
+        /// Makefile-style list of the source files that each input transitively imports.
+        Deps,
+        /// JSON object mapping each input to the source files it transitively imports.
+        DepsJson,
     }
 }
 
@@ -255,6 +280,20 @@ str_enum! {
     }
 }
 
+str_enum! {
+    /// How to print the program as Solidity source. See `-Zunpretty`.
+    #[strum(serialize_all = "kebab-case")]
+    #[non_exhaustive]
+    pub enum Unpretty {
+        /// The AST, as parsed.
+        Ast,
+        /// The HIR, after name resolution and lowering.
+        Hir,
+        /// The HIR with loops desugared and modifiers inlined into the functions that use them.
+        Desugared,
+    }
+}
+
 str_enum! {
     /// How errors and other messages are produced.
     #[derive(Default)]
@@ -332,6 +371,21 @@ impl fmt::Debug for ImportRemapping {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ImportRemapping {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ImportRemapping {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e| serde::de::Error::custom(format!("invalid remapping {s:?}: {e}")))
+    }
+}
+
 /// A single library address for linking: `[path.sol:]Name=0xADDRESS`.
 #[derive(Clone, PartialEq, Eq)]
 pub struct LibraryAddress {
@@ -392,6 +446,158 @@ impl fmt::Debug for LibraryAddress {
     }
 }
 
+/// ABI-encoded constructor arguments, given as hex: `[0x]HEX`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct CtorArgs(pub Vec<u8>);
+
+impl std::str::FromStr for CtorArgs {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let digits = s.strip_prefix("0x").unwrap_or(s).as_bytes();
+        if digits.len() % 2 != 0 {
+            return Err("odd number of hexadecimal digits");
+        }
+        let nibble = |b: u8| match b {
+            b'0'..=b'9' => Ok(b - b'0'),
+            b'a'..=b'f' => Ok(b - b'a' + 10),
+            b'A'..=b'F' => Ok(b - b'A' + 10),
+            _ => Err("arguments contain a non-hexadecimal digit"),
+        };
+        let bytes = digits
+            .chunks_exact(2)
+            .map(|pair| Ok((nibble(pair[0])? << 4) | nibble(pair[1])?))
+            .collect::<Result<Vec<_>, _>>()?;
+        if bytes.len() % 32 != 0 {
+            return Err("ABI-encoded arguments must be a whole number of 32-byte words");
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl CtorArgs {
+    /// Returns the `index`th 32-byte head word, if present.
+    pub fn word(&self, index: usize) -> Option<&[u8; 32]> {
+        self.0.chunks_exact(32).nth(index).map(|word| word.try_into().unwrap())
+    }
+
+    /// Returns the number of 32-byte words.
+    pub fn len_words(&self) -> usize {
+        self.0.len() / 32
+    }
+}
+
+impl fmt::Display for CtorArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        for b in &self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CtorArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CtorArgs({self})")
+    }
+}
+
+/// A definition for conditional compilation directives: `KEY[=VALUE]`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CfgDefinition {
+    /// The key.
+    pub key: String,
+    /// The value, if any.
+    pub value: Option<String>,
+}
+
+impl std::str::FromStr for CfgDefinition {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+            None => (s.trim(), None),
+        };
+        if key.is_empty() {
+            return Err("empty key");
+        }
+        if key.contains(char::is_whitespace) {
+            return Err("key contains whitespace");
+        }
+        Ok(Self { key: key.into(), value })
+    }
+}
+
+impl CfgDefinition {
+    /// Returns whether this definition satisfies the condition `key`, or `key=value` if `value` is
+    /// given.
+    pub fn matches(&self, key: &str, value: Option<&str>) -> bool {
+        self.key == key && value.is_none_or(|value| self.value.as_deref() == Some(value))
+    }
+}
+
+impl fmt::Display for CfgDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key)?;
+        if let Some(value) = &self.value {
+            write!(f, "={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CfgDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CfgDefinition({self})")
+    }
+}
+
+/// A Solidity version: `MAJOR[.MINOR[.PATCH]]`.
+///
+/// Missing components are zero.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SolidityVersion {
+    /// The major version.
+    pub major: u64,
+    /// The minor version.
+    pub minor: u64,
+    /// The patch version.
+    pub patch: u64,
+}
+
+impl std::str::FromStr for SolidityVersion {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('.');
+        // `split` always yields at least one part, so only the minor and patch versions can be
+        // missing.
+        let mut next = || {
+            parts.next().map_or(Ok(0), |part| part.parse().map_err(|_| "invalid version number"))
+        };
+        let version = Self { major: next()?, minor: next()?, patch: next()? };
+        if parts.next().is_some() {
+            return Err("expected at most 3 version components");
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for SolidityVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl fmt::Debug for SolidityVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SolidityVersion({self})")
+    }
+}
+
 /// Wrapper to implement a custom `Default` value for the number of threads.
 #[derive(Clone, Copy)]
 pub struct Threads(pub NonZeroUsize);
@@ -475,4 +681,15 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn solidity_version() {
+        let parse = |s: &str| s.parse::<SolidityVersion>().map(|v| v.to_string());
+        assert_eq!(parse("0").unwrap(), "0.0.0");
+        assert_eq!(parse("0.7").unwrap(), "0.7.0");
+        assert_eq!(parse("0.8.28").unwrap(), "0.8.28");
+        assert!(parse("").is_err());
+        assert!(parse("0.8.x").is_err());
+        assert!(parse("0.8.0.1").is_err());
+    }
 }
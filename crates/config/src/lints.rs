@@ -0,0 +1,51 @@
+/// Lint configuration.
+///
+/// This is read from the `[lints]` table of a `solar.toml` file, or from the `[solar.lints]`
+/// table of a `foundry.toml` file.
+///
+/// ```toml
+/// [lints]
+/// allow = ["magic-number"]
+/// deny = ["reentrancy"]
+/// max-line-length = 100
+/// allowed-numbers = [0, 1, 10]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case", deny_unknown_fields)
+)]
+pub struct LintOpts {
+    /// Lint codes to not report.
+    pub allow: Vec<String>,
+    /// Lint codes to report as errors instead of warnings.
+    pub deny: Vec<String>,
+    /// The maximum number of characters in a line, for the `max-line-length` lint.
+    pub max_line_length: usize,
+    /// The numbers that are not reported by the `magic-number` lint.
+    pub allowed_numbers: Vec<u64>,
+}
+
+impl Default for LintOpts {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            max_line_length: 120,
+            allowed_numbers: vec![0, 1],
+        }
+    }
+}
+
+impl LintOpts {
+    /// Returns `true` if the lint with the given code is allowed.
+    pub fn is_allowed(&self, code: &str) -> bool {
+        self.allow.iter().any(|allowed| allowed == code)
+    }
+
+    /// Returns `true` if the lint with the given code is denied.
+    pub fn is_denied(&self, code: &str) -> bool {
+        self.deny.iter().any(|denied| denied == code)
+    }
+}
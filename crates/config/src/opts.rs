@@ -1,8 +1,9 @@
 //! Solar CLI arguments.
 
 use crate::{
-    ColorChoice, CompilerOutput, CompilerStage, Dump, ErrorFormat, EvmVersion, HumanEmitterKind,
-    ImportRemapping, Language, LibraryAddress, OptimizationMode, Threads,
+    CfgDefinition, ColorChoice, CompilerOutput, CompilerStage, CtorArgs, Dump, ErrorFormat,
+    EvmVersion, HumanEmitterKind, ImportRemapping, Language, LibraryAddress, LintOpts,
+    OptimizationMode, SolidityVersion, Threads, Unpretty,
 };
 use std::{num::NonZeroUsize, path::PathBuf};
 
@@ -24,7 +25,8 @@ use clap::{Parser, ValueHint};
 pub struct CompileOpts {
     /// Files to compile, or import remappings.
     ///
-    /// `-` specifies standard input.
+    /// `-` specifies standard input. Multiple named sources can be passed through standard input as
+    /// a JSON object of the form `{"sources": {"a.sol": "..."}}`.
     ///
     /// In Standard JSON mode, no input or `-` reads from standard input; otherwise, exactly one
     /// input file may be specified.
@@ -59,15 +61,43 @@ pub struct CompileOpts {
         feature = "clap",
         arg(
             help_heading = "Input options",
-            name = "include-path",
+            id = "include_paths",
             value_name = "INCLUDE_PATH",
-            long,
+            long = "include-path",
             short = 'I',
             alias = "import-path",
             value_hint = ValueHint::DirPath,
         )
     )]
     pub include_paths: Vec<PathBuf>,
+    /// Remap source path prefixes in diagnostics and artifacts, as `FROM=TO`.
+    ///
+    /// Can be used multiple times. When several prefixes match, the last one wins.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            help_heading = "Input options",
+            long = "remap-path-prefix",
+            value_name = "FROM=TO",
+            value_parser = parse_path_prefix_remapping,
+        )
+    )]
+    pub remap_path_prefix: Vec<(PathBuf, PathBuf)>,
+    /// Read the source at `PATH` from `FILE` instead, as `PATH=FILE`.
+    ///
+    /// Analyzes a project as if `FILE` had been saved to `PATH`, such as an editor's unsaved
+    /// buffer written to a temporary file. `PATH` does not need to exist. Can be used multiple
+    /// times.
+    #[cfg_attr(
+        feature = "clap",
+        arg(
+            help_heading = "Input options",
+            long = "overlay",
+            value_name = "PATH=FILE",
+            value_parser = parse_overlay,
+        )
+    )]
+    pub overlays: Vec<(PathBuf, PathBuf)>,
     /// Allow a given path for imports.
     #[cfg_attr(
         feature = "clap",
@@ -79,6 +109,14 @@ pub struct CompileOpts {
         )
     )]
     pub allow_paths: Vec<PathBuf>,
+    /// Define a key for conditional compilation directives, as `KEY` or `KEY=VALUE`.
+    ///
+    /// Can be used multiple times. Only has an effect with `-Zcfg-directives`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(help_heading = "Input options", long, value_name = "KEY[=VALUE]")
+    )]
+    pub cfg: Vec<CfgDefinition>,
     /// Source code language. Only Solidity is currently implemented.
     #[cfg_attr(
         feature = "clap",
@@ -98,6 +136,24 @@ pub struct CompileOpts {
     /// MIR optimization objective.
     #[cfg_attr(feature = "clap", arg(short = 'O', long = "optimize", value_enum, default_value_t))]
     pub optimization: OptimizationMode,
+    /// Execution profile used to guide MIR inlining.
+    ///
+    /// A JSON object mapping qualified function signatures to the number of times they were
+    /// executed, such as `{"Token.transfer(address,uint256)": 1200, "Token.rescue()": 0}`. Calls to
+    /// hot functions are inlined more aggressively, and functions that were never executed are
+    /// kept out of line.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, global = true)
+    )]
+    pub profile_data: Option<PathBuf>,
+    /// ABI-encoded constructor arguments to assume at deployment, as hex.
+    ///
+    /// Immutables assigned directly from a constructor parameter are then folded into the runtime
+    /// code as constants, like immutables initialized with a constant expression. The contract must
+    /// be deployed with exactly these arguments.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "HEX"))]
+    pub assume_ctor_args: Option<CtorArgs>,
 
     /// Library addresses for linking, as `LibraryName=0xADDRESS`.
     ///
@@ -112,16 +168,65 @@ pub struct CompileOpts {
     pub libraries: Vec<LibraryAddress>,
 
     /// Directory to write output files.
+    ///
+    /// JSON outputs are written to `combined.json`. With `--emit=abi`, the ABI of each contract is
+    /// also written to `<file name>/<contract name>.abi`.
     #[cfg_attr(feature = "clap", arg(long, value_hint = ValueHint::DirPath))]
     pub out_dir: Option<PathBuf>,
+    /// Directory to write per-contract JSON artifacts to, in Foundry's `out/` layout.
+    ///
+    /// Each contract is written to `<file name>/<contract name>.json`. Contracts whose artifact
+    /// paths would collide are nested under their full source path instead. Artifacts have the
+    /// same format as Foundry's, so that `forge` can read them.
+    #[cfg_attr(feature = "clap", arg(long, short = 'o', value_hint = ValueHint::DirPath))]
+    pub out: Option<PathBuf>,
+    /// Overwrite existing artifacts in the `--out` directory.
+    #[cfg_attr(feature = "clap", arg(long, requires = "out"))]
+    pub overwrite: bool,
     /// Comma separated list of types of output for the compiler to emit.
     #[cfg_attr(feature = "clap", arg(long, value_delimiter = ','))]
     pub emit: Vec<CompilerOutput>,
+    /// Only compile and emit the given contract, as `Name` or `path:Name`.
+    ///
+    /// Only the sources of the selected contracts and the files they import are analyzed.
+    /// Contracts that a selected contract creates with `new` are still compiled. Can be used
+    /// multiple times.
+    #[cfg_attr(feature = "clap", arg(long = "contract", value_name = "CONTRACT"))]
+    pub contracts: Vec<String>,
+
+    /// Load a compiler plugin from the given dynamic library or `.wasm` module.
+    ///
+    /// Plugins run after analysis. Can be used multiple times.
+    #[cfg_attr(feature = "clap", arg(long = "plugin", value_name = "PATH", value_hint = ValueHint::FilePath))]
+    pub plugins: Vec<PathBuf>,
 
     /// Switch to Standard JSON input/output mode.
     #[cfg_attr(feature = "clap", arg(long))]
     pub standard_json: bool,
 
+    /// Produce byte-identical output across machines.
+    ///
+    /// Strips the base path and include paths from source paths in diagnostics and artifacts, and
+    /// sets the timing fields of `--events` to zero.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub reproducible: bool,
+
+    /// Write structured compiler events to the given file as JSON Lines.
+    ///
+    /// Event names and fields are stable. See `solar_interface::events` for the full list.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "PATH", value_hint = ValueHint::FilePath))]
+    pub events: Option<PathBuf>,
+
+    /// Read options from the given configuration file.
+    ///
+    /// Defaults to `solar.toml` in the current directory. Options set on the command line take
+    /// precedence over `SOLAR_*` environment variables, which take precedence over the file.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "PATH", value_hint = ValueHint::FilePath))]
+    pub config: Option<PathBuf>,
+    /// Print the effective configuration and exit.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub print_config: bool,
+
     /// Coloring.
     #[cfg_attr(
         feature = "clap",
@@ -131,6 +236,11 @@ pub struct CompileOpts {
     /// Use verbose output.
     #[cfg_attr(feature = "clap", arg(help_heading = "Display options", long, short))]
     pub verbose: bool,
+    /// Show the number of files processed while compiling.
+    ///
+    /// Written to stderr, and disabled when stderr is not a terminal.
+    #[cfg_attr(feature = "clap", arg(help_heading = "Display options", long))]
+    pub progress: bool,
     /// Pretty-print JSON output.
     ///
     /// Does not include errors. See `--pretty-json-err`.
@@ -151,6 +261,7 @@ pub struct CompileOpts {
         arg(
             help_heading = "Display options",
             long,
+            alias = "ui",
             value_name = "VALUE",
             value_enum,
             default_value_t
@@ -173,6 +284,12 @@ pub struct CompileOpts {
     )]
     pub allow: Vec<String>,
 
+    /// Lint configuration, read from the `[lints]` table of a configuration file.
+    ///
+    /// Lints are run if this is set, or with `-Zlints`.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    pub lints: Option<LintOpts>,
+
     /// Unstable flags. WARNING: these are completely unstable, and may change at any time.
     ///
     /// See `-Zhelp` for more details.
@@ -200,6 +317,24 @@ impl CompileOpts {
         self.threads.0
     }
 
+    /// Sets the options that are not set on the command line from the configuration file and the
+    /// environment.
+    ///
+    /// `is_set` returns whether the option with the given name was set on the command line. See
+    /// [`FileOpts`](crate::FileOpts) for more details.
+    ///
+    /// This must be called before [`finish`](Self::finish), so that the loaded options are
+    /// validated together with the command line.
+    #[cfg(feature = "toml")]
+    pub fn load_config(&mut self, is_set: impl Fn(&str) -> bool) -> Result<(), String> {
+        let file = match &self.config {
+            Some(path) => crate::FileOpts::load(path)?,
+            None => crate::FileOpts::discover(".".as_ref())?,
+        };
+        file.merge(crate::FileOpts::from_env()?).apply(self, is_set);
+        Ok(())
+    }
+
     /// Finishes argument parsing.
     #[cfg(feature = "clap")]
     pub fn finish(&mut self) -> Result<(), clap::Error> {
@@ -220,7 +355,7 @@ impl CompileOpts {
             }
         }
 
-        self.import_remappings = self
+        let import_remappings = self
             .input
             .iter()
             .filter(|s| s.contains('='))
@@ -232,7 +367,11 @@ impl CompileOpts {
                     )
                 })
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
+        // Remappings on the command line replace the ones from the configuration file.
+        if !import_remappings.is_empty() {
+            self.import_remappings = import_remappings;
+        }
         self.input.retain(|s| !s.contains('='));
 
         if !self._unstable.is_empty() {
@@ -339,6 +478,14 @@ pub struct UnstableOpts {
     )]
     pub dump: Option<Dump>,
 
+    /// Print the program as reconstructed Solidity source and stop.
+    ///
+    /// `ast` prints the source as parsed. `hir` prints it after lowering, with getters
+    /// synthesized and loops lowered. `desugared` additionally inlines modifiers and prints loops
+    /// as `while (true)`.
+    #[cfg_attr(feature = "clap", arg(long, require_equals = true, value_name = "MODE"))]
+    pub unpretty: Option<Unpretty>,
+
     /// Print AST stats.
     #[cfg_attr(feature = "clap", arg(long))]
     pub ast_stats: bool,
@@ -355,6 +502,10 @@ pub struct UnstableOpts {
     #[cfg_attr(feature = "clap", arg(long))]
     pub span_visitor: bool,
 
+    /// Validate AST span invariants after parsing.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub validate_spans: bool,
+
     /// Print contracts' max storage sizes.
     #[cfg_attr(feature = "clap", arg(long))]
     pub print_max_storage_sizes: bool,
@@ -375,6 +526,10 @@ pub struct UnstableOpts {
     #[cfg_attr(feature = "clap", arg(long))]
     pub time_passes: bool,
 
+    /// Print MIR inlining stats for each module.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub inline_stats: bool,
+
     /// Enable the experimental EVM code generator (MIR lowering and backend).
     ///
     /// Off by default: MIR and EVM IR dumps and bytecode output are only produced
@@ -383,6 +538,87 @@ pub struct UnstableOpts {
     #[cfg_attr(feature = "clap", arg(long))]
     pub codegen: bool,
 
+    /// Try to minimize the crashing source file when the compiler panics.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub minimize_ice: bool,
+
+    /// Set the directory to write internal compiler error reports to.
+    ///
+    /// Defaults to the system's temporary directory.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, require_equals = true, value_name = "DIR", value_hint = ValueHint::DirPath)
+    )]
+    pub ice_dir: Option<PathBuf>,
+
+    /// Set the maximum recursion depth of the parser, AST lowering, and type resolution.
+    ///
+    /// Defaults to 128. Deeper nesting is reported as an error.
+    #[cfg_attr(feature = "clap", arg(long, require_equals = true, value_name = "LIMIT"))]
+    pub recursion_limit: Option<usize>,
+
+    /// Run lints for code that compiles but is likely unintended.
+    ///
+    /// Lint warnings have named codes, such as `self-assignment`, which can be passed to `--allow`.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub lints: bool,
+
+    /// Comma separated list of lint codes to report as errors instead of warnings.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, require_equals = true, value_name = "CODE", value_delimiter = ',')
+    )]
+    pub deny_lints: Vec<String>,
+
+    /// Log the MIR lowering decisions made for a contract.
+    ///
+    /// Storage and immutable slots, local memory slots, inlining, and function frame layouts are
+    /// logged as `debug` events under the `solar_codegen::lower` target, in addition to any
+    /// `RUST_LOG` directives.
+    #[cfg_attr(feature = "clap", arg(long, require_equals = true, value_name = "CONTRACT"))]
+    pub trace_lowering: Option<String>,
+
+    /// Include or exclude source regions marked with `// solar:if` directives.
+    ///
+    /// A region starts at a `// solar:if KEY` or `// solar:if KEY=VALUE` line comment, may contain
+    /// a `// solar:else` branch, and ends at `// solar:endif`. Its condition holds if the key is
+    /// defined with `--cfg`, with the given value if any.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub cfg_directives: bool,
+
+    /// Skip a `#!` interpreter line at the start of each source file.
+    ///
+    /// The line is treated like a comment, so that executable scripts can be compiled as is.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub shebang: bool,
+
+    /// Panic when the Nth error is emitted, to test internal compiler error handling.
+    #[cfg_attr(feature = "clap", arg(long, require_equals = true, value_name = "N"))]
+    pub treat_err_as_bug: Option<NonZeroUsize>,
+
+    /// Exit with code 1 on any compilation error, instead of the code of the failed stage.
+    ///
+    /// The test suite uses this to infer the exit status from error annotations.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub uniform_exit_code: bool,
+
+    /// Translate diagnostic messages with the message catalog at `PATH`.
+    ///
+    /// Each line of the catalog is an `id = template` entry, where the template may reference the
+    /// message arguments as `{name}`. Messages without an entry are emitted untranslated.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, require_equals = true, value_name = "PATH", value_hint = ValueHint::FilePath)
+    )]
+    pub message_catalog: Option<PathBuf>,
+
+    /// Check `pragma solidity` version requirements against the given Solidity version.
+    ///
+    /// Source files whose requirements are not satisfied by `VERSION` are rejected. Missing minor
+    /// and patch versions are zero, so `0.7` is checked as `0.7.0`.
+    #[cfg_attr(feature = "clap", arg(long, require_equals = true, value_name = "VERSION"))]
+    pub solidity_version: Option<SolidityVersion>,
+
     // ----------------------------------------
     // Please add new options above this point!
     // ----------------------------------------
@@ -404,6 +640,36 @@ pub struct UnstableOpts {
     pub test_value: Option<usize>,
 }
 
+impl UnstableOpts {
+    /// The default value of [`recursion_limit`](Self::recursion_limit).
+    pub const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+    /// Returns the maximum recursion depth of the parser, AST lowering, and type resolution.
+    #[inline]
+    pub fn recursion_limit(&self) -> usize {
+        self.recursion_limit.unwrap_or(Self::DEFAULT_RECURSION_LIMIT)
+    }
+}
+
+#[cfg(feature = "clap")]
+fn parse_overlay(s: &str) -> Result<(PathBuf, PathBuf), &'static str> {
+    let Some((path, file)) = s.rsplit_once('=') else {
+        return Err("expected `PATH=FILE`");
+    };
+    if path.is_empty() || file.is_empty() {
+        return Err("expected `PATH=FILE`");
+    }
+    Ok((path.into(), file.into()))
+}
+
+#[cfg(feature = "clap")]
+fn parse_path_prefix_remapping(s: &str) -> Result<(PathBuf, PathBuf), &'static str> {
+    let Some((from, to)) = s.rsplit_once('=') else {
+        return Err("expected `FROM=TO`");
+    };
+    Ok((from.into(), to.into()))
+}
+
 #[cfg(all(test, feature = "clap"))]
 mod tests {
     use super::*;
@@ -429,6 +695,58 @@ mod tests {
         assert_eq!(opts.allow, ["1234", "5678"]);
     }
 
+    #[test]
+    fn configured_remappings() {
+        let mut opts = CompileOpts::try_parse_from(["solar", "a.sol"]).unwrap();
+        opts.import_remappings = vec!["a/=b/".parse().unwrap()];
+        opts.finish().unwrap();
+        assert_eq!(opts.import_remappings[0].to_string(), "a/=b/");
+
+        // Remappings on the command line replace the configured ones.
+        let mut opts = CompileOpts::try_parse_from(["solar", "c/=d/", "a.sol"]).unwrap();
+        opts.import_remappings = vec!["a/=b/".parse().unwrap()];
+        opts.finish().unwrap();
+        assert_eq!(opts.import_remappings.len(), 1);
+        assert_eq!(opts.import_remappings[0].to_string(), "c/=d/");
+        assert_eq!(opts.input, ["a.sol"]);
+    }
+
+    #[test]
+    fn remap_path_prefix() {
+        let opts = CompileOpts::try_parse_from([
+            "solar",
+            "--remap-path-prefix",
+            "/home/user/project=/src",
+            "--remap-path-prefix=/a=b=",
+            "a.sol",
+        ])
+        .unwrap();
+        assert_eq!(
+            opts.remap_path_prefix,
+            [("/home/user/project".into(), "/src".into()), ("/a=b".into(), PathBuf::new())]
+        );
+
+        assert!(
+            CompileOpts::try_parse_from(["solar", "--remap-path-prefix", "a", "a.sol"]).is_err()
+        );
+    }
+
+    #[test]
+    fn overlay() {
+        let opts = CompileOpts::try_parse_from([
+            "solar",
+            "--overlay",
+            "src/A.sol=/tmp/buffer.sol",
+            "src/A.sol",
+        ])
+        .unwrap();
+        assert_eq!(opts.overlays, [("src/A.sol".into(), "/tmp/buffer.sol".into())]);
+
+        for arg in ["a", "=b", "a="] {
+            assert!(CompileOpts::try_parse_from(["solar", "--overlay", arg, "a.sol"]).is_err());
+        }
+    }
+
     #[test]
     fn standard_json_input() {
         let mut opts = CompileOpts::try_parse_from(["solar", "--standard-json"]).unwrap();
@@ -42,3 +42,79 @@ pub fn version() -> &'static str {
 fn solc_wrapper() -> bool {
     std::env::var_os("SOLC_WRAPPER").is_some_and(|x| x == "1")
 }
+
+/// Information about the build of the compiler, for bug reports and artifact metadata.
+#[cfg(feature = "version")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BuildInfo {
+    /// The semver version.
+    pub version: &'static str,
+    /// The full git commit hash.
+    pub commit_sha: &'static str,
+    /// The build timestamp, in RFC 3339 format.
+    pub build_timestamp: &'static str,
+    /// The Cargo profile that the compiler was built with.
+    pub build_profile: &'static str,
+    /// The enabled Cargo features.
+    pub features: Vec<&'static str>,
+    /// The target triple of the machine that built the compiler.
+    pub host: &'static str,
+    /// The target triple that the compiler was built for.
+    pub target: &'static str,
+    /// The global allocator, if known.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub allocator: Option<&'static str>,
+}
+
+#[cfg(feature = "version")]
+impl Default for BuildInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "version")]
+impl BuildInfo {
+    /// Returns the build information of the current compiler.
+    ///
+    /// The allocator is set by the binary, see [`with_allocator`](Self::with_allocator).
+    pub fn new() -> Self {
+        let features = env!("BUILD_INFO_BUILD_FEATURES");
+        Self {
+            version: SEMVER_VERSION,
+            commit_sha: env!("BUILD_INFO_COMMIT_SHA"),
+            build_timestamp: env!("BUILD_INFO_BUILD_TIMESTAMP"),
+            build_profile: env!("BUILD_INFO_BUILD_PROFILE"),
+            features: features.split(',').filter(|feature| !feature.is_empty()).collect(),
+            host: env!("BUILD_INFO_HOST"),
+            target: env!("BUILD_INFO_TARGET"),
+            allocator: None,
+        }
+    }
+
+    /// Sets the name of the global allocator.
+    pub fn with_allocator(mut self, allocator: &'static str) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+}
+
+/// Formats the build information as `Key: Value` lines, after the short version.
+#[cfg(feature = "version")]
+impl std::fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "solar {SHORT_VERSION}")?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Commit SHA: {}", self.commit_sha)?;
+        writeln!(f, "Build Timestamp: {}", self.build_timestamp)?;
+        writeln!(f, "Build Features: {}", self.features.join(","))?;
+        writeln!(f, "Build Profile: {}", self.build_profile)?;
+        writeln!(f, "Host: {}", self.host)?;
+        write!(f, "Target: {}", self.target)?;
+        if let Some(allocator) = self.allocator {
+            write!(f, "\nAllocator: {allocator}")?;
+        }
+        Ok(())
+    }
+}
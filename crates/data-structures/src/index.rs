@@ -1,11 +1,131 @@
 //! Index types. See [`::oxc_index`].
+//!
+//! Index types are created with [`newtype_index!`](crate::newtype_index), and are used to key
+//! dense [`IndexVec`]s. Besides the compiler's own data structures, this makes them a good fit for
+//! side tables: per-item data that is computed by an analysis and stored outside of the HIR.
+//!
+//! Prefer a [`DenseMap`] sized to the number of IDs over a hash map keyed by ID when most IDs
+//! have a value, and use [`IndexVecExt`] to fill it, possibly in parallel.
+//!
+//! With the `serde` feature enabled, index types are serialized as their `u32` value.
+//!
+//! # Examples
+//!
+//! ```
+//! use solar_data_structures::{
+//!     index::{DenseMap, Idx, IndexVecExt},
+//!     newtype_index,
+//! };
+//!
+//! newtype_index! {
+//!     /// A function ID.
+//!     struct FunctionId;
+//! }
+//!
+//! // Compute a value for each of the 100 functions in parallel.
+//! let mut table = DenseMap::<FunctionId, usize>::par_from_fn_n(|id| id.index() * 2, 100);
+//! assert_eq!(table[FunctionId::from_usize(21)], 42);
+//!
+//! // Update the table in place.
+//! table.par_iter_enumerated_mut().for_each(|(id, value)| *value += id.index());
+//! assert_eq!(table[FunctionId::from_usize(21)], 63);
+//!
+//! // Iterate over a range of IDs.
+//! let ids = FunctionId::range(FunctionId::from_usize(20), FunctionId::from_usize(22));
+//! assert_eq!(ids.map(|id| table[id]).collect::<Vec<_>>(), [60, 63]);
+//! assert_eq!(FunctionId::iter_n(table.len()).count(), 100);
+//! ```
+
+use rayon::prelude::*;
+use std::ops::Range;
 
 pub use oxc_index::{
     Idx, IdxRangeBounds, IdxSliceIndex, IndexBox, IndexSlice, IndexVec, index_box, index_vec,
     nonmax::NonMaxU32,
 };
 
+/// A dense map from an index type to values, stored as an [`IndexVec`].
+///
+/// This is the preferred representation for side tables in which most keys have a value.
+pub type DenseMap<K, V> = IndexVec<K, V>;
+
+/// An iterator over a range of indices.
+///
+/// Created by [`idx_range`], or by the `range` and `iter_n` functions generated by
+/// [`newtype_index!`](crate::newtype_index).
+pub type IdxRange<I> = std::iter::Map<Range<usize>, fn(usize) -> I>;
+
+/// Returns an iterator over the indices in `range`.
+#[inline]
+pub fn idx_range<I: Idx>(range: Range<I>) -> IdxRange<I> {
+    (range.start.index()..range.end.index()).map(I::from_usize as fn(usize) -> I)
+}
+
+/// Extension trait for [`IndexVec`], for building tables keyed by an index type.
+///
+/// See the [module-level documentation](self) for more details.
+pub trait IndexVecExt<I: Idx, T>: Sized {
+    /// Creates a new vector with `n` elements, by calling `f` with each index in order.
+    fn from_fn_n(f: impl FnMut(I) -> T, n: usize) -> Self;
+
+    /// Creates a new vector with `n` elements, by calling `f` with each index in parallel on the
+    /// current Rayon thread pool.
+    fn par_from_fn_n(f: impl Fn(I) -> T + Sync + Send, n: usize) -> Self
+    where
+        I: Send,
+        T: Send;
+
+    /// Returns a parallel iterator over the elements and their indices.
+    fn par_iter_enumerated(&self) -> impl IndexedParallelIterator<Item = (I, &T)>
+    where
+        I: Send,
+        T: Sync;
+
+    /// Returns a parallel iterator over mutable references to the elements and their indices.
+    fn par_iter_enumerated_mut(&mut self) -> impl IndexedParallelIterator<Item = (I, &mut T)>
+    where
+        I: Send,
+        T: Send;
+}
+
+impl<I: Idx, T> IndexVecExt<I, T> for IndexVec<I, T> {
+    #[inline]
+    fn from_fn_n(mut f: impl FnMut(I) -> T, n: usize) -> Self {
+        Self::from_vec((0..n).map(|i| f(I::from_usize(i))).collect())
+    }
+
+    #[inline]
+    fn par_from_fn_n(f: impl Fn(I) -> T + Sync + Send, n: usize) -> Self
+    where
+        I: Send,
+        T: Send,
+    {
+        Self::from_vec((0..n).into_par_iter().map(|i| f(I::from_usize(i))).collect())
+    }
+
+    #[inline]
+    fn par_iter_enumerated(&self) -> impl IndexedParallelIterator<Item = (I, &T)>
+    where
+        I: Send,
+        T: Sync,
+    {
+        self.raw.par_iter().enumerate().map(|(i, value)| (I::from_usize(i), value))
+    }
+
+    #[inline]
+    fn par_iter_enumerated_mut(&mut self) -> impl IndexedParallelIterator<Item = (I, &mut T)>
+    where
+        I: Send,
+        T: Send,
+    {
+        self.raw.par_iter_mut().enumerate().map(|(i, value)| (I::from_usize(i), value))
+    }
+}
+
 /// Creates a new index type backed by `NonMaxU32`, with niche optimization for `Option<T>`.
+///
+/// The generated type also has `range` and `iter_n` functions returning an [`IdxRange`], and
+/// implements `serde::Serialize` and `serde::Deserialize` if the `serde` feature is enabled.
 #[macro_export]
 macro_rules! newtype_index {
     ($($(#[$attr:meta])* $vis:vis struct $name:ident;)*) => {$(
@@ -17,8 +137,24 @@ macro_rules! newtype_index {
         impl $name {
             /// The maximum index value.
             $vis const MAX: Self = Self::new(Self::MAX_INDEX);
+
+            /// Returns an iterator over the indices from `start` (inclusive) to `end` (exclusive).
+            #[inline]
+            #[allow(dead_code)]
+            $vis fn range(start: Self, end: Self) -> $crate::index::IdxRange<Self> {
+                $crate::index::idx_range(start..end)
+            }
+
+            /// Returns an iterator over the first `n` indices.
+            #[inline]
+            #[allow(dead_code)]
+            $vis fn iter_n(n: usize) -> $crate::index::IdxRange<Self> {
+                (0..n).map(<Self as $crate::index::Idx>::from_usize as fn(usize) -> Self)
+            }
         }
 
+        $crate::__newtype_index_serde!($name);
+
         impl $crate::bit_set::BitSetIndex for $name {
             #[inline]
             fn from_usize(index: usize) -> Self {
@@ -34,3 +170,45 @@ macro_rules! newtype_index {
 }
 
 pub use oxc_index::define_nonmax_u32_index_type;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __newtype_index_serde {
+    ($name:ident) => {
+        impl $crate::index::__serde::Serialize for $name {
+            fn serialize<S: $crate::index::__serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                serializer.serialize_u32(<Self as $crate::index::Idx>::index(*self) as u32)
+            }
+        }
+
+        impl<'de> $crate::index::__serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::index::__serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                let index =
+                    <u32 as $crate::index::__serde::Deserialize>::deserialize(deserializer)?;
+                if index as usize > Self::MAX_INDEX {
+                    return Err(<D::Error as $crate::index::__serde::de::Error>::custom(
+                        format_args!("index out of range: {index}"),
+                    ));
+                }
+                Ok(Self::new(index as usize))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __newtype_index_serde {
+    ($name:ident) => {};
+}
@@ -25,6 +25,47 @@ mod private {
 /// The `PrivateZst` field means you can pattern match with `Interned(v, _)`
 /// but you can only construct a `Interned` with `new_unchecked`, and not
 /// directly.
+///
+/// # Examples
+///
+/// Interning values allocated on an arena:
+///
+/// ```
+/// use solar_data_structures::{Interned, map::FxHashSet};
+///
+/// #[derive(Debug, PartialEq, Eq, Hash)]
+/// struct Layout {
+///     size: u32,
+/// }
+///
+/// struct Interner<'a> {
+///     arena: &'a bumpalo::Bump,
+///     set: std::cell::RefCell<FxHashSet<&'a Layout>>,
+/// }
+///
+/// impl<'a> Interner<'a> {
+///     fn intern(&self, layout: Layout) -> Interned<'a, Layout> {
+///         let mut set = self.set.borrow_mut();
+///         let layout = match set.get(&layout) {
+///             Some(&layout) => layout,
+///             None => {
+///                 let layout = &*self.arena.alloc(layout);
+///                 set.insert(layout);
+///                 layout
+///             }
+///         };
+///         Interned::new_unchecked(layout)
+///     }
+/// }
+///
+/// let arena = bumpalo::Bump::new();
+/// let interner = Interner { arena: &arena, set: Default::default() };
+/// let a = interner.intern(Layout { size: 32 });
+/// let b = interner.intern(Layout { size: 32 });
+/// // Compared by address.
+/// assert_eq!(a, b);
+/// assert_eq!(a.size, 32);
+/// ```
 #[cfg_attr(feature = "nightly", rustc_pass_by_value)]
 pub struct Interned<'a, T>(pub &'a T, pub private::PrivateZst);
 
@@ -63,6 +63,16 @@ macro_rules! pluralize {
     };
 }
 
+/// Statically asserts the size of a type in bytes.
+///
+/// This is used to catch accidental growth of frequently allocated types at compile time.
+#[macro_export]
+macro_rules! static_assert_size {
+    ($ty:ty, $size:expr $(,)?) => {
+        const _: [(); $size] = [(); ::std::mem::size_of::<$ty>()];
+    };
+}
+
 /// This calls the passed function while ensuring it won't be inlined into the caller.
 #[inline(never)]
 #[cold]
@@ -7,9 +7,31 @@ use std::{alloc::Layout, fmt};
 ///
 /// This is similar to `[T]`, but the length is stored in the slice itself,
 /// rather than in a separate field, making it a single word in size, instead of two.
+///
+/// Thin slices are allocated on an arena with the `*_thin` methods of [`BumpExt`](crate::BumpExt).
+/// Use them for large numbers of small lists, such as the per-item lists of a side table, where
+/// halving the size of each reference matters.
+///
+/// # Examples
+///
+/// ```
+/// use solar_data_structures::{BumpExt, ThinSlice};
+///
+/// let arena = bumpalo::Bump::new();
+/// let slice: &ThinSlice<u32> = arena.alloc_from_iter_thin((), 1..=3);
+/// assert_eq!(size_of_val(&slice), size_of::<usize>());
+/// assert_eq!(slice.as_slice(), &[1, 2, 3]);
+///
+/// // The empty slice is static and does not allocate.
+/// let empty: &ThinSlice<u32> = Default::default();
+/// assert!(empty.is_empty());
+/// ```
 pub type ThinSlice<T> = RawThinSlice<(), T>;
 
 /// [`ThinSlice`] with a custom header.
+///
+/// The header is stored inline before the length, and can be used to attach data to a list
+/// without increasing the size of references to it.
 pub struct RawThinSlice<H, T> {
     skel: ThinSliceSkeleton<H, T>,
     _opaque: OpaqueListContents,
@@ -0,0 +1,217 @@
+//! Message catalogs for translating diagnostics.
+//!
+//! Diagnostic messages created with [`DiagMsg::translatable`] have a stable key and a default
+//! template. A [`MessageCatalog`] maps keys to templates in another language; when set on a
+//! [`DiagCtxt`](super::DiagCtxt), messages are translated right before they are emitted.
+//!
+//! Since keys are stable, tools can also match on [`DiagMsg::id`] instead of the message text.
+
+use super::{Diag, DiagMsg, Suggestions};
+use solar_data_structures::map::FxHashMap;
+use std::{borrow::Cow, fmt};
+
+/// A collection of message templates for a locale, keyed by message ID.
+///
+/// # Examples
+///
+/// ```
+/// use solar_interface::diagnostics::{DiagMsg, MessageCatalog};
+///
+/// let src = "resolve-unresolved = symbole non résolu `{name}`\n";
+/// let catalog = MessageCatalog::parse("fr", src).unwrap();
+///
+/// let mut msg = DiagMsg::translatable("resolve-unresolved", "unresolved symbol `{name}`")
+///     .arg("name", "foo");
+/// msg.translate(&catalog);
+/// assert_eq!(msg.as_str(), "symbole non résolu `foo`");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MessageCatalog {
+    locale: String,
+    messages: FxHashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Creates a new empty catalog for `locale`.
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self { locale: locale.into(), messages: FxHashMap::default() }
+    }
+
+    /// Parses a catalog for `locale` from `src`.
+    ///
+    /// Each message is written as `id = template`. Lines indented with whitespace continue the
+    /// previous message on a new line. Empty lines and lines starting with `#` are ignored.
+    pub fn parse(locale: impl Into<String>, src: &str) -> Result<Self, ParseCatalogError> {
+        let mut catalog = Self::new(locale);
+        let mut last = None;
+        for (i, line) in src.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace)
+                && let Some(id) = &last
+                && let Some(template) = catalog.messages.get_mut(id)
+            {
+                template.push('\n');
+                template.push_str(trimmed);
+                continue;
+            }
+            let Some((id, template)) = line.split_once('=') else {
+                return Err(ParseCatalogError { line: i + 1 });
+            };
+            let id = id.trim();
+            if id.is_empty() || id.contains(char::is_whitespace) {
+                return Err(ParseCatalogError { line: i + 1 });
+            }
+            catalog.insert(id, template.trim());
+            last = Some(id.to_string());
+        }
+        Ok(catalog)
+    }
+
+    /// Returns the locale of the catalog.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Inserts a message template, returning the previous one, if any.
+    pub fn insert(&mut self, id: impl Into<String>, template: impl Into<String>) -> Option<String> {
+        self.messages.insert(id.into(), template.into())
+    }
+
+    /// Returns the message template for `id`.
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.messages.get(id).map(String::as_str)
+    }
+
+    /// Returns the number of messages in the catalog.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if the catalog contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Translates all the messages of `diag`, including its labels, subdiagnostics, and
+    /// suggestions.
+    pub fn translate_diag(&self, diag: &mut Diag) {
+        let msgs = diag.messages.iter_mut().map(|(msg, _)| msg);
+        msgs.for_each(|msg| msg.translate(self));
+        diag.span.span_label_msgs_mut().for_each(|msg| msg.translate(self));
+        for child in &mut diag.children {
+            child.messages.iter_mut().for_each(|(msg, _)| msg.translate(self));
+            child.span.span_label_msgs_mut().for_each(|msg| msg.translate(self));
+        }
+        let suggestions = match &mut diag.suggestions {
+            Suggestions::Enabled(suggestions) => &mut suggestions[..],
+            Suggestions::Sealed(suggestions) => &mut suggestions[..],
+            Suggestions::Disabled => &mut [],
+        };
+        suggestions.iter_mut().for_each(|sugg| sugg.msg.translate(self));
+    }
+}
+
+/// An error returned by [`MessageCatalog::parse`].
+#[derive(Debug)]
+pub struct ParseCatalogError {
+    line: usize,
+}
+
+impl ParseCatalogError {
+    /// Returns the 1-based line number of the invalid entry.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for ParseCatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid message catalog entry on line {}: expected `id = template`", self.line)
+    }
+}
+
+impl std::error::Error for ParseCatalogError {}
+
+/// Replaces the `{name}` placeholders in `template` with the values of `args`.
+///
+/// `{{` and `}}` are replaced with literal braces. Placeholders without a matching argument are
+/// left as-is.
+pub(crate) fn interpolate(template: &str, args: &[(Cow<'static, str>, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            out.push_str(&rest[..1]);
+            rest = after;
+        } else if rest.starts_with('{')
+            && let Some(end) = rest.find('}')
+            && let Some((_, value)) = args.iter().find(|(name, _)| **name == rest[1..end])
+        {
+            out.push_str(value);
+            rest = &rest[end + 1..];
+        } else {
+            out.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[(&'static str, &str)]) -> Vec<(Cow<'static, str>, String)> {
+        args.iter().map(|&(k, v)| (k.into(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn interpolation() {
+        let args = args(&[("a", "1"), ("b", "two")]);
+        assert_eq!(interpolate("no args", &args), "no args");
+        assert_eq!(interpolate("{a} and {b}", &args), "1 and two");
+        assert_eq!(interpolate("{{a}} {a}", &args), "{a} 1");
+        assert_eq!(interpolate("{missing} {a", &args), "{missing} {a");
+        assert_eq!(interpolate("}", &args), "}");
+    }
+
+    #[test]
+    fn parse() {
+        let src = "# comment\n\na = first\nb=second\n  continued\n";
+        let catalog = MessageCatalog::parse("en", src).unwrap();
+        assert_eq!(catalog.locale(), "en");
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(catalog.get("a"), Some("first"));
+        assert_eq!(catalog.get("b"), Some("second\ncontinued"));
+
+        let err = MessageCatalog::parse("en", "a = ok\nnot an entry\n").unwrap_err();
+        assert_eq!(err.line(), 2);
+    }
+
+    #[test]
+    fn translate() {
+        let mut catalog = MessageCatalog::new("test");
+        catalog.insert("greeting", "bonjour, {name}");
+
+        let mut msg = DiagMsg::translatable("greeting", "hello, {name}").arg("name", "solar");
+        assert_eq!(msg.as_str(), "hello, solar");
+        assert_eq!(msg.args().collect::<Vec<_>>(), [("name", "solar")]);
+        msg.translate(&catalog);
+        assert_eq!(msg.as_str(), "bonjour, solar");
+
+        // Unknown keys and plain messages are left unchanged.
+        let mut msg = DiagMsg::translatable("unknown", "hello");
+        msg.translate(&catalog);
+        assert_eq!(msg.as_str(), "hello");
+        let mut msg = DiagMsg::from("greeting");
+        msg.translate(&catalog);
+        assert_eq!(msg.as_str(), "greeting");
+        assert_eq!(msg.id(), None);
+    }
+}
@@ -3,9 +3,9 @@
 //! Modified from rustc's [`DiagCtxt`](https://github.com/rust-lang/rust/blob/3b58636b30eb364ac72aeaf03d46347084ed87d1/compiler/rustc_errors/src/lib.rs).
 
 use super::{
-    BugAbort, Diag, DiagBuilder, DiagMsg, DynEmitter, EmissionGuarantee, EmittedDiagnostics,
-    ErrorGuaranteed, FatalAbort, HumanBufferEmitter, Level, MultiSpan, SilentEmitter,
-    emitter::HumanEmitter,
+    BugAbort, CallbackEmitter, Diag, DiagBuilder, DiagMsg, DynEmitter, EmissionGuarantee,
+    EmittedDiagnostics, ErrorGuaranteed, FatalAbort, HumanBufferEmitter, Level, MessageCatalog,
+    MultiSpan, SilentEmitter, emitter::HumanEmitter,
 };
 use crate::{Result, SourceMap, Span};
 use anstream::ColorChoice;
@@ -44,11 +44,13 @@ impl DiagCtxtFlags {
     /// Looks at the following options:
     /// - `unstable.ui_testing`
     /// - `unstable.track_diagnostics`
+    /// - `unstable.treat_err_as_bug`
     /// - `no_warnings`
     pub fn update_from_opts(&mut self, opts: &CompileOpts) {
         self.deduplicate_diagnostics &= !opts.unstable.ui_testing;
         self.track_diagnostics &= !opts.unstable.ui_testing;
         self.track_diagnostics |= opts.unstable.track_diagnostics;
+        self.treat_err_as_bug = self.treat_err_as_bug.or(opts.unstable.treat_err_as_bug);
         self.can_emit_warnings &= !opts.no_warnings;
     }
 }
@@ -80,6 +82,8 @@ struct DiagCtxtInner {
 
     flags: DiagCtxtFlags,
     allowed_diagnostic_codes: FxHashSet<String>,
+    /// The catalog used to translate diagnostic messages before they are emitted.
+    catalog: Option<Arc<MessageCatalog>>,
 
     /// The number of errors that have been emitted, including duplicates.
     ///
@@ -107,6 +111,7 @@ impl DiagCtxt {
                 emitter,
                 flags: DiagCtxtFlags::default(),
                 allowed_diagnostic_codes: FxHashSet::default(),
+                catalog: None,
                 err_count: 0,
                 deduplicated_err_count: 0,
                 warn_count: 0,
@@ -159,6 +164,15 @@ impl DiagCtxt {
         Self::new(Box::new(HumanBufferEmitter::new(color_choice).source_map(source_map)))
     }
 
+    /// Creates a new `DiagCtxt` with an emitter that calls `callback` with each emitted
+    /// diagnostic.
+    pub fn with_callback_emitter(
+        source_map: Option<Arc<SourceMap>>,
+        callback: impl FnMut(Diag) + Send + 'static,
+    ) -> Self {
+        Self::new(Box::new(CallbackEmitter::new(callback).source_map(source_map)))
+    }
+
     /// Creates a new `DiagCtxt` from the given options.
     ///
     /// This is the default `DiagCtxt` used by the `Session` if one is not provided manually.
@@ -263,6 +277,17 @@ impl DiagCtxt {
         f(&mut self.inner.get_mut().flags);
     }
 
+    /// Sets the catalog used to translate diagnostic messages.
+    pub fn with_message_catalog(mut self, catalog: Option<Arc<MessageCatalog>>) -> Self {
+        self.inner.get_mut().catalog = catalog;
+        self
+    }
+
+    /// Sets the catalog used to translate diagnostic messages.
+    pub fn set_message_catalog(&self, catalog: Option<Arc<MessageCatalog>>) {
+        self.inner.lock().catalog = catalog;
+    }
+
     /// Adds diagnostic codes that should be allowed.
     pub fn set_allowed_diagnostic_codes(&self, codes: impl IntoIterator<Item = String>) {
         self.inner.lock().allowed_diagnostic_codes.extend(codes);
@@ -525,6 +550,21 @@ impl DiagCtxtInner {
             // Unlike rustc, deduplication is only disabled internally for UI testing, so do not
             // attach rustc's `-Z deduplicate-diagnostics=no` note.
 
+            if let Some(catalog) = &self.catalog {
+                catalog.translate_diag(diagnostic);
+            }
+
+            crate::event!(
+                "diag_emitted",
+                level = diagnostic.level.to_str(),
+                code = diagnostic.id(),
+                message = %diagnostic.label(),
+            );
+            crate::SessionGlobals::try_with(|globals| {
+                if let Some(globals) = globals {
+                    globals.ice.record_diagnostic(diagnostic);
+                }
+            });
             self.emitter.emit_diagnostic(diagnostic);
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
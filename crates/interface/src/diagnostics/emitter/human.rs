@@ -22,9 +22,10 @@ use solar_data_structures::pluralize;
 use std::{
     any::Any,
     borrow::Cow,
-    io::{self, Write},
+    io::{self, IsTerminal, Write},
     sync::{Arc, OnceLock},
 };
+use unicode_width::UnicodeWidthStr;
 
 type Writer = dyn Write + Send + 'static;
 
@@ -33,6 +34,9 @@ type Writer = dyn Write + Send + 'static;
 /// Arbitrary, but taken from trait import suggestion limit
 pub(super) const MAX_SUGGESTIONS: usize = 4;
 
+/// Messages are never wrapped to fewer than this many columns.
+const MIN_WRAP_WIDTH: usize = 40;
+
 const DEFAULT_RENDERER: Renderer = Renderer::styled()
     .error(Level::Error.style())
     .warning(Level::Warning.style())
@@ -51,6 +55,8 @@ pub struct HumanEmitter {
     source_map: Option<Arc<SourceMap>>,
     renderer: Renderer,
     short_message: bool,
+    /// The terminal width to wrap messages to, if known.
+    term_width: Option<usize>,
 }
 
 // SAFETY: `real_writer` always points to the `Writer` in `writer`.
@@ -118,6 +124,7 @@ impl HumanEmitter {
             source_map: None,
             renderer: DEFAULT_RENDERER,
             short_message: false,
+            term_width: None,
         }
         .human_kind(Default::default())
     }
@@ -147,9 +154,12 @@ impl HumanEmitter {
     }
 
     /// Creates a new `HumanEmitter` that writes to stderr.
+    ///
+    /// If stderr is a terminal, messages are wrapped to its width.
     pub fn stderr(color_choice: ColorChoice) -> Self {
         // `io::Stderr` is not buffered.
         Self::new(io::BufWriter::new(io::stderr()), stderr_choice(color_choice))
+            .terminal_width(stderr_terminal_width())
     }
 
     /// Sets the source map.
@@ -194,9 +204,13 @@ impl HumanEmitter {
     }
 
     /// Sets the terminal width for formatting.
+    ///
+    /// Long messages are soft-wrapped to this width, and long source lines are trimmed.
+    /// Does nothing if `width` is `None`.
     pub fn terminal_width(mut self, width: Option<usize>) -> Self {
         if let Some(w) = width {
             self.renderer = self.renderer.term_width(w);
+            self.term_width = Some(w);
         }
         self
     }
@@ -236,7 +250,11 @@ impl HumanEmitter {
         let mut title = if msgs.iter().any(|(_, style)| style != &Style::NoStyle) {
             annotation_level.clone().secondary_title(Cow::Owned(self.pre_style_msgs(msgs, *level)))
         } else {
-            annotation_level.clone().primary_title(self.no_style_msgs(msgs))
+            let prefix_width =
+                level.to_str().len() + code.as_ref().map_or(0, |c| c.as_str().len() + 2);
+            annotation_level
+                .clone()
+                .primary_title(self.wrap(self.no_style_msgs(msgs), prefix_width))
         };
 
         if let Some(c) = code {
@@ -254,7 +272,7 @@ impl HumanEmitter {
         // If we don't have span information, emit and exit
         let Some(sm) = self.source_map.as_ref() else {
             group = group.elements(children.iter().map(|c| {
-                let msg = self.no_style_msgs(&c.messages);
+                let msg = self.wrap(self.no_style_msgs(&c.messages), c.level.to_str().len());
                 let level = annotation_level_for_level(c.level);
                 level.message(msg)
             }));
@@ -271,21 +289,8 @@ impl HumanEmitter {
             return;
         };
 
-        let mut file_ann = collect_annotations(msp, sm);
-
-        // Make sure our primary file comes first
-        let primary_span = msp.primary_span().unwrap_or_default();
-        if !primary_span.is_dummy() {
-            let primary_lo = sm.lookup_char_pos(primary_span.lo());
-            if let Ok(pos) = file_ann.binary_search_by(|(f, _)| f.name.cmp(&primary_lo.file.name)) {
-                file_ann.swap(0, pos);
-            }
-
-            for (file, annotations) in file_ann.into_iter() {
-                if let Some(snippet) = self.annotated_snippet(annotations, &file.name, sm) {
-                    group = group.element(snippet);
-                }
-            }
+        if !msp.primary_span().unwrap_or_default().is_dummy() {
+            group = group.elements(self.file_snippets(msp, sm));
         }
 
         for c in children {
@@ -296,7 +301,7 @@ impl HumanEmitter {
             let msg = if c.messages.iter().any(|(_, style)| style != &Style::NoStyle) {
                 Cow::Owned(self.pre_style_msgs(&c.messages, c.level))
             } else {
-                Cow::Owned(self.no_style_msgs(&c.messages))
+                Cow::Owned(self.wrap(self.no_style_msgs(&c.messages), c.level.to_str().len()))
             };
 
             // This is a secondary message with no span info
@@ -310,22 +315,7 @@ impl HumanEmitter {
                 Group::with_title(level.clone().secondary_title(msg)),
             ));
 
-            let mut file_ann = collect_annotations(&c.span, sm);
-            let primary_span = c.span.primary_span().unwrap_or_default();
-            if !primary_span.is_dummy() {
-                let primary_lo = sm.lookup_char_pos(primary_span.lo());
-                if let Ok(pos) =
-                    file_ann.binary_search_by(|(f, _)| f.name.cmp(&primary_lo.file.name))
-                {
-                    file_ann.swap(0, pos);
-                }
-            }
-
-            for (file, annotations) in file_ann.into_iter() {
-                if let Some(snippet) = self.annotated_snippet(annotations, &file.name, sm) {
-                    group = group.element(snippet);
-                }
-            }
+            group = group.elements(self.file_snippets(&c.span, sm));
         }
 
         for suggestion in suggestions.unwrap_tag() {
@@ -335,6 +325,7 @@ impl HumanEmitter {
                 }
                 SuggestionStyle::HideCodeAlways => {
                     let msg = self.no_style_msgs(&[(suggestion.msg.to_owned(), Style::HeaderMsg)]);
+                    let msg = self.wrap(msg, Level::Help.to_str().len());
                     group = group.element(annotate_snippets::Level::HELP.message(msg));
                 }
                 SuggestionStyle::HideCodeInline
@@ -465,6 +456,7 @@ impl HumanEmitter {
                         })
                         .collect::<Vec<_>>();
                     if !subs.is_empty() {
+                        let msg = self.wrap(msg, Level::Help.to_str().len());
                         report.push(std::mem::replace(
                             &mut group,
                             Group::with_title(annotate_snippets::Level::HELP.secondary_title(msg)),
@@ -515,6 +507,48 @@ impl HumanEmitter {
         msgs.iter().map(|(m, _)| m.as_str()).collect()
     }
 
+    /// Returns a snippet for each file with annotations in `msp`.
+    ///
+    /// The file of the primary span comes first, followed by the other files in the order in which
+    /// their annotations were added.
+    fn file_snippets<'a>(
+        &self,
+        msp: &MultiSpan,
+        sm: &Arc<SourceMap>,
+    ) -> Vec<Snippet<'a, ASAnnotation<'a>>> {
+        let mut file_ann = collect_annotations(msp, sm);
+        if let Some(primary_span) = msp.primary_span()
+            && !primary_span.is_dummy()
+        {
+            let primary_file = sm.lookup_source_file(primary_span.lo());
+            if let Some(pos) =
+                file_ann.iter().position(|(f, _)| f.start_pos == primary_file.start_pos)
+            {
+                file_ann[..=pos].rotate_right(1);
+            }
+        }
+        file_ann
+            .into_iter()
+            .filter_map(|(file, annotations)| self.annotated_snippet(annotations, &file.name, sm))
+            .collect()
+    }
+
+    /// Soft-wraps `msg` to the terminal width, if known.
+    ///
+    /// `prefix_width` is the width of the message's level and code, which are rendered before it.
+    fn wrap(&self, msg: String, prefix_width: usize) -> String {
+        let Some(width) = self.term_width else { return msg };
+        if self.short_message {
+            return msg;
+        }
+        // Account for the `: ` after the prefix.
+        let width = width.saturating_sub(prefix_width + 2).max(MIN_WRAP_WIDTH);
+        if msg.lines().all(|line| line.width() <= width) {
+            return msg;
+        }
+        soft_wrap(&msg, width)
+    }
+
     fn annotated_snippet<'a>(
         &self,
         annotations: Vec<Annotation>,
@@ -527,14 +561,19 @@ impl HumanEmitter {
 
             let filename = sm.filename_for_diagnostics(file_name).to_string();
 
-            Some(Snippet::source(source).line_start(offset_line).path(filename).annotations(
-                annotations.into_iter().map(move |a| {
-                    let lo = a.span.lo().to_usize().saturating_sub(adj_lo);
-                    let hi = a.span.hi().to_usize().saturating_sub(adj_lo);
-                    let ann = a.kind.span(lo..hi);
-                    if let Some(label) = a.label { ann.label(label) } else { ann }
-                }),
-            ))
+            // Folding elides unannotated lines, and the middle of long multiline spans, with `...`.
+            Some(
+                Snippet::source(source)
+                    .line_start(offset_line)
+                    .path(filename)
+                    .fold(true)
+                    .annotations(annotations.into_iter().map(move |a| {
+                        let lo = a.span.lo().to_usize().saturating_sub(adj_lo);
+                        let hi = a.span.hi().to_usize().saturating_sub(adj_lo);
+                        let ann = a.kind.span(lo..hi);
+                        if let Some(label) = a.label { ann.label(label) } else { ann }
+                    })),
+            )
         } else {
             None
         }
@@ -647,6 +686,57 @@ fn stderr_choice(color_choice: ColorChoice) -> ColorChoice {
     }
 }
 
+/// Returns the width of the terminal connected to stderr, if any.
+///
+/// The `COLUMNS` environment variable takes precedence over querying the terminal.
+fn stderr_terminal_width() -> Option<usize> {
+    if !io::stderr().is_terminal() {
+        return None;
+    }
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok())
+        && columns > 0
+    {
+        return Some(columns);
+    }
+    #[cfg(unix)]
+    {
+        // SAFETY: `winsize` is plain data, and `TIOCGWINSZ` only writes to it.
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut size) } == 0
+            && size.ws_col > 0
+        {
+            return Some(size.ws_col as usize);
+        }
+    }
+    None
+}
+
+/// Wraps each line of `text` at whitespace so that it is at most `width` columns wide.
+///
+/// Words that are wider than `width` are put on their own line.
+fn soft_wrap(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / width);
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let mut line_width = 0;
+        for word in line.split(' ') {
+            let word_width = word.width();
+            if line_width > 0 && line_width + 1 + word_width > width {
+                out.push('\n');
+                line_width = 0;
+            } else if line_width > 0 {
+                out.push(' ');
+                line_width += 1;
+            }
+            out.push_str(word);
+            line_width += word_width;
+        }
+    }
+    out
+}
+
 fn emit_to_destination(
     rendered: String,
     lvl: &Level,
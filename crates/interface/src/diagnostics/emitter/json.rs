@@ -5,11 +5,11 @@
 
 use super::{Emitter, human::HumanBufferEmitter, io_panic};
 use crate::{
-    Span,
+    BytePos, Span,
     diagnostics::{
         Applicability, CodeSuggestion, Diag, Level, MultiSpan, SpanLabel, SubDiagnostic,
     },
-    source_map::{LineInfo, SourceFile, SourceMap},
+    source_map::{ColumnEncoding, LineInfo, SourceFile, SourceMap},
 };
 use anstream::ColorChoice;
 use serde::{Deserialize, Serialize};
@@ -170,6 +170,8 @@ impl JsonEmitter {
             line_end: end.line,
             column_start: start.col.0 + 1,
             column_end: end.col.0 + 1,
+            column_start_utf16: Some(utf16_column(&start.file, span.lo())),
+            column_end_utf16: Some(utf16_column(&end.file, span.hi())),
             is_primary: label.is_primary,
             text: self.span_lines(span),
             label: label.label.as_ref().map(|msg| Cow::Owned(msg.as_str().to_string())),
@@ -211,6 +213,8 @@ impl JsonEmitter {
             line_end: end.line,
             column_start: start.col.0 + 1,
             column_end: end.col.0 + 1,
+            column_start_utf16: Some(utf16_column(&start.file, span.lo())),
+            column_end_utf16: Some(utf16_column(&end.file, span.hi())),
             is_primary: true,
             text: self.span_lines(span),
             label: None,
@@ -377,6 +381,12 @@ pub struct JsonDiagnosticSpan<'a> {
     pub column_start: usize,
     /// 1-based, character offset.
     pub column_end: usize,
+    /// 1-based, UTF-16 code unit offset, as used by the Language Server Protocol.
+    #[serde(default)]
+    pub column_start_utf16: Option<usize>,
+    /// 1-based, UTF-16 code unit offset, as used by the Language Server Protocol.
+    #[serde(default)]
+    pub column_end_utf16: Option<usize>,
     /// Is this a "primary" span -- meaning the point, or one of the points,
     /// where the error occurred?
     pub is_primary: bool,
@@ -485,6 +495,11 @@ pub enum Severity {
     Info,
 }
 
+/// Returns the 1-based UTF-16 column of `pos`.
+fn utf16_column(file: &SourceFile, pos: BytePos) -> usize {
+    file.lookup_line_col(pos, ColumnEncoding::Utf16).1 + 1
+}
+
 fn to_severity(level: Level) -> Severity {
     match level {
         Level::Bug | Level::Fatal | Level::Error => Severity::Error,
@@ -524,6 +539,39 @@ mod tests {
         assert!(span.expansion.is_none());
     }
 
+    #[test]
+    fn multibyte_columns() {
+        let source_map = Arc::new(SourceMap::empty());
+        let src = "/* 🦀é */ foo\n/* ✓ */ bar\n";
+        let file = source_map
+            .new_source_file(crate::source_map::FileName::custom("test.sol"), src)
+            .unwrap();
+        let emitter = JsonEmitter::new(Box::new(io::sink()), source_map, ColorChoice::Never);
+
+        let span_of = |needle: &str| {
+            let lo = file.start_pos + crate::BytePos(src.find(needle).unwrap() as u32);
+            Span::new(lo, lo + crate::BytePos(needle.len() as u32))
+        };
+        let label = |span| SpanLabel { span, is_primary: true, label: None };
+
+        // `🦀` is 4 bytes and 2 UTF-16 code units, `é` is 2 bytes and 1 code unit.
+        let span = emitter.span(&label(span_of("foo")));
+        assert_eq!((span.byte_start, span.byte_end), (13, 16));
+        assert_eq!((span.column_start, span.column_end), (10, 13));
+        assert_eq!((span.column_start_utf16, span.column_end_utf16), (Some(11), Some(14)));
+
+        // `✓` is 3 bytes and 1 UTF-16 code unit.
+        let span = emitter.span(&label(span_of("bar")));
+        assert_eq!((span.byte_start, span.byte_end), (27, 30));
+        assert_eq!((span.line_start, span.column_start), (2, 9));
+        assert_eq!((span.column_start_utf16, span.column_end_utf16), (Some(9), Some(12)));
+
+        // Spans that end in the middle of a multibyte line.
+        let span = emitter.span(&label(span_of("🦀é")));
+        assert_eq!((span.column_start, span.column_end), (4, 6));
+        assert_eq!((span.column_start_utf16, span.column_end_utf16), (Some(4), Some(7)));
+    }
+
     #[test]
     fn solc_diagnostic_serializes_borrowed_strings() {
         let diagnostic = SolcDiagnostic {
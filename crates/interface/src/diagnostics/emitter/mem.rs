@@ -1,4 +1,7 @@
-use crate::diagnostics::{Diag, Emitter};
+use crate::{
+    SourceMap,
+    diagnostics::{Diag, Emitter},
+};
 use solar_data_structures::sync::RwLock;
 use std::sync::Arc;
 
@@ -27,3 +30,35 @@ impl Emitter for InMemoryEmitter {
         self.buffer.write().push(diagnostic.clone());
     }
 }
+
+/// A diagnostics emitter that passes each diagnostic to a callback.
+///
+/// This allows embedders to receive structured [`Diag`]s as they are emitted, rather than parsing
+/// rendered output.
+pub struct CallbackEmitter {
+    callback: Box<dyn FnMut(Diag) + Send>,
+    source_map: Option<Arc<SourceMap>>,
+}
+
+impl CallbackEmitter {
+    /// Creates a new emitter that calls `callback` with each emitted diagnostic.
+    pub fn new(callback: impl FnMut(Diag) + Send + 'static) -> Self {
+        Self { callback: Box::new(callback), source_map: None }
+    }
+
+    /// Sets the source map, which is used to resolve the spans of the emitted diagnostics.
+    pub fn source_map(mut self, source_map: Option<Arc<SourceMap>>) -> Self {
+        self.source_map = source_map;
+        self
+    }
+}
+
+impl Emitter for CallbackEmitter {
+    fn emit_diagnostic(&mut self, diagnostic: &mut Diag) {
+        (self.callback)(diagnostic.clone());
+    }
+
+    fn source_map(&self) -> Option<&Arc<SourceMap>> {
+        self.source_map.as_ref()
+    }
+}
@@ -19,7 +19,7 @@ pub use json::{
 };
 
 mod mem;
-pub use mem::InMemoryEmitter;
+pub use mem::{CallbackEmitter, InMemoryEmitter};
 
 /// Dynamic diagnostic emitter. See [`Emitter`].
 pub type DynEmitter = dyn Emitter + Send;
@@ -1,11 +1,27 @@
 //! Modified from [`rustc_error_messages`](https://github.com/rust-lang/rust/blob/3b58636b30eb364ac72aeaf03d46347084ed87d1/compiler/rustc_error_messages/src/lib.rs).
 
+use super::catalog::{MessageCatalog, interpolate};
 use crate::Span;
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, fmt, ops::Deref};
 
+/// A diagnostic message.
+///
+/// Messages are usually plain strings, but they can also be created with
+/// [`translatable`](Self::translatable), in which case they can be translated by a
+/// [`MessageCatalog`] when emitted.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DiagMsg {
+    /// The message text. For translatable messages, this is the interpolated default template.
     inner: Cow<'static, str>,
+    translatable: Option<Box<Translatable>>,
+}
+
+/// The catalog key and arguments of a translatable [`DiagMsg`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Translatable {
+    id: Cow<'static, str>,
+    template: Cow<'static, str>,
+    args: Vec<(Cow<'static, str>, String)>,
 }
 
 impl Deref for DiagMsg {
@@ -19,23 +35,79 @@ impl Deref for DiagMsg {
 
 impl From<&'static str> for DiagMsg {
     fn from(value: &'static str) -> Self {
-        Self { inner: Cow::Borrowed(value) }
+        Self { inner: Cow::Borrowed(value), translatable: None }
     }
 }
 
 impl From<String> for DiagMsg {
     fn from(value: String) -> Self {
-        Self { inner: Cow::Owned(value) }
+        Self { inner: Cow::Owned(value), translatable: None }
     }
 }
 
 impl From<Cow<'static, str>> for DiagMsg {
     fn from(value: Cow<'static, str>) -> Self {
-        Self { inner: value }
+        Self { inner: value, translatable: None }
     }
 }
 
 impl DiagMsg {
+    /// Creates a new translatable message with the given catalog key and default template.
+    ///
+    /// The template may reference arguments added with [`arg`](Self::arg) as `{name}`. Literal
+    /// braces are escaped as `{{` and `}}`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use solar_interface::diagnostics::DiagMsg;
+    ///
+    /// let msg = DiagMsg::translatable("resolve-unresolved", "unresolved symbol `{name}`")
+    ///     .arg("name", "foo");
+    /// assert_eq!(msg.as_str(), "unresolved symbol `foo`");
+    /// assert_eq!(msg.id(), Some("resolve-unresolved"));
+    /// ```
+    pub fn translatable(
+        id: impl Into<Cow<'static, str>>,
+        template: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        let template = template.into();
+        let inner = Cow::Owned(interpolate(&template, &[]));
+        let translatable = Translatable { id: id.into(), template, args: Vec::new() };
+        Self { inner, translatable: Some(Box::new(translatable)) }
+    }
+
+    /// Adds an argument to a translatable message.
+    ///
+    /// Does nothing if the message is not [translatable](Self::translatable).
+    pub fn arg(mut self, name: impl Into<Cow<'static, str>>, value: impl fmt::Display) -> Self {
+        if let Some(t) = &mut self.translatable {
+            t.args.push((name.into(), value.to_string()));
+            self.inner = Cow::Owned(interpolate(&t.template, &t.args));
+        }
+        self
+    }
+
+    /// Returns the catalog key of a translatable message.
+    pub fn id(&self) -> Option<&str> {
+        self.translatable.as_ref().map(|t| &*t.id)
+    }
+
+    /// Returns the arguments of a translatable message.
+    pub fn args(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.translatable.iter().flat_map(|t| t.args.iter().map(|(k, v)| (&**k, v.as_str())))
+    }
+
+    /// Translates the message with `catalog`, if it is translatable and the catalog contains its
+    /// key.
+    pub fn translate(&mut self, catalog: &MessageCatalog) {
+        if let Some(t) = &self.translatable
+            && let Some(template) = catalog.get(&t.id)
+        {
+            self.inner = Cow::Owned(interpolate(template, &t.args));
+        }
+    }
+
     /// Returns the message as a string.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -98,6 +170,11 @@ impl MultiSpan {
         self.span_labels.push((span, label.into()));
     }
 
+    /// Returns mutable references to the span label messages.
+    pub(crate) fn span_label_msgs_mut(&mut self) -> impl Iterator<Item = &mut DiagMsg> {
+        self.span_labels.iter_mut().map(|(_, msg)| msg)
+    }
+
     /// Adds an already constructed diagnostic message as a span label.
     pub fn push_span_diag(&mut self, span: Span, diag: DiagMsg) {
         self.span_labels.push((span, diag));
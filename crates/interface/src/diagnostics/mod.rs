@@ -17,13 +17,16 @@ use std::{
 mod builder;
 pub use builder::{DiagBuilder, EmissionGuarantee};
 
+mod catalog;
+pub use catalog::{MessageCatalog, ParseCatalogError};
+
 mod context;
 pub use context::{DiagCtxt, DiagCtxtFlags};
 
 mod emitter;
 pub use emitter::{
-    DynEmitter, Emitter, HumanBufferEmitter, HumanEmitter, InMemoryEmitter, LocalEmitter,
-    SilentEmitter,
+    CallbackEmitter, DynEmitter, Emitter, HumanBufferEmitter, HumanEmitter, InMemoryEmitter,
+    LocalEmitter, SilentEmitter,
 };
 #[cfg(feature = "json")]
 pub use emitter::{
@@ -1325,6 +1328,38 @@ mod tests {
             str![[r#"
 error: boom
 
+"#]]
+        );
+    }
+
+    #[test]
+    fn test_long_message_is_wrapped_to_terminal_width() {
+        let msg = "this is a very long diagnostic message that does not fit in a narrow terminal";
+        let mut emitter = HumanBufferEmitter::new(ColorChoice::Never).terminal_width(Some(50));
+        let mut diag = Diag::new(Level::Error, msg);
+        diag.note(msg);
+        emitter.emit_diagnostic(&mut diag);
+
+        assert_data_eq!(
+            emitter.buffer(),
+            str![[r#"
+error: this is a very long diagnostic message that
+       does not fit in a narrow terminal
+  │
+  ╰ note: this is a very long diagnostic message that
+          does not fit in a narrow terminal
+
+"#]]
+        );
+
+        // Without a width, messages are not wrapped.
+        let mut emitter = HumanBufferEmitter::new(ColorChoice::Never);
+        emitter.emit_diagnostic(&mut Diag::new(Level::Error, msg));
+        assert_data_eq!(
+            emitter.buffer(),
+            str![[r#"
+error: this is a very long diagnostic message that does not fit in a narrow terminal
+
 "#]]
         );
     }
@@ -1392,6 +1427,47 @@ note: mutable variables should use mixedCase
         }
     }
 
+    #[test]
+    fn test_multi_file_labels_render_primary_file_first() {
+        let sm = std::sync::Arc::new(source_map::SourceMap::empty());
+        let mut spans = Vec::new();
+        for name in ["a.sol", "c.sol", "b.sol"] {
+            let file = sm
+                .new_source_file(source_map::FileName::custom(name), "contract C {}\n".to_string())
+                .unwrap();
+            spans.push(Span::new(file.start_pos, file.start_pos + BytePos(8)));
+        }
+        let mut msp = MultiSpan::from_span(spans[2]);
+        msp.push_span_label(spans[0], "first secondary");
+        msp.push_span_label(spans[1], "second secondary");
+        let mut diag = Diag::new(Level::Error, "multi-file");
+        diag.span(msp);
+
+        let mut emitter = HumanBufferEmitter::new(ColorChoice::Never).source_map(Some(sm));
+        emitter.emit_diagnostic(&mut diag);
+        assert_data_eq!(
+            emitter.buffer(),
+            str![[r#"
+error: multi-file
+  ╭▸ <b.sol>:1:1
+  │
+1 │ contract C {}
+  │ ━━━━━━━━
+  │
+  ⸬  <a.sol>:1:1
+  │
+1 │ contract C {}
+  │ ──────── first secondary
+  │
+  ⸬  <c.sol>:1:1
+  │
+1 │ contract C {}
+  ╰╴──────── second secondary
+
+"#]]
+        );
+    }
+
     #[test]
     fn test_inline_suggestion_marks_confusable_case() {
         let span = Span::new(BytePos(43), BytePos(47));
@@ -1463,6 +1539,25 @@ warning: confusable spelling
         assert_eq!(diagnostics.read().len(), 2);
     }
 
+    #[test]
+    fn test_message_catalog_translates_emitted_diagnostics() {
+        let (emitter, diagnostics) = InMemoryEmitter::new();
+        let mut catalog = MessageCatalog::new("test");
+        catalog.insert("test-error", "translated {what}");
+        catalog.insert("test-note", "translated note");
+        let dcx = DiagCtxt::new(Box::new(emitter))
+            .with_flags(|flags| flags.track_diagnostics = false)
+            .with_message_catalog(Some(std::sync::Arc::new(catalog)));
+
+        let msg = DiagMsg::translatable("test-error", "original {what}").arg("what", "error");
+        let _ = dcx.err(msg).note(DiagMsg::translatable("test-note", "original note")).emit();
+
+        let diagnostics = diagnostics.read();
+        assert_eq!(diagnostics[0].label(), "translated error");
+        assert_eq!(diagnostics[0].messages[0].0.id(), Some("test-error"));
+        assert_eq!(diagnostics[0].children[0].label(), "translated note");
+    }
+
     #[test]
     fn test_once_subdiagnostic_is_collected_once() {
         let (emitter, diagnostics) = InMemoryEmitter::new();
@@ -1650,7 +1745,9 @@ help: consider changing visibility and mutability
           "byte_end": 72,
           "byte_start": 66,
           "column_end": 23,
+          "column_end_utf16": 23,
           "column_start": 17,
+          "column_start_utf16": 17,
           "file_name": "<test.sol>",
           "is_primary": true,
           "label": null,
@@ -1679,7 +1776,9 @@ help: consider changing visibility and mutability
       "byte_end": 72,
       "byte_start": 66,
       "column_end": 23,
+      "column_end_utf16": 23,
       "column_start": 17,
+      "column_start_utf16": 17,
       "file_name": "<test.sol>",
       "is_primary": true,
       "label": null,
@@ -1736,7 +1835,9 @@ help: consider changing visibility and mutability
           "byte_end": 42,
           "byte_start": 36,
           "column_end": 26,
+          "column_end_utf16": 26,
           "column_start": 20,
+          "column_start_utf16": 20,
           "file_name": "<test.sol>",
           "is_primary": true,
           "label": null,
@@ -1757,7 +1858,9 @@ help: consider changing visibility and mutability
           "byte_end": 47,
           "byte_start": 43,
           "column_end": 31,
+          "column_end_utf16": 31,
           "column_start": 27,
+          "column_start_utf16": 27,
           "file_name": "<test.sol>",
           "is_primary": true,
           "label": null,
@@ -1786,7 +1889,9 @@ help: consider changing visibility and mutability
       "byte_end": 42,
       "byte_start": 36,
       "column_end": 26,
+      "column_end_utf16": 26,
       "column_start": 20,
+      "column_start_utf16": 20,
       "file_name": "<test.sol>",
       "is_primary": true,
       "label": null,
@@ -1807,7 +1912,9 @@ help: consider changing visibility and mutability
       "byte_end": 47,
       "byte_start": 43,
       "column_end": 31,
+      "column_end_utf16": 31,
       "column_start": 27,
+      "column_start_utf16": 27,
       "file_name": "<test.sol>",
       "is_primary": true,
       "label": null,
@@ -0,0 +1,65 @@
+//! Structured compiler events.
+//!
+//! Events are emitted as [`tracing`] events at the `INFO` level with the [`TARGET`] target, and
+//! can be written to a JSON Lines file with `--events <PATH>`. Unlike other log messages, they are
+//! a machine interface: event names and fields are stable, and are only ever added to.
+//!
+//! Every event has an `event` field with its name, followed by:
+//!
+//! | `event`          | Fields                                   |
+//! |------------------|------------------------------------------|
+//! | `file_parsed`    | `file`, `bytes`, `elapsed_us`            |
+//! | `item_lowered`   | `file`, `kind`, `name`                   |
+//! | `diag_emitted`   | `level`, `code` (optional), `message`    |
+//! | `pass_timing`    | `pass`, `elapsed_us`                     |
+//!
+//! - `file` is the display name of a source file.
+//! - `elapsed_us` is a duration in microseconds.
+//! - `kind` is the item kind, such as `contract`, `library`, `function`, or `struct`.
+//! - `pass` is one of `parsing`, `lowering`, `analysis`, or `emit`.
+//!
+//! Events are not emitted if `tracing` is compiled out with the `tracing-off` feature.
+
+use std::time::Instant;
+
+/// The `tracing` target of all structured events.
+pub const TARGET: &str = "solar::events";
+
+/// Emits a structured event. See the [`events`](crate::events) module for the available events.
+///
+/// # Examples
+///
+/// ```
+/// solar_interface::event!("pass_timing", pass = "parsing", elapsed_us = 42u64);
+/// ```
+#[macro_export]
+macro_rules! event {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        ::tracing::info!(target: $crate::events::TARGET, event = $name $(, $($fields)*)?)
+    };
+}
+
+/// Times a compiler pass, emitting a `pass_timing` event when dropped.
+#[must_use = "the pass is timed until the guard is dropped"]
+pub struct PassTimer {
+    pass: &'static str,
+    start: Instant,
+}
+
+impl PassTimer {
+    /// Starts timing the given pass.
+    pub fn new(pass: &'static str) -> Self {
+        Self { pass, start: Instant::now() }
+    }
+}
+
+impl Drop for PassTimer {
+    fn drop(&mut self) {
+        event!("pass_timing", pass = self.pass, elapsed_us = elapsed_us(self.start));
+    }
+}
+
+/// Returns the time elapsed since `start` in microseconds.
+pub fn elapsed_us(start: Instant) -> u64 {
+    start.elapsed().as_micros().try_into().unwrap_or(u64::MAX)
+}
@@ -14,6 +14,7 @@ scoped_tls::scoped_thread_local!(static SESSION_GLOBALS: SessionGlobals);
 pub(crate) struct SessionGlobals {
     pub(crate) symbol_interner: crate::symbol::Interner,
     pub(crate) source_map: Arc<SourceMap>,
+    pub(crate) ice: crate::panic_hook::IceState,
 }
 
 impl Default for SessionGlobals {
@@ -25,7 +26,11 @@ impl Default for SessionGlobals {
 impl SessionGlobals {
     /// Creates a new session globals object.
     pub(crate) fn new(source_map: Arc<SourceMap>) -> Self {
-        Self { symbol_interner: crate::symbol::Interner::fresh(), source_map }
+        Self {
+            symbol_interner: crate::symbol::Interner::fresh(),
+            source_map,
+            ice: Default::default(),
+        }
     }
 
     /// Sets this instance as the global instance for the duration of the closure.
@@ -12,6 +12,8 @@ extern crate tracing;
 pub mod diagnostics;
 use diagnostics::ErrorGuaranteed;
 
+pub mod events;
+
 mod globals;
 use globals::SessionGlobals;
 
@@ -1,11 +1,51 @@
 //! Functions for installing a custom panic hook.
+//!
+//! On an internal compiler error (ICE), the hook writes a report file to the system's temporary
+//! directory, or the one given with `-Zice-dir`, with the compiler version, arguments, panic
+//! message, the source file being processed, the most recent diagnostics, and a backtrace. With
+//! `-Zminimize-ice`, it also attempts to minimize the crashing source file by re-running the
+//! compiler on smaller versions of it, which are written to the same directory.
+//!
+//! The recent diagnostics and the options are kept per session in [`SessionGlobals`], so the hook
+//! only has them for panics on the session's threads.
 
-use crate::diagnostics::{DiagCtxt, ExplicitBug, FatalAbort};
-use std::panic::PanicHookInfo;
+use crate::{
+    SessionGlobals,
+    diagnostics::{Diag, DiagCtxt, ExplicitBug, FatalAbort},
+    source_map::FileName,
+};
+use std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    io,
+    panic::PanicHookInfo,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 const BUG_REPORT_URL: &str =
     "https://github.com/paradigmxyz/solar/issues/new/?labels=C-bug%2C+I-ICE&template=ice.yml";
 
+/// Set in the environment of the compiler processes spawned while minimizing an input.
+const MINIMIZING_ENV: &str = "SOLAR_ICE_MINIMIZING";
+
+/// The number of most recent diagnostics to include in ICE reports.
+const RECENT_DIAGNOSTICS: usize = 10;
+
+/// The maximum number of compiler runs to spend on minimizing an input.
+const MAX_MINIMIZATION_RUNS: usize = 500;
+
+/// The exit code of a process that panicked.
+const PANIC_EXIT_CODE: i32 = 101;
+
+thread_local! {
+    static CURRENT_FILE: RefCell<Option<FileName>> = const { RefCell::new(None) };
+}
+
 /// Install the compiler's default panic hook.
 pub fn install() {
     if std::env::var_os("RUST_BACKTRACE").is_none() {
@@ -29,6 +69,88 @@ pub fn install() {
     });
 }
 
+/// Records `name` as the file being processed on the current thread, until the returned guard is
+/// dropped.
+///
+/// The file is included in ICE reports.
+pub fn enter_file(name: &FileName) -> CurrentFileGuard {
+    let prev = CURRENT_FILE.with(|current| current.replace(Some(name.clone())));
+    CurrentFileGuard { prev }
+}
+
+/// Guard returned by [`enter_file`].
+#[must_use = "the file is only recorded until the guard is dropped"]
+pub struct CurrentFileGuard {
+    prev: Option<FileName>,
+}
+
+impl Drop for CurrentFileGuard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        CURRENT_FILE.with(|current| *current.borrow_mut() = prev);
+    }
+}
+
+/// The ICE reporting state of a session.
+#[derive(Debug, Default)]
+pub(crate) struct IceState {
+    /// The most recent diagnostics.
+    recent: Mutex<VecDeque<String>>,
+    /// The ICE options of the session.
+    opts: Mutex<IceOpts>,
+}
+
+/// ICE options, set from the session options.
+#[derive(Clone, Debug, Default)]
+struct IceOpts {
+    /// Whether to attempt to minimize the crashing source file.
+    minimize: bool,
+    /// The directory to write reports to, defaulting to the temporary directory.
+    dir: Option<PathBuf>,
+}
+
+impl IceOpts {
+    fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(std::env::temp_dir)
+    }
+}
+
+impl IceState {
+    /// Sets the options from `-Zminimize-ice` and `-Zice-dir`.
+    pub(crate) fn configure(&self, minimize: bool, dir: Option<PathBuf>) {
+        if let Ok(mut opts) = self.opts.lock() {
+            *opts = IceOpts { minimize, dir };
+        }
+    }
+
+    /// Records an emitted diagnostic to be included in ICE reports.
+    pub(crate) fn record_diagnostic(&self, diagnostic: &Diag) {
+        let Ok(mut recent) = self.recent.lock() else { return };
+        if recent.len() == RECENT_DIAGNOSTICS {
+            recent.pop_front();
+        }
+        let mut s = diagnostic.level.to_str().to_string();
+        if let Some(id) = diagnostic.id() {
+            let _ = write!(s, "[{id}]");
+        }
+        let _ = write!(s, ": {}", diagnostic.label());
+        recent.push_back(s);
+    }
+
+    /// Returns a snapshot of the options and recent diagnostics.
+    ///
+    /// Another thread may be panicking while holding a lock, in which case its data is skipped.
+    fn snapshot(&self) -> (IceOpts, Vec<String>) {
+        let opts = self.opts.try_lock().map(|opts| opts.clone()).unwrap_or_default();
+        let recent = self
+            .recent
+            .try_lock()
+            .map(|recent| recent.iter().cloned().collect())
+            .unwrap_or_default();
+        (opts, recent)
+    }
+}
+
 fn panic_hook(info: &PanicHookInfo<'_>) {
     let dcx = DiagCtxt::new_early();
 
@@ -37,7 +159,144 @@ fn panic_hook(info: &PanicHookInfo<'_>) {
         dcx.err("the compiler unexpectedly panicked; this is a bug.").emit();
     }
 
+    // Don't write reports for the inputs tried while minimizing.
+    if std::env::var_os(MINIMIZING_ENV).is_some() {
+        return;
+    }
+
     dcx.note(format!("we would appreciate a bug report: {BUG_REPORT_URL}")).emit();
+
+    let file = CURRENT_FILE.try_with(|current| current.borrow().clone()).ok().flatten();
+    let (opts, recent) =
+        SessionGlobals::try_with(|globals| globals.map(|globals| globals.ice.snapshot()))
+            .unwrap_or_default();
+    let report_path = match write_report(&opts.dir(), info, file.as_ref(), &recent) {
+        Ok(path) => {
+            dcx.note(format!(
+                "the compiler state was saved to `{}`; please attach it to the bug report",
+                path.display()
+            ))
+            .emit();
+            path
+        }
+        Err(e) => {
+            dcx.warn(format!("failed to write the ICE report: {e}")).emit();
+            return;
+        }
+    };
+
+    if !opts.minimize {
+        return;
+    }
+    let Some(FileName::Real(file)) = &file else {
+        dcx.warn("cannot minimize the input: the crashing source file is unknown").emit();
+        return;
+    };
+    let out = report_path.with_extension("min.sol");
+    match minimize(file, &out) {
+        Ok(()) => {
+            dcx.note(format!("a minimized reproducer was saved to `{}`", out.display())).emit()
+        }
+        Err(e) => dcx.warn(format!("failed to minimize `{}`: {e}", file.display())).emit(),
+    }
+}
+
+/// Writes the ICE report to `dir`, returning its path.
+fn write_report(
+    dir: &Path,
+    message: &dyn fmt::Display,
+    file: Option<&FileName>,
+    recent: &[String],
+) -> io::Result<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("solar-ice-{timestamp}-{}.txt", std::process::id()));
+
+    let mut report = String::new();
+    let _ = writeln!(report, "version: {}", crate::config::version::version());
+    let _ = writeln!(report, "args: {:?}", std::env::args_os().collect::<Vec<_>>());
+    let _ = match file {
+        Some(FileName::Real(path)) => writeln!(report, "file: {}", path.display()),
+        Some(FileName::Stdin) => writeln!(report, "file: <stdin>"),
+        Some(FileName::Custom(name)) => writeln!(report, "file: <{name}>"),
+        None => writeln!(report, "file: <unknown>"),
+    };
+    let _ = writeln!(report, "\n{message}");
+    let _ = writeln!(report, "\nrecent diagnostics:");
+    if recent.is_empty() {
+        let _ = writeln!(report, "<none>");
+    }
+    for diagnostic in recent {
+        let _ = writeln!(report, "{diagnostic}");
+    }
+    let _ = writeln!(report, "\nbacktrace:\n{}", Backtrace::force_capture());
+
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Minimizes `file` by removing chunks of lines for as long as the compiler still panics, writing
+/// the result to `out`.
+///
+/// The compiler is re-run with the same arguments, with `file` replaced by a candidate input
+/// written next to `out`. The user's source directory is never written to, so relative imports
+/// of `file` are resolved from the directory of `out`.
+fn minimize(file: &Path, out: &Path) -> io::Result<()> {
+    let args = std::env::args_os().skip(1).collect::<Vec<_>>();
+    let canonical = std::fs::canonicalize(file)?;
+    let Some(input_idx) = args.iter().position(|arg| {
+        Path::new(arg) == file || std::fs::canonicalize(arg).is_ok_and(|arg| arg == canonical)
+    }) else {
+        return Err(io::Error::other("the crashing file is not a command-line input"));
+    };
+    let exe = std::env::current_exe()?;
+    let src = std::fs::read_to_string(file)?;
+    let candidate_path = out.with_extension("candidate.sol");
+
+    let still_panics = |lines: &[&str]| -> io::Result<bool> {
+        std::fs::write(&candidate_path, lines.join("\n"))?;
+        let mut args = args.clone();
+        args[input_idx] = candidate_path.clone().into_os_string();
+        let status = Command::new(&exe)
+            .args(&args)
+            .env(MINIMIZING_ENV, "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        Ok(status.code() == Some(PANIC_EXIT_CODE))
+    };
+
+    // Delta debugging over lines: try removing chunks of decreasing size.
+    let mut lines = src.lines().collect::<Vec<_>>();
+    let mut runs = 0;
+    let mut chunk = lines.len().div_ceil(2);
+    let result = 'ddmin: {
+        while chunk > 0 {
+            let mut start = 0;
+            while start < lines.len() {
+                if runs >= MAX_MINIMIZATION_RUNS {
+                    break 'ddmin Ok(());
+                }
+                runs += 1;
+                let end = (start + chunk).min(lines.len());
+                let candidate = [&lines[..start], &lines[end..]].concat();
+                match still_panics(&candidate) {
+                    Ok(true) => lines = candidate,
+                    Ok(false) => start = end,
+                    Err(e) => break 'ddmin Err(e),
+                }
+            }
+            chunk /= 2;
+        }
+        Ok(())
+    };
+    let _ = std::fs::remove_file(&candidate_path);
+    result?;
+
+    let mut minimized = lines.join("\n");
+    minimized.push('\n');
+    std::fs::write(out, minimized)
 }
 
 #[cfg(feature = "nightly")]
@@ -55,3 +314,46 @@ where
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| hook_fn(&default_hook, info)));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Level;
+
+    #[test]
+    fn records_recent_diagnostics() {
+        let state = IceState::default();
+        for i in 0..RECENT_DIAGNOSTICS + 2 {
+            let mut diag = Diag::new(Level::Warning, format!("warning {i}"));
+            if i % 2 == 0 {
+                diag.code(crate::error_code!(1234));
+            }
+            state.record_diagnostic(&diag);
+        }
+        let (opts, recent) = state.snapshot();
+        assert!(!opts.minimize);
+        assert_eq!(opts.dir(), std::env::temp_dir());
+        assert_eq!(recent.len(), RECENT_DIAGNOSTICS);
+        assert_eq!(recent[0], "warning[1234]: warning 2");
+        assert_eq!(recent[1], "warning: warning 3");
+    }
+
+    #[test]
+    fn writes_report_to_ice_dir() {
+        let dir = std::env::temp_dir().join(format!("solar-ice-dir-{}", std::process::id()));
+        let state = IceState::default();
+        state.configure(true, Some(dir.clone()));
+        let (opts, _) = state.snapshot();
+        assert!(opts.minimize);
+
+        let file = FileName::Real("src/A.sol".into());
+        let recent = ["error: unresolved symbol `a`".to_string()];
+        let path = write_report(&opts.dir(), &"panicked at A.sol", Some(&file), &recent).unwrap();
+        assert_eq!(path.parent(), Some(dir.as_path()));
+        let report = std::fs::read_to_string(&path).unwrap();
+        assert!(report.contains("file: src/A.sol\n"), "{report}");
+        assert!(report.contains("\npanicked at A.sol\n"), "{report}");
+        assert!(report.contains("recent diagnostics:\nerror: unresolved symbol `a`\n"), "{report}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
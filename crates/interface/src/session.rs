@@ -1,13 +1,14 @@
 use crate::{
     ByteSymbol, ColorChoice, SessionGlobals, SourceMap, Symbol,
-    diagnostics::{DiagCtxt, EmittedDiagnostics},
+    diagnostics::{Diag, DiagCtxt, EmittedDiagnostics, MessageCatalog},
+    source_map::FilePathMapping,
 };
 use solar_config::{
     CompileOpts, CompilerOutput, CompilerStage, SINGLE_THREADED_TARGET, UnstableOpts,
 };
 use std::{
-    fmt,
-    path::Path,
+    fmt, io,
+    path::{Path, PathBuf},
     sync::{Arc, OnceLock},
 };
 
@@ -20,9 +21,20 @@ pub struct Session {
     pub dcx: DiagCtxt,
     /// The globals.
     globals: Arc<SessionGlobals>,
-    /// The rayon thread pool. This is spawned lazily on first use, rather than always constructing
+    /// The rayon thread pool.
+    thread_pool: ThreadPool,
+}
+
+/// The rayon thread pool of a [`Session`].
+enum ThreadPool {
+    /// Owned by the session. This is spawned lazily on first use, rather than always constructing
     /// one with `SessionBuilder`.
-    thread_pool: OnceLock<rayon::ThreadPool>,
+    Owned(OnceLock<rayon::ThreadPool>),
+    /// Provided by the caller with [`SessionBuilder::with_thread_pool`].
+    Shared(Arc<rayon::ThreadPool>),
+    /// Disabled with [`SessionBuilder::with_thread_pool`]. Parallel work runs in the current
+    /// thread's thread pool, or in the global one.
+    None,
 }
 
 impl Default for Session {
@@ -45,8 +57,9 @@ impl fmt::Debug for Session {
 #[must_use = "builders don't do anything unless you call `build`"]
 pub struct SessionBuilder {
     dcx: Option<DiagCtxt>,
-    globals: Option<SessionGlobals>,
+    globals: Option<Arc<SessionGlobals>>,
     opts: Option<CompileOpts>,
+    thread_pool: Option<Option<Arc<rayon::ThreadPool>>>,
 }
 
 impl SessionBuilder {
@@ -99,6 +112,16 @@ impl SessionBuilder {
         self.dcx(DiagCtxt::with_buffer_emitter(Some(sm), color_choice))
     }
 
+    /// Sets the diagnostic context to an emitter that calls `callback` with each emitted
+    /// diagnostic.
+    ///
+    /// This is useful for embedders that need structured diagnostics instead of rendered output.
+    #[inline]
+    pub fn with_callback_emitter(mut self, callback: impl FnMut(Diag) + Send + 'static) -> Self {
+        let sm = self.get_source_map();
+        self.dcx(DiagCtxt::with_callback_emitter(Some(sm), callback))
+    }
+
     /// Sets the diagnostic context to a silent emitter.
     #[inline]
     pub fn with_silent_emitter(self, fatal_note: Option<String>) -> Self {
@@ -119,13 +142,64 @@ impl SessionBuilder {
         self
     }
 
+    /// Sets the rayon thread pool to run in, instead of having the session build its own.
+    ///
+    /// This lets host applications, like language servers, control the lifetime of the threads,
+    /// and avoid deadlocks caused by blocking on a nested thread pool.
+    ///
+    /// If `pool` is `Some`, [`Session::enter`] runs in the given thread pool, and the number of
+    /// threads is set to the pool's. The pool's threads must have the session globals set, which
+    /// can be done by building it with [`thread_spawn_handler`](Self::thread_spawn_handler).
+    ///
+    /// If `pool` is `None`, no thread pool is created, and [`Session::enter`] runs in the current
+    /// thread. Parallel work then runs in the current thread's thread pool, or in the global one,
+    /// whose threads must also have the session globals set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use solar_interface::{ColorChoice, Session};
+    /// use std::sync::Arc;
+    ///
+    /// let mut builder = Session::builder().with_buffer_emitter(ColorChoice::Never);
+    /// let pool = rayon::ThreadPoolBuilder::new()
+    ///     .num_threads(2)
+    ///     .spawn_handler(builder.thread_spawn_handler())
+    ///     .build()
+    ///     .unwrap();
+    /// let sess = builder.with_thread_pool(Some(Arc::new(pool))).build();
+    /// assert_eq!(sess.threads(), 2);
+    /// ```
+    pub fn with_thread_pool(mut self, pool: Option<Arc<rayon::ThreadPool>>) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
+    /// Returns a spawn handler for [`rayon::ThreadPoolBuilder::spawn_handler`] which sets the
+    /// globals of the session being built on each thread of the pool.
+    ///
+    /// See [`with_thread_pool`](Self::with_thread_pool) for more details.
+    ///
+    /// # Panics
+    ///
+    /// The returned handler holds on to the session globals, so calling
+    /// [`source_map`](Self::source_map) after this method will panic.
+    pub fn thread_spawn_handler(
+        &mut self,
+    ) -> impl FnMut(rayon::ThreadBuilder) -> io::Result<()> + Send + use<> {
+        let globals = self.globals.get_or_insert_default().clone();
+        move |thread| spawn_with_globals(&globals, thread)
+    }
+
     /// Gets the source map from the diagnostics context.
     fn get_source_map(&mut self) -> Arc<SourceMap> {
-        self.get_globals().source_map.clone()
+        self.globals.get_or_insert_default().source_map.clone()
     }
 
+    #[track_caller]
     fn get_globals(&mut self) -> &mut SessionGlobals {
-        self.globals.get_or_insert_default()
+        Arc::get_mut(self.globals.get_or_insert_default())
+            .expect("cannot modify the session globals after calling `thread_spawn_handler`")
     }
 
     /// Returns a mutable reference to the options.
@@ -146,14 +220,22 @@ impl SessionBuilder {
     /// - the source map in the diagnostics context does not match the one set in the builder
     #[track_caller]
     pub fn build(mut self) -> Session {
-        let opts = self.opts.take();
+        let mut opts = self.opts.take();
+        let thread_pool = match self.thread_pool.take() {
+            None => ThreadPool::Owned(OnceLock::new()),
+            Some(Some(pool)) => {
+                opts.get_or_insert_default().threads = pool.current_num_threads().into();
+                ThreadPool::Shared(pool)
+            }
+            Some(None) => ThreadPool::None,
+        };
         let mut dcx = self.dcx.take().unwrap_or_else(|| {
             opts.as_ref()
                 .map(DiagCtxt::from_opts)
                 .unwrap_or_else(|| panic!("either diagnostics context or options must be set"))
         });
         let sess = Session {
-            globals: Arc::new(match self.globals.take() {
+            globals: match self.globals.take() {
                 Some(globals) => {
                     // Check that the source map matches the one in the diagnostics context.
                     if let Some(sm) = dcx.source_map_mut() {
@@ -167,12 +249,12 @@ impl SessionBuilder {
                 None => {
                     // Set the source map from the diagnostics context.
                     let sm = dcx.source_map_mut().cloned().unwrap_or_default();
-                    SessionGlobals::new(sm)
+                    Arc::new(SessionGlobals::new(sm))
                 }
-            }),
+            },
             dcx,
             opts: opts.unwrap_or_default(),
-            thread_pool: OnceLock::new(),
+            thread_pool,
         };
         sess.reconfigure();
         debug!(version = %solar_config::version::SEMVER_VERSION, "created new session");
@@ -207,9 +289,26 @@ impl Session {
     pub fn validate(&self) -> crate::Result<()> {
         let mut result = Ok(());
         result = result.and(self.check_unique("emit", &self.opts.emit));
+        result = result.and(self.load_message_catalog());
         result
     }
 
+    /// Loads the catalog given with `-Zmessage-catalog` and uses it to translate diagnostics.
+    fn load_message_catalog(&self) -> crate::Result<()> {
+        let Some(path) = &self.opts.unstable.message_catalog else { return Ok(()) };
+        let src = std::fs::read_to_string(path).map_err(|e| {
+            let msg = format!("failed to read message catalog `{}`: {e}", path.display());
+            self.dcx.err(msg).emit()
+        })?;
+        let locale = path.file_stem().unwrap_or_default().to_string_lossy();
+        let catalog = MessageCatalog::parse(locale, &src).map_err(|e| {
+            let msg = format!("failed to parse message catalog `{}`: {e}", path.display());
+            self.dcx.err(msg).emit()
+        })?;
+        self.dcx.set_message_catalog(Some(Arc::new(catalog)));
+        Ok(())
+    }
+
     /// Reconfigures inner state to match any new options.
     ///
     /// Call this after updating options.
@@ -228,6 +327,24 @@ impl Session {
             };
             self.source_map().set_base_path(new_base_path);
         }
+
+        let mut mapping = Vec::new();
+        if self.opts.reproducible {
+            let file_loader = self.source_map().file_loader();
+            let base_path = self.opts.base_path.clone().or_else(|| std::env::current_dir().ok());
+            for path in base_path.iter().chain(&self.opts.include_paths) {
+                if let Ok(path) = file_loader.canonicalize_path(path) {
+                    mapping.push((path, PathBuf::new()));
+                }
+            }
+        }
+        // Explicit remappings take precedence over the ones above.
+        mapping.extend(self.opts.remap_path_prefix.iter().cloned());
+        self.source_map().set_path_mapping(FilePathMapping::new(mapping));
+
+        self.globals
+            .ice
+            .configure(self.opts.unstable.minimize_ice, self.opts.unstable.ice_dir.clone());
     }
 
     fn check_unique<T: Eq + std::hash::Hash + std::fmt::Display>(
@@ -382,6 +499,8 @@ impl Session {
     ///
     /// The thread pool and globals are stored in this [`Session`] itself, meaning multiple
     /// consecutive calls to [`enter`](Self::enter) will share the same globals and resources.
+    ///
+    /// See [`SessionBuilder::with_thread_pool`] to use a different thread pool, or none at all.
     #[track_caller]
     pub fn enter<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
         if in_rayon() {
@@ -397,7 +516,12 @@ impl Session {
             }
         }
 
-        self.enter_sequential(|| self.thread_pool().install(f))
+        match &self.thread_pool {
+            ThreadPool::Owned(_) => self.enter_sequential(|| self.thread_pool().install(f)),
+            // The calling thread might not be part of the pool, so set the globals inside of it.
+            ThreadPool::Shared(pool) => pool.install(|| self.enter_sequential(f)),
+            ThreadPool::None => self.enter_sequential(f),
+        }
     }
 
     /// Sets up the session globals and executes the given closure in the current thread.
@@ -455,21 +579,13 @@ impl Session {
     }
 
     fn thread_pool(&self) -> &rayon::ThreadPool {
-        self.thread_pool.get_or_init(|| {
+        let ThreadPool::Owned(thread_pool) = &self.thread_pool else {
+            unreachable!("thread pool is not owned by the session")
+        };
+        thread_pool.get_or_init(|| {
             trace!(threads = self.threads(), "building rayon thread pool");
             self.thread_pool_builder()
-                .spawn_handler(|thread| {
-                    let mut builder = std::thread::Builder::new();
-                    if let Some(name) = thread.name() {
-                        builder = builder.name(name.to_string());
-                    }
-                    if let Some(size) = thread.stack_size() {
-                        builder = builder.stack_size(size);
-                    }
-                    let globals = self.globals.clone();
-                    builder.spawn(move || globals.set(|| thread.run()))?;
-                    Ok(())
-                })
+                .spawn_handler(|thread| spawn_with_globals(&self.globals, thread))
                 .build()
                 .unwrap_or_else(|e| self.handle_thread_pool_build_error(e))
         })
@@ -502,6 +618,23 @@ impl Session {
     }
 }
 
+/// Spawns a rayon thread with the given session globals set.
+fn spawn_with_globals(
+    globals: &Arc<SessionGlobals>,
+    thread: rayon::ThreadBuilder,
+) -> io::Result<()> {
+    let mut builder = std::thread::Builder::new();
+    if let Some(name) = thread.name() {
+        builder = builder.name(name.to_string());
+    }
+    if let Some(size) = thread.stack_size() {
+        builder = builder.stack_size(size);
+    }
+    let globals = globals.clone();
+    builder.spawn(move || globals.set(|| thread.run()))?;
+    Ok(())
+}
+
 fn reentrant_log() {
     debug!(
         "running in the current thread's rayon thread pool; \
@@ -565,6 +698,25 @@ mod tests {
         let _ = Session::builder().with_stderr_emitter().build();
     }
 
+    #[test]
+    fn callback_emitter() {
+        let diagnostics = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sess = Session::builder()
+            .with_callback_emitter({
+                let diagnostics = diagnostics.clone();
+                move |diag| diagnostics.lock().unwrap().push(diag)
+            })
+            .build();
+        let _ = sess.dcx.err("first").emit();
+        let _ = sess.dcx.warn("second").emit();
+
+        let diagnostics = diagnostics.lock().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].label(), "first");
+        assert_eq!(diagnostics[1].label(), "second");
+        assert!(sess.dcx.has_errors().is_err());
+    }
+
     #[test]
     fn not_builder() {
         let _ = Session::new(CompileOpts::default());
@@ -691,6 +843,53 @@ mod tests {
         assert!(!in_rayon());
     }
 
+    #[test]
+    fn enter_custom_thread_pool() {
+        let mut builder = Session::builder().with_buffer_emitter(ColorChoice::Never);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .spawn_handler(builder.thread_spawn_handler())
+            .build()
+            .unwrap();
+        let pool = Arc::new(pool);
+        let sess = builder.with_thread_pool(Some(pool.clone())).build();
+        assert_eq!(sess.threads(), 2);
+        sess.source_map().new_source_file(PathBuf::from("test"), "abcd").unwrap();
+
+        assert!(!in_rayon());
+        sess.enter(|| {
+            assert!(pool.current_thread_index().is_some());
+            use_globals_parallel(&sess);
+        });
+        pool.install(|| {
+            use_globals();
+            sess.enter(|| use_globals_parallel(&sess));
+        });
+        assert!(!in_rayon());
+    }
+
+    #[test]
+    fn enter_without_thread_pool() {
+        let sess = Session::builder()
+            .with_buffer_emitter(ColorChoice::Never)
+            .single_threaded()
+            .with_thread_pool(None)
+            .build();
+        sess.source_map().new_source_file(PathBuf::from("test"), "abcd").unwrap();
+        sess.enter(|| {
+            assert!(!in_rayon());
+            use_globals();
+        });
+    }
+
+    #[test]
+    #[should_panic = "cannot modify the session globals after calling `thread_spawn_handler`"]
+    fn source_map_after_spawn_handler() {
+        let mut builder = Session::builder();
+        let _handler = builder.thread_spawn_handler();
+        let _ = builder.source_map(Default::default());
+    }
+
     #[test]
     fn enter_different_nested_sessions() {
         let sess1 = enter_tests_session();
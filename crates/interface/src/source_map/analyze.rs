@@ -31,6 +31,20 @@ pub(crate) fn analyze_source_file(src: &str) -> (Vec<RelativeBytePos>, Vec<Multi
     (lines, multi_byte_chars)
 }
 
+/// Finds all multi-byte characters in a SourceFile whose line starts are already known.
+pub(crate) fn analyze_multi_byte_chars(src: &str) -> Vec<MultiByteChar> {
+    if src.is_ascii() {
+        return Vec::new();
+    }
+    src.char_indices()
+        .filter(|(_, c)| !c.is_ascii())
+        .map(|(pos, c)| MultiByteChar {
+            pos: RelativeBytePos::from_usize(pos),
+            bytes: c.len_utf8() as u8,
+        })
+        .collect()
+}
+
 fn analyze_source_file_dispatch(
     src: &str,
     lines: &mut Vec<RelativeBytePos>,
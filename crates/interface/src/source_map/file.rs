@@ -1,5 +1,7 @@
+use super::SourceText;
 use crate::{BytePos, CharPos, pos::RelativeBytePos};
 use std::{
+    borrow::Cow,
     fmt, io,
     ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
@@ -15,6 +17,31 @@ pub struct MultiByteChar {
     pub bytes: u8,
 }
 
+/// The unit in which a column is counted from the start of its line.
+///
+/// Editors and tools disagree on this, so each consumer picks the one it reports columns in. They
+/// only differ on lines containing non-ASCII characters, such as in comments and strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnEncoding {
+    /// UTF-8 code units, or bytes.
+    Utf8,
+    /// UTF-16 code units, as used by the Language Server Protocol and JavaScript.
+    Utf16,
+    /// Unicode scalar values, or [`char`]s, as used by [`Loc::col`](crate::source_map::Loc::col).
+    Utf32,
+}
+
+impl ColumnEncoding {
+    /// Returns the length of `s` in this encoding.
+    pub fn len(self, s: &str) -> usize {
+        match self {
+            Self::Utf8 => s.len(),
+            Self::Utf16 => s.chars().map(char::len_utf16).sum(),
+            Self::Utf32 => s.chars().count(),
+        }
+    }
+}
+
 /// The name of a source file.
 ///
 /// This is used as the key in the source map. See
@@ -107,9 +134,11 @@ impl FileName {
     /// Displays the filename.
     #[inline]
     pub fn display(&self) -> FileNameDisplay<'_> {
-        let base_path =
-            crate::SessionGlobals::try_with(|g| g.and_then(|g| g.source_map.base_path()));
-        FileNameDisplay { inner: self, base_path }
+        let (base_path, path_mapping) = crate::SessionGlobals::try_with(|g| {
+            g.map(|g| (g.source_map.base_path(), Some(g.source_map.path_mapping())))
+                .unwrap_or_default()
+        });
+        FileNameDisplay { inner: self, base_path, path_mapping }
     }
 
     /// Returns the path if the file name is a real file.
@@ -128,12 +157,18 @@ impl FileName {
 pub struct FileNameDisplay<'a> {
     pub(crate) inner: &'a FileName,
     pub(crate) base_path: Option<PathBuf>,
+    pub(crate) path_mapping: Option<Arc<FilePathMapping>>,
 }
 
 impl fmt::Display for FileNameDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.inner {
             FileName::Real(path) => {
+                if let Some(mapping) = &self.path_mapping
+                    && let (mapped, true) = mapping.map_prefix(path)
+                {
+                    return mapped.display().fmt(f);
+                }
                 let path = if let Some(base_path) = &self.base_path
                     && let Ok(rpath) = path.strip_prefix(base_path)
                 {
@@ -149,6 +184,46 @@ impl fmt::Display for FileNameDisplay<'_> {
     }
 }
 
+/// A list of path prefix remappings applied when displaying file names.
+///
+/// Mappings are checked in reverse order, so later mappings take precedence over earlier ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilePathMapping {
+    mapping: Vec<(PathBuf, PathBuf)>,
+}
+
+impl FilePathMapping {
+    /// Creates a new mapping from a list of `(from, to)` path prefixes.
+    pub fn new(mapping: Vec<(PathBuf, PathBuf)>) -> Self {
+        Self { mapping }
+    }
+
+    /// Returns `true` if there are no mappings.
+    pub fn is_empty(&self) -> bool {
+        self.mapping.is_empty()
+    }
+
+    /// Returns the `(from, to)` path prefixes.
+    pub fn mapping(&self) -> &[(PathBuf, PathBuf)] {
+        &self.mapping
+    }
+
+    /// Applies the mapping to the given path.
+    ///
+    /// Returns the remapped path and `true` if a mapping was applied, or the original path and
+    /// `false` otherwise.
+    pub fn map_prefix<'a>(&self, path: impl Into<Cow<'a, Path>>) -> (Cow<'a, Path>, bool) {
+        let path = path.into();
+        for (from, to) in self.mapping.iter().rev() {
+            if let Ok(rest) = path.strip_prefix(from) {
+                let remapped = if rest.as_os_str().is_empty() { to.clone() } else { to.join(rest) };
+                return (Cow::Owned(remapped), true);
+            }
+        }
+        (path, false)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct SourceFileId(u64);
 
@@ -179,6 +254,43 @@ impl From<OffsetOverflowError> for io::Error {
     }
 }
 
+/// The locations of line beginnings in a [`SourceFile`].
+#[derive(Clone)]
+enum SourceLines {
+    /// Line starts found by scanning the source.
+    Table(Vec<RelativeBytePos>),
+    /// The line index of the text that the file was created from.
+    Text(SourceText),
+}
+
+impl SourceLines {
+    fn len(&self) -> usize {
+        match self {
+            Self::Table(lines) => lines.len(),
+            Self::Text(text) => text.count_lines(),
+        }
+    }
+
+    fn get(&self, line: usize) -> Option<RelativeBytePos> {
+        match self {
+            Self::Table(lines) => lines.get(line).copied(),
+            Self::Text(text) => (line < text.count_lines())
+                .then(|| RelativeBytePos::from_usize(text.line_start(line))),
+        }
+    }
+
+    /// Returns the index of the line containing `pos`, or `None` if there are no lines.
+    fn lookup(&self, pos: RelativeBytePos) -> Option<usize> {
+        match self {
+            Self::Table(lines) => lines.partition_point(|x| x <= &pos).checked_sub(1),
+            Self::Text(text) => {
+                let last = text.count_lines().checked_sub(1)?;
+                Some(text.line_of_byte(pos.to_usize().min(text.len())).min(last))
+            }
+        }
+    }
+}
+
 /// A single source in the `SourceMap`.
 #[derive(Clone, derive_more::Debug)]
 #[non_exhaustive]
@@ -196,7 +308,7 @@ pub struct SourceFile {
     pub source_len: RelativeBytePos,
     /// Locations of lines beginnings in the source code.
     #[debug(skip)]
-    lines: Vec<RelativeBytePos>,
+    lines: SourceLines,
     /// Locations of multi-byte characters in the source code.
     #[debug(skip)]
     pub multibyte_chars: Vec<MultiByteChar>,
@@ -240,17 +352,37 @@ impl SourceFile {
             src: Arc::new(src),
             start_pos: BytePos::from_u32(0),
             source_len: RelativeBytePos::from_u32(source_len),
-            lines,
+            lines: SourceLines::Table(lines),
             multibyte_chars,
         })
     }
 
-    pub fn lines(&self) -> &[RelativeBytePos] {
-        &self.lines
+    /// Creates a new `SourceFile` from a snapshot of `text`, sharing its line index.
+    ///
+    /// Use the [`SourceMap`](crate::SourceMap) methods instead.
+    pub(crate) fn from_text(
+        name: FileName,
+        id: SourceFileId,
+        text: &SourceText,
+    ) -> Result<Self, OffsetOverflowError> {
+        debug_assert_eq!(id, SourceFileId::new(&name));
+        let source_len = u32::try_from(text.len()).map_err(|_| OffsetOverflowError(()))?;
+
+        // The lexer needs contiguous text, but the rope already indexes the line starts.
+        let src = text.to_string();
+        let multibyte_chars = super::analyze::analyze_multi_byte_chars(&src);
+        Ok(Self {
+            name,
+            src: Arc::new(src),
+            start_pos: BytePos::from_u32(0),
+            source_len: RelativeBytePos::from_u32(source_len),
+            lines: SourceLines::Text(text.clone()),
+            multibyte_chars,
+        })
     }
 
     pub fn count_lines(&self) -> usize {
-        self.lines().len()
+        self.lines.len()
     }
 
     #[inline]
@@ -273,7 +405,7 @@ impl SourceFile {
     /// number. If the source_file is empty or the position is located before the
     /// first line, `None` is returned.
     pub fn lookup_line(&self, pos: RelativeBytePos) -> Option<usize> {
-        self.lines().partition_point(|x| x <= &pos).checked_sub(1)
+        self.lines.lookup(pos)
     }
 
     pub fn line_bounds(&self, line_index: usize) -> Range<BytePos> {
@@ -281,19 +413,18 @@ impl SourceFile {
             return self.start_pos..self.start_pos;
         }
 
-        let lines = self.lines();
-        assert!(line_index < lines.len());
-        if line_index == (lines.len() - 1) {
-            self.absolute_position(lines[line_index])..self.end_position()
-        } else {
-            self.absolute_position(lines[line_index])..self.absolute_position(lines[line_index + 1])
-        }
+        let start = self.lines.get(line_index).expect("line index out of bounds");
+        let end = match self.lines.get(line_index + 1) {
+            Some(next) => self.absolute_position(next),
+            None => self.end_position(),
+        };
+        self.absolute_position(start)..end
     }
 
     /// Returns the relative byte position of the start of the line at the given
     /// 0-based line index.
     pub fn line_position(&self, line_number: usize) -> Option<usize> {
-        self.lines().get(line_number).map(|x| x.to_usize())
+        self.lines.get(line_number).map(|x| x.to_usize())
     }
 
     /// Converts a `RelativeBytePos` to a `CharPos` relative to the `SourceFile`.
@@ -325,16 +456,27 @@ impl SourceFile {
         match self.lookup_line(pos) {
             Some(a) => {
                 let line = a + 1; // Line numbers start at 1
-                let linebpos = self.lines()[a];
+                let linebpos = self.line_text_start(a);
                 let linechpos = self.bytepos_to_file_charpos(linebpos);
-                let col = chpos - linechpos;
-                assert!(chpos >= linechpos);
+                // Positions inside the byte order mark are at the start of the first line.
+                let col = CharPos(chpos.0.saturating_sub(linechpos.0));
                 (line, col)
             }
             None => (0, chpos),
         }
     }
 
+    /// Looks up the (0-based) line index and (0-based) column offset of the given `BytePos`, with
+    /// the column counted in `encoding` units.
+    pub fn lookup_line_col(&self, pos: BytePos, encoding: ColumnEncoding) -> (usize, usize) {
+        let pos = self.relative_position(pos);
+        let line = self.lookup_line(pos).unwrap_or(0);
+        let line_start =
+            if line < self.lines.len() { self.line_text_start(line).to_usize() } else { 0 };
+        let pos = pos.to_usize().max(line_start);
+        (line, encoding.len(&self.src[line_start..pos]))
+    }
+
     /// Looks up the file's (1-based) line number, (0-based `CharPos`) column offset, and (0-based)
     /// column offset when displayed, for a given `BytePos`.
     pub fn lookup_file_pos_with_col_display(&self, pos: BytePos) -> (usize, CharPos, usize) {
@@ -362,6 +504,9 @@ impl SourceFile {
 
     /// Gets a line from the list of pre-computed line-beginnings.
     /// The line number here is 0-based.
+    ///
+    /// The returned text excludes the line terminator, including the `\r` of a `\r\n`, and the
+    /// byte order mark at the start of the file, if any.
     pub fn get_line(&self, line_number: usize) -> Option<&str> {
         fn get_until_newline(src: &str, begin: usize) -> &str {
             // We can't use `lines.get(line_number+1)` because we might
@@ -369,13 +514,26 @@ impl SourceFile {
             // line is the last one we have line info for.
             let slice = &src[begin..];
             match slice.find('\n') {
-                Some(e) => &slice[..e],
+                Some(e) => slice[..e].strip_suffix('\r').unwrap_or(&slice[..e]),
                 None => slice,
             }
         }
 
-        let start = self.lines().get(line_number)?.to_usize();
-        Some(get_until_newline(&self.src, start))
+        self.lines.get(line_number)?;
+        Some(get_until_newline(&self.src, self.line_text_start(line_number).to_usize()))
+    }
+
+    /// Returns the start of the text of the given 0-based line, which skips the byte order mark
+    /// at the start of the file, if any.
+    ///
+    /// The line must exist.
+    fn line_text_start(&self, line_number: usize) -> RelativeBytePos {
+        let start = self.lines.get(line_number).expect("line index out of bounds");
+        if line_number == 0 && self.src.starts_with('\u{feff}') {
+            start + RelativeBytePos::from_usize('\u{feff}'.len_utf8())
+        } else {
+            start
+        }
     }
 
     /// Gets a slice of the source text between two lines, including the
@@ -389,9 +547,8 @@ impl SourceFile {
         }
 
         let (start, end) = range.into_inner();
-        let lines = self.lines();
-        let start = lines.get(start)?.to_usize();
-        let end = lines.get(end)?.to_usize();
+        let start = self.lines.get(start)?.to_usize();
+        let end = self.lines.get(end)?.to_usize();
         Some(get_until_newline(&self.src, start, end))
     }
 
@@ -405,7 +405,8 @@ impl<'a> FileResolver<'a> {
                 .source_map()
                 // Store the file with `rpath` as the name instead of `path`.
                 // In case of symlinks we want to reference the symlink path, not the target path.
-                .load_file_with_name(rpath.to_path_buf().into(), &path)
+                // Files reachable through multiple paths are deduplicated by canonical path.
+                .load_file_deduplicated(rpath.to_path_buf().into(), &path)
                 .map(Some)
                 .map_err(|e| ResolveError::ReadFile(path, e));
         }
@@ -635,6 +636,43 @@ mod tests {
         assert_eq!(resolved.unwrap().name.as_real(), Some(Path::new("B.sol")));
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn deduplicates_files_by_canonical_path() {
+        let tmp = tempfile::Builder::new().prefix("solar-file-resolver-test").tempdir().unwrap();
+        let base_path = tmp.path().to_path_buf();
+        for path in ["lib/A.sol", "vendor/A.sol"] {
+            let path = base_path.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "contract A {}").unwrap();
+        }
+        std::os::unix::fs::symlink(base_path.join("lib"), base_path.join("node_modules")).unwrap();
+
+        let sm = SourceMap::empty();
+        sm.set_base_path(Some(base_path.clone()));
+        let mut file_resolver = FileResolver::new(&sm);
+        file_resolver.set_current_dir(&base_path);
+        file_resolver.add_import_remapping("@lib/=node_modules/".parse().unwrap());
+
+        let a = file_resolver.resolve_file(Path::new("lib/A.sol"), None).unwrap();
+        let remapped = file_resolver.resolve_file(Path::new("@lib/A.sol"), None).unwrap();
+        let vendored = file_resolver.resolve_file(Path::new("vendor/A.sol"), None).unwrap();
+        assert!(Arc::ptr_eq(&a, &remapped));
+        // Same contents, but a different file.
+        assert!(!Arc::ptr_eq(&a, &vendored));
+        assert_eq!(sm.files().len(), 2);
+        assert_eq!(a.name.as_real(), Some(base_path.join("lib/A.sol").as_path()));
+        assert_eq!(
+            sm.aliases(&a),
+            [crate::source_map::FileName::real(base_path.join("node_modules/A.sol"))]
+        );
+        assert!(sm.aliases(&vendored).is_empty());
+
+        // Aliases are resolved from the cache.
+        let alias = file_resolver.get_file(Path::new("node_modules/A.sol")).unwrap();
+        assert!(Arc::ptr_eq(&a, &alias));
+    }
+
     #[test]
     fn direct_import_reuses_preloaded_source_unit_name() {
         let tmp = tempfile::Builder::new().prefix("solar-file-resolver-test").tempdir().unwrap();
@@ -4,14 +4,17 @@ use crate::{BytePos, CharPos, Span};
 use once_map::OnceMap;
 use solar_data_structures::{
     fmt,
-    map::FxBuildHasher,
+    map::{FxBuildHasher, FxHashMap},
     sync::{RwLock, RwLockReadGuard},
 };
 use std::{
     io::{self, Read},
     ops::Range,
     path::{Path, PathBuf},
-    sync::{Arc, OnceLock},
+    sync::{
+        Arc, OnceLock, Weak,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 mod analyze;
@@ -22,6 +25,9 @@ pub use file::*;
 mod file_resolver;
 pub use file_resolver::{FileResolver, ResolveError};
 
+mod text;
+pub use text::SourceText;
+
 #[cfg(test)]
 mod tests;
 
@@ -175,12 +181,21 @@ impl FileLoader for RealFileLoader {
 /// Stores all the sources of the current compilation session.
 #[derive(derive_more::Debug)]
 pub struct SourceMap {
-    // INVARIANT: `source_files` is monotonic.
+    // INVARIANT: `source_files` is sorted by `start_pos`.
     source_files: RwLock<Vec<Arc<SourceFile>>>,
     #[debug(skip)]
-    id_to_file: OnceMap<SourceFileId, Arc<SourceFile>, FxBuildHasher>,
+    id_to_file: RwLock<OnceMap<SourceFileId, Arc<SourceFile>, FxBuildHasher>>,
+    /// Whether files have been removed, leaving position ranges that can be reused by new files.
+    has_gaps: AtomicBool,
+    /// Files loaded with `load_file_deduplicated`, by canonical path.
+    #[debug(skip)]
+    canonical_to_file: RwLock<FxHashMap<PathBuf, Weak<SourceFile>>>,
+    /// The other names of deduplicated files, by the ID of the file's own name.
+    #[debug(skip)]
+    aliases: RwLock<FxHashMap<SourceFileId, Vec<FileName>>>,
 
     base_path: RwLock<Option<PathBuf>>,
+    path_mapping: RwLock<Arc<FilePathMapping>>,
     #[debug(skip)]
     file_loader: OnceLock<Box<dyn FileLoader>>,
 }
@@ -197,7 +212,11 @@ impl SourceMap {
         Self {
             source_files: Default::default(),
             id_to_file: Default::default(),
+            has_gaps: Default::default(),
+            canonical_to_file: Default::default(),
+            aliases: Default::default(),
             base_path: Default::default(),
+            path_mapping: Default::default(),
             file_loader: Default::default(),
         }
     }
@@ -210,10 +229,67 @@ impl SourceMap {
     /// Clears the source map, returning all the contained `SourceFile`s.
     #[must_use]
     pub fn take(&mut self) -> Vec<Arc<SourceFile>> {
-        self.id_to_file.clear();
+        self.id_to_file.get_mut().clear();
+        *self.has_gaps.get_mut() = false;
+        self.canonical_to_file.get_mut().clear();
+        self.aliases.get_mut().clear();
         std::mem::take(self.source_files.get_mut())
     }
 
+    /// Retains only the source files for which `f` returns `true`, removing all the others.
+    ///
+    /// This allows long-lived processes, like language servers and watch mode, to reuse the same
+    /// source map across compilations, removing files which have been closed or modified so that
+    /// they can be loaded again.
+    ///
+    /// The position ranges of removed files are reused by new files, so spans pointing into removed
+    /// files must not be used anymore.
+    pub fn retain_files(&self, mut f: impl FnMut(&SourceFile) -> bool) {
+        self.retain_files_inner(|file, _| f(file));
+    }
+
+    /// Removes the source files that are not referenced outside of the source map, returning the
+    /// number of removed files.
+    ///
+    /// Files are kept alive by the [`Arc`]s returned from the source map, such as the ones held by
+    /// parsed sources, so this only evicts files which are no longer used, like the ones closed
+    /// in an editor. This keeps the memory usage of long-lived sessions bounded.
+    ///
+    /// The position ranges of evicted files are reused by new files, so spans pointing into
+    /// evicted files must not be used anymore.
+    pub fn evict_unused_files(&self) -> usize {
+        // One reference in `source_files`, and one in `id_to_file` for each name.
+        self.retain_files_inner(|file, aliases| Arc::strong_count(file) > 2 + aliases)
+    }
+
+    /// Calls `f` with each file and its number of aliases.
+    fn retain_files_inner(&self, mut f: impl FnMut(&Arc<SourceFile>, usize) -> bool) -> usize {
+        let id_to_file = &mut *self.id_to_file.write();
+        let aliases = &mut *self.aliases.write();
+        let source_files = &mut *self.source_files.write();
+        let len = source_files.len();
+        source_files.retain(|file| {
+            let id = SourceFileId::new(&file.name);
+            let keep = f(file, aliases.get(&id).map_or(0, Vec::len));
+            if !keep {
+                trace!(name=%file.name.display(), "removing from source map");
+                id_to_file.remove(&id);
+                for alias in aliases.remove(&id).into_iter().flatten() {
+                    id_to_file.remove(&SourceFileId::new(&alias));
+                }
+            }
+            keep
+        });
+        if len != source_files.len() {
+            self.canonical_to_file.write().retain(|_, file| file.strong_count() > 0);
+        }
+        let removed = len - source_files.len();
+        if removed > 0 {
+            self.has_gaps.store(true, Ordering::Relaxed);
+        }
+        removed
+    }
+
     /// Sets the file loader for the source map.
     /// This may only be called once. Further calls will do nothing.
     ///
@@ -242,6 +318,18 @@ impl SourceMap {
         self.base_path.read().as_ref().cloned()
     }
 
+    /// Sets the path prefix remappings applied when displaying file names.
+    ///
+    /// Remapped paths are displayed as-is, without trimming the base path.
+    pub fn set_path_mapping(&self, path_mapping: FilePathMapping) {
+        *self.path_mapping.write() = Arc::new(path_mapping);
+    }
+
+    /// Returns the path prefix remappings applied when displaying file names.
+    pub fn path_mapping(&self) -> Arc<FilePathMapping> {
+        self.path_mapping.read().clone()
+    }
+
     /// Returns `true` if the source map is empty.
     pub fn is_empty(&self) -> bool {
         self.files().is_empty()
@@ -273,7 +361,7 @@ impl SourceMap {
     /// Returns the source file with the given path, if it exists.
     /// Does not attempt to load the file.
     pub fn get_file_ref(&self, filename: &FileName) -> Option<Arc<SourceFile>> {
-        self.id_to_file.get_cloned(&SourceFileId::new(filename))
+        self.id_to_file.read().get_cloned(&SourceFileId::new(filename))
     }
 
     /// Loads a file from the given path.
@@ -286,6 +374,45 @@ impl SourceMap {
         self.new_source_file_with(name, || self.file_loader().load_file(path))
     }
 
+    /// Loads a file with the given name from the given canonical path, deduplicating it by path.
+    ///
+    /// If the same path was already loaded with this method under a different name, like when a
+    /// file is reachable through both a symlink and its target, or through multiple remappings,
+    /// then that file is returned instead, and `name` is recorded as one of its
+    /// [`aliases`](Self::aliases). This way the file is only processed once, while diagnostics
+    /// still show the name it was first loaded with.
+    ///
+    /// Distinct files with the same contents are not deduplicated, since their relative imports
+    /// resolve against different directories.
+    pub fn load_file_deduplicated(
+        &self,
+        name: FileName,
+        path: &Path,
+    ) -> io::Result<Arc<SourceFile>> {
+        let id = SourceFileId::new(&name);
+        self.id_to_file.read().try_insert_cloned(id, |&id| {
+            let existing = self.canonical_to_file.read().get(path).and_then(Weak::upgrade);
+            if let Some(file) = existing {
+                trace!(name=%name.display(), original=%file.name.display(), "deduplicated file");
+                let original_id = SourceFileId::new(&file.name);
+                self.aliases.write().entry(original_id).or_default().push(name);
+                return Ok(file);
+            }
+
+            let src = self.file_loader().load_file(path)?;
+            let file = self.append_source_file(SourceFile::new(name, id, src)?)?;
+            self.canonical_to_file.write().insert(path.to_path_buf(), Arc::downgrade(&file));
+            Ok(file)
+        })
+    }
+
+    /// Returns the other names that `file` was loaded with.
+    ///
+    /// See [`load_file_deduplicated`](Self::load_file_deduplicated) for more details.
+    pub fn aliases(&self, file: &SourceFile) -> Vec<FileName> {
+        self.aliases.read().get(&SourceFileId::new(&file.name)).cloned().unwrap_or_default()
+    }
+
     /// Loads `stdin`.
     pub fn load_stdin(&self) -> io::Result<Arc<SourceFile>> {
         self.new_source_file_with(FileName::Stdin, || self.file_loader().load_stdin())
@@ -302,6 +429,25 @@ impl SourceMap {
         self.new_source_file_with(name.into(), || Ok(src.into()))
     }
 
+    /// Creates a new `SourceFile` with the given name and a snapshot of the given text.
+    ///
+    /// The file shares the line index of the [`SourceText`] instead of scanning the text for line
+    /// starts again. The text itself is still copied, since the lexer needs it to be contiguous.
+    ///
+    /// See [`new_source_file_with`](Self::new_source_file_with) for more details.
+    pub fn new_source_file_from_text(
+        &self,
+        filename: impl Into<FileName>,
+        text: &SourceText,
+    ) -> io::Result<Arc<SourceFile>> {
+        let filename = filename.into();
+        let id = SourceFileId::new(&filename);
+        self.id_to_file.read().try_insert_cloned(id, |&id| {
+            let file = SourceFile::from_text(filename, id, text)?;
+            self.append_source_file(file)
+        })
+    }
+
     /// Creates a new `SourceFile` with the given name and source string closure.
     ///
     /// If a file already exists in the `SourceMap` with the same ID, that file is returned
@@ -318,7 +464,7 @@ impl SourceMap {
         get_src: impl FnOnce() -> io::Result<String>,
     ) -> io::Result<Arc<SourceFile>> {
         let id = SourceFileId::new(&filename);
-        self.id_to_file.try_insert_cloned(id, |&id| {
+        self.id_to_file.read().try_insert_cloned(id, |&id| {
             let file = SourceFile::new(filename, id, get_src()?)?;
             self.append_source_file(file)
         })
@@ -328,20 +474,44 @@ impl SourceMap {
         trace!(name=%file.name.display(), len=file.src.len(), loc=file.count_lines(), "adding to source map");
 
         let source_files = &mut *self.source_files.write();
-        file.start_pos = BytePos(if let Some(last_file) = source_files.last() {
-            // Add one so there is some space between files. This lets us distinguish
-            // positions in the `SourceMap`, even in the presence of zero-length files.
-            last_file.end_position().0.checked_add(1).ok_or(OffsetOverflowError(()))?
-        } else {
-            0
-        });
+        let (idx, start_pos) = match self.find_gap(source_files, file.source_len.0) {
+            Some(gap) => gap,
+            None => (
+                source_files.len(),
+                if let Some(last_file) = source_files.last() {
+                    // Add one so there is some space between files. This lets us distinguish
+                    // positions in the `SourceMap`, even in the presence of zero-length files.
+                    last_file.end_position().0.checked_add(1).ok_or(OffsetOverflowError(()))?
+                } else {
+                    0
+                },
+            ),
+        };
+        file.start_pos = BytePos(start_pos);
 
         let file = Arc::new(file);
-        source_files.push(file.clone());
+        source_files.insert(idx, file.clone());
 
         Ok(file)
     }
 
+    /// Finds the first range left by removed files that fits a file of length `len`, returning
+    /// the index to insert the file at and its start position.
+    fn find_gap(&self, source_files: &[Arc<SourceFile>], len: u32) -> Option<(usize, u32)> {
+        if !self.has_gaps.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut start = 0u32;
+        for (idx, file) in source_files.iter().enumerate() {
+            // Keep one byte of space after the file, like when appending.
+            if start.checked_add(len).and_then(|end| end.checked_add(1))? <= file.start_pos.0 {
+                return Some((idx, start));
+            }
+            start = file.end_position().0.checked_add(1)?;
+        }
+        None
+    }
+
     /// Returns a read guard to the source files in the source map.
     pub fn files(&self) -> impl std::ops::Deref<Target = [Arc<SourceFile>]> + '_ {
         RwLockReadGuard::map(self.source_files.read(), std::ops::Deref::deref)
@@ -349,7 +519,11 @@ impl SourceMap {
 
     /// Display the filename for diagnostics.
     pub fn filename_for_diagnostics<'a>(&self, filename: &'a FileName) -> FileNameDisplay<'a> {
-        FileNameDisplay { inner: filename, base_path: self.base_path() }
+        FileNameDisplay {
+            inner: filename,
+            base_path: self.base_path(),
+            path_mapping: Some(self.path_mapping()),
+        }
     }
 
     /// Returns `true` if the given span is multi-line.
@@ -386,7 +560,7 @@ impl SourceMap {
 
     /// Returns the index of the [`SourceFile`] (in `self.files`) that contains `pos`.
     ///
-    /// This index is guaranteed to be valid for the lifetime of this `SourceMap`.
+    /// This index is only valid until files are removed from this `SourceMap`.
     pub fn lookup_source_file_idx(&self, pos: BytePos) -> usize {
         Self::lookup_sf_idx(&self.files(), pos)
     }
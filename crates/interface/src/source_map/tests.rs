@@ -309,31 +309,23 @@ impl SourceMapExtension for SourceMap {
 }
 
 // Takes a unix-style path and returns a platform specific path.
-#[cfg(any())]
 fn path(p: &str) -> PathBuf {
     path_str(p).into()
 }
 
 // Takes a unix-style path and returns a platform specific path.
-#[cfg(any())]
 fn path_str(p: &str) -> String {
-    #[cfg(not(windows))]
-    {
+    if !cfg!(windows) {
         return p.into();
     }
 
-    #[cfg(windows)]
-    {
-        let mut path = p.replace('/', "\\");
-        if let Some(rest) = path.strip_prefix('\\') {
-            path = ["X:\\", rest].concat();
-        }
-
-        path
+    let mut path = p.replace('/', "\\");
+    if let Some(rest) = path.strip_prefix('\\') {
+        path = ["X:\\", rest].concat();
     }
+    path
 }
 
-#[cfg(any())]
 fn map_path_prefix(mapping: &FilePathMapping, p: &str) -> String {
     // It's important that we convert to a string here because that's what
     // later stages do too (e.g. in the backend), and comparing `Path` values
@@ -347,15 +339,11 @@ fn reverse_map_prefix(mapping: &FilePathMapping, p: &str) -> Option<String> {
     mapping.reverse_map_prefix_heuristically(&path(p)).map(|q| q.to_string_lossy().to_string())
 }
 
-#[cfg(any())]
 #[test]
 fn path_prefix_remapping() {
     // Relative to relative
     {
-        let mapping = &FilePathMapping::new(
-            vec![(path("abc/def"), path("foo"))],
-            FileNameDisplayPreference::Remapped,
-        );
+        let mapping = &FilePathMapping::new(vec![(path("abc/def"), path("foo"))]);
 
         assert_eq!(map_path_prefix(mapping, "abc/def/src/main.rs"), path_str("foo/src/main.rs"));
         assert_eq!(map_path_prefix(mapping, "abc/def"), path_str("foo"));
@@ -363,10 +351,7 @@ fn path_prefix_remapping() {
 
     // Relative to absolute
     {
-        let mapping = &FilePathMapping::new(
-            vec![(path("abc/def"), path("/foo"))],
-            FileNameDisplayPreference::Remapped,
-        );
+        let mapping = &FilePathMapping::new(vec![(path("abc/def"), path("/foo"))]);
 
         assert_eq!(map_path_prefix(mapping, "abc/def/src/main.rs"), path_str("/foo/src/main.rs"));
         assert_eq!(map_path_prefix(mapping, "abc/def"), path_str("/foo"));
@@ -374,10 +359,7 @@ fn path_prefix_remapping() {
 
     // Absolute to relative
     {
-        let mapping = &FilePathMapping::new(
-            vec![(path("/abc/def"), path("foo"))],
-            FileNameDisplayPreference::Remapped,
-        );
+        let mapping = &FilePathMapping::new(vec![(path("/abc/def"), path("foo"))]);
 
         assert_eq!(map_path_prefix(mapping, "/abc/def/src/main.rs"), path_str("foo/src/main.rs"));
         assert_eq!(map_path_prefix(mapping, "/abc/def"), path_str("foo"));
@@ -385,10 +367,7 @@ fn path_prefix_remapping() {
 
     // Absolute to absolute
     {
-        let mapping = &FilePathMapping::new(
-            vec![(path("/abc/def"), path("/foo"))],
-            FileNameDisplayPreference::Remapped,
-        );
+        let mapping = &FilePathMapping::new(vec![(path("/abc/def"), path("/foo"))]);
 
         assert_eq!(map_path_prefix(mapping, "/abc/def/src/main.rs"), path_str("/foo/src/main.rs"));
         assert_eq!(map_path_prefix(mapping, "/abc/def"), path_str("/foo"));
@@ -588,6 +567,120 @@ fn test_next_point() {
     assert!(sm.span_to_snippet(span).is_err());
 }
 
+#[test]
+fn retain_files() {
+    let sm = init_source_map();
+    let blork2 = sm.get_file(PathBuf::from("blork2.rs")).unwrap();
+    sm.retain_files(|file| file.name != FileName::real("empty.rs"));
+    assert_eq!(sm.files().len(), 2);
+    assert!(sm.get_file(PathBuf::from("empty.rs")).is_none());
+    assert_eq!(sm.lookup_source_file(blork2.start_pos).name, blork2.name);
+
+    // Removed files can be loaded again with new contents.
+    let empty = sm.new_source_file(PathBuf::from("empty.rs"), "not empty").unwrap();
+    assert_eq!(*empty.src, "not empty");
+    assert!(empty.start_pos > blork2.start_pos);
+    assert_eq!(sm.files().len(), 3);
+}
+
+#[test]
+fn evict_unused_files() {
+    let sm = init_source_map();
+    let blork2 = sm.get_file(PathBuf::from("blork2.rs")).unwrap();
+    assert_eq!(sm.evict_unused_files(), 2);
+    assert_eq!(sm.evict_unused_files(), 0);
+    assert_eq!(sm.files().len(), 1);
+    assert!(sm.get_file(PathBuf::from("blork.rs")).is_none());
+
+    // The range of the evicted files is reused.
+    let short = sm.new_source_file(PathBuf::from("short.rs"), "short").unwrap();
+    assert_eq!(short.start_pos, BytePos(0));
+    assert_eq!(sm.files().len(), 2);
+    assert_eq!(sm.lookup_source_file(BytePos(2)).name, short.name);
+    assert_eq!(sm.lookup_source_file(blork2.start_pos).name, blork2.name);
+    let span = Span::new(short.start_pos, short.end_position());
+    assert_eq!(sm.span_to_snippet(span).unwrap(), "short");
+
+    // Files that don't fit in the range are appended.
+    let long = sm.new_source_file(PathBuf::from("long.rs"), "a".repeat(100)).unwrap();
+    assert!(long.start_pos > blork2.end_position());
+
+    drop((blork2, short, long));
+    assert_eq!(sm.evict_unused_files(), 3);
+    assert!(sm.is_empty());
+}
+
+#[test]
+fn lookup_line_col_encodings() {
+    let sm = SourceMap::empty();
+    let src = "a\n/* 🦀é */ foo";
+    let file = sm.new_source_file(PathBuf::from("a.sol"), src).unwrap();
+    let pos = file.start_pos + BytePos(src.find("foo").unwrap() as u32);
+
+    // `🦀` is 4 bytes, 2 UTF-16 code units, and 1 scalar value; `é` is 2, 1, and 1.
+    assert_eq!(file.lookup_line_col(pos, ColumnEncoding::Utf8), (1, 13));
+    assert_eq!(file.lookup_line_col(pos, ColumnEncoding::Utf16), (1, 10));
+    assert_eq!(file.lookup_line_col(pos, ColumnEncoding::Utf32), (1, 9));
+    assert_eq!(file.lookup_line_col(pos, ColumnEncoding::Utf32).1, sm.lookup_char_pos(pos).col.0);
+    assert_eq!(file.lookup_line_col(file.start_pos, ColumnEncoding::Utf16), (0, 0));
+}
+
+#[test]
+fn bom_and_crlf_lines() {
+    let sm = SourceMap::empty();
+    let src = "\u{feff}ab\r\ncd\r\n";
+    let file = sm.new_source_file(PathBuf::from("a.sol"), src).unwrap();
+    assert_eq!(file.get_line(0), Some("ab"));
+    assert_eq!(file.get_line(1), Some("cd"));
+
+    let b = file.start_pos + BytePos(src.find('b').unwrap() as u32);
+    assert_eq!(file.lookup_line_col(b, ColumnEncoding::Utf16), (0, 1));
+    let loc = sm.lookup_char_pos(b).data;
+    assert_eq!((loc.line, loc.col.0, loc.col_display), (1, 1, 1));
+    let d = file.start_pos + BytePos(src.find('d').unwrap() as u32);
+    assert_eq!(file.lookup_line_col(d, ColumnEncoding::Utf8), (1, 1));
+}
+
+#[test]
+fn source_text_edits() {
+    let mut text = SourceText::from("first line\nsecond line\n");
+    assert_eq!(text.count_lines(), 2);
+    assert_eq!(text.line_start(1), 11);
+    assert_eq!(text.line_of_byte(12), 1);
+
+    text.replace(0..5, "1st\nthe");
+    assert_eq!(text, "1st\nthe line\nsecond line\n");
+    assert_eq!(text.count_lines(), 3);
+    assert_eq!(text.line_start(2), 13);
+    assert_eq!(text.line_of_byte(12), 1);
+
+    let sm = SourceMap::empty();
+    let file = sm.new_source_file_from_text(PathBuf::from("a.sol"), &text).unwrap();
+    assert_eq!(*file.src, text.to_string());
+    assert_eq!(file.line_position(2), Some(text.line_start(2)));
+}
+
+#[test]
+fn source_text_line_index() {
+    let sm = SourceMap::empty();
+    for (i, src) in ["", "a", "a\n", "a\r\nb", "é\n\n🦀 b\n c"].into_iter().enumerate() {
+        let table = sm.new_source_file(PathBuf::from(format!("table{i}.sol")), src).unwrap();
+        let text = SourceText::from(src);
+        let file = sm.new_source_file_from_text(PathBuf::from(format!("text{i}.sol")), &text);
+        let file = file.unwrap();
+        assert_eq!(file.count_lines(), table.count_lines(), "{src:?}");
+        assert_eq!(file.multibyte_chars, table.multibyte_chars, "{src:?}");
+        for line in 0..=table.count_lines() {
+            assert_eq!(file.line_position(line), table.line_position(line), "{src:?}");
+            assert_eq!(file.get_line(line), table.get_line(line), "{src:?}");
+        }
+        for pos in 0..=src.len() {
+            let pos = crate::RelativeBytePos::from_usize(pos);
+            assert_eq!(file.lookup_line(pos), table.lookup_line(pos), "{src:?}");
+        }
+    }
+}
+
 #[cfg(any())]
 #[cfg(target_os = "linux")]
 #[test]
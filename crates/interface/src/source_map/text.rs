@@ -0,0 +1,134 @@
+use crop::Rope;
+use std::{fmt, ops::Range};
+
+/// The text of a source that is edited incrementally, such as an open editor buffer.
+///
+/// The text is stored in a rope that also indexes line starts, so edits and line lookups take
+/// `O(log n)` time instead of rescanning the whole source after every change. The underlying
+/// [`Rope`] is available through `Deref` for other queries, such as UTF-16 offsets.
+///
+/// This is the storage of editor buffers in the language server. Use
+/// [`SourceMap::new_source_file_from_text`](crate::SourceMap::new_source_file_from_text) to
+/// compile a snapshot of the text; the source file shares the line index of the snapshot.
+#[derive(Clone, PartialEq)]
+pub struct SourceText(Rope);
+
+impl Default for SourceText {
+    fn default() -> Self {
+        Self(Rope::new())
+    }
+}
+
+impl fmt::Debug for SourceText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for SourceText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<&str> for SourceText {
+    fn from(s: &str) -> Self {
+        Self(Rope::from(s))
+    }
+}
+
+impl From<String> for SourceText {
+    fn from(s: String) -> Self {
+        Self(Rope::from(s))
+    }
+}
+
+impl From<Rope> for SourceText {
+    fn from(rope: Rope) -> Self {
+        Self(rope)
+    }
+}
+
+impl PartialEq<str> for SourceText {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for SourceText {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl std::ops::Deref for SourceText {
+    type Target = Rope;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl SourceText {
+    /// Creates a new, empty text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the length of the text in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.byte_len()
+    }
+
+    /// Returns `true` if the text is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.byte_len() == 0
+    }
+
+    /// Returns the number of lines in the text.
+    ///
+    /// A trailing line terminator does not start a new line.
+    #[inline]
+    pub fn count_lines(&self) -> usize {
+        self.0.line_len()
+    }
+
+    /// Returns the 0-based index of the line containing the byte at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is out of bounds.
+    #[inline]
+    pub fn line_of_byte(&self, offset: usize) -> usize {
+        self.0.line_of_byte(offset)
+    }
+
+    /// Returns the byte offset of the start of the 0-based line `line`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is out of bounds.
+    #[inline]
+    pub fn line_start(&self, line: usize) -> usize {
+        self.0.byte_of_line(line)
+    }
+
+    /// Replaces the bytes in `range` with `text`, updating the line index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or does not lie on character boundaries.
+    #[inline]
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.0.replace(range, text);
+    }
+
+    /// Returns the underlying rope.
+    #[inline]
+    pub fn as_rope(&self) -> &Rope {
+        &self.0
+    }
+}
@@ -958,8 +958,11 @@ symbols! {
     // There is currently no checking that all symbols are used; that would be
     // nice to have.
     Symbols {
+        Error,
+        Panic,
         Test,
         X,
+        __internal_dispatch,
         __load_storage_bytes,
         __ret_bytes,
         __revert_error,
@@ -1017,6 +1020,7 @@ symbols! {
         global,
         heap,
         hir,
+        immutables,
         infallible,
         interfaceId,
         internal_call,
@@ -489,6 +489,8 @@ mod tests {
                 line_end: 1,
                 column_start: 1,
                 column_end: 1,
+                column_start_utf16: None,
+                column_end_utf16: None,
                 is_primary: true,
                 text: vec![JsonDiagnosticSpanLine {
                     text: Cow::Borrowed(""),
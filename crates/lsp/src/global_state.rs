@@ -21,13 +21,14 @@ use solar_config::{CompileOpts, version::SHORT_VERSION};
 use solar_interface::{
     Session,
     data_structures::{
+        index::{IndexVec, index_vec},
         map::{FxHashMap, FxHashSet},
         sync::{Mutex, RwLock},
     },
     diagnostics::{DiagCtxt, InMemoryEmitter},
-    source_map::{FileName, SourceMap},
+    source_map::{FileName, SourceMap, SourceText},
 };
-use solar_sema::Compiler;
+use solar_sema::{Compiler, Progress, Sources, hir};
 use std::{
     borrow::Cow,
     mem,
@@ -37,6 +38,7 @@ use std::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
+    time::{Duration, Instant},
 };
 use tokio::{
     sync::{oneshot, watch},
@@ -73,6 +75,8 @@ pub(crate) struct GlobalState {
     analysis_version: Arc<AtomicUsize>,
     published_analysis_version: watch::Sender<usize>,
     analysis_commit: Arc<Mutex<AnalysisCommitState>>,
+    analysis_cache: Arc<Mutex<AnalysisCache>>,
+    analysis_source_map: Arc<Mutex<Arc<SourceMap>>>,
     analysis_progress: ProgressCoordinator,
     flycheck_versions: Arc<RwLock<FxHashMap<DiagnosticOwner, usize>>>,
     flycheck_cancels: FxHashMap<DiagnosticOwner, oneshot::Sender<()>>,
@@ -91,6 +95,8 @@ impl GlobalState {
             analysis_version: Arc::new(AtomicUsize::new(0)),
             published_analysis_version,
             analysis_commit: Arc::new(Default::default()),
+            analysis_cache: Arc::new(Default::default()),
+            analysis_source_map: Arc::new(Mutex::new(Arc::new(SourceMap::empty()))),
             analysis_progress,
             flycheck_versions: Arc::new(Default::default()),
             flycheck_cancels: FxHashMap::default(),
@@ -152,10 +158,14 @@ impl GlobalState {
     /// taken of the global state ([`GlobalStateSnapshot`]) and analysis is performed on
     /// the entire project in a separate thread.
     ///
-    /// Currently, Solar is sufficiently fast at parsing and lowering even large Solidity projects,
-    /// so while analysing the entire project is relatively expensive compared to incremental
-    /// analysis, it is still fast enough for most workloads. A potential improvement would be to
-    /// enable incremental parsing and analysis in Solar using e.g. [`salsa`].
+    /// Workspaces whose sources did not change since their last analysis reuse its results, see
+    /// [`AnalysisCache`]. This is the only incremental step: ASTs and HIR are not reused across
+    /// analyses, since the HIR numbers the items of all sources of a compilation together and
+    /// cannot lower a single source again. Within the workspace of an edited document, every
+    /// source is therefore parsed, lowered and analyzed again. Solar is sufficiently fast at this
+    /// even for large Solidity projects, so while this is relatively expensive compared to
+    /// incremental analysis, it is still fast enough for most workloads. A potential improvement
+    /// would be to enable incremental parsing and analysis in Solar using e.g. [`salsa`].
     ///
     /// [`salsa`]: https://docs.rs/salsa/latest/salsa/
     pub(crate) fn recompute_with_disk_files(&mut self, disk_paths: Vec<PathBuf>) {
@@ -196,6 +206,7 @@ impl GlobalState {
                 analysis_version,
                 published_analysis_version,
                 analysis_commit,
+                analysis_cache,
                 analysis_progress,
                 ..
             } = self;
@@ -205,6 +216,7 @@ impl GlobalState {
             analysis_progress.finish_active_after("Workspace index cleared", || {
                 // Invalidate workers before doing the potentially expensive diagnostic publication.
                 analysis_version.store(version, Ordering::Release);
+                analysis_cache.lock().clear();
                 let old_symbol_tables = mem::take(&mut *symbol_tables.write());
                 let batches = diagnostics.write().replace_and_publish_batches(
                     DiagnosticOwner::Compiler,
@@ -235,8 +247,10 @@ impl GlobalState {
         removed_paths: Vec<PathBuf>,
         changed_paths: Vec<PathBuf>,
     ) {
+        let disk_changed = !disk_paths.is_empty() || !removed_paths.is_empty();
         let removed_uris = self.prepare_removed_file_diagnostics(removed_paths);
-        let Some((version, progress)) = self.begin_analysis(mode, removed_uris, changed_paths)
+        let Some((version, progress)) =
+            self.begin_analysis(mode, removed_uris, changed_paths, disk_changed)
         else {
             return;
         };
@@ -253,10 +267,9 @@ impl GlobalState {
                 return AnalysisTaskOutcome::Superseded;
             }
 
-            let mut diagnostics = DiagnosticMap::default();
-            let mut symbol_tables = SymbolTables::default();
+            let mut result = AnalysisResult::default();
 
-            for batch in batches {
+            for (idx, batch) in batches.into_iter().enumerate() {
                 if batch.files.is_empty() {
                     continue;
                 }
@@ -265,11 +278,7 @@ impl GlobalState {
                     return AnalysisTaskOutcome::Superseded;
                 }
 
-                let result = analyze(batch);
-                symbol_tables.extend(result.symbol_tables);
-                for (uri, mut batch_diagnostics) in result.diagnostics {
-                    diagnostics.entry(uri).or_default().append(&mut batch_diagnostics);
-                }
+                result.extend(snapshot.analyze_batch(version, idx, batch, Some(&worker_progress)));
 
                 if !snapshot.is_current(version) {
                     return AnalysisTaskOutcome::Superseded;
@@ -277,7 +286,7 @@ impl GlobalState {
             }
 
             worker_progress.report("Publishing workspace index");
-            if snapshot.publish_analysis(version, AnalysisResult { diagnostics, symbol_tables }) {
+            if snapshot.publish_analysis(version, result) {
                 AnalysisTaskOutcome::Published
             } else {
                 AnalysisTaskOutcome::Superseded
@@ -291,6 +300,7 @@ impl GlobalState {
         mode: AnalysisMode,
         removed_uris: Vec<Url>,
         changed_paths: Vec<PathBuf>,
+        disk_changed: bool,
     ) -> Option<(usize, ProgressTicket)> {
         let (version, rediscover, progress) = {
             let analysis_commit = self.analysis_commit.clone();
@@ -306,6 +316,10 @@ impl GlobalState {
             // end the previous wave after the new analysis becomes current.
             let progress = self.analysis_progress.start(version);
             self.commit_analysis_epoch(&mut commit, version, changed_paths, rediscover);
+            // Clear after publishing the new version so that older workers cannot repopulate it.
+            if rediscover || disk_changed {
+                self.analysis_cache.lock().clear();
+            }
             let batches = self.diagnostics.write().clear_uris_and_publish_batches(removed_uris);
             publish_diagnostic_batches(&mut self.client, batches);
             (version, rediscover, progress)
@@ -540,6 +554,8 @@ impl GlobalState {
             analysis_version: self.analysis_version.clone(),
             published_analysis_version: self.published_analysis_version.clone(),
             analysis_commit: self.analysis_commit.clone(),
+            analysis_cache: self.analysis_cache.clone(),
+            analysis_source_map: self.analysis_source_map.clone(),
             flycheck_versions: self.flycheck_versions.clone(),
             symbol_tables: self.symbol_tables.clone(),
             diagnostics: self.diagnostics.clone(),
@@ -631,11 +647,22 @@ fn handle_analysis_failure(
     true
 }
 
+#[derive(Clone, Default)]
 struct AnalysisResult {
     diagnostics: DiagnosticMap,
     symbol_tables: SymbolTables,
 }
 
+impl AnalysisResult {
+    /// Merges the result of analyzing sources that are independent of the ones in `self`.
+    fn extend(&mut self, other: Self) {
+        self.symbol_tables.extend(other.symbol_tables);
+        for (uri, mut diagnostics) in other.diagnostics {
+            self.diagnostics.entry(uri).or_default().append(&mut diagnostics);
+        }
+    }
+}
+
 fn watched_file_registration_params() -> RegistrationParams {
     let kind = Some(WatchKind::Create | WatchKind::Change | WatchKind::Delete);
     let options = DidChangeWatchedFilesRegistrationOptions {
@@ -671,6 +698,13 @@ pub(crate) struct GlobalStateSnapshot {
     analysis_version: Arc<AtomicUsize>,
     published_analysis_version: watch::Sender<usize>,
     analysis_commit: Arc<Mutex<AnalysisCommitState>>,
+    analysis_cache: Arc<Mutex<AnalysisCache>>,
+    /// The source map shared by all analyses.
+    ///
+    /// Files loaded by an analysis are evicted once it finishes, so the files of closed and
+    /// edited documents do not accumulate. The lock is held while analyzing, since a file that is
+    /// still used by another analysis would be returned with its old contents.
+    analysis_source_map: Arc<Mutex<Arc<SourceMap>>>,
     flycheck_versions: Arc<RwLock<FxHashMap<DiagnosticOwner, usize>>>,
     symbol_tables: Arc<RwLock<SymbolTables>>,
     diagnostics: Arc<RwLock<DiagnosticStore>>,
@@ -690,9 +724,7 @@ impl GlobalStateSnapshot {
             .vfs
             .read()
             .iter()
-            .filter_map(|(path, contents)| {
-                Some((path.as_path()?.to_path_buf(), contents.to_string()))
-            })
+            .filter_map(|(path, contents)| Some((path.as_path()?.to_path_buf(), contents.clone())))
             .collect::<Vec<_>>();
         let workspaces = self.analysis_workspaces();
         let workspace_path_index = WorkspacePathIndex::new(&workspaces);
@@ -740,6 +772,80 @@ impl GlobalStateSnapshot {
         batches
     }
 
+    /// Analyzes the `idx`th batch of `version`.
+    ///
+    /// Only the units of the batch with changed files are analyzed again; see [`AnalysisCache`].
+    fn analyze_batch(
+        &self,
+        version: usize,
+        idx: usize,
+        batch: AnalysisBatch,
+        progress: Option<&ProgressTicket>,
+    ) -> AnalysisResult {
+        let plan = self.analysis_cache.lock().plan(idx, &batch.files);
+        let cached = match plan {
+            AnalysisPlan::Unchanged(result) => return result,
+            AnalysisPlan::Units(units) => self.analyze_units(&batch, units, progress),
+            AnalysisPlan::Full => None,
+        };
+        let cached = cached.unwrap_or_else(|| {
+            let files = batch.files.clone();
+            CachedBatch::new(files, self.analyze(batch, progress))
+        });
+        let result = cached.result.clone();
+        let mut cache = self.analysis_cache.lock();
+        if self.is_current(version) {
+            cache.insert(idx, cached);
+        }
+        result
+    }
+
+    /// Analyzes the `units` of `batch` without a result on their own, and merges the results of
+    /// all units.
+    ///
+    /// Returns `None` if an edit connected a unit to another one, in which case the batch must be
+    /// analyzed as a whole to find its new units.
+    fn analyze_units(
+        &self,
+        batch: &AnalysisBatch,
+        mut units: Vec<CachedUnit>,
+        progress: Option<&ProgressTicket>,
+    ) -> Option<CachedBatch> {
+        for idx in 0..units.len() {
+            if units[idx].result.is_some() {
+                continue;
+            }
+            let unit_batch =
+                AnalysisBatch::from_files(batch.opts.clone(), units[idx].files.clone());
+            let analysis = self.analyze(unit_batch, progress);
+            let sources = analysis.units.into_iter().flatten().collect::<FxHashSet<_>>();
+            if units
+                .iter()
+                .enumerate()
+                .any(|(other, unit)| other != idx && !unit.sources.is_disjoint(&sources))
+            {
+                return None;
+            }
+            units[idx].sources = sources;
+            units[idx].result = Some(analysis.result);
+        }
+
+        let mut result = AnalysisResult::default();
+        for unit in &units {
+            result.extend(unit.result.clone().expect("all units were analyzed"));
+        }
+        Some(CachedBatch { files: batch.files.clone(), result, units })
+    }
+
+    /// Analyzes `batch` in the shared source map, then evicts the files it no longer uses.
+    fn analyze(&self, batch: AnalysisBatch, progress: Option<&ProgressTicket>) -> BatchAnalysis {
+        let source_map = self.analysis_source_map.lock();
+        let analysis = analyze_inner(batch, Arc::clone(&source_map), progress);
+        let evicted = source_map.evict_unused_files();
+        tracing::debug!(evicted, "evicted unused source files");
+        analysis
+    }
+
     fn publish_analysis(&mut self, version: usize, result: AnalysisResult) -> bool {
         let old_symbol_tables = {
             let analysis_commit = self.analysis_commit.clone();
@@ -814,7 +920,7 @@ impl GlobalStateSnapshot {
 
 struct AnalysisBatch {
     opts: CompileOpts,
-    files: Vec<(PathBuf, String)>,
+    files: Vec<(PathBuf, SourceText)>,
     seen_paths: FxHashSet<PathBuf>,
 }
 
@@ -824,7 +930,10 @@ impl AnalysisBatch {
     }
 
     #[cfg(any(test, feature = "bench"))]
-    fn from_files(opts: CompileOpts, files: impl IntoIterator<Item = (PathBuf, String)>) -> Self {
+    fn from_files<T: Into<SourceText>>(
+        opts: CompileOpts,
+        files: impl IntoIterator<Item = (PathBuf, T)>,
+    ) -> Self {
         let mut batch = Self::new(opts);
         for (path, contents) in files {
             batch.push_file(path, contents);
@@ -833,9 +942,9 @@ impl AnalysisBatch {
         batch
     }
 
-    fn push_file(&mut self, path: PathBuf, contents: String) {
+    fn push_file(&mut self, path: PathBuf, contents: impl Into<SourceText>) {
         if self.seen_paths.insert(path.clone()) {
-            self.files.push((path, contents));
+            self.files.push((path, contents.into()));
         }
     }
 
@@ -844,6 +953,113 @@ impl AnalysisBatch {
     }
 }
 
+/// The results of the last analysis of each batch, reused for the sources that did not change.
+///
+/// Batches are compiled independently of each other, so editing a document only analyzes the
+/// workspace that owns it again. Within a batch, the sources fall into units that do not import
+/// each other, such as independent contracts. The diagnostics and symbols of a unit only depend on
+/// its own sources, so after an edit only the units with changed files are analyzed again, each on
+/// its own, and the results of the others are reused. A unit that has only been analyzed together
+/// with the rest of its batch has no result of its own yet, so it is analyzed once more after the
+/// first edit.
+///
+/// Entries are keyed by the position of the batch in [`GlobalStateSnapshot::analysis_batches`],
+/// which is stable until the workspaces are rediscovered. The cache is cleared at that point, and
+/// whenever files change on disk, since a batch may import files that are not part of its inputs.
+#[derive(Default)]
+struct AnalysisCache {
+    batches: FxHashMap<usize, CachedBatch>,
+}
+
+struct CachedBatch {
+    /// The inputs of the batch, sorted by path.
+    files: Vec<(PathBuf, SourceText)>,
+    /// The result of the whole batch.
+    result: AnalysisResult,
+    units: Vec<CachedUnit>,
+}
+
+#[derive(Clone)]
+struct CachedUnit {
+    /// The inputs of the batch that belong to the unit, sorted by path.
+    files: Vec<(PathBuf, SourceText)>,
+    /// The paths of all sources of the unit, including imported files that are not inputs.
+    sources: FxHashSet<PathBuf>,
+    /// The result of analyzing the unit on its own, if it was.
+    result: Option<AnalysisResult>,
+}
+
+/// How to analyze a batch, given the cached results of its last analysis.
+enum AnalysisPlan {
+    /// No file changed.
+    Unchanged(AnalysisResult),
+    /// Analyze the units without a result on their own again.
+    Units(Vec<CachedUnit>),
+    /// Analyze the whole batch.
+    Full,
+}
+
+impl CachedBatch {
+    fn new(files: Vec<(PathBuf, SourceText)>, analysis: BatchAnalysis) -> Self {
+        let units = analysis
+            .units
+            .into_iter()
+            .map(|sources| CachedUnit {
+                files: files.iter().filter(|(path, _)| sources.contains(path)).cloned().collect(),
+                sources,
+                result: None,
+            })
+            .collect();
+        Self { files, result: analysis.result, units }
+    }
+}
+
+impl AnalysisCache {
+    /// Plans the analysis of the `idx`th batch with `files`.
+    ///
+    /// The units of the last analysis with a changed file are dirty and lose their result.
+    /// Adding or removing files may connect or separate units, so it needs a full analysis.
+    fn plan(&self, idx: usize, files: &[(PathBuf, SourceText)]) -> AnalysisPlan {
+        let Some(cached) = self.batches.get(&idx) else {
+            return AnalysisPlan::Full;
+        };
+        if cached.files == files {
+            return AnalysisPlan::Unchanged(cached.result.clone());
+        }
+        if cached.files.len() != files.len()
+            || cached.files.iter().zip(files).any(|((cached, _), (path, _))| cached != path)
+        {
+            return AnalysisPlan::Full;
+        }
+
+        let mut units = cached
+            .units
+            .iter()
+            .map(|unit| CachedUnit { files: Vec::new(), ..unit.clone() })
+            .collect::<Vec<_>>();
+        for (path, contents) in files {
+            let Some(unit) = units.iter_mut().find(|unit| unit.sources.contains(path)) else {
+                return AnalysisPlan::Full;
+            };
+            unit.files.push((path.clone(), contents.clone()));
+        }
+        for (unit, cached) in units.iter_mut().zip(&cached.units) {
+            if unit.files != cached.files {
+                unit.result = None;
+            }
+        }
+        AnalysisPlan::Units(units)
+    }
+
+    fn insert(&mut self, idx: usize, batch: CachedBatch) {
+        self.batches.insert(idx, batch);
+    }
+
+    fn clear(&mut self) {
+        self.batches.clear();
+    }
+}
+
 #[cfg(test)]
 mod analysis_batch_tests {
     use super::*;
@@ -866,6 +1082,29 @@ mod analysis_batch_tests {
         assert_eq!(batch.files[1], (b.clone(), "contract B {}".into()));
         assert_eq!(batch.seen_paths, FxHashSet::from_iter([a, b]));
     }
+
+    #[test]
+    fn analysis_cache_reuses_results_for_unchanged_files() {
+        let files = vec![(PathBuf::from("a.sol"), SourceText::from("contract A {}"))];
+        let analysis = analyze_inner(
+            AnalysisBatch::from_files(CompileOpts::default(), files.clone()),
+            Arc::new(SourceMap::empty()),
+            None,
+        );
+        let mut cache = AnalysisCache::default();
+        assert!(matches!(cache.plan(0, &files), AnalysisPlan::Full));
+
+        cache.insert(0, CachedBatch::new(files.clone(), analysis));
+        assert!(matches!(cache.plan(0, &files), AnalysisPlan::Unchanged(_)));
+        assert!(matches!(cache.plan(1, &files), AnalysisPlan::Full));
+        let edited = vec![(PathBuf::from("a.sol"), SourceText::from("contract A { }"))];
+        assert!(matches!(cache.plan(0, &edited), AnalysisPlan::Units(_)));
+        let added = vec![files[0].clone(), (PathBuf::from("b.sol"), SourceText::from(""))];
+        assert!(matches!(cache.plan(0, &added), AnalysisPlan::Full));
+
+        cache.clear();
+        assert!(matches!(cache.plan(0, &files), AnalysisPlan::Full));
+    }
 }
 
 fn analyze(batch: AnalysisBatch) -> AnalysisResult {
@@ -873,6 +1112,22 @@ fn analyze(batch: AnalysisBatch) -> AnalysisResult {
 }
 
 fn analyze_with_source_map(batch: AnalysisBatch, source_map: Arc<SourceMap>) -> AnalysisResult {
+    analyze_inner(batch, source_map, None).result
+}
+
+/// The result of analyzing a batch, and the units its sources fall into.
+struct BatchAnalysis {
+    result: AnalysisResult,
+    /// The paths of the sources of each unit.
+    units: Vec<FxHashSet<PathBuf>>,
+}
+
+/// Analyzes `batch`, reporting the number of files processed on `progress`.
+fn analyze_inner(
+    batch: AnalysisBatch,
+    source_map: Arc<SourceMap>,
+    progress: Option<&ProgressTicket>,
+) -> BatchAnalysis {
     let (emitter, diag_buffer) = InMemoryEmitter::new();
     let AnalysisBatch { mut opts, files, seen_paths: document_link_sources } = batch;
     debug_assert_eq!(files.len(), document_link_sources.len());
@@ -886,15 +1141,19 @@ fn analyze_with_source_map(batch: AnalysisBatch, source_map: Arc<SourceMap>) ->
 
     let mut compiler = Compiler::new(sess);
     compiler.enter_mut(move |compiler| {
+        let mut units = Vec::new();
         {
             let mut parsing_context = compiler.parse();
+            if let Some(progress) = progress.filter(|progress| !progress.is_disabled()) {
+                report_file_counts(parsing_context.progress(), progress.clone());
+            }
             let files = files
                 .into_iter()
                 .map(|(path, contents)| {
                     parsing_context
                         .sess
                         .source_map()
-                        .new_source_file(FileName::real(path), contents)
+                        .new_source_file_from_text(FileName::real(path), &contents)
                         .map_err(|error| {
                             parsing_context
                                 .dcx()
@@ -911,6 +1170,7 @@ fn analyze_with_source_map(batch: AnalysisBatch, source_map: Arc<SourceMap>) ->
                 compiler.sources_mut().topo_sort();
                 let _ = compiler.lower_asts();
                 let _ = compiler.analysis();
+                units = analysis_units(compiler.sources());
             }
         }
 
@@ -924,10 +1184,62 @@ fn analyze_with_source_map(batch: AnalysisBatch, source_map: Arc<SourceMap>) ->
                 diagnostics
             });
 
-        AnalysisResult { diagnostics, symbol_tables }
+        BatchAnalysis { result: AnalysisResult { diagnostics, symbol_tables }, units }
     })
 }
 
+/// Splits `sources` into units that do not import each other, returning the paths of the sources
+/// of each unit.
+fn analysis_units(sources: &Sources<'_>) -> Vec<FxHashSet<PathBuf>> {
+    let mut neighbors: IndexVec<hir::SourceId, Vec<hir::SourceId>> =
+        index_vec![Vec::new(); sources.len()];
+    for (id, source) in sources.iter_enumerated() {
+        for &(_, import) in &source.imports {
+            neighbors[id].push(import);
+            neighbors[import].push(id);
+        }
+    }
+
+    let mut visited: IndexVec<hir::SourceId, bool> = index_vec![false; sources.len()];
+    let mut units = Vec::new();
+    for root in sources.indices() {
+        if mem::replace(&mut visited[root], true) {
+            continue;
+        }
+        let mut unit = FxHashSet::default();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if let Some(path) = sources[id].file.name.as_real() {
+                unit.insert(path.to_path_buf());
+            }
+            for &neighbor in &neighbors[id] {
+                if !mem::replace(&mut visited[neighbor], true) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+        units.push(unit);
+    }
+    units
+}
+
+/// The minimum time between two reports of the compiler's file counts.
+const FILE_COUNTS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Forwards the compiler's file counts to `ticket`, at most once per [`FILE_COUNTS_INTERVAL`].
+fn report_file_counts(progress: &Progress, ticket: ProgressTicket) {
+    let last_report = Mutex::new(None::<Instant>);
+    progress.set_listener(move |counts| {
+        let now = Instant::now();
+        let mut last_report = last_report.lock();
+        if last_report.is_some_and(|last| now - last < FILE_COUNTS_INTERVAL) {
+            return;
+        }
+        *last_report = Some(now);
+        ticket.report(format!("Analyzing workspace: {counts}"));
+    });
+}
+
 /// Access to prepared, fully analyzed in-memory projects for benchmarks and tests.
 #[cfg(any(test, feature = "bench"))]
 #[cfg_attr(all(test, not(feature = "bench")), allow(dead_code, unreachable_pub))]
@@ -2,7 +2,6 @@
 
 use super::{AnalysisBatch, DiagnosticMap, SymbolTables, analyze, analyze_with_source_map};
 use crate::{project_fixture::ProjectFixture, utils::apply_document_changes, workspace::Workspace};
-use crop::Rope;
 use lsp_types::{
     Diagnostic, GotoDefinitionResponse, Hover, HoverContents, Location, Position, Range,
     TextDocumentContentChangeEvent, Url, WorkspaceSymbol,
@@ -11,7 +10,7 @@ use normalize_path::NormalizePath;
 use solar_config::{CompileOpts, Threads};
 use solar_interface::{
     data_structures::map::{FxHashMap, FxHashSet},
-    source_map::{FileLoader, SourceMap},
+    source_map::{FileLoader, SourceMap, SourceText},
 };
 use std::{
     collections::BTreeMap,
@@ -260,7 +259,7 @@ impl BenchmarkProject {
             })?;
         let source = &mut self.files[index].1;
         let updated =
-            apply_document_changes(&Rope::from(source.as_str()), vec![edit.change.clone()])
+            apply_document_changes(&SourceText::from(source.as_str()), vec![edit.change.clone()])
                 .to_string();
         *source = updated.clone();
         self.loader.overlays.insert(edit.path.clone(), updated);
@@ -280,7 +279,7 @@ impl BenchmarkProject {
                 ))
             })?;
         Ok(BenchmarkDocumentChange {
-            contents: Rope::from(self.files[index].1.as_str()),
+            contents: SourceText::from(self.files[index].1.as_str()),
             changes: vec![edit.change.clone()],
         })
     }
@@ -324,11 +323,11 @@ pub struct BenchmarkEdit {
     change: TextDocumentContentChangeEvent,
 }
 
-/// An opaque document change input using the same Rope path as production notifications.
+/// An opaque document change input using the same rope path as production notifications.
 #[doc(hidden)]
 #[derive(Clone)]
 pub struct BenchmarkDocumentChange {
-    contents: Rope,
+    contents: SourceText,
     changes: Vec<TextDocumentContentChangeEvent>,
 }
 
@@ -1,10 +1,10 @@
 use crate::{NotifyResult, global_state::GlobalState, proto, utils::apply_document_changes};
-use crop::Rope;
 use lsp_types::{
     DidChangeConfigurationParams, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
     DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
     DidSaveTextDocumentParams, FileChangeType, WillSaveTextDocumentParams,
 };
+use solar_interface::source_map::SourceText;
 use std::{ops::ControlFlow, sync::Arc};
 use tracing::{debug, error};
 
@@ -22,7 +22,7 @@ pub(crate) fn did_open_text_document(
         let mut vfs = state.vfs.write();
         vfs.set_file_contents_with_version(
             path,
-            Some(Rope::from(params.text_document.text)),
+            Some(SourceText::from(params.text_document.text)),
             Some(params.text_document.version),
         );
         let changed = vfs.mark_clean();
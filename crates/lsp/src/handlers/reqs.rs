@@ -21,7 +21,10 @@ use lsp_types::{
     WorkspaceEdit, WorkspaceSymbolParams, WorkspaceSymbolResponse,
     request::GotoImplementationParams,
 };
-use solar_interface::{data_structures::sync::RwLock, source_map::SourceMap};
+use solar_interface::{
+    data_structures::sync::RwLock,
+    source_map::{SourceMap, SourceText},
+};
 use solar_parse::lexer::is_ident;
 use std::{collections::HashMap, future::ready, io, path::Path, sync::Arc};
 use tracing::warn;
@@ -501,7 +504,7 @@ fn validate_rename(
     if candidate.conflicting_contents {
         return Err(content_modified());
     }
-    let mut contents = HashMap::<Url, (Rope, Option<i32>)>::new();
+    let mut contents = HashMap::<Url, (SourceText, Option<i32>)>::new();
     let source_map = SourceMap::empty();
     for (uri, analyzed_contents) in &candidate.analyzed_contents {
         let Some((file_contents, version)) = rename_file_contents(&vfs, &source_map, uri) else {
@@ -540,7 +543,7 @@ fn rename_file_contents(
     vfs: &solar_interface::data_structures::sync::RwLock<crate::vfs::Vfs>,
     source_map: &SourceMap,
     uri: &Url,
-) -> Option<(Rope, Option<i32>)> {
+) -> Option<(SourceText, Option<i32>)> {
     let path = crate::proto::vfs_path(uri)?;
     let vfs = vfs.read();
     if let Some(contents) = vfs.get_file_contents(&path) {
@@ -548,7 +551,7 @@ fn rename_file_contents(
     }
     drop(vfs);
     let contents = source_map.file_loader().load_file(path.as_path()?).ok()?;
-    Some((Rope::from(contents), None))
+    Some((SourceText::from(contents), None))
 }
 
 fn content_modified() -> ResponseError {
@@ -2,7 +2,10 @@
 
 use lsp_types::{MarkupContent, MarkupKind};
 use solar_interface::Symbol;
-use solar_sema::{Gcx, hir, ty::NatSpecView};
+use solar_sema::{
+    Gcx, hir,
+    ty::{NatSpecView, TyKind},
+};
 use std::fmt::Write;
 
 pub(crate) fn render(gcx: Gcx<'_>, item_id: hir::ItemId) -> Option<MarkupContent> {
@@ -11,10 +14,10 @@ pub(crate) fn render(gcx: Gcx<'_>, item_id: hir::ItemId) -> Option<MarkupContent
         hir::ItemId::Variable(id) => render_variable(gcx, id),
         hir::ItemId::Event(id) => render_event(gcx, id),
         hir::ItemId::Error(id) => render_error(gcx, id),
-        hir::ItemId::Contract(_)
-        | hir::ItemId::Struct(_)
-        | hir::ItemId::Enum(_)
-        | hir::ItemId::Udvt(_) => None,
+        hir::ItemId::Contract(_) | hir::ItemId::Struct(_) | hir::ItemId::Enum(_) => {
+            Some(gcx.type_of_item(item_id).display(gcx).to_string())
+        }
+        hir::ItemId::Udvt(id) => render_udvt(gcx, id),
     }?;
     let mut value = format!("```solidity\n{signature}\n```");
     append_documentation(&mut value, &documentation(gcx, item_id));
@@ -383,6 +386,12 @@ fn render_error(gcx: Gcx<'_>, id: hir::ErrorId) -> Option<String> {
     Some(signature)
 }
 
+fn render_udvt(gcx: Gcx<'_>, id: hir::UdvtId) -> Option<String> {
+    let ty = gcx.type_of_item(id.into());
+    let TyKind::Udvt(underlying, _) = ty.kind else { return None };
+    Some(format!("type {} is {}", ty.display(gcx), underlying.display(gcx)))
+}
+
 fn render_variables(
     gcx: Gcx<'_>,
     variables: &[hir::VariableId],
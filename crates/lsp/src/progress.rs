@@ -13,6 +13,7 @@ use lsp_types::{
 };
 use solar_interface::data_structures::sync::Mutex;
 use std::{
+    borrow::Cow,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -167,9 +168,9 @@ impl ProgressTicket {
         self.guard.is_none()
     }
 
-    pub(crate) fn report(&self, message: &'static str) {
+    pub(crate) fn report(&self, message: impl Into<Cow<'static, str>>) {
         if let Some(guard) = &self.guard {
-            guard.report(self.version, message);
+            guard.report(self.version, message.into());
         }
     }
 
@@ -191,7 +192,7 @@ enum Phase {
 struct ProgressState {
     version: usize,
     phase: Phase,
-    message: Option<&'static str>,
+    message: Option<Cow<'static, str>>,
     terminal: Option<&'static str>,
     restart_reported: bool,
     create_timed_out: bool,
@@ -338,13 +339,13 @@ impl WorkDoneProgressGuard {
             } else {
                 state.restart_reported = true;
             }
-        } else if state.message != Some(RESTART_MESSAGE) {
-            state.message = Some(RESTART_MESSAGE);
+        } else if state.message.as_deref() != Some(RESTART_MESSAGE) {
+            state.message = Some(RESTART_MESSAGE.into());
         }
         true
     }
 
-    fn report(&self, version: usize, message: &'static str) {
+    fn report(&self, version: usize, message: Cow<'static, str>) {
         let mut state = self.state.lock();
         if state.version != version || state.terminal.is_some() {
             return;
@@ -356,7 +357,7 @@ impl WorkDoneProgressGuard {
                 &self.token,
                 WorkDoneProgress::Report(WorkDoneProgressReport {
                     cancellable: Some(false),
-                    message: Some(message.into()),
+                    message: Some(message.into_owned()),
                     percentage: None,
                 }),
             ) {
@@ -380,7 +381,7 @@ impl WorkDoneProgressGuard {
             }
             Phase::Creating => {
                 if state.terminal.is_none() {
-                    state.message = Some(message);
+                    state.message = Some(message.into());
                     state.terminal = Some(message);
                 }
             }
@@ -412,7 +413,7 @@ impl WorkDoneProgressGuard {
                 state.message = None;
             }
             Phase::Creating => {
-                state.message = Some(message);
+                state.message = Some(message.into());
                 state.terminal = Some(message);
             }
             Phase::Begun => {
@@ -438,11 +439,11 @@ impl WorkDoneProgressGuard {
 
         state.phase = Phase::Begun;
         let message = state.message.take();
-        let is_restart = message == Some(RESTART_MESSAGE);
+        let is_restart = message.as_deref() == Some(RESTART_MESSAGE);
         let begin = WorkDoneProgress::Begin(WorkDoneProgressBegin {
             title: PROGRESS_TITLE.into(),
             cancellable: Some(false),
-            message: message.map(str::to_owned),
+            message: message.map(Cow::into_owned),
             percentage: None,
         });
         if !send_progress(&self.client, &self.token, begin) {
@@ -2,9 +2,9 @@ use crate::vfs::{self, VfsPath};
 use crop::Rope;
 use lsp_types::{DiagnosticSeverity, NumberOrString};
 use solar_interface::{
-    CharPos, SourceMap, Span,
+    SourceMap, Span,
     diagnostics::{Diag, Level},
-    source_map::SpanLoc,
+    source_map::ColumnEncoding,
 };
 
 pub(crate) fn vfs_path(url: &lsp_types::Url) -> Option<vfs::VfsPath> {
@@ -218,38 +218,19 @@ pub(crate) fn diagnostic(
 }
 
 pub(crate) fn span_to_location(source_map: &SourceMap, span: Span) -> Option<lsp_types::Location> {
-    let (file, SpanLoc { lo, hi }) = source_map.span_to_location_info(span);
+    let (file, _) = source_map.span_to_location_info(span);
     let file = file?;
+    let position = |pos| {
+        let (line, character) = file.lookup_line_col(pos, ColumnEncoding::Utf16);
+        Some(lsp_types::Position::new(line.try_into().ok()?, character.try_into().ok()?))
+    };
 
     Some(lsp_types::Location {
-        uri: lsp_types::Url::from_file_path(file.name.as_real().unwrap()).ok()?,
-        range: lsp_types::Range {
-            start: lsp_types::Position {
-                line: lo.line as u32 - 1,
-                character: utf16_column(lo.col, file.get_line(lo.line - 1)?),
-            },
-            end: lsp_types::Position {
-                line: hi.line as u32 - 1,
-                character: utf16_column(hi.col, file.get_line(hi.line - 1)?),
-            },
-        },
+        uri: lsp_types::Url::from_file_path(file.name.as_real()?).ok()?,
+        range: lsp_types::Range { start: position(span.lo())?, end: position(span.hi())? },
     })
 }
 
-/// Takes a UTF8 string slice and a UTF8 character position (relative to the line start), and
-/// converts the position to a UTF16 character position.
-fn utf16_column(utf8_pos: CharPos, line: &str) -> u32 {
-    let mut utf16_codepoints = 0;
-    for (idx, char) in line.chars().enumerate() {
-        if idx >= utf8_pos.to_usize() {
-            break;
-        }
-        utf16_codepoints += char.len_utf16();
-    }
-
-    utf16_codepoints as u32
-}
-
 #[inline]
 fn severity(level: Level) -> lsp_types::DiagnosticSeverity {
     match level {
@@ -651,6 +651,26 @@ impl RenameIndex {
         Some(symbols)
     }
 
+    /// Returns the declarations of the `import * as X` aliases referenced at the given position.
+    pub(crate) fn namespace_alias_locations_at(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Option<Vec<Location>> {
+        let occurrence = self.occurrence_at(uri, position)?;
+        let locations = occurrence
+            .targets
+            .iter()
+            .filter_map(|&target| match target {
+                RenameTarget::ImportAlias(alias_id) => {
+                    Some(self.aliases[alias_id].location.clone())
+                }
+                RenameTarget::Symbol(_) | RenameTarget::MappingName(_) => None,
+            })
+            .collect::<Vec<_>>();
+        (!locations.is_empty()).then_some(locations)
+    }
+
     fn add_namespace_alias(
         &mut self,
         gcx: Gcx<'_>,
@@ -729,7 +749,16 @@ impl RenameIndex {
         else {
             return;
         };
+        // Each namespace qualifier is declared in the source that the previous one refers to.
+        let mut alias_source = context.source;
         for (&ident, resolutions) in qualifiers.iter().zip(resolutions) {
+            let namespace_source = alias_source;
+            if let Some(namespace) = resolutions.iter().find_map(|res| match *res {
+                hir::Res::Namespace(namespace) => Some(namespace),
+                _ => None,
+            }) {
+                alias_source = namespace;
+            }
             let targets = resolutions
                 .into_iter()
                 .filter_map(|resolution| match resolution {
@@ -751,7 +780,7 @@ impl RenameIndex {
                         .bindings
                         .aliases
                         .get(&ImportBindingKey {
-                            source: context.source,
+                            source: namespace_source,
                             resolution: ImportBindingResolution::Namespace(namespace),
                             name: ident.name,
                         })
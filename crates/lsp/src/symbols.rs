@@ -962,14 +962,16 @@ impl SymbolTables {
         position: Position,
         target: NavigationTarget,
     ) -> Option<Vec<Location>> {
-        let symbol_ids = self.symbol_ids_at_position(uri, position)?;
-        let mut locations = symbol_ids
+        let mut locations = self
+            .symbol_ids_at_position(uri, position)
             .into_iter()
+            .flatten()
             .filter(|&symbol_id| target.includes(self, symbol_id))
             .map(|symbol_id| self.selection_location(symbol_id))
             .collect::<Vec<_>>();
         if locations.is_empty() {
-            return None;
+            // Namespace references navigate to their `import ... as X` alias.
+            locations = self.rename.namespace_alias_locations_at(uri, position)?;
         }
         sort_locations(&mut locations);
         locations.dedup_by(|a, b| a.uri == b.uri && a.range == b.range);
@@ -1333,7 +1335,7 @@ impl<'gcx> ScopeBuilder<'_, 'gcx> {
         match stmt.kind {
             StmtKind::Block(block)
             | StmtKind::UncheckedBlock(block)
-            | StmtKind::AssemblyBlock(block)
+            | StmtKind::AssemblyBlock(&hir::StmtAssembly { block, .. })
             | StmtKind::Loop(block, _) => self.visit_block_scope(block),
             _ => {
                 let _ = self.visit_stmt(stmt);
@@ -1454,7 +1456,7 @@ impl<'gcx> hir::Visit<'gcx> for ScopeBuilder<'_, 'gcx> {
             }
             StmtKind::Block(block)
             | StmtKind::UncheckedBlock(block)
-            | StmtKind::AssemblyBlock(block)
+            | StmtKind::AssemblyBlock(&hir::StmtAssembly { block, .. })
             | StmtKind::Loop(block, _) => self.visit_block_scope(block),
             StmtKind::If(_, true_, false_) => {
                 self.visit_statement_child_scope(true_);
@@ -1635,9 +1637,35 @@ impl<'gcx> ReferenceCollector<'_, 'gcx> {
         self.visit_expr(receiver)?;
         let targets = self.symbol_ids_for_member_expr(expr);
         self.push_reference_with_kind(ident.span, targets, kind);
+        // Nested namespaces (`X.Y`) are aliased in the source that `X` refers to.
+        if let Some(Res::Namespace(namespace)) = self.gcx.resolved_member(expr.id)
+            && let Some(receiver_source) = self.namespace_of_expr(receiver)
+        {
+            self.tables.rename.push_namespace_reference(
+                self.gcx,
+                self.bindings,
+                receiver_source,
+                ident.span,
+                [namespace],
+            );
+        }
         ControlFlow::Continue(())
     }
 
+    fn namespace_of_expr(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<hir::SourceId> {
+        let res = match expr.kind {
+            hir::ExprKind::Ident(resolutions) => {
+                resolutions.iter().copied().find(|res| matches!(res, Res::Namespace(_)))?
+            }
+            hir::ExprKind::Member(..) => self.gcx.resolved_member(expr.id)?,
+            _ => return None,
+        };
+        match res {
+            Res::Namespace(source) => Some(source),
+            _ => None,
+        }
+    }
+
     fn visit_lvalue(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Never> {
         match expr.kind {
             hir::ExprKind::Ident(resolutions) => {
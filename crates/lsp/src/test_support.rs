@@ -3,8 +3,8 @@ use crate::{
     project_fixture::{FixtureMarker, ProjectFixture},
     vfs::{Vfs, VfsPath},
 };
-use crop::Rope;
 use lsp_types::{InitializeParams, Url, WorkspaceFolder};
+use solar_interface::source_map::SourceText;
 use std::{
     fs,
     io::Read,
@@ -134,7 +134,7 @@ impl TestProject {
         for (path, contents) in &self.open_files {
             vfs.set_file_contents_with_version(
                 VfsPath::from(path.clone()),
-                Some(Rope::from(contents.as_str())),
+                Some(SourceText::from(contents.as_str())),
                 Some(0),
             );
         }
@@ -162,3 +162,49 @@ fn distinguishes_function_declarations_from_definitions() {
 "#]],
     );
 }
+
+#[test]
+fn resolves_nested_namespace_aliases() {
+    let fixture = RequestFixture::new(
+        r#"
+        //- /Lib.sol
+        function helper() pure returns (uint256) {
+            return 1;
+        }
+
+        //- /Middle.sol
+        import * as Lib from "./Lib.sol";
+
+        //- /Main.sol
+        import * as Middle from "./Middle.sol";
+        contract C {
+            function f() public pure returns (uint256) {
+                return $1Middle.$2Lib.$3helper();
+            }
+        }
+        "#,
+        "/Main.sol",
+    );
+
+    fixture.check_goto_definition(
+        "$1",
+        str![[r#"
+/Main.sol:0:12 import * as Middle from "./Middle.sol";
+
+"#]],
+    );
+    fixture.check_goto_definition(
+        "$2",
+        str![[r#"
+/Middle.sol:0:12 import * as Lib from "./Lib.sol";
+
+"#]],
+    );
+    fixture.check_goto_definition(
+        "$3",
+        str![[r#"
+/Lib.sol:0:9 function helper() pure returns (uint256) {
+
+"#]],
+    );
+}
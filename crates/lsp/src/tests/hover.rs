@@ -949,14 +949,79 @@ function read(uint256 leafValue) public pure override returns (uint256 leafResul
 }
 
 #[test]
-fn returns_no_hover_for_unsupported_or_non_symbol_positions() {
-    let fixture = RequestFixture::new_allowing_diagnostics(
+fn shows_user_defined_types() {
+    let fixture = RequestFixture::new(
         r#"
-        //- /Unsupported.sol open
+        //- /Types.sol open
         contract $1C {
             struct $2Data {}
             enum $3Kind { A }
+            type $4Amount is uint128;
+        }
+
+        library $5L {}
+        "#,
+        "/Types.sol",
+    );
+
+    fixture.check_hover(
+        "$1",
+        str![[r#"
+0:9-0:10
+```solidity
+contract C
+```
+
+"#]],
+    );
+    fixture.check_hover(
+        "$2",
+        str![[r#"
+1:11-1:15
+```solidity
+struct C.Data
+```
+
+"#]],
+    );
+    fixture.check_hover(
+        "$3",
+        str![[r#"
+2:9-2:13
+```solidity
+enum C.Kind
+```
+
+"#]],
+    );
+    fixture.check_hover(
+        "$4",
+        str![[r#"
+3:9-3:15
+```solidity
+type C.Amount is uint128
+```
 
+"#]],
+    );
+    fixture.check_hover(
+        "$5",
+        str![[r#"
+5:8-5:9
+```solidity
+library L
+```
+
+"#]],
+    );
+}
+
+#[test]
+fn returns_no_hover_for_non_symbol_positions() {
+    let fixture = RequestFixture::new_allowing_diagnostics(
+        r#"
+        //- /Unsupported.sol open
+        contract C {
             function use() public returns (uint256) {
                 $7uint256 value = $4missing;
                 return $8 1;
@@ -969,7 +1034,7 @@ fn returns_no_hover_for_unsupported_or_non_symbol_positions() {
         "/Unsupported.sol",
     );
 
-    for marker in ["$1", "$2", "$3", "$4", "$5", "$7", "$8"] {
+    for marker in ["$4", "$5", "$7", "$8"] {
         fixture.check_hover(marker, "<none>\n");
     }
 }
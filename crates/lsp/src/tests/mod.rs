@@ -4,6 +4,7 @@ use crate::test_support::process_exists;
 use crate::{
     config::negotiate_capabilities,
     test_support::{MarkedProject, TestProject},
+    vfs::VfsPath,
 };
 use async_lsp::{ClientSocket, ErrorCode, ResponseError, ServerSocket, router::Router};
 use lsp_types::{
@@ -16,6 +17,7 @@ use lsp_types::{
     VersionedTextDocumentIdentifier, WatchKind, WorkDoneProgress, WorkDoneProgressCreateParams,
     WorkDoneProgressParams, WorkspaceSymbol, notification, notification::Notification, request,
 };
+use solar_interface::source_map::SourceText;
 use std::{
     future::Future,
     path::Path,
@@ -145,6 +147,8 @@ fn snapshot_with_config(config: Config, vfs: Vfs) -> GlobalStateSnapshot {
         analysis_version: Arc::new(AtomicUsize::new(1)),
         published_analysis_version,
         analysis_commit: Arc::new(Default::default()),
+        analysis_cache: Arc::new(Default::default()),
+        analysis_source_map: Arc::new(Mutex::new(Arc::new(SourceMap::empty()))),
         flycheck_versions: Arc::new(Default::default()),
         symbol_tables: Arc::new(Default::default()),
         diagnostics: Arc::new(Default::default()),
@@ -364,7 +368,7 @@ fn replacement_analysis_invalidates_old_worker_before_removed_diagnostics_publis
     let uri = diagnostic_uri();
     let mut state = GlobalState::new(ClientSocket::new_closed());
     let (stale_version, _stale_progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     state.snapshot().publish_diagnostics(
         DiagnosticOwner::Compiler,
         DiagnosticMap::from_iter([(uri.clone(), vec![diagnostic("removed")])]),
@@ -372,7 +376,7 @@ fn replacement_analysis_invalidates_old_worker_before_removed_diagnostics_publis
 
     assert_analysis_stale_before_diagnostic_publication(state, stale_version, move |state| {
         state
-            .begin_analysis(AnalysisMode::Recompute, vec![uri], Vec::new())
+            .begin_analysis(AnalysisMode::Recompute, vec![uri], Vec::new(), false)
             .expect("replacement analysis should start");
     });
 }
@@ -546,7 +550,7 @@ async fn clearing_analysis_cache_publishes_before_ending_progress() {
     }
 
     let (_, progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     progress.report("Analyzing workspace");
     let WorkDoneEvent::Create(create) = harness.next_event().await else {
         panic!("expected progress creation")
@@ -608,7 +612,7 @@ async fn clearing_analysis_cache_supersedes_a_pending_progress_end() {
     assert!(matches!(harness.next_event().await, WorkDoneEvent::Diagnostics(_)));
 
     let (_, progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     let WorkDoneEvent::Create(create) = harness.next_event().await else {
         panic!("expected progress creation")
     };
@@ -666,7 +670,7 @@ async fn superseded_analysis_cannot_publish_or_end_latest_progress() {
         ProgressCoordinator::with_timing(client, true, Duration::ZERO, Duration::from_secs(1));
 
     let (stale_version, stale_progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     let mut stale_snapshot = state.snapshot();
     let WorkDoneEvent::Create(create) = harness.next_event().await else {
         panic!("expected progress creation")
@@ -682,7 +686,7 @@ async fn superseded_analysis_cannot_publish_or_end_latest_progress() {
     ));
 
     let (latest_version, latest_progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     let mut latest_snapshot = state.snapshot();
     match harness.next_event().await {
         WorkDoneEvent::Progress(ProgressParams {
@@ -796,7 +800,7 @@ async fn failed_current_analysis_ends_visible_progress() {
     state.analysis_progress =
         ProgressCoordinator::with_timing(client, true, Duration::ZERO, Duration::from_secs(1));
     let (version, progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
 
     let WorkDoneEvent::Create(create) = harness.next_event().await else {
         panic!("expected progress creation")
@@ -856,7 +860,7 @@ async fn failed_current_analysis_recovers_after_save() {
     );
 
     let (version, progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     let task = tokio::spawn(async { panic!("test analysis failure") });
     state.monitor_analysis_task(version, task, progress);
 
@@ -914,7 +918,7 @@ async fn cancelled_current_analysis_recovers_after_save() {
     state.config = Arc::new(project.config());
 
     let (version, progress) =
-        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new()).unwrap();
+        state.begin_analysis(AnalysisMode::Recompute, Vec::new(), Vec::new(), false).unwrap();
     let task = tokio::spawn(std::future::pending::<AnalysisTaskOutcome>());
     task.abort();
     state.monitor_analysis_task(version, task, progress);
@@ -1152,6 +1156,94 @@ fn did_open_tracks_the_source_until_analysis_publishes() {
     });
 }
 
+#[test]
+fn analysis_reanalyzes_only_units_with_edited_files() {
+    let project = TestProject::from_fixture(
+        r#"
+        //- /A.sol open
+        contract A {}
+
+        //- /B.sol open
+        contract B {}
+        "#,
+    );
+    let snapshot = snapshot(&project);
+    let b_path = project.path("/B.sol");
+    let b_uri = Url::from_file_path(&b_path).unwrap();
+    let edit_b = |contents: &str| {
+        snapshot
+            .vfs
+            .write()
+            .set_file_contents(VfsPath::from(b_path.clone()), Some(SourceText::from(contents)));
+    };
+    let analyze = || {
+        let batch = snapshot.analysis_batches(Vec::new()).pop().unwrap();
+        snapshot.analyze_batch(1, 0, batch, None)
+    };
+    let dirty_files = || {
+        let batch = snapshot.analysis_batches(Vec::new()).pop().unwrap();
+        let plan = snapshot.analysis_cache.lock().plan(0, &batch.files);
+        let AnalysisPlan::Units(units) = plan else {
+            panic!("expected an incremental analysis");
+        };
+        units
+            .into_iter()
+            .filter(|unit| unit.result.is_none())
+            .flat_map(|unit| unit.files.into_iter().map(|(path, _)| path))
+            .collect::<Vec<_>>()
+    };
+
+    analyze();
+    assert_eq!(snapshot.analysis_cache.lock().batches[&0].units.len(), 2);
+
+    // The first edit analyzes both units on their own, and later edits reuse the clean one.
+    edit_b("contract B { function f() public { missing; } }");
+    assert!(analyze().diagnostics.contains_key(&b_uri));
+    edit_b("contract B {}");
+    assert_eq!(dirty_files(), [b_path.clone()]);
+    assert!(!analyze().diagnostics.contains_key(&b_uri));
+
+    // Importing the other unit joins both units.
+    edit_b("import \"./A.sol\"; contract B is A {}");
+    let result = analyze();
+    assert!(result.diagnostics.is_empty(), "{:#?}", result.diagnostics);
+    assert_eq!(snapshot.analysis_cache.lock().batches[&0].units.len(), 1);
+}
+
+#[test]
+fn analysis_evicts_closed_documents_from_the_source_map() {
+    let project = TestProject::from_fixture(
+        r#"
+        //- /Request.sol
+        contract Request {}
+        "#,
+    );
+    let snapshot = snapshot(&project);
+    let unsaved = project.path("/Unsaved.sol");
+    let unsaved_uri = Url::from_file_path(&unsaved).unwrap();
+    let set_unsaved = |contents: Option<&str>| {
+        snapshot
+            .vfs
+            .write()
+            .set_file_contents(VfsPath::from(unsaved.clone()), contents.map(SourceText::from));
+    };
+    let analyze = || snapshot.analyze(snapshot.analysis_batches(Vec::new()).pop().unwrap(), None);
+
+    set_unsaved(Some("contract Unsaved { function f() public { missing; } }"));
+    assert!(analyze().diagnostics.contains_key(&unsaved_uri));
+
+    // Edited documents are loaded again instead of reusing the previous contents.
+    set_unsaved(Some("contract Unsaved {}"));
+    let result = analyze();
+    assert!(!result.diagnostics.contains_key(&unsaved_uri), "{:#?}", result.diagnostics);
+
+    set_unsaved(None);
+    analyze();
+    let source_map = snapshot.analysis_source_map.lock();
+    assert!(source_map.get_file(unsaved).is_none());
+    assert!(source_map.is_empty());
+}
+
 #[test]
 fn did_close_tracks_the_source_until_analysis_publishes() {
     let project = TestProject::from_fixture(
@@ -13,6 +13,7 @@ use lsp_types::{
 };
 use snapbox::{IntoData, assert_data_eq};
 use solar_config::CompileOpts;
+use solar_interface::source_map::SourceText;
 use std::{
     fmt::Write as _,
     future::Future,
@@ -164,7 +165,7 @@ impl RequestFixture {
         let uri = Url::from_file_path(&path).unwrap();
         state.vfs.write().set_file_contents(
             crate::vfs::VfsPath::from(path),
-            Some(crop::Rope::from(changed_contents)),
+            Some(SourceText::from(changed_contents)),
         );
         let position = self.marked.marker(marker).position();
         let response =
@@ -190,7 +191,7 @@ impl RequestFixture {
             state.mark_source_analysis_pending_for_test(path.clone());
             state.vfs.write().set_file_contents(
                 crate::vfs::VfsPath::from(path),
-                Some(crop::Rope::from(contents)),
+                Some(SourceText::from(contents)),
             );
         }
         let uri = Url::from_file_path(self.marked.project().path(request_path)).unwrap();
@@ -508,7 +509,7 @@ impl RequestFixture {
         let mut state = self.state();
         state.vfs.write().set_file_contents(
             crate::vfs::VfsPath::from(path),
-            Some(crop::Rope::from(changed_contents)),
+            Some(SourceText::from(changed_contents)),
         );
         *state.symbol_tables.write() = result.symbol_tables;
         let position = self.marked.marker(marker).position();
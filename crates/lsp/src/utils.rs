@@ -1,17 +1,17 @@
 use crate::proto;
-use crop::Rope;
+use solar_interface::source_map::SourceText;
 use std::mem;
 
 pub(crate) fn apply_document_changes(
-    file_contents: &Rope,
+    file_contents: &SourceText,
     mut content_changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
-) -> Rope {
+) -> SourceText {
     // If at least one of the changes is a full document change, use the last
     // of them as the starting point and ignore all previous changes.
     let (mut text, content_changes) =
         match content_changes.iter().rposition(|change| change.range.is_none()) {
             Some(idx) => {
-                let text = Rope::from(mem::take(&mut content_changes[idx].text));
+                let text = SourceText::from(mem::take(&mut content_changes[idx].text));
                 (text, &content_changes[idx + 1..])
             }
             None => (file_contents.clone(), &content_changes[..]),
@@ -29,8 +29,8 @@ pub(crate) fn apply_document_changes(
 #[cfg(test)]
 mod tests {
     use crate::utils::apply_document_changes;
-    use crop::Rope;
     use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+    use solar_interface::source_map::SourceText;
 
     #[test]
     fn test_apply_document_changes() {
@@ -47,7 +47,7 @@ mod tests {
             };
         }
 
-        let text = apply_document_changes(&Rope::new(), vec![]);
+        let text = apply_document_changes(&SourceText::new(), vec![]);
         assert_eq!(text, "");
 
         let text = apply_document_changes(
@@ -90,19 +90,20 @@ mod tests {
         let text = apply_document_changes(&text, c![0, 10; 1, 5 => "", 2, 0; 2, 12 => ""]);
         assert_eq!(text, "the quick \nthey have quiet dreams\n");
 
-        let text = Rope::from("❤️");
+        let text = SourceText::from("❤️");
         let text = apply_document_changes(&text, c![0, 0; 0, 0 => "a"]);
         assert_eq!(text, "a❤️");
 
-        let text = Rope::from("a\nb");
+        let text = SourceText::from("a\nb");
         let text = apply_document_changes(&text, c![0, 1; 1, 0 => "\nțc", 0, 1; 1, 1 => "d"]);
         assert_eq!(text, "adcb");
 
-        let text = Rope::from("a\nb");
+        let text = SourceText::from("a\nb");
         let text = apply_document_changes(&text, c![0, 1; 1, 0 => "ț\nc", 0, 2; 0, 2 => "c"]);
         assert_eq!(text, "ațc\ncb");
 
-        let text = Rope::from("function increment() public {\n    // 中文😀\n    umber++;\n}");
+        let text =
+            SourceText::from("function increment() public {\n    // 中文😀\n    umber++;\n}");
         let text = apply_document_changes(&text, c![2, 4; 2, 9 => "number"]);
         assert_eq!(text, "function increment() public {\n    // 中文😀\n    number++;\n}");
     }
@@ -14,21 +14,22 @@
 //! readily available optimizations we can do, such as path interning, and moving IO out of the hot
 //! path, which would be more [`rust-analyzer`](https://github.com/rust-lang/rust-analyzer/)-esque.
 //!
-//! It is also possible to change the VFS to use a [rope](https://en.wikipedia.org/wiki/Rope_(data_structure)) internally. Originally this was considered, but it does not seem to offer a lot of performance benefit in regular use cases for the scale that most Solidity projects have.
+//! File contents are stored as [`SourceText`], a [rope] with a line index, so incremental edits
+//! from the client don't rescan the whole document.
 //!
 //! We can also cache source files in-memory as we compile, as the compiler output includes all
 //! loaded source files along with their paths. This can prevent additional IO, but care must be
 //! taken here as to not end up loading the entire project into memory needlessly.
 //!
 //! [`SourceFile`]: solar_interface::source_map::SourceFile
+//! [rope]: https://en.wikipedia.org/wiki/Rope_(data_structure)
 
 use super::VfsPath;
-use crop::Rope;
-use solar_interface::data_structures::map::rustc_hash::FxHashMap;
+use solar_interface::{data_structures::map::rustc_hash::FxHashMap, source_map::SourceText};
 
 #[derive(Default)]
 pub(crate) struct Vfs {
-    data: FxHashMap<VfsPath, Rope>,
+    data: FxHashMap<VfsPath, SourceText>,
     versions: FxHashMap<VfsPath, i32>,
     dirty: bool,
 }
@@ -36,14 +37,14 @@ pub(crate) struct Vfs {
 impl Vfs {
     /// Set the contents of a file. A content of `None` means the file is to be removed from the
     /// VFS.
-    pub(crate) fn set_file_contents(&mut self, path: VfsPath, contents: Option<Rope>) {
+    pub(crate) fn set_file_contents(&mut self, path: VfsPath, contents: Option<SourceText>) {
         self.set_file_contents_with_version(path, contents, None);
     }
 
     pub(crate) fn set_file_contents_with_version(
         &mut self,
         path: VfsPath,
-        contents: Option<Rope>,
+        contents: Option<SourceText>,
         version: Option<i32>,
     ) {
         if let Some(contents) = contents {
@@ -60,7 +61,7 @@ impl Vfs {
         self.dirty = true;
     }
 
-    pub(crate) fn get_file_contents(&self, path: &VfsPath) -> Option<&Rope> {
+    pub(crate) fn get_file_contents(&self, path: &VfsPath) -> Option<&SourceText> {
         self.data.get(path)
     }
 
@@ -88,7 +89,7 @@ impl Vfs {
     }
 
     /// Returns an iterator over stored paths and their corresponding contents.
-    pub(crate) fn iter(&self) -> impl Iterator<Item = (&VfsPath, &Rope)> {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&VfsPath, &SourceText)> {
         self.data.iter()
     }
 }
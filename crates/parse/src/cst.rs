@@ -0,0 +1,588 @@
+//! Lossless concrete syntax tree.
+//!
+//! The [`Cst`] preserves the exact source text, including whitespace, comments, and parentheses,
+//! which the AST discards. It is built on demand from a parsed [`SourceUnit`](ast::SourceUnit)
+//! and the source it was parsed from, so tools that do not need it, such as the compiler, pay
+//! nothing for it.
+//!
+//! The tree is split into two layers:
+//! - the green tree ([`GreenNode`], [`GreenToken`]) is immutable, position-independent, and can be
+//!   shared;
+//! - the red tree ([`CstNode`], [`CstToken`]) is a cheap view over the green tree that knows the
+//!   absolute position of each element.
+//!
+//! Every AST node with a span maps to a CST node of the corresponding [`CstKind`], which can be
+//! looked up with [`Cst::node`]. Trivia is attached to the innermost node that encloses it, so
+//! leading and trailing trivia of a node belongs to its parent.
+
+use crate::{
+    Lexer,
+    lexer::token::{FullToken, RawTokenKind},
+};
+use solar_ast::{self as ast, visit::Visit, yul};
+use solar_data_structures::Never;
+use solar_interface::{BytePos, Span, source_map::SourceFile};
+use std::{fmt, ops::ControlFlow, sync::Arc};
+
+/// The kind of a [`CstNode`].
+///
+/// Each kind corresponds to the AST node with the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CstKind {
+    /// The root node, spanning the entire source.
+    SourceUnit,
+    /// [`ast::Item`].
+    Item,
+    /// [`ast::VariableDefinition`].
+    VariableDefinition,
+    /// [`ast::ParameterList`].
+    ParameterList,
+    /// [`ast::CallArgs`].
+    CallArgs,
+    /// [`ast::Block`].
+    Block,
+    /// [`ast::Stmt`].
+    Stmt,
+    /// [`ast::Expr`].
+    ///
+    /// Parenthesized expressions are tuples with a single component, so their parentheses are
+    /// tokens of the enclosing expression node.
+    Expr,
+    /// [`ast::Type`].
+    Type,
+    /// [`yul::Block`].
+    YulBlock,
+    /// [`yul::Stmt`].
+    YulStmt,
+    /// [`yul::Expr`].
+    YulExpr,
+}
+
+/// A leaf of the green tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: RawTokenKind,
+    text: Box<str>,
+}
+
+impl GreenToken {
+    /// Creates a new token.
+    pub fn new(kind: RawTokenKind, text: &str) -> Self {
+        Self { kind, text: text.into() }
+    }
+
+    /// Returns the kind of the token.
+    #[inline]
+    pub fn kind(&self) -> RawTokenKind {
+        self.kind
+    }
+
+    /// Returns the exact source text of the token.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// An inner node of the green tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: CstKind,
+    len: usize,
+    children: Box<[GreenElement]>,
+}
+
+impl GreenNode {
+    /// Creates a new node from its children.
+    pub fn new(kind: CstKind, children: impl IntoIterator<Item = GreenElement>) -> Self {
+        let children = children.into_iter().collect::<Box<[_]>>();
+        let len = children.iter().map(GreenElement::text_len).sum();
+        Self { kind, len, children }
+    }
+
+    /// Returns the kind of the node.
+    #[inline]
+    pub fn kind(&self) -> CstKind {
+        self.kind
+    }
+
+    /// Returns the length of the source text of the node, in bytes.
+    #[inline]
+    pub fn text_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the children of the node.
+    #[inline]
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.children.iter().try_for_each(|child| fmt::Display::fmt(child, f))
+    }
+}
+
+/// A child of a [`GreenNode`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    /// Returns the length of the source text of the element, in bytes.
+    #[inline]
+    pub fn text_len(&self) -> usize {
+        match self {
+            Self::Node(node) => node.text_len(),
+            Self::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl fmt::Display for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(node) => fmt::Display::fmt(node, f),
+            Self::Token(token) => f.write_str(token.text()),
+        }
+    }
+}
+
+/// A lossless concrete syntax tree of a source file.
+///
+/// # Examples
+///
+/// ```
+/// use solar_parse::{
+///     Parser, ast,
+///     cst::{Cst, CstKind},
+///     interface::Session,
+/// };
+///
+/// let sess = Session::builder().with_silent_emitter(None).single_threaded().build();
+/// sess.enter_sequential(|| {
+///     let src = "contract C {\n    // Comment.\n    uint x = (1 + 2) * 3;\n}\n";
+///     let file = sess.source_map().new_source_file("C.sol".to_string(), src).unwrap();
+///     let arena = ast::Arena::new();
+///     let unit = Parser::from_source_file(&sess, &arena, &file).parse_file().unwrap();
+///
+///     let cst = Cst::from_source_file(&file, &unit);
+///     assert_eq!(cst.to_string(), src);
+///
+///     let ast::ItemKind::Contract(contract) = &unit.items[0].kind else { unreachable!() };
+///     let ast::ItemKind::Variable(var) = &contract.body[0].kind else { unreachable!() };
+///     let init = var.initializer.as_ref().unwrap();
+///     assert_eq!(cst.node(CstKind::Expr, init.span).unwrap().to_string(), "(1 + 2) * 3");
+/// });
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cst {
+    root: Arc<GreenNode>,
+    start_pos: BytePos,
+}
+
+impl Cst {
+    /// Builds the CST of a source file from its AST.
+    pub fn from_source_file(file: &SourceFile, unit: &ast::SourceUnit<'_>) -> Self {
+        Self::new(&file.src, file.start_pos, unit)
+    }
+
+    /// Builds the CST of `src` from its AST.
+    ///
+    /// `start_pos` is the position of the start of `src` in the source map, which is used to map
+    /// AST spans to offsets in `src`.
+    pub fn new(src: &str, start_pos: BytePos, unit: &ast::SourceUnit<'_>) -> Self {
+        let mut collector = SpanCollector { nodes: Vec::new(), start_pos, len: src.len() };
+        let _ = collector.visit_source_unit(unit);
+        let mut nodes = collector.nodes;
+        // Sort outer nodes before inner nodes that start at the same position, keeping the visit
+        // order otherwise.
+        nodes.sort_by_key(|&(_, lo, hi)| (lo, std::cmp::Reverse(hi)));
+        nodes.dedup();
+        let tokens = Lexer::tokenize_with_trivia(src);
+        let root = build(src, &tokens, &nodes);
+        Self { root: Arc::new(root), start_pos }
+    }
+
+    /// Returns the root node, which spans the entire source.
+    #[inline]
+    pub fn root(&self) -> CstNode<'_> {
+        CstNode { green: &self.root, offset: self.start_pos.to_usize() }
+    }
+
+    /// Returns the root of the green tree.
+    #[inline]
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.root
+    }
+
+    /// Returns the outermost node of `kind` that has exactly the given span.
+    ///
+    /// This maps AST nodes to their CST nodes, e.g. `cst.node(CstKind::Expr, expr.span)`.
+    pub fn node(&self, kind: CstKind, span: Span) -> Option<CstNode<'_>> {
+        let mut node = self.root();
+        loop {
+            if node.kind() == kind && node.span() == span {
+                return Some(node);
+            }
+            node = node.child_nodes().find(|child| child.span().contains(span))?;
+        }
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)
+    }
+}
+
+/// A node of the CST, with its absolute position.
+#[derive(Clone, Copy, Debug)]
+pub struct CstNode<'a> {
+    green: &'a GreenNode,
+    offset: usize,
+}
+
+impl<'a> CstNode<'a> {
+    /// Returns the kind of the node.
+    #[inline]
+    pub fn kind(self) -> CstKind {
+        self.green.kind
+    }
+
+    /// Returns the span of the node, including any trivia inside of it.
+    #[inline]
+    pub fn span(self) -> Span {
+        span(self.offset, self.green.len)
+    }
+
+    /// Returns the underlying green node.
+    #[inline]
+    pub fn green(self) -> &'a GreenNode {
+        self.green
+    }
+
+    /// Returns an iterator over the children of the node.
+    pub fn children(self) -> impl Iterator<Item = CstElement<'a>> + Clone {
+        self.green.children.iter().scan(self.offset, |offset, child| {
+            let element = match child {
+                GreenElement::Node(green) => CstElement::Node(CstNode { green, offset: *offset }),
+                GreenElement::Token(green) => {
+                    CstElement::Token(CstToken { green, offset: *offset })
+                }
+            };
+            *offset += child.text_len();
+            Some(element)
+        })
+    }
+
+    /// Returns an iterator over the child nodes of the node.
+    pub fn child_nodes(self) -> impl Iterator<Item = CstNode<'a>> + Clone {
+        self.children().filter_map(CstElement::into_node)
+    }
+
+    /// Returns all the tokens of the node, including trivia, in source order.
+    pub fn tokens(self) -> Vec<CstToken<'a>> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens(self, tokens: &mut Vec<CstToken<'a>>) {
+        for child in self.children() {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(tokens),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+}
+
+impl fmt::Display for CstNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.green, f)
+    }
+}
+
+/// A token of the CST, with its absolute position.
+#[derive(Clone, Copy, Debug)]
+pub struct CstToken<'a> {
+    green: &'a GreenToken,
+    offset: usize,
+}
+
+impl<'a> CstToken<'a> {
+    /// Returns the kind of the token.
+    #[inline]
+    pub fn kind(self) -> RawTokenKind {
+        self.green.kind
+    }
+
+    /// Returns the exact source text of the token.
+    #[inline]
+    pub fn text(self) -> &'a str {
+        &self.green.text
+    }
+
+    /// Returns the span of the token.
+    #[inline]
+    pub fn span(self) -> Span {
+        span(self.offset, self.green.text.len())
+    }
+
+    /// Returns `true` if this token is a whitespace, line comment, or block comment.
+    #[inline]
+    pub fn is_trivial(self) -> bool {
+        self.kind().is_trivial()
+    }
+}
+
+impl fmt::Display for CstToken<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+/// A child of a [`CstNode`].
+#[derive(Clone, Copy, Debug)]
+pub enum CstElement<'a> {
+    Node(CstNode<'a>),
+    Token(CstToken<'a>),
+}
+
+impl<'a> CstElement<'a> {
+    /// Returns the node, if this is a node.
+    #[inline]
+    pub fn into_node(self) -> Option<CstNode<'a>> {
+        match self {
+            Self::Node(node) => Some(node),
+            Self::Token(_) => None,
+        }
+    }
+
+    /// Returns the token, if this is a token.
+    #[inline]
+    pub fn into_token(self) -> Option<CstToken<'a>> {
+        match self {
+            Self::Node(_) => None,
+            Self::Token(token) => Some(token),
+        }
+    }
+
+    /// Returns the span of the element.
+    #[inline]
+    pub fn span(self) -> Span {
+        match self {
+            Self::Node(node) => node.span(),
+            Self::Token(token) => token.span(),
+        }
+    }
+}
+
+fn span(offset: usize, len: usize) -> Span {
+    Span::new(BytePos::from_usize(offset), BytePos::from_usize(offset + len))
+}
+
+/// A node that is being built.
+struct OpenNode {
+    kind: CstKind,
+    end: usize,
+    children: Vec<GreenElement>,
+}
+
+/// Builds the green tree by nesting `tokens` into `nodes`, which must be sorted by start position.
+///
+/// Nodes that do not start and end on token boundaries, or that are not properly nested in their
+/// enclosing node, are skipped, so that the tree always reproduces `src` exactly.
+fn build(src: &str, tokens: &[FullToken], nodes: &[(CstKind, usize, usize)]) -> GreenNode {
+    let is_boundary = |pos: usize| {
+        pos == src.len() || tokens.binary_search_by_key(&pos, |t| t.range.start).is_ok()
+    };
+    let finish = |stack: &mut Vec<OpenNode>| {
+        let node = stack.pop().unwrap();
+        let green = GreenNode::new(node.kind, node.children);
+        stack.last_mut().unwrap().children.push(GreenElement::Node(Arc::new(green)));
+    };
+
+    let mut stack =
+        vec![OpenNode { kind: CstKind::SourceUnit, end: src.len(), children: Vec::new() }];
+    let mut nodes = nodes.iter().copied().peekable();
+    for token in tokens {
+        let pos = token.range.start;
+        while stack.len() > 1 && stack.last().unwrap().end <= pos {
+            finish(&mut stack);
+        }
+        while let Some(&(kind, lo, hi)) = nodes.peek()
+            && lo <= pos
+        {
+            nodes.next();
+            if lo == pos && hi <= stack.last().unwrap().end && is_boundary(hi) {
+                stack.push(OpenNode { kind, end: hi, children: Vec::new() });
+            }
+        }
+        let green = GreenToken::new(token.kind, &src[token.range.clone()]);
+        stack.last_mut().unwrap().children.push(GreenElement::Token(Arc::new(green)));
+    }
+    while stack.len() > 1 {
+        finish(&mut stack);
+    }
+    let root = stack.pop().unwrap();
+    GreenNode::new(root.kind, root.children)
+}
+
+/// Collects the spans of the AST nodes, relative to the start of the source.
+struct SpanCollector {
+    nodes: Vec<(CstKind, usize, usize)>,
+    start_pos: BytePos,
+    len: usize,
+}
+
+impl SpanCollector {
+    fn push(&mut self, kind: CstKind, span: Span) {
+        if span.is_dummy() || span.lo() < self.start_pos {
+            return;
+        }
+        let lo = (span.lo() - self.start_pos).to_usize();
+        let hi = (span.hi() - self.start_pos).to_usize();
+        if lo < hi && hi <= self.len {
+            self.nodes.push((kind, lo, hi));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SpanCollector {
+    type BreakValue = Never;
+
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::Item, item.span);
+        self.walk_item(item)
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::VariableDefinition, var.span);
+        self.walk_variable_definition(var)
+    }
+
+    fn visit_parameter_list(
+        &mut self,
+        list: &'ast ast::ParameterList<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::ParameterList, list.span);
+        self.walk_parameter_list(list)
+    }
+
+    fn visit_call_args(
+        &mut self,
+        args: &'ast ast::CallArgs<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::CallArgs, args.span);
+        self.walk_call_args(args)
+    }
+
+    fn visit_block(&mut self, block: &'ast ast::Block<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::Block, block.span);
+        self.walk_block(block)
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::Stmt, stmt.span);
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::Expr, expr.span);
+        self.walk_expr(expr)
+    }
+
+    fn visit_ty(&mut self, ty: &'ast ast::Type<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::Type, ty.span);
+        self.walk_ty(ty)
+    }
+
+    fn visit_yul_block(&mut self, block: &'ast yul::Block<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::YulBlock, block.span);
+        self.walk_yul_block(block)
+    }
+
+    fn visit_yul_stmt(&mut self, stmt: &'ast yul::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::YulStmt, stmt.span);
+        self.walk_yul_stmt(stmt)
+    }
+
+    fn visit_yul_expr(&mut self, expr: &'ast yul::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.push(CstKind::YulExpr, expr.span);
+        self.walk_yul_expr(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use solar_interface::Session;
+
+    #[test]
+    fn lossless() {
+        let src = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Docs.
+contract C {
+    uint256 public x = ((1 + 2)) * 3; // Trailing.
+
+    function f(uint a, /* b */ uint b) external returns (uint) {
+        assembly {
+            let y := add(a,  b)
+        }
+        return  g( a , b );
+    }
+}
+"#;
+        let sess =
+            Session::builder().with_buffer_emitter(Default::default()).single_threaded().build();
+        sess.enter_sequential(|| {
+            let arena = ast::Arena::new();
+            let file = sess.source_map().new_source_file("test.sol".to_string(), src).unwrap();
+            let unit = Parser::from_source_file(&sess, &arena, &file).parse_file().unwrap();
+            let cst = Cst::from_source_file(&file, &unit);
+
+            assert_eq!(cst.to_string(), src);
+            let root = cst.root();
+            assert_eq!(root.kind(), CstKind::SourceUnit);
+            assert_eq!(root.span(), Span::new(file.start_pos, file.end_position()));
+            assert_eq!(root.tokens().iter().map(|t| t.text()).collect::<String>(), src);
+
+            // Every AST node maps to a CST node with the same text.
+            let ast::ItemKind::Contract(contract) = &unit.items[1].kind else { panic!() };
+            let item = cst.node(CstKind::Item, unit.items[1].span).unwrap();
+            assert!(item.to_string().starts_with("contract C {"));
+            assert!(item.to_string().ends_with('}'));
+
+            let ast::ItemKind::Variable(var) = &contract.body[0].kind else { panic!() };
+            let init = var.initializer.as_ref().unwrap();
+            let node = cst.node(CstKind::Expr, init.span).unwrap();
+            assert_eq!(node.to_string(), "((1 + 2)) * 3");
+            let parens = node.child_nodes().next().unwrap();
+            assert_eq!(parens.kind(), CstKind::Expr);
+            assert_eq!(parens.to_string(), "((1 + 2))");
+
+            let ast::ItemKind::Function(f) = &contract.body[1].kind else { panic!() };
+            let params = cst.node(CstKind::ParameterList, f.header.parameters.span).unwrap();
+            assert_eq!(params.to_string(), "(uint a, /* b */ uint b)");
+            let comment = params.tokens().into_iter().find(|t| t.kind().is_comment()).unwrap();
+            assert_eq!(comment.text(), "/* b */");
+            assert_eq!(sess.source_map().span_to_snippet(comment.span()).unwrap(), "/* b */");
+
+            let body = f.body.as_ref().unwrap();
+            let ret = cst.node(CstKind::Stmt, body.stmts[1].span).unwrap();
+            assert_eq!(ret.to_string(), "return  g( a , b );");
+        });
+    }
+}
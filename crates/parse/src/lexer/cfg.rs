@@ -0,0 +1,159 @@
+//! Conditional compilation directives.
+//!
+//! With `-Zcfg-directives`, regions of a source are included or excluded depending on the keys
+//! defined with `--cfg`:
+//!
+//! ```solidity
+//! // solar:if mock
+//! contract Oracle is MockOracle {}
+//! // solar:else
+//! contract Oracle is ChainlinkOracle {}
+//! // solar:endif
+//! ```
+//!
+//! A condition is either `KEY`, which holds if `KEY` is defined, or `KEY=VALUE`, which holds if
+//! `KEY` is defined with that value. Regions can be nested. Excluded regions are still lexed, but
+//! their tokens are dropped, so the spans of the remaining tokens are unchanged.
+//!
+//! Only line comments of the exact forms `solar:if`, `solar:else` and `solar:endif` are
+//! directives; any other comment, such as `// solar: note`, is an ordinary comment.
+
+use solar_ast::token::{CommentKind, Token, TokenKind};
+use solar_interface::{Session, Span};
+
+/// The conditional compilation state of a source being lexed.
+pub(super) struct CfgDirectives<'sess> {
+    sess: &'sess Session,
+    /// The open regions, innermost last.
+    regions: Vec<Region>,
+}
+
+struct Region {
+    /// The span of the `solar:if` directive.
+    span: Span,
+    /// Whether the condition holds.
+    condition: bool,
+    /// Whether the enclosing region is included.
+    parent_included: bool,
+    /// Whether the current branch is included.
+    included: bool,
+    /// Whether the `solar:else` branch has started.
+    in_else: bool,
+}
+
+impl<'sess> CfgDirectives<'sess> {
+    /// Returns the initial state, or `None` if directives are disabled.
+    pub(super) fn new(sess: &'sess Session) -> Option<Self> {
+        sess.opts.unstable.cfg_directives.then(|| Self { sess, regions: Vec::new() })
+    }
+
+    /// Processes `token`, returning whether it is included in the token stream.
+    ///
+    /// Directives themselves are never included.
+    pub(super) fn include(&mut self, token: &Token) -> bool {
+        if let TokenKind::Comment(false, CommentKind::Line, content) = token.kind
+            && let Some((name, arg)) = parse_directive(content.as_str())
+        {
+            self.directive(name, arg, token.span);
+            return false;
+        }
+        self.is_included()
+    }
+
+    /// Reports the regions that are still open at the end of the source.
+    pub(super) fn finish(self) {
+        for region in self.regions {
+            self.sess
+                .dcx
+                .err("unterminated `solar:if` directive")
+                .span(region.span)
+                .help("close the region with `// solar:endif`")
+                .emit();
+        }
+    }
+
+    fn is_included(&self) -> bool {
+        self.regions.last().is_none_or(|region| region.included)
+    }
+
+    fn directive(&mut self, name: &str, arg: &str, span: Span) {
+        if matches!(name, "else" | "endif") && !arg.is_empty() {
+            self.sess
+                .dcx
+                .err(format!("`solar:{name}` does not take a condition"))
+                .span(span)
+                .emit();
+        }
+        match name {
+            "if" => {
+                let condition = self.condition(arg, span);
+                let parent_included = self.is_included();
+                self.regions.push(Region {
+                    span,
+                    condition,
+                    parent_included,
+                    included: parent_included && condition,
+                    in_else: false,
+                });
+            }
+            "else" => {
+                let Some(region) = self.regions.last_mut() else {
+                    return self.unmatched(name, span);
+                };
+                if region.in_else {
+                    self.sess
+                        .dcx
+                        .err("`solar:else` directive after another `solar:else`")
+                        .span(span)
+                        .span_note(region.span, "in the region opened here")
+                        .emit();
+                    return;
+                }
+                region.in_else = true;
+                region.included = region.parent_included && !region.condition;
+            }
+            "endif" => {
+                if self.regions.pop().is_none() {
+                    self.unmatched(name, span);
+                }
+            }
+            _ => unreachable!("unknown directive `solar:{name}`"),
+        }
+    }
+
+    /// Evaluates the condition of a `solar:if` directive.
+    fn condition(&self, condition: &str, span: Span) -> bool {
+        let (key, value) = match condition.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim())),
+            None => (condition, None),
+        };
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            self.sess
+                .dcx
+                .err("expected a condition of the form `KEY` or `KEY=VALUE`")
+                .span(span)
+                .emit();
+            return false;
+        }
+        self.sess.opts.cfg.iter().any(|cfg| cfg.matches(key, value))
+    }
+
+    fn unmatched(&self, name: &str, span: Span) {
+        self.sess
+            .dcx
+            .err(format!("`solar:{name}` directive without a `solar:if`"))
+            .span(span)
+            .emit();
+    }
+}
+
+/// Splits the content of a line comment into the name and the argument of a directive, or returns
+/// `None` if the comment is not a directive.
+fn parse_directive(content: &str) -> Option<(&str, &str)> {
+    let directive = content.trim().strip_prefix("solar:")?;
+    let (name, arg) = match directive.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (directive, ""),
+    };
+    matches!(name, "if" | "else" | "endif").then_some((name, arg))
+}
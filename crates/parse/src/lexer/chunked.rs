@@ -0,0 +1,266 @@
+//! Chunked, parallel lexing of large sources.
+//!
+//! Large sources are split into chunks at line breaks that are not inside a comment or a string
+//! literal. Tokens never span such a boundary, so each chunk can be lexed independently, and the
+//! token streams of consecutive chunks are simply concatenated.
+
+use super::{Lexer, file_prefix_len};
+use solar_ast::token::Token;
+use solar_interface::BytePos;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Sources at least this large are lexed in chunks by [`Lexer::with_token_stream`].
+pub const CHUNKED_LEXING_THRESHOLD: usize = 4 << 20;
+
+/// The minimum size of each chunk, in bytes.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// The number of non-comment tokens that are guaranteed to be buffered after the current one.
+///
+/// This bounds [`Parser::look_ahead`](crate::Parser::look_ahead) when tokens are streamed.
+pub(crate) const LOOK_AHEAD: usize = 16;
+
+type Chunks = Arc<[Mutex<Option<Vec<Token>>>]>;
+
+/// A stream of lexed tokens.
+///
+/// Created from a vector of tokens, or by [`Lexer::with_token_stream`], in which case tokens are
+/// produced by other threads while they are being consumed.
+pub struct TokenStream {
+    /// The buffered tokens.
+    buf: std::vec::IntoIter<Token>,
+    /// The number of non-comment tokens in `buf`.
+    non_comments: usize,
+    /// The chunks after the first one, which are being lexed on the thread pool.
+    chunks: Chunks,
+    /// The index of the next chunk to append to `buf`.
+    next_chunk: usize,
+}
+
+impl From<Vec<Token>> for TokenStream {
+    fn from(tokens: Vec<Token>) -> Self {
+        // There is nothing left to append, so `non_comments` is never checked.
+        Self {
+            buf: tokens.into_iter(),
+            non_comments: usize::MAX,
+            chunks: Arc::new([]),
+            next_chunk: 0,
+        }
+    }
+}
+
+impl TokenStream {
+    fn chunked(first: Vec<Token>, chunks: Chunks) -> Self {
+        let non_comments = count_non_comments(&first);
+        Self { buf: first.into_iter(), non_comments, chunks, next_chunk: 0 }
+    }
+
+    /// Returns the buffered tokens.
+    ///
+    /// If tokens are being streamed, at least [`LOOK_AHEAD`] non-comment tokens are buffered,
+    /// unless the end of the source has been reached.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[Token] {
+        self.buf.as_slice()
+    }
+
+    #[cold]
+    fn append_next_chunk(&mut self) {
+        let chunk = self.wait_for_chunk(self.next_chunk);
+        self.next_chunk += 1;
+        self.non_comments += count_non_comments(&chunk);
+        let rest = self.buf.as_slice();
+        self.buf = if rest.is_empty() {
+            chunk.into_iter()
+        } else {
+            let mut buf = Vec::with_capacity(rest.len() + chunk.len());
+            buf.extend_from_slice(rest);
+            buf.extend(chunk);
+            buf.into_iter()
+        };
+    }
+
+    fn wait_for_chunk(&self, index: usize) -> Vec<Token> {
+        loop {
+            let tokens = self.chunks[index].lock().unwrap_or_else(PoisonError::into_inner).take();
+            if let Some(tokens) = tokens {
+                return tokens;
+            }
+            // Help with the pending chunks instead of blocking, which could deadlock the thread
+            // pool if all of its threads are waiting.
+            if !matches!(rayon::yield_now(), Some(rayon::Yield::Executed)) {
+                std::thread::yield_now();
+            }
+        }
+    }
+}
+
+impl Iterator for TokenStream {
+    type Item = Token;
+
+    #[inline]
+    fn next(&mut self) -> Option<Token> {
+        while self.non_comments <= LOOK_AHEAD && self.next_chunk < self.chunks.len() {
+            self.append_next_chunk();
+        }
+        let token = self.buf.next()?;
+        if !token.is_comment_or_doc() {
+            self.non_comments -= 1;
+        }
+        Some(token)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.buf.size_hint();
+        if self.next_chunk < self.chunks.len() { (lo, None) } else { (lo, hi) }
+    }
+}
+
+impl Lexer<'_, '_> {
+    /// Consumes the lexer and calls `f` with a stream of the remaining tokens.
+    ///
+    /// Sources of at least [`CHUNKED_LEXING_THRESHOLD`] bytes are split into chunks which are
+    /// lexed in parallel on the current thread pool, while `f` consumes the tokens that have
+    /// already been produced. Other sources are lexed with [`into_tokens`](Self::into_tokens).
+    ///
+    /// Note that when lexing in chunks, the diagnostics of different chunks may be emitted in any
+    /// order.
+    #[instrument(name = "lex_chunked", level = "debug", skip_all)]
+    pub fn with_token_stream<R>(self, f: impl FnOnce(TokenStream) -> R) -> R {
+        // Conditional compilation regions can span chunks, so they need a single pass.
+        let prefix = file_prefix_len(self.sess, self.src);
+        if self.src.len() < CHUNKED_LEXING_THRESHOLD
+            || self.src_index(self.pos) != prefix
+            || self.sess.opts.unstable.cfg_directives
+            || rayon::current_num_threads() <= 1
+        {
+            return f(self.into_tokens().into());
+        }
+        let mut boundaries = chunk_boundaries(&self.src[prefix..], CHUNK_SIZE);
+        boundaries.iter_mut().for_each(|boundary| *boundary += prefix);
+        let Some(&first_end) = boundaries.first() else {
+            return f(self.into_tokens().into());
+        };
+        debug!(src.len = self.src.len(), chunks = boundaries.len() + 1, "lexing in chunks");
+
+        let Self { sess, src, start_pos, .. } = self;
+        let chunks: Chunks = boundaries.iter().map(|_| Mutex::new(None)).collect();
+        rayon::in_place_scope(|scope| {
+            let ends = boundaries.iter().skip(1).copied().chain(std::iter::once(src.len()));
+            for (i, (start, end)) in boundaries.iter().copied().zip(ends).enumerate() {
+                let chunks = chunks.clone();
+                scope.spawn(move |_| {
+                    let start_pos = start_pos + BytePos(start as u32);
+                    let tokens = Lexer::with_start_pos(sess, &src[start..end], start_pos);
+                    let tokens = tokens.into_tokens();
+                    *chunks[i].lock().unwrap_or_else(PoisonError::into_inner) = Some(tokens);
+                });
+            }
+
+            let first = Lexer::with_start_pos(sess, &src[..first_end], start_pos);
+            let first = first.skip_file_prefix().into_tokens();
+            f(TokenStream::chunked(first, chunks))
+        })
+    }
+}
+
+fn count_non_comments(tokens: &[Token]) -> usize {
+    tokens.iter().filter(|token| !token.is_comment_or_doc()).count()
+}
+
+/// Returns the start offsets of the chunks after the first one, each at least `chunk_size` bytes
+/// after the previous one.
+///
+/// Every offset is right after a line break which is not inside a comment or a string literal.
+/// This mirrors the way the [`Cursor`](super::Cursor) lexes comments and strings.
+fn chunk_boundaries(src: &str, chunk_size: usize) -> Vec<usize> {
+    let bytes = src.as_bytes();
+    let mut boundaries = Vec::new();
+    let mut target = chunk_size;
+    let mut pos = 0;
+    while target < bytes.len() {
+        // Only comments and string literals can span multiple lines.
+        let next =
+            memchr::memchr3(b'/', b'"', b'\'', &bytes[pos..]).map_or(bytes.len(), |i| pos + i);
+        if next > target {
+            let from = target.max(pos);
+            if let Some(i) = memchr::memchr(b'\n', &bytes[from..next]) {
+                let boundary = from + i + 1;
+                if boundary == bytes.len() {
+                    break;
+                }
+                boundaries.push(boundary);
+                target = boundary + chunk_size;
+                pos = boundary;
+                continue;
+            }
+        }
+        if next == bytes.len() {
+            break;
+        }
+        pos = skip_multiline_token(bytes, next);
+    }
+    boundaries
+}
+
+/// Skips the comment, string literal, or slash starting at `pos`, returning the position after it.
+fn skip_multiline_token(bytes: &[u8], pos: usize) -> usize {
+    let rest = |start: usize| bytes.get(start..).unwrap_or_default();
+    match bytes[pos] {
+        b'/' => {
+            match bytes.get(pos + 1) {
+                Some(b'/') => memchr::memchr2(b'\n', b'\r', rest(pos + 2))
+                    .map_or(bytes.len(), |i| pos + 2 + i),
+                Some(b'*') => memchr::memmem::find(rest(pos + 2), b"*/")
+                    .map_or(bytes.len(), |i| pos + 2 + i + 2),
+                _ => pos + 1,
+            }
+        }
+        quote => {
+            let mut pos = pos + 1;
+            loop {
+                let Some(i) = memchr::memchr2(quote, b'\\', rest(pos)) else {
+                    return bytes.len();
+                };
+                pos += i;
+                if bytes[pos] == quote {
+                    return pos + 1;
+                }
+                // Skip the escaped character if it is a backslash or the quote.
+                pos += 1;
+                if matches!(bytes.get(pos), Some(&c) if c == b'\\' || c == quote) {
+                    pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::Session;
+
+    #[test]
+    fn boundaries_skip_comments_and_strings() {
+        let src = "a\n/* x\ny */ b\n\"c\nd\" e\n'f\\'\ng' h\n// i\nj\n";
+        let boundaries = chunk_boundaries(src, 1);
+        let expected = ["/* x", "\"c", "'f", "// i", "j"]
+            .map(|prefix| src.find(&format!("\n{prefix}")).unwrap() + 1);
+        assert_eq!(boundaries, expected);
+    }
+
+    #[test]
+    fn chunked_tokens_match() {
+        let item = "/// Doc.\ncontract C {\n    /* multi\n line */ string s = \"a\\\"\nb\";\n}\n";
+        let src = item.repeat(CHUNKED_LEXING_THRESHOLD / item.len() + 1);
+        let sess = Session::builder().with_silent_emitter(None).threads(2).build();
+        sess.enter(|| {
+            let expected = Lexer::new(&sess, &src).into_tokens();
+            let tokens =
+                Lexer::new(&sess, &src).with_token_stream(|tokens| tokens.collect::<Vec<_>>());
+            assert_eq!(tokens, expected);
+        });
+    }
+}
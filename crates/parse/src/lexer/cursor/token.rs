@@ -4,6 +4,7 @@ use solar_ast::{
     Base, StrKind,
     token::{BinOpToken, Delimiter},
 };
+use std::ops::Range;
 
 /// A raw token.
 ///
@@ -140,6 +141,50 @@ impl RawTokenKind {
     pub const fn is_trivial(&self) -> bool {
         matches!(self, Self::Whitespace | Self::LineComment { .. } | Self::BlockComment { .. })
     }
+
+    /// Returns `true` if this token is malformed, and would be reported as an error by the
+    /// [`Lexer`](crate::Lexer).
+    ///
+    /// This includes unknown characters, unterminated comments and strings, and number literals
+    /// with missing digits.
+    #[inline]
+    pub const fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Unknown
+                | Self::BlockComment { terminated: false, .. }
+                | Self::Literal {
+                    kind: RawLiteralKind::Str { terminated: false, .. }
+                        | RawLiteralKind::Int { empty_int: true, .. }
+                        | RawLiteralKind::Rational { empty_exponent: true, .. }
+                }
+        )
+    }
+}
+
+/// A raw token with its exact byte range in the source, including trivia.
+///
+/// Created by [`Lexer::tokenize_with_trivia`](crate::Lexer::tokenize_with_trivia).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FullToken {
+    /// The kind of token.
+    pub kind: RawTokenKind,
+    /// The byte range of the token in the source string.
+    pub range: Range<usize>,
+}
+
+impl FullToken {
+    /// Returns `true` if this token is a whitespace, line comment, or block comment.
+    #[inline]
+    pub const fn is_trivial(&self) -> bool {
+        self.kind.is_trivial()
+    }
+
+    /// Returns `true` if this token is malformed. See [`RawTokenKind::is_error`].
+    #[inline]
+    pub const fn is_error(&self) -> bool {
+        self.kind.is_error()
+    }
 }
 
 /// The literal types supported by the lexer.
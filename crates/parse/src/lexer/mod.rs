@@ -9,8 +9,14 @@ use solar_interface::{
     BytePos, Session, Span, Symbol, diagnostics::DiagCtxt, source_map::SourceFile,
 };
 
+mod cfg;
+use cfg::CfgDirectives;
+
+mod chunked;
+pub use chunked::{CHUNKED_LEXING_THRESHOLD, TokenStream};
+
 mod cursor;
-use cursor::token::{RawLiteralKind, RawToken, RawTokenKind};
+use cursor::token::{FullToken, RawLiteralKind, RawToken, RawTokenKind};
 pub use cursor::*;
 
 pub mod unescape;
@@ -44,18 +50,25 @@ pub struct Lexer<'sess, 'src> {
 
 impl<'sess, 'src> Lexer<'sess, 'src> {
     /// Creates a new `Lexer` for the given source string.
+    ///
+    /// A leading byte order mark, and a `#!` line with `-Zshebang`, are skipped.
     pub fn new(sess: &'sess Session, src: &'src str) -> Self {
-        Self::with_start_pos(sess, src, BytePos(0))
+        Self::with_start_pos(sess, src, BytePos(0)).skip_file_prefix()
     }
 
     /// Creates a new `Lexer` for the given source file.
     ///
+    /// A leading byte order mark, and a `#!` line with `-Zshebang`, are skipped.
+    ///
     /// Note that the source file must be added to the source map before calling this function.
     pub fn from_source_file(sess: &'sess Session, file: &'src SourceFile) -> Self {
-        Self::with_start_pos(sess, &file.src, file.start_pos)
+        Self::with_start_pos(sess, &file.src, file.start_pos).skip_file_prefix()
     }
 
     /// Creates a new `Lexer` for the given source string and starting position.
+    ///
+    /// Unlike [`new`](Self::new), this does not skip a byte order mark or a `#!` line, as `src`
+    /// is not necessarily the start of a file.
     pub fn with_start_pos(sess: &'sess Session, src: &'src str, start_pos: BytePos) -> Self {
         assert!(sess.is_entered(), "session should be entered before lexing");
         Self {
@@ -68,6 +81,16 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
         }
     }
 
+    /// Advances past the prefix of a file which is not part of the token stream.
+    fn skip_file_prefix(mut self) -> Self {
+        let len = file_prefix_len(self.sess, self.src);
+        if len > 0 {
+            self.cursor = Cursor::new(&self.src[len..]);
+            self.pos = self.start_pos + BytePos::from_usize(len);
+        }
+        self
+    }
+
     /// Returns a reference to the diagnostic context.
     #[inline]
     pub fn dcx(&self) -> &'sess DiagCtxt {
@@ -76,23 +99,33 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
 
     /// Consumes the lexer and collects the remaining tokens into a vector.
     ///
-    /// Note that this skips comments, as [required by the parser](crate::Parser::new).
+    /// Note that this skips comments, as [required by the parser](crate::Parser::new), and the
+    /// regions excluded by `-Zcfg-directives`.
     ///
     /// Prefer using this method instead of manually collecting tokens using [`Iterator`].
     #[instrument(name = "lex", level = "debug", skip_all)]
     pub fn into_tokens(mut self) -> Vec<Token> {
         // This is an estimate of the number of tokens in the source.
         let mut tokens = Vec::with_capacity(self.src.len() / 4);
+        let mut cfg = CfgDirectives::new(self.sess);
         loop {
             let token = self.slop();
             if token.is_eof() {
                 break;
             }
+            if let Some(cfg) = &mut cfg
+                && !cfg.include(&token)
+            {
+                continue;
+            }
             if token.is_comment() {
                 continue;
             }
             tokens.push(token);
         }
+        if let Some(cfg) = cfg {
+            cfg.finish();
+        }
         trace!(
             src.len = self.src.len(),
             tokens.len = tokens.len(),
@@ -148,8 +181,11 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
                     TokenKind::Ident(sym)
                 }
                 RawTokenKind::Literal { kind } => {
-                    let (kind, symbol) = self.cook_literal(start, self.pos, kind);
-                    TokenKind::Literal(kind, symbol)
+                    let (lit_kind, symbol) = self.cook_literal(start, self.pos, kind);
+                    if !matches!(kind, RawLiteralKind::Str { .. }) {
+                        self.eat_number_suffix(start, raw_kind.is_error());
+                    }
+                    TokenKind::Literal(lit_kind, symbol)
                 }
 
                 // Expression-operator symbols.
@@ -274,6 +310,33 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
         token
     }
 
+    /// Eats an identifier which directly follows a number literal, like the `g3` in `0x12g3`, as
+    /// part of the literal.
+    ///
+    /// This reports the malformed literal once, and keeps the digits before the suffix as the
+    /// value of the literal, instead of having the parser fail on an unexpected identifier.
+    #[inline]
+    fn eat_number_suffix(&mut self, lit_start: BytePos, reported: bool) {
+        if !self.cursor.as_bytes().first().is_some_and(|&b| is_id_continue_byte(b)) {
+            return;
+        }
+        cold_path();
+        let mut cursor = self.cursor.clone();
+        let RawToken { kind: RawTokenKind::Ident, len } = cursor.slop() else { return };
+        self.cursor = cursor;
+        let start = self.pos;
+        self.pos += len;
+        if !reported {
+            let span = self.new_span(start, self.pos);
+            let msg = "identifier-start is not allowed at the end of a number";
+            let mut err = self.dcx().err(msg).span(span);
+            if self.str_from_to(lit_start, start) == "0" && self.str_from(start).starts_with('X') {
+                err = err.help("hexadecimal literals start with a lowercase `0x` prefix");
+            }
+            err.emit();
+        }
+    }
+
     fn cook_doc_comment(
         &self,
         _content_start: BytePos,
@@ -413,6 +476,25 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
     }
 }
 
+impl Lexer<'_, '_> {
+    /// Tokenizes the given source string, keeping all trivia.
+    ///
+    /// Unlike [`into_tokens`](Self::into_tokens), the returned tokens include comments, whitespace
+    /// runs, and malformed tokens, and together cover the entire input. No diagnostics are emitted
+    /// and no session is required, making this suitable for syntax highlighters and formatters.
+    ///
+    /// Use [`FullToken::is_error`] to find tokens that the lexer would report as errors.
+    pub fn tokenize_with_trivia(src: &str) -> Vec<FullToken> {
+        Cursor::new(src)
+            .with_position()
+            .map(|(pos, token)| FullToken {
+                kind: token.kind,
+                range: pos..pos + token.len as usize,
+            })
+            .collect()
+    }
+}
+
 impl Iterator for Lexer<'_, '_> {
     type Item = Token;
 
@@ -425,6 +507,20 @@ impl Iterator for Lexer<'_, '_> {
 
 impl std::iter::FusedIterator for Lexer<'_, '_> {}
 
+/// Returns the length of the prefix of a file which is skipped by the lexer: a UTF-8 byte order
+/// mark, followed by a `#!` line if enabled with `-Zshebang`.
+///
+/// The line break ending the `#!` line is not part of the prefix, so that line numbers and columns
+/// of the following lines are unaffected.
+fn file_prefix_len(sess: &Session, src: &str) -> usize {
+    let mut len = if src.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    if sess.opts.unstable.shebang && src[len..].starts_with("#!") {
+        let line = &src[len..];
+        len += memchr::memchr2(b'\n', b'\r', line.as_bytes()).unwrap_or(line.len());
+    }
+    len
+}
+
 /// Pushes a character to a message string for error reporting
 fn escaped_char(c: char) -> String {
     match c {
@@ -543,11 +639,17 @@ mod tests {
 
         checks_full![
             ("0b0", true, &[(0..3, lit(Integer, "0b0"))]),
-            ("0B0", false, &[(0..1, lit(Integer, "0")), (1..3, id("B0"))]),
+            ("0B0", true, &[(0..3, lit(Integer, "0"))]),
             ("0o0", true, &[(0..3, lit(Integer, "0o0"))]),
-            ("0O0", false, &[(0..1, lit(Integer, "0")), (1..3, id("O0"))]),
+            ("0O0", true, &[(0..3, lit(Integer, "0"))]),
             ("0xa", false, &[(0..3, lit(Integer, "0xa"))]),
-            ("0Xa", false, &[(0..1, lit(Integer, "0")), (1..3, id("Xa"))]),
+            ("0Xa", true, &[(0..3, lit(Integer, "0"))]),
+            // Identifiers directly after a number are part of the malformed literal.
+            ("0x12g3", true, &[(0..6, lit(Integer, "0x12"))]),
+            ("0xg 1", true, &[(0..3, lit(Integer, "0x")), (4..5, lit(Integer, "1"))]),
+            ("123abc;", true, &[(0..6, lit(Integer, "123")), (6..7, Semi)]),
+            ("1.5e3x", true, &[(0..6, lit(Rational, "1.5e3"))]),
+            ("1hex\"00\"", false, &[(0..1, lit(Integer, "1")), (1..8, lit(HexStr, "00"))]),
         ];
     }
 
@@ -700,4 +802,102 @@ mod tests {
             ("- -", &[(0..1, BinOp(Minus)), (2..3, BinOp(Minus))]),
         ];
     }
+
+    #[test]
+    fn file_prefix() {
+        checks![
+            ("\u{feff}", &[]),
+            ("\u{feff}a b", &[(3..4, id("a")), (5..6, id("b"))]),
+            ("\u{feff}\na", &[(4..5, id("a"))]),
+        ];
+
+        let mut opts = solar_interface::config::CompileOpts::default();
+        opts.unstable.shebang = true;
+        let sess = Session::builder()
+            .with_buffer_emitter(Default::default())
+            .single_threaded()
+            .opts(opts)
+            .build();
+        sess.enter_sequential(|| {
+            for (src, expected) in [
+                ("#!/usr/bin/env solar\na", 21..22),
+                ("#!/usr/bin/env solar\r\na", 22..23),
+                ("\u{feff}#!solar\na", 11..12),
+            ] {
+                let file = sess.source_map().new_source_file(format!("{src:?}"), src).unwrap();
+                let token = Lexer::from_source_file(&sess, &file).next().unwrap();
+                let span = token.span.lo().to_usize()..token.span.hi().to_usize();
+                assert_eq!((span, token.kind), (expected, id("a")), "{src:?}");
+                let loc = sess.source_map().lookup_char_pos(token.span.lo()).data;
+                assert_eq!((loc.line, loc.col.0), (src.lines().count(), 0), "{src:?}");
+            }
+            assert!(sess.dcx.has_errors().is_ok());
+        });
+    }
+
+    #[test]
+    fn crlf() {
+        let lf = "a\n\tb /* \n */ \"c\"\n\n  d";
+        let crlf = lf.replace('\n', "\r\n");
+        let sess =
+            Session::builder().with_buffer_emitter(Default::default()).single_threaded().build();
+        sess.enter_sequential(|| {
+            let sm = sess.source_map();
+            let lf = sm.new_source_file("lf".to_string(), lf).unwrap();
+            let crlf = sm.new_source_file("crlf".to_string(), crlf).unwrap();
+            let lf_tokens = Lexer::from_source_file(&sess, &lf).into_tokens();
+            let crlf_tokens = Lexer::from_source_file(&sess, &crlf).into_tokens();
+            assert!(sess.dcx.has_errors().is_ok());
+            assert_eq!(lf_tokens.len(), 4);
+            assert_eq!(lf_tokens.len(), crlf_tokens.len());
+            for (lf_token, crlf_token) in lf_tokens.iter().zip(&crlf_tokens) {
+                assert_eq!(lf_token.kind, crlf_token.kind);
+                let len = |token: &Token| token.span.hi() - token.span.lo();
+                assert_eq!(len(lf_token), len(crlf_token));
+                for (lf_pos, crlf_pos) in [
+                    (lf_token.span.lo(), crlf_token.span.lo()),
+                    (lf_token.span.hi(), crlf_token.span.hi()),
+                ] {
+                    let lf_loc = sm.lookup_char_pos(lf_pos).data;
+                    let crlf_loc = sm.lookup_char_pos(crlf_pos).data;
+                    assert_eq!(
+                        (lf_loc.line, lf_loc.col, lf_loc.col_display),
+                        (crlf_loc.line, crlf_loc.col, crlf_loc.col_display),
+                        "{:?}",
+                        crlf_token.kind,
+                    );
+                }
+            }
+            let lines = sm.span_to_lines(crlf_tokens[1].span.to(crlf_tokens[2].span)).unwrap();
+            assert_eq!(crlf.get_line(lines.data[0].line_index), Some("\tb /* "));
+            assert_eq!(lines.data[0].end_col.0, 6);
+        });
+    }
+
+    #[test]
+    fn trivia() {
+        let src = "a /* b */\n// c\n\"d 0x";
+        let tokens = Lexer::tokenize_with_trivia(src);
+        let kinds = tokens.iter().map(|t| (t.range.clone(), t.kind)).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (0..1, RawTokenKind::Ident),
+                (1..2, RawTokenKind::Whitespace),
+                (2..9, RawTokenKind::BlockComment { is_doc: false, terminated: true }),
+                (9..10, RawTokenKind::Whitespace),
+                (10..14, RawTokenKind::LineComment { is_doc: false }),
+                (14..15, RawTokenKind::Whitespace),
+                (
+                    15..20,
+                    RawTokenKind::Literal {
+                        kind: RawLiteralKind::Str { kind: StrKind::Str, terminated: false }
+                    }
+                ),
+            ]
+        );
+        assert_eq!(tokens.last().unwrap().range.end, src.len());
+        assert!(tokens.last().unwrap().is_error());
+        assert_eq!(tokens.iter().filter(|t| t.is_trivial()).count(), 4);
+    }
 }
@@ -63,15 +63,22 @@ impl EscapeError {
             Self::UnicodeEscapeTooShort => "unicode escape must be followed by 4 hex digits",
             Self::InvalidUnicodeEscape => "invalid character in unicode escape",
             Self::StrNewline => "unescaped newline",
-            Self::StrNonAsciiChar => {
-                "unicode characters are not allowed in string literals; use a `unicode\"...\"` literal instead"
-            }
+            Self::StrNonAsciiChar => "unicode characters are not allowed in string literals",
             Self::HexNotHexDigit => "invalid hex digit",
             Self::HexBadUnderscore => "invalid underscore in hex literal",
             Self::HexOddDigits => "odd number of hex nibbles",
             Self::HexPrefix => "hex prefix is not allowed",
         }
     }
+
+    fn help(&self) -> Option<&'static str> {
+        match self {
+            Self::StrNonAsciiChar => Some("use a `unicode\"...\"` literal instead"),
+            Self::HexOddDigits => Some("hex literals must contain an even number of hex digits"),
+            Self::HexPrefix => Some("remove the `0x` prefix"),
+            _ => None,
+        }
+    }
 }
 
 pub(crate) fn emit_unescape_error(
@@ -98,5 +105,8 @@ pub(crate) fn emit_unescape_error(
     ) {
         diag = diag.span(last_char().1);
     }
+    if let Some(help) = error.help() {
+        diag = diag.help(help);
+    }
     diag.emit();
 }
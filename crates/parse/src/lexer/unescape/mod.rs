@@ -293,11 +293,15 @@ where
         callback(0..2, Err(EscapeError::HexPrefix));
     }
 
-    let count = chars.clone().filter(|(_, c)| c.is_ascii_hexdigit()).count();
-    if !count.is_multiple_of(2) {
-        callback(0..src.len(), Err(EscapeError::HexOddDigits));
-        return;
-    }
+    // If there is an odd number of digits, point at the last, unpaired one and don't unescape any
+    // of the digits. Invalid characters and underscores are still reported wherever they are.
+    let odd_digit = chars
+        .clone()
+        .filter(|(_, c)| c.is_ascii_hexdigit())
+        .enumerate()
+        .last()
+        .filter(|&(i, _)| i % 2 == 0)
+        .map(|(_, (start, _))| start);
 
     let mut emit_underscore_errors = true;
     let mut allow_underscore = false;
@@ -315,6 +319,12 @@ where
                 }
             }
             c if !c.is_ascii_hexdigit() => Err(EscapeError::HexNotHexDigit),
+            _ if odd_digit == Some(start) => Err(EscapeError::HexOddDigits),
+            _ if odd_digit.is_some() => {
+                even = !even;
+                allow_underscore = true;
+                continue;
+            }
             c => Ok(UnescapedUnit::CodePoint(c as u32)),
         };
 
@@ -482,7 +492,10 @@ mod tests {
             ("0X11", "11", &[(0..2, HexPrefix)]),
             ("1", "", &[(0..1, HexOddDigits)]),
             ("12", "12", &[]),
-            ("123", "", &[(0..3, HexOddDigits)]),
+            ("123", "", &[(2..3, HexOddDigits)]),
+            ("12_3", "", &[(3..4, HexOddDigits)]),
+            ("1z23", "", &[(1..2, HexNotHexDigit), (3..4, HexOddDigits)]),
+            ("0x1", "", &[(0..2, HexPrefix), (2..3, HexOddDigits)]),
             ("1234", "1234", &[]),
             ("_", "", &[(0..1, HexBadUnderscore)]),
             ("_11", "11", &[(0..1, HexBadUnderscore)]),
@@ -14,7 +14,9 @@ extern crate tracing;
 use solar_interface::diagnostics::{DiagBuilder, ErrorGuaranteed};
 
 pub mod lexer;
-pub use lexer::{Cursor, Lexer, unescape};
+pub use lexer::{Cursor, Lexer, TokenStream, unescape};
+
+pub mod cst;
 
 pub mod natspec;
 
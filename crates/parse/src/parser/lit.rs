@@ -1,5 +1,5 @@
 use crate::{PResult, Parser, unescape};
-use alloy_primitives::U256;
+use alloy_primitives::{Address, B256, U256};
 use num_bigint::{BigInt, BigUint};
 use num_rational::Ratio;
 use num_traits::{Num, Signed, Zero};
@@ -114,6 +114,9 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
     ) -> PResult<'sess, LitKind<'ast>> {
         use LitError::*;
         match parse_integer(self.sess, symbol, subdenomination) {
+            Ok(LitKind::Number(n)) if subdenomination.is_none() => {
+                Ok(self.recover_address_length(symbol, n))
+            }
             Ok(l) => Ok(l),
             // User error.
             Err(e @ (IntegerLeadingZeros | IntegerTooLarge)) => Err(self.dcx().err(e.to_string())),
@@ -129,6 +132,27 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
         }
     }
 
+    /// Reports a hex number one digit short or long of an address, which solc rejects as a
+    /// malformed address literal.
+    ///
+    /// The literal is kept as the address of its low 160 bits, so that its uses are not reported
+    /// again as type mismatches.
+    fn recover_address_length(&mut self, symbol: Symbol, n: U256) -> LitKind<'ast> {
+        let s = strip_underscores(symbol, self.sess);
+        let Some(digits) = s.strip_prefix("0x").map(str::len) else {
+            return LitKind::Number(n);
+        };
+        if !matches!(digits, 39 | 41) {
+            return LitKind::Number(n);
+        }
+        self.dcx()
+            .err("this looks like an address but is not exactly 40 hex digits")
+            .span(self.prev_token.span)
+            .note(format!("it is {digits} hex digits"))
+            .emit();
+        LitKind::Address(Address::from_word(B256::from(n.to_be_bytes())))
+    }
+
     /// Parses a rational literal.
     fn parse_lit_rational(
         &mut self,
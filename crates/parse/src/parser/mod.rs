@@ -1,4 +1,4 @@
-use crate::{Lexer, PErr, PResult};
+use crate::{Lexer, PErr, PResult, TokenStream};
 use smallvec::SmallVec;
 use solar_ast::{
     self as ast, AstPath, Box, BoxSlice, DocComment, DocComments,
@@ -7,7 +7,7 @@ use solar_ast::{
 use solar_data_structures::{BumpExt, fmt::or_list};
 use solar_interface::{
     BytePos, Ident, Result, Session, Span, Symbol,
-    diagnostics::DiagCtxt,
+    diagnostics::{Applicability, DiagCtxt, SuggestionStyle},
     error_code,
     source_map::{FileName, SourceFile},
 };
@@ -20,9 +20,6 @@ mod stmt;
 mod ty;
 mod yul;
 
-/// Maximum allowed recursive descent depth for selected parser entry points.
-const PARSER_RECURSION_LIMIT: usize = 128;
-
 /// Solidity and Yul parser.
 ///
 /// # Examples
@@ -50,7 +47,7 @@ pub struct Parser<'sess, 'ast, 'cb> {
     docs: Vec<DocComment<'ast>>,
 
     /// The token stream.
-    tokens: std::vec::IntoIter<Token>,
+    tokens: TokenStream,
 
     /// Whether the parser is in Yul mode.
     ///
@@ -147,6 +144,17 @@ pub enum Recovered {
 impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
     /// Creates a new parser.
     pub fn new(sess: &'sess Session, arena: &'ast ast::Arena, tokens: Vec<Token>) -> Self {
+        Self::from_token_stream(sess, arena, tokens.into())
+    }
+
+    /// Creates a new parser from a token stream.
+    ///
+    /// See [`Lexer::with_token_stream`].
+    pub fn from_token_stream(
+        sess: &'sess Session,
+        arena: &'ast ast::Arena,
+        tokens: TokenStream,
+    ) -> Self {
         assert!(sess.is_entered(), "session should be entered before parsing");
         let mut parser = Self {
             sess,
@@ -156,7 +164,7 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
             expected_tokens: Vec::with_capacity(8),
             last_unexpected_token_span: None,
             docs: Vec::with_capacity(4),
-            tokens: tokens.into_iter(),
+            tokens,
             in_yul: false,
             in_contract: false,
             recover_incomplete_input: sess.opts.unstable.recover_incomplete_input,
@@ -817,7 +825,8 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
     /// Returns the token `dist` tokens ahead of the current one.
     ///
     /// [`Eof`](Token::EOF) will be returned if the look-ahead is any distance past the end of the
-    /// tokens.
+    /// tokens. When tokens are [streamed](Lexer::with_token_stream), only the next 16 tokens are
+    /// guaranteed to be available.
     #[inline]
     pub fn look_ahead(&self, dist: usize) -> Token {
         // Specialize for the common `dist` cases.
@@ -873,7 +882,7 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
         f: impl FnOnce(&mut Self) -> PResult<'sess, T>,
     ) -> PResult<'sess, T> {
         self.recursion_depth += 1;
-        let res = if self.recursion_depth > PARSER_RECURSION_LIMIT {
+        let res = if self.recursion_depth > self.sess.opts.unstable.recursion_limit() {
             Err(self.recursion_limit_reached(context))
         } else {
             f(self)
@@ -888,7 +897,7 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
         if !self.prev_token.span.is_dummy() {
             err = err.span_label(self.prev_token.span, format!("while parsing {context}"));
         }
-        err
+        err.help("consider increasing the limit with `-Zrecursion-limit=<LIMIT>`")
     }
 }
 
@@ -1004,6 +1013,7 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
         let ident = self.ident_or_err(recover)?;
         if ident.is_reserved(self.in_yul) {
             let err = self.expected_ident_found_err();
+            let err = self.suggest_renaming_keyword(ident, err);
             if recover {
                 err.emit();
             } else {
@@ -1059,6 +1069,38 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
     fn expected_ident_found_err(&mut self) -> PErr<'sess> {
         self.expected_ident_found(false).unwrap_err()
     }
+
+    /// Suggests renaming a reserved keyword that is used as the name of a declaration, like the
+    /// `new` in `uint new;`.
+    ///
+    /// Keywords which are not followed by a token that can follow a name are more likely to be
+    /// misplaced than misused as a name, so they are left alone.
+    #[cold]
+    fn suggest_renaming_keyword(&self, ident: Ident, err: PErr<'sess>) -> PErr<'sess> {
+        let used_as_name = matches!(
+            self.look_ahead(1).kind,
+            TokenKind::Semi
+                | TokenKind::Eq
+                | TokenKind::Comma
+                | TokenKind::OpenDelim(Delimiter::Parenthesis)
+                | TokenKind::CloseDelim(Delimiter::Parenthesis)
+        );
+        if self.in_yul || !used_as_name {
+            return err;
+        }
+        let alternatives = [format!("{ident}_"), format!("_{ident}")];
+        let msg = format!(
+            "`{ident}` is a reserved keyword; consider renaming it, for example to `{}` or `{}`",
+            alternatives[0], alternatives[1],
+        );
+        err.span_suggestions_with_style(
+            ident.span,
+            msg,
+            alternatives.map(Into::into),
+            Applicability::MaybeIncorrect,
+            SuggestionStyle::HideCodeAlways,
+        )
+    }
 }
 
 // Default @notice behavior:
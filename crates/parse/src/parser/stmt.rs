@@ -371,6 +371,10 @@ impl<'sess, 'ast, 'cb> Parser<'sess, 'ast, 'cb> {
                 // These aren't valid but we include them for a better error message.
                 || next.is_mutability_specifier()
                 || next.is_visibility_specifier()
+                // A keyword used as the variable's name, like `uint new;`.
+                || (!self.in_yul
+                    && next.is_reserved_ident(false)
+                    && matches!(self.look_ahead(2).kind, TokenKind::Semi | TokenKind::Eq))
             {
                 return LookAheadInfo::VariableDeclaration;
             }
@@ -0,0 +1,132 @@
+//! Single-source analysis.
+
+use crate::{Compiler, ty::Gcx};
+use solar_interface::{
+    Result, Session, config::CompileOpts, diagnostics::Diag, source_map::FileName,
+};
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// The result of [`analyze_source`].
+///
+/// Holds the compiler, which can be entered to query the analyzed program, and the diagnostics
+/// emitted during analysis.
+#[derive(Debug)]
+pub struct AnalyzedUnit {
+    compiler: Compiler,
+    diagnostics: Vec<Diag>,
+}
+
+impl AnalyzedUnit {
+    /// Returns the diagnostics emitted during analysis, such as warnings.
+    #[inline]
+    pub fn diagnostics(&self) -> &[Diag] {
+        &self.diagnostics
+    }
+
+    /// Returns a reference to the compiler.
+    #[inline]
+    pub fn compiler(&self) -> &Compiler {
+        &self.compiler
+    }
+
+    /// Consumes the unit, returning the compiler.
+    #[inline]
+    pub fn into_compiler(self) -> Compiler {
+        self.compiler
+    }
+
+    /// Enters the compiler context and calls `f` with the global context.
+    pub fn enter<T: Send>(&self, f: impl FnOnce(Gcx<'_>) -> T + Send) -> T {
+        self.compiler.enter(|compiler| f(compiler.gcx()))
+    }
+}
+
+/// Parses, lowers, and analyzes a single source, together with the imports that it resolves.
+///
+/// This sets up a [`Session`] and a [`Compiler`] with `opts`, and collects the emitted diagnostics
+/// instead of printing them. If `name` is a real path, relative imports are resolved from it, as
+/// well as from the base and include paths in `opts`.
+///
+/// Returns the analyzed unit if no errors were emitted, otherwise all the emitted diagnostics.
+///
+/// # Examples
+///
+/// ```
+/// use solar_sema::{analyze_source, interface::config::CompileOpts};
+///
+/// let src = "contract C { function f() public pure returns (uint) { return 1; } }";
+/// let unit = analyze_source("C.sol".to_string(), src, CompileOpts::default()).unwrap();
+/// let names = unit.enter(|gcx| {
+///     gcx.hir.contracts().map(|c| c.name.to_string()).collect::<Vec<_>>()
+/// });
+/// assert_eq!(names, ["C"]);
+///
+/// let errors = analyze_source("D.sol".to_string(), "contract D {", CompileOpts::default())
+///     .err()
+///     .unwrap();
+/// assert!(errors.iter().any(|diag| diag.is_error()));
+/// ```
+pub fn analyze_source(
+    name: impl Into<FileName>,
+    src: impl Into<String>,
+    opts: CompileOpts,
+) -> Result<AnalyzedUnit, Vec<Diag>> {
+    let diagnostics = Arc::new(Mutex::new(Vec::new()));
+    let mut sess = Session::builder()
+        .with_callback_emitter({
+            let diagnostics = diagnostics.clone();
+            move |diag| diagnostics.lock().unwrap_or_else(PoisonError::into_inner).push(diag)
+        })
+        .opts(opts)
+        .build();
+    let opts = sess.opts.clone();
+    sess.dcx.set_flags_mut(|flags| flags.update_from_opts(&opts));
+
+    let (name, src) = (name.into(), src.into());
+    let mut compiler = Compiler::new(sess);
+    let _ = compiler.enter_mut(|compiler| -> Result<()> {
+        let file = compiler
+            .sess()
+            .source_map()
+            .new_source_file(name, src)
+            .map_err(|e| compiler.dcx().err(e.to_string()).emit())?;
+        let mut pcx = compiler.parse();
+        pcx.add_file(file);
+        pcx.parse();
+        let ControlFlow::Continue(()) = compiler.lower_asts()? else { return Ok(()) };
+        let _ = compiler.analysis()?;
+        Ok(())
+    });
+
+    let diagnostics =
+        std::mem::take(&mut *diagnostics.lock().unwrap_or_else(PoisonError::into_inner));
+    if compiler.dcx().has_errors().is_err() {
+        return Err(diagnostics);
+    }
+    Ok(AnalyzedUnit { compiler, diagnostics })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analyze_ok() {
+        let src = "contract C { function f() public { uint x; } }";
+        let unit = analyze_source("C.sol".to_string(), src, CompileOpts::default()).unwrap();
+        assert!(unit.diagnostics().iter().all(|diag| !diag.is_error()));
+        let functions = unit.enter(|gcx| gcx.hir.functions().count());
+        assert_eq!(functions, 1);
+    }
+
+    #[test]
+    fn analyze_with_errors() {
+        let src = "contract C { function f() public { undefined(); } }";
+        let errors =
+            analyze_source("C.sol".to_string(), src, CompileOpts::default()).err().unwrap();
+        assert!(errors.iter().any(|diag| diag.is_error()));
+    }
+}
@@ -138,6 +138,9 @@ impl<'gcx> super::LoweringContext<'gcx> {
                 self.collect_yul_functions_in_item(item, &mut items);
             }
         }
+        for (i, &item) in items.iter().enumerate() {
+            self.hir.item_indices.insert(item, i as u32);
+        }
         self.hir.contracts[id].items = self.arena.alloc_slice_copy(&items);
 
         self.current_contract_id = prev_contract_id;
@@ -222,6 +225,9 @@ impl<'gcx> super::LoweringContext<'gcx> {
                 let is_free = self.current_contract_id.is_none();
                 if kind.is_modifier() || is_free {
                     ast::Visibility::Internal
+                } else if kind.is_fallback() {
+                    // The missing visibility is already reported, suggesting `external`.
+                    ast::Visibility::External
                 } else {
                     ast::Visibility::Public
                 }
@@ -8,7 +8,7 @@ use solar_data_structures::{
     index::{Idx, IndexVec},
     map::FxHashMap,
 };
-use solar_interface::{Session, diagnostics::DiagCtxt};
+use solar_interface::{Session, diagnostics::DiagCtxt, events};
 
 mod lower;
 
@@ -48,6 +48,28 @@ pub(crate) fn lower(mut gcx: GcxMut<'_>) {
 
     let gcx = gcx.get_mut();
     (gcx.hir, gcx.symbol_resolver) = lcx.finish();
+
+    if enabled!(target: events::TARGET, tracing::Level::INFO) {
+        emit_item_events(&gcx.hir);
+    }
+}
+
+fn emit_item_events(hir: &Hir<'_>) {
+    for id in hir.item_ids() {
+        if let hir::ItemId::Variable(id) = id
+            && !hir.variable(id).is_state_variable()
+        {
+            continue;
+        }
+        let item = hir.item(id);
+        let name = item.name();
+        solar_interface::event!(
+            "item_lowered",
+            file = %hir.source(item.source()).file.name.display(),
+            kind = item.description(),
+            name = name.as_ref().map(|name| name.as_str()),
+        );
+    }
 }
 
 struct LoweringContext<'gcx> {
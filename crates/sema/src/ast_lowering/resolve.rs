@@ -9,7 +9,7 @@ use solar_data_structures::{
 };
 use solar_interface::{
     Ident, Session, Span, Symbol,
-    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    diagnostics::{DiagCtxt, DiagMsg, ErrorGuaranteed},
     error_code, sym,
 };
 use std::fmt;
@@ -212,10 +212,20 @@ impl super::LoweringContext<'_> {
                         if base_id != contract_id {
                             continue;
                         }
+                        let code = match func.kind {
+                            ast::FunctionKind::Constructor => error_code!(7997),
+                            ast::FunctionKind::Fallback => error_code!(7301),
+                            _ => error_code!(4046),
+                        };
                         let msg = format!("{} function already declared", func.kind);
                         let note = "previous declaration here";
                         let prev_span = self.hir.function(prev).span;
-                        self.dcx().emit_err_span_note(func.span, msg, prev_span, note);
+                        self.dcx()
+                            .err(msg)
+                            .code(code)
+                            .span(func.span)
+                            .span_note(prev_span, note)
+                            .emit();
                     } else {
                         *slot = Some(function_id);
                     }
@@ -252,6 +262,10 @@ pub(super) struct ResolveContext<'gcx> {
     function_id: Option<hir::FunctionId>,
     yul_scopes: Vec<usize>,
     yul_function_scope: Option<usize>,
+    /// Current recursion depth when lowering expressions and types.
+    recursion_depth: usize,
+    /// The error emitted when the recursion limit was first reached, to avoid cascading errors.
+    recursion_limit_reached: Option<ErrorGuaranteed>,
 }
 
 impl<'gcx> std::ops::Deref for ResolveContext<'gcx> {
@@ -277,6 +291,8 @@ impl<'gcx> ResolveContext<'gcx> {
             function_id: None,
             yul_scopes: Vec::new(),
             yul_function_scope: None,
+            recursion_depth: 0,
+            recursion_limit_reached: None,
         }
     }
 
@@ -618,6 +634,7 @@ impl<'gcx> ResolveContext<'gcx> {
                     self.sess
                         .dcx
                         .err("base constructor arguments given twice")
+                        .code(error_code!(3364))
                         .span(base.span)
                         .span_help(prev.span, "previous declaration")
                         .emit();
@@ -729,15 +746,11 @@ impl<'gcx> ResolveContext<'gcx> {
                 hir::VarKind::FunctionParam,
             )
         };
-        for i in 0usize.. {
-            // let index_name = || Some(Ident::new(Symbol::intern(&format!("index{i}")), span));
-            let _ = i;
-            let index_name = || None;
+        loop {
             match ret_ty.kind {
                 // mapping(k => v) -> arguments += k, ret_ty = v
                 hir::TypeKind::Mapping(map) => {
-                    let name = map.key_name.or_else(index_name);
-                    let mut param = new_param(self, map.key.clone(), name);
+                    let mut param = new_param(self, map.key.clone(), map.key_name);
                     if param.ty.kind.is_reference_type() {
                         param.data_location = Some(hir::DataLocation::Calldata);
                     }
@@ -753,7 +766,8 @@ impl<'gcx> ResolveContext<'gcx> {
                         )),
                         span: ret_ty.span,
                     };
-                    let param = new_param(self, u256, index_name());
+                    // Array indices are unnamed, like in solc.
+                    let param = new_param(self, u256, None);
                     parameters.push(self.hir.variables.push(param));
                     ret_ty = &array.element;
                 }
@@ -1063,7 +1077,11 @@ impl<'gcx> ResolveContext<'gcx> {
             }
         }
 
-        hir::StmtKind::AssemblyBlock(self.lower_yul_block(&assembly.block))
+        hir::StmtKind::AssemblyBlock(self.arena.alloc(hir::StmtAssembly {
+            dialect: assembly.dialect.as_ref().map(|dialect| dialect.value),
+            flags: self.arena.alloc_from_iter(assembly.flags.iter().map(|flag| flag.value)),
+            block: self.lower_yul_block(&assembly.block),
+        }))
     }
 
     fn lower_yul_block(&mut self, block: &ast::yul::Block<'_>) -> hir::Block<'gcx> {
@@ -1119,7 +1137,8 @@ impl<'gcx> ResolveContext<'gcx> {
             self.lower_yul_function_variables(id, function.returns, hir::VarKind::FunctionReturn);
 
         let block = self.lower_yul_block(&function.body);
-        let unchecked = self.hir_builder().stmt(hir::StmtKind::AssemblyBlock(block), block.span);
+        let assembly = self.arena.alloc(hir::StmtAssembly { dialect: None, flags: &[], block });
+        let unchecked = self.hir_builder().stmt(hir::StmtKind::AssemblyBlock(assembly), block.span);
         let body = self.hir_builder().block(self.arena.alloc_as_slice(unchecked), block.span);
 
         self.yul_function_scope = previous_yul_function_scope;
@@ -1711,13 +1730,25 @@ impl<'gcx> ResolveContext<'gcx> {
 
     #[instrument(name = "lower_expr", level = "trace", skip_all)]
     fn lower_expr_full(&mut self, expr: &ast::Expr<'_>) -> hir::Expr<'gcx> {
-        let kind = match &expr.kind {
+        let kind = self
+            .with_recursion_limit(expr.span, |this| this.lower_expr_kind(expr))
+            .unwrap_or_else(hir::ExprKind::Err);
+        self.hir_builder().expr_owned(self.next_id(), kind, expr.span)
+    }
+
+    fn lower_expr_kind(&mut self, expr: &ast::Expr<'_>) -> hir::ExprKind<'gcx> {
+        match &expr.kind {
             ast::ExprKind::Array(exprs) => hir::ExprKind::Array(self.lower_exprs(&**exprs)),
             ast::ExprKind::Assign(lhs, op, rhs) => {
                 hir::ExprKind::Assign(self.lower_expr(lhs), *op, self.lower_expr(rhs))
             }
             ast::ExprKind::Binary(lhs, op, rhs) => {
-                hir::ExprKind::Binary(self.lower_expr(lhs), *op, self.lower_expr(rhs))
+                // Left-associative chains such as `a + b + c` nest on the left, so only count the
+                // right operand towards the recursion limit.
+                self.recursion_depth -= 1;
+                let lhs = self.lower_expr(lhs);
+                self.recursion_depth += 1;
+                hir::ExprKind::Binary(lhs, *op, self.lower_expr(rhs))
             }
             ast::ExprKind::Call(callee, args) => {
                 let (callee, options) = self.lower_call_callee(callee);
@@ -1781,8 +1812,7 @@ impl<'gcx> ResolveContext<'gcx> {
             ast::ExprKind::Type(ty) => hir::ExprKind::Type(self.lower_type(ty)),
             ast::ExprKind::Unary(op, expr) => hir::ExprKind::Unary(*op, self.lower_expr(expr)),
             ast::ExprKind::Err(guar) => hir::ExprKind::Err(*guar),
-        };
-        self.hir_builder().expr_owned(self.next_id(), kind, expr.span)
+        }
     }
 
     fn lower_lit(&mut self, lit: &ast::Lit<'_>) -> &'gcx ast::Lit<'gcx> {
@@ -1843,7 +1873,14 @@ impl<'gcx> ResolveContext<'gcx> {
 
     #[instrument(name = "lower_type", level = "trace", skip_all)]
     fn lower_type(&mut self, ty: &ast::Type<'_>) -> hir::Type<'gcx> {
-        let kind = match &ty.kind {
+        let kind = self
+            .with_recursion_limit(ty.span, |this| this.lower_type_kind(ty))
+            .unwrap_or_else(hir::TypeKind::Err);
+        hir::Type { kind, span: ty.span }
+    }
+
+    fn lower_type_kind(&mut self, ty: &ast::Type<'_>) -> hir::TypeKind<'gcx> {
+        match &ty.kind {
             ast::TypeKind::Elementary(ty) => hir::TypeKind::Elementary(match *ty {
                 ast::ElementaryType::Int(size) if size == ast::TypeSize::ZERO => {
                     ast::ElementaryType::Int(ast::TypeSize::new_int_bits(256))
@@ -1888,8 +1925,38 @@ impl<'gcx> ResolveContext<'gcx> {
                 Ok(id) => hir::TypeKind::Custom(id),
                 Err(guar) => hir::TypeKind::Err(guar),
             },
-        };
-        hir::Type { kind, span: ty.span }
+        }
+    }
+
+    /// Runs `f` with recursion depth tracking, returning an error instead if the recursion limit
+    /// is reached.
+    fn with_recursion_limit<T>(
+        &mut self,
+        span: Span,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, ErrorGuaranteed> {
+        if self.recursion_depth >= self.sess.opts.unstable.recursion_limit() {
+            return Err(self.recursion_limit_reached(span));
+        }
+        self.recursion_depth += 1;
+        let res = f(self);
+        self.recursion_depth -= 1;
+        Ok(res)
+    }
+
+    #[cold]
+    fn recursion_limit_reached(&mut self, span: Span) -> ErrorGuaranteed {
+        if let Some(guar) = self.recursion_limit_reached {
+            return guar;
+        }
+        let guar = self
+            .dcx()
+            .err("recursion limit reached")
+            .span(span)
+            .help("consider increasing the limit with `-Zrecursion-limit=<LIMIT>`")
+            .emit();
+        self.recursion_limit_reached = Some(guar);
+        guar
     }
 
     #[inline]
@@ -1996,18 +2063,20 @@ impl ResolverError {
         self.name.span
     }
 
-    fn format(&self) -> String {
+    fn format(&self) -> DiagMsg {
         let name = self.name;
         match self.kind {
-            ResolverErrorKind::Unresolved => format!("unresolved symbol `{name}`"),
-            ResolverErrorKind::NotAScope(kind) => {
-                format!(
-                    "`{name}` is a {}, which cannot be indexed in type paths",
-                    kind.description()
-                )
+            ResolverErrorKind::Unresolved => {
+                DiagMsg::translatable("resolve-unresolved", "unresolved symbol `{name}`")
+                    .arg("name", name)
             }
+            ResolverErrorKind::NotAScope(kind) => format!(
+                "`{name}` is a {}, which cannot be indexed in type paths",
+                kind.description()
+            )
+            .into(),
             ResolverErrorKind::MultipleDeclarations => {
-                format!("symbol `{name}` resolved to multiple declarations")
+                format!("symbol `{name}` resolved to multiple declarations").into()
             }
         }
     }
@@ -2363,8 +2432,9 @@ impl Declarations {
                 }
             }
             let same_kind = |decl2: &Declaration| match decl2.res {
-                Item(Variable(v)) => hir.variable(v).getter == getter,
-                Item(Function(f)) => hir.function(f).kind.is_ordinary(),
+                // Events only overload other events.
+                Item(Variable(v)) => getter.is_some() && hir.variable(v).getter == getter,
+                Item(Function(f)) => getter.is_some() && hir.function(f).kind.is_ordinary(),
                 ref k => k.matches(&decl.res),
             };
             declarations.iter().find(|&decl2| !same_kind(decl2)).copied()
@@ -2409,7 +2479,11 @@ pub(super) fn report_conflict(
 ) -> ErrorGuaranteed {
     debug_assert_ne!(decl.span, previous.span);
 
-    let mut err = sess.dcx.err(format!("identifier `{name}` already declared")).span(decl.span);
+    let mut err = sess
+        .dcx
+        .err(format!("identifier `{name}` already declared"))
+        .code(error_code!(2333))
+        .span(decl.span);
 
     // If `previous` is coming from an import, show both the import and the real span.
     if let Res::Item(item_id) = previous.res
@@ -3,7 +3,7 @@
 use alloy_primitives::Address;
 use solar_ast::{self as ast, visit::Visit};
 use solar_data_structures::Never;
-use solar_interface::{Session, Span, diagnostics::DiagCtxt, sym};
+use solar_interface::{Session, Span, diagnostics::DiagCtxt, error_code, sym};
 use std::ops::ControlFlow;
 
 #[instrument(name = "ast_passes", level = "debug", skip_all)]
@@ -26,6 +26,8 @@ struct AstValidator<'sess, 'ast> {
     function_kind: Option<ast::FunctionKind>,
     in_unchecked_block: bool,
     placeholder_count: u32,
+    /// The Solidity version to check version pragmas against, set by `-Zsolidity-version`.
+    solidity_version: Option<ast::SemverVersion>,
 }
 
 impl<'sess> AstValidator<'sess, '_> {
@@ -37,6 +39,14 @@ impl<'sess> AstValidator<'sess, '_> {
             function_kind: None,
             in_unchecked_block: false,
             placeholder_count: 0,
+            solidity_version: sess.opts.unstable.solidity_version.map(|version| {
+                ast::SemverVersion {
+                    span: Span::DUMMY,
+                    major: version.major.into(),
+                    minor: Some(version.minor.into()),
+                    patch: Some(version.patch.into()),
+                }
+            }),
         }
     }
 
@@ -124,6 +134,10 @@ impl<'sess> AstValidator<'sess, '_> {
         let ast::LitKind::Address(addr) = lit.kind else {
             return;
         };
+        // Addresses of the wrong length were already reported by the parser.
+        if lit.symbol.as_str().bytes().filter(|&b| b != b'_').count() != 42 {
+            return;
+        }
 
         if Address::parse_checksummed(lit.symbol.as_str(), None).is_err() {
             self.dcx()
@@ -174,10 +188,21 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         pragma: &'ast ast::PragmaDirective<'ast>,
     ) -> ControlFlow<Self::BreakValue> {
         match &pragma.tokens {
-            ast::PragmaTokens::Version(name, _version) => {
+            ast::PragmaTokens::Version(name, req) => {
                 if name.name != sym::solidity {
                     let msg = "only `solidity` is supported as a version pragma";
                     self.dcx().emit_err(name.span, msg);
+                } else if let Some(version) = &self.solidity_version
+                    && !req.matches(version)
+                {
+                    self.dcx()
+                        .err("source file requires a different Solidity version")
+                        .span(self.item_span)
+                        .note(format!(
+                            "`{req}` does not match the version `{version}` set with \
+                             `-Zsolidity-version`"
+                        ))
+                        .emit();
                 }
             }
             ast::PragmaTokens::Custom(name, value) => {
@@ -329,6 +354,22 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
                     .help(format!("remove `{}` from the declaration", *visibility))
                     .emit();
             }
+            if let Some(virtual_) = func.header.virtual_ {
+                self.dcx()
+                    .err("free functions cannot be virtual")
+                    .code(error_code!(4493))
+                    .span(virtual_)
+                    .emit();
+            }
+            if let Some(state_mutability) = func.header.state_mutability
+                && *state_mutability == ast::StateMutability::Payable
+            {
+                self.dcx()
+                    .err("free functions cannot be payable")
+                    .code(error_code!(9559))
+                    .span(state_mutability.span)
+                    .emit();
+            }
         }
 
         let current_placeholder_count = self.placeholder_count;
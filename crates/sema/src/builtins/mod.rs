@@ -348,6 +348,9 @@ impl Builtin {
     const FIRST_YUL: usize = Self::YulAdd as usize;
     const LAST_YUL: usize = Self::YulMcopy as usize + 1;
 
+    /// The gas forwarded to the recipient by `address.transfer` and `address.send`.
+    pub const TRANSFER_GAS_STIPEND: u64 = 2300;
+
     /// Returns an iterator over all builtins.
     #[inline]
     pub fn iter() -> std::iter::Map<std::ops::Range<usize>, impl FnMut(usize) -> Self> {
@@ -3,7 +3,7 @@ use crate::{
     ty::{Gcx, GcxMut, GlobalCtxt},
 };
 use solar_data_structures::trustme;
-use solar_interface::{Result, Session, diagnostics::DiagCtxt};
+use solar_interface::{Result, Session, config::CompilerStage, diagnostics::DiagCtxt};
 use std::{
     fmt,
     marker::PhantomPinned,
@@ -272,6 +272,23 @@ impl<'c> CompilerRef<'c> {
         });
     }
 
+    /// Resets the compiler to its initial state, so that it can be reused for a new compilation.
+    ///
+    /// This drops the global context, including all the sources, ASTs, and HIR, and resets the
+    /// diagnostic counts. The session is kept as-is, including its source map, symbol interner,
+    /// and thread pool, so that long-lived processes don't have to create a new one for each
+    /// compilation.
+    ///
+    /// Files which have changed since the previous compilation should be removed from the source
+    /// map with [`SourceMap::retain_files`](solar_interface::SourceMap::retain_files), otherwise
+    /// the previously loaded contents will be used.
+    pub fn reset(&mut self) {
+        let _guard = debug_span!("reset").entered();
+        let sess = self.sess();
+        sess.dcx.reset_err_count();
+        self.inner.gcx = GlobalCtxt::new(sess);
+    }
+
     /// Returns a builder for parsing sources.
     ///
     /// [`ParsingContext::parse`](ParsingContext::parse) must be called at the end to actually parse
@@ -291,6 +308,14 @@ impl<'c> CompilerRef<'c> {
         crate::analysis(self.gcx())
     }
 
+    /// Advances to the code generation stage.
+    ///
+    /// Code generation itself is performed by the caller. Returns `Break` if compilation should
+    /// stop before it, as requested with `stop_after`.
+    pub fn start_codegen(&self) -> ControlFlow<()> {
+        self.gcx().advance_stage(CompilerStage::Codegen)
+    }
+
     fn debug_fmt(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct(name).field("gcx", &self.gcx()).finish_non_exhaustive()
     }
@@ -384,6 +409,73 @@ mod tests {
         assert_eq!(compiler.enter(|c| c.gcx().sources.asts().count()), 0);
     }
 
+    #[test]
+    fn reset() {
+        let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+        let mut compiler = Compiler::new(sess);
+
+        for i in 0..3 {
+            let src = format!("contract C{i} {{ function f() public {{ undefined(); }} }}");
+            compiler.enter_mut(|c| {
+                let sm = c.sess().source_map();
+                sm.retain_files(|_| false);
+                let mut pcx = c.parse();
+                pcx.add_file(sm.new_source_file(PathBuf::from("test.sol"), src).unwrap());
+                pcx.parse();
+                let _ = c.lower_asts();
+                let _ = c.analysis();
+            });
+            assert_eq!(compiler.enter(|c| c.gcx().sources.len()), 1);
+            assert_eq!(compiler.sess().source_map().files().len(), 1);
+            assert_eq!(compiler.dcx().err_count(), 1);
+            let name = format!("C{i}");
+            assert!(compiler.enter(|c| c.gcx().hir.contracts().all(|c| c.name.as_str() == name)));
+
+            compiler.enter_mut(|c| c.reset());
+            assert!(compiler.enter(|c| c.gcx().sources.is_empty()));
+            assert_eq!(compiler.enter(|c| c.gcx().stage()), None);
+            assert!(compiler.dcx().has_errors().is_ok());
+        }
+    }
+
+    #[test]
+    fn contract_item_indices() {
+        let sess = Session::builder().with_test_emitter().build();
+        let mut compiler = Compiler::new(sess);
+
+        let src = "
+            struct S { uint x; }
+            contract C {
+                event E();
+                uint public x;
+                function f() internal {}
+                constructor() {}
+                error Err();
+            }
+        ";
+        compiler.enter_mut(|c| {
+            let sm = c.sess().source_map();
+            let mut pcx = c.parse();
+            pcx.add_file(sm.new_source_file(PathBuf::from("test.sol"), src).unwrap());
+            pcx.parse();
+            let _ = c.lower_asts();
+        });
+        compiler.enter(|c| {
+            let hir = &c.gcx().hir;
+            let contract = hir.contract(hir.contract_ids().next().unwrap());
+            assert_eq!(contract.items.len(), 6);
+            for (i, &item) in contract.items.iter().enumerate() {
+                assert_eq!(hir.item_index(item), Some(i));
+            }
+            let x = contract.variables().next().unwrap();
+            let getter = hir.variable(x).getter.unwrap();
+            assert_eq!(hir.item_index(x), Some(1));
+            assert_eq!(hir.item_index(getter), Some(2));
+            assert_eq!(hir.item_index(contract.ctor.unwrap()), Some(4));
+            assert_eq!(hir.item_index(hir.struct_ids().next().unwrap()), None);
+        });
+    }
+
     fn stage_test(expected: Result<(), &str>, f: fn(&mut CompilerRef<'_>)) {
         let sess =
             Session::builder().with_buffer_emitter(solar_interface::ColorChoice::Never).build();
@@ -419,7 +511,7 @@ mod tests {
         });
 
         // Too far ahead.
-        stage_test(Err("from `none` to `analysis`"), |c| {
+        stage_test(Err("from `none` to `typeck`"), |c| {
             assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
         });
 
@@ -430,7 +522,7 @@ mod tests {
             assert_eq!(c.lower_asts(), Ok(ControlFlow::Continue(())));
             assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
         });
-        stage_test(Err("from `analysis` to `analysis`"), |c| {
+        stage_test(Err("from `analysis` to `typeck`"), |c| {
             parse_dummy_file(c);
             assert_eq!(c.lower_asts(), Ok(ControlFlow::Continue(())));
             assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
@@ -441,6 +533,36 @@ mod tests {
             parse_dummy_file(c);
             parse_dummy_file(c);
         });
+        stage_test(Ok(()), |c| {
+            parse_dummy_file(c);
+            assert_eq!(c.lower_asts(), Ok(ControlFlow::Continue(())));
+            assert_eq!(c.analysis(), Ok(ControlFlow::Continue(())));
+            assert_eq!(c.start_codegen(), ControlFlow::Continue(()));
+            assert_eq!(c.gcx().stage(), Some(CompilerStage::Codegen));
+        });
+    }
+
+    #[test]
+    fn error_stage() {
+        let cases = [
+            ("contract C {", CompilerStage::Parsing),
+            ("contract C is Undefined {}", CompilerStage::Lowering),
+            ("contract C { function f() public { uint x = true; } }", CompilerStage::Typeck),
+        ];
+        for (src, stage) in cases {
+            let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+            let mut compiler = Compiler::new(sess);
+            compiler.enter_mut(|c| {
+                let mut pcx = c.parse();
+                let file = c.sess().source_map().new_source_file(PathBuf::from("test.sol"), src);
+                pcx.add_file(file.unwrap());
+                pcx.parse();
+                let _ = c.lower_asts();
+                let _ = c.analysis();
+                let _ = c.start_codegen();
+                assert_eq!(c.gcx().error_stage(), Some(stage), "{src}");
+            });
+        }
     }
 
     #[test]
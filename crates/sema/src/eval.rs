@@ -1,13 +1,20 @@
 use crate::{builtins::Builtin, hir, ty::Gcx};
 use alloy_primitives::{B256, U256, keccak256};
 use num_bigint::{BigInt, BigUint, Sign};
+use num_rational::{BigRational, Ratio};
 use num_traits::{One, Signed, Zero};
 use solar_ast::{LitKind, StrKind};
 use solar_interface::{ByteSymbol, Span, diagnostics::ErrorGuaranteed};
 use std::fmt;
 
 const RECURSION_LIMIT: usize = 64;
+/// Maximum number of bits of evaluated constant values.
 const MAX_BITS: u64 = solar_ast::TypeSize::MAX as u64;
+/// Maximum number of bits of intermediate values, and of the numerator and denominator of
+/// rational values.
+///
+/// Same as solc.
+const MAX_RATIONAL_BITS: u64 = 4096;
 
 // TODO: `convertType` for truncating and extending correctly: https://github.com/argotorg/solidity/blob/de1a017ccb935d149ed6bcbdb730d89883f8ce02/libsolidity/analysis/ConstantEvaluator.cpp#L234
 
@@ -22,6 +29,48 @@ pub fn erc7201_slot(namespace_id: &[u8]) -> B256 {
     outer
 }
 
+/// Returns `true` if the given expression is an operation made up only of number literals and
+/// arithmetic or comparison operators, e.g. `2**256 - 1` or `0.1 * 10`.
+///
+/// Such expressions are evaluated as a whole, like in solc: intermediate values are exact and may
+/// exceed 256 bits, and only the final value has to fit in a type.
+pub fn is_number_const_expr(expr: &hir::Expr<'_>) -> bool {
+    matches!(expr.peel_parens().kind, hir::ExprKind::Binary(..) | hir::ExprKind::Unary(..))
+        && walk_number_const_expr(expr, &mut false)
+}
+
+/// Returns `true` if the given expression is made up only of number literals and arithmetic or
+/// comparison operators, and contains at least one rational literal, e.g. `0.1 * 10`.
+///
+/// Such expressions are evaluated exactly at compile time, and are only valid when they do not
+/// evaluate to a fractional value.
+pub fn is_rational_const_expr(expr: &hir::Expr<'_>) -> bool {
+    let mut has_rational = false;
+    walk_number_const_expr(expr, &mut has_rational) && has_rational
+}
+
+fn walk_number_const_expr(expr: &hir::Expr<'_>, has_rational: &mut bool) -> bool {
+    match &expr.peel_parens().kind {
+        hir::ExprKind::Lit(lit) => match lit.kind {
+            LitKind::Number(_) => true,
+            LitKind::Rational(_) => {
+                *has_rational = true;
+                true
+            }
+            _ => false,
+        },
+        hir::ExprKind::Unary(op, inner) if op.kind == hir::UnOpKind::Neg => {
+            walk_number_const_expr(inner, has_rational)
+        }
+        hir::ExprKind::Binary(lhs, op, rhs)
+            if !matches!(op.kind, hir::BinOpKind::Or | hir::BinOpKind::And) =>
+        {
+            walk_number_const_expr(lhs, has_rational) && walk_number_const_expr(rhs, has_rational)
+        }
+        _ => false,
+    }
+}
+
 /// Evaluates the given array size expression, emitting an error diagnostic if it fails.
 pub fn eval_array_len(gcx: Gcx<'_>, size: &hir::Expr<'_>) -> Result<U256, ErrorGuaranteed> {
     let int = gcx.eval_const(size)?;
@@ -48,6 +97,7 @@ impl<'gcx> Gcx<'gcx> {
     pub fn try_eval_const(self, expr: &hir::Expr<'_>) -> Result<&'gcx IntScalar, EvalError> {
         match self.try_eval_const_value(expr)? {
             ConstValue::Integer(value) => Ok(value),
+            ConstValue::Rational(_) => Err(EE::NotAnInteger.into()),
             ConstValue::Bool(_) => Err(EE::UnsupportedExpr.into()),
             ConstValue::String(_) => Err(EE::UnsupportedLiteral.into()),
         }
@@ -84,7 +134,15 @@ impl<'gcx> Gcx<'gcx> {
 }
 
 pub(crate) fn eval_const(gcx: Gcx<'_>, expr: &hir::Expr<'_>) -> EvalResult {
-    ConstantEvaluator::new(gcx).try_eval_value(expr)
+    let value = ConstantEvaluator::new(gcx).try_eval_value(expr)?;
+    // Intermediate values are only bounded by `MAX_RATIONAL_BITS`, the final value must fit in
+    // 256 bits.
+    if let ConstValue::Integer(int) = &value
+        && int.bit_len() > MAX_BITS
+    {
+        return Err(EE::ValueOutOfRange.spanned(expr.span));
+    }
+    Ok(value)
 }
 
 /// Evaluates Solidity constant expressions.
@@ -191,7 +249,9 @@ impl<'gcx> ConstantEvaluator<'gcx> {
             LitKind::Str(StrKind::Str | StrKind::Unicode, s, _) => Ok(ConstValue::String(s)),
             LitKind::Str(StrKind::Hex, _, _) => Err(EE::UnsupportedLiteral.into()),
             LitKind::Number(n) => Ok(ConstValue::Integer(IntScalar::new(n))),
-            // LitKind::Rational(ratio) => todo!(),
+            LitKind::Rational(ratio) => {
+                ConstValue::from_rational(RationalScalar::from_lit(&ratio)).map_err(Into::into)
+            }
             LitKind::Address(address) => {
                 Ok(ConstValue::Integer(IntScalar::from_be_bytes(address.as_slice())))
             }
@@ -207,6 +267,10 @@ impl<'gcx> ConstantEvaluator<'gcx> {
 pub enum ConstValue {
     /// Integer-like constant value.
     Integer(IntScalar),
+    /// Fractional rational constant value, e.g. `0.5` or `1 / 3.0`.
+    ///
+    /// Rational values that are integers are always represented as [`Integer`](Self::Integer).
+    Rational(RationalScalar),
     /// Boolean constant value.
     Bool(bool),
     /// String constant value.
@@ -214,11 +278,22 @@ pub enum ConstValue {
 }
 
 impl ConstValue {
+    /// Creates a new number value from the given rational, normalizing integers to
+    /// [`Integer`](Self::Integer).
+    fn from_rational(value: RationalScalar) -> Result<Self, EE> {
+        let value = value.checked()?;
+        if value.data.is_integer() {
+            IntScalar::checked(value.data.to_integer()).map(Self::Integer)
+        } else {
+            Ok(Self::Rational(value))
+        }
+    }
+
     /// Returns the non-negative integer value as unsigned data.
     pub fn as_u256(&self) -> Option<U256> {
         match self {
             Self::Integer(value) => value.as_u256(),
-            Self::Bool(_) | Self::String(_) => None,
+            Self::Rational(_) | Self::Bool(_) | Self::String(_) => None,
         }
     }
 
@@ -226,7 +301,7 @@ impl ConstValue {
     pub fn as_bool(&self) -> Option<bool> {
         match self {
             Self::Bool(value) => Some(*value),
-            Self::Integer(_) | Self::String(_) => None,
+            Self::Integer(_) | Self::Rational(_) | Self::String(_) => None,
         }
     }
 
@@ -239,6 +314,7 @@ impl ConstValue {
     pub fn into_integer(self) -> Result<IntScalar, EvalError> {
         match self {
             Self::Integer(value) => Ok(value),
+            Self::Rational(_) => Err(EE::NotAnInteger.into()),
             Self::Bool(_) => Err(EE::UnsupportedExpr.into()),
             Self::String(_) => Err(EE::UnsupportedLiteral.into()),
         }
@@ -248,8 +324,11 @@ impl ConstValue {
     pub fn unop(self, op: hir::UnOpKind) -> Result<Self, EE> {
         Ok(match (self, op) {
             (Self::Integer(value), op) => Self::Integer(value.unop(op)?),
+            (Self::Rational(value), hir::UnOpKind::Neg) => Self::Rational(value.negate()),
             (Self::Bool(value), hir::UnOpKind::Not) => Self::Bool(!value),
-            (Self::Bool(_) | Self::String(_), _) => return Err(EE::UnsupportedUnaryOp),
+            (Self::Rational(_) | Self::Bool(_) | Self::String(_), _) => {
+                return Err(EE::UnsupportedUnaryOp);
+            }
         })
     }
 
@@ -269,6 +348,22 @@ impl ConstValue {
                 }
                 Or | And => return Err(EE::UnsupportedBinaryOp),
             },
+            (
+                lhs @ (Self::Integer(_) | Self::Rational(_)),
+                rhs @ (Self::Integer(_) | Self::Rational(_)),
+            ) => {
+                let lhs = RationalScalar::from_value(lhs);
+                let rhs = RationalScalar::from_value(rhs);
+                match op {
+                    Lt => Self::Bool(lhs.data < rhs.data),
+                    Le => Self::Bool(lhs.data <= rhs.data),
+                    Gt => Self::Bool(lhs.data > rhs.data),
+                    Ge => Self::Bool(lhs.data >= rhs.data),
+                    Eq => Self::Bool(lhs.data == rhs.data),
+                    Ne => Self::Bool(lhs.data != rhs.data),
+                    _ => Self::from_rational(lhs.binop(rhs, op)?)?,
+                }
+            }
             (Self::Bool(lhs), Self::Bool(rhs)) => match op {
                 And => Self::Bool(lhs && rhs),
                 Or => Self::Bool(lhs || rhs),
@@ -284,6 +379,83 @@ impl ConstValue {
     }
 }
 
+/// Represents an exact rational value for constant evaluation.
+#[derive(Debug)]
+pub struct RationalScalar {
+    data: BigRational,
+}
+
+impl RationalScalar {
+    fn from_lit(ratio: &Ratio<U256>) -> Self {
+        let numer = IntScalar::bigint_from_u256(*ratio.numer());
+        let denom = IntScalar::bigint_from_u256(*ratio.denom());
+        Self { data: BigRational::new(numer, denom) }
+    }
+
+    /// Converts a number constant value into a rational.
+    fn from_value(value: ConstValue) -> Self {
+        match value {
+            ConstValue::Integer(value) => Self { data: BigRational::from_integer(value.data) },
+            ConstValue::Rational(value) => value,
+            ConstValue::Bool(_) | ConstValue::String(_) => unreachable!("not a number"),
+        }
+    }
+
+    fn checked(self) -> Result<Self, EE> {
+        if self.data.numer().bits() > MAX_RATIONAL_BITS
+            || self.data.denom().bits() > MAX_RATIONAL_BITS
+        {
+            return Err(EE::ArithmeticOverflow);
+        }
+        Ok(self)
+    }
+
+    fn negate(self) -> Self {
+        Self { data: -self.data }
+    }
+
+    /// Applies the given arithmetic binary operation to this value.
+    ///
+    /// The result is exact: `0.1 * 10` evaluates to `1`.
+    fn binop(self, r: Self, op: hir::BinOpKind) -> Result<Self, EE> {
+        use hir::BinOpKind::*;
+        let data = match op {
+            Add => self.data + r.data,
+            Sub => self.data - r.data,
+            Mul => self.data * r.data,
+            Div | Rem if r.data.is_zero() => return Err(EE::DivisionByZero),
+            Div => self.data / r.data,
+            Rem => self.data % r.data,
+            Pow => {
+                // Rational exponents could produce irrational results.
+                if !r.data.is_integer() {
+                    return Err(EE::UnsupportedBinaryOp);
+                }
+                let exp = i32::try_from(r.data.to_integer()).map_err(|_| EE::ArithmeticOverflow)?;
+                if exp.is_negative() && self.data.is_zero() {
+                    return Err(EE::DivisionByZero);
+                }
+                // Bound the result size before computing it.
+                let bits = self.data.numer().bits().max(self.data.denom().bits());
+                if bits.saturating_mul(exp.unsigned_abs().into()) > MAX_RATIONAL_BITS {
+                    return Err(EE::ArithmeticOverflow);
+                }
+                self.data.pow(exp)
+            }
+            BitOr | BitAnd | BitXor | Shr | Shl | Sar | Lt | Le | Gt | Ge | Eq | Ne | Or | And => {
+                return Err(EE::UnsupportedBinaryOp);
+            }
+        };
+        Self { data }.checked()
+    }
+}
+
+impl fmt::Display for RationalScalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.data.numer(), self.data.denom())
+    }
+}
+
 /// Represents an integer value for constant evaluation.
 #[derive(Debug)]
 pub struct IntScalar {
@@ -357,8 +529,11 @@ impl IntScalar {
         BigInt::from_bytes_be(Sign::Plus, &data.to_be_bytes::<32>())
     }
 
+    /// Checks that an intermediate value stays within `MAX_RATIONAL_BITS`.
+    ///
+    /// Final values are checked against `MAX_BITS` in [`eval_const`].
     fn checked(data: BigInt) -> Result<Self, EE> {
-        if Self::bits(&data) > MAX_BITS {
+        if Self::bits(&data) > MAX_RATIONAL_BITS {
             return Err(EE::ArithmeticOverflow);
         }
         Ok(Self { data })
@@ -455,7 +630,7 @@ impl IntScalar {
             .try_into()
             .map_err(|_| EE::ArithmeticOverflow)?;
         let bits = Self::bits(&self.data);
-        if shift > MAX_BITS.saturating_sub(bits) {
+        if shift > MAX_RATIONAL_BITS.saturating_sub(bits) {
             return Err(EE::ArithmeticOverflow);
         }
         Self::checked(self.data << usize::try_from(shift).map_err(|_| EE::ArithmeticOverflow)?)
@@ -473,11 +648,17 @@ impl IntScalar {
             let is_odd = exp.bit(0);
             return Self::checked(if is_odd { self.data } else { BigInt::one() });
         }
-        let exp = r.as_u256().ok_or(EE::ArithmeticOverflow)?;
-        if exp > U256::from(MAX_BITS) {
+        let exp: u32 = r
+            .as_u256()
+            .ok_or(EE::ArithmeticOverflow)?
+            .try_into()
+            .map_err(|_| EE::ArithmeticOverflow)?;
+        // Bound the result size before computing it: the result has at least
+        // `(bits - 1) * exp + 1` bits.
+        let bits = self.data.magnitude().bits();
+        if (bits - 1).saturating_mul(exp.into()) >= MAX_RATIONAL_BITS {
             return Err(EE::ArithmeticOverflow);
         }
-        let exp = exp.try_into().map_err(|_| EE::ArithmeticOverflow)?;
         Self::checked(self.data.pow(exp))
     }
 }
@@ -486,12 +667,14 @@ impl IntScalar {
 pub enum EvalErrorKind {
     RecursionLimitReached,
     ArithmeticOverflow,
+    ValueOutOfRange,
     DivisionByZero,
     UnsupportedLiteral,
     UnsupportedUnaryOp,
     UnsupportedBinaryOp,
     UnsupportedExpr,
     NonConstantVar,
+    NotAnInteger,
     AlreadyEmitted(ErrorGuaranteed),
 }
 use EvalErrorKind as EE;
@@ -505,12 +688,14 @@ impl EvalErrorKind {
         match self {
             Self::RecursionLimitReached => "recursion limit reached",
             Self::ArithmeticOverflow => "arithmetic overflow",
+            Self::ValueOutOfRange => "value does not fit in 256 bits",
             Self::DivisionByZero => "attempted to divide by zero",
             Self::UnsupportedLiteral => "unsupported literal",
             Self::UnsupportedUnaryOp => "unsupported unary operation",
             Self::UnsupportedBinaryOp => "unsupported binary operation",
             Self::UnsupportedExpr => "unsupported expression",
             Self::NonConstantVar => "only constant variables are allowed",
+            Self::NotAnInteger => "value is not an integer",
             Self::AlreadyEmitted(_) => unreachable!(),
         }
     }
@@ -538,9 +723,10 @@ impl std::error::Error for EvalError {}
 
 #[cfg(test)]
 mod tests {
-    use super::{ConstValue, IntScalar, erc7201_slot};
+    use super::{ConstValue, IntScalar, RationalScalar, erc7201_slot};
     use crate::hir;
     use alloy_primitives::{U256, b256};
+    use num_rational::Ratio;
 
     #[test]
     fn const_value_integer_accessors() {
@@ -567,6 +753,34 @@ mod tests {
         assert!(!value.is_zero());
     }
 
+    #[test]
+    fn rational_arithmetic_is_exact() {
+        let tenth = || {
+            ConstValue::from_rational(RationalScalar::from_lit(&Ratio::new(
+                U256::from(1),
+                U256::from(10),
+            )))
+            .unwrap()
+        };
+        let ten = || ConstValue::Integer(IntScalar::new(U256::from(10)));
+
+        assert!(matches!(tenth(), ConstValue::Rational(_)));
+        assert_eq!(tenth().as_u256(), None);
+
+        let product = tenth().binop(ten(), hir::BinOpKind::Mul).unwrap();
+        assert_eq!(product.as_u256(), Some(U256::from(1)));
+
+        let sum = tenth().binop(tenth(), hir::BinOpKind::Add).unwrap();
+        let ConstValue::Rational(sum) = sum else { panic!("expected rational: {sum:?}") };
+        assert_eq!(sum.to_string(), "1/5");
+
+        let cmp = tenth().binop(ten(), hir::BinOpKind::Lt).unwrap();
+        assert_eq!(cmp.as_bool(), Some(true));
+
+        assert!(tenth().binop(ten(), hir::BinOpKind::BitOr).is_err());
+        assert!(tenth().into_integer().is_err());
+    }
+
     #[test]
     fn erc7201_slot_matches_eip_example() {
         assert_eq!(
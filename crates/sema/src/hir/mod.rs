@@ -7,10 +7,13 @@ use rayon::prelude::*;
 use solar_ast as ast;
 use solar_data_structures::{
     BumpExt,
-    index::{Idx, IndexVec},
+    index::{Idx, IndexVec, IndexVecExt},
+    map::FxHashMap,
     newtype_index,
 };
-use solar_interface::{Ident, Span, Symbol, diagnostics::ErrorGuaranteed, source_map::SourceFile};
+use solar_interface::{
+    Ident, Span, Symbol, diagnostics::ErrorGuaranteed, source_map::SourceFile, sym,
+};
 use std::{cell::Cell, fmt, ops::ControlFlow, sync::Arc};
 use strum::EnumIs;
 
@@ -97,6 +100,8 @@ pub struct Hir<'hir> {
     pub(crate) errors: IndexVec<ErrorId, Error<'hir>>,
     /// All events.
     pub(crate) events: IndexVec<EventId, Event<'hir>>,
+    /// The position of each contract item in its contract's `items`.
+    pub(crate) item_indices: FxHashMap<ItemId, u32>,
 }
 
 macro_rules! indexvec_methods {
@@ -156,6 +161,12 @@ macro_rules! indexvec_methods {
                 // of the respective index type.
                 self.[<par_ $plural>]().enumerate().map(|(i, v)| (unsafe { $id::from_usize_unchecked(i) }, v))
             }
+
+            #[doc = "Creates a side table with a value for each " $singular ", computed by calling `f` with each ID in parallel."]
+            #[inline]
+            pub fn [<par_ $singular _table>]<T: Send>(&self, f: impl Fn($id) -> T + Sync + Send) -> IndexVec<$id, T> {
+                IndexVec::par_from_fn_n(f, self.$plural.len())
+            }
         )*
 
         pub(crate) fn shrink_to_fit(&mut self) {
@@ -187,6 +198,7 @@ impl<'hir> Hir<'hir> {
             udvts: IndexVec::new(),
             errors: IndexVec::new(),
             events: IndexVec::new(),
+            item_indices: FxHashMap::default(),
         }
     }
 
@@ -320,6 +332,14 @@ impl<'hir> Hir<'hir> {
         self.contract_item_ids(id).map(move |id| self.item(id))
     }
 
+    /// Returns the position of the given item in its contract's [`Contract::items`].
+    ///
+    /// This is the declaration order of the item in the source, and is stable across runs.
+    /// Returns `None` if the item is not declared inside of a contract.
+    pub fn item_index(&self, id: impl Into<ItemId>) -> Option<usize> {
+        self.item_indices.get(&id.into()).map(|&i| i as usize)
+    }
+
     /// Creates a builder for constructing HIR nodes.
     pub fn builder<'id>(
         arena: &'hir bumpalo::Bump,
@@ -889,7 +909,10 @@ pub struct Contract<'hir> {
     pub fallback: Option<FunctionId>,
     /// The resolved `receive` function.
     pub receive: Option<FunctionId>,
-    /// The contract items.
+    /// The contract items, in declaration order.
+    ///
+    /// Public state variable getters immediately follow their variable, and Yul functions
+    /// immediately follow the function they are defined in. See [`Hir::item_index`].
     ///
     /// Note that this only includes items defined in the contract itself, not inherited items.
     /// For getting all items, use [`Hir::contract_items`].
@@ -1442,8 +1465,8 @@ pub enum StmtKind<'hir> {
     /// An unchecked block: `unchecked { ... }`.
     UncheckedBlock(Block<'hir>),
 
-    /// An inline assembly block: `assembly { ... }`.
-    AssemblyBlock(Block<'hir>),
+    /// An inline assembly block: `assembly ("memory-safe") { ... }`.
+    AssemblyBlock(&'hir StmtAssembly<'hir>),
 
     /// An emit statement: `emit Foo.bar(42);`.
     ///
@@ -1485,6 +1508,24 @@ pub enum StmtKind<'hir> {
     Err(ErrorGuaranteed),
 }
 
+/// An inline assembly block: `assembly "evmasm" ("memory-safe") { ... }`.
+#[derive(Debug)]
+pub struct StmtAssembly<'hir> {
+    /// The assembly block dialect, if specified.
+    pub dialect: Option<Symbol>,
+    /// Additional flags.
+    pub flags: &'hir [Symbol],
+    /// The assembly block.
+    pub block: Block<'hir>,
+}
+
+impl StmtAssembly<'_> {
+    /// Returns `true` if the block is marked as `memory-safe`.
+    pub fn is_memory_safe(&self) -> bool {
+        self.flags.contains(&sym::memory_dash_safe)
+    }
+}
+
 /// A switch statement: `switch expr case 0 { ... } default { ... }`.
 #[derive(Debug)]
 pub struct StmtSwitch<'hir> {
@@ -2003,6 +2044,21 @@ pub struct TypeMapping<'hir> {
     pub value_name: Option<Ident>,
 }
 
+// Some nodes are used a lot. Make sure they don't unintentionally get bigger.
+#[cfg(all(target_pointer_width = "64", not(feature = "nightly")))]
+mod size_asserts {
+    use super::*;
+    use solar_data_structures::static_assert_size;
+
+    static_assert_size!(Expr<'_>, 64);
+    static_assert_size!(ExprKind<'_>, 48);
+    static_assert_size!(Item<'_, '_>, 16);
+    static_assert_size!(Stmt<'_>, 40);
+    static_assert_size!(StmtKind<'_>, 32);
+    static_assert_size!(Type<'_>, 24);
+    static_assert_size!(TypeKind<'_>, 16);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2052,9 +2108,10 @@ mod tests {
             assert_data_eq!(actual.to_string(), expected);
         }
 
-        assert_size::<Hir<'_>>(str!["240"]);
+        // The sizes of the most common nodes are asserted at compile time in `size_asserts`.
+
+        assert_size::<Hir<'_>>(str!["272"]);
 
-        assert_size::<Item<'_, '_>>(str!["16"]);
         assert_size::<Contract<'_>>(str!["152"]);
         assert_size::<Function<'_>>(str!["144"]);
         assert_size::<Struct<'_>>(str!["48"]);
@@ -2064,14 +2121,6 @@ mod tests {
         assert_size::<Event<'_>>(str!["56"]);
         assert_size::<Variable<'_>>(str!["104"]);
 
-        assert_size::<TypeKind<'_>>(str!["16"]);
-        assert_size::<Type<'_>>(str!["24"]);
-
-        assert_size::<ExprKind<'_>>(str!["48"]);
-        assert_size::<Expr<'_>>(str!["64"]);
-
-        assert_size::<StmtKind<'_>>(str!["32"]);
-        assert_size::<Stmt<'_>>(str!["40"]);
         assert_size::<Block<'_>>(str!["24"]);
     }
 }
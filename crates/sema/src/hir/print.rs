@@ -10,12 +10,45 @@ pub struct HirPrinter<'gcx> {
     gcx: Gcx<'gcx>,
     out: String,
     indent: usize,
+    full_parens: bool,
+    desugared: bool,
+    /// The code that `_;` expands to in the modifier bodies being inlined, innermost last.
+    placeholders: Vec<(Vec<&'gcx hir::Modifier<'gcx>>, &'gcx hir::Block<'gcx>)>,
 }
 
 impl<'gcx> HirPrinter<'gcx> {
     /// Creates a new HIR printer.
     pub fn new(gcx: Gcx<'gcx>) -> Self {
-        Self { gcx, out: String::new(), indent: 0 }
+        Self {
+            gcx,
+            out: String::new(),
+            indent: 0,
+            full_parens: false,
+            desugared: false,
+            placeholders: Vec::new(),
+        }
+    }
+
+    /// Sets whether nested operator expressions are always parenthesized.
+    ///
+    /// By default, parentheses are only printed where they are needed to preserve the evaluation
+    /// order, e.g. `a + b * c` and `(a + b) * c`. When enabled, every operator expression that is
+    /// an operand of another one is parenthesized, e.g. `a + (b * c)`, which makes the evaluation
+    /// order explicit.
+    pub fn with_full_parens(mut self, yes: bool) -> Self {
+        self.full_parens = yes;
+        self
+    }
+
+    /// Sets whether to print the desugared program.
+    ///
+    /// When enabled, loops are printed as `while (true)`, or `for (;; <next>)` for `for` loops with
+    /// an increment, and the bodies of the modifiers applied to a function are inlined into it,
+    /// with the modifier parameters declared as local variables initialized to the modifier
+    /// arguments.
+    pub fn with_desugared(mut self, yes: bool) -> Self {
+        self.desugared = yes;
+        self
     }
 
     /// Prints all HIR sources and returns the accumulated output.
@@ -178,7 +211,11 @@ impl<'gcx> HirPrinter<'gcx> {
         if func.state_mutability != hir::StateMutability::NonPayable {
             write!(self.out, " {}", func.state_mutability).unwrap();
         }
+        let inlined = self.inlined_modifiers(func);
         for modifier in func.modifiers {
+            if inlined.iter().any(|&inlined| std::ptr::eq(inlined, modifier)) {
+                continue;
+            }
             self.out.push(' ');
             self.print_modifier(modifier);
         }
@@ -205,12 +242,70 @@ impl<'gcx> HirPrinter<'gcx> {
         }
         if let Some(body) = &func.body {
             self.out.push(' ');
-            self.print_block(body);
+            self.print_inlined(&inlined, body);
         } else {
             self.out.push_str(";\n");
         }
     }
 
+    /// Returns the modifiers that are inlined into the body of `func` when printing the desugared
+    /// program. Base constructor calls and modifiers without a body are not inlined.
+    fn inlined_modifiers(&self, func: &'gcx hir::Function<'gcx>) -> Vec<&'gcx hir::Modifier<'gcx>> {
+        if !self.desugared || func.body.is_none() {
+            return Vec::new();
+        }
+        func.modifiers
+            .iter()
+            .filter(|modifier| {
+                modifier.id.as_function().is_some_and(|id| {
+                    let modifier = self.gcx.hir.function(id);
+                    modifier.kind.is_modifier() && modifier.body.is_some()
+                })
+            })
+            .collect()
+    }
+
+    /// Prints `body` wrapped in the bodies of `modifiers`, outermost first.
+    fn print_inlined(
+        &mut self,
+        modifiers: &[&'gcx hir::Modifier<'gcx>],
+        body: &'gcx hir::Block<'gcx>,
+    ) {
+        let [modifier, rest @ ..] = modifiers else {
+            self.print_block(body);
+            return;
+        };
+        let func = self.gcx.hir.function(modifier.id.as_function().unwrap());
+        self.out.push_str("{\n");
+        self.indent += 1;
+        self.write_indent();
+        self.out.push_str("// modifier ");
+        self.print_modifier(modifier);
+        self.out.push('\n');
+        let names = func
+            .parameters
+            .iter()
+            .map(|&param| self.gcx.hir.variable(param).name.map(|name| name.name))
+            .collect::<Vec<_>>();
+        for (i, &param) in func.parameters.iter().enumerate() {
+            self.write_indent();
+            self.print_variable(param, VarMode::Local);
+            if let Some(arg) = modifier.args.argument_for_parameter(i, Some(&names)) {
+                self.out.push_str(" = ");
+                self.print_expr(arg);
+            }
+            self.out.push_str(";\n");
+        }
+        self.placeholders.push((rest.to_vec(), body));
+        for stmt in func.body.as_ref().unwrap().stmts {
+            self.print_stmt(stmt);
+        }
+        self.placeholders.pop();
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push_str("}\n");
+    }
+
     fn print_usings(&mut self, usings: &[hir::UsingDirective<'gcx>]) {
         for using in usings {
             self.write_indent();
@@ -353,9 +448,22 @@ impl<'gcx> HirPrinter<'gcx> {
                 self.out.push_str("unchecked ");
                 self.print_block(block);
             }
-            StmtKind::AssemblyBlock(block) => {
+            StmtKind::AssemblyBlock(assembly) => {
                 self.out.push_str("assembly ");
-                self.print_block(block);
+                if let Some(dialect) = assembly.dialect {
+                    write!(self.out, "\"{dialect}\" ").unwrap();
+                }
+                if !assembly.flags.is_empty() {
+                    self.out.push('(');
+                    for (i, flag) in assembly.flags.iter().enumerate() {
+                        if i != 0 {
+                            self.out.push_str(", ");
+                        }
+                        write!(self.out, "\"{flag}\"").unwrap();
+                    }
+                    self.out.push_str(") ");
+                }
+                self.print_block(&assembly.block);
             }
             StmtKind::Emit(expr) => {
                 self.out.push_str("emit ");
@@ -378,12 +486,16 @@ impl<'gcx> HirPrinter<'gcx> {
             StmtKind::Break => self.out.push_str("break;\n"),
             StmtKind::Continue => self.out.push_str("continue;\n"),
             StmtKind::Loop(block, source) => {
-                write!(self.out, "hir.loop({}) ", source.name()).unwrap();
-                self.print_block(block);
+                if self.desugared {
+                    self.print_desugared_loop(block, *source);
+                } else {
+                    write!(self.out, "hir.loop({}) ", source.name()).unwrap();
+                    self.print_block(block);
+                }
             }
             StmtKind::If(cond, then, else_) => {
                 self.out.push_str("if (");
-                self.print_condition(cond);
+                self.print_expr(cond);
                 self.out.push_str(") ");
                 self.print_stmt_as_block(then);
                 if let Some(else_) = else_ {
@@ -415,11 +527,50 @@ impl<'gcx> HirPrinter<'gcx> {
                 self.print_expr(expr);
                 self.out.push_str(";\n");
             }
-            StmtKind::Placeholder => self.out.push_str("_;\n"),
+            StmtKind::Placeholder => {
+                if let Some((modifiers, body)) = self.placeholders.last().cloned() {
+                    self.print_inlined(&modifiers, body);
+                } else {
+                    self.out.push_str("_;\n");
+                }
+            }
             StmtKind::Err(_) => self.out.push_str("<error>;\n"),
         }
     }
 
+    /// Prints a loop as `while (true) { ... }`.
+    ///
+    /// The increment of a `for` loop is lowered to the end of the loop body, where a `continue`
+    /// would skip it, so it is printed in the loop header instead: `for (;; <next>) { ... }`.
+    fn print_desugared_loop(&mut self, block: &hir::Block<'gcx>, source: hir::LoopSource) {
+        let Some((body, next)) = for_loop_next(block).filter(|_| source == hir::LoopSource::For)
+        else {
+            self.out.push_str("while (true) ");
+            self.print_block(block);
+            return;
+        };
+
+        self.out.push_str("for (;; ");
+        self.print_expr(next);
+        self.out.push_str(") {\n");
+        self.indent += 1;
+        self.write_indent();
+        if let StmtKind::If(cond, _, Some(else_)) = &block.stmts[0].kind {
+            self.out.push_str("if (");
+            self.print_expr(cond);
+            self.out.push_str(") ");
+            self.print_stmt_as_block(body);
+            self.write_indent();
+            self.out.push_str("else ");
+            self.print_stmt_as_block(else_);
+        } else {
+            self.print_stmt_as_block(body);
+        }
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push_str("}\n");
+    }
+
     fn print_stmt_as_block(&mut self, stmt: &hir::Stmt<'gcx>) {
         if let StmtKind::Block(block) = &stmt.kind {
             self.print_block(block);
@@ -453,6 +604,30 @@ impl<'gcx> HirPrinter<'gcx> {
     }
 
     fn print_expr(&mut self, expr: &hir::Expr<'gcx>) {
+        self.print_expr_prec(expr, PREC_MIN);
+    }
+
+    /// Prints an expression in a context that binds with precedence `min`, parenthesizing it if
+    /// needed.
+    fn print_expr_prec(&mut self, expr: &hir::Expr<'gcx>, min: u8) {
+        // Parentheses are grouping-only tuples, so they are printed based on precedence instead.
+        if let ExprKind::Tuple([Some(inner)]) = expr.kind {
+            return self.print_expr_prec(inner, min);
+        }
+
+        let prec = expr_precedence(expr);
+        let parens =
+            if self.full_parens { min > PREC_MIN && prec < PREC_POSTFIX } else { prec < min };
+        if parens {
+            self.out.push('(');
+        }
+        self.print_expr_kind(expr);
+        if parens {
+            self.out.push(')');
+        }
+    }
+
+    fn print_expr_kind(&mut self, expr: &hir::Expr<'gcx>) {
         match &expr.kind {
             ExprKind::Array(exprs) => {
                 self.out.push('[');
@@ -465,23 +640,28 @@ impl<'gcx> HirPrinter<'gcx> {
                 self.out.push(']');
             }
             ExprKind::Assign(lhs, op, rhs) => {
-                self.print_expr(lhs);
+                self.print_expr_prec(lhs, PREC_TERNARY + 1);
                 self.out.push(' ');
                 if let Some(op) = op {
                     self.out.push_str(op.kind.to_str());
                 }
                 self.out.push_str("= ");
-                self.print_expr(rhs);
+                self.print_expr_prec(rhs, PREC_ASSIGN);
             }
             ExprKind::Binary(lhs, op, rhs) => {
-                self.out.push('(');
-                self.print_expr(lhs);
+                let prec = op.kind.precedence();
+                let (lhs_prec, rhs_prec) =
+                    if op.kind.is_right_assoc() { (prec + 1, prec) } else { (prec, prec + 1) };
+                self.print_expr_prec(lhs, lhs_prec);
                 write!(self.out, " {} ", op.kind.to_str()).unwrap();
-                self.print_expr(rhs);
-                self.out.push(')');
+                self.print_expr_prec(rhs, rhs_prec);
             }
             ExprKind::Call(callee, args, opts) => {
-                self.print_expr(callee);
+                if let ExprKind::New(_) = callee.kind {
+                    self.print_expr(callee);
+                } else {
+                    self.print_expr_prec(callee, PREC_POSTFIX);
+                }
                 if let Some(opts) = opts {
                     self.out.push_str(" { ");
                     for (i, arg) in opts.args.iter().enumerate() {
@@ -497,11 +677,11 @@ impl<'gcx> HirPrinter<'gcx> {
             }
             ExprKind::Delete(expr) => {
                 self.out.push_str("delete ");
-                self.print_expr(expr);
+                self.print_expr_prec(expr, PREC_PREFIX);
             }
             ExprKind::Ident(res) => self.print_res_list(res),
             ExprKind::Index(expr, index) => {
-                self.print_expr(expr);
+                self.print_expr_prec(expr, PREC_POSTFIX);
                 self.out.push('[');
                 if let Some(index) = index {
                     self.print_expr(index);
@@ -509,7 +689,7 @@ impl<'gcx> HirPrinter<'gcx> {
                 self.out.push(']');
             }
             ExprKind::Slice(expr, start, end) => {
-                self.print_expr(expr);
+                self.print_expr_prec(expr, PREC_POSTFIX);
                 self.out.push('[');
                 if let Some(start) = start {
                     self.print_expr(start);
@@ -522,7 +702,7 @@ impl<'gcx> HirPrinter<'gcx> {
             }
             ExprKind::Lit(lit) => write!(self.out, "{lit}").unwrap(),
             ExprKind::Member(expr, ident) | ExprKind::YulMember(expr, ident) => {
-                self.print_expr(expr);
+                self.print_expr_prec(expr, PREC_POSTFIX);
                 write!(self.out, ".{ident}").unwrap();
             }
             ExprKind::New(ty) => {
@@ -535,13 +715,11 @@ impl<'gcx> HirPrinter<'gcx> {
                 self.out.push(')');
             }
             ExprKind::Ternary(cond, then, else_) => {
-                self.out.push('(');
-                self.print_expr(cond);
+                self.print_expr_prec(cond, PREC_TERNARY + 1);
                 self.out.push_str(" ? ");
-                self.print_expr(then);
+                self.print_expr_prec(then, PREC_ASSIGN);
                 self.out.push_str(" : ");
-                self.print_expr(else_);
-                self.out.push(')');
+                self.print_expr_prec(else_, PREC_ASSIGN);
             }
             ExprKind::Tuple(exprs) => {
                 self.out.push('(');
@@ -564,9 +742,20 @@ impl<'gcx> HirPrinter<'gcx> {
             ExprKind::Unary(op, expr) => {
                 if op.kind.is_prefix() {
                     self.out.push_str(op.kind.to_str());
-                    self.print_expr(expr);
+                    // `-(-x)` must not be printed as `--x`.
+                    let min = match expr.kind {
+                        ExprKind::Unary(inner, _)
+                            if inner.kind.is_prefix()
+                                && op.kind.to_str().ends_with('-')
+                                && inner.kind.to_str().starts_with('-') =>
+                        {
+                            PREC_PRIMARY
+                        }
+                        _ => PREC_PREFIX,
+                    };
+                    self.print_expr_prec(expr, min);
                 } else {
-                    self.print_expr(expr);
+                    self.print_expr_prec(expr, PREC_POSTFIX);
                     self.out.push_str(op.kind.to_str());
                 }
             }
@@ -574,16 +763,6 @@ impl<'gcx> HirPrinter<'gcx> {
         }
     }
 
-    fn print_condition(&mut self, expr: &hir::Expr<'gcx>) {
-        if let ExprKind::Binary(lhs, op, rhs) = &expr.kind {
-            self.print_expr(lhs);
-            write!(self.out, " {} ", op.kind.to_str()).unwrap();
-            self.print_expr(rhs);
-        } else {
-            self.print_expr(expr);
-        }
-    }
-
     fn print_call_args(&mut self, args: &hir::CallArgs<'gcx>) {
         match args.kind {
             CallArgsKind::Unnamed(exprs) => {
@@ -732,6 +911,64 @@ impl<'gcx> HirPrinter<'gcx> {
     }
 }
 
+/// The precedence of an expression that is not an operand, e.g. a statement or a call argument.
+const PREC_MIN: u8 = 0;
+/// The precedence of assignments. Binary operators are in between this and [`PREC_PREFIX`], see
+/// [`hir::BinOpKind::precedence`].
+const PREC_ASSIGN: u8 = 2;
+const PREC_TERNARY: u8 = 3;
+const PREC_PREFIX: u8 = 15;
+const PREC_POSTFIX: u8 = 16;
+const PREC_PRIMARY: u8 = 17;
+
+/// Returns the precedence of an expression when printed. Higher precedences bind tighter.
+/// Splits the body of a lowered `for` loop with an increment expression into the original body
+/// and the increment.
+///
+/// The loop body is `if (<cond>) { <body> <next>; } else break;`, or `{ <body> <next>; }` without
+/// a condition, where the block containing `<next>` has the same span as the loop's block. A
+/// block written by the user is always smaller than the whole `for` statement.
+fn for_loop_next<'a, 'hir>(
+    block: &'a hir::Block<'hir>,
+) -> Option<(&'a hir::Stmt<'hir>, &'hir hir::Expr<'hir>)> {
+    let [stmt] = block.stmts else { return None };
+    let stmt = match &stmt.kind {
+        StmtKind::If(_, then, Some(_)) => *then,
+        _ => stmt,
+    };
+    let StmtKind::Block(inner) = &stmt.kind else { return None };
+    match inner.stmts {
+        [body, hir::Stmt { kind: StmtKind::Expr(next), .. }] if inner.span == block.span => {
+            Some((body, *next))
+        }
+        _ => None,
+    }
+}
+
+fn expr_precedence(expr: &hir::Expr<'_>) -> u8 {
+    match expr.kind {
+        ExprKind::Assign(..) => PREC_ASSIGN,
+        ExprKind::Ternary(..) => PREC_TERNARY,
+        ExprKind::Binary(_, op, _) => op.kind.precedence(),
+        ExprKind::Unary(op, _) if op.kind.is_prefix() => PREC_PREFIX,
+        ExprKind::Delete(_) | ExprKind::New(_) => PREC_PREFIX,
+        ExprKind::Unary(..)
+        | ExprKind::Call(..)
+        | ExprKind::Index(..)
+        | ExprKind::Slice(..)
+        | ExprKind::Member(..)
+        | ExprKind::YulMember(..) => PREC_POSTFIX,
+        ExprKind::Array(_)
+        | ExprKind::Ident(_)
+        | ExprKind::Lit(_)
+        | ExprKind::Payable(_)
+        | ExprKind::Tuple(_)
+        | ExprKind::TypeCall(_)
+        | ExprKind::Type(_)
+        | ExprKind::Err(_) => PREC_PRIMARY,
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum VarMode {
     Item,
@@ -743,3 +980,47 @@ enum VarMode {
 fn builtin_name(builtin: Builtin) -> impl fmt::Display {
     solar_data_structures::fmt::from_fn(move |f| f.write_str(builtin.name().as_str()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze_source, interface::config::CompileOpts};
+
+    /// Prints `expr` as the returned expression of a function.
+    fn print(expr: &str, ty: &str, full_parens: bool) -> String {
+        let src = format!(
+            "contract C {{ function f(uint a, uint b, uint c, bool p, bool q) public pure returns ({ty}) {{ return {expr}; }} }}"
+        );
+        let unit = analyze_source("C.sol".to_string(), src, CompileOpts::default()).unwrap();
+        let out = unit.enter(|gcx| HirPrinter::new(gcx).with_full_parens(full_parens).print_all());
+        let line = out.lines().map(str::trim).find(|line| line.starts_with("return ")).unwrap();
+        line.strip_prefix("return ").unwrap().strip_suffix(';').unwrap().to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        let cases = [
+            ("a + b * c", "uint", "a + b * c", "a + (b * c)"),
+            ("(a + b) * c", "uint", "(a + b) * c", "(a + b) * c"),
+            ("((a)) + (b)", "uint", "a + b", "a + b"),
+            ("(a - b) - c", "uint", "a - b - c", "(a - b) - c"),
+            ("a - (b - c)", "uint", "a - (b - c)", "a - (b - c)"),
+            ("a ** b ** c", "uint", "a ** b ** c", "a ** (b ** c)"),
+            ("(a ** b) ** c", "uint", "(a ** b) ** c", "(a ** b) ** c"),
+            ("a << b + c & a", "uint", "a << b + c & a", "(a << (b + c)) & a"),
+            ("p || q && !p", "bool", "p || q && !p", "p || (q && (!p))"),
+            ("(p || q) && !(p && q)", "bool", "(p || q) && !(p && q)", "(p || q) && (!(p && q))"),
+            ("p ? a : b + c", "uint", "p ? a : b + c", "p ? a : (b + c)"),
+            ("(p ? a : b) + c", "uint", "(p ? a : b) + c", "(p ? a : b) + c"),
+            ("(p ? q : p) ? a : b", "uint", "(p ? q : p) ? a : b", "(p ? q : p) ? a : b"),
+            ("-(-int(a))", "int", "-(-int256(a))", "-(-int256(a))"),
+            ("~(~a)", "uint", "~~a", "~(~a)"),
+        ];
+        for (src, ty, minimal, full) in cases {
+            assert_eq!(print(src, ty, false), minimal, "{src}");
+            assert_eq!(print(src, ty, true), full, "{src}");
+            // Re-parsing the minimal output must give the same evaluation structure.
+            assert_eq!(print(minimal, ty, true), full, "{src}");
+        }
+    }
+}
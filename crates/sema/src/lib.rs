@@ -13,7 +13,9 @@ use indexmap as _;
 use rayon::prelude::*;
 use solar_interface::{
     Result, Session,
-    config::{CompilerStage, DumpKind},
+    config::{CompilerStage, DumpKind, Unpretty},
+    events::PassTimer,
+    panic_hook,
 };
 use std::ops::ControlFlow;
 
@@ -32,22 +34,34 @@ mod natspec;
 mod compiler;
 pub use compiler::{Compiler, CompilerRef};
 
+mod analyze;
+pub use analyze::{AnalyzedUnit, analyze_source};
+
 mod parse;
 pub use parse::{ParsingContext, Source, Sources};
 
+mod progress;
+pub use progress::{Progress, ProgressCounts};
+
 pub mod builtins;
 pub mod eval;
 
 pub mod output;
 
+pub mod profile;
+
 pub mod hir;
 pub use hir::Hir;
 
 pub mod ty;
 pub use ty::{Gcx, NatSpecView, Ty};
 
+mod selection;
+
 mod typeck;
 
+mod lints;
+
 pub mod stats;
 
 mod span_visitor;
@@ -83,10 +97,27 @@ pub(crate) fn lower(compiler: &mut CompilerRef<'_>) -> Result<ControlFlow<()>> {
         }
     }
 
+    if sess.opts.unstable.validate_spans {
+        use crate::span_visitor::SpanValidator;
+        use ast::visit::Visit;
+        for source in gcx.sources.iter() {
+            let Some(ast) = &source.ast else { continue };
+            let mut validator = SpanValidator::new(sess, &source.file);
+            let _ = validator.visit_source_unit(ast);
+            debug!(errors = validator.errors(), "span validation completed");
+        }
+    }
+
+    if sess.opts.unstable.unpretty == Some(Unpretty::Ast) {
+        print!("{}", unpretty_ast(&gcx.sources));
+        return Ok(ControlFlow::Break(()));
+    }
+
     if sess.opts.language.is_yul() || gcx.advance_stage(CompilerStage::Lowering).is_break() {
         return Ok(ControlFlow::Break(()));
     }
 
+    let _timer = PassTimer::new("lowering");
     compiler.gcx_mut().sources.topo_sort();
 
     debug_span!("all_ast_passes").in_scope(|| {
@@ -96,15 +127,17 @@ pub(crate) fn lower(compiler: &mut CompilerRef<'_>) -> Result<ControlFlow<()>> {
     });
 
     ast_lowering::lower(compiler.gcx_mut());
+    gcx.progress.set_lowered(gcx.sources.asts().count());
 
     Ok(ControlFlow::Continue(()))
 }
 
 #[instrument(level = "debug", skip_all)]
 fn analysis(gcx: Gcx<'_>) -> Result<ControlFlow<()>> {
-    if let ControlFlow::Break(()) = gcx.advance_stage(CompilerStage::Analysis) {
+    if let ControlFlow::Break(()) = gcx.advance_stage(CompilerStage::Typeck) {
         return Ok(ControlFlow::Break(()));
     }
+    let _timer = PassTimer::new("analysis");
 
     if let Some(dump) = &gcx.sess.opts.unstable.dump
         && dump.kinds.contains(&DumpKind::Hir)
@@ -112,12 +145,26 @@ fn analysis(gcx: Gcx<'_>) -> Result<ControlFlow<()>> {
         dump_hir(gcx, dump.paths.as_deref())?;
     }
 
+    if let Some(unpretty @ (Unpretty::Hir | Unpretty::Desugared)) = gcx.sess.opts.unstable.unpretty
+    {
+        let desugared = unpretty == Unpretty::Desugared;
+        print!("{}", hir::HirPrinter::new(gcx).with_desugared(desugared).print_all());
+        return Ok(ControlFlow::Break(()));
+    }
+
     if gcx.sess.opts.unstable.hir_stats {
         stats::print_hir_stats(&gcx.hir, "HIR STATS");
     }
 
+    selection::select_contracts(gcx)?;
+
     // Lower HIR types.
     gcx.hir.par_item_ids().for_each(|id| {
+        let source = gcx.hir.item(id).source();
+        if !gcx.is_source_selected(source) {
+            return;
+        }
+        let _file = panic_hook::enter_file(&gcx.hir.source(source).file.name);
         let _ = gcx.type_of_item(id);
         match id {
             hir::ItemId::Struct(id) => {
@@ -132,6 +179,11 @@ fn analysis(gcx: Gcx<'_>) -> Result<ControlFlow<()>> {
 
     typeck::check(gcx);
 
+    if let ControlFlow::Break(()) = gcx.advance_stage(CompilerStage::Analysis) {
+        return Ok(ControlFlow::Break(()));
+    }
+    lints::check(gcx);
+
     Ok(ControlFlow::Continue(()))
 }
 
@@ -163,6 +215,22 @@ fn dump_ast(sess: &Session, sources: &Sources<'_>, paths: Option<&[String]>) ->
     Ok(())
 }
 
+/// Prints the ASTs of all sources as Solidity, each preceded by a comment with its file name.
+fn unpretty_ast(sources: &Sources<'_>) -> String {
+    let mut out = String::new();
+    for source in sources.iter() {
+        let Some(ast) = &source.ast else { continue };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("// {}\n", source.file.name.display()));
+        let mut printer = ast::AstPrinter::new();
+        printer.print_source_unit(ast);
+        out.push_str(&printer.finish());
+    }
+    out
+}
+
 fn dump_hir(gcx: Gcx<'_>, paths: Option<&[String]>) -> Result<()> {
     if let Some(paths) = paths {
         let mut printer = hir::HirPrinter::new(gcx);
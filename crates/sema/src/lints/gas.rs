@@ -0,0 +1,323 @@
+//! Lints for code patterns that can be made cheaper in gas.
+
+use crate::{
+    hir::{self, ExprKind, StmtKind, VariableId, Visit},
+    ty::{Gcx, TyKind},
+};
+use solar_ast::{DataLocation, UnOpKind, Visibility};
+use solar_data_structures::{
+    Never,
+    map::{FxHashSet, FxIndexMap},
+};
+use solar_interface::{
+    BytePos, Span,
+    diagnostics::{Applicability, Diag, DiagMsg},
+};
+use std::ops::ControlFlow;
+
+pub(super) fn check_function<'gcx>(
+    gcx: Gcx<'gcx>,
+    function: &'gcx hir::Function<'gcx>,
+) -> Vec<Diag> {
+    if function.is_yul {
+        return Vec::new();
+    }
+    let mut checker = GasChecker {
+        gcx,
+        written: FxHashSet::default(),
+        copied: FxHashSet::default(),
+        loop_depth: 0,
+        diagnostics: Vec::new(),
+    };
+    if let Some(body) = function.body {
+        for stmt in body.iter() {
+            let _ = checker.visit_stmt(stmt);
+        }
+    }
+    let mut diagnostics = checker.check_parameters(function);
+    diagnostics.append(&mut checker.diagnostics);
+    diagnostics
+}
+
+/// A storage read that is repeated in every iteration of a loop.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum LoopRead {
+    /// The length of a dynamic storage array.
+    Length(VariableId),
+    /// The value of a state variable.
+    Value(VariableId),
+}
+
+struct GasChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The variables that are written in the function, including through their members.
+    written: FxHashSet<VariableId>,
+    /// The variables that are used as a whole, rather than only indexed or accessed.
+    copied: FxHashSet<VariableId>,
+    loop_depth: usize,
+    diagnostics: Vec<Diag>,
+}
+
+impl<'gcx> GasChecker<'gcx> {
+    fn lint(&mut self, code: &'static str, span: Span, msg: impl Into<DiagMsg>) -> &mut Diag {
+        self.diagnostics.push(super::lint(self.gcx, code, span, msg));
+        self.diagnostics.last_mut().unwrap()
+    }
+
+    fn snippet(&self, span: Span) -> Option<String> {
+        self.gcx.sess.source_map().span_to_snippet(span).ok()
+    }
+
+    /// Records a write to the variable that the place `place` is a part of.
+    fn write(&mut self, place: &'gcx hir::Expr<'gcx>) {
+        let place = place.peel_parens();
+        if let ExprKind::Tuple(components) = place.kind {
+            for component in components.iter().flatten() {
+                self.write(component);
+            }
+            return;
+        }
+        let mut root = place;
+        while let ExprKind::Index(base, _) | ExprKind::Slice(base, ..) | ExprKind::Member(base, _) =
+            root.peel_parens().kind
+        {
+            root = base;
+        }
+        if let Some(id) = root.as_variable() {
+            self.written.insert(id);
+        }
+    }
+
+    /// Checks for `memory` parameters of external functions that could be `calldata`.
+    fn check_parameters(&self, function: &'gcx hir::Function<'gcx>) -> Vec<Diag> {
+        if function.visibility != Visibility::External {
+            return Vec::new();
+        }
+        let mut diagnostics = Vec::new();
+        for &id in function.parameters {
+            let var = self.gcx.hir.variable(id);
+            if var.data_location != Some(DataLocation::Memory) || self.written.contains(&id) {
+                continue;
+            }
+            let Some(name) = var.name else { continue };
+            let between = var.ty.span.between(name.span);
+            let Some(offset) = self.snippet(between).and_then(|s| s.find("memory")) else {
+                continue;
+            };
+            let lo = BytePos(between.lo().0 + offset as u32);
+            let location = Span::new(lo, BytePos(lo.0 + "memory".len() as u32));
+            // Passing the parameter around may require it to be in memory.
+            let applicability = if self.copied.contains(&id) {
+                Applicability::MaybeIncorrect
+            } else {
+                Applicability::MachineApplicable
+            };
+            let mut diag = super::lint(
+                self.gcx,
+                "calldata-parameter",
+                var.span,
+                format!("parameter `{name}` is copied to memory but never modified"),
+            );
+            diag.span_suggestion(
+                location,
+                "use `calldata` to read the argument without copying it",
+                "calldata",
+                applicability,
+            );
+            diagnostics.push(diag);
+        }
+        diagnostics
+    }
+
+    /// Checks for `x++` and `x--` whose value is unused.
+    fn check_postfix(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        let ExprKind::Unary(op, operand) = expr.peel_parens().kind else { return };
+        let prefix = match op.kind {
+            UnOpKind::PostInc => "++",
+            UnOpKind::PostDec => "--",
+            _ => return,
+        };
+        let Some(operand) = self.snippet(operand.span) else { return };
+        let msg = format!("postfix `{prefix}` keeps a copy of the previous value, which is unused");
+        self.lint("postfix-increment", expr.span, msg).span_suggestion(
+            expr.span,
+            "use the prefix operator instead",
+            format!("{prefix}{operand}"),
+            Applicability::MachineApplicable,
+        );
+    }
+
+    /// Checks for storage reads that are repeated in every iteration of the loop.
+    fn check_loop(&mut self, loop_span: Span, block: hir::Block<'gcx>) {
+        let mut scanner = LoopScanner {
+            gcx: self.gcx,
+            reads: FxIndexMap::default(),
+            written: FxHashSet::default(),
+            has_assembly: false,
+        };
+        for stmt in block.iter() {
+            let _ = scanner.visit_stmt(stmt);
+        }
+        if scanner.has_assembly {
+            return;
+        }
+        let insert_at = loop_span.shrink_to_lo();
+        for (read, spans) in scanner.reads {
+            let (LoopRead::Length(id) | LoopRead::Value(id)) = read;
+            if scanner.written.contains(&id) {
+                continue;
+            }
+            let name = self.gcx.item_name(id);
+            let (code, msg, local, ty, value) = match read {
+                LoopRead::Length(_) => (
+                    "cache-array-length",
+                    format!("`{name}.length` is read from storage in every loop iteration"),
+                    format!("{name}Length"),
+                    "uint256".to_string(),
+                    format!("{name}.length"),
+                ),
+                LoopRead::Value(_) => (
+                    "cache-storage-read",
+                    format!("state variable `{name}` is read from storage in every loop iteration"),
+                    format!("{name}Cached"),
+                    self.gcx.type_of_item(id.into()).display(self.gcx).to_string(),
+                    name.to_string(),
+                ),
+            };
+            let mut substitutions = vec![(insert_at, format!("{ty} {local} = {value}; ").into())];
+            substitutions.extend(spans.iter().map(|&span| (span, local.clone().into())));
+            // Functions called in the loop may still modify the variable.
+            self.lint(code, spans[0], msg).multipart_suggestion(
+                "read it once before the loop",
+                substitutions,
+                Applicability::MaybeIncorrect,
+            );
+        }
+    }
+}
+
+impl<'gcx> Visit<'gcx> for GasChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match stmt.kind {
+            StmtKind::Expr(expr) => self.check_postfix(expr),
+            StmtKind::Loop(block, _) => {
+                // Nested loops are covered by the outermost one.
+                if self.loop_depth == 0 {
+                    self.check_loop(stmt.span, block);
+                }
+                self.loop_depth += 1;
+                let flow = self.walk_stmt(stmt);
+                self.loop_depth -= 1;
+                return flow;
+            }
+            StmtKind::AssemblyBlock(_) => return ControlFlow::Continue(()),
+            _ => {}
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            ExprKind::Assign(lhs, _, _) | ExprKind::Delete(lhs) => self.write(lhs),
+            ExprKind::Unary(op, operand) if op.kind.has_side_effects() => self.write(operand),
+            // Only the accessed part of a variable is read, so the variable itself is skipped.
+            ExprKind::Index(base, index) if base.as_variable().is_some() => {
+                if let Some(index) = index {
+                    self.visit_expr(index)?;
+                }
+                return ControlFlow::Continue(());
+            }
+            ExprKind::Slice(base, start, end) if base.as_variable().is_some() => {
+                for expr in start.into_iter().chain(end) {
+                    self.visit_expr(expr)?;
+                }
+                return ControlFlow::Continue(());
+            }
+            ExprKind::Member(base, _) if base.as_variable().is_some() => {
+                return ControlFlow::Continue(());
+            }
+            ExprKind::Ident(_) => {
+                if let Some(id) = expr.as_variable() {
+                    self.copied.insert(id);
+                }
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
+/// Collects the storage reads and the state variable writes in a loop.
+struct LoopScanner<'gcx> {
+    gcx: Gcx<'gcx>,
+    reads: FxIndexMap<LoopRead, Vec<Span>>,
+    written: FxHashSet<VariableId>,
+    has_assembly: bool,
+}
+
+impl<'gcx> LoopScanner<'gcx> {
+    fn state_variable(&self, expr: &hir::Expr<'_>) -> Option<VariableId> {
+        let id = expr.as_variable()?;
+        let var = self.gcx.hir.variable(id);
+        (var.is_state_variable() && !var.is_constant() && !var.is_immutable()).then_some(id)
+    }
+}
+
+impl<'gcx> Visit<'gcx> for LoopScanner<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let StmtKind::AssemblyBlock(_) = stmt.kind {
+            self.has_assembly = true;
+            return ControlFlow::Continue(());
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            ExprKind::Assign(place, ..) | ExprKind::Delete(place) => {
+                self.written.extend(super::state_variable_root(self.gcx, place));
+            }
+            ExprKind::Unary(op, place) if op.kind.has_side_effects() => {
+                self.written.extend(super::state_variable_root(self.gcx, place));
+            }
+            ExprKind::Call(callee, ..) => {
+                if let ExprKind::Member(base, member) = callee.peel_parens().kind
+                    && matches!(member.as_str(), "push" | "pop")
+                {
+                    self.written.extend(super::state_variable_root(self.gcx, base));
+                }
+            }
+            ExprKind::Member(base, member) if member.as_str() == "length" => {
+                if let Some(id) = self.state_variable(base)
+                    && let Some(ty) = self.gcx.type_of_expr(base.id)
+                    && ty.loc() == Some(DataLocation::Storage)
+                    && matches!(ty.peel_refs().kind, TyKind::DynArray(_))
+                {
+                    self.reads.entry(LoopRead::Length(id)).or_default().push(expr.span);
+                    return ControlFlow::Continue(());
+                }
+            }
+            ExprKind::Ident(_) => {
+                if let Some(id) = self.state_variable(expr)
+                    && self.gcx.type_of_item(id.into()).is_value_type()
+                {
+                    self.reads.entry(LoopRead::Value(id)).or_default().push(expr.span);
+                }
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
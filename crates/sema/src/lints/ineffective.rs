@@ -0,0 +1,176 @@
+//! Lints for statements and expressions that have no effect or an unintended one.
+
+use crate::{
+    hir::{self, ExprKind, StmtKind, Visit},
+    ty::{Gcx, TyKind},
+};
+use solar_ast::{BinOpKind, LitKind};
+use solar_data_structures::Never;
+use solar_interface::{
+    Span,
+    diagnostics::{Diag, DiagMsg},
+};
+use std::ops::ControlFlow;
+
+pub(super) fn check_function<'gcx>(
+    gcx: Gcx<'gcx>,
+    function: &'gcx hir::Function<'gcx>,
+) -> Vec<Diag> {
+    let mut checker = IneffectiveChecker { gcx, function, in_else: false, diagnostics: Vec::new() };
+    let _ = checker.visit_function(function);
+    checker.diagnostics
+}
+
+struct IneffectiveChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    function: &'gcx hir::Function<'gcx>,
+    /// Whether the next visited statement is the `else` branch of an `if` statement.
+    in_else: bool,
+    diagnostics: Vec<Diag>,
+}
+
+impl<'gcx> IneffectiveChecker<'gcx> {
+    fn lint(&mut self, code: &'static str, span: Span, msg: impl Into<DiagMsg>) -> &mut Diag {
+        self.diagnostics.push(super::lint(self.gcx, code, span, msg));
+        self.diagnostics.last_mut().unwrap()
+    }
+
+    /// Checks the conditions of an `if`-`else if` chain starting at `stmt` for duplicates.
+    fn check_if_chain(&mut self, stmt: &'gcx hir::Stmt<'gcx>) {
+        let mut conditions = Vec::<&hir::Expr<'_>>::new();
+        let mut next = Some(stmt);
+        while let Some(stmt) = next
+            && let StmtKind::If(cond, _, else_) = stmt.kind
+        {
+            if let Some(prev) = conditions.iter().find(|prev| is_same_pure_expr(prev, cond)) {
+                let prev = prev.span;
+                self.lint(
+                    "duplicate-condition",
+                    cond.span,
+                    "duplicate condition in `if`-`else if` chain",
+                )
+                .span_note(prev, "the same condition is checked here first")
+                .note("this branch is never executed");
+            }
+            conditions.push(cond);
+            next = else_;
+        }
+    }
+
+    fn is_udvt(&self, expr: &hir::Expr<'_>) -> bool {
+        self.gcx.type_of_expr(expr.id).is_some_and(|ty| matches!(ty.kind, TyKind::Udvt(..)))
+    }
+}
+
+impl<'gcx> Visit<'gcx> for IneffectiveChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        let in_else = std::mem::take(&mut self.in_else);
+        match stmt.kind {
+            StmtKind::If(cond, then, else_) => {
+                // `else if` statements are checked as part of the chain they belong to.
+                if !in_else {
+                    self.check_if_chain(stmt);
+                }
+                self.visit_expr(cond)?;
+                self.visit_stmt(then)?;
+                if let Some(else_) = else_ {
+                    self.in_else = true;
+                    self.visit_stmt(else_)?;
+                }
+                return ControlFlow::Continue(());
+            }
+            StmtKind::Expr(expr) => {
+                // Comparisons of user-defined value types may call a user-defined operator.
+                if let ExprKind::Binary(lhs, op, _) = expr.peel_parens().kind
+                    && op.kind.is_cmp()
+                    && !self.is_udvt(lhs)
+                {
+                    let diag =
+                        self.lint("unused-comparison", expr.span, "comparison has no effect");
+                    if op.kind == BinOpKind::Eq {
+                        diag.help("if you meant to assign a value, use `=` instead");
+                    }
+                }
+            }
+            StmtKind::Return(_) if self.function.is_constructor() => {
+                self.lint("return-in-constructor", stmt.span, "`return` in a constructor")
+                    .note("the rest of the constructor body is skipped");
+            }
+            _ => {}
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            ExprKind::Assign(lhs, None, rhs) if is_same_pure_expr(lhs, rhs) => {
+                self.lint(
+                    "self-assignment",
+                    expr.span,
+                    "assignment of a value to itself has no effect",
+                );
+            }
+            ExprKind::Binary(lhs, op, rhs) if matches!(op.kind, BinOpKind::Eq | BinOpKind::Ne) => {
+                if let Some(value) = bool_lit(rhs).or_else(|| bool_lit(lhs)) {
+                    let help = if value == (op.kind == BinOpKind::Eq) {
+                        "use the boolean expression directly"
+                    } else {
+                        "negate the boolean expression with `!` instead"
+                    };
+                    self.lint(
+                        "bool-literal-comparison",
+                        expr.span,
+                        "comparison with a boolean literal",
+                    )
+                    .help(help);
+                }
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
+/// Returns the value of `expr` if it is a boolean literal.
+fn bool_lit(expr: &hir::Expr<'_>) -> Option<bool> {
+    match expr.peel_parens().kind {
+        ExprKind::Lit(hir::Lit { kind: LitKind::Bool(value), .. }) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Returns `true` if `a` and `b` are the same expression without side effects, ignoring
+/// parentheses.
+fn is_same_pure_expr(a: &hir::Expr<'_>, b: &hir::Expr<'_>) -> bool {
+    match (&a.peel_parens().kind, &b.peel_parens().kind) {
+        (ExprKind::Ident(a), ExprKind::Ident(b)) => a == b,
+        (ExprKind::Lit(a), ExprKind::Lit(b)) => match (a.kind, b.kind) {
+            (LitKind::Str(a_kind, a, _), LitKind::Str(b_kind, b, _)) => a_kind == b_kind && a == b,
+            (LitKind::Number(a), LitKind::Number(b)) => a == b,
+            (LitKind::Address(a), LitKind::Address(b)) => a == b,
+            (LitKind::Bool(a), LitKind::Bool(b)) => a == b,
+            _ => false,
+        },
+        (ExprKind::Member(a, a_member), ExprKind::Member(b, b_member)) => {
+            a_member.name == b_member.name && is_same_pure_expr(a, b)
+        }
+        (ExprKind::Index(a, Some(a_index)), ExprKind::Index(b, Some(b_index))) => {
+            is_same_pure_expr(a, b) && is_same_pure_expr(a_index, b_index)
+        }
+        (ExprKind::Unary(a_op, a), ExprKind::Unary(b_op, b)) => {
+            a_op.kind == b_op.kind && !a_op.kind.has_side_effects() && is_same_pure_expr(a, b)
+        }
+        (ExprKind::Binary(a_lhs, a_op, a_rhs), ExprKind::Binary(b_lhs, b_op, b_rhs)) => {
+            a_op.kind == b_op.kind
+                && is_same_pure_expr(a_lhs, b_lhs)
+                && is_same_pure_expr(a_rhs, b_rhs)
+        }
+        _ => false,
+    }
+}
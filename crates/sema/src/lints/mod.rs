@@ -0,0 +1,116 @@
+//! Analysis lints.
+//!
+//! Lints report code that compiles but is likely unintended, or that does not follow common style
+//! conventions. They are only run with `-Zlints`, and are configured with the `[lints]` table of the
+//! configuration file. Each one has a named diagnostic code that can be passed to `--allow`, or to
+//! `-Zdeny-lints` to report it as an error instead.
+
+use crate::{
+    hir::{self, ExprKind, VariableId},
+    ty::Gcx,
+};
+use rayon::prelude::*;
+use solar_interface::{
+    Span,
+    config::LintOpts,
+    diagnostics::{Diag, DiagId, DiagMsg, Level},
+};
+use std::sync::LazyLock;
+
+mod gas;
+mod ineffective;
+mod reentrancy;
+mod storage;
+mod style;
+mod transfer;
+
+pub(crate) fn check(gcx: Gcx<'_>) {
+    let opts = &gcx.sess.opts;
+    if !opts.unstable.lints || gcx.dcx().has_errors().is_err() {
+        return;
+    }
+    let diagnostics = gcx
+        .hir
+        .par_functions()
+        .filter(|function| !function.is_getter() && gcx.is_source_selected(function.source))
+        .map(|function| {
+            let mut diagnostics = ineffective::check_function(gcx, function);
+            diagnostics.extend(storage::check_function(gcx, function));
+            diagnostics.extend(gas::check_function(gcx, function));
+            diagnostics.extend(transfer::check_function(gcx, function));
+            diagnostics
+        })
+        .collect::<Vec<_>>();
+    let reentrancy = reentrancy::check(gcx);
+    let style = style::check(gcx);
+    let lint_opts = lint_opts(gcx);
+    for diagnostic in diagnostics.into_iter().flatten().chain(reentrancy).chain(style) {
+        if diagnostic.id().is_some_and(|code| lint_opts.is_allowed(code)) {
+            continue;
+        }
+        let _ = gcx.dcx().emit_diagnostic(diagnostic);
+    }
+}
+
+/// Returns the configured lint options.
+fn lint_opts(gcx: Gcx<'_>) -> &LintOpts {
+    static DEFAULT: LazyLock<LintOpts> = LazyLock::new(LintOpts::default);
+    gcx.sess.opts.lints.as_ref().unwrap_or(&DEFAULT)
+}
+
+/// Creates a lint diagnostic with the given code, at the level configured with `-Zdeny-lints` or
+/// the `[lints]` table.
+fn lint(gcx: Gcx<'_>, code: &'static str, span: Span, msg: impl Into<DiagMsg>) -> Diag {
+    let denied = gcx.sess.opts.unstable.deny_lints.iter().any(|denied| denied == code)
+        || lint_opts(gcx).is_denied(code);
+    let level = if denied { Level::Error } else { Level::Warning };
+    let mut diag = Diag::new(level, msg);
+    diag.code(DiagId::new_str(code)).span(span);
+    diag
+}
+
+/// Returns the mutable state variable that the place `expr` is a part of.
+fn state_variable_root(gcx: Gcx<'_>, mut expr: &hir::Expr<'_>) -> Option<VariableId> {
+    loop {
+        expr = expr.peel_parens();
+        match expr.kind {
+            ExprKind::Index(base, _) | ExprKind::Slice(base, ..) | ExprKind::Member(base, _) => {
+                expr = base;
+            }
+            _ => break,
+        }
+    }
+    let id = expr.as_variable()?;
+    let var = gcx.hir.variable(id);
+    (var.is_state_variable() && !var.is_constant() && !var.is_immutable()).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analyze_source,
+        interface::config::{CompileOpts, LintOpts},
+    };
+
+    /// Line lengths and allowed numbers can only be configured in a configuration file, so they
+    /// are not covered by the UI tests in `tests/ui/lints`.
+    #[test]
+    fn configured_style() {
+        let src =
+            "contract C {\n    function f() external pure returns (uint256) { return 60; }\n}\n";
+        let lint_opts =
+            LintOpts { max_line_length: 40, allowed_numbers: vec![60], ..Default::default() };
+        let mut opts = CompileOpts { lints: Some(lint_opts), ..Default::default() };
+        opts.unstable.lints = true;
+        let unit = analyze_source("C.sol".to_string(), src, opts).unwrap();
+        let ids = unit.diagnostics().iter().filter_map(|diag| diag.id()).collect::<Vec<_>>();
+        assert_eq!(ids, ["max-line-length"]);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let src = "contract C { function f(uint a) external pure { a = a; } }";
+        let unit = analyze_source("C.sol".to_string(), src, CompileOpts::default()).unwrap();
+        assert!(unit.diagnostics().iter().all(|diag| diag.id() != Some("self-assignment")));
+    }
+}
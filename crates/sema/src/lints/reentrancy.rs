@@ -0,0 +1,282 @@
+//! Heuristic reentrancy lint.
+//!
+//! Reports state variables that are written after an external call in the same function, which
+//! violates the checks-effects-interactions pattern. Internal calls are followed through
+//! per-function summaries of the call graph, recording whether the callee may make an external call
+//! and which state variable it may write. Inline assembly is not analyzed.
+
+use crate::{
+    builtins::Builtin,
+    hir::{self, ExprKind, FunctionId, StmtKind, VariableId, Visit},
+    ty::{Gcx, TyFnKind, TyKind},
+};
+use solar_ast::StateMutability;
+use solar_data_structures::{Never, map::FxHashMap};
+use solar_interface::{Span, diagnostics::Diag};
+use std::ops::ControlFlow;
+
+pub(super) fn check(gcx: Gcx<'_>) -> Vec<Diag> {
+    let mut cx =
+        ReentrancyCx { gcx, summaries: FxHashMap::default(), violations: FxHashMap::default() };
+    let mut diagnostics = Vec::new();
+    for id in gcx.hir.function_ids() {
+        let function = gcx.hir.function(id);
+        if !gcx.is_source_selected(function.source) || !can_reenter(gcx, function) {
+            continue;
+        }
+        cx.summary(id);
+        let violations = cx.violations.remove(&id).unwrap_or_default();
+        diagnostics.extend(violations.iter().map(|violation| violation.to_diag(gcx)));
+    }
+    diagnostics
+}
+
+/// Returns `true` if the function may be reentered while one of its external calls is running.
+fn can_reenter(gcx: Gcx<'_>, function: &hir::Function<'_>) -> bool {
+    !function.kind.is_modifier()
+        && !function.is_constructor()
+        && !function.is_getter()
+        && !function.is_yul
+        && function.mutates_state()
+        && !function.modifiers.iter().any(|modifier| {
+            gcx.item_name_opt(modifier.id)
+                .is_some_and(|name| name.as_str().to_lowercase().contains("reentran"))
+        })
+}
+
+/// The effects of calling a function that are relevant for reentrancy.
+#[derive(Clone, Copy, Default)]
+struct Summary {
+    /// Whether the function may make an external call.
+    call: bool,
+    /// The first state variable that the function may write.
+    write: Option<VariableId>,
+}
+
+/// An external call, made either directly or by the given internal function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Call {
+    span: Span,
+    via: Option<FunctionId>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Violation {
+    call: Call,
+    write: Span,
+    var: VariableId,
+    /// The internal function that writes `var`, if it is not written directly.
+    write_via: Option<FunctionId>,
+}
+
+impl Violation {
+    fn to_diag(&self, gcx: Gcx<'_>) -> Diag {
+        let var = gcx.item_name(self.var);
+        let msg = match self.write_via {
+            None => format!("state variable `{var}` is written after an external call"),
+            Some(id) => format!(
+                "state variable `{var}` may be written by `{}` after an external call",
+                gcx.item_name(id)
+            ),
+        };
+        let note = match self.call.via {
+            None => "the external call is made here".to_string(),
+            Some(id) => format!("an external call may be made by `{}` here", gcx.item_name(id)),
+        };
+        let mut diag = super::lint(gcx, "reentrancy", self.write, msg);
+        diag.span_note(self.call.span, note)
+            .help("update the state before making external calls, or add a reentrancy guard");
+        diag
+    }
+}
+
+struct ReentrancyCx<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The computed function summaries. `None` while the function is being analyzed.
+    summaries: FxHashMap<FunctionId, Option<Summary>>,
+    /// The violations found in the analyzed functions, which have not been reported yet.
+    violations: FxHashMap<FunctionId, Vec<Violation>>,
+}
+
+impl<'gcx> ReentrancyCx<'gcx> {
+    /// Returns the summary of the given function, analyzing it if it has not been analyzed yet.
+    fn summary(&mut self, id: FunctionId) -> Summary {
+        match self.summaries.get(&id) {
+            Some(Some(summary)) => return *summary,
+            // Recursive call.
+            Some(None) => return Summary::default(),
+            None => {}
+        }
+        self.summaries.insert(id, None);
+        let function = self.gcx.hir.function(id);
+        let mut walker = FunctionWalker {
+            cx: self,
+            call: None,
+            summary: Summary::default(),
+            violations: Vec::new(),
+        };
+        if let Some(body) = function.body {
+            for stmt in body.iter() {
+                let _ = walker.visit_stmt(stmt);
+            }
+        }
+        let FunctionWalker { summary, violations, .. } = walker;
+        self.summaries.insert(id, Some(summary));
+        if !violations.is_empty() {
+            self.violations.insert(id, violations);
+        }
+        summary
+    }
+}
+
+/// Walks a function body in evaluation order, tracking whether an external call may have been made.
+struct FunctionWalker<'a, 'gcx> {
+    cx: &'a mut ReentrancyCx<'gcx>,
+    /// The first external call that may have been made before the current point.
+    call: Option<Call>,
+    summary: Summary,
+    violations: Vec<Violation>,
+}
+
+impl<'gcx> FunctionWalker<'_, 'gcx> {
+    fn record_call(&mut self, span: Span, via: Option<FunctionId>) {
+        self.summary.call = true;
+        self.call.get_or_insert(Call { span, via });
+    }
+
+    fn record_write(&mut self, span: Span, var: VariableId, write_via: Option<FunctionId>) {
+        self.summary.write.get_or_insert(var);
+        let Some(call) = self.call else { return };
+        let violation = Violation { call, write: span, var, write_via };
+        // Loop bodies are walked twice.
+        if !self.violations.contains(&violation) {
+            self.violations.push(violation);
+        }
+    }
+
+    /// Records a write to the place `expr`, if it is rooted in a state variable.
+    fn write_place(&mut self, expr: &'gcx hir::Expr<'gcx>, span: Span) {
+        let expr = expr.peel_parens();
+        if let ExprKind::Tuple(exprs) = expr.kind {
+            for expr in exprs.iter().flatten() {
+                self.write_place(expr, expr.span);
+            }
+        } else if let Some(var) = super::state_variable_root(self.cx.gcx, expr) {
+            self.record_write(span, var, None);
+        }
+    }
+
+    fn visit_call(&mut self, expr: &'gcx hir::Expr<'gcx>, callee: &'gcx hir::Expr<'gcx>) {
+        let gcx = self.cx.gcx;
+        if let Some(builtin) = gcx.builtin_callee(callee.id) {
+            if matches!(builtin, Builtin::ArrayPush | Builtin::ArrayPush0 | Builtin::ArrayPop)
+                && let ExprKind::Member(base, _) = callee.peel_parens().kind
+            {
+                self.write_place(base, expr.span);
+            }
+            return;
+        }
+        let Some(TyKind::Fn(f)) = gcx.type_of_expr(callee.id).map(|ty| ty.kind) else { return };
+        match f.kind {
+            TyFnKind::External | TyFnKind::BareCall | TyFnKind::BareDelegateCall
+                if !matches!(f.state_mutability, StateMutability::Pure | StateMutability::View) =>
+            {
+                self.record_call(expr.span, None);
+            }
+            TyFnKind::Internal | TyFnKind::InternalWithSelector => {
+                let Some(id) = f.function_id else { return };
+                let summary = self.cx.summary(id);
+                // The callee's writes may happen before its calls, so they are recorded first.
+                if let Some(var) = summary.write {
+                    self.record_write(expr.span, var, Some(id));
+                }
+                if summary.call {
+                    self.record_call(expr.span, Some(id));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'gcx> Visit<'gcx> for FunctionWalker<'_, 'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.cx.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match stmt.kind {
+            StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond)?;
+                let before = self.call;
+                self.visit_stmt(then)?;
+                let after_then = std::mem::replace(&mut self.call, before);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_)?;
+                }
+                self.call = self.call.or(after_then);
+                ControlFlow::Continue(())
+            }
+            StmtKind::Loop(block, _) => {
+                // Walk the body twice so that writes at the start of the next iteration are seen
+                // after the calls at the end of the previous one.
+                for _ in 0..2 {
+                    for stmt in block.iter() {
+                        self.visit_stmt(stmt)?;
+                    }
+                }
+                ControlFlow::Continue(())
+            }
+            StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr)?;
+                let before = self.call;
+                let mut after = None;
+                for clause in try_.clauses {
+                    self.call = before;
+                    for stmt in clause.block.iter() {
+                        self.visit_stmt(stmt)?;
+                    }
+                    after = after.or(self.call);
+                }
+                self.call = after;
+                ControlFlow::Continue(())
+            }
+            StmtKind::AssemblyBlock(_) => ControlFlow::Continue(()),
+            _ => self.walk_stmt(stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            ExprKind::Assign(lhs, _, rhs) => {
+                self.visit_expr(rhs)?;
+                self.visit_expr(lhs)?;
+                self.write_place(lhs, expr.span);
+            }
+            ExprKind::Unary(op, operand) if op.kind.has_side_effects() => {
+                self.visit_expr(operand)?;
+                self.write_place(operand, expr.span);
+            }
+            ExprKind::Delete(operand) => {
+                self.visit_expr(operand)?;
+                self.write_place(operand, expr.span);
+            }
+            ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond)?;
+                let before = self.call;
+                self.visit_expr(then)?;
+                let after_then = std::mem::replace(&mut self.call, before);
+                self.visit_expr(else_)?;
+                self.call = self.call.or(after_then);
+            }
+            ExprKind::Call(callee, ..) => {
+                self.walk_expr(expr)?;
+                self.visit_call(expr, callee);
+            }
+            _ => return self.walk_expr(expr),
+        }
+        ControlFlow::Continue(())
+    }
+}
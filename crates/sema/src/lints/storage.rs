@@ -0,0 +1,228 @@
+//! Lints for storage pointers, deletions, and copies between storage and memory.
+
+use crate::{
+    hir::{self, ExprKind, StmtKind, VariableId, Visit},
+    ty::{Gcx, Ty, TyKind},
+};
+use solar_ast::DataLocation;
+use solar_data_structures::{Never, map::FxHashSet};
+use solar_interface::{
+    Span,
+    diagnostics::{Diag, DiagMsg},
+};
+use std::ops::ControlFlow;
+
+pub(super) fn check_function<'gcx>(
+    gcx: Gcx<'gcx>,
+    function: &'gcx hir::Function<'gcx>,
+) -> Vec<Diag> {
+    let mut checker = StorageChecker {
+        gcx,
+        pointers: FxHashSet::default(),
+        assigned: Some(FxHashSet::default()),
+        reported: FxHashSet::default(),
+        loop_depth: 0,
+        in_assembly: false,
+        diagnostics: Vec::new(),
+    };
+    let _ = checker.visit_function(function);
+    checker.diagnostics
+}
+
+struct StorageChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The local storage pointers that were declared without an initializer.
+    pointers: FxHashSet<VariableId>,
+    /// The storage pointers that are assigned on every path to the current point, or `None` if
+    /// the current point is unreachable.
+    assigned: Option<FxHashSet<VariableId>>,
+    /// The storage pointers that were already reported as used before being assigned.
+    reported: FxHashSet<VariableId>,
+    loop_depth: usize,
+    in_assembly: bool,
+    diagnostics: Vec<Diag>,
+}
+
+impl<'gcx> StorageChecker<'gcx> {
+    fn lint(&mut self, code: &'static str, span: Span, msg: impl Into<DiagMsg>) -> &mut Diag {
+        self.diagnostics.push(super::lint(self.gcx, code, span, msg));
+        self.diagnostics.last_mut().unwrap()
+    }
+
+    fn assign(&mut self, id: VariableId) {
+        if let Some(assigned) = &mut self.assigned {
+            assigned.insert(id);
+        }
+    }
+
+    /// Merges the assigned storage pointers of the current path with the ones of `other`.
+    fn merge(&mut self, other: Option<FxHashSet<VariableId>>) {
+        self.assigned = match (self.assigned.take(), other) {
+            (None, other) | (other, None) => other,
+            (Some(a), Some(b)) => Some(a.intersection(&b).copied().collect()),
+        };
+    }
+
+    fn visit_place(&mut self, place: &'gcx hir::Expr<'gcx>) -> ControlFlow<Never> {
+        let place = place.peel_parens();
+        if let ExprKind::Tuple(components) = place.kind {
+            for component in components.iter().flatten() {
+                self.visit_place(component)?;
+            }
+        } else if let Some(id) = place.as_variable() {
+            self.assign(id);
+        } else {
+            self.visit_expr(place)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn check_read(&mut self, expr: &'gcx hir::Expr<'gcx>, id: VariableId) {
+        if !self.pointers.contains(&id)
+            || self.assigned.as_ref().is_none_or(|assigned| assigned.contains(&id))
+            || !self.reported.insert(id)
+        {
+            return;
+        }
+        let var = self.gcx.hir.variable(id);
+        let name = self.gcx.item_name(id);
+        let decl = var.span;
+        self.lint(
+            "uninitialized-storage-pointer",
+            expr.span,
+            format!("storage pointer `{name}` may be used before it is assigned"),
+        )
+        .span_note(decl, "declared here without an initializer")
+        .help("assign a storage reference to the variable before using it");
+    }
+
+    /// Checks for a storage array that is copied to memory inside of a loop.
+    fn check_copy(&mut self, target: Ty<'gcx>, value: &'gcx hir::Expr<'gcx>) {
+        if self.loop_depth == 0
+            || target.loc() != Some(DataLocation::Memory)
+            || !matches!(target.peel_refs().kind, TyKind::Array(..) | TyKind::DynArray(_))
+            || self.gcx.type_of_expr(value.id).and_then(|ty| ty.loc())
+                != Some(DataLocation::Storage)
+        {
+            return;
+        }
+        self.lint(
+            "storage-copy-in-loop",
+            value.span,
+            "storage array is copied to memory in every loop iteration",
+        )
+        .help("copy the array once before the loop, or use a `storage` reference instead");
+    }
+}
+
+impl<'gcx> Visit<'gcx> for StorageChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match stmt.kind {
+            StmtKind::DeclSingle(id) => {
+                let var = self.gcx.hir.variable(id);
+                if let Some(init) = var.initializer {
+                    self.visit_expr(init)?;
+                    self.check_copy(self.gcx.type_of_item(id.into()), init);
+                } else if var.data_location == Some(DataLocation::Storage) {
+                    self.pointers.insert(id);
+                }
+            }
+            StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond)?;
+                let before = self.assigned.clone();
+                self.visit_stmt(then)?;
+                let after_then = std::mem::replace(&mut self.assigned, before);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_)?;
+                }
+                self.merge(after_then);
+            }
+            StmtKind::Loop(block, _) => {
+                // The loop body may not be executed at all.
+                let before = self.assigned.clone();
+                self.loop_depth += 1;
+                for stmt in block.iter() {
+                    self.visit_stmt(stmt)?;
+                }
+                self.loop_depth -= 1;
+                self.assigned = before;
+            }
+            StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr)?;
+                let before = std::mem::take(&mut self.assigned);
+                for clause in try_.clauses {
+                    let after_previous = std::mem::replace(&mut self.assigned, before.clone());
+                    for stmt in clause.block.iter() {
+                        self.visit_stmt(stmt)?;
+                    }
+                    self.merge(after_previous);
+                }
+            }
+            StmtKind::AssemblyBlock(_) => {
+                // Storage pointers used in inline assembly are assumed to be assigned there.
+                self.in_assembly = true;
+                self.walk_stmt(stmt)?;
+                self.in_assembly = false;
+            }
+            StmtKind::Return(_) | StmtKind::Revert(_) | StmtKind::Break | StmtKind::Continue => {
+                self.walk_stmt(stmt)?;
+                self.assigned = None;
+            }
+            _ => return self.walk_stmt(stmt),
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            ExprKind::Ident(_) => {
+                if let Some(id) = expr.as_variable() {
+                    if self.in_assembly {
+                        self.assign(id);
+                    } else {
+                        self.check_read(expr, id);
+                    }
+                }
+            }
+            ExprKind::Assign(lhs, None, rhs) if !self.in_assembly => {
+                self.visit_expr(rhs)?;
+                self.visit_place(lhs)?;
+                if let Some(target) = self.gcx.type_of_expr(lhs.id) {
+                    self.check_copy(target, rhs);
+                }
+            }
+            ExprKind::Ternary(cond, then, else_) => {
+                self.visit_expr(cond)?;
+                let before = self.assigned.clone();
+                self.visit_expr(then)?;
+                let after_then = std::mem::replace(&mut self.assigned, before);
+                self.visit_expr(else_)?;
+                self.merge(after_then);
+            }
+            ExprKind::Delete(operand) => {
+                self.visit_expr(operand)?;
+                let ty = self.gcx.type_of_expr(operand.id).map(Ty::peel_refs);
+                if let Some(ty) = ty
+                    && !matches!(ty.kind, TyKind::Mapping(..))
+                    && ty.has_mapping(self.gcx)
+                {
+                    self.lint(
+                        "partial-delete",
+                        expr.span,
+                        "`delete` does not clear the mappings contained in the deleted value",
+                    )
+                    .note("the mapping entries are left in storage")
+                    .help("delete the mapping entries individually before deleting the value");
+                }
+            }
+            _ => return self.walk_expr(expr),
+        }
+        ControlFlow::Continue(())
+    }
+}
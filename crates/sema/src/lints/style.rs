@@ -0,0 +1,225 @@
+//! Style lints for naming conventions, line length, visibility, and magic numbers.
+//!
+//! These follow the most commonly used rules of [solhint](https://protofire.github.io/solhint/).
+
+use super::lint_opts;
+use crate::{
+    hir::{self, ExprKind, StmtKind, Visit},
+    ty::Gcx,
+};
+use alloy_primitives::U256;
+use solar_ast::LitKind;
+use solar_data_structures::Never;
+use solar_interface::{BytePos, Ident, Span, diagnostics::Diag};
+use std::ops::ControlFlow;
+
+pub(super) fn check(gcx: Gcx<'_>) -> Vec<Diag> {
+    let mut diagnostics = Vec::new();
+    check_line_lengths(gcx, &mut diagnostics);
+    check_names(gcx, &mut diagnostics);
+    check_state_visibility(gcx, &mut diagnostics);
+    for function in gcx.hir.functions() {
+        if function.is_yul || !gcx.is_source_selected(function.source) {
+            continue;
+        }
+        let mut checker = MagicNumberChecker { gcx, diagnostics: &mut diagnostics };
+        let _ = checker.visit_function(function);
+    }
+    diagnostics
+}
+
+fn check_line_lengths(gcx: Gcx<'_>, diagnostics: &mut Vec<Diag>) {
+    let max = lint_opts(gcx).max_line_length;
+    for (_, source) in gcx.hir.sources_enumerated().filter(|&(id, _)| gcx.is_source_selected(id)) {
+        let file = &source.file;
+        let mut offset = 0;
+        for line in file.src.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+            let Some((excess, _)) = line.char_indices().nth(max) else { continue };
+            let lo = file.start_pos + BytePos::from_usize(start + excess);
+            let hi = file.start_pos + BytePos::from_usize(start + line.len());
+            let msg = format!("line is longer than {max} characters");
+            diagnostics.push(super::lint(gcx, "max-line-length", Span::new(lo, hi), msg));
+        }
+    }
+}
+
+fn check_names(gcx: Gcx<'_>, diagnostics: &mut Vec<Diag>) {
+    let mut check = |code, kind, name: Ident, valid: fn(&str) -> bool, fix: fn(&str) -> String| {
+        if valid(name.as_str()) {
+            return;
+        }
+        let msg = format!("{kind} name `{name}` does not follow the naming convention");
+        let mut diag = super::lint(gcx, code, name.span, msg);
+        diag.help(format!("rename it to `{}`", fix(name.as_str())));
+        diagnostics.push(diag);
+    };
+
+    let selected = |source| gcx.is_source_selected(source);
+    let cap_words = "contract-name-capwords";
+    for contract in gcx.hir.contracts().filter(|contract| selected(contract.source)) {
+        check(cap_words, contract.kind.to_str(), contract.name, is_cap_words, to_cap_words);
+    }
+    for strukt in gcx.hir.strukts().filter(|strukt| selected(strukt.source)) {
+        check(cap_words, "struct", strukt.name, is_cap_words, to_cap_words);
+    }
+    for enumm in gcx.hir.enumms().filter(|enumm| selected(enumm.source)) {
+        check(cap_words, "enum", enumm.name, is_cap_words, to_cap_words);
+    }
+    for error in gcx.hir.errors().filter(|error| selected(error.source)) {
+        check(cap_words, "error", error.name, is_cap_words, to_cap_words);
+    }
+    for event in gcx.hir.events().filter(|event| selected(event.source)) {
+        check("event-name-capwords", "event", event.name, is_cap_words, to_cap_words);
+    }
+    for function in gcx.hir.functions() {
+        if function.is_yul || function.is_getter() || !selected(function.source) {
+            continue;
+        }
+        let Some(name) = function.name else { continue };
+        let code = if function.kind.is_modifier() {
+            "modifier-name-mixedcase"
+        } else {
+            "func-name-mixedcase"
+        };
+        check(code, function.kind.to_str(), name, is_mixed_case, to_mixed_case);
+    }
+    for var in gcx.hir.variables() {
+        if !var.is_constant() || !selected(var.source) {
+            continue;
+        }
+        let Some(name) = var.name else { continue };
+        check("const-name-snakecase", "constant", name, is_upper_snake_case, to_upper_snake_case);
+    }
+}
+
+fn check_state_visibility(gcx: Gcx<'_>, diagnostics: &mut Vec<Diag>) {
+    for var in gcx.hir.variables() {
+        if !var.is_state_variable()
+            || var.visibility.is_some()
+            || !gcx.is_source_selected(var.source)
+        {
+            continue;
+        }
+        let Some(name) = var.name else { continue };
+        let msg = format!("state variable `{name}` has no explicit visibility");
+        let mut diag = super::lint(gcx, "state-visibility", var.span, msg);
+        diag.help("declare it as `internal`, `private`, or `public`");
+        diagnostics.push(diag);
+    }
+}
+
+struct MagicNumberChecker<'a, 'gcx> {
+    gcx: Gcx<'gcx>,
+    diagnostics: &'a mut Vec<Diag>,
+}
+
+impl<'gcx> Visit<'gcx> for MagicNumberChecker<'_, 'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_ty(&mut self, _ty: &'gcx hir::Type<'gcx>) -> ControlFlow<Self::BreakValue> {
+        // Array lengths are not magic.
+        ControlFlow::Continue(())
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let StmtKind::AssemblyBlock(_) = stmt.kind {
+            return ControlFlow::Continue(());
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        // Numbers with a subdenomination, such as `30 days`, are not magic.
+        if let ExprKind::Lit(lit) = expr.kind
+            && let LitKind::Number(value) = lit.kind
+            && lit.span == expr.span
+            && !lint_opts(self.gcx).allowed_numbers.iter().any(|&n| U256::from(n) == value)
+        {
+            let mut diag = super::lint(
+                self.gcx,
+                "magic-number",
+                expr.span,
+                format!("magic number `{}`", lit.symbol),
+            );
+            diag.help("declare it as a named constant");
+            self.diagnostics.push(diag);
+        }
+        self.walk_expr(expr)
+    }
+}
+
+/// Returns `true` if `name` is in `CapWords`, ignoring leading underscores.
+fn is_cap_words(name: &str) -> bool {
+    let name = name.trim_start_matches('_');
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_')
+}
+
+/// Returns `true` if `name` is in `mixedCase`, ignoring leading and trailing underscores.
+fn is_mixed_case(name: &str) -> bool {
+    let name = name.trim_matches('_');
+    !name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_')
+}
+
+/// Returns `true` if `name` is in `UPPER_SNAKE_CASE`.
+fn is_upper_snake_case(name: &str) -> bool {
+    name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Splits `name` into words at underscores and lowercase-to-uppercase boundaries.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::<String>::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c == '_' {
+            prev_lower = false;
+            words.push(String::new());
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower {
+            words.push(String::new());
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        match words.last_mut() {
+            Some(word) => word.push(c),
+            None => words.push(c.to_string()),
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+    })
+}
+
+fn to_cap_words(name: &str) -> String {
+    words(name).iter().map(|word| capitalize(word)).collect()
+}
+
+fn to_mixed_case(name: &str) -> String {
+    let prefix = &name[..name.len() - name.trim_start_matches('_').len()];
+    let suffix = &name[name.trim_end_matches('_').len()..];
+    let mut out = prefix.to_string();
+    for (i, word) in words(name).iter().enumerate() {
+        if i == 0 {
+            out.push_str(&word.to_ascii_lowercase());
+        } else {
+            out.push_str(&capitalize(word));
+        }
+    }
+    out + suffix
+}
+
+fn to_upper_snake_case(name: &str) -> String {
+    words(name).join("_").to_ascii_uppercase()
+}
@@ -0,0 +1,53 @@
+//! Lints for Ether transfers that forward a fixed amount of gas.
+
+use crate::{
+    builtins::Builtin,
+    hir::{self, ExprKind, Visit},
+    ty::Gcx,
+};
+use solar_data_structures::Never;
+use solar_interface::diagnostics::Diag;
+use std::ops::ControlFlow;
+
+pub(super) fn check_function<'gcx>(
+    gcx: Gcx<'gcx>,
+    function: &'gcx hir::Function<'gcx>,
+) -> Vec<Diag> {
+    let mut checker = TransferChecker { gcx, diagnostics: Vec::new() };
+    let _ = checker.visit_function(function);
+    checker.diagnostics
+}
+
+struct TransferChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    diagnostics: Vec<Diag>,
+}
+
+impl<'gcx> Visit<'gcx> for TransferChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let ExprKind::Call(callee, ..) = expr.kind
+            && let Some(builtin @ (Builtin::AddressPayableTransfer | Builtin::AddressPayableSend)) =
+                self.gcx.builtin_callee(callee.id)
+        {
+            let name = builtin.name();
+            let msg = format!(
+                "`{name}` forwards a fixed stipend of {} gas",
+                Builtin::TRANSFER_GAS_STIPEND
+            );
+            let mut diag = super::lint(self.gcx, "transfer-send", expr.span, msg);
+            diag.note(
+                "gas costs can change between hard forks, as with EIP-1884, so recipients with a \
+                 `receive` or `fallback` function may run out of gas",
+            )
+            .help("use `call{value: amount}(\"\")` and check that it succeeded");
+            self.diagnostics.push(diag);
+        }
+        self.walk_expr(expr)
+    }
+}
@@ -1,8 +1,13 @@
 use crate::{
-    hir,
+    hir::{self, Visit},
     ty::{Gcx, Ty, TyAbiPrinter, TyAbiPrinterMode, TyKind, TySolcPrinter},
 };
 use alloy_json_abi as json;
+use solar_ast::Visibility;
+use solar_data_structures::{
+    Never,
+    map::{FxHashSet, FxIndexSet},
+};
 use std::ops::ControlFlow;
 
 impl<'gcx> Gcx<'gcx> {
@@ -32,15 +37,18 @@ impl<'gcx> Gcx<'gcx> {
         for f in self.interface_functions(id) {
             items.push(self.function_abi(f.id).into());
         }
-        // TODO: Does not include referenced items: https://github.com/paradigmxyz/solar/issues/305
-        // See solc `interfaceEvents` and `interfaceErrors`.
-        for item in self.hir.contract_item_ids(id) {
-            match item {
-                hir::ItemId::Event(id) => items.push(self.event_abi(id).into()),
-                hir::ItemId::Error(id) => items.push(self.error_abi(id).into()),
-                _ => {}
+        let referenced = ReferencedItems::collect(self, id);
+        let mut event_selectors = FxHashSet::default();
+        for event in referenced.events {
+            // Events with the same signature, such as a file-level event and a contract-level
+            // one, are only listed once.
+            if event_selectors.insert(self.event_selector(event)) {
+                items.push(self.event_abi(event).into());
             }
         }
+        for error in referenced.errors {
+            items.push(self.error_abi(error).into());
+        }
 
         // https://github.com/argotorg/solidity/blob/87d86bfba64d8b88537a4a85c1d71f521986b614/libsolidity/interface/ABI.cpp#L43-L47
         fn cmp_key<'a>(item: &'a json::AbiItem<'_>) -> impl Ord + use<'a> {
@@ -123,6 +131,107 @@ impl<'gcx> Gcx<'gcx> {
     }
 }
 
+/// The events and errors of a contract's interface.
+///
+/// Like solc's `interfaceEvents` and `interfaceErrors`, these are the events and errors declared in
+/// the contract and its bases, followed by the events emitted and the errors used by code that the
+/// contract can run, including file-level declarations. Unlike solc, every function of the
+/// contract counts as reachable.
+struct ReferencedItems<'gcx> {
+    gcx: Gcx<'gcx>,
+    events: FxIndexSet<hir::EventId>,
+    errors: FxIndexSet<hir::ErrorId>,
+    visited: FxHashSet<hir::FunctionId>,
+}
+
+impl<'gcx> ReferencedItems<'gcx> {
+    fn collect(gcx: Gcx<'gcx>, id: hir::ContractId) -> Self {
+        let mut this = Self {
+            gcx,
+            events: FxIndexSet::default(),
+            errors: FxIndexSet::default(),
+            visited: FxHashSet::default(),
+        };
+        for item in gcx.hir.contract_item_ids(id) {
+            this.add(hir::Res::Item(item));
+        }
+        for &base in gcx.hir.contract(id).linearized_bases {
+            let _ = this.visit_nested_contract(base);
+        }
+        this
+    }
+
+    fn add(&mut self, res: hir::Res) {
+        match res {
+            hir::Res::Item(hir::ItemId::Event(id)) => {
+                self.events.insert(id);
+            }
+            hir::Res::Item(hir::ItemId::Error(id)) => {
+                self.errors.insert(id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Records a reference to `res` from code that the contract can run.
+    fn reference(&mut self, res: hir::Res) -> ControlFlow<Never> {
+        match res {
+            hir::Res::Item(hir::ItemId::Error(_)) => self.add(res),
+            // Free functions and internal library functions run in the contract's context.
+            hir::Res::Item(hir::ItemId::Function(id)) => {
+                let function = self.gcx.hir.function(id);
+                let runs_inline = match function.contract {
+                    None => true,
+                    Some(contract) => {
+                        self.gcx.hir.contract(contract).kind.is_library()
+                            && function.visibility <= Visibility::Internal
+                    }
+                };
+                if runs_inline && self.visited.insert(id) {
+                    return self.visit_function(function);
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'gcx> Visit<'gcx> for ReferencedItems<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::StmtKind::Emit(expr) = stmt.kind
+            && let Some(callee) = self.gcx.resolved_call(expr)
+        {
+            self.add(callee.res);
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            hir::ExprKind::Call(callee, ..) => {
+                if let Some(resolved) = self.gcx.resolved_callee(callee.id) {
+                    self.reference(resolved.res)?;
+                }
+            }
+            hir::ExprKind::Ident(&[res]) => self.reference(res)?,
+            hir::ExprKind::Member(..) => {
+                if let Some(res) = self.gcx.resolved_member(expr.id) {
+                    self.reference(res)?;
+                }
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
 fn json_state_mutability(s: hir::StateMutability) -> json::StateMutability {
     match s {
         hir::StateMutability::Pure => json::StateMutability::Pure,
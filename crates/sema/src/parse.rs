@@ -1,4 +1,4 @@
-use crate::{Gcx, hir::SourceId, ty::GcxMut};
+use crate::{Gcx, Progress, hir::SourceId, ty::GcxMut};
 use rayon::prelude::*;
 use solar_ast::{self as ast, Span};
 use solar_data_structures::{
@@ -11,10 +11,12 @@ use solar_interface::{
     Result, Session,
     config::CompilerStage,
     diagnostics::{DiagCtxt, ErrorGuaranteed},
+    events::{self, PassTimer},
+    panic_hook,
     source_map::{FileName, FileResolver, ResolveError, SourceFile},
 };
 use solar_parse::{Lexer, Parser, unescape};
-use std::{fmt, path::Path, sync::Arc};
+use std::{fmt, path::Path, sync::Arc, time::Instant};
 use thread_local::ThreadLocal;
 
 /// Builder for parsing sources into a [`Compiler`](crate::Compiler).
@@ -73,6 +75,15 @@ impl<'gcx> ParsingContext<'gcx> {
         &self.sess.dcx
     }
 
+    /// Returns the number of source files processed by each pass.
+    ///
+    /// Set a listener on it before calling [`parse`](Self::parse) to be notified of the progress
+    /// of the whole compilation.
+    #[inline]
+    pub fn progress(&self) -> &'gcx Progress {
+        &self.gcx.progress
+    }
+
     /// Sets whether to recursively resolve and parse imports.
     ///
     /// Default: `!sess.opts.unstable.no_resolve_imports`, `true`.
@@ -157,6 +168,13 @@ impl<'gcx> ParsingContext<'gcx> {
         Ok(())
     }
 
+    /// Returns the files of the sources loaded so far.
+    ///
+    /// Before [`parse`](Self::parse) is called, these are the input files, without their imports.
+    pub fn files(&self) -> impl ExactSizeIterator<Item = &Arc<SourceFile>> {
+        self.sources.iter().map(|source| &source.file)
+    }
+
     /// Adds a preloaded file to the resolver.
     pub fn add_files(&mut self, files: impl IntoIterator<Item = Arc<SourceFile>>) {
         for file in files {
@@ -204,11 +222,13 @@ impl<'gcx> ParsingContext<'gcx> {
 
     #[instrument(name = "parse", level = "debug", skip_all)]
     fn parse_inner(&mut self) {
+        let _timer = PassTimer::new("parsing");
         self.parsed = true;
         let _ = self.gcx.advance_stage(CompilerStage::Parsing);
 
         let mut sources = std::mem::take(self.sources);
         if !sources.is_empty() {
+            self.progress().set_files(sources.len());
             let dbg = enabled!(tracing::Level::DEBUG);
             let len_before = sources.len();
             let sources_parsed_before = if dbg { sources.count_parsed() } else { 0 };
@@ -265,6 +285,7 @@ impl<'gcx> ParsingContext<'gcx> {
                 };
                 sources.add_import(id, item_id, import_file, false);
             });
+            self.progress().set_files(sources.len());
             if ast.is_none() {
                 sources[id].imports.truncate(imports_len);
             }
@@ -322,7 +343,9 @@ impl<'gcx> ParsingContext<'gcx> {
 
             let (import_id, is_new) = {
                 let sources = &mut *lock.lock();
-                sources.get_or_insert_file(import_file.clone())
+                let (import_id, is_new) = sources.get_or_insert_file(import_file.clone());
+                self.progress().set_files(sources.len());
+                (import_id, is_new)
             };
             if is_new {
                 self.spawn_parse_job(lock, import_id, import_file, arenas, scope);
@@ -349,17 +372,29 @@ impl<'gcx> ParsingContext<'gcx> {
         arena: &'ast ast::Arena,
         import_callback: impl FnMut(ast::ItemId, Span, &ast::ImportDirective<'ast>),
     ) -> Option<ast::SourceUnit<'ast>> {
+        let _file = panic_hook::enter_file(&file.name);
+        let start = Instant::now();
         let lexer = Lexer::from_source_file(self.sess, file);
-        let mut parser = Parser::from_lexer(arena, lexer);
-        if self.resolve_imports {
-            parser.set_import_callback(import_callback);
-        }
-        if self.sess.opts.language.is_yul() {
-            let _file = parser.parse_yul_file_object().map_err(|e| e.emit());
-            None
-        } else {
-            parser.parse_file().map_err(|e| e.emit()).ok()
-        }
+        let ast = lexer.with_token_stream(|tokens| {
+            let mut parser = Parser::from_token_stream(self.sess, arena, tokens);
+            if self.resolve_imports {
+                parser.set_import_callback(import_callback);
+            }
+            if self.sess.opts.language.is_yul() {
+                let _file = parser.parse_yul_file_object().map_err(|e| e.emit());
+                None
+            } else {
+                parser.parse_file().map_err(|e| e.emit()).ok()
+            }
+        });
+        solar_interface::event!(
+            "file_parsed",
+            file = %file.name.display(),
+            bytes = file.src.len(),
+            elapsed_us = events::elapsed_us(start),
+        );
+        self.progress().file_parsed();
+        ast
     }
 
     /// Resolves the imports of the given file, returning an iterator over all the imported files
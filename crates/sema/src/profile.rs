@@ -0,0 +1,45 @@
+//! Execution profiles given with `--profile-data`.
+
+use solar_data_structures::map::FxHashMap;
+use std::path::Path;
+
+/// Function hit counts from an execution profile.
+///
+/// Functions are keyed by the contract they are compiled into and their signature, such as
+/// `Token.transfer(address,uint256)`, so that overloads and functions with the same name in
+/// different contracts are counted separately. See
+/// [`Gcx::function_profile_signature`](crate::Gcx::function_profile_signature).
+#[derive(Debug, Default)]
+pub struct ExecutionProfile {
+    /// Number of times each function was executed, keyed by `<contract>.<signature>`.
+    hits: FxHashMap<String, u64>,
+    /// The highest hit count in the profile.
+    max_hits: u64,
+}
+
+impl ExecutionProfile {
+    /// Loads a profile from a JSON object mapping qualified function signatures to hit counts.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let src = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read `{}`: {e}", path.display()))?;
+        Self::parse(&src).map_err(|e| format!("failed to parse `{}`: {e}", path.display()))
+    }
+
+    /// Parses a profile from a JSON object mapping qualified function signatures to hit counts.
+    pub fn parse(src: &str) -> Result<Self, serde_json::Error> {
+        let hits: FxHashMap<String, u64> = serde_json::from_str(src)?;
+        let max_hits = hits.values().copied().max().unwrap_or_default();
+        Ok(Self { hits, max_hits })
+    }
+
+    /// Returns the number of times the function with the given signature in `contract` was
+    /// executed, or `None` if it is not in the profile.
+    pub fn hits(&self, contract: &str, signature: &str) -> Option<u64> {
+        self.hits.get(&format!("{contract}.{signature}")).copied()
+    }
+
+    /// Returns the highest hit count in the profile.
+    pub fn max_hits(&self) -> u64 {
+        self.max_hits
+    }
+}
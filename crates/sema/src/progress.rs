@@ -0,0 +1,145 @@
+//! Progress reporting for long builds.
+
+use std::{
+    fmt,
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+type Listener = Box<dyn Fn(ProgressCounts) + Send + Sync>;
+
+/// Counts the source files processed by each compiler pass.
+///
+/// Available as [`gcx.progress`](crate::ty::GlobalCtxt::progress) and
+/// [`ParsingContext::progress`](crate::ParsingContext::progress). The counts start from zero for
+/// each compilation, and are reset together with the listener by
+/// [`CompilerRef::reset`](crate::CompilerRef::reset).
+#[derive(Default)]
+pub struct Progress {
+    files: AtomicUsize,
+    parsed: AtomicUsize,
+    lowered: AtomicUsize,
+    analyzed: AtomicUsize,
+    listener: OnceLock<Listener>,
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Progress").field(&self.counts()).finish()
+    }
+}
+
+impl Progress {
+    /// Returns the current counts.
+    pub fn counts(&self) -> ProgressCounts {
+        ProgressCounts {
+            files: self.files.load(Ordering::Relaxed),
+            parsed: self.parsed.load(Ordering::Relaxed),
+            lowered: self.lowered.load(Ordering::Relaxed),
+            analyzed: self.analyzed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sets a function to call with the new counts every time they change.
+    ///
+    /// The listener may be called concurrently from several threads.
+    /// This may only be called once. Further calls will do nothing.
+    pub fn set_listener(&self, listener: impl Fn(ProgressCounts) + Send + Sync + 'static) {
+        if let Err(_prev) = self.listener.set(Box::new(listener)) {
+            warn!("progress listener already set");
+        }
+    }
+
+    /// Records that at least `files` source files are known, including discovered imports.
+    pub(crate) fn set_files(&self, files: usize) {
+        if self.files.fetch_max(files, Ordering::Relaxed) < files {
+            self.notify();
+        }
+    }
+
+    pub(crate) fn file_parsed(&self) {
+        self.parsed.fetch_add(1, Ordering::Relaxed);
+        self.notify();
+    }
+
+    pub(crate) fn set_lowered(&self, files: usize) {
+        self.lowered.store(files, Ordering::Relaxed);
+        self.notify();
+    }
+
+    pub(crate) fn file_analyzed(&self) {
+        self.analyzed.fetch_add(1, Ordering::Relaxed);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        if let Some(listener) = self.listener.get() {
+            listener(self.counts());
+        }
+    }
+}
+
+/// The number of source files processed by each compiler pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProgressCounts {
+    /// The number of source files known so far, including discovered imports.
+    pub files: usize,
+    /// The number of source files parsed.
+    pub parsed: usize,
+    /// The number of source files lowered to HIR.
+    pub lowered: usize,
+    /// The number of source files type checked.
+    pub analyzed: usize,
+}
+
+/// Describes the latest pass, such as `parsed 3/10 files`.
+impl fmt::Display for ProgressCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { files, parsed, lowered, analyzed } = *self;
+        if analyzed > 0 {
+            write!(f, "analyzed {analyzed}/{lowered} files")
+        } else if lowered > 0 {
+            write!(f, "lowered {lowered} files")
+        } else {
+            write!(f, "parsed {parsed}/{files} files")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn counts_and_listener() {
+        let progress = Progress::default();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen2 = seen.clone();
+        progress.set_listener(move |counts| seen2.lock().unwrap().push(counts.to_string()));
+
+        progress.set_files(2);
+        progress.set_files(1);
+        progress.file_parsed();
+        progress.file_parsed();
+        progress.set_lowered(2);
+        progress.file_analyzed();
+
+        assert_eq!(
+            progress.counts(),
+            ProgressCounts { files: 2, parsed: 2, lowered: 2, analyzed: 1 }
+        );
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "parsed 0/2 files",
+                "parsed 1/2 files",
+                "parsed 2/2 files",
+                "lowered 2 files",
+                "analyzed 1/2 files",
+            ]
+        );
+    }
+}
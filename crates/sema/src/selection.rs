@@ -0,0 +1,116 @@
+//! Contract selection with `--contract`.
+
+use crate::{hir, ty::Gcx};
+use solar_data_structures::bit_set::DenseBitSet;
+use solar_interface::Result;
+use std::sync::Arc;
+
+/// The contracts selected with `--contract`, and the sources they depend on.
+#[derive(Debug)]
+pub(crate) struct ContractSelection {
+    contracts: DenseBitSet<hir::ContractId>,
+    /// The sources of the selected contracts, and the sources they transitively import.
+    sources: DenseBitSet<hir::SourceId>,
+}
+
+/// Resolves the contracts selected with `--contract`.
+///
+/// Analysis and code generation are then limited to the sources of the selected contracts and
+/// their imports.
+pub(crate) fn select_contracts(gcx: Gcx<'_>) -> Result<()> {
+    let specs = &gcx.sess.opts.contracts;
+    if specs.is_empty() {
+        return Ok(());
+    }
+
+    let mut contracts = DenseBitSet::new_empty(gcx.hir.contract_ids().len());
+    for spec in specs {
+        let matches = gcx
+            .hir
+            .contract_ids()
+            .filter(|&id| contract_matches(gcx, id, spec))
+            .collect::<Vec<_>>();
+        match matches[..] {
+            [] => {
+                let msg = format!("`--contract {spec}` did not match any contract");
+                let available = gcx
+                    .hir
+                    .contract_ids()
+                    .map(|id| contract_name(gcx, id))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(gcx
+                    .dcx()
+                    .err(msg)
+                    .note(format!("available contracts: {available}"))
+                    .emit());
+            }
+            [id] => {
+                contracts.insert(id);
+            }
+            _ => {
+                let msg = format!("`--contract {spec}` matches more than one contract");
+                let candidates = matches
+                    .iter()
+                    .map(|&id| format!("`{}`", contract_name(gcx, id)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(gcx
+                    .dcx()
+                    .err(msg)
+                    .help(format!("specify the contract by its full path: {candidates}"))
+                    .emit());
+            }
+        }
+    }
+
+    let mut sources = DenseBitSet::new_empty(gcx.hir.source_ids().len());
+    let mut stack = contracts.iter().map(|id| gcx.hir.contract(id).source).collect::<Vec<_>>();
+    while let Some(id) = stack.pop() {
+        if sources.insert(id) {
+            stack.extend(gcx.hir.source(id).imports.iter().map(|&(_, import)| import));
+        }
+    }
+
+    let _ = gcx.selection.set(ContractSelection { contracts, sources });
+    Ok(())
+}
+
+/// Returns `true` if the contract is named by `spec`, either as `Name` or as `path:Name`.
+fn contract_matches(gcx: Gcx<'_>, id: hir::ContractId, spec: &str) -> bool {
+    let contract = gcx.hir.contract(id);
+    let Some((path, name)) = spec.rsplit_once(':') else {
+        return contract.name.as_str() == spec;
+    };
+    if contract.name.as_str() != name {
+        return false;
+    }
+    let source = gcx.hir.source(contract.source);
+    gcx.get_file(path.to_owned()).is_some_and(|file| Arc::ptr_eq(&file, &source.file))
+        || source.file.name.display().to_string().replace('\\', "/") == path.replace('\\', "/")
+}
+
+fn contract_name(gcx: Gcx<'_>, id: hir::ContractId) -> String {
+    gcx.contract_fully_qualified_name(id).to_string().replace('\\', "/")
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the contracts selected with `--contract`, in definition order.
+    ///
+    /// All contracts are selected if `--contract` was not passed.
+    pub fn selected_contracts(self) -> impl Iterator<Item = hir::ContractId> + 'gcx {
+        self.hir.contract_ids().filter(move |&id| self.is_contract_selected(id))
+    }
+
+    /// Returns `true` if the contract was selected with `--contract`, or if `--contract` was not
+    /// passed.
+    pub fn is_contract_selected(self, id: hir::ContractId) -> bool {
+        self.selection.get().is_none_or(|selection| selection.contracts.contains(id))
+    }
+
+    /// Returns `true` if the source is analyzed: it defines or is transitively imported by the
+    /// source of a contract selected with `--contract`, or `--contract` was not passed.
+    pub fn is_source_selected(self, id: hir::SourceId) -> bool {
+        self.selection.get().is_none_or(|selection| selection.sources.contains(id))
+    }
+}
@@ -1,7 +1,8 @@
-//! Private span visitor for debugging.
+//! Private span visitors for debugging.
 
-use solar_ast::visit::Visit;
-use solar_interface::{Session, Span};
+use solar_ast::{self as ast, visit::Visit, yul};
+use solar_data_structures::Never;
+use solar_interface::{Session, Span, source_map::SourceFile};
 use std::ops::ControlFlow;
 
 /// A visitor that emits a diagnostic for each span it encounters.
@@ -23,7 +24,7 @@ impl<'sess> SpanVisitor<'sess> {
 }
 
 impl<'ast, 'sess> Visit<'ast> for SpanVisitor<'sess> {
-    type BreakValue = solar_data_structures::Never;
+    type BreakValue = Never;
 
     fn visit_span(&mut self, span: &'ast Span) -> ControlFlow<Self::BreakValue> {
         self.count += 1;
@@ -31,3 +32,120 @@ impl<'ast, 'sess> Visit<'ast> for SpanVisitor<'sess> {
         ControlFlow::Continue(())
     }
 }
+
+/// A visitor that checks AST span invariants.
+///
+/// Every span must lie within its source file, node spans must not be dummy, and the spans of
+/// statements, blocks and expressions must contain the spans of their children.
+pub(crate) struct SpanValidator<'sess> {
+    sess: &'sess Session,
+    file: Span,
+    parents: Vec<Span>,
+    errors: usize,
+}
+
+impl<'sess> SpanValidator<'sess> {
+    /// Creates a new span validator for the given source file.
+    pub(crate) fn new(sess: &'sess Session, file: &SourceFile) -> Self {
+        let file = Span::new(file.start_pos, file.end_position());
+        Self { sess, file, parents: Vec::new(), errors: 0 }
+    }
+
+    /// Returns the number of invariant violations found.
+    pub(crate) fn errors(&self) -> usize {
+        self.errors
+    }
+
+    fn check_node(&mut self, span: Span, node: &str) {
+        if span.is_dummy() {
+            self.errors += 1;
+            self.sess.dcx.err(format!("{node} has a dummy span")).span(self.parent()).emit();
+        }
+    }
+
+    fn parent(&self) -> Span {
+        self.parents.last().copied().unwrap_or(self.file)
+    }
+
+    fn with_parent(
+        &mut self,
+        span: Span,
+        f: impl FnOnce(&mut Self) -> ControlFlow<Never>,
+    ) -> ControlFlow<Never> {
+        self.parents.push(span);
+        let r = f(self);
+        self.parents.pop();
+        r
+    }
+}
+
+impl<'ast, 'sess> Visit<'ast> for SpanValidator<'sess> {
+    type BreakValue = Never;
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(stmt.span, "statement");
+        self.with_parent(stmt.span, |this| this.walk_stmt(stmt))
+    }
+
+    fn visit_block(&mut self, block: &'ast ast::Block<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(block.span, "block");
+        self.with_parent(block.span, |this| this.walk_block(block))
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(expr.span, "expression");
+        self.with_parent(expr.span, |this| this.walk_expr(expr))
+    }
+
+    fn visit_yul_stmt(&mut self, stmt: &'ast yul::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(stmt.span, "Yul statement");
+        self.with_parent(stmt.span, |this| this.walk_yul_stmt(stmt))
+    }
+
+    fn visit_yul_block(&mut self, block: &'ast yul::Block<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(block.span, "Yul block");
+        self.with_parent(block.span, |this| this.walk_yul_block(block))
+    }
+
+    fn visit_yul_expr(&mut self, expr: &'ast yul::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.check_node(expr.span, "Yul expression");
+        self.with_parent(expr.span, |this| this.walk_yul_expr(expr))
+    }
+
+    fn visit_doc_comments(
+        &mut self,
+        doc_comments: &'ast ast::DocComments<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        // Doc comments precede the node they document, so only check them against the file.
+        let parents = std::mem::take(&mut self.parents);
+        let r = self.walk_doc_comments(doc_comments);
+        self.parents = parents;
+        r
+    }
+
+    fn visit_span(&mut self, span: &'ast Span) -> ControlFlow<Self::BreakValue> {
+        let span = *span;
+        if span.is_dummy() {
+            return ControlFlow::Continue(());
+        }
+        if !self.file.contains(span) {
+            self.errors += 1;
+            self.sess
+                .dcx
+                .err("span is out of bounds of its source file")
+                .span(self.parent())
+                .emit();
+        } else if let Some(&parent) = self.parents.last()
+            && !parent.contains(span)
+        {
+            self.errors += 1;
+            self.sess
+                .dcx
+                .err("span is not contained in its parent's span")
+                .span(span)
+                .span_note(parent, "parent span is here")
+                .emit();
+        }
+        ControlFlow::Continue(())
+    }
+}
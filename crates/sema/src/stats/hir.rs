@@ -18,7 +18,7 @@ impl EnumVariantSize for hir::StmtKind<'_> {
             Self::DeclMulti(vars, expr) => variant_payload_size!(self, vars, expr),
             Self::Block(block) => variant_payload_size!(self, block),
             Self::UncheckedBlock(block) => variant_payload_size!(self, block),
-            Self::AssemblyBlock(block) => variant_payload_size!(self, block),
+            Self::AssemblyBlock(assembly) => variant_payload_size!(self, assembly),
             Self::Emit(expr) => variant_payload_size!(self, expr),
             Self::Revert(expr) => variant_payload_size!(self, expr),
             Self::Return(expr) => variant_payload_size!(self, expr),
@@ -466,7 +466,7 @@ impl<'hir> HirVisit<'hir> for HirStatCollector<'hir> {
             }
             hir::StmtKind::Block(block)
             | hir::StmtKind::UncheckedBlock(block)
-            | hir::StmtKind::AssemblyBlock(block)
+            | hir::StmtKind::AssemblyBlock(hir::StmtAssembly { block, .. })
             | hir::StmtKind::Loop(block, _) => self.visit_block(block)?,
             hir::StmtKind::Emit(expr) | hir::StmtKind::Revert(expr) => self.visit_expr(expr)?,
             hir::StmtKind::Return(expr) => {
@@ -1,8 +1,10 @@
 use crate::{
-    Source, Sources, ast,
+    Progress, Source, Sources, ast,
     ast_lowering::SymbolResolver,
     builtins::{Builtin, members},
     hir::{self, Hir, SourceId},
+    profile::ExecutionProfile,
+    selection::ContractSelection,
     typeck::override_checker::OverrideProxy,
 };
 use alloy_primitives::{B256, Selector, U256, keccak256};
@@ -17,7 +19,7 @@ use solar_data_structures::{
     trustme,
 };
 use solar_interface::{
-    Ident, Session, Span, Symbol,
+    ByteSymbol, Ident, Session, Span, Symbol,
     config::CompilerStage,
     diagnostics::{DiagCtxt, ErrorGuaranteed},
     source_map::{FileName, SourceFile},
@@ -332,10 +334,18 @@ pub struct GlobalCtxt<'gcx> {
     pub sources: Sources<'gcx>,
     pub(crate) symbol_resolver: SymbolResolver<'gcx>,
     pub hir: Hir<'gcx>,
+    /// The number of source files processed by each pass.
+    pub progress: Progress,
     stage: AtomicCompilerStage,
+    /// The stage that was current when the first error was emitted.
+    error_stage: OnceLock<Option<CompilerStage>>,
 
     pub types: CommonTypes<'gcx>,
     typeck_results: OnceLock<TypeckResults<'gcx>>,
+    /// The execution profile given with `--profile-data`, loaded on first use.
+    execution_profile: OnceLock<Option<ExecutionProfile>>,
+    /// The contracts selected with `--contract`, set before analysis.
+    pub(crate) selection: OnceLock<ContractSelection>,
 
     pub(crate) ast_arenas: ThreadLocal<ast::Arena>,
     pub(crate) hir_arenas: ThreadLocal<hir::Arena>,
@@ -364,7 +374,9 @@ impl<'gcx> GlobalCtxt<'gcx> {
             sources: Sources::new(),
             symbol_resolver: SymbolResolver::new(&sess.dcx),
             hir: Hir::new(),
+            progress: Progress::default(),
             stage: AtomicCompilerStage::new(),
+            error_stage: OnceLock::new(),
 
             // SAFETY: stable address because ThreadLocal holds the arenas through indirection.
             types: CommonTypes::new(
@@ -372,6 +384,8 @@ impl<'gcx> GlobalCtxt<'gcx> {
                 unsafe { trustme::decouple_lt(&hir_arenas) }.get_or_default().bump(),
             ),
             typeck_results: Default::default(),
+            execution_profile: OnceLock::new(),
+            selection: OnceLock::new(),
 
             ast_arenas: ThreadLocal::new(),
             hir_arenas,
@@ -392,6 +406,14 @@ impl<'gcx> Gcx<'gcx> {
         self.stage.get()
     }
 
+    /// Returns the compiler stage during which the first error was emitted.
+    ///
+    /// This is only meaningful if an error was emitted, in which case it is the current stage if
+    /// the error was emitted after the last stage transition.
+    pub fn error_stage(&self) -> Option<CompilerStage> {
+        self.error_stage.get().copied().unwrap_or_else(|| self.stage())
+    }
+
     pub(crate) fn advance_stage(&self, to: CompilerStage) -> ControlFlow<()> {
         let from = self.stage();
         let result = self.advance_stage_(to);
@@ -402,6 +424,10 @@ impl<'gcx> Gcx<'gcx> {
     fn advance_stage_(&self, to: CompilerStage) -> ControlFlow<()> {
         let current = self.stage();
 
+        if self.dcx().has_errors().is_err() {
+            let _ = self.error_stage.set(current);
+        }
+
         // Special case: allow calling `parse` multiple times while currently parsing.
         if to == CompilerStage::Parsing && current == Some(to) {
             return ControlFlow::Continue(());
@@ -895,12 +921,84 @@ impl<'gcx> Gcx<'gcx> {
         self.item_selector(id.into())
     }
 
+    /// Returns `true` if a value of type `from` can be used where `to` is expected, without a cast.
+    ///
+    /// This follows solc's implicit conversion rules, including literal ranges, contract
+    /// inheritance, and data location changes. See [`Ty::try_convert_implicit_to`] for the reason
+    /// a conversion is rejected.
+    ///
+    /// See: <https://docs.soliditylang.org/en/latest/types.html#implicit-conversions>
+    pub fn is_implicitly_convertible(self, from: Ty<'gcx>, to: Ty<'gcx>) -> bool {
+        from.convert_implicit_to(to, self)
+    }
+
+    /// Returns `true` if `from` can be cast to `to` with a type conversion expression, `to(from)`.
+    ///
+    /// Explicit conversions are a superset of implicit conversions. See
+    /// [`Ty::try_convert_explicit_to`] for the reason a conversion is rejected.
+    ///
+    /// See: <https://docs.soliditylang.org/en/latest/types.html#explicit-conversions>
+    pub fn is_explicitly_convertible(self, from: Ty<'gcx>, to: Ty<'gcx>) -> bool {
+        from.convert_explicit_to(to, self)
+    }
+
+    /// Returns the 4-byte selector of the given signature, such as `transfer(address,uint256)`.
+    pub fn signature_selector(self, signature: &str) -> Selector {
+        self.keccak256(ByteSymbol::intern(signature.as_bytes()))[..4].try_into().unwrap()
+    }
+
+    /// Returns the execution profile given with `--profile-data`, if any.
+    ///
+    /// The profile is loaded once, on first use. If it cannot be loaded, an error is emitted and
+    /// `None` is returned.
+    pub fn execution_profile(self) -> Option<&'gcx ExecutionProfile> {
+        self.0
+            .execution_profile
+            .get_or_init(|| {
+                let path = self.sess.opts.profile_data.as_deref()?;
+                ExecutionProfile::load(path)
+                    .map_err(|err| {
+                        self.dcx().err(format!("failed to load profile data: {err}")).emit()
+                    })
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// Returns the signature of the given function in an [`ExecutionProfile`], such as
+    /// `transfer(address,uint256)`.
+    ///
+    /// Unlike [`item_signature`](Self::item_signature), this is defined for internal functions too:
+    /// user-defined types are printed by name and storage parameters are marked, like in library
+    /// signatures. Unnamed functions are printed by kind, such as `constructor(address)`.
+    pub fn function_profile_signature(self, id: hir::FunctionId) -> String {
+        let func = self.hir.function(id);
+        let name = match func.name {
+            Some(name) => name.to_string(),
+            None => func.kind.to_str().to_string(),
+        };
+        self.mk_abi_signature(&name, self.item_parameter_types(id).iter().copied(), true)
+    }
+
     /// Computes the [`Ty`] of the given [`hir::Type`]. Not cached.
     pub fn type_of_hir_ty(self, ty: &hir::Type<'_>) -> Ty<'gcx> {
+        self.type_of_hir_ty_at(ty, 0)
+    }
+
+    fn type_of_hir_ty_at(self, ty: &hir::Type<'_>, depth: usize) -> Ty<'gcx> {
+        if depth >= self.sess.opts.unstable.recursion_limit() {
+            let guar = self
+                .dcx()
+                .err("recursion limit reached")
+                .span(ty.span)
+                .help("consider increasing the limit with `-Zrecursion-limit=<LIMIT>`")
+                .emit();
+            return self.mk_ty_err(guar);
+        }
         let kind = match ty.kind {
             hir::TypeKind::Elementary(ty) => TyKind::Elementary(ty),
             hir::TypeKind::Array(array) => {
-                let elem = self.type_of_hir_ty(&array.element);
+                let elem = self.type_of_hir_ty_at(&array.element, depth + 1);
                 match array.size {
                     Some(size) => match crate::eval::eval_array_len(self, size) {
                         Ok(size) => TyKind::Array(elem, size),
@@ -925,8 +1023,8 @@ impl<'gcx> Gcx<'gcx> {
                 });
             }
             hir::TypeKind::Mapping(mapping) => {
-                let key = self.type_of_hir_ty(&mapping.key);
-                let value = self.type_of_hir_ty(&mapping.value);
+                let key = self.type_of_hir_ty_at(&mapping.key, depth + 1);
+                let value = self.type_of_hir_ty_at(&mapping.value, depth + 1);
                 TyKind::Mapping(key, value)
             }
             hir::TypeKind::Custom(item) => return self.type_of_item_simple(item, ty.span),
@@ -1561,16 +1659,29 @@ pub(crate) fn natspec_contract_in_source(
 }
 
 /// Returns the ABI signature of the given item. Only accepts functions, errors, and events.
+///
+/// This is the single source of signatures for selectors, event topics, outputs, and diagnostics.
+/// Structs are printed as tuples, and enums and user-defined value types as their underlying type.
+/// Library functions instead print types by name, with a `storage` suffix on storage references,
+/// like solc's `externalSignature`. Events and errors always use ABI types, even in libraries.
 pub fn item_signature(gcx: _, id: hir::ItemId) -> &'gcx str {
     let name = gcx.item_name(id);
     let tys = gcx.item_parameter_types(id);
-    let in_library =
-        gcx.hir.item(id).contract().is_some_and(|c| gcx.hir.contract(c).kind.is_library());
+    let in_library = matches!(id, hir::ItemId::Function(_))
+        && gcx.hir.item(id).contract().is_some_and(|c| gcx.hir.contract(c).kind.is_library());
     gcx.bump().alloc_str(&gcx.mk_abi_signature(name.as_str(), tys.iter().copied(), in_library))
 }
 
 pub(crate) fn item_selector(gcx: _, id: hir::ItemId) -> B256 {
-    keccak256(gcx.item_signature(id))
+    gcx.keccak256(ByteSymbol::intern(gcx.item_signature(id).as_bytes()))
+}
+
+/// Returns the Keccak-256 hash of the given bytes, such as a function, error, or event signature.
+///
+/// Use this over [`keccak256`](alloy_primitives::keccak256) for compile-time constants, which
+/// are often hashed more than once.
+pub fn keccak256(_gcx: _, data: ByteSymbol) -> B256 {
+    keccak256(data.as_byte_str())
 }
 
 /// Returns the type of the given builtin.
@@ -2111,4 +2222,29 @@ contract D {
             [Some("33".into()), Some("44".into()), None]
         );
     }
+
+    #[test]
+    fn convertibility() {
+        let sess = Session::builder().opts(CompileOpts::default()).with_test_emitter().build();
+        let compiler = Compiler::new(sess);
+        compiler.enter(|c| {
+            let gcx = c.gcx();
+            let types = &gcx.types;
+            let (uint8, uint256, int128) = (types.uint(8), types.uint(256), types.int(128));
+
+            assert!(gcx.is_implicitly_convertible(uint8, uint256));
+            assert!(!gcx.is_implicitly_convertible(uint256, uint8));
+            assert!(gcx.is_explicitly_convertible(uint256, uint8));
+            // Sign and size cannot change at the same time.
+            assert!(!gcx.is_explicitly_convertible(int128, uint256));
+
+            assert!(gcx.is_implicitly_convertible(types.address_payable, types.address));
+            assert!(!gcx.is_implicitly_convertible(types.address, types.address_payable));
+            assert!(gcx.is_explicitly_convertible(types.address, types.address_payable));
+
+            let bytes = &types.bytes_ref;
+            assert!(gcx.is_implicitly_convertible(bytes.calldata, bytes.memory));
+            assert!(!gcx.is_implicitly_convertible(bytes.memory, bytes.calldata));
+        });
+    }
 }
@@ -104,6 +104,11 @@ impl<'gcx> Ty<'gcx> {
     }
 
     /// Displays the type for human-readable diagnostics.
+    ///
+    /// Types are printed like solc does, with data locations and qualified names, e.g.
+    /// `uint256`, `address payable`, `struct C.S memory`, `contract C`, or
+    /// `mapping(address => uint256)`. This is the format used in type checking errors and by the
+    /// language server, so use it everywhere a type is shown to users.
     pub fn display(self, gcx: Gcx<'gcx>) -> impl fmt::Display + use<'gcx> {
         fmt::from_fn(move |f| TySolcPrinter::new(gcx, f).data_locations(true).print(self))
     }
@@ -592,17 +597,20 @@ impl<'gcx> Ty<'gcx> {
 
     /// Returns `true` if the type can be used for variables.
     pub fn nameable(self) -> bool {
-        matches!(
-            self.kind,
+        match self.kind {
             TyKind::Elementary(_)
-                | TyKind::Array(..)
-                | TyKind::DynArray(_)
-                | TyKind::Contract(_)
-                | TyKind::Struct(_)
-                | TyKind::Enum(_)
-                | TyKind::Udvt(..)
-                | TyKind::Mapping(..)
-        )
+            | TyKind::Array(..)
+            | TyKind::DynArray(_)
+            | TyKind::Contract(_)
+            | TyKind::Struct(_)
+            | TyKind::Enum(_)
+            | TyKind::Udvt(..)
+            | TyKind::Mapping(..) => true,
+            TyKind::Fn(f) => {
+                !f.attached && matches!(f.kind, TyFnKind::Internal | TyFnKind::External)
+            }
+            _ => false,
+        }
     }
 
     /// Returns the common type between the two types.
@@ -1,6 +1,6 @@
 use crate::{
     builtins::{Builtin, members},
-    eval::{ConstValue, EvalErrorKind},
+    eval::{ConstValue, EvalErrorKind, IntScalar, is_number_const_expr, is_rational_const_expr},
     hir::{self, Visit},
     ty::{
         CallableParamSource, Gcx, ResolvedCallee, Ty, TyConvertError, TyFn, TyFnKind, TyKind,
@@ -14,7 +14,7 @@ use solar_ast::{
 use solar_data_structures::{Never, bit_set::DenseBitSet, pluralize, smallvec::SmallVec};
 use solar_interface::{
     Ident, Symbol,
-    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    diagnostics::{DiagBuilder, DiagCtxt, ErrorGuaranteed},
     kw, sym,
 };
 use std::ops::ControlFlow;
@@ -67,6 +67,9 @@ struct TypeChecker<'gcx> {
     in_revert: bool,
     /// Whether we're checking expressions lowered from inline assembly.
     in_yul: bool,
+    /// Whether we're checking the length of an array type, whose evaluation errors are reported
+    /// when the type is resolved.
+    in_array_len: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -93,6 +96,7 @@ impl<'gcx> TypeChecker<'gcx> {
             in_emit: false,
             in_revert: false,
             in_yul: false,
+            in_array_len: false,
         }
     }
 
@@ -101,6 +105,11 @@ impl<'gcx> TypeChecker<'gcx> {
     }
 
     fn check_storage_layout_base_slot(&mut self, slot: &'gcx hir::Expr<'gcx>) {
+        if let Some(expr) = self.out_of_range_base_slot_subexpr(slot) {
+            let err = EvalErrorKind::ArithmeticOverflow.spanned(expr.span);
+            self.gcx.emit_const_eval_error(slot, err);
+            return;
+        }
         match self.gcx.try_eval_const_value(slot) {
             Ok(ConstValue::Integer(value)) => {
                 if let Some(base_slot) = value.as_u256() {
@@ -122,7 +131,7 @@ impl<'gcx> TypeChecker<'gcx> {
                     self.dcx().emit_err(slot.span, "base slot of storage layout evaluates to a value outside the range of type `uint256`");
                 }
             }
-            Ok(ConstValue::Bool(_)) => {
+            Ok(ConstValue::Rational(_) | ConstValue::Bool(_)) => {
                 self.dcx()
                     .emit_err(slot.span, "base slot of storage layout must evaluate to an integer");
             }
@@ -136,6 +145,33 @@ impl<'gcx> TypeChecker<'gcx> {
         }
     }
 
+    /// Returns the innermost subexpression of a storage layout base slot whose value does not fit
+    /// in 256 bits.
+    ///
+    /// Unlike other constant expressions, solc requires all intermediate values of base slots to
+    /// be in the range of `uint256`.
+    fn out_of_range_base_slot_subexpr(
+        &self,
+        expr: &'gcx hir::Expr<'gcx>,
+    ) -> Option<&'gcx hir::Expr<'gcx>> {
+        let operands = match expr.kind {
+            hir::ExprKind::Binary(lhs, _, rhs) => [Some(lhs), Some(rhs)],
+            hir::ExprKind::Unary(_, inner) | hir::ExprKind::Tuple([Some(inner)]) => {
+                [Some(inner), None]
+            }
+            _ => [None, None],
+        };
+        operands
+            .into_iter()
+            .flatten()
+            .find_map(|operand| self.out_of_range_base_slot_subexpr(operand))
+            .or_else(|| {
+                let res = self.gcx.try_eval_const_value(expr);
+                matches!(res, Err(err) if matches!(err.kind, EvalErrorKind::ValueOutOfRange))
+                    .then_some(expr)
+            })
+    }
+
     fn get(&self, expr: &'gcx hir::Expr<'gcx>) -> Ty<'gcx> {
         self.results.expr_types[&expr.id]
     }
@@ -182,6 +218,12 @@ impl<'gcx> TypeChecker<'gcx> {
         expr: &'gcx hir::Expr<'gcx>,
         expected: Option<Ty<'gcx>>,
     ) -> Ty<'gcx> {
+        if is_number_const_expr(expr)
+            && let Some(ty) = self.check_number_const_expr(expr)
+        {
+            return ty;
+        }
+
         match expr.kind {
             hir::ExprKind::Array(exprs) => {
                 let mut common = expected.and_then(|arr| arr.base_type(self.gcx));
@@ -375,13 +417,19 @@ impl<'gcx> TypeChecker<'gcx> {
 
                 ty
             }
-            hir::ExprKind::Delete(expr) => {
-                let ty = self.require_lvalue(expr);
+            hir::ExprKind::Delete(inner) => {
+                let ty = self.require_lvalue(inner);
                 if valid_delete(ty) {
                     self.gcx.types.unit
                 } else {
                     let msg = format!("cannot delete `{}`", ty.display(self.gcx));
-                    let err = self.dcx().err(msg).span(expr.span);
+                    let keyword_span =
+                        expr.span.with_hi(expr.span.lo() + kw::Delete.as_str().len() as u32);
+                    let err = self
+                        .dcx()
+                        .err(msg)
+                        .span(keyword_span)
+                        .span_label(inner.span, ty.display(self.gcx).to_string());
                     self.gcx.mk_ty_err(err.emit())
                 }
             }
@@ -442,26 +490,30 @@ impl<'gcx> TypeChecker<'gcx> {
             }
             hir::ExprKind::Slice(lhs, start, end) => {
                 let ty = self.check_expr(lhs);
-                if !ty.is_sliceable() {
-                    self.dcx().emit_err(expr.span, "can only slice arrays");
-                } else if !is_calldata_sliceable(ty) {
-                    self.dcx().emit_err(expr.span, "can only slice dynamic calldata arrays");
+                for bound in [start, end].into_iter().flatten() {
+                    let _ = self.expect_ty(bound, self.gcx.types.uint(256));
                 }
-                if let Some((_index_ty, _result_ty)) = self.index_types(ty) {
-                    if let Some(start) = start {
-                        let _ = self.expect_ty(start, self.gcx.types.uint(256));
-                    }
-                    if let Some(end) = end {
-                        let _ = self.expect_ty(end, self.gcx.types.uint(256));
-                    }
-                    if let TyKind::Slice(_) = ty.kind {
-                        ty
-                    } else {
-                        self.gcx.mk_ty(TyKind::Slice(ty))
-                    }
-                } else {
-                    self.gcx.mk_ty_err(self.dcx().emit_err(expr.span, "cannot index"))
+                if ty.references_error() {
+                    return ty;
                 }
+                let msg = if !ty.is_sliceable() {
+                    "can only slice arrays"
+                } else if !is_calldata_sliceable(ty) {
+                    "can only slice dynamic calldata arrays"
+                } else if self.index_types(ty).is_none() {
+                    "cannot index"
+                } else if ty
+                    .peel_refs()
+                    .base_type(self.gcx)
+                    .is_some_and(|base| base.is_dynamically_encoded(self.gcx))
+                {
+                    "cannot slice arrays with dynamically encoded base types"
+                } else if let TyKind::Slice(_) = ty.kind {
+                    return ty;
+                } else {
+                    return self.gcx.mk_ty(TyKind::Slice(ty));
+                };
+                self.gcx.mk_ty_err(self.dcx().emit_err(expr.span, msg))
             }
             hir::ExprKind::Lit(lit) if self.in_yul => self.check_yul_lit(lit),
             hir::ExprKind::Lit(lit) => self.gcx.type_of_lit(lit),
@@ -547,6 +599,16 @@ impl<'gcx> TypeChecker<'gcx> {
                         if !c.kind.is_contract() {
                             let msg = format!("cannot instantiate {}s", c.kind);
                             self.gcx.mk_ty_err(self.dcx().emit_err(hir_ty.span, msg))
+                        } else if let Some(current) = self.contract
+                            && c.linearized_bases.contains(&current)
+                        {
+                            let guar = self
+                                .dcx()
+                                .err("circular reference for contract creation")
+                                .span(hir_ty.span)
+                                .note("cannot create an instance of the same or a derived contract")
+                                .emit();
+                            self.gcx.mk_ty_err(guar)
                         } else {
                             let mut parameters: &[Ty<'_>] = &[];
                             let mut sm = hir::StateMutability::NonPayable;
@@ -622,13 +684,17 @@ impl<'gcx> TypeChecker<'gcx> {
                 match (true_ty, false_ty) {
                     (Some(true_ty), Some(false_ty)) => {
                         true_ty.common_type(false_ty, self.gcx).unwrap_or_else(|| {
-                            self.gcx.mk_ty_err(
-                                self.dcx()
-                                    .err("incompatible conditional types")
-                                    //.span(vec![true_.span, false_.span])
-                                    .span(expr.span)
-                                    .emit(),
-                            )
+                            let label = format!(
+                                "true expression's type `{}` does not match false expression's type `{}`",
+                                true_ty.display(self.gcx),
+                                false_ty.display(self.gcx),
+                            );
+                            self.gcx.mk_ty_err(self.dcx().emit_err_label(
+                                expr.span,
+                                "incompatible conditional types",
+                                expr.span,
+                                label,
+                            ))
                         })
                     }
                     (true_ty, false_ty) => {
@@ -681,6 +747,12 @@ impl<'gcx> TypeChecker<'gcx> {
                 }
             }
             hir::ExprKind::Type(ref ty) => {
+                if let hir::TypeKind::Elementary(
+                    elem @ (ElementaryType::Fixed(..) | ElementaryType::UFixed(..)),
+                ) = ty.kind
+                {
+                    self.check_fixed_point_ty(ty.span, elem);
+                }
                 self.gcx.mk_ty(TyKind::Type(self.gcx.type_of_hir_ty(ty)))
             }
             hir::ExprKind::Unary(op, inner) => {
@@ -718,7 +790,11 @@ impl<'gcx> TypeChecker<'gcx> {
                         "cannot apply unary operator `{op}` to `{}`",
                         ty.display(self.gcx),
                     );
-                    let err = self.dcx().err(msg).span(expr.span);
+                    let err = self
+                        .dcx()
+                        .err(msg)
+                        .span(op.span)
+                        .span_label(inner.span, ty.display(self.gcx).to_string());
                     self.gcx.mk_ty_err(err.emit())
                 }
             }
@@ -801,11 +877,36 @@ impl<'gcx> TypeChecker<'gcx> {
         for (i, (&lhs_component, &lhs_component_ty)) in
             lhs_components.iter().zip(lhs_types).enumerate()
         {
-            if let Some(lhs_component) = lhs_component
-                && let Some(rhs_component) = rhs_components.and_then(|components| components[i])
-                && !self.can_assign_storage_copy(lhs_component, rhs_types[i], lhs_component_ty)
-            {
-                let _ = self.check_expected(rhs_component, rhs_types[i], lhs_component_ty);
+            // Empty components on the left hand side accept any value.
+            let Some(lhs_component) = lhs_component else { continue };
+            let rhs_component_ty = rhs_types[i];
+            if self.can_assign_storage_copy(lhs_component, rhs_component_ty, lhs_component_ty) {
+                continue;
+            }
+            match rhs_components {
+                Some(components) => {
+                    if let Some(rhs_component) = components[i] {
+                        let _ =
+                            self.check_expected(rhs_component, rhs_component_ty, lhs_component_ty);
+                    }
+                }
+                // The right hand side is a single expression of tuple type, such as a call, so
+                // report the mismatch on the left hand side component instead.
+                None => {
+                    if let Err(err) =
+                        rhs_component_ty.try_convert_implicit_to(lhs_component_ty, self.gcx)
+                    {
+                        self.dcx().emit_err_label(
+                            lhs_component.span,
+                            "mismatched types",
+                            rhs.span,
+                            format!(
+                                "component {i} of this tuple: {}",
+                                err.message(rhs_component_ty, lhs_component_ty, self.gcx)
+                            ),
+                        );
+                    }
+                }
             }
         }
     }
@@ -872,14 +973,80 @@ impl<'gcx> TypeChecker<'gcx> {
     /// This is used to preserve literal type through literal expressions.
     fn try_eval_int_literal_expr(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<Ty<'gcx>> {
         let result = self.gcx.try_eval_const(expr).ok()?;
-        let compatible_fixed_bytes = result.is_zero().then_some(TypeSize::ZERO);
+        self.int_literal_ty(result)
+    }
+
+    fn int_literal_ty(&self, value: &IntScalar) -> Option<Ty<'gcx>> {
+        let compatible_fixed_bytes = value.is_zero().then_some(TypeSize::ZERO);
         self.gcx.mk_ty_int_literal_with_fixed_bytes(
-            result.is_negative(),
-            result.bit_len(),
+            value.is_negative(),
+            value.bit_len(),
             compatible_fixed_bytes,
         )
     }
 
+    /// Checks an operation made up only of number literals, such as `2**256 - 1` or `0.1 * 10`.
+    ///
+    /// The expression is evaluated as a whole, and has the literal type of its value. Operands are
+    /// only typed if their own value fits in a type, since intermediate values may be fractional
+    /// or exceed 256 bits.
+    ///
+    /// Returns `None` to check the expression operand by operand instead, for integer array
+    /// lengths whose evaluation errors are reported when the type is resolved.
+    fn check_number_const_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> Option<Ty<'gcx>> {
+        let value = self.gcx.try_eval_const_value(expr);
+        if value.is_err() && self.in_array_len && !is_rational_const_expr(expr) {
+            return None;
+        }
+        self.register_number_const_operands(expr);
+        Some(match value {
+            Ok(ConstValue::Integer(value)) => self.int_literal_ty(value).unwrap_or_else(|| {
+                let msg = "integer literal is greater than 2**256";
+                self.gcx.mk_ty_err(self.dcx().emit_err(expr.span, msg))
+            }),
+            Ok(ConstValue::Bool(_)) => self.gcx.types.bool,
+            Ok(ConstValue::Rational(value)) => {
+                let guar = self
+                    .dcx()
+                    .err("rational numbers are not supported")
+                    .span(expr.span)
+                    .note(format!("expression evaluates to `{value}`, which is not an integer"))
+                    .emit();
+                self.gcx.mk_ty_err(guar)
+            }
+            Ok(ConstValue::String(_)) => unreachable!("not a number expression"),
+            Err(err) => self.gcx.mk_ty_err(self.gcx.emit_const_eval_error(expr, err)),
+        })
+    }
+
+    /// Registers the types of the operands of a number literal expression whose values can be
+    /// typed on their own.
+    fn register_number_const_operands(&mut self, expr: &'gcx hir::Expr<'gcx>) {
+        let (first, second) = match expr.kind {
+            hir::ExprKind::Binary(lhs, _, rhs) => (lhs, Some(rhs)),
+            hir::ExprKind::Unary(_, inner) | hir::ExprKind::Tuple([Some(inner)]) => (inner, None),
+            _ => return,
+        };
+        for operand in std::iter::once(first).chain(second) {
+            match operand.kind {
+                hir::ExprKind::Lit(lit) if matches!(lit.kind, LitKind::Number(_)) => {
+                    let _ = self.check_expr(operand);
+                }
+                _ => {
+                    let ty = match self.gcx.try_eval_const_value(operand) {
+                        Ok(ConstValue::Integer(value)) => self.int_literal_ty(value),
+                        Ok(ConstValue::Bool(_)) => Some(self.gcx.types.bool),
+                        _ => None,
+                    };
+                    if let Some(ty) = ty {
+                        self.register_ty(operand, ty);
+                    }
+                    self.register_number_const_operands(operand);
+                }
+            }
+        }
+    }
+
     fn check_binop(
         &mut self,
         lhs_e: &'gcx hir::Expr<'gcx>,
@@ -1248,13 +1415,7 @@ impl<'gcx> TypeChecker<'gcx> {
 
         let selected = match self.select_call_overload(&error_res, &args) {
             Ok(res) => res,
-            Err(e) => {
-                let msg = match e {
-                    OverloadError::NotFound => "no matching declarations found",
-                    OverloadError::Ambiguous => "no unique declarations found",
-                };
-                hir::Res::Err(self.dcx().emit_err(callee.span, msg))
-            }
+            Err(e) => hir::Res::Err(self.emit_overload_error(e, callee.span, &error_res)),
         };
         let callee_ty = self.type_of_res(selected);
         let TyKind::Error(param_tys, _) = callee_ty.kind else {
@@ -1666,13 +1827,53 @@ impl<'gcx> TypeChecker<'gcx> {
                         )
                     }
                 };
-                self.gcx.mk_ty_err(self.dcx().emit_err(ident.span, msg))
+                let mut diag = self.dcx().err(msg).span(ident.span);
+                self.note_overload_candidates(
+                    &mut diag,
+                    possible_members.iter().filter_map(|member| member.res),
+                );
+                self.gcx.mk_ty_err(diag.emit())
             }
         };
         self.register_ty(callee, ty);
         ty
     }
 
+    fn emit_overload_error(
+        &self,
+        error: OverloadError,
+        span: Span,
+        candidates: &[hir::Res],
+    ) -> ErrorGuaranteed {
+        let msg = match error {
+            OverloadError::NotFound => "no matching declarations found",
+            OverloadError::Ambiguous => "no unique declarations found",
+        };
+        let mut diag = self.dcx().err(msg).span(span);
+        self.note_overload_candidates(&mut diag, candidates.iter().copied());
+        diag.emit()
+    }
+
+    /// Points to the declaration of each overload candidate that is a named item, in source order.
+    fn note_overload_candidates(
+        &self,
+        diag: &mut DiagBuilder<'_, ErrorGuaranteed>,
+        candidates: impl IntoIterator<Item = hir::Res>,
+    ) {
+        let mut spans = candidates
+            .into_iter()
+            .filter_map(|candidate| match candidate {
+                hir::Res::Item(id) => self.gcx.hir.item(id).name().map(|name| name.span),
+                _ => None,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+        spans.sort_by_key(|span| span.lo());
+        spans.dedup();
+        for span in spans {
+            diag.span_note(span, "candidate declared here");
+        }
+    }
+
     fn select_member_access<'a>(
         &self,
         members: &'a [members::Member<'gcx>],
@@ -1714,13 +1915,7 @@ impl<'gcx> TypeChecker<'gcx> {
     ) -> Ty<'gcx> {
         let res = match self.select_call_overload(res, args) {
             Ok(res) => res,
-            Err(e) => {
-                let msg = match e {
-                    OverloadError::NotFound => "no matching declarations found",
-                    OverloadError::Ambiguous => "no unique declarations found",
-                };
-                hir::Res::Err(self.dcx().emit_err(callee.span, msg))
-            }
+            Err(e) => hir::Res::Err(self.emit_overload_error(e, callee.span, res)),
         };
         let ty = self.type_of_res(res);
         self.results.resolved_callees.insert(callee.id, ResolvedCallee::new(res, false));
@@ -1959,6 +2154,12 @@ impl<'gcx> TypeChecker<'gcx> {
                             opt.name.span,
                             "function call option `salt` can only be used with `new`",
                         );
+                    } else if !self.gcx.sess.opts.evm_version.has_create2() {
+                        self.dcx()
+                            .err("unsupported call option `salt`")
+                            .span(opt.name.span)
+                            .help("compile with `--evm-version constantinople` or newer")
+                            .emit();
                     }
                     let _ = self.expect_ty(&opt.value, self.gcx.types.fixed_bytes(32));
                     std::mem::replace(&mut salt_set, true)
@@ -2153,6 +2354,151 @@ impl<'gcx> TypeChecker<'gcx> {
         ty
     }
 
+    /// Checks the call and the clauses of a `try` statement.
+    ///
+    /// See: <https://docs.soliditylang.org/en/latest/control-structures.html#try-catch>
+    fn check_try(&mut self, try_: &'gcx hir::StmtTry<'gcx>) {
+        let _ = self.check_expr(&try_.expr);
+        let returns = self.try_call_returns(&try_.expr);
+
+        let [returns_clause, catch_clauses @ ..] = try_.clauses else {
+            unreachable!("try statement without clauses")
+        };
+        let return_var_tys = returns_clause
+            .args
+            .iter()
+            .map(|&var| self.check_var(var))
+            .collect::<SmallVec<[_; 4]>>();
+        if let Some(returns) = returns
+            && !returns_clause.args.is_empty()
+        {
+            self.check_try_returns_clause(returns_clause, &return_var_tys, returns);
+        }
+
+        let mut error_clause = None;
+        let mut panic_clause = None;
+        let mut low_level_clause = None;
+        for clause in catch_clauses {
+            let tys =
+                clause.args.iter().map(|&var| self.check_var(var)).collect::<SmallVec<[_; 2]>>();
+            if tys.iter().any(|ty| ty.references_error()) {
+                continue;
+            }
+            let (kind, seen, valid, expected) = match clause.name {
+                None => (
+                    "low-level",
+                    &mut low_level_clause,
+                    tys.is_empty() || tys[..] == [self.gcx.types.bytes_ref.memory],
+                    "`catch (bytes memory ...) { ... }` or `catch { ... }`",
+                ),
+                Some(name) if name.name == sym::Error => (
+                    "`Error`",
+                    &mut error_clause,
+                    tys[..] == [self.gcx.types.string_ref.memory],
+                    "`catch Error(string memory ...) { ... }`",
+                ),
+                Some(name) if name.name == sym::Panic => (
+                    "`Panic`",
+                    &mut panic_clause,
+                    tys[..] == [self.gcx.types.uint(256)],
+                    "`catch Panic(uint256 ...) { ... }`",
+                ),
+                Some(name) => {
+                    self.dcx()
+                        .err("invalid catch clause name")
+                        .span(name.span)
+                        .help("expected `catch (...)`, `catch Error(...)`, or `catch Panic(...)`")
+                        .emit();
+                    continue;
+                }
+            };
+
+            let catch_span =
+                clause.span.with_hi(clause.span.lo() + kw::Catch.as_str().len() as u32);
+            if let Some(prev_span) = *seen {
+                self.dcx()
+                    .err(format!("this try statement already has a {kind} catch clause"))
+                    .span(catch_span)
+                    .span_note(prev_span, "previous clause here")
+                    .emit();
+            } else {
+                *seen = Some(catch_span);
+            }
+
+            if !valid {
+                let span = if clause.args.is_empty() {
+                    clause.name.map_or(catch_span, |name| name.span)
+                } else {
+                    Span::join_first_last(
+                        clause.args.iter().map(|&var| self.gcx.hir.variable(var).span),
+                    )
+                };
+                self.dcx()
+                    .err("invalid catch clause parameters")
+                    .span(span)
+                    .help(format!("expected {expected}"))
+                    .emit();
+            }
+        }
+    }
+
+    /// Returns the return types of the call in a `try` statement, if it is valid.
+    fn try_call_returns(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<&'gcx [Ty<'gcx>]> {
+        let msg = "try can only be used with external function calls and contract creation calls";
+        let hir::ExprKind::Call(callee, ..) = expr.kind else {
+            self.dcx().emit_err(expr.span, msg);
+            return None;
+        };
+        let callee_ty = self.get(callee);
+        match callee_ty.kind {
+            TyKind::Fn(f) if matches!(f.kind, TyFnKind::External | TyFnKind::Creation) => {
+                Some(f.returns)
+            }
+            _ if callee_ty.references_error() => None,
+            _ => {
+                self.dcx().emit_err(expr.span, msg);
+                None
+            }
+        }
+    }
+
+    /// Checks that the variables of the `returns` clause of a `try` statement match the return
+    /// types of the call exactly.
+    fn check_try_returns_clause(
+        &self,
+        clause: &'gcx hir::TryCatchClause<'gcx>,
+        var_tys: &[Ty<'gcx>],
+        returns: &'gcx [Ty<'gcx>],
+    ) {
+        if var_tys.len() != returns.len() {
+            let span = Span::join_first_last(
+                clause.args.iter().map(|&var| self.gcx.hir.variable(var).span),
+            );
+            self.dcx().emit_err(
+                span,
+                format!(
+                    "function returns {} value{}, but the `returns` clause has {} variable{}",
+                    returns.len(),
+                    pluralize!(returns.len()),
+                    var_tys.len(),
+                    pluralize!(var_tys.len()),
+                ),
+            );
+            return;
+        }
+        for ((&var, &var_ty), &ret_ty) in clause.args.iter().zip(var_tys).zip(returns) {
+            if var_ty != ret_ty && !var_ty.references_error() && !ret_ty.references_error() {
+                let span = self.gcx.hir.variable(var).span;
+                self.dcx().emit_err_label(
+                    span,
+                    "mismatched types",
+                    span,
+                    TyConvertError::Incompatible.message(var_ty, ret_ty, self.gcx),
+                );
+            }
+        }
+    }
+
     #[must_use]
     fn check_var(&mut self, id: hir::VariableId) -> Ty<'gcx> {
         self.check_var_(id, true)
@@ -2231,6 +2577,19 @@ impl<'gcx> TypeChecker<'gcx> {
         ty
     }
 
+    /// Fixed-point types are type-checked like any other type, but code cannot be generated for
+    /// them yet, so reject them up front instead of lowering them as integers.
+    fn check_fixed_point_ty(&self, span: Span, ty: ElementaryType) {
+        if !self.gcx.sess.opts.unstable.codegen {
+            return;
+        }
+        self.dcx()
+            .err(format!("fixed-point type `{ty}` is not yet implemented"))
+            .span(span)
+            .note("fixed-point types can be declared, but no code can be generated for them yet")
+            .emit();
+    }
+
     fn check_var_type_size(&self, var: &hir::Variable<'gcx>, ty: Ty<'gcx>) {
         if let Some(loc @ (DataLocation::Memory | DataLocation::Calldata)) = ty.loc()
             && let Some(size) = self.ty_memory_static_size(ty.peel_refs())
@@ -2618,7 +2977,9 @@ impl<'gcx> hir::Visit<'gcx> for TypeChecker<'gcx> {
         match hir_ty.kind {
             hir::TypeKind::Array(array) => {
                 if let Some(size) = array.size {
+                    let prev = std::mem::replace(&mut self.in_array_len, true);
                     let _ = self.expect_ty(size, self.gcx.types.uint(256));
+                    self.in_array_len = prev;
                 }
                 return self.visit_ty(&array.element);
             }
@@ -2630,6 +2991,11 @@ impl<'gcx> hir::Visit<'gcx> for TypeChecker<'gcx> {
                 // size expression of a fixed-array value type).
                 return self.visit_ty(&mapping.value);
             }
+            hir::TypeKind::Elementary(
+                ty @ (ElementaryType::Fixed(..) | ElementaryType::UFixed(..)),
+            ) => {
+                self.check_fixed_point_ty(hir_ty.span, ty);
+            }
             // TODO: https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/analysis/TypeChecker.cpp#L713
             // hir::TypeKind::Function(func) => {
             //     if func.visibility == hir::Visibility::External {
@@ -2709,9 +3075,18 @@ impl<'gcx> hir::Visit<'gcx> for TypeChecker<'gcx> {
                 }
                 return ControlFlow::Continue(());
             }
-            hir::StmtKind::AssemblyBlock(block) => {
+            hir::StmtKind::Try(try_) => {
+                self.check_try(try_);
+                for clause in try_.clauses {
+                    for stmt in clause.block.iter() {
+                        self.visit_stmt(stmt)?;
+                    }
+                }
+                return ControlFlow::Continue(());
+            }
+            hir::StmtKind::AssemblyBlock(assembly) => {
                 let prev = std::mem::replace(&mut self.in_yul, true);
-                for stmt in block.stmts {
+                for stmt in assembly.block.stmts {
                     self.visit_stmt(stmt)?;
                 }
                 self.in_yul = prev;
@@ -2857,8 +3232,15 @@ fn valid_delete(ty: Ty<'_>) -> bool {
     }
 
     match ty.kind {
-        TyKind::Elementary(_) | TyKind::Contract(_) | TyKind::Enum(_) | TyKind::Fn(_) => true,
-        TyKind::Ref(_, loc) => !matches!(loc, DataLocation::Calldata),
+        TyKind::Elementary(_)
+        | TyKind::Contract(_)
+        | TyKind::Enum(_)
+        | TyKind::Udvt(..)
+        | TyKind::Fn(_) => true,
+        // Mappings cannot be deleted on their own, only as part of a struct.
+        TyKind::Ref(inner, loc) => {
+            !matches!(loc, DataLocation::Calldata) && !matches!(inner.kind, TyKind::Mapping(..))
+        }
 
         TyKind::Err(_) => true,
 
@@ -2867,7 +3249,7 @@ fn valid_delete(ty: Ty<'_>) -> bool {
 }
 
 fn is_calldata_sliceable(ty: Ty<'_>) -> bool {
-    ty.is_ref_at(DataLocation::Calldata)
+    (ty.is_ref_at(DataLocation::Calldata) && ty.peel_refs().is_dynamically_sized())
         || matches!(ty.kind, TyKind::Slice(array) if array.data_stored_in(DataLocation::Calldata))
 }
 
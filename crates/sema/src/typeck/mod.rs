@@ -17,20 +17,30 @@ mod view_pure_checker;
 
 pub(crate) fn check(gcx: Gcx<'_>) {
     let mut typeck_results = TypeckResults::default();
-    parallel!(gcx.sess, gcx.hir.par_contract_ids().for_each(|id| check_contract(gcx, id)), {
-        typeck_results = gcx
-            .hir
-            .par_source_ids()
-            .map(|id| {
-                check_source(gcx, id);
-                // TODO: Parallelize more.
-                checker::check(gcx, id)
-            })
-            .reduce(TypeckResults::default, |mut a, b| {
-                merge_typeck_results(gcx, &mut a, b);
-                a
-            });
-    },);
+    parallel!(
+        gcx.sess,
+        gcx.hir
+            .par_contract_ids()
+            .filter(|&id| gcx.is_source_selected(gcx.hir.contract(id).source))
+            .for_each(|id| check_contract(gcx, id)),
+        {
+            typeck_results = gcx
+                .hir
+                .par_source_ids()
+                .filter(|&id| gcx.is_source_selected(id))
+                .map(|id| {
+                    check_source(gcx, id);
+                    // TODO: Parallelize more.
+                    let results = checker::check(gcx, id);
+                    gcx.progress.file_analyzed();
+                    results
+                })
+                .reduce(TypeckResults::default, |mut a, b| {
+                    merge_typeck_results(gcx, &mut a, b);
+                    a
+                });
+        },
+    );
     gcx.set_typeck_results(typeck_results);
     view_pure_checker::check(gcx);
 }
@@ -41,6 +51,8 @@ fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
     check_payable_fallback_without_receive(gcx, id);
     check_external_type_clashes(gcx, id);
     check_receive_function(gcx, id);
+    check_fallback_function(gcx, id);
+    check_base_constructor_arguments(gcx, id);
     for using in gcx.hir.contract(id).usings {
         check_using_directive(gcx, using);
     }
@@ -301,17 +313,25 @@ fn check_duplicate_definitions(gcx: Gcx<'_>, scope: &Declarations) {
             for (j, &other_decl) in decls.iter().enumerate().skip(i + 1) {
                 if is_duplicate(decl, other_decl) {
                     reported.insert(j);
-                    duplicates.push(other_decl.span);
+                    duplicates.push(other_decl);
                 }
             }
             if !duplicates.is_empty() {
+                let Res::Item(item) = decl.res else { unreachable!() };
                 let msg = format!(
                     "{} with same name and parameter types declared twice",
                     decl.description()
                 );
-                let mut err = gcx.dcx().err(msg).span(decl.span);
+                let code = if item.is_event() { error_code!(5883) } else { error_code!(1686) };
+                let mut err = gcx.dcx().err(msg).code(code).span(decl.span);
+                let mut help = None;
                 for duplicate in duplicates {
-                    err = err.span_note(duplicate, "other declaration");
+                    err = err.span_note(duplicate.span, "other declaration");
+                    let Res::Item(other) = duplicate.res else { unreachable!() };
+                    help = help.or_else(|| duplicate_definition_help(gcx, item, other));
+                }
+                if let Some(help) = help {
+                    err = err.help(help);
                 }
                 err.emit();
             }
@@ -319,6 +339,18 @@ fn check_duplicate_definitions(gcx: Gcx<'_>, scope: &Declarations) {
     }
 }
 
+/// Explains why two declarations that look different are still considered duplicates.
+fn duplicate_definition_help(gcx: Gcx<'_>, a: hir::ItemId, b: hir::ItemId) -> Option<&'static str> {
+    let (a, b) = (gcx.type_of_item(a), gcx.type_of_item(b));
+    if a.parameters() != b.parameters() {
+        Some("parameter data locations are not part of the signature")
+    } else if a.returns() != b.returns() {
+        Some("functions cannot be overloaded by return types alone")
+    } else {
+        None
+    }
+}
+
 fn same_external_params<'gcx>(gcx: Gcx<'gcx>, a: Ty<'gcx>, b: Ty<'gcx>) -> bool {
     let key = |ty: Ty<'gcx>| ty.as_externally_callable_function(false, gcx).parameters().unwrap();
     key(a) == key(b)
@@ -408,6 +440,116 @@ fn check_receive_function(gcx: Gcx<'_>, contract_id: hir::ContractId) {
     }
 }
 
+fn check_fallback_function(gcx: Gcx<'_>, contract_id: hir::ContractId) {
+    let contract = gcx.hir.contract(contract_id);
+    let Some(fallback) = contract.fallback else { return };
+    let f = gcx.hir.function(fallback);
+    // Inherited fallback functions are checked in the contract that declares them.
+    if f.contract != Some(contract_id) {
+        return;
+    }
+
+    if contract.kind.is_library() {
+        gcx.dcx()
+            .err("libraries cannot have fallback functions")
+            .code(error_code!(5982))
+            .span(gcx.item_span(fallback))
+            .emit();
+        return;
+    }
+
+    if f.visibility != Visibility::External {
+        gcx.dcx()
+            .err("fallback function must be defined as `external`")
+            .code(error_code!(1159))
+            .span(gcx.item_span(fallback))
+            .emit();
+    }
+
+    // Either `fallback()` or `fallback(bytes calldata) returns (bytes memory)`.
+    let is_bytes = |var: hir::VariableId, loc: DataLocation| {
+        let ty = gcx.type_of_item(var.into());
+        ty.loc() == Some(loc)
+            && matches!(ty.peel_refs().kind, TyKind::Elementary(hir::ElementaryType::Bytes))
+    };
+    let valid = match (f.parameters, f.returns) {
+        ([], []) => true,
+        (&[input], &[output]) => {
+            is_bytes(input, DataLocation::Calldata) && is_bytes(output, DataLocation::Memory)
+        }
+        _ => false,
+    };
+    if !valid {
+        gcx.dcx()
+            .err("invalid fallback function signature")
+            .code(error_code!(5570))
+            .span(gcx.item_span(fallback))
+            .help(
+                "fallback functions must have the signature `fallback()` or \
+                 `fallback(bytes calldata) returns (bytes memory)`",
+            )
+            .emit();
+    }
+}
+
+/// Checks that the arguments of each base constructor are given exactly once in the inheritance
+/// hierarchy, unless the contract is abstract.
+///
+/// Duplicates within a single contract are already reported during name resolution.
+fn check_base_constructor_arguments(gcx: Gcx<'_>, contract_id: hir::ContractId) {
+    let contract = gcx.hir.contract(contract_id);
+    if contract.linearization_failed() {
+        return;
+    }
+
+    let mut given = FxIndexMap::default();
+    for &c_id in contract.linearized_bases {
+        let c = gcx.hir.contract(c_id);
+        for (&base_id, base) in c.linearized_bases.iter().skip(1).zip(c.linearized_bases_args) {
+            // `is Base` without parentheses does not give any arguments.
+            let Some(base) = *base else { continue };
+            if base.args.is_dummy() {
+                continue;
+            }
+            match given.get(&base_id) {
+                Some(&prev) if !prev.args.is_empty() && !base.args.is_empty() => {
+                    gcx.dcx()
+                        .err("base constructor arguments given twice")
+                        .code(error_code!(3364))
+                        .span(contract.name.span)
+                        .span_note(prev.span, "first constructor call is here")
+                        .span_note(base.span, "second constructor call is here")
+                        .emit();
+                }
+                Some(_) => {}
+                None => {
+                    given.insert(base_id, base);
+                }
+            }
+        }
+    }
+
+    if !contract.kind.is_contract() || contract.is_abstract() {
+        return;
+    }
+    for &base_id in &contract.linearized_bases[1..] {
+        let Some(ctor) = gcx.hir.contract(base_id).ctor else { continue };
+        if gcx.hir.function(ctor).parameters.is_empty() || given.contains_key(&base_id) {
+            continue;
+        }
+        gcx.dcx()
+            .err(format!(
+                "no arguments given for the constructor of base contract `{}`",
+                gcx.hir.contract(base_id).name
+            ))
+            .code(error_code!(3656))
+            .span(contract.name.span)
+            .span_note(gcx.item_span(ctor), "base constructor defined here")
+            .help("pass the arguments in the inheritance list or in the constructor, or mark the contract as `abstract`")
+            .emit();
+    }
+}
+
 /// Checks for violation of maximum storage size to ensure slot allocation algorithms works.
 ///
 /// Reference: <https://github.com/argotorg/solidity/blob/03e2739809769ae0c8d236a883aadc900da60536/libsolidity/analysis/ContractLevelChecker.cpp#L556C1-L570C2>
@@ -22,7 +22,10 @@ pub(super) fn check(gcx: Gcx<'_>) {
         .hir
         .par_functions()
         .filter(|function| {
-            !function.kind.is_modifier() && !function.is_getter() && !function.is_yul
+            !function.kind.is_modifier()
+                && !function.is_getter()
+                && !function.is_yul
+                && gcx.is_source_selected(function.source)
         })
         .map(|function| ViewPureChecker::new(gcx, &function_effects).check_function(function))
         .collect::<Vec<_>>();
@@ -0,0 +1,212 @@
+#!/usr/bin/env python3
+"""Report which solc error codes Solar reproduces on the solc syntax test corpus.
+
+Each test's expected solc errors are compared with the diagnostic codes Solar emits for
+the same file. For every solc error code, a test is counted as:
+
+- reproduced: Solar emits the same code;
+- different: Solar reports an error, but not with the expected code;
+- missed: Solar reports no error at all.
+
+Examples:
+    cargo build --release --bin solar
+    ./scripts/solc_coverage.py
+    ./scripts/solc_coverage.py --check
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+import os
+import re
+import subprocess
+import sys
+from concurrent.futures import ThreadPoolExecutor
+from dataclasses import dataclass, field
+from pathlib import Path
+
+
+ROOT = Path(__file__).resolve().parents[1]
+DEFAULT_TESTS = ROOT / "testdata" / "solidity" / "test" / "libsolidity" / "syntaxTests"
+DEFAULT_SOLAR = ROOT / "target" / "release" / "solar"
+DEFAULT_OUTPUT = ROOT / "docs" / "SOLC_COVERAGE.md"
+
+DELIMITER = "// ----"
+# TypeError 2519: (80-89): This declaration shadows an existing declaration.
+EXPECTATION_RE = re.compile(r"^//\s*(\w+Error)\s+(\d+):")
+TIMEOUT = 30
+
+
+@dataclass
+class CodeStats:
+    kind: str
+    expected: int = 0
+    reproduced: int = 0
+    different: int = 0
+    missed: int = 0
+
+
+@dataclass
+class Report:
+    tests: int = 0
+    skipped: int = 0
+    codes: dict[str, CodeStats] = field(default_factory=dict)
+
+
+def main() -> int:
+    args = parse_args()
+    if not args.solar.exists():
+        print(f"solar binary not found at {args.solar}; build it first", file=sys.stderr)
+        return 1
+    if not args.tests.is_dir():
+        print(
+            f"test directory not found at {args.tests}; "
+            "run `git submodule update --init --checkout testdata/solidity`",
+            file=sys.stderr,
+        )
+        return 1
+
+    report = Report()
+    tests = []
+    for path in sorted(args.tests.rglob("*.sol")):
+        src = path.read_text(errors="replace")
+        # Multi-source tests need the file splitting done by the tester; skip them.
+        if "==== Source:" in src or "==== ExternalSource:" in src:
+            report.skipped += 1
+            continue
+        expected = expected_errors(src)
+        if expected:
+            tests.append((path, expected))
+
+    with ThreadPoolExecutor(max_workers=args.jobs) as executor:
+        emitted = executor.map(lambda test: solar_codes(args.solar, test[0]), tests)
+        for (path, expected), result in zip(tests, emitted):
+            if result is None:
+                report.skipped += 1
+                continue
+            report.tests += 1
+            has_errors, codes = result
+            for kind, code in expected:
+                stats = report.codes.setdefault(code, CodeStats(kind))
+                stats.expected += 1
+                if code in codes:
+                    stats.reproduced += 1
+                elif has_errors:
+                    stats.different += 1
+                else:
+                    stats.missed += 1
+
+    rendered = render(report, args.tests)
+    if args.check:
+        if not args.output.exists() or args.output.read_text() != rendered:
+            print(f"{args.output} is out of date; rerun {Path(__file__).name}", file=sys.stderr)
+            return 1
+        return 0
+    args.output.write_text(rendered)
+    print(f"Wrote {args.output}")
+    return 0
+
+
+def expected_errors(src: str) -> list[tuple[str, str]]:
+    """Returns the `(kind, code)` pairs of the errors expected by a solc test."""
+    idx = src.rfind(DELIMITER)
+    if idx == -1:
+        return []
+    errors = []
+    for line in src[idx:].splitlines()[1:]:
+        if match := EXPECTATION_RE.match(line.strip()):
+            errors.append((match.group(1), match.group(2)))
+    return errors
+
+
+def solar_codes(solar: Path, path: Path) -> tuple[bool, set[str]] | None:
+    """Runs Solar on a file, returning whether it errored and the emitted diagnostic codes."""
+    cmd = [str(solar), "-j1", "--error-format=rustc-json", "--no-warnings", str(path)]
+    try:
+        output = subprocess.run(
+            cmd, cwd=path.parent, capture_output=True, text=True, timeout=TIMEOUT
+        )
+    except subprocess.TimeoutExpired:
+        return None
+
+    has_errors = False
+    codes = set()
+    for line in output.stderr.splitlines():
+        try:
+            diagnostic = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        if not isinstance(diagnostic, dict) or not diagnostic.get("level", "").startswith("error"):
+            continue
+        has_errors = True
+        if code := diagnostic.get("code"):
+            codes.add(code["code"])
+    return has_errors, codes
+
+
+def render(report: Report, tests: Path) -> str:
+    codes = sorted(report.codes.items(), key=lambda item: (item[1].kind, int(item[0])))
+    expected = sum(stats.expected for _, stats in codes)
+    reproduced = sum(stats.reproduced for _, stats in codes)
+    full = sum(1 for _, stats in codes if stats.reproduced == stats.expected)
+    none = sum(1 for _, stats in codes if stats.reproduced == 0)
+
+    lines = [
+        "# solc Error Code Coverage",
+        "",
+        "This file is generated by `scripts/solc_coverage.py`; do not edit it by hand.",
+        "",
+        f"It compares the errors expected by the solc tests in `{display_path(tests)}`",
+        "with the diagnostic codes `solar` emits for the same files.",
+        "",
+        "## Summary",
+        "",
+        "| Metric | Value |",
+        "| --- | --- |",
+        f"| Tests checked | {report.tests} |",
+        f"| Tests skipped | {report.skipped} |",
+        f"| Error codes | {len(codes)} |",
+        f"| Fully reproduced codes | {full} |",
+        f"| Never reproduced codes | {none} |",
+        f"| Reproduced expectations | {reproduced} / {expected} ({percent(reproduced, expected)}) |",
+        "",
+        "## Codes",
+        "",
+        "| Code | Kind | Expected | Reproduced | Different | Missed |",
+        "| --- | --- | --- | --- | --- | --- |",
+    ]
+    for code, stats in codes:
+        lines.append(
+            f"| {code} | {stats.kind} | {stats.expected} | {stats.reproduced} "
+            f"| {stats.different} | {stats.missed} |"
+        )
+    return "\n".join(lines) + "\n"
+
+
+def display_path(path: Path) -> str:
+    return str(path.relative_to(ROOT)) if path.is_relative_to(ROOT) else str(path)
+
+
+def percent(n: int, total: int) -> str:
+    return f"{n / total:.1%}" if total else "n/a"
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter
+    )
+    parser.add_argument("--solar", type=Path, default=DEFAULT_SOLAR, help="path to `solar`")
+    parser.add_argument("--tests", type=Path, default=DEFAULT_TESTS, help="solc test directory")
+    parser.add_argument("--output", type=Path, default=DEFAULT_OUTPUT, help="report path")
+    parser.add_argument("--jobs", type=int, default=os.cpu_count(), help="parallel jobs")
+    parser.add_argument(
+        "--check", action="store_true", help="fail if the checked-in report is out of date"
+    )
+    args = parser.parse_args()
+    args.tests = args.tests.resolve()
+    return args
+
+
+if __name__ == "__main__":
+    sys.exit(main())
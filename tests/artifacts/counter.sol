@@ -0,0 +1,7 @@
+contract Counter {
+    uint256 public count;
+
+    function increment() external {
+        count += 1;
+    }
+}
@@ -0,0 +1,21 @@
+library Math {
+    function max(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a > b ? a : b;
+    }
+}
+
+contract Owned {
+    address owner;
+}
+
+contract Vault is Owned {
+    using Math for uint256;
+
+    uint128 balance;
+    uint64 deposits;
+
+    function deposit(uint128 amount) external {
+        balance = uint128(uint256(balance).max(amount));
+        deposits += 1;
+    }
+}
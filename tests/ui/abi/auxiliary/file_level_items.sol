@@ -0,0 +1,4 @@
+event Transfer(address indexed from, uint256 value);
+event Deposited(uint256 amount);
+
+error Failed(uint256 code);
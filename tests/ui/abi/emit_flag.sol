@@ -0,0 +1,9 @@
+//@ emit: abi, hashes
+//@ compile-flags: --pretty-json
+
+// The `emit` test flag is equivalent to `--emit`.
+contract C {
+    function balanceOf(address) external pure returns (uint256) {
+        return 0;
+    }
+}
@@ -0,0 +1,29 @@
+//@ compile-flags: --emit=abi --pretty-json
+
+import "./auxiliary/file_level_items.sol" as Items;
+
+error Unauthorized(address caller);
+error Unused();
+
+function requireOwner(address owner) view {
+    if (msg.sender != owner) revert Unauthorized(msg.sender);
+}
+
+// File-level events and errors are part of the ABI when the contract uses them. An event with the
+// same signature as one declared in the contract is only listed once.
+contract C {
+    event Transfer(address indexed from, uint256 value);
+
+    address owner;
+
+    function f(uint256 value) external {
+        requireOwner(owner);
+        emit Transfer(msg.sender, value);
+        emit Items.Transfer(msg.sender, value);
+        emit Items.Deposited(value);
+    }
+
+    function g() external pure {
+        revert Items.Failed(1);
+    }
+}
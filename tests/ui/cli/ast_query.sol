@@ -0,0 +1,15 @@
+//@ compile-flags: ast-query contract[name=Foo]>function[visibility=external]
+
+contract Foo {
+    function a() external {
+        b();
+    }
+
+    function b() public {}
+
+    function c() external view {}
+}
+
+contract Bar {
+    function a() external {}
+}
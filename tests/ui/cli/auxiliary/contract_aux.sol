@@ -0,0 +1,5 @@
+contract A {}
+
+contract Helper {
+    function help() external {}
+}
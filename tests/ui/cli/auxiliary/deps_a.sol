@@ -0,0 +1,3 @@
+import "./deps_b.sol";
+
+contract A {}
@@ -0,0 +1,3 @@
+contract New {
+    function name() external {}
+}
@@ -0,0 +1,3 @@
+contract Token {
+    function symbol() external {}
+}
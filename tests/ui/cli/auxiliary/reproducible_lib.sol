@@ -0,0 +1,3 @@
+contract Lib {
+    function lib() external {}
+}
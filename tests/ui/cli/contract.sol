@@ -0,0 +1,9 @@
+//@ compile-flags: --emit=hashes --contract A
+
+contract A {
+    function a() external {}
+}
+
+contract B {
+    function b() external {}
+}
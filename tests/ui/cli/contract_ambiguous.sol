@@ -0,0 +1,6 @@
+//@ compile-flags: --emit=hashes --contract A
+//~? ERROR: `--contract A` matches more than one contract
+
+import "./auxiliary/contract_aux.sol" as Aux;
+
+contract A {}
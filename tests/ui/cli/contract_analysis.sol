@@ -0,0 +1,12 @@
+//@ compile-flags: --emit=hashes --contract Helper
+
+// Only the sources of the selected contracts and the files they import are analyzed, so the type
+// error below is not reported.
+
+import "./auxiliary/contract_aux.sol";
+
+contract C {
+    function f() external pure returns (uint256) {
+        return true;
+    }
+}
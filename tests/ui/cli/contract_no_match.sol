@@ -0,0 +1,6 @@
+//@ compile-flags: --emit=hashes --contract C
+//~? ERROR: `--contract C` did not match any contract
+
+contract A {}
+
+contract B {}
@@ -0,0 +1,6 @@
+//@ compile-flags: --emit=deps,deps-json --pretty-json
+
+import "./auxiliary/deps_a.sol";
+import {B} from "./auxiliary/deps_b.sol";
+
+contract C is A, B {}
@@ -0,0 +1,17 @@
+//@events
+
+// Fields are matched in pairs that are adjacent whether or not JSON object keys are sorted.
+
+// CHECK: "event":"file_parsed","file":"{{.*}}tests/ui/cli/events.sol"
+// CHECK: "event":"pass_timing","pass":"parsing"
+// CHECK: "event":"item_lowered","file":"{{.*}}tests/ui/cli/events.sol","kind":"contract","name":"C"
+// CHECK: "event":"item_lowered","file":"{{.*}}tests/ui/cli/events.sol","kind":"function","name":"f"
+// CHECK: "event":"pass_timing","pass":"lowering"
+// CHECK: "event":"diag_emitted","level":"warning"
+// CHECK-SAME: "message":"function state mutability can be restricted to pure"
+// CHECK: "event":"pass_timing","pass":"analysis"
+
+contract C {
+    function f() public returns (uint256) { return 1; }
+    //~^ WARN: function state mutability can be restricted to pure
+}
@@ -0,0 +1,13 @@
+//@compile-flags: -Zcodegen --evm-version shanghai --stop-after=codegen
+//@exit-status: 5
+// Code generation errors exit with code 5.
+
+contract C {
+    function copy() external pure {
+        assembly {
+            mcopy(0x80, 0xa0, 0x20)
+            //~^ ERROR: codegen requires Cancun-compatible EVM for memory copy
+            //~| HELP: compile with `--evm-version cancun` or newer
+        }
+    }
+}
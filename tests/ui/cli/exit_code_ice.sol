@@ -0,0 +1,8 @@
+//@compile-flags: -Ztreat-err-as-bug=1
+//@exit-status: 101
+//@normalize-stderr-test: "(?s)thread '.*" -> "PANIC"
+// Internal compiler errors exit with code 101.
+
+function f() {
+    a; //~ ICE: unresolved symbol
+}
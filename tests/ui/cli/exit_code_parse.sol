@@ -0,0 +1,6 @@
+//@exit-status: 3
+// Parse errors exit with code 3.
+
+function f() {
+    f{}(); //~ ERROR: expected one of
+}
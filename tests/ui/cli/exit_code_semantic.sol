@@ -0,0 +1,6 @@
+//@exit-status: 4
+// Semantic errors, such as name resolution and type errors, exit with code 4.
+
+function f() {
+    a; //~ ERROR: unresolved symbol
+}
@@ -0,0 +1,31 @@
+//@compile-flags: --pretty-json
+//@out-artifact: out_artifact.sol/Counter.json
+
+// CHECK: {
+// CHECK-NEXT:   "abi": [
+// CHECK-NEXT:     {
+// CHECK-NEXT:       "type": "function",
+// CHECK-NEXT:       "name": "increment",
+// CHECK:          "type": "function",
+// CHECK-NEXT:       "name": "number",
+// CHECK:        "methodIdentifiers": {
+// CHECK-NEXT:     "increment()": "d09de08a",
+// CHECK-NEXT:     "number()": "8381f58a"
+// CHECK-NEXT:   },
+// CHECK-NEXT:   "rawMetadata": "{{.+}}",
+// CHECK-NEXT:   "metadata": {
+// CHECK:          "compilationTarget": {
+// CHECK-NEXT:       "{{.*}}tests/ui/cli/out_artifact.sol": "Counter"
+// CHECK:          "optimizer": {
+// CHECK-NEXT:       "enabled": false
+// CHECK-NEXT:     },
+// CHECK:        "id": 0
+// CHECK-NEXT: }
+
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+}
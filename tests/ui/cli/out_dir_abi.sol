@@ -0,0 +1,27 @@
+//@compile-flags: --emit=abi --pretty-json
+//@out-dir-file: out_dir_abi.sol/Counter.abi
+
+// With `--out-dir`, the ABI of each contract is also written to `<file name>/<contract name>.abi`.
+
+// CHECK: [
+// CHECK-NEXT:   {
+// CHECK-NEXT:     "type": "function",
+// CHECK-NEXT:     "name": "increment",
+// CHECK-NEXT:     "inputs": [],
+// CHECK-NEXT:     "outputs": [],
+// CHECK-NEXT:     "stateMutability": "nonpayable"
+// CHECK-NEXT:   },
+// CHECK-NEXT:   {
+// CHECK-NEXT:     "type": "function",
+// CHECK-NEXT:     "name": "number",
+// CHECK:          "stateMutability": "view"
+// CHECK-NEXT:   }
+// CHECK-NEXT: ]
+
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+}
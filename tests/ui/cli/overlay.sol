@@ -0,0 +1,11 @@
+//@ compile-flags: --emit=hashes
+//@ overlay: auxiliary/overlay_token.sol=auxiliary/overlay_token_buffer.sol
+//@ overlay: auxiliary/overlay_new.sol=auxiliary/overlay_new_buffer.sol
+
+// Imported sources are read from their overlays but keep their own paths: `overlay_token.sol` is
+// replaced by an unsaved buffer, and `overlay_new.sol` only exists as one.
+
+import {Token} from "./auxiliary/overlay_token.sol";
+import {New} from "./auxiliary/overlay_new.sol";
+
+contract C is Token, New {}
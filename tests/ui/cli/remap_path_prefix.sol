@@ -0,0 +1,11 @@
+//@ compile-flags: --emit=hashes
+//@ remap-path-prefix: .=/src
+//@ remap-path-prefix: auxiliary=/lib
+
+// When several prefixes match a path, the last one wins.
+
+import {Lib} from "./auxiliary/remap_path_prefix_lib.sol";
+
+contract C is Lib {
+    function c() external {}
+}
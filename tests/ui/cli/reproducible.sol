@@ -0,0 +1,10 @@
+//@ compile-flags: --emit=hashes --reproducible
+//@ base-path: .
+
+// Source paths are displayed relative to the base path instead of as absolute paths.
+
+import {Lib} from "./auxiliary/reproducible_lib.sol";
+
+contract C is Lib {
+    function c() external {}
+}
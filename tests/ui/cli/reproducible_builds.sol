@@ -0,0 +1,13 @@
+//@ compile-flags: --reproducible
+//@ check-reproducible: abi,hashes,metadata,storage-layout
+
+// Builds from different directories write byte-identical outputs, artifacts and events.
+
+import {Lib} from "./auxiliary/reproducible_lib.sol";
+
+/// @title A contract.
+contract C is Lib {
+    uint256 x;
+
+    function c() external {}
+}
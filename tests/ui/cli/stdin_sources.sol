@@ -0,0 +1,8 @@
+//@ compile-flags: --emit=hashes -
+//@ stdin: auxiliary/stdin_sources.json
+
+// Sources passed on stdin as `{"sources": {...}}` keep their names and can import each other.
+
+contract C {
+    function c() external {}
+}
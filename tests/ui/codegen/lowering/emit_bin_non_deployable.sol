@@ -0,0 +1,9 @@
+//@compile-flags: -Zcodegen --emit=bin,bin-runtime
+
+interface IToken {
+    function balanceOf(address account) external view returns (uint256);
+}
+
+abstract contract Base is IToken {
+    function owner() public view virtual returns (address);
+}
@@ -1,8 +1,8 @@
-//@compile-flags: -Zcodegen -Zdump=evm-ir-runtime
+//@compile-flags: -Zcodegen -Zdump=mir
 //@ filecheck:
 
 // An explicit enum conversion written through its container, `Container.Enum(x)`
-// (the callee is a member access resolving to an enum), is the identity on the
+// (the callee is a member access resolving to an enum), range-checks the
 // underlying integer — matching the plain `Enum(x)` (`Ident` callee) path.
 // Used by aave-v3-core FlashLoanLogic:
 //   `DataTypes.InterestRateMode(params.interestRateModes[i]) == ...NONE`.
@@ -17,10 +17,11 @@ library DataTypes {
 }
 
 contract E {
-    // CHECK: push 0xbc477c04
-    // CHECK: calldataload
-    // CHECK-NEXT: iszero
-    // CHECK: return
+    // CHECK-LABEL: fn @isNone{{[( ]}}
+    // CHECK: [[IN_RANGE:v[0-9]+]] = lt arg0, 3
+    // CHECK: jumpi [[IN_RANGE]],
+    // CHECK: mstore 4, 33
+    // CHECK: eq arg0, 0
     function isNone(uint256 x) external pure returns (bool) {
         return DataTypes.Mode(x) == DataTypes.Mode.NONE;
     }
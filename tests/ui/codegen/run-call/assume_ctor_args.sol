@@ -0,0 +1,22 @@
+//@compile-flags: --assume-ctor-args 0x000000000000000000000000000000000000000000000000000000000000002a
+//@ run-call: values; constructor=[42] => 7, 42, 44
+
+// `fixedValue` and `fromArg` are folded into the runtime code: one is
+// initialized with a constant and the other is assigned the assumed argument.
+// `computed` is derived from the argument at construction and keeps its
+// placeholder.
+
+contract AssumeCtorArgs {
+    uint256 immutable fixedValue = 7;
+    uint256 immutable fromArg;
+    uint256 immutable computed;
+
+    constructor(uint256 arg) {
+        fromArg = arg;
+        computed = arg + 2;
+    }
+
+    function values() external view returns (uint256, uint256, uint256) {
+        return (fixedValue, fromArg, computed);
+    }
+}
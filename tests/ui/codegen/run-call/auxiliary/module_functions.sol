@@ -0,0 +1,7 @@
+function double(uint256 value) pure returns (uint256) {
+    return value * 2;
+}
+
+function add(uint256 a, uint256 b) pure returns (uint256) {
+    return a + b;
+}
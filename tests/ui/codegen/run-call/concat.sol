@@ -0,0 +1,22 @@
+//@ run-call: greet "solar" => "hello, solar!"
+//@ run-call: join 0x0102, 0x03 => 0x0102030405
+//@ run-call: sameGreeting "solar" => true
+//@ run-call: sameGreeting "solc" => false
+
+contract Concat {
+    function greet(string calldata name) external pure returns (string memory) {
+        string memory suffix = "!";
+        return string.concat("hello, ", name, suffix);
+    }
+
+    function join(bytes memory a, bytes calldata b) external pure returns (bytes memory) {
+        bytes2 tail = 0x0405;
+        return bytes.concat(a, b, tail);
+    }
+
+    function sameGreeting(string calldata name) external pure returns (bool) {
+        string memory expected = "hello, solar!";
+        return keccak256(bytes(string.concat("hello, ", name, "!")))
+            == keccak256(bytes(expected));
+    }
+}
@@ -0,0 +1,50 @@
+//@ run-call: min => 0
+//@ run-call: max => 2
+//@ run-call: toEnum 2 => 2
+//@ run-call: qualifiedToEnum 1 => 1
+//@ run-call: toUint 1 => 1
+//@ run-call-fail: toEnum 3 => 0x4e487b710000000000000000000000000000000000000000000000000000000000000021
+//@ run-call-fail: qualifiedToEnum 255 => 0x4e487b710000000000000000000000000000000000000000000000000000000000000021
+//@ run-call-fail: fromConstant() => 0x4e487b710000000000000000000000000000000000000000000000000000000000000021
+
+library Lights {
+    enum Light {
+        Red,
+        Yellow,
+        Green
+    }
+}
+
+contract EnumConversions {
+    enum E {
+        A,
+        B,
+        C
+    }
+
+    uint8 constant OUT_OF_RANGE = 3;
+
+    function min() external pure returns (E) {
+        return type(E).min;
+    }
+
+    function max() external pure returns (E) {
+        return type(E).max;
+    }
+
+    function toEnum(uint256 value) external pure returns (E) {
+        return E(value);
+    }
+
+    function qualifiedToEnum(uint8 value) external pure returns (Lights.Light) {
+        return Lights.Light(value);
+    }
+
+    function toUint(E value) external pure returns (uint8) {
+        return uint8(value);
+    }
+
+    function fromConstant() external pure returns (E) {
+        return E(OUT_OF_RANGE);
+    }
+}
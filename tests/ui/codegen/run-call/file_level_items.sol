@@ -0,0 +1,33 @@
+//@ run-call: errorSelector => 0xc77ea641
+//@ run-call: eventSelector => 0x2a89b2e3d580398d6dc2db5e0f336b52602bbaa51afa9bb5cdf59239cf0d2bea
+//@ run-call: deposit 3 => 3
+//@ run-call-fail: deposit 11 => 0x1f4d5abe000000000000000000000000000000000000000000000000000000000000000b
+//@ run-call-fail: fail() => 0xc77ea6410000000000000000000000000000000000000000000000000000000000000007
+
+import "../../abi/auxiliary/file_level_items.sol" as Items;
+
+error TooLarge(uint256 value);
+
+function checked(uint256 value) pure returns (uint256) {
+    if (value > 10) revert TooLarge(value);
+    return value;
+}
+
+contract FileLevelItems {
+    function errorSelector() external pure returns (bytes4) {
+        return Items.Failed.selector;
+    }
+
+    function eventSelector() external pure returns (bytes32) {
+        return Items.Deposited.selector;
+    }
+
+    function deposit(uint256 amount) external returns (uint256) {
+        emit Items.Deposited(checked(amount));
+        return amount;
+    }
+
+    function fail() external pure {
+        revert Items.Failed(7);
+    }
+}
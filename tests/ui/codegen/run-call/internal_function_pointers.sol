@@ -0,0 +1,64 @@
+//@ run-call: apply 0, 5 => 6
+//@ run-call: apply 1, 5 => 10
+//@ run-call: fromArray 1, 7 => 14
+//@ run-call: stored 4 => 8
+//@ run-call: pair 3 => 4, 6
+//@ run-call: same => true, false
+//@ run-call-fail: unassigned() => 0x4e487b710000000000000000000000000000000000000000000000000000000000000051
+
+contract InternalFunctionPointers {
+    function(uint256) internal pure returns (uint256) internal op;
+
+    constructor() {
+        op = double;
+    }
+
+    function inc(uint256 x) internal pure returns (uint256) {
+        return x + 1;
+    }
+
+    function double(uint256 x) internal pure returns (uint256) {
+        return x * 2;
+    }
+
+    function split(uint256 x) internal pure returns (uint256, uint256) {
+        return (x + 1, x * 2);
+    }
+
+    function select(uint256 which)
+        internal
+        pure
+        returns (function(uint256) internal pure returns (uint256))
+    {
+        return which == 0 ? inc : double;
+    }
+
+    function apply(uint256 which, uint256 x) external pure returns (uint256) {
+        function(uint256) internal pure returns (uint256) f = select(which);
+        return f(x);
+    }
+
+    function fromArray(uint256 i, uint256 x) external pure returns (uint256) {
+        function(uint256) internal pure returns (uint256)[2] memory fs = [inc, double];
+        return fs[i](x);
+    }
+
+    function stored(uint256 x) external view returns (uint256) {
+        return op(x);
+    }
+
+    function pair(uint256 x) external pure returns (uint256 a, uint256 b) {
+        function(uint256) internal pure returns (uint256, uint256) f = split;
+        (a, b) = f(x);
+    }
+
+    function same() external pure returns (bool, bool) {
+        function(uint256) internal pure returns (uint256) f = inc;
+        return (f == inc, f == double);
+    }
+
+    function unassigned() external pure returns (uint256) {
+        function(uint256) internal pure returns (uint256) f;
+        return f(1);
+    }
+}
@@ -0,0 +1,28 @@
+//@ run-call: eventSelector => 0xcc041d59ea3de703115f9c48edca881edf64a7846913d9519acb3c68f51e5ae9
+//@ run-call: errorSelector => 0x013486ec
+
+// Events and errors declared in a library use ABI types in their signatures, unlike library
+// functions: `E((uint256),uint8)`, not `E(L.S,L.K)`.
+library L {
+    struct S {
+        uint256 a;
+    }
+
+    enum K {
+        A,
+        B
+    }
+
+    event E(S s, K k);
+    error Err(S s, K k);
+}
+
+contract C {
+    function eventSelector() external pure returns (bytes32) {
+        return L.E.selector;
+    }
+
+    function errorSelector() external pure returns (bytes4) {
+        return L.Err.selector;
+    }
+}
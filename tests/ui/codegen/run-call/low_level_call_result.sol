@@ -0,0 +1,34 @@
+//@ run-call: forward 21 => true, 0x000000000000000000000000000000000000000000000000000000000000002a
+//@ run-call: forwardInternal 4 => true, 0x0000000000000000000000000000000000000000000000000000000000000008
+//@ run-call: forwardFailure => false, 0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000046e6f706500000000000000000000000000000000000000000000000000000000
+//@ run-call: sendZero => false
+
+contract LowLevelCallResult {
+    function double(uint256 x) external pure returns (uint256) {
+        return x * 2;
+    }
+
+    function fail() external pure {
+        revert("nope");
+    }
+
+    function forward(uint256 x) external returns (bool, bytes memory) {
+        return address(this).call(abi.encodeWithSelector(this.double.selector, x));
+    }
+
+    function forwardInternal(uint256 x) external view returns (bool, bytes memory) {
+        return staticDouble(x);
+    }
+
+    function forwardFailure() external returns (bool, bytes memory) {
+        return address(this).call(abi.encodeWithSelector(this.fail.selector));
+    }
+
+    function sendZero() external returns (bool) {
+        return payable(address(this)).send(0);
+    }
+
+    function staticDouble(uint256 x) internal view returns (bool, bytes memory) {
+        return address(this).staticcall(abi.encodeWithSelector(this.double.selector, x));
+    }
+}
@@ -0,0 +1,103 @@
+//@ run-call: Modifiers::bounded 5 => 5
+//@ run-call: Modifiers::bounded 11 => 0
+//@ run-call: Modifiers::ordered => 123
+//@ run-call: Modifiers::earlyReturn 7 => 7
+//@ run-call-fail: Modifiers::earlyReturn 6
+//@ run-call: Modifiers::twice => 2
+//@ run-call: Modifiers::overridden => 2
+//@ run-call: Modifiers::nested => 12
+//@ run-call: Modifiers::nestedSlots => 12
+
+contract Base {
+    uint256 internal trace;
+
+    modifier tagged() virtual {
+        trace = 1;
+        _;
+    }
+}
+
+contract Modifiers is Base {
+    uint256 internal counter;
+
+    modifier tagged() override {
+        trace = 2;
+        _;
+    }
+
+    modifier atMost(uint256 limit, uint256 value) {
+        if (value > limit) {
+            return;
+        }
+        _;
+    }
+
+    modifier append(uint256 digit) {
+        trace = trace * 10 + digit;
+        _;
+    }
+
+    modifier settles() {
+        _;
+        require(counter == 7);
+    }
+
+    // Reads its argument after `_`, so applying it twice checks that each
+    // level keeps its own binding.
+    modifier nest(uint256 digit) {
+        trace = trace * 10 + digit;
+        _;
+        require(trace % 10 == digit);
+        trace /= 10;
+    }
+
+    // Same as `nest`, with the argument and a local kept in memory slots.
+    modifier nestSlots(uint256 digit) {
+        digit += 10;
+        uint256 expected = 0;
+        expected += digit - 10;
+        trace = trace * 10 + expected;
+        _;
+        require(trace % 10 == digit - 10 && trace % 10 == expected);
+        trace /= 10;
+    }
+
+    modifier runTwice() {
+        _;
+        _;
+    }
+
+    function bounded(uint256 value) external pure atMost(10, value) returns (uint256 r) {
+        r = value;
+    }
+
+    function ordered() external append(1) append(2) append(3) returns (uint256) {
+        return trace;
+    }
+
+    function earlyReturn(uint256 value) external settles returns (uint256) {
+        counter = value;
+        if (value > 5) {
+            return value;
+        }
+        counter = 0;
+        return 0;
+    }
+
+    function nested() external nest(1) nest(2) returns (uint256) {
+        return trace;
+    }
+
+    function nestedSlots() external nestSlots(1) nestSlots(2) returns (uint256) {
+        return trace;
+    }
+
+    function twice() external runTwice returns (uint256) {
+        counter += 1;
+        return counter;
+    }
+
+    function overridden() external tagged returns (uint256) {
+        return trace;
+    }
+}
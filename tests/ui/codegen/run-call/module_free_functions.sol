@@ -0,0 +1,14 @@
+//@ run-call: double 3 => 6
+//@ run-call: add 2, 5 => 7
+
+import * as M from "./auxiliary/module_functions.sol";
+
+contract ModuleFreeFunctions {
+    function double(uint256 value) external pure returns (uint256) {
+        return M.double(value);
+    }
+
+    function add(uint256 a, uint256 b) external pure returns (uint256) {
+        return M.add(a, b);
+    }
+}
@@ -0,0 +1,27 @@
+//@ run-call: Factory::create "child", 42 => "child", 42
+//@ run-call: Factory::create2 0x0000000000000000000000000000000000000000000000000000000000000007 => true
+
+contract Child {
+    string public label;
+    uint256 public num;
+
+    constructor(string memory label_, uint256 num_) {
+        label = label_;
+        num = num_;
+    }
+}
+
+contract Factory {
+    function create(string calldata label, uint256 num) external returns (string memory, uint256) {
+        Child child = new Child(label, num);
+        return (child.label(), child.num());
+    }
+
+    function create2(bytes32 salt) external returns (bool) {
+        Child child = new Child{salt: salt}("salted", 1);
+        bytes memory initcode = abi.encodePacked(type(Child).creationCode, abi.encode("salted", 1));
+        bytes32 hash =
+            keccak256(abi.encodePacked(bytes1(0xff), address(this), salt, keccak256(initcode)));
+        return address(child) == address(uint160(uint256(hash))) && child.num() == 1;
+    }
+}
@@ -0,0 +1,7 @@
+uint constant A = B; //~ ERROR: unresolved symbol `B`
+// Diagnostics are reported at the same columns as in `bom_crlf.sol`.
+contract C {
+    function f() public pure returns (uint) {
+        return y; //~ ERROR: unresolved symbol `y`
+    }
+}
@@ -0,0 +1,6 @@
+bytes constant b1 = hex"123"; //~ ERROR: odd number of hex nibbles
+bytes constant b2 = hex"12zz"; //~ ERROR: invalid hex digit
+//~| ERROR: invalid hex digit
+bytes constant b3 = hex"0x12"; //~ ERROR: hex prefix is not allowed
+string constant s1 = "héllo"; //~ ERROR: unicode characters are not allowed in string literals
+string constant s2 = unicode"héllo";
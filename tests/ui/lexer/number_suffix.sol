@@ -0,0 +1,8 @@
+contract C {
+    uint256 a = 0x12g3; //~ ERROR: identifier-start is not allowed at the end of a number
+    uint256 b = 123abc; //~ ERROR: identifier-start is not allowed at the end of a number
+    uint256 c = 1.5e3x; //~ ERROR: identifier-start is not allowed at the end of a number
+    uint256 d = 0X12; //~ ERROR: identifier-start is not allowed at the end of a number
+    uint256 e = 0xg; //~ ERROR: no valid digits found for number
+    uint256 f = a + b + c + d + e;
+}
@@ -0,0 +1,9 @@
+//@compile-flags: -Zlints --allow contract-name-capwords,state-visibility,magic-number
+
+contract my_contract {
+    uint256 balance;
+
+    function DoThing() external { //~ WARN: function name `DoThing` does not follow the naming convention
+        balance = 60;
+    }
+}
@@ -0,0 +1,11 @@
+//@compile-flags: -Zlints -Zdeny-lints=reentrancy
+
+contract C {
+    uint256 internal x;
+
+    function f() external {
+        (bool ok, ) = payable(msg.sender).call("");
+        require(ok);
+        x = 1; //~ ERROR: state variable `x` is written after an external call
+    }
+}
@@ -0,0 +1,32 @@
+//@compile-flags: -Zlints
+
+contract C {
+    uint256[] internal xs;
+    uint256 internal total;
+    uint256 internal count;
+
+    function params(uint256[] memory a, uint256[] memory b, uint256[] memory c) external {
+        //~^ WARN: parameter `a` is copied to memory but never modified
+        //~| WARN: parameter `b` is copied to memory but never modified
+        c[0] = 1;
+        g(b);
+        total = a[0];
+    }
+
+    function lengths() external view returns (uint256 n) {
+        for (uint256 i = 0; i < xs.length; ++i) n += i; //~ WARN: `xs.length` is read from storage
+    }
+
+    function values(uint256 n) external view returns (uint256 sum) {
+        for (uint256 i = 0; i < n; ++i) sum += count; //~ WARN: state variable `count` is read
+    }
+
+    function postfix(uint256 n) external {
+        for (uint256 i = 0; i < n; i++) { //~ WARN: postfix `++` keeps a copy of the previous value
+            total += i;
+        }
+        count--; //~ WARN: postfix `--` keeps a copy of the previous value
+    }
+
+    function g(uint256[] memory) internal {}
+}
@@ -0,0 +1,23 @@
+//@compile-flags: -Zlints
+
+contract C {
+    uint256 internal x;
+    uint256[] internal xs;
+
+    constructor(uint256 a) {
+        if (a == 0) return; //~ WARN: `return` in a constructor
+        x = a;
+    }
+
+    function f(uint256 a, bool b) external {
+        x = x; //~ WARN: assignment of a value to itself has no effect
+        xs[a] = xs[a]; //~ WARN: assignment of a value to itself has no effect
+        x += x;
+        a == 1; //~ WARN: comparison has no effect
+        (x < a); //~ WARN: comparison has no effect
+        if (b == true) {} //~ WARN: comparison with a boolean literal
+        if (false == b) {} //~ WARN: comparison with a boolean literal
+        if (a == 0) {} else if (b) {} else if ((a) == 0) {} //~ WARN: duplicate condition
+        if (a == 0) {} else if (b) {}
+    }
+}
@@ -0,0 +1,55 @@
+//@compile-flags: -Zlints
+
+interface I {
+    function f() external;
+    function g() external view returns (uint256);
+}
+
+contract C {
+    uint256 internal x;
+    mapping(address => uint256) internal balances;
+    uint256[] internal xs;
+
+    modifier nonReentrant() {
+        _;
+    }
+
+    function callThenWrite(I i) external {
+        i.f();
+        x = 1; //~ WARN: state variable `x` is written after an external call
+    }
+
+    function writeThenCall(I i) external {
+        x = 1;
+        i.f();
+    }
+
+    function viewCall(I i) external {
+        x = i.g();
+    }
+
+    function lowLevel() external {
+        (bool ok, ) = msg.sender.call("");
+        require(ok);
+        balances[msg.sender] = 0; //~ WARN: state variable `balances` is written after an external call
+        xs.push(1); //~ WARN: state variable `xs` is written after an external call
+    }
+
+    function viaInternal(I i) external {
+        call(i);
+        write(); //~ WARN: state variable `x` may be written by `write` after an external call
+    }
+
+    function guarded(I i) external nonReentrant {
+        i.f();
+        x = 1;
+    }
+
+    function call(I i) internal {
+        i.f();
+    }
+
+    function write() internal {
+        x = 0;
+    }
+}
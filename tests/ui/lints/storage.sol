@@ -0,0 +1,53 @@
+//@compile-flags: -Zlints
+
+contract C {
+    struct S {
+        uint256 a;
+        mapping(uint256 => uint256) m;
+    }
+
+    struct T {
+        uint256 a;
+    }
+
+    S internal s;
+    S[] internal ss;
+    T internal t;
+    uint256[] internal xs;
+
+    function pointers(bool b) external {
+        S storage p;
+        S storage q;
+        S storage r;
+        if (b) {
+            p = s;
+            q = s;
+        } else {
+            q = s;
+        }
+        p.a = 1; //~ WARN: storage pointer `p` may be used before it is assigned
+        q.a = 1;
+        assembly {
+            r.slot := 0
+        }
+        r.a = 1;
+    }
+
+    function deletes() external {
+        delete s; //~ WARN: `delete` does not clear the mappings contained in the deleted value
+        delete ss; //~ WARN: `delete` does not clear the mappings contained in the deleted value
+        delete ss[0]; //~ WARN: `delete` does not clear the mappings contained in the deleted value
+        delete s.m[1];
+        delete t;
+    }
+
+    function copies(uint256 n) external view returns (uint256 sum) {
+        uint256[] memory copy = xs;
+        for (uint256 i = 0; i < n; ++i) {
+            uint256[] memory inner = xs; //~ WARN: storage array is copied to memory
+            uint256[] storage stored = xs;
+            copy = xs; //~ WARN: storage array is copied to memory
+            sum += inner[i] + stored[i] + copy[i];
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//@compile-flags: -Zlints
+
+// This comment is longer than the maximum line length of 120 characters that is configured by default, so it is reported.
+//~^ WARN: line is longer than 120 characters
+contract my_contract { //~ WARN: contract name `my_contract` does not follow the naming convention
+    uint256 constant maxSupply = 100; //~ WARN: constant name `maxSupply` does not follow the naming convention
+    //~^ WARN: state variable `maxSupply` has no explicit visibility
+    uint256 internal count;
+    uint256 balance; //~ WARN: state variable `balance` has no explicit visibility
+
+    event transfer(uint256 amount); //~ WARN: event name `transfer` does not follow the naming convention
+    error bad_input(); //~ WARN: error name `bad_input` does not follow the naming convention
+
+    struct point { //~ WARN: struct name `point` does not follow the naming convention
+        uint256 x;
+    }
+
+    enum color { //~ WARN: enum name `color` does not follow the naming convention
+        Red
+    }
+
+    modifier OnlyOwner() { //~ WARN: modifier name `OnlyOwner` does not follow the naming convention
+        _;
+    }
+
+    function DoThing(uint256[3] calldata values) external OnlyOwner returns (uint256) {
+        //~^ WARN: function name `DoThing` does not follow the naming convention
+        count = values[0] * 60 + 1 days; //~ WARN: magic number `60`
+        return count + 1;
+    }
+}
@@ -0,0 +1,11 @@
+//@compile-flags: -Zlints
+
+contract C {
+    function f(address payable to, uint256 amount) external {
+        to.transfer(amount); //~ WARN: `transfer` forwards a fixed stipend of 2300 gas
+        bool sent = to.send(amount); //~ WARN: `send` forwards a fixed stipend of 2300 gas
+        require(sent);
+        (bool ok, ) = to.call{value: amount}("");
+        require(ok);
+    }
+}
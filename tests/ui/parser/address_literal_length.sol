@@ -0,0 +1,15 @@
+// Hex numbers one digit short or long of an address are reported once and still typed as
+// addresses, so their uses are not reported again.
+contract C {
+    address a = 0x12345678901234567890123456789012345678901; //~ ERROR: this looks like an address but is not exactly 40 hex digits
+    address b = 0x123456789012345678901234567890123456789; //~ ERROR: this looks like an address but is not exactly 40 hex digits
+    address c = 0x1234_5678901234567890123456789012345678901; //~ ERROR: this looks like an address but is not exactly 40 hex digits
+    address d = 0xb71cb1A7ab0B6Bc6c07f5A3Ef2EA36757968A121; //~ ERROR: invalid checksummed address
+
+    // Numbers of other lengths are not addresses.
+    uint256 e = 0x12345678901234567890123456789012345678;
+
+    function f() public view returns (address[4] memory) {
+        return [a, b, c, d];
+    }
+}
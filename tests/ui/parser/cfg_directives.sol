@@ -0,0 +1,43 @@
+//@compile-flags: -Zcfg-directives --cfg mock --cfg network=mainnet
+
+// solar:if mock
+contract Oracle {
+    function price() external pure returns (uint256) {
+        return 1;
+    }
+}
+// solar:else
+contract Oracle {
+    this is not valid Solidity
+}
+// solar:endif
+
+// solar:if network=testnet
+contract Oracle {}
+// solar:endif
+
+// Other comments starting with `solar:` are not directives.
+// solar: note
+// solar:unless mock
+// solar:ifdef mock
+
+contract Feed {
+    // solar:if network=mainnet
+    uint256 constant DECIMALS = 8;
+    // solar:if mock
+    uint256 constant MOCKED = 1;
+    // solar:endif
+    // solar:else
+    uint256 constant DECIMALS = 18;
+    // solar:endif
+
+    // solar:if missing
+    // solar:if mock
+    uint256 constant MOCKED = 2;
+    // solar:endif
+    // solar:endif
+
+    function decimals() external pure returns (uint256) {
+        return DECIMALS + MOCKED;
+    }
+}
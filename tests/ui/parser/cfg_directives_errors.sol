@@ -0,0 +1,19 @@
+//@compile-flags: -Zcfg-directives
+
+// solar:endif
+//~^ ERROR: without a `solar:if`
+// solar:else
+//~^ ERROR: without a `solar:if`
+// solar:if
+//~^ ERROR: expected a condition
+// solar:endif
+// solar:if a b
+//~^ ERROR: expected a condition
+// solar:else
+// solar:else
+//~^ ERROR: after another
+// solar:endif extra
+//~^ ERROR: does not take a condition
+contract C {}
+// solar:if mock
+//~^ ERROR: unterminated
@@ -0,0 +1,14 @@
+//@ compile-flags: --stop-after parsing
+
+contract C {
+    uint new; //~ ERROR: expected identifier, found keyword `new`
+
+    function delete(uint a, uint address) external { //~ ERROR: expected identifier, found keyword `delete`
+        //~^ ERROR: expected identifier, found keyword `address`
+        uint emit = a; //~ ERROR: expected identifier, found keyword `emit`
+    }
+
+    // Misplaced keywords are not renamed.
+    function f( public {} //~ ERROR: expected identifier, found keyword `public`
+    //~^ ERROR: expected one of
+}
@@ -0,0 +1,7 @@
+//@ solidity-version: 0.7
+
+pragma solidity ^0.7.0;
+pragma solidity >=0.6.0 <0.8.0;
+pragma solidity ^0.6.0 || ^0.7.0;
+pragma solidity ^0.8.0; //~ ERROR: source file requires a different Solidity version
+pragma solidity 0.7.6; //~ ERROR: source file requires a different Solidity version
@@ -0,0 +1,38 @@
+//@ compile-flags: -Zvalidate-spans --stop-after parsing
+
+/// @title ValidateSpans
+contract ValidateSpans {
+    struct S {
+        uint256 a;
+        bytes b;
+    }
+
+    /// @notice a state variable
+    uint256 x;
+    mapping(address => S) internal s;
+
+    modifier m(uint256 y) {
+        require(y > 0, "zero");
+        _;
+    }
+
+    /// @param bar a parameter
+    function foo(uint256 bar) public m(bar) returns (uint256 r) {
+        /// @dev statement
+        unchecked {
+            x = bar + 1;
+        }
+        (uint256 a, ) = (bar, x);
+        for (uint256 i = 0; i < a; i++) {
+            if (i % 2 == 0) continue;
+            r += i;
+        }
+        s[msg.sender].a = r > 10 ? r : 0;
+        assembly {
+            let z := add(mload(0x40), 32)
+            if iszero(z) { revert(0, 0) }
+            r := z
+        }
+        return r;
+    }
+}
@@ -0,0 +1,3 @@
+struct S {
+    uint256 a;
+}
@@ -0,0 +1,3 @@
+import {S} from "./Bar.sol";
+
+function get() pure returns (S memory s) {}
@@ -9,6 +9,9 @@ event Ev1(int);
 error Er1(uint);
 error Er1(int); //~ ERROR: already declared
 
+event EvEr();
+error EvEr(); //~ ERROR: already declared
+
 contract C {
     // OK
     function f2(uint) public  pure {}
@@ -0,0 +1,16 @@
+function f() {}
+event f(); //~ ERROR: already declared
+
+event e();
+function e() {} //~ ERROR: already declared
+
+contract C {
+    function g() public {}
+    event g(); //~ ERROR: already declared
+
+    event h(uint);
+    function h(uint) public {} //~ ERROR: already declared
+
+    uint internal x;
+    event x(); //~ ERROR: already declared
+}
@@ -0,0 +1,5 @@
+function a() virtual {} //~ ERROR: free functions cannot be virtual
+
+function b() payable {} //~ ERROR: free functions cannot be payable
+
+function c() pure {}
@@ -41,3 +41,30 @@ contract C {
         bool x8 = this.nestedArrayOfMapsOfArrays("", 0, "", 0);
     }
 }
+
+contract Structs {
+    struct Inner {
+        uint x;
+    }
+
+    // Mapping and non-byte array members are omitted from the getter.
+    struct S {
+        uint a;
+        string b;
+        uint[] c;
+        mapping(uint => uint) d;
+        Inner e;
+        bytes f;
+        uint[2] g;
+    }
+
+    S public simple;
+    S[2] public fixedArray;
+    mapping(uint k => S[] v) public mapOfArrays;
+
+    function doCall() public view {
+        (uint a1, string memory b1, Inner memory e1, bytes memory f1) = this.simple();
+        (uint a2, string memory b2, Inner memory e2, bytes memory f2) = this.fixedArray(1);
+        (uint a3, string memory b3, Inner memory e3, bytes memory f3) = this.mapOfArrays(0, 1);
+    }
+}
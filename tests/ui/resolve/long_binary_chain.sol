@@ -0,0 +1,17 @@
+// Left-associative chains of binary operators do not count towards the recursion limit, so long
+// sums compile with the default limit.
+
+contract C {
+    function sum(uint256 a) public pure returns (uint256) {
+        return a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a
+            + a + a + a + a + a + a + a + a + a + a + a;
+    }
+}
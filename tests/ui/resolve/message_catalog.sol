@@ -0,0 +1,6 @@
+// Diagnostics are translated with `message_catalog.catalog`, passed with `-Zmessage-catalog`.
+
+function f() {
+    uint a = b; //~ ERROR: symbole non résolu `b`
+    c(a); //~ ERROR: symbole non résolu `c`
+}
@@ -0,0 +1,14 @@
+import "./namespace_members.sol" as self1;
+import * as self2 from "./namespace_members.sol";
+
+function helper() pure returns (uint) {
+    return 1;
+}
+
+uint constant VALUE = 2;
+
+contract C {
+    function f() external pure returns (uint) {
+        return self1.helper() + self1.self2.helper() + self2.self1.self2.VALUE;
+    }
+}
@@ -0,0 +1,11 @@
+//@ compile-flags: -Zrecursion-limit=8
+
+contract C {
+    function f(uint a) public pure returns (uint) {
+        return a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a;
+    }
+
+    function g(uint a) public pure returns (uint) {
+        return a + (a + (a + (a + (a + a)))); //~ ERROR: recursion limit reached
+    }
+}
@@ -0,0 +1,8 @@
+// Files with identical contents in different directories are distinct source units: their
+// relative imports resolve against their own directory.
+import "./auxiliary/same_content_a/Foo.sol" as A;
+import "./auxiliary/same_content_b/Foo.sol" as B;
+
+function f() pure returns (uint256, bool) {
+    return (A.get().a, B.get().b);
+}
@@ -0,0 +1,21 @@
+contract Base {
+    constructor(uint256) {}
+}
+
+abstract contract A is Base(1) {}
+
+abstract contract B is Base {
+    constructor() Base(2) {}
+}
+
+contract C is A, B {} //~ ERROR: base constructor arguments given twice
+
+abstract contract Deferred is Base {}
+
+contract Missing is Deferred {} //~ ERROR: no arguments given for the constructor of base contract `Base`
+
+contract Provided is Deferred {
+    constructor() Base(3) {}
+}
+
+contract ProvidedInList is Base(4), Deferred {}
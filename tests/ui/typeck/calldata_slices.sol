@@ -0,0 +1,15 @@
+contract C {
+    function f(
+        uint256[] calldata dynamicArray,
+        uint256[3] calldata fixedArray,
+        uint256[][] calldata nested,
+        bytes calldata data
+    ) external pure {
+        dynamicArray[1:2];
+        dynamicArray[1:][:1];
+        data[:4];
+        fixedArray[1:]; //~ ERROR: can only slice dynamic calldata arrays
+        nested[1:]; //~ ERROR: cannot slice arrays with dynamically encoded base types
+        dynamicArray[true:]; //~ ERROR: mismatched types
+    }
+}
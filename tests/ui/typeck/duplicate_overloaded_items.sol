@@ -100,3 +100,8 @@ contract C2 {
 contract D is C2 {
     event E5() anonymous; //~ ERROR: event with same name and parameter types declared twice
 }
+
+contract R {
+    function r1() public returns (uint) {} //~ ERROR: function with same name and parameter types declared twice
+    function r1() public returns (int) {}
+}
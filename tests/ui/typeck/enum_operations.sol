@@ -0,0 +1,20 @@
+contract C {
+    enum E {
+        A,
+        B,
+        C
+    }
+
+    function f(E a, E b) public pure {
+        E min = type(E).min;
+        E max = type(E).max;
+        bool eq = a == b;
+        bool lt = min < max;
+        uint8 n = uint8(a) + uint8(b);
+
+        a + b; //~ ERROR: cannot apply builtin operator `+` to `enum C.E` and `enum C.E`
+        a * b; //~ ERROR: cannot apply builtin operator `*` to `enum C.E` and `enum C.E`
+        a + 1; //~ ERROR: cannot apply builtin operator `+` to `enum C.E` and `int_literal[1]`
+        a & b; //~ ERROR: cannot apply builtin operator `&` to `enum C.E` and `enum C.E`
+    }
+}
@@ -5,7 +5,7 @@ uint constant rec2 = rec1;
 
 uint constant bigLiteral = 115792089237316195423570985008687907853269984665640564039457584007913129639935;
 
-uint constant fails = 0 / 0;
+uint constant fails = 0 / 0; //~ ERROR: failed to evaluate constant: attempted to divide by zero
 
 contract C {
     uint constant zero = x - x;
@@ -14,7 +14,7 @@ contract C {
     uint[zeroPublic + 1] public oneArray;
 
     uint[bigLiteral] public big;
-    uint[bigLiteral + 1] public tooBig1; //~ ERROR: failed to evaluate constant: arithmetic overflow
+    uint[bigLiteral + 1] public tooBig1; //~ ERROR: failed to evaluate constant: value does not fit in 256 bits
 
     uint private stateVar = 69;
     uint public stateVarPublic = 420;
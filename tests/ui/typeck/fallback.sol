@@ -0,0 +1,31 @@
+library L {
+    fallback() external {} //~ ERROR: libraries cannot have fallback functions
+}
+
+contract A {
+    fallback() external {}
+}
+
+contract B {
+    fallback(bytes calldata input) external payable returns (bytes memory) {
+        return input;
+    }
+}
+
+contract C {
+    fallback(bytes calldata input) external {} //~ ERROR: invalid fallback function signature
+}
+
+contract D {
+    fallback() external returns (bytes memory) {} //~ ERROR: invalid fallback function signature
+}
+
+contract E {
+    fallback(bytes memory input) external returns (bytes memory) {} //~ ERROR: invalid fallback function signature
+}
+
+contract F {
+    fallback(bytes calldata input) external returns (uint256) {} //~ ERROR: invalid fallback function signature
+}
+
+contract G is B {}
@@ -0,0 +1,10 @@
+//@compile-flags: -Zcodegen
+
+contract C {
+    fixed128x18 stored; //~ ERROR: fixed-point type `fixed128x18` is not yet implemented
+
+    function f(ufixed64x2 x) public pure { //~ ERROR: fixed-point type `ufixed64x2` is not yet implemented
+        ufixed64x2 y = x; //~ ERROR: fixed-point type `ufixed64x2` is not yet implemented
+        y;
+    }
+}
@@ -6,6 +6,8 @@ library Lib {
 
 error CustomError(uint256 value);
 
+contract D {}
+
 contract C {
     event CustomEvent(uint256 value);
 
@@ -16,7 +18,7 @@ contract C {
     function test(uint256 value, bytes memory data) public {
         abi.encodeCall(internalTarget, (value)); //~ ERROR: first argument to `abi.encodeCall` must be an external function
         abi.encodeCall(Lib.externalTarget, (value)); //~ ERROR: first argument to `abi.encodeCall` cannot be a library function
-        abi.encodeCall(new C, (value)); //~ ERROR: first argument to `abi.encodeCall` cannot be a creation function
+        abi.encodeCall(new D, (value)); //~ ERROR: first argument to `abi.encodeCall` cannot be a creation function
         abi.encodeCall(address(this).call, (data)); //~ ERROR: first argument to `abi.encodeCall` cannot be a special function
         abi.encodeCall(CustomEvent, (value)); //~ ERROR: first argument to `abi.encodeCall` cannot be an event
         abi.encodeCall(CustomError, (value)); //~ ERROR: first argument to `abi.encodeCall` cannot be an error
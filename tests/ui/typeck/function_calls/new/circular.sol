@@ -0,0 +1,12 @@
+contract A {
+    function f() public {
+        new A(); //~ ERROR: circular reference for contract creation
+        new B(); //~ ERROR: circular reference for contract creation
+    }
+}
+
+contract B is A {
+    function g() public {
+        new A();
+    }
+}
@@ -0,0 +1,10 @@
+//@compile-flags: --evm-version byzantium
+
+contract SaltChild {}
+
+contract SaltCreator {
+    function f(bytes32 s) public {
+        new SaltChild{salt: s}(); //~ ERROR: unsupported call option `salt`
+        new SaltChild();
+    }
+}
@@ -86,6 +86,21 @@ contract C {
         sameFixedArray([uint256(1), uint256(2), uint256(3)]);
     }
 
+    // === Valid: inline arrays of internal function pointers ===
+    function fnPtrElement(uint256 x) internal pure returns (uint256) {
+        return x;
+    }
+
+    function viewFnPtrElement(uint256 x) internal view returns (uint256) {
+        return x + storageArr.length;
+    }
+
+    function inlineFnPtrArray() internal view {
+        function(uint256) internal view returns (uint256)[2] memory a =
+            [fnPtrElement, viewFnPtrElement];
+        [fnPtrElement, fnPtrElement];
+    }
+
     // === Valid: explicit conversions preserve data locations ===
 
     function explicitMemoryArray(uint256[] memory a) internal pure returns (uint256[] memory) {
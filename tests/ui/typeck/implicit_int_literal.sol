@@ -89,4 +89,15 @@ function f() {
     // More complex expressions with literals
     int16 complex_lit = -(2 * 3 + 1);
     int32 large_lit = -(256 + 1);  // Result is -257, int_literal[2]
+
+    // Literal-only arithmetic is evaluated before the conversion to the declared type
+    uint8 sum_u8 = 1 + 2;
+    uint8 sum_overflow_u8 = 255 + 1; //~ ERROR: mismatched types
+    uint256 div_by_zero = 1 / 0; //~ ERROR: failed to evaluate constant: attempted to divide by zero
+    uint256 pow_overflow = 2 ** 256; //~ ERROR: failed to evaluate constant: value does not fit in 256 bits
+
+    // Intermediate values may exceed 256 bits, only the final value has to fit
+    uint256 pow_max = 2 ** 256 - 1;
+    int256 pow_min = -2 ** 255;
+    uint8 pow_scaled = 2 ** 300 / 2 ** 296;
 }
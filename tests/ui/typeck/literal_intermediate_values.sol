@@ -0,0 +1,17 @@
+// Literal-only expressions are evaluated with rational precision, like in solc: intermediate values
+// may exceed 256 bits, and only the final value has to fit in the target type.
+uint256 constant MAX = 2**256 - 1;
+int256 constant MIN = -2**255;
+uint256 constant SCALED = 2**512 / 2**300;
+uint256 constant MASK = (1 << 256) - 1;
+uint256 constant ROUNDTRIP = (2**256 + 1) * 2 - 2**256 - 3;
+
+contract C {
+    uint256 public constant HALF = (2**256 - 2) / 2;
+    uint256[2**300 / 2**299] arr;
+
+    function f() public pure returns (uint256 a, int256 b) {
+        a = 2**256 - 1;
+        b = -(2**255);
+    }
+}
@@ -0,0 +1,23 @@
+contract C {
+    type U is uint256;
+
+    struct S {
+        mapping(uint256 => uint256) m;
+        uint256 x;
+    }
+
+    mapping(uint256 => uint256) m;
+    S s;
+    U u;
+    uint256[] arr;
+
+    function f() external {
+        delete m; //~ ERROR: cannot delete `mapping(uint256 => uint256) storage`
+        delete s.m; //~ ERROR: cannot delete `mapping(uint256 => uint256) storage`
+        delete s;
+        delete s.x;
+        delete u;
+        delete arr;
+        delete arr[0];
+    }
+}
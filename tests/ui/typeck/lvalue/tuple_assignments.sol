@@ -44,4 +44,12 @@ contract Test {
         (x, ) = (true, 1); //~ ERROR: mismatched types
         (, x) = (1, true); //~ ERROR: mismatched types
     }
+
+    function testTupleCallTypeMismatch() external {
+        bool b;
+        uint256 x;
+        (b, x) = returnsTuple(); //~ ERROR: mismatched types
+        (, b) = returnsTuple(); //~ ERROR: mismatched types
+        (, x) = returnsTuple();
+    }
 }
@@ -0,0 +1,11 @@
+contract C {
+    uint256 constant A = 0.1 * 10;
+    uint256 constant B = 2.5 * 2 ether;
+    int256 constant N = -0.5 * 4;
+    bool constant L = 0.5 < 1;
+    uint256[0.5 * 4] arr;
+
+    uint256 constant D = 1.5 * 3; //~ ERROR: rational numbers are not supported
+    uint8 constant F = 0.5 * 1024; //~ ERROR: mismatched types
+    uint256 constant G = 0.5 | 1; //~ ERROR: unsupported binary operation
+}
@@ -0,0 +1,51 @@
+struct Inner {
+    uint256 value;
+    uint256[] values;
+}
+
+struct Outer {
+    Inner inner;
+    mapping(uint256 => uint256) map;
+    uint256 x;
+}
+
+contract C {
+    Outer stored;
+    Outer other;
+
+    function f(Inner calldata cd, Inner memory mem) public {
+        stored.x = cd.value;
+        stored.inner.value = mem.value;
+        stored.inner.values.push(stored.x);
+        stored.map[stored.x] = stored.inner.values[0];
+        mem.value = cd.value;
+        mem.values = cd.values;
+        Inner storage ptr = stored.inner;
+        ptr.values = mem.values;
+        uint256[] memory copy = ptr.values;
+        copy[0] = stored.inner.values[0];
+        stored.inner = mem;
+        mem = stored.inner;
+
+        stored.missing = 1; //~ ERROR: member `missing` not found on type `struct Outer storage`
+        mem.inner.value = 1; //~ ERROR: member `inner` not found on type `struct Inner memory`
+        cd.values.value; //~ ERROR: member `value` not found on type `uint256[] calldata`
+    }
+
+    function calldataLocation(Inner calldata cd, Inner memory mem) public {
+        cd.value = 1; //~ ERROR: calldata structs are read-only
+        cd.values[0] = 1; //~ ERROR: calldata arrays are read-only
+        cd = mem; //~ ERROR: mismatched types
+    }
+
+    function memoryLocation(Inner memory mem) public {
+        mem.values.length = 1; //~ ERROR: member `length` is read-only and cannot be used to resize arrays
+    }
+
+    function storageLocation(Inner memory mem) public {
+        stored = other; //~ ERROR: types in storage containing (nested) mappings cannot be assigned to
+        stored.inner.values.length = 1; //~ ERROR: member `length` is read-only and cannot be used to resize arrays
+        Inner storage ptr = stored.inner;
+        ptr = mem; //~ ERROR: mismatched types
+    }
+}
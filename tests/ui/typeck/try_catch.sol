@@ -0,0 +1,29 @@
+contract D {}
+
+library L {
+    function lib() external pure returns (uint256) {}
+}
+
+contract C {
+    function ext() external pure returns (uint256, bool) {}
+
+    function internalFn() internal pure returns (uint256) {}
+
+    function returnsClause() public {
+        try this.ext() returns (uint256 a, bool b) {} catch {}
+        try new D() returns (D d) {} catch {}
+        try this.ext() returns (uint256 a) {} catch {} //~ ERROR: function returns 2 values, but the `returns` clause has 1 variable
+        try this.ext() returns (uint8 a, bool b) {} catch {} //~ ERROR: mismatched types
+        try internalFn() {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+        try L.lib() {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+    }
+
+    function catchClauses() public {
+        try this.ext() {} catch Error(string memory reason) {} catch Panic(uint256 code) {} catch (bytes memory data) {}
+        try this.ext() {} catch Error(bytes memory reason) {} //~ ERROR: invalid catch clause parameters
+        try this.ext() {} catch Panic() {} //~ ERROR: invalid catch clause parameters
+        try this.ext() {} catch (uint256 x) {} //~ ERROR: invalid catch clause parameters
+        try this.ext() {} catch Custom(uint256 x) {} //~ ERROR: invalid catch clause name
+        try this.ext() {} catch {} catch (bytes memory data) {} //~ ERROR: this try statement already has a low-level catch clause
+    }
+}
@@ -28,6 +28,7 @@ contract C {
     bytes32 constant convertedConstant = bytes32(uint256(1));
     string constant stringConstant = "abc";
     uint256 immutable immutableValue = 1;
+    uint256 transient transientState;
 
     function positive(
         uint256 local,
@@ -35,10 +36,13 @@ contract C {
         U256 udvt,
         Word udvtWord,
         uint256[] calldata data,
-        function() external returns (uint256) extFn
+        function() external returns (uint256) extFn,
+        StructValue calldata cdStruct,
+        uint256[2] calldata cdFixed
     ) external {
         uint256[] storage storageRef = stateArray;
         bytes memory memoryBytes = hex"1234";
+        StructValue memory memStruct;
         bool ok;
         assembly {
             let scratch := 0
@@ -90,6 +94,13 @@ contract C {
             extFn.address := 0
             extFn.selector := 0
             pop(add(1, 2))
+
+            pop(transientState.slot)
+            pop(transientState.offset)
+            pop(cdStruct)
+            pop(cdFixed)
+            pop(memStruct)
+            memStruct := mload(64)
         }
     }
 
@@ -108,9 +119,11 @@ contract C {
     function negative(
         uint256 local,
         uint256[] calldata data,
-        function() external returns (uint256) extFn
+        function() external returns (uint256) extFn,
+        StructValue calldata cdStruct
     ) external {
         uint256[] storage storageRef = stateArray;
+        StructValue memory memStruct;
         function() internal returns (uint256) intFn = helper;
         assembly {
             function pair() -> a, b {
@@ -205,6 +218,14 @@ contract C {
 
             CustomEvent := 1 //~ ERROR: only local variables can be assigned to in inline assembly
             //~^ ERROR: expression has to be an lvalue
+
+            pop(transientState) //~ ERROR: only local variables are supported in inline assembly
+
+            transientState.slot := 1 //~ ERROR: state variables cannot be assigned to in inline assembly
+
+            pop(memStruct.value) //~ ERROR: suffix `.value` is not supported by this variable or type
+
+            pop(cdStruct.offset) //~ ERROR: suffix `.offset` is not supported by this variable or type
         }
     }
 }
@@ -0,0 +1,31 @@
+//@ compile-flags: -Zunpretty=ast
+
+pragma solidity ^0.8.0;
+
+interface I {
+    function f(uint256 x) external view returns (uint256);
+}
+
+contract C {
+    uint256 public constant N = 10 ether;
+    mapping(address => uint256) internal balances;
+
+    event Transfer(address indexed from, uint256 value);
+
+    modifier onlyPositive(uint256 x) {
+        require(x > 0, "zero");
+        _;
+    }
+
+    function sum(uint256[] memory xs) public pure onlyPositive(xs.length) returns (uint256 total) {
+        for (uint256 i = 0; i < xs.length; i++) {
+            total += xs[i] * (i + 1);
+        }
+        do total--; while (total > 100);
+        if (total == 0) return 1; else if (total > 5) { total = 5; } else total = (2);
+        assembly {
+            let y := add(total, 1)
+            if lt(y, 2) { y := 2 }
+        }
+    }
+}
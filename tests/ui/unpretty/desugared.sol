@@ -0,0 +1,38 @@
+//@ compile-flags: -Zunpretty=desugared
+
+contract Base {
+    constructor(uint256 x) {}
+}
+
+contract C is Base {
+    address owner;
+
+    modifier onlyOwner() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    modifier atLeast(uint256 min, uint256 value) {
+        if (value >= min) {
+            _;
+        }
+    }
+
+    constructor() Base(1) onlyOwner {}
+
+    function f(uint256 n) public onlyOwner atLeast(1, n) returns (uint256 total) {
+        for (uint256 i = 0; i < n; i++) {
+            total += i;
+        }
+    }
+
+    function g(uint256 n) public pure returns (uint256 odd) {
+        for (uint256 i = 0; i < n; i++) {
+            if (i % 2 == 0) continue;
+            odd++;
+        }
+        for (;;) {
+            break;
+        }
+    }
+}
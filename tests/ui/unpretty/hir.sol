@@ -0,0 +1,18 @@
+//@ compile-flags: -Zunpretty=hir
+
+contract C {
+    event Inc(uint256 x);
+
+    error Overflow();
+
+    function f(uint256 x) public {
+        unchecked {
+            x += 1;
+        }
+        assembly "evmasm" ("memory-safe") {}
+        emit Inc(x);
+        if (x == 0) {
+            revert Overflow();
+        }
+    }
+}
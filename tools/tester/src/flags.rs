@@ -0,0 +1,675 @@
+//! Custom comment flags that configure the compiler for a single test.
+
+use crate::run_call;
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+    process::Command,
+};
+use ui_test::{
+    CommentParser, Errored, Revisioned,
+    build_manager::BuildManager,
+    custom_flags::Flag,
+    per_test_config::TestConfig,
+    spanned::{Span, Spanned},
+};
+
+/// `//@evm-version: <version>`: compiles the test for the given EVM version.
+///
+/// This is equivalent to `//@compile-flags: --evm-version=<version>`, and is also used as the
+/// EVM specification for `run-call` directives.
+#[derive(Debug, Clone)]
+pub(crate) struct EvmVersion;
+
+impl EvmVersion {
+    pub(crate) const NAME: &'static str = "evm-version";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        parser.set_custom_once(Self::NAME, Self, span);
+        let version = args.trim();
+        if let Err(err) = run_call::parse_spec_id(version) {
+            parser.error(args.span(), err);
+            return;
+        }
+        parser.compile_flags.push(format!("--evm-version={version}"));
+    }
+}
+
+/// `//@emit: <output>[,<output>...]`: emits the given compiler outputs.
+///
+/// This is equivalent to `//@compile-flags: --emit=<output>`.
+#[derive(Debug, Clone)]
+pub(crate) struct Emit;
+
+impl Emit {
+    pub(crate) const NAME: &'static str = "emit";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        parser.set_custom_once(Self::NAME, Self, span);
+        let outputs = args.split(',').map(str::trim).collect::<Vec<_>>();
+        if outputs.iter().any(|output| output.is_empty()) {
+            parser.error(args.span(), "`emit` requires a comma-separated list of outputs");
+            return;
+        }
+        parser.compile_flags.push(format!("--emit={}", outputs.join(",")));
+    }
+}
+
+/// `//@solidity-version: <version>`: checks version pragmas against the given Solidity version.
+///
+/// This is equivalent to `//@compile-flags: -Zsolidity-version=<version>`.
+#[derive(Debug, Clone)]
+pub(crate) struct SolidityVersion;
+
+impl SolidityVersion {
+    pub(crate) const NAME: &'static str = "solidity-version";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        parser.set_custom_once(Self::NAME, Self, span);
+        let version = args.trim();
+        if version.is_empty() {
+            parser.error(args.span(), "`solidity-version` requires a version");
+            return;
+        }
+        parser.compile_flags.push(format!("-Zsolidity-version={version}"));
+    }
+}
+
+/// `//@out-artifact: <path>`: writes artifacts with `--out`, and checks the artifact at `<path>`,
+/// relative to the output directory, against the test's FileCheck directives.
+#[derive(Debug, Clone)]
+pub(crate) struct OutArtifact {
+    path: PathBuf,
+}
+
+impl OutArtifact {
+    pub(crate) const NAME: &'static str = "out-artifact";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let path = args.trim();
+        if path.is_empty() {
+            parser.error(args.span(), "`out-artifact` requires an artifact path");
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self { path: path.into() }, span);
+    }
+}
+
+/// Returns the temporary directory the output files of `test` are written to.
+fn out_dir(test: &Path) -> PathBuf {
+    let test = test
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component),
+            _ => None,
+        })
+        .collect::<PathBuf>();
+    std::env::temp_dir().join("solar-ui-out").join(test)
+}
+
+/// Creates an empty output directory for `test`, removing any files left over from a previous run.
+fn create_out_dir(test: &Path) -> Result<PathBuf, Errored> {
+    let out_dir = out_dir(test);
+    match std::fs::remove_dir_all(&out_dir) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            return Err(io_error("remove_dir_all", &out_dir, err));
+        }
+        _ => {}
+    }
+    std::fs::create_dir_all(&out_dir).map_err(|err| io_error("create_dir_all", &out_dir, err))?;
+    Ok(out_dir)
+}
+
+impl Flag for OutArtifact {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let out_dir = create_out_dir(config.status.path())?;
+        cmd.arg(format!("--out={}", out_dir.display())).arg("--overwrite");
+        Ok(())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &std::process::Output,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let artifact = out_dir(config.status.path()).join(&self.path);
+        crate::run_filecheck(config.status.path(), &[], &artifact)
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@out-dir-file: <path>`: writes output files with `--out-dir`, and checks the file at
+/// `<path>`, relative to the output directory, against the test's FileCheck directives.
+#[derive(Debug, Clone)]
+pub(crate) struct OutDirFile {
+    path: PathBuf,
+}
+
+impl OutDirFile {
+    pub(crate) const NAME: &'static str = "out-dir-file";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let path = args.trim();
+        if path.is_empty() {
+            parser.error(args.span(), "`out-dir-file` requires a file path");
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self { path: path.into() }, span);
+    }
+}
+
+impl Flag for OutDirFile {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let out_dir = create_out_dir(config.status.path())?;
+        cmd.arg(format!("--out-dir={}", out_dir.display()));
+        Ok(())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &std::process::Output,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let file = out_dir(config.status.path()).join(&self.path);
+        crate::run_filecheck(config.status.path(), &[], &file)
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@events`: writes structured compiler events with `--events`, and checks them against the
+/// test's FileCheck directives.
+#[derive(Debug, Clone)]
+pub(crate) struct Events;
+
+impl Events {
+    pub(crate) const NAME: &'static str = "events";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    /// The name of the events file in the output directory.
+    const FILE: &'static str = "events.jsonl";
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        if !args.trim().is_empty() {
+            parser.error(args.span(), "`events` does not take any arguments");
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self, span);
+    }
+}
+
+impl Flag for Events {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let out_dir = create_out_dir(config.status.path())?;
+        cmd.arg(format!("--events={}", out_dir.join(Self::FILE).display()));
+        Ok(())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &std::process::Output,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let file = out_dir(config.status.path()).join(Self::FILE);
+        crate::run_filecheck(config.status.path(), &[], &file)
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@overlay: <path>=<file>`: reads the source at `<path>` from `<file>` instead.
+///
+/// This is equivalent to `//@compile-flags: --overlay=<path>=<file>`, with both paths relative to
+/// the test's directory. Can be used multiple times.
+#[derive(Debug, Clone)]
+pub(crate) struct Overlay {
+    path: PathBuf,
+    file: PathBuf,
+}
+
+impl Overlay {
+    pub(crate) const NAME: &'static str = "overlay";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let Some((path, file)) = args.trim().split_once('=') else {
+            parser.error(args.span(), "`overlay` requires `<path>=<file>`");
+            return;
+        };
+        let (path, file) = (path.trim(), file.trim());
+        if path.is_empty() || file.is_empty() {
+            parser.error(args.span(), "`overlay` requires `<path>=<file>`");
+            return;
+        }
+        parser.add_custom_spanned(Self::NAME, Self { path: path.into(), file: file.into() }, span);
+    }
+}
+
+impl Flag for Overlay {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let dir = config.status.path().parent().unwrap_or(Path::new(""));
+        let (path, file) = (dir.join(&self.path), dir.join(&self.file));
+        cmd.arg(format!("--overlay={}={}", path.display(), file.display()));
+        Ok(())
+    }
+
+    fn must_be_unique(&self) -> bool {
+        false
+    }
+}
+
+/// `//@base-path: <dir>`: sets the base path to `<dir>`.
+///
+/// This is equivalent to `//@compile-flags: --base-path=<dir>`, with `<dir>` relative to the
+/// test's directory.
+#[derive(Debug, Clone)]
+pub(crate) struct BasePath {
+    dir: PathBuf,
+}
+
+impl BasePath {
+    pub(crate) const NAME: &'static str = "base-path";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let dir = args.trim();
+        if dir.is_empty() {
+            parser.error(args.span(), "`base-path` requires a directory");
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self { dir: dir.into() }, span);
+    }
+}
+
+impl Flag for BasePath {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let dir = config.status.path().parent().unwrap_or(Path::new(""));
+        cmd.arg(format!("--base-path={}", dir.join(&self.dir).display()));
+        Ok(())
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@remap-path-prefix: <from>=<to>`: displays source paths starting with `<from>` as starting
+/// with `<to>` instead.
+///
+/// This is equivalent to `//@compile-flags: --remap-path-prefix=<from>=<to>`, with `<from>`
+/// relative to the test's directory. Can be used multiple times.
+#[derive(Debug, Clone)]
+pub(crate) struct RemapPathPrefix {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl RemapPathPrefix {
+    pub(crate) const NAME: &'static str = "remap-path-prefix";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let Some((from, to)) = args.trim().split_once('=') else {
+            parser.error(args.span(), "`remap-path-prefix` requires `<from>=<to>`");
+            return;
+        };
+        let (from, to) = (from.trim(), to.trim());
+        if from.is_empty() {
+            parser.error(args.span(), "`remap-path-prefix` requires `<from>=<to>`");
+            return;
+        }
+        parser.add_custom_spanned(Self::NAME, Self { from: from.into(), to: to.into() }, span);
+    }
+}
+
+impl Flag for RemapPathPrefix {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let dir = config.status.path().parent().unwrap_or(Path::new(""));
+        let from = dir.join(&self.from);
+        cmd.arg(format!("--remap-path-prefix={}={}", from.display(), self.to.display()));
+        Ok(())
+    }
+
+    fn must_be_unique(&self) -> bool {
+        false
+    }
+}
+
+/// `//@stdin: <file>`: passes the contents of `<file>`, relative to the test's directory, to the
+/// compiler's standard input.
+///
+/// Sources are only read from stdin if `-` is also passed with `//@compile-flags`.
+#[derive(Debug, Clone)]
+pub(crate) struct Stdin {
+    file: PathBuf,
+}
+
+impl Stdin {
+    pub(crate) const NAME: &'static str = "stdin";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let file = args.trim();
+        if file.is_empty() {
+            parser.error(args.span(), "`stdin` requires a file path");
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self { file: file.into() }, span);
+    }
+}
+
+impl Flag for Stdin {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn apply(
+        &self,
+        cmd: &mut Command,
+        config: &TestConfig,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let dir = config.status.path().parent().unwrap_or(Path::new(""));
+        let file = dir.join(&self.file);
+        let stdin = std::fs::File::open(&file).map_err(|err| io_error("open", &file, err))?;
+        cmd.stdin(stdin);
+        Ok(())
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// `//@check-reproducible: <output>[,<output>...]`: checks that `--reproducible` builds of the
+/// test from two different directories produce byte-identical outputs.
+///
+/// The test and its `auxiliary` directory are copied to each directory, and compiled there with
+/// the test's compile flags, emitting the given outputs with `--out-dir`, artifacts with `--out`,
+/// and events with `--events`. Standard output, standard error, and all output files must be the
+/// same in both builds.
+#[derive(Debug, Clone)]
+pub(crate) struct CheckReproducible {
+    outputs: String,
+}
+
+impl CheckReproducible {
+    pub(crate) const NAME: &'static str = "check-reproducible";
+    pub(crate) const DEFAULT: Option<Self> = None;
+
+    /// The build directories, relative to the output directory. They have different depths so
+    /// that neither absolute nor relative paths to them are the same.
+    const DIRS: [&'static str; 2] = ["first", "second/build"];
+
+    /// The output paths written by each build, relative to its directory.
+    const OUTPUTS: [&'static str; 3] = ["out", "artifacts", "events.jsonl"];
+
+    pub(crate) fn parse(
+        parser: &mut CommentParser<&mut Revisioned>,
+        args: Spanned<&str>,
+        span: Span,
+    ) {
+        let outputs = args.split(',').map(str::trim).collect::<Vec<_>>();
+        if outputs.iter().any(|output| output.is_empty()) {
+            parser.error(
+                args.span(),
+                "`check-reproducible` requires a comma-separated list of outputs",
+            );
+            return;
+        }
+        parser.set_custom_once(Self::NAME, Self { outputs: outputs.join(",") }, span);
+    }
+
+    /// Builds `test` in `dir`, returning the contents of all of its outputs by name.
+    fn build(
+        &self,
+        test: &Path,
+        dir: &Path,
+        flags: &[String],
+    ) -> Result<Vec<(PathBuf, Vec<u8>)>, Errored> {
+        let file_name = test.file_name().unwrap();
+        let auxiliary = test.with_file_name("auxiliary");
+        std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::copy(test, dir.join(file_name)))
+            .and_then(|_| {
+                if auxiliary.is_dir() {
+                    copy_dir(&auxiliary, &dir.join("auxiliary"))
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(|err| io_error("copy", dir, err))?;
+
+        let mut cmd = Command::new(crate::solar_binary());
+        cmd.current_dir(dir).arg(file_name).args(flags);
+        if !flags.iter().any(|flag| flag == "--reproducible") {
+            cmd.arg("--reproducible");
+        }
+        cmd.arg(format!("--emit={}", self.outputs))
+            .args(["--out-dir=out", "--out=artifacts", "--events=events.jsonl"])
+            // Events are only written in a deterministic order by a single thread.
+            .arg("-j1");
+        let output = cmd.output().map_err(|err| io_error("spawn", crate::solar_binary(), err))?;
+
+        let mut files = vec![("stdout".into(), output.stdout), ("stderr".into(), output.stderr)];
+        for path in Self::OUTPUTS {
+            read_files(dir, Path::new(path), &mut files)
+                .map_err(|err| io_error("read", &dir.join(path), err))?;
+        }
+        Ok(files)
+    }
+}
+
+impl Flag for CheckReproducible {
+    fn clone_inner(&self) -> Box<dyn Flag> {
+        Box::new(self.clone())
+    }
+
+    fn post_test_action(
+        &self,
+        config: &TestConfig,
+        _output: &std::process::Output,
+        _build_manager: &BuildManager,
+    ) -> Result<(), Errored> {
+        let test = config.status.path();
+        let out_dir = create_out_dir(test)?;
+        let flags = config
+            .comments()
+            .flat_map(|comments| &comments.compile_flags)
+            .cloned()
+            .collect::<Vec<_>>();
+        let [first, second] = Self::DIRS.map(|dir| self.build(test, &out_dir.join(dir), &flags));
+        let (first, second) = (first?, second?);
+        if first == second {
+            return Ok(());
+        }
+        let first_paths = first.iter().map(|(path, _)| path).collect::<Vec<_>>();
+        let second_paths = second.iter().map(|(path, _)| path).collect::<Vec<_>>();
+        let message = if first_paths != second_paths {
+            format!("the builds wrote different files: {first_paths:?} and {second_paths:?}")
+        } else {
+            let (path, _) = first.iter().zip(&second).find(|(a, b)| a != b).unwrap().0;
+            format!("`{}` differs between the builds", path.display())
+        };
+        Err(Errored {
+            command: Self::NAME.into(),
+            errors: vec![ui_test::Error::ConfigError(message)],
+            stderr: vec![],
+            stdout: vec![],
+        })
+    }
+
+    fn must_be_unique(&self) -> bool {
+        true
+    }
+}
+
+/// Recursively copies the directory `from` to `to`.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let to = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to)?;
+        } else {
+            std::fs::copy(entry.path(), to)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the file at `path` relative to `dir`, or all files under it if it is a directory, in
+/// sorted order. Missing paths are skipped.
+fn read_files(dir: &Path, path: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) -> io::Result<()> {
+    let full_path = dir.join(path);
+    if full_path.is_dir() {
+        let mut entries = std::fs::read_dir(&full_path)?
+            .map(|entry| entry.map(|entry| path.join(entry.file_name())))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            read_files(dir, &entry, files)?;
+        }
+    } else if full_path.exists() {
+        files.push((path.to_path_buf(), std::fs::read(full_path)?));
+    }
+    Ok(())
+}
+
+/// Returns the error of the I/O operation `op` on `path`.
+fn io_error(op: &str, path: &Path, err: io::Error) -> Errored {
+    Errored {
+        command: format!("{op}({})", path.display()),
+        errors: vec![],
+        stderr: err.to_string().into_bytes(),
+        stdout: vec![],
+    }
+}
+
+macro_rules! impl_flag {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Flag for $ty {
+                fn clone_inner(&self) -> Box<dyn Flag> {
+                    Box::new(self.clone())
+                }
+
+                fn must_be_unique(&self) -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+impl_flag!(EvmVersion, Emit, SolidityVersion);
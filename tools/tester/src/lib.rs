@@ -22,11 +22,15 @@ use ui_test::{
 };
 
 mod errors;
+mod flags;
 mod run_call;
 mod solc;
 mod standard_json;
 mod utils;
 
+/// The path to the `solar` binary, set by [`run_tests`].
+static SOLAR: OnceLock<&'static Path> = OnceLock::new();
+
 /// Runs all the tests.
 ///
 /// `cmd` is the path to the `solar` binary used by all modes. `Mode::Mir`
@@ -34,6 +38,7 @@ mod utils;
 /// `solar evm-opt …`.
 pub fn run_tests(cmd: &'static Path) -> Result<()> {
     ui_test::color_eyre::install()?;
+    let _ = SOLAR.set(cmd);
 
     let mut args = ui_test::Args::test()?;
 
@@ -57,9 +62,9 @@ pub fn run_tests(cmd: &'static Path) -> Result<()> {
     modes.insert(2, Mode::EvmIr);
 
     // TESTER_MODE can be a single mode or a comma-separated list.
-    // The "ui" alias also implicitly runs the "mir" mode, since they share the
-    // same `tests/ui/` tree and users typically want `cargo uitest` to cover
-    // both.
+    // The "ui" alias also implicitly runs the "mir", "evm-ir" and "artifacts"
+    // modes, since users typically want `cargo uitest` and `cargo uibless` to
+    // cover all snapshot tests.
     if let Ok(mode_str) = std::env::var("TESTER_MODE") {
         let mut requested = Vec::new();
         for name in mode_str.split(',') {
@@ -71,6 +76,9 @@ pub fn run_tests(cmd: &'static Path) -> Result<()> {
             if name.trim() == "ui" && !requested.contains(&Mode::EvmIr) {
                 requested.push(Mode::EvmIr);
             }
+            if name.trim() == "ui" && !requested.contains(&Mode::Artifacts) {
+                requested.push(Mode::Artifacts);
+            }
         }
         modes = requested;
     }
@@ -107,6 +115,7 @@ fn config(cmd: &'static Path, args: &ui_test::Args, mode: Mode) -> ui_test::Conf
         Mode::Ui | Mode::StandardJson => "tests/ui/",
         Mode::Mir => "tests/ui/codegen/mir/",
         Mode::EvmIr => "tests/ui/codegen/evm-ir/",
+        Mode::Artifacts => "tests/artifacts/",
         Mode::SolcSolidity => "testdata/solidity/test/",
         Mode::SolcYul => "testdata/solidity/test/libyul/",
     };
@@ -134,6 +143,14 @@ fn config(cmd: &'static Path, args: &ui_test::Args, mode: Mode) -> ui_test::Conf
                     Mode::EvmIr => args.extend(
                         ["evm-opt", "-Zui-testing", "-Zparse-yul", "-Zpass-diff"].map(Into::into),
                     ),
+                    Mode::Artifacts => args.extend(
+                        [
+                            "-Zui-testing",
+                            "--emit=abi,hashes,metadata,storage-layout",
+                            "--pretty-json",
+                        ]
+                        .map(Into::into),
+                    ),
                     Mode::StandardJson => {
                         args.extend(
                             ["-Zui-testing", "-Zparse-yul", "--standard-json", "--pretty-json"]
@@ -170,7 +187,22 @@ fn config(cmd: &'static Path, args: &ui_test::Args, mode: Mode) -> ui_test::Conf
             )*
         };
     }
-    register_custom_flags![FileCheck, run_call::RunCall, run_call::RunCallFail];
+    register_custom_flags![
+        FileCheck,
+        run_call::RunCall,
+        run_call::RunCallFail,
+        flags::EvmVersion,
+        flags::Emit,
+        flags::SolidityVersion,
+        flags::OutArtifact,
+        flags::OutDirFile,
+        flags::Events,
+        flags::Overlay,
+        flags::BasePath,
+        flags::RemapPathPrefix,
+        flags::Stdin,
+        flags::CheckReproducible,
+    ];
 
     config.comment_defaults.base().exit_status = None.into();
     config.infer_exit_status_from_annotations = !mode.is_solc();
@@ -266,6 +298,8 @@ fn mode_from_config(config: &ui_test::Config) -> Mode {
         Mode::Mir
     } else if config.root_dir.ends_with("tests/ui/codegen/evm-ir") {
         Mode::EvmIr
+    } else if config.root_dir.ends_with("tests/artifacts") {
+        Mode::Artifacts
     } else if config.root_dir.ends_with("testdata/solidity/test/libyul") {
         Mode::SolcYul
     } else if config.root_dir.ends_with("testdata/solidity/test") {
@@ -300,7 +334,7 @@ fn file_filter(path: &Path, config: &ui_test::Config, cfg: MyConfig<'_>) -> Opti
         return Some(false);
     }
     let skip = match cfg.mode {
-        Mode::Ui | Mode::Mir | Mode::EvmIr | Mode::StandardJson => false,
+        Mode::Ui | Mode::Mir | Mode::EvmIr | Mode::Artifacts | Mode::StandardJson => false,
         Mode::SolcSolidity => solc::solidity::should_skip(path).is_err(),
         Mode::SolcYul => solc::yul::should_skip(path).is_err(),
     };
@@ -327,8 +361,34 @@ fn per_file_config(config: &mut ui_test::Config, file: &Spanned<Vec<u8>>, cfg: M
         }
         return;
     }
+    if matches!(cfg.mode, Mode::Artifacts) {
+        config.comment_defaults.base().require_annotations = Spanned::dummy(false).into();
+        config.comment_defaults.base().exit_status = Spanned::dummy(0).into();
+        return;
+    }
 
     assert_eq!(config.comment_start, "//");
+    // Failed compilations exit with a code that depends on the stage of the first error, which
+    // cannot be inferred from the annotations. Tests that pin the exit status check the actual
+    // code; all others expect `1`. See `solar_cli::exit_code`.
+    if matches!(cfg.mode, Mode::Ui)
+        && !src.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("//@") && line.contains("exit-status")
+        })
+    {
+        config.program.args.push("-Zuniform-exit-code".into());
+    }
+    // An execution profile next to a test, `<name>.profile.json`, is passed with `--profile-data`.
+    let profile = path.with_extension("profile.json");
+    if profile.exists() {
+        config.program.args.push(format!("--profile-data={}", profile.display()).into());
+    }
+    // A message catalog next to a test, `<name>.catalog`, is passed with `-Zmessage-catalog`.
+    let catalog = path.with_extension("catalog");
+    if catalog.exists() {
+        config.program.args.push(format!("-Zmessage-catalog={}", catalog.display()).into());
+    }
     if matches!(cfg.mode, Mode::Ui) && src.lines().any(run_call::is_directive) {
         config.program.args.extend(["-Zcodegen".into(), "--emit=abi,bin".into()]);
         config.stdout_filter(r"(?s).+", "");
@@ -350,8 +410,9 @@ fn solc_per_file_config(config: &mut ui_test::Config, src: &str, path: &Path, cf
     let expected_error = expected_errors.iter().find(|e| e.is_error());
     let code = if let Some(expected_error) = expected_error {
         // Expect failure only for parser errors, otherwise ignore exit code.
+        // `3` is `solar_cli::exit_code::PARSE_ERROR`.
         if expected_error.solc_kind.is_some_and(|kind| kind.is_parser_error()) {
-            Some(1)
+            Some(3)
         } else {
             None
         }
@@ -381,12 +442,16 @@ enum Mode {
     /// EVM-IR-level tests: runs `solar evm-opt` on `.evmir` files under
     /// `tests/ui/codegen/evm-ir/`.
     EvmIr,
+    /// Artifact snapshot tests: emits the ABI, function hashes, metadata and storage layout of
+    /// the `.sol` fixtures under `tests/artifacts/` and compares them to `.stdout` files.
+    Artifacts,
     StandardJson,
     SolcSolidity,
     SolcYul,
 }
 
-const DEFAULT_MODES: &[Mode] = &[Mode::Ui, Mode::StandardJson, Mode::SolcSolidity, Mode::SolcYul];
+const DEFAULT_MODES: &[Mode] =
+    &[Mode::Ui, Mode::Artifacts, Mode::StandardJson, Mode::SolcSolidity, Mode::SolcYul];
 
 impl Mode {
     fn parse(s: &str) -> Option<Self> {
@@ -394,6 +459,7 @@ impl Mode {
             "ui" => Self::Ui,
             "mir" => Self::Mir,
             "evm-ir" => Self::EvmIr,
+            "artifacts" => Self::Artifacts,
             "standard-json" => Self::StandardJson,
             "solc-solidity" => Self::SolcSolidity,
             "solc-yul" => Self::SolcYul,
@@ -406,6 +472,7 @@ impl Mode {
             Self::Ui => "ui",
             Self::Mir => "mir",
             Self::EvmIr => "evm-ir",
+            Self::Artifacts => "artifacts",
             Self::StandardJson => "standard-json",
             Self::SolcSolidity => "solc-solidity",
             Self::SolcYul => "solc-yul",
@@ -417,7 +484,7 @@ impl Mode {
     }
 
     fn allows_yul(self) -> bool {
-        !matches!(self, Self::SolcSolidity | Self::Mir | Self::EvmIr)
+        !matches!(self, Self::SolcSolidity | Self::Mir | Self::EvmIr | Self::Artifacts)
     }
 }
 
@@ -505,6 +572,11 @@ fn run_filecheck(
     })
 }
 
+/// Returns the path to the `solar` binary.
+fn solar_binary() -> &'static Path {
+    SOLAR.get().expect("`run_tests` sets the `solar` binary")
+}
+
 fn filecheck_binary() -> Result<&'static PathBuf, String> {
     static CACHE: OnceLock<Result<PathBuf, String>> = OnceLock::new();
     CACHE.get_or_init(find_filecheck).as_ref().map_err(Clone::clone)
@@ -690,7 +690,11 @@ fn spec_id(config: &TestConfig) -> Result<SpecId, String> {
     if expects_value {
         return Err("`--evm-version` requires a value".to_owned());
     }
-    match version.unwrap_or("osaka") {
+    parse_spec_id(version.unwrap_or("osaka"))
+}
+
+pub(crate) fn parse_spec_id(version: &str) -> Result<SpecId, String> {
+    match version {
         "homestead" => Ok(SpecId::HOMESTEAD),
         "tangerineWhistle" => Ok(SpecId::TANGERINE),
         "spuriousDragon" => Ok(SpecId::SPURIOUS_DRAGON),